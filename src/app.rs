@@ -1,17 +1,47 @@
 #![allow(dead_code)]
 
 use anyhow::Result;
-use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
-use std::sync::Arc;
-use std::time::Instant;
-
-use crate::config::Config;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+use crate::checkpoint;
+use crate::config::{Config, ScheduleConfig, SessionLayout};
+use crate::demo::DemoFleet;
+use crate::drain::{self, DrainOutcome, DrainState};
 use crate::ea::{self, EaId, EaInfo};
-use crate::memory;
+use crate::exec::{self, ExecPopup};
+use crate::file_browser::FileBrowser;
+use crate::firehose::Firehose;
+use crate::github;
+use crate::mailbox;
+use crate::manager;
+use crate::memory::{self, AgentRole};
+use crate::naming;
+use crate::notify::Notifier;
+use crate::pipeline::{self, PipelineRun, RunStatus};
 use crate::projects::{self, Project};
+use crate::restore::{self, ResumableAgent};
+use crate::review::{self, Review, Verdict};
+use crate::runs::{self, Outcome, RunTracker, RunWorker};
+use crate::sandbox::preflight::{self, Preflight};
 use crate::scheduler::{ScheduledEvent, Scheduler, TickerBuffer};
-use crate::tmux::{HealthChecker, HealthState, Session, TmuxClient};
+use crate::schedules;
+use crate::scripting::{ScriptAction, ScriptEvent, ScriptHost};
+use crate::search::{self, SearchMatch};
+use crate::snapshot::{Poller, PollerHandle, Priority, SharedPriorities, SharedSnapshot, Snapshot};
+use crate::spawn_form::{SpawnForm, SpawnRequest};
+use crate::spawn_queue::{self, QueuedSpawn};
+use crate::tmux::{
+    team_health, AgentResult, CaptureCache, DeliveryOptions, HealthState, HealthTransition,
+    RateLimit, RemoteHost, ResultKind, Session, TmuxClient,
+};
+use crate::transcript;
+use crate::ui::i18n::{tr, trf};
+use crate::ui::theme::Theme;
+use crate::ui_state::{self, LayoutPanel, UiState};
 use crate::DASHBOARD_SESSION;
 
 /// What kind of confirmation the user is being prompted for.
@@ -19,10 +49,96 @@ use crate::DASHBOARD_SESSION;
 pub enum ConfirmAction {
     /// Kill the selected agent
     Kill,
-    /// Quit and reset persisted OMAR runtime state.
-    ResetQuit,
+    /// Kill the selected agent and everyone reporting to it
+    KillGroup,
     /// Delete the currently active EA (blocked only if it is the last one)
     DeleteEa,
+    /// Re-spawn workers left unfinished by the previous dashboard run
+    ResumeRun,
+    /// Start draining the fleet, or cancel a drain in progress
+    Drain,
+}
+
+/// What quitting does with the agents, picked in the shutdown dialog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownChoice {
+    /// Leave every agent running for the next dashboard to pick up
+    KeepRunning,
+    /// Stop every EA and agent and reset persisted runtime state
+    KillAll,
+    /// Stop idle and finished workers; keep EAs and workers still busy,
+    /// blocked, or rate-limited
+    KillIdle,
+}
+
+impl ShutdownChoice {
+    /// Whether a worker in `health` is stopped.
+    pub fn stops(self, health: HealthState) -> bool {
+        match self {
+            ShutdownChoice::KeepRunning => false,
+            ShutdownChoice::KillAll => true,
+            ShutdownChoice::KillIdle => {
+                matches!(health, HealthState::Idle | HealthState::Finished)
+            }
+        }
+    }
+}
+
+/// Workers of every registered EA among `sessions`, with their last polled
+/// health. Workers not polled yet count as running, so "kill idle" spares
+/// one that was just spawned.
+fn fleet_workers(
+    sessions: &[Session],
+    health: &HashMap<String, HealthState>,
+    eas: &[EaInfo],
+    base_prefix: &str,
+) -> Vec<(String, HealthState)> {
+    let prefixes: Vec<String> = eas
+        .iter()
+        .map(|info| ea::ea_prefix(info.id, base_prefix))
+        .collect();
+    sessions
+        .iter()
+        .filter(|session| {
+            prefixes
+                .iter()
+                .any(|prefix| session.name.starts_with(prefix.as_str()))
+        })
+        .map(|session| {
+            let state = health
+                .get(&session.name)
+                .copied()
+                .unwrap_or(HealthState::Running);
+            (session.name.clone(), state)
+        })
+        .collect()
+}
+
+/// Workers `auto_kill_idle` may shut down, with their seconds since the
+/// last output. PMs and reviewers, agents blocked on a question or rate
+/// limited, with live children, or attached are left alone.
+fn idle_workers(
+    agents: &[AgentInfo],
+    parents: &HashMap<String, String>,
+    roles: &HashMap<String, AgentRole>,
+    now: i64,
+    limit: u64,
+) -> Vec<(String, i64)> {
+    agents
+        .iter()
+        .filter(|agent| {
+            matches!(agent.health, HealthState::Idle | HealthState::Finished)
+                && !agent.is_unresolved
+                && !agent.session.attached
+                && !matches!(
+                    roles.get(&agent.session.name),
+                    Some(AgentRole::Pm | AgentRole::Reviewer)
+                )
+                && !parents.values().any(|p| *p == agent.session.name)
+        })
+        .map(|agent| (agent.session.name.clone(), now - agent.session.activity))
+        .filter(|(_, idle_secs)| *idle_secs >= limit as i64)
+        .collect()
 }
 
 /// Which left-sidebar panel is active.
@@ -33,6 +149,16 @@ pub enum SidebarPanel {
     ChainOfCommand,
 }
 
+/// Agent counts per health state, for the status bar
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HealthCounts {
+    pub running: usize,
+    pub idle: usize,
+    pub finished: usize,
+    pub blocked: usize,
+    pub rate_limited: usize,
+}
+
 /// Information about an agent for display
 #[derive(Debug, Clone)]
 pub struct AgentInfo {
@@ -41,6 +167,25 @@ pub struct AgentInfo {
     pub is_unresolved: bool,
 }
 
+/// Build remote clients for the active EA's session prefix.
+fn remote_tmux_for(config: &Config, session_prefix: &str) -> Vec<TmuxClient> {
+    config
+        .hosts
+        .iter()
+        .map(|host| {
+            TmuxClient::new(session_prefix).with_remote(RemoteHost {
+                name: host.name.clone(),
+                ssh: host.ssh.clone(),
+                control_path: host.control_path.as_deref().map(|path| {
+                    crate::config::expand_tilde(path)
+                        .to_string_lossy()
+                        .to_string()
+                }),
+            })
+        })
+        .collect()
+}
+
 /// A node in the chain-of-command tree
 #[derive(Debug, Clone)]
 pub struct CommandTreeNode {
@@ -59,8 +204,21 @@ pub struct CommandTreeNode {
     pub ancestor_is_last: Vec<bool>,
     /// Whether this node came from a non-canonical OMAR session name.
     pub is_unresolved: bool,
+    /// Worst health among this node's descendants (None without children)
+    pub team_health: Option<HealthState>,
+    /// Role the agent was spawned with (`Ea` for the root)
+    pub role: AgentRole,
 }
 
+/// How often sandboxed workers are checked for tampering.
+const TAMPER_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// How long the banner for a completed pipeline run stays up.
+const CELEBRATION_DURATION: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Status bar text while the sandbox preflight runs.
+const SANDBOX_CHECKING: &str = "Checking sandbox runtime…";
+
 /// Application state
 pub struct App {
     // EA fields
@@ -77,8 +235,13 @@ pub struct App {
     pub manager_selected: bool,
     pub should_quit: bool,
     pub reset_on_quit: bool,
+    /// Workers listed in the shutdown dialog (None = dialog hidden)
+    pub shutdown_dialog: Option<Vec<(String, HealthState)>>,
+    /// What quitting does with the agents; unset quits (drain) kill all
+    pub shutdown: Option<ShutdownChoice>,
     pub show_help: bool,
     pub pending_confirm: Option<ConfirmAction>,
+    /// Agent filter: a name substring or an exact tag (empty = show all)
     pub filter: String,
     pub status_message: Option<String>,
     status_set_at: Option<Instant>,
@@ -89,6 +252,70 @@ pub struct App {
     pub project_input: String,
     pub ea_input_mode: bool,
     pub ea_input: String,
+    /// Session the message popup sends to (None = popup hidden). Captured on
+    /// open so a refresh reordering the grid cannot redirect the message.
+    pub message_target: Option<String>,
+    pub message_input: String,
+    /// Session whose note is being edited (None = popup hidden)
+    pub note_target: Option<String>,
+    pub note_input: String,
+    /// Search-all query being typed (None = prompt hidden)
+    pub search_input: Option<String>,
+    /// Agent filter being typed (None = prompt hidden)
+    pub filter_input: Option<String>,
+    /// Command popup for the selected worker's sandbox (None = hidden)
+    pub exec_popup: Option<ExecPopup>,
+    /// Sandbox preflight running in the background
+    sandbox_preflight: Option<mpsc::Receiver<Preflight>>,
+    /// Why the last sandbox preflight failed; sandboxed spawns are refused
+    pub sandbox_preflight_error: Option<String>,
+    /// Pipeline runs for the active EA, reloaded on refresh
+    pub pipeline_runs: Vec<PipelineRun>,
+    /// Active EA's schedules and their next fire time (ns), soonest first
+    pub schedule_timers: Vec<(String, u64)>,
+    /// `spawn_agent` requests waiting for a worker pool slot (active EA)
+    pub spawn_queue_len: usize,
+    /// Queued spawns in start order, for the queue panel
+    pub spawn_queue: Vec<QueuedSpawn>,
+    /// Workers holding a pool slot, as of the last refresh
+    pub pool_active: usize,
+    /// config.toml's mtime when health patterns were last read from it
+    config_modified: Option<std::time::SystemTime>,
+    /// Queue panel selection (None = hidden)
+    pub queue_selected: Option<usize>,
+    /// "Needs attention" panel selection (None = hidden)
+    pub attention_selected: Option<usize>,
+    /// Quick reply being typed in the "Needs attention" panel
+    pub attention_input: String,
+    /// Set while a queued spawn is being started in the background
+    queue_draining: Arc<AtomicBool>,
+    /// Set while mailbox messages are being delivered in the background
+    mail_delivering: Arc<AtomicBool>,
+    /// Unfinished workers from the previous run, offered for resume
+    pub resumable: Vec<ResumableAgent>,
+    /// Fleet drain in progress (from `~/.omar/drain.json`)
+    pub drain: Option<DrainState>,
+    /// Workers the drain is still waiting for, across all EAs
+    pub drain_waiting: usize,
+    /// Pipeline run shown in the approval review dialog (None = hidden)
+    pub approval_review: Option<u64>,
+    /// Runs whose review dialog was already opened automatically
+    approvals_surfaced: HashSet<u64>,
+    /// Workdir browser popup state (None = hidden)
+    pub file_browser: Option<FileBrowser>,
+    /// Spawn wizard popup state (None = hidden)
+    pub spawn_form: Option<SpawnForm>,
+    /// Last search-all query and its matches (None = results hidden)
+    pub search_results: Option<(String, Vec<SearchMatch>)>,
+    pub search_scroll: usize,
+    /// Interleaved log tail of all agents (None = firehose view hidden)
+    pub firehose: Option<Firehose>,
+    /// Lines scrolled up from the newest firehose line (0 = follow)
+    pub firehose_scroll: usize,
+    /// Agent whose card fills the screen (None = grid view)
+    pub zoomed: Option<String>,
+    /// Lines scrolled up from the zoomed agent's newest output (0 = follow)
+    pub zoom_scroll: usize,
     pub show_events: bool,
     /// Enlarged sidebar popup (None = hidden)
     pub sidebar_popup: Option<SidebarPanel>,
@@ -105,6 +332,8 @@ pub struct App {
     /// committed to `config` on Enter.
     pub settings_edit_buffer: Option<String>,
     pub config: Config,
+    /// Dashboard palette from `[theme]`
+    pub theme: Theme,
     /// Session name of the agent shown in the bottom panel (the EA's manager session)
     pub focus_parent: String,
     /// Stack for Esc navigation (drill-up restores previous parent)
@@ -113,16 +342,90 @@ pub struct App {
     pub focus_child_indices: Vec<usize>,
     agent_parents: HashMap<String, String>,
     worker_tasks: HashMap<String, String>,
+    agent_tags: HashMap<String, Vec<String>>,
+    agent_progress: HashMap<String, memory::ProgressReport>,
+    agent_notes: HashMap<String, String>,
+    /// Hot and cold agents of the active EA, shared with the poller
+    priorities: SharedPriorities,
+    /// Auxiliary pane shown on an agent's card: its pane ids and the index
+    /// in view (0, the agent's own pane, is never stored)
+    pane_views: HashMap<String, (Vec<String>, usize)>,
+    /// Project sessions of the `grouped` layout and the agents linked into
+    /// each, as last synced to tmux
+    session_groups: BTreeMap<String, Vec<String>>,
+    /// Worst descendant health per parent session (active EA)
+    team_health: HashMap<String, HealthState>,
     /// Whether the left sidebar is focused (vs the right agent panels)
     pub sidebar_focused: bool,
     /// Which sidebar panel is active
     pub sidebar_panel: SidebarPanel,
+    /// Layout preset and visible panels
+    pub ui: UiState,
     client: TmuxClient,
-    health_checker: HealthChecker,
+    /// Pane output shown by the dashboard, reused while a pane is quiet
+    captures: CaptureCache,
+    /// Latest tmux snapshot, published by the background poller
+    snapshot: SharedSnapshot,
+    /// Snapshot the current refresh was built from
+    latest: Snapshot,
+    /// Polls inline until `start_background_polling` moves it to a thread
+    poller: Option<Poller>,
+    poller_handle: Option<PollerHandle>,
+    /// Simulated fleet replacing tmux in `--demo` mode
+    demo: Option<DemoFleet>,
+    /// `[[hosts]]` tmux servers aggregated into the agent list
+    remote: Vec<TmuxClient>,
     health_threshold: i64,
     default_command: String,
     default_workdir: String,
     pub scheduler: Arc<Scheduler>,
+    /// Loaded automation scripts (None = scripting disabled)
+    scripts: Option<ScriptHost>,
+    /// Health per session as last reported to scripts (None until the first
+    /// refresh, so pre-existing agents are not reported as spawned)
+    script_health: Option<HashMap<String, HealthState>>,
+    /// Rate-limited sessions with their paused dispatch and resume nudge
+    rate_limited: HashMap<String, RateLimitHold>,
+    /// Desktop notifications for `[notifications]`
+    notifier: Notifier,
+    /// An attention event since the last render; see `take_bell`
+    bell_pending: bool,
+    /// Priced duration of finished agents, summed when a pipeline completes
+    run_costs: HashMap<String, f64>,
+    /// Active EA's workers since the last run report
+    run: RunTracker,
+    /// Last periodic context refresh check for the EA, and the snapshot it
+    /// was last sent
+    context_refresh: Option<(Instant, String)>,
+    /// memory.md snapshot being written off the event loop for the next
+    /// context refresh
+    context_snapshot: Option<mpsc::Receiver<String>>,
+    /// Banner for a pipeline run that just completed, and when it appeared
+    pub celebration: Option<(String, Instant)>,
+    /// `send` blocks (target, message) on each sender's screen as of its
+    /// last successful scan, so each is relayed once
+    relayed: HashMap<String, HashSet<(String, String)>>,
+    /// EAs whose senders have been scanned once; the first scan only takes
+    /// a baseline, so blocks already on screen are not replayed
+    relay_baselined: HashSet<EaId>,
+    /// Last relayed instruction per worker session: (sender, message)
+    last_instructions: HashMap<String, (String, String)>,
+    /// Stuck workers already escalated in their current idle stretch
+    escalated: HashSet<String>,
+    /// Last sandbox tamper check, and the sessions already alerted on
+    tamper_checked_at: Option<Instant>,
+    tamper_alerted: HashSet<String>,
+    /// Last GitHub issue sync, and the one in flight
+    github_synced_at: Option<Instant>,
+    github_sync: Option<mpsc::Receiver<Result<Option<Vec<github::Imported>>>>>,
+}
+
+/// Dispatch pause applied while an agent is rate-limited.
+struct RateLimitHold {
+    receiver: String,
+    until_ns: u64,
+    /// Scheduled resume nudge, if `auto_resume` is on.
+    nudge_id: Option<String>,
 }
 
 impl App {
@@ -134,7 +437,27 @@ impl App {
         Self::new_with_omar_dir(config, ticker, scheduler, omar_dir)
     }
 
-    fn new_with_omar_dir(
+    /// Dashboard over a simulated fleet seeded into `omar_dir`, which
+    /// should be a throwaway directory.
+    pub fn new_demo(
+        config: &Config,
+        ticker: TickerBuffer,
+        scheduler: Arc<Scheduler>,
+        omar_dir: PathBuf,
+    ) -> Self {
+        let fleet = DemoFleet::new(config, &omar_dir, &scheduler);
+        let mut app = Self::new_with_omar_dir(config, ticker, scheduler, omar_dir);
+        app.poller = None;
+        app.demo = Some(fleet);
+        app
+    }
+
+    pub fn is_demo(&self) -> bool {
+        self.demo.is_some()
+    }
+
+    /// Dashboard state rooted at `omar_dir` instead of `~/.omar`.
+    pub fn new_with_omar_dir(
         config: &Config,
         ticker: TickerBuffer,
         scheduler: Arc<Scheduler>,
@@ -153,10 +476,22 @@ impl App {
         let manager_session = ea::ea_manager_session(active_ea, &base_prefix);
 
         let client = TmuxClient::new(&session_prefix);
-        let health_checker = HealthChecker::new(client.clone(), config.health.idle_warning);
 
         let state_dir = ea::ea_state_dir(active_ea, &omar_dir);
         std::fs::create_dir_all(state_dir.join("status")).ok();
+        let ui =
+            ui_state::load(&omar_dir).unwrap_or_else(|| UiState::from_config(&config.dashboard));
+
+        let (scripts, script_errors) = ScriptHost::load(&config.scripting);
+        for error in script_errors {
+            ticker.push(error);
+        }
+        let priorities = SharedPriorities::default();
+        let poller = Poller::new(config)
+            .with_config_reload(omar_dir.join("config.toml"))
+            .with_history_dir(omar_dir.clone())
+            .with_priorities(priorities.clone());
+        let config_modified = crate::snapshot::modified(&omar_dir.join("config.toml"));
 
         Self {
             active_ea,
@@ -170,6 +505,8 @@ impl App {
             manager_selected: true,
             should_quit: false,
             reset_on_quit: false,
+            shutdown_dialog: None,
+            shutdown: None,
             show_help: false,
             pending_confirm: None,
             filter: String::new(),
@@ -181,6 +518,39 @@ impl App {
             project_input: String::new(),
             ea_input_mode: false,
             ea_input: String::new(),
+            message_target: None,
+            message_input: String::new(),
+            note_target: None,
+            note_input: String::new(),
+            search_input: None,
+            filter_input: None,
+            exec_popup: None,
+            sandbox_preflight: None,
+            sandbox_preflight_error: None,
+            approval_review: None,
+            file_browser: None,
+            approvals_surfaced: HashSet::new(),
+            spawn_form: None,
+            pipeline_runs: Vec::new(),
+            schedule_timers: Vec::new(),
+            spawn_queue_len: 0,
+            spawn_queue: Vec::new(),
+            pool_active: 0,
+            config_modified,
+            queue_selected: None,
+            attention_selected: None,
+            attention_input: String::new(),
+            queue_draining: Arc::new(AtomicBool::new(false)),
+            mail_delivering: Arc::new(AtomicBool::new(false)),
+            resumable: Vec::new(),
+            drain: None,
+            drain_waiting: 0,
+            search_results: None,
+            search_scroll: 0,
+            firehose: None,
+            firehose_scroll: 0,
+            zoomed: None,
+            zoom_scroll: 0,
             show_events: false,
             sidebar_popup: None,
             scheduled_events: Vec::new(),
@@ -206,20 +576,53 @@ impl App {
             show_settings: false,
             settings_selected: 0,
             settings_edit_buffer: None,
+            theme: Theme::from_config(&config.theme),
             config: config.clone(),
             focus_parent: manager_session,
             focus_stack: Vec::new(),
             focus_child_indices: Vec::new(),
             agent_parents: HashMap::new(),
             worker_tasks: HashMap::new(),
+            agent_tags: HashMap::new(),
+            agent_progress: HashMap::new(),
+            agent_notes: HashMap::new(),
+            priorities,
+            pane_views: HashMap::new(),
+            session_groups: BTreeMap::new(),
+            team_health: HashMap::new(),
             sidebar_focused: false,
             sidebar_panel: SidebarPanel::Projects,
+            ui,
+            remote: remote_tmux_for(config, &session_prefix),
             client,
-            health_checker,
+            captures: CaptureCache::default(),
+            snapshot: SharedSnapshot::default(),
+            latest: Snapshot::default(),
+            poller: Some(poller),
+            poller_handle: None,
+            demo: None,
             health_threshold: config.health.idle_warning,
             default_command: config.agent.default_command.clone(),
             default_workdir: config.agent.default_workdir.clone(),
             scheduler,
+            scripts,
+            script_health: None,
+            rate_limited: HashMap::new(),
+            notifier: Notifier::new(&config.notifications),
+            bell_pending: false,
+            run_costs: HashMap::new(),
+            run: RunTracker::default(),
+            context_refresh: None,
+            context_snapshot: None,
+            celebration: None,
+            relayed: HashMap::new(),
+            relay_baselined: HashSet::new(),
+            last_instructions: HashMap::new(),
+            escalated: HashSet::new(),
+            tamper_checked_at: None,
+            github_synced_at: None,
+            github_sync: None,
+            tamper_alerted: HashSet::new(),
         }
     }
 
@@ -237,24 +640,43 @@ impl App {
         ea::ea_prefix(self.active_ea, &self.base_prefix)
     }
 
+    /// Short name of a worker of the active EA; other sessions (managers,
+    /// other EAs' workers) lose only the base prefix.
     fn short_session_name<'a>(&self, session_name: &'a str) -> &'a str {
-        let active_prefix = self.active_session_prefix();
-        session_name
-            .strip_prefix(&active_prefix)
-            .or_else(|| session_name.strip_prefix(&self.base_prefix))
-            .unwrap_or(session_name)
+        match naming::short_name(&self.active_session_prefix(), session_name) {
+            short if short.len() < session_name.len() => short,
+            _ => naming::short_name(&self.base_prefix, session_name),
+        }
     }
 
     /// True when any popup or input overlay is active.
+    /// Whether to ring the tmux bell after this render (demo mode never
+    /// does). Clears the request.
+    pub fn take_bell(&mut self) -> bool {
+        std::mem::take(&mut self.bell_pending) && self.demo.is_none()
+    }
+
     pub fn has_popup(&self) -> bool {
         self.show_help
             || self.pending_confirm.is_some()
+            || self.shutdown_dialog.is_some()
             || self.project_input_mode
             || self.ea_input_mode
+            || self.message_target.is_some()
+            || self.note_target.is_some()
+            || self.search_input.is_some()
+            || self.filter_input.is_some()
+            || self.exec_popup.is_some()
+            || self.spawn_form.is_some()
+            || self.approval_review.is_some()
+            || self.file_browser.is_some()
+            || self.search_results.is_some()
             || self.show_events
             || self.show_debug_console
             || self.show_settings
             || self.sidebar_popup.is_some()
+            || self.queue_selected.is_some()
+            || self.attention_selected.is_some()
     }
 
     pub fn client(&self) -> &TmuxClient {
@@ -277,27 +699,21 @@ impl App {
             }
         }
 
+        self.reload_config();
+
         // Ensure manager exists
         self.ensure_manager()?;
 
-        // Get all sessions (used for both active EA state and multi-EA CoC sidebar)
-        let all_sessions = self.client.list_all_sessions()?;
-        // Only snapshot health for OMAR-owned sessions. Every EA session name
-        // starts with `base_prefix` (e.g. "omar-agent-") and the manager is
-        // `<base_prefix>ea-<id>` — so the prefix check covers both agents and
-        // managers across all EAs. This avoids running a tmux `capture-pane`
-        // per unrelated shell session on hosts where the user has many tmux
-        // sessions for their own work.
-        let mut health_snapshot: HashMap<String, HealthState> = HashMap::new();
-        for session in &all_sessions {
-            if !self.base_prefix.is_empty() && !session.name.starts_with(&self.base_prefix) {
-                continue;
-            }
-            health_snapshot.insert(
-                session.name.clone(),
-                self.health_checker.check(&session.name),
-            );
+        // Sessions and health for every EA (active EA state and the multi-EA
+        // CoC sidebar), captured off the event loop by the poller
+        self.poll_snapshot();
+        if let Some(error) = &self.latest.error {
+            anyhow::bail!("{}", error);
         }
+        let all_sessions = self.latest.sessions.clone();
+        let health_snapshot = self.latest.health.clone();
+        let live: Vec<String> = all_sessions.iter().map(|s| s.name.clone()).collect();
+        memory::sweep_agent_env_files_in(&self.state_dir(), &live, chrono::Utc::now().timestamp());
 
         let mut managers_by_ea: HashMap<EaId, Session> = HashMap::new();
         let mut agents_by_ea: HashMap<EaId, Vec<Session>> = HashMap::new();
@@ -332,7 +748,7 @@ impl App {
             let health = health_snapshot
                 .get(&session.name)
                 .copied()
-                .unwrap_or_else(|| self.health_checker.check(&session.name));
+                .unwrap_or(HealthState::Running);
             AgentInfo {
                 session,
                 health,
@@ -356,7 +772,7 @@ impl App {
                 let health = health_snapshot
                     .get(&session.name)
                     .copied()
-                    .unwrap_or_else(|| self.health_checker.check(&session.name));
+                    .unwrap_or(HealthState::Running);
                 let is_unresolved = unresolved_names.contains(&session.name);
                 AgentInfo {
                     session,
@@ -365,6 +781,8 @@ impl App {
                 }
             })
             .collect();
+        let remote_agents = self.remote_agents();
+        self.agents.extend(remote_agents);
 
         // Clean up stale frame data for sessions that no longer exist
         let active: Vec<String> = self
@@ -373,22 +791,53 @@ impl App {
             .map(|a| a.session.name.clone())
             .chain(self.manager.iter().map(|m| m.session.name.clone()))
             .collect();
-        self.health_checker.retain_sessions(&active);
+        self.captures.retain(&active);
+        self.apply_rate_limits();
+
+        let state_dir = self.state_dir();
+        let new_results = self.collect_agent_results(&state_dir);
+        self.track_run(&state_dir);
+        self.check_drain(&state_dir);
+        self.advance_pipelines(&state_dir, &active);
+        self.relay_manager_sends(&state_dir);
+        self.run_schedules(&state_dir);
+        self.sync_github(&state_dir);
+        self.drain_spawn_queue(&state_dir);
+        self.refresh_manager_context(&state_dir);
+        self.deliver_mailboxes(&state_dir);
+        self.surface_pending_approval();
+        self.send_notifications(&new_results);
+        self.check_sandbox_tamper(&state_dir);
+        self.escalate_stuck(&state_dir);
+        self.auto_kill_idle(&state_dir);
+        let script_actions = self.run_script_hooks(&new_results);
 
         // Apply filter if set
-        if !self.filter.is_empty() {
-            let filter = self.filter.to_lowercase();
-            self.agents
-                .retain(|a| a.session.name.to_lowercase().contains(&filter));
+        self.agent_tags = memory::load_agent_tags_from(&state_dir);
+        self.agent_progress = memory::load_agent_progress_from(&state_dir);
+        self.agent_notes = memory::load_agent_notes_from(&state_dir);
+        if let Ok(mut shared) = self.priorities.write() {
+            *shared = memory::load_agent_priorities_from(&state_dir);
+        }
+        let agents = &self.agents;
+        self.pane_views
+            .retain(|session, _| agents.iter().any(|a| &a.session.name == session));
+        if self.config.dashboard.layout == SessionLayout::Grouped {
+            self.sync_session_groups(&state_dir);
+        }
+        self.poll_firehose();
+        if let Some(selected) = self.attention_selected {
+            let last = self.attention_agents().len().saturating_sub(1);
+            self.attention_selected = Some(selected.min(last));
         }
 
         // Reload projects from EA-scoped file (picks up API-side changes)
-        let state_dir = self.state_dir();
         self.projects = projects::load_projects_from(&state_dir);
 
         // Load parent mappings, worker tasks, and build the chain-of-command tree
         self.agent_parents = memory::load_agent_parents_from(&state_dir);
         self.worker_tasks = memory::load_worker_tasks_from(&state_dir);
+        self.team_health = team_health(&health_snapshot, &self.agent_parents);
 
         // Build multi-EA CoC: all EAs sorted by ID, each with its real subtree and health.
 
@@ -401,12 +850,13 @@ impl App {
             let ea_manager = ea::ea_manager_session(ea_info.id, &self.base_prefix);
             let ea_state_dir = ea::ea_state_dir(ea_info.id, &self.omar_dir);
             let ea_parents = memory::load_agent_parents_from(&ea_state_dir);
+            let ea_roles = memory::load_agent_roles_from(&ea_state_dir);
 
             let manager_info = managers_by_ea.get(&ea_info.id).cloned().map(|session| {
                 let health = health_snapshot
                     .get(&session.name)
                     .copied()
-                    .unwrap_or_else(|| self.health_checker.check(&session.name));
+                    .unwrap_or(HealthState::Running);
                 AgentInfo {
                     session,
                     health,
@@ -422,7 +872,7 @@ impl App {
                     let health = health_snapshot
                         .get(&session.name)
                         .copied()
-                        .unwrap_or_else(|| self.health_checker.check(&session.name));
+                        .unwrap_or(HealthState::Running);
                     AgentInfo {
                         session: session.clone(),
                         health,
@@ -437,7 +887,7 @@ impl App {
                     let health = health_snapshot
                         .get(&session.name)
                         .copied()
-                        .unwrap_or_else(|| self.health_checker.check(&session.name));
+                        .unwrap_or(HealthState::Running);
                     AgentInfo {
                         session,
                         health,
@@ -456,6 +906,11 @@ impl App {
             if let Some(root) = nodes.first_mut() {
                 root.name = ea_info.name.clone();
             }
+            for node in nodes.iter_mut().skip(1) {
+                if let Some(role) = ea_roles.get(&node.session_name) {
+                    node.role = *role;
+                }
+            }
             all_nodes.extend(nodes);
         }
         self.command_tree = all_nodes;
@@ -471,151 +926,1992 @@ impl App {
             self.selected = self.focus_child_indices.len() - 1;
         }
 
+        for action in script_actions {
+            self.apply_script_action(action);
+        }
+
         Ok(())
     }
 
-    fn apply_dashboard_launch_handoff(&mut self) -> Result<()> {
-        let Some(handoff) = ea::take_dashboard_launch_handoff(&self.omar_dir) else {
-            return Ok(());
-        };
-
-        // Capture the pre-handoff backend so we only restart the manager when
-        // the user actually switched backends. `omar -a claude` while already
-        // on claude shouldn't kill the running EA — that throws away the
-        // backend's in-memory conversation for nothing.
-        let backend_changed = self.default_command != handoff.default_command;
-
-        self.config.agent.default_command = handoff.default_command.clone();
-        self.config.agent.default_workdir = handoff.default_workdir.clone();
-        self.default_command = handoff.default_command;
-        self.default_workdir = handoff.default_workdir;
-        self.activate_ea_local(handoff.active_ea)?;
-        ea::save_active_ea(&self.omar_dir, handoff.active_ea)?;
+    /// Project sessions for the `grouped` layout: each local agent's window
+    /// is linked into the session of its project, or of "unassigned".
+    fn desired_session_groups(&self, state_dir: &Path) -> BTreeMap<String, Vec<String>> {
+        let agent_projects = memory::load_agent_projects_from(state_dir);
+        let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for agent in self.agents.iter().filter(|a| a.session.host.is_none()) {
+            let project = agent_projects
+                .get(&agent.session.name)
+                .and_then(|id| self.projects.iter().find(|p| p.id == *id))
+                .map_or("unassigned", |p| p.name.as_str());
+            let group = ea::ea_group_session(self.active_ea, &self.base_prefix, project);
+            groups
+                .entry(group)
+                .or_default()
+                .push(agent.session.name.clone());
+        }
+        groups
+    }
 
-        if handoff.restart_manager && backend_changed {
-            let manager_session = ea::ea_manager_session(handoff.active_ea, &self.base_prefix);
-            if self.client.has_session(&manager_session)? {
-                self.client.kill_session(&manager_session)?;
-                self.manager = None;
+    /// Bring tmux's project sessions in line with the fleet, rebuilding only
+    /// the groups whose members changed.
+    fn sync_session_groups(&mut self, state_dir: &Path) {
+        let desired = self.desired_session_groups(state_dir);
+        if desired == self.session_groups {
+            return;
+        }
+        for (group, members) in &self.session_groups {
+            if desired.get(group) != Some(members) {
+                let _ = self.client.kill_session(group);
             }
         }
-        Ok(())
+        let mut synced = BTreeMap::new();
+        for (group, members) in desired {
+            if self.session_groups.get(&group) != Some(&members) {
+                let _ = self.client.kill_session(&group);
+                if let Err(e) = self.client.link_group(&group, &members) {
+                    self.set_status_trf("Error grouping {}: {}", &[&group, &e]);
+                    continue;
+                }
+            }
+            synced.insert(group, members);
+        }
+        self.session_groups = synced;
     }
 
-    /// Build the startup command attempts for the manager.
-    ///
-    /// In strict mode we issue exactly one launch command so behavior matches
-    /// main and does not silently degrade to alternate variants.
-    fn manager_startup_attempts(&self) -> Vec<(String, bool)> {
-        vec![(self.default_command.clone(), true)]
+    /// Active-EA workers on `[[hosts]]` from the latest snapshot.
+    fn remote_agents(&self) -> Vec<AgentInfo> {
+        let prefix = ea::ea_prefix(self.active_ea, &self.base_prefix);
+        self.latest
+            .remote
+            .iter()
+            .filter(|(session, _)| session.name.starts_with(&prefix))
+            .map(|(session, health)| AgentInfo {
+                session: session.clone(),
+                health: *health,
+                is_unresolved: false,
+            })
+            .collect()
     }
 
-    /// Ensure manager session exists, start if not
-    fn ensure_manager(&mut self) -> Result<()> {
-        let canonical_manager_session = self.manager_session_name();
-        let manager_session = canonical_manager_session.clone();
-
-        if self.client.has_session(&manager_session)? {
-            if self.client.session_has_live_pane(&manager_session)? {
-                return Ok(());
+    /// Take the latest snapshot: poll inline without a background poller,
+    /// otherwise read what it last published and wake it for the next one.
+    fn poll_snapshot(&mut self) {
+        if let Some(fleet) = &self.demo {
+            self.latest = fleet.snapshot(chrono::Utc::now().timestamp());
+            return;
+        }
+        if let Some(poller) = self.poller.as_mut() {
+            let snapshot = poller.poll();
+            if let Ok(mut slot) = self.snapshot.write() {
+                *slot = snapshot.clone();
             }
-            let _ = self.client.kill_session(&manager_session);
+            self.latest = snapshot;
+            return;
+        }
+        if let Ok(slot) = self.snapshot.read() {
+            self.latest = slot.clone();
+        }
+        if let Some(handle) = &self.poller_handle {
+            handle.wake();
         }
+    }
 
-        // Reload registry on cache miss so we have the latest EA names
-        self.registered_eas = ea::load_registry(&self.omar_dir);
+    /// Move tmux polling onto a background thread so refreshes (and the
+    /// `App` lock) never wait on tmux or SSH.
+    pub fn start_background_polling(&mut self) {
+        if let Some(poller) = self.poller.take() {
+            let interval =
+                std::time::Duration::from_secs(self.config.dashboard.refresh_interval.max(1));
+            self.poller_handle = Some(poller.spawn(self.snapshot.clone(), interval));
+        }
+    }
 
-        // Get EA name for prompt
-        let ea_name = self
-            .registered_eas
+    /// Client owning `session`: the local server unless the session was
+    /// listed from a remote host.
+    pub fn client_for_session(&self, session: &str) -> &TmuxClient {
+        let host = self
+            .agents
             .iter()
-            .find(|ea| ea.id == self.active_ea)
-            .map(|ea| ea.name.as_str())
-            .unwrap_or("Default");
+            .find(|a| a.session.name == session)
+            .and_then(|a| a.session.host.as_deref());
+        host.and_then(|host| {
+            self.remote
+                .iter()
+                .find(|remote| remote.host() == Some(host))
+        })
+        .unwrap_or(&self.client)
+    }
 
-        let workdir = self.default_workdir.clone();
+    /// Persist newly seen completion markers and wake each agent's parent
+    /// once per marker. Returns the newly recorded results.
+    fn collect_agent_results(&mut self, state_dir: &Path) -> Vec<(String, AgentResult)> {
+        let stored = memory::load_agent_results_from(state_dir);
+        let tasks = memory::load_worker_tasks_from(state_dir);
+        let now = chrono::Utc::now();
+        let fresh: Vec<(String, AgentResult)> = self
+            .agents
+            .iter()
+            .filter_map(|agent| {
+                let name = &agent.session.name;
+                let result = self.latest.results.get(name)?;
+                if stored
+                    .get(name)
+                    .is_some_and(|prev| prev.same_marker(result))
+                {
+                    return None;
+                }
+                let mut result = result.clone();
+                result.detected_at = now.to_rfc3339();
+                Some((name.clone(), result))
+            })
+            .collect();
 
-        let (default_command, inject_prompt) = self
-            .manager_startup_attempts()
-            .into_iter()
-            .next()
-            .unwrap_or_else(|| (self.default_command.clone(), true));
+        let mut reviews = review::load_reviews_from(state_dir);
+        let reviews_before = reviews.clone();
+        for (name, result) in &fresh {
+            memory::save_agent_result_in(state_dir, name, result);
+            if let Some(cost) = self.record_agent_run(name, result, now, tasks.get(name)) {
+                self.run_costs.insert(name.clone(), cost);
+            }
+            if !self.review_result(state_dir, &mut reviews, name, result, &tasks) {
+                self.notify_parent_of_result(state_dir, name, result, None);
+            }
+        }
+        self.check_reviewers(state_dir, &mut reviews);
+        if reviews != reviews_before {
+            review::save_reviews_in(state_dir, &reviews);
+        }
+        fresh
+    }
 
-        let context = crate::manager::McpLaunchContext {
-            omar_dir: self.omar_dir.clone(),
-            ea_id: self.active_ea,
-            session_prefix: self.base_prefix.clone(),
-            default_command: default_command.clone(),
-            default_workdir: self.default_workdir.clone(),
-            health_idle_warning: self.health_threshold,
-            tmux_server: std::env::var("OMAR_TMUX_SERVER")
-                .ok()
-                .map(|server| server.trim().to_string())
-                .filter(|server| !server.is_empty()),
+    /// Tell an agent's parent (or the EA) about its result. `note` is
+    /// appended, e.g. how its review went.
+    fn notify_parent_of_result(
+        &self,
+        state_dir: &Path,
+        session: &str,
+        result: &AgentResult,
+        note: Option<&str>,
+    ) {
+        if !self.config.health.notify_parent_on_result {
+            return;
+        }
+        let receiver = match memory::load_agent_parents_from(state_dir).get(session) {
+            Some(parent) if *parent != self.manager_session_name() => {
+                self.short_session_name(parent).to_string()
+            }
+            _ => "ea".to_string(),
         };
+        let sender = self.short_session_name(session).to_string();
+        let mut payload = format!(
+            "[CHILD RESULT] {} {}: {}",
+            sender,
+            result.kind.as_str(),
+            result.detail
+        );
+        if let Some(note) = note {
+            payload.push_str(&format!(" ({})", note));
+        }
+        if let Some(trace_id) = memory::load_agent_traces_from(state_dir).get(session) {
+            payload.push_str(&format!(" (trace {})", trace_id));
+        }
+        self.schedule_now(sender, receiver, payload);
+    }
 
-        let (cmd, workspace_cwd) = if inject_prompt {
-            crate::manager::build_ea_command(
-                &default_command,
-                self.active_ea,
-                ea_name,
-                &self.omar_dir,
-                &context,
-            )
-        } else {
-            (default_command.clone(), None)
-        };
+    /// Route a fresh result through the review stage: a worker's
+    /// `[TASK COMPLETE]` starts a review round and a reviewer's result
+    /// settles one. Returns true when the parent has been told already or
+    /// must wait for the review.
+    fn review_result(
+        &mut self,
+        state_dir: &Path,
+        reviews: &mut HashMap<String, Review>,
+        session: &str,
+        result: &AgentResult,
+        tasks: &HashMap<String, String>,
+    ) -> bool {
+        let reviewing = reviews
+            .iter()
+            .find(|(_, open)| open.reviewer.as_deref() == Some(session))
+            .map(|(worker, _)| worker.clone());
+        if let Some(worker) = reviewing {
+            self.settle_review(state_dir, reviews, &worker, review::verdict(result));
+            return true;
+        }
+        if !self.config.orchestration.review
+            || result.kind != ResultKind::Complete
+            || memory::load_agent_roles_from(state_dir).get(session) == Some(&AgentRole::Reviewer)
+        {
+            return false;
+        }
+        let worker = self.short_session_name(session).to_string();
+        let round = reviews.get(session).map_or(0, |open| open.round) + 1;
+        if round > self.config.orchestration.max_review_rounds {
+            reviews.remove(session);
+            let note = format!("not approved after {} review rounds", round - 1);
+            self.ticker.push(format!("{} {}", worker, note));
+            self.notify_parent_of_result(state_dir, session, result, Some(&note));
+            return true;
+        }
+        let task = tasks.get(session).map_or("", String::as_str);
+        match self.spawn_reviewer(state_dir, session, result, round, task) {
+            Ok(reviewer) => {
+                reviews.insert(
+                    session.to_string(),
+                    Review {
+                        reviewer: Some(reviewer),
+                        round,
+                        result: result.clone(),
+                    },
+                );
+                self.ticker
+                    .push(format!("review round {} of {} started", round, worker));
+                true
+            }
+            Err(e) => {
+                reviews.remove(session);
+                self.ticker
+                    .push(format!("review of {} skipped: {}", worker, e));
+                false
+            }
+        }
+    }
 
-        // For backends whose manager prompt is now loaded from an auto-
-        // discovered file (codex `AGENTS.md`, agy MCP plugin config, opencode
-        // `AGENTS.md`), build_ea_command returns the workspace dir we must
-        // launch in. Fall back to the user's workdir for claude/cursor.
-        let launch_cwd = workspace_cwd
-            .as_ref()
-            .map(|p| p.to_string_lossy().into_owned())
-            .unwrap_or(workdir);
+    /// Act on the reviewer's verdict for `worker` and retire the reviewer.
+    fn settle_review(
+        &mut self,
+        state_dir: &Path,
+        reviews: &mut HashMap<String, Review>,
+        worker: &str,
+        verdict: Verdict,
+    ) {
+        let Some(reviewer) = reviews
+            .get_mut(worker)
+            .and_then(|open| open.reviewer.take())
+        else {
+            return;
+        };
+        let reviewer_name = self.short_session_name(&reviewer).to_string();
+        let worker_name = self.short_session_name(worker).to_string();
+        if let Err(e) = self.kill_agent_session(&reviewer) {
+            self.ticker
+                .push(format!("could not stop reviewer {}: {}", reviewer_name, e));
+        }
+        let note = match verdict {
+            Verdict::ChangesRequested(feedback) => {
+                self.ticker.push(format!(
+                    "{} requested changes from {}",
+                    reviewer_name, worker_name
+                ));
+                let round = reviews.get(worker).map_or(1, |open| open.round);
+                let message = review::feedback_message(&reviewer_name, round, &feedback);
+                self.schedule_now(reviewer_name, worker_name, message);
+                return;
+            }
+            Verdict::Approved => format!("approved by {}", reviewer_name),
+            Verdict::Failed(reason) => format!("review failed: {}", reason),
+        };
+        self.ticker.push(format!("{} {}", worker_name, note));
+        if let Some(open) = reviews.remove(worker) {
+            self.notify_parent_of_result(state_dir, worker, &open.result, Some(&note));
+        }
+    }
 
-        self.client
-            .new_session(&manager_session, &cmd, Some(&launch_cwd))
-            .map_err(|err| {
-                let msg = format!(
-                    "tmux failed to start manager '{}' (cwd '{}') with command '{}': {}",
-                    manager_session, launch_cwd, default_command, err
-                );
-                anyhow::anyhow!(msg)
-            })?;
+    /// Settle reviews whose reviewer exited without a verdict.
+    fn check_reviewers(&mut self, state_dir: &Path, reviews: &mut HashMap<String, Review>) {
+        let gone: Vec<String> = reviews
+            .iter()
+            .filter(|(_, open)| {
+                open.reviewer
+                    .as_ref()
+                    .is_some_and(|reviewer| !self.client.has_session(reviewer).unwrap_or(true))
+            })
+            .map(|(worker, _)| worker.clone())
+            .collect();
+        for worker in gone {
+            let reason = "reviewer exited without a result".to_string();
+            self.settle_review(state_dir, reviews, &worker, Verdict::Failed(reason));
+        }
+    }
 
-        let state_dir = self.state_dir();
-        let events = self.scheduler.list_by_ea(self.active_ea);
-        memory::write_memory_to(
-            &state_dir,
-            &self.agents,
-            None,
-            &manager_session,
-            &self.client,
-            &events,
-        );
-        Ok(())
+    /// Start a reviewer for `worker`'s result in the worker's directory,
+    /// listed under the worker in the tree.
+    fn spawn_reviewer(
+        &mut self,
+        state_dir: &Path,
+        worker: &str,
+        result: &AgentResult,
+        round: u32,
+        task: &str,
+    ) -> Result<String> {
+        let command = match self.config.orchestration.reviewer_backend.as_deref() {
+            Some(backend) => {
+                crate::config::resolve_backend(backend).map_err(|e| anyhow::anyhow!(e))?
+            }
+            None => self.config.agent.default_command.clone(),
+        };
+        let workdir = memory::load_agent_launches_from(state_dir)
+            .remove(worker)
+            .map(|launch| launch.workdir)
+            .or_else(|| self.client.get_pane_path(worker).ok())
+            .unwrap_or_else(|| self.resolved_default_workdir());
+        let worker_name = self.short_session_name(worker).to_string();
+        let request = SpawnRequest {
+            name: Some(format!("{}-review-{}", worker_name, round)),
+            task: Some(review::reviewer_task(
+                &worker_name,
+                task,
+                result,
+                &review::diff_stat(&workdir),
+                &transcript::log_path(worker),
+            )),
+            command,
+            workdir,
+            parent: worker_name,
+            sandbox: true,
+            profile: None,
+        };
+        let session = self.spawn_from_request(&request)?;
+        memory::save_agent_role_in(state_dir, &session, AgentRole::Reviewer);
+        Ok(session)
     }
 
-    /// Get default command
-    pub fn default_command(&self) -> &str {
-        &self.default_command
+    /// Append a finished run to the estimate history. Returns its cost when
+    /// the backend is priced.
+    fn record_agent_run(
+        &self,
+        session: &str,
+        result: &AgentResult,
+        now: chrono::DateTime<chrono::Utc>,
+        task: Option<&String>,
+    ) -> Option<f64> {
+        let Ok(created) = self.client.get_session_created(session) else {
+            return None;
+        };
+        let duration_secs = (now.timestamp() - created).max(0) as u64;
+        let backend = self.client.get_pane_command(session).unwrap_or_default();
+        let cost_usd = self
+            .config
+            .metrics
+            .cost_per_hour
+            .get(&backend)
+            .map(|rate| rate * duration_secs as f64 / 3600.0);
+        crate::metrics::record_agent_run(&crate::metrics::AgentRunRecord {
+            role: crate::metrics::role_of(self.short_session_name(session)),
+            task: task.cloned().unwrap_or_default(),
+            backend,
+            duration_secs,
+            cost_usd,
+            result: result.kind.as_str().to_string(),
+            finished_at: now.to_rfc3339(),
+        });
+        cost_usd
     }
 
-    /// Get the worker_tasks map (for display)
-    pub fn worker_tasks(&self) -> &HashMap<String, String> {
-        &self.worker_tasks
+    /// Queue an immediate scheduler event so delivery goes through the
+    /// normal path (popup deferral, draft restore).
+    fn schedule_now(&self, sender: String, receiver: String, payload: String) {
+        let timestamp = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default() as u64;
+        self.scheduler.insert(ScheduledEvent {
+            id: uuid::Uuid::new_v4().to_string(),
+            sender,
+            receiver,
+            timestamp,
+            payload,
+            created_at: timestamp,
+            recurring_ns: None,
+            ea_id: self.active_ea,
+        });
     }
 
-    /// Compute indices into self.agents for focus_parent's direct children
-    fn compute_focus_child_indices(&self) -> Vec<usize> {
-        let manager_session = self.manager_session_name();
-        let mut indices = Vec::new();
-        if self.focus_parent == manager_session {
-            // Root view: show agents that are direct children of EA, plus orphans
-            for (i, agent) in self.agents.iter().enumerate() {
-                let parent = self.agent_parents.get(&agent.session.name);
+    /// Pause dispatch to agents that hit a provider rate limit and schedule a
+    /// resume nudge for when the cooldown ends. A limit still shown after
+    /// the cooldown is treated as a fresh one.
+    fn apply_rate_limits(&mut self) {
+        let settings = self.config.health.rate_limit.clone();
+        let now = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default() as u64;
+        let manager_session = ea::ea_manager_session(self.active_ea, &self.base_prefix);
+        let prefix = ea::ea_prefix(self.active_ea, &self.base_prefix);
+        let limited: Vec<(String, RateLimit)> = self
+            .manager
+            .iter()
+            .chain(self.agents.iter())
+            .filter(|a| a.health == HealthState::RateLimited)
+            .filter_map(|a| {
+                let limit = self.latest.rate_limits.get(&a.session.name)?;
+                Some((a.session.name.clone(), limit.clone()))
+            })
+            .collect();
+
+        let still_limited: HashSet<&str> = limited.iter().map(|(s, _)| s.as_str()).collect();
+        let recovered: Vec<String> = self
+            .rate_limited
+            .keys()
+            .filter(|session| !still_limited.contains(session.as_str()))
+            .cloned()
+            .collect();
+        for session in recovered {
+            if let Some(hold) = self.rate_limited.remove(&session) {
+                self.scheduler
+                    .resume_receiver(&hold.receiver, self.active_ea);
+                if let Some(id) = hold.nudge_id {
+                    let _ = self.scheduler.cancel_if_ea(&id, self.active_ea);
+                }
+                self.ticker
+                    .push(format!("{} recovered from rate limit", hold.receiver));
+            }
+        }
+
+        for (session, limit) in limited {
+            if self
+                .rate_limited
+                .get(&session)
+                .is_some_and(|hold| hold.until_ns > now)
+            {
+                continue;
+            }
+            let receiver = naming::address(&session, &manager_session, &prefix);
+            let cooldown = limit.cooldown.unwrap_or(std::time::Duration::from_secs(
+                settings.default_cooldown_secs,
+            ));
+            let until_ns = now + cooldown.as_nanos() as u64;
+            if settings.pause_dispatch {
+                self.scheduler
+                    .pause_receiver(&receiver, self.active_ea, until_ns);
+            }
+            let nudge_id = settings.auto_resume.then(|| {
+                let id = uuid::Uuid::new_v4().to_string();
+                self.scheduler.insert(ScheduledEvent {
+                    id: id.clone(),
+                    sender: "omar".to_string(),
+                    receiver: receiver.clone(),
+                    timestamp: until_ns,
+                    payload: settings.resume_message.clone(),
+                    created_at: now,
+                    recurring_ns: None,
+                    ea_id: self.active_ea,
+                });
+                id
+            });
+            self.ticker.push(format!(
+                "{} rate-limited, resuming in {}s: {}",
+                receiver,
+                cooldown.as_secs(),
+                limit.message
+            ));
+            self.rate_limited.insert(
+                session,
+                RateLimitHold {
+                    receiver,
+                    until_ns,
+                    nudge_id,
+                },
+            );
+        }
+    }
+
+    /// Spawn due pipeline stages and record stage results.
+    fn advance_pipelines(&mut self, state_dir: &Path, live: &[String]) {
+        let live: HashSet<String> = live.iter().cloned().collect();
+        let results = memory::load_agent_results_from(state_dir);
+        let mut runs = pipeline::load_runs_from(state_dir);
+        for run in runs
+            .iter_mut()
+            .filter(|run| run.status == RunStatus::Running)
+        {
+            let before = run.clone();
+            if let Some(idx) = run.advance(&results, &live) {
+                match self.spawn_pipeline_stage(run, idx) {
+                    Ok(session) => run.stages[idx].agent = Some(session),
+                    Err(e) => {
+                        run.status = RunStatus::Failed;
+                        run.detail = Some(format!("{}: {}", run.stages[idx].role, e));
+                    }
+                }
+            }
+            if *run != before {
+                pipeline::save_run_in(state_dir, run);
+                self.ticker.push(format!("pipeline {}", run.progress()));
+                if run.status == RunStatus::Completed {
+                    self.celebrate(run);
+                }
+            }
+        }
+        let running = &runs;
+        self.run_costs.retain(|session, _| {
+            running
+                .iter()
+                .filter(|run| run.status == RunStatus::Running)
+                .any(|run| run.stages.iter().any(|s| s.agent.as_ref() == Some(session)))
+        });
+        self.pipeline_runs = runs;
+    }
+
+    /// Banner, notification, and webhook `plan_completed` for a run whose
+    /// last stage just completed, with its duration and priced cost.
+    fn celebrate(&mut self, run: &PipelineRun) {
+        if !self.config.notifications.on_plan_complete {
+            return;
+        }
+        let costs: Vec<f64> = run
+            .stages
+            .iter()
+            .filter_map(|stage| self.run_costs.get(stage.agent.as_ref()?))
+            .copied()
+            .collect();
+        let cost = (!costs.is_empty()).then(|| costs.iter().sum());
+        let summary = run.completion_summary(chrono::Utc::now(), cost);
+        self.celebration = Some((summary.clone(), Instant::now()));
+        let last_agent = run
+            .stages
+            .last()
+            .and_then(|stage| stage.agent.clone())
+            .unwrap_or_default();
+        let notification = self
+            .notifier
+            .plan_completed(&last_agent, &run.pipeline, &summary);
+        self.bell_pending |= self.notifier.wants_bell(&notification);
+        self.notifier.send(
+            notification,
+            RunStatus::Completed.as_str(),
+            None,
+            &self.ticker,
+        );
+    }
+
+    /// The completed-run banner while it is fresh.
+    pub fn celebration_banner(&self) -> Option<&str> {
+        self.celebration
+            .as_ref()
+            .filter(|(_, at)| at.elapsed() < CELEBRATION_DURATION)
+            .map(|(summary, _)| summary.as_str())
+    }
+
+    /// Deliver `send` blocks the EA or a PM prints to their target workers,
+    /// once per block, and log each relay.
+    fn relay_manager_sends(&mut self, state_dir: &Path) {
+        if self.demo.is_some() {
+            return;
+        }
+        let manager_session = self.manager_session_name();
+        let prefix = ea::ea_prefix(self.active_ea, &self.base_prefix);
+        let parents = memory::load_agent_parents_from(state_dir);
+        let senders: Vec<String> = self
+            .manager
+            .iter()
+            .chain(&self.agents)
+            .filter(|a| a.session.host.is_none())
+            .map(|a| a.session.name.clone())
+            .filter(|session| *session == manager_session || parents.values().any(|p| p == session))
+            .collect();
+        let baseline = self.relay_baselined.insert(self.active_ea);
+        let mut relays = Vec::new();
+        for sender in senders {
+            // A failed capture keeps the sender's last screen, so its blocks
+            // are not replayed once it succeeds again
+            let Ok(output) = self.client.capture_pane_plain(&sender, 100) else {
+                continue;
+            };
+            let screen = manager::protocol::parse_send_messages(&output);
+            for (target, message) in new_relays(&mut self.relayed, baseline, &sender, screen) {
+                relays.push((sender.clone(), target, message));
+            }
+        }
+        let live: HashSet<&str> = self
+            .latest
+            .sessions
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        self.relayed
+            .retain(|sender, _| live.contains(sender.as_str()));
+        self.last_instructions
+            .retain(|session, _| self.agents.iter().any(|a| &a.session.name == session));
+
+        let traces = memory::load_agent_traces_from(state_dir);
+        for (sender, target, message) in relays {
+            let target_session = naming::resolve_address(&target, &manager_session, &prefix);
+            if !self.agents.iter().any(|a| a.session.name == target_session) {
+                self.ticker
+                    .push(format!("relay to unknown agent {} skipped", target));
+                continue;
+            }
+            let from = naming::address(&sender, &manager_session, &prefix);
+            let to = naming::address(&target_session, &manager_session, &prefix);
+            self.schedule_now(from.clone(), to.clone(), message.clone());
+            if let Err(e) = crate::mcp::append_action_log(
+                state_dir,
+                self.active_ea,
+                &from,
+                "relay",
+                &format!("to {}: {}", to, message),
+                traces.get(&target_session).map(String::as_str),
+            ) {
+                self.ticker.push(format!("audit log failed: {}", e));
+            }
+            self.ticker.push(format!("relay {} → {}", from, to));
+            self.last_instructions
+                .insert(target_session, (from, message));
+        }
+    }
+
+    /// The last instruction relayed to `session`: (sender, message).
+    pub fn last_instruction(&self, session: &str) -> Option<&(String, String)> {
+        self.last_instructions.get(session)
+    }
+
+    /// Spawn a worker for each due schedule of the active EA and refresh the
+    /// timers shown in the status bar.
+    fn run_schedules(&mut self, state_dir: &Path) {
+        let all = schedules::for_ea(&self.config.schedules, self.active_ea, state_dir);
+        let now = chrono::Local::now();
+        let mut last_runs = schedules::load_last_runs_in(state_dir);
+        let before = last_runs.clone();
+        let due = if self.drain.is_some() {
+            Vec::new()
+        } else {
+            schedules::take_due(&all, &mut last_runs, now)
+        };
+        for schedule in due {
+            match self.spawn_scheduled(&schedule, now) {
+                Ok(session) => self.ticker.push(format!(
+                    "schedule {} spawned {}",
+                    schedule.name,
+                    self.short_session_name(&session)
+                )),
+                Err(e) => self
+                    .ticker
+                    .push(format!("schedule {} failed: {}", schedule.name, e)),
+            }
+        }
+        if last_runs != before {
+            schedules::save_last_runs_in(state_dir, &last_runs);
+        }
+
+        let mut timers: Vec<(String, u64)> = all
+            .iter()
+            .filter_map(|schedule| {
+                let next = schedules::next_fire(schedule, now)?;
+                let ns = next.timestamp_nanos_opt()?;
+                Some((schedule.name.clone(), ns as u64))
+            })
+            .collect();
+        timers.sort_by_key(|(_, ns)| *ns);
+        self.schedule_timers = timers;
+    }
+
+    /// Every `github.sync_interval_secs`, import newly labeled issues in the
+    /// background and tell the EA about each one.
+    fn sync_github(&mut self, state_dir: &Path) {
+        if let Some(rx) = &self.github_sync {
+            let Ok(result) = rx.try_recv() else {
+                return;
+            };
+            self.github_sync = None;
+            match result {
+                Ok(imported) => {
+                    for issue in imported.into_iter().flatten() {
+                        self.ticker.push(format!(
+                            "github: imported #{} as project {}",
+                            issue.number, issue.project_id
+                        ));
+                        self.schedule_now(
+                            "github".to_string(),
+                            "ea".to_string(),
+                            github::import_event(&issue),
+                        );
+                    }
+                    self.projects = projects::load_projects_from(state_dir);
+                }
+                Err(e) => self.ticker.push(format!("github: sync failed: {}", e)),
+            }
+        }
+        let config = &self.config.github;
+        let interval = Duration::from_secs(config.sync_interval_secs.max(30));
+        if self.demo.is_some()
+            || config.repo.is_none()
+            || self
+                .github_synced_at
+                .is_some_and(|at| at.elapsed() < interval)
+        {
+            return;
+        }
+        self.github_synced_at = Some(Instant::now());
+        let (tx, rx) = mpsc::channel();
+        let (state_dir, config) = (state_dir.to_path_buf(), config.clone());
+        std::thread::spawn(move || {
+            let _ = tx.send(github::sync_in(&state_dir, &config));
+        });
+        self.github_sync = Some(rx);
+    }
+
+    /// MCP server context for the active EA.
+    fn mcp_context(&self, default_command: String) -> crate::manager::McpLaunchContext {
+        crate::manager::McpLaunchContext {
+            omar_dir: self.omar_dir.clone(),
+            ea_id: self.active_ea,
+            session_prefix: self.base_prefix.clone(),
+            default_command,
+            default_workdir: self.default_workdir.clone(),
+            health_idle_warning: self.health_threshold,
+            tmux_server: std::env::var("OMAR_TMUX_SERVER")
+                .ok()
+                .map(|server| server.trim().to_string())
+                .filter(|server| !server.is_empty()),
+            sandbox: self.config.sandbox.clone(),
+            limits: self.config.limits.clone(),
+            prompts: self.config.prompts.clone(),
+        }
+    }
+
+    /// Start the oldest queued `spawn_agent` request in the background once
+    /// the worker pool has room, one at a time.
+    fn drain_spawn_queue(&mut self, state_dir: &Path) {
+        self.spawn_queue = spawn_queue::load_queue_in(state_dir);
+        self.spawn_queue_len = self.spawn_queue.len();
+        if self.drain.is_some() {
+            return;
+        }
+        let sessions: Vec<String> = self
+            .agents
+            .iter()
+            .filter(|agent| agent.session.host.is_none())
+            .map(|agent| agent.session.name.clone())
+            .collect();
+        self.pool_active = spawn_queue::count_active(
+            &sessions,
+            &self.manager_session_name(),
+            &memory::load_agent_results_from(state_dir),
+        );
+        if let Some(selected) = self.queue_selected.as_mut() {
+            *selected = (*selected).min(self.spawn_queue_len.saturating_sub(1));
+        }
+        if self.spawn_queue_len == 0 || self.queue_draining.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let context = self.mcp_context(self.default_command.clone());
+        let max_concurrent = self.config.agent.max_concurrent;
+        let ticker = self.ticker.clone();
+        let draining = self.queue_draining.clone();
+        std::thread::spawn(move || {
+            match crate::mcp::start_next_queued_spawn(context, max_concurrent) {
+                Some((agent, Ok(_))) => ticker.push(format!("queue: started {}", agent)),
+                Some((agent, Err(e))) => ticker.push(format!("queue: {} failed: {}", agent, e)),
+                None => {}
+            }
+            draining.store(false, Ordering::SeqCst);
+        });
+    }
+
+    /// Type the oldest queued mailbox message into each local agent that is
+    /// waiting for input, in the background. Running agents keep theirs.
+    fn deliver_mailboxes(&mut self, state_dir: &Path) {
+        if !mailbox::exists_in(state_dir) {
+            return;
+        }
+        let local: Vec<&AgentInfo> = self
+            .agents
+            .iter()
+            .chain(self.manager.as_ref())
+            .filter(|agent| agent.session.host.is_none())
+            .collect();
+        let live: HashSet<String> = local.iter().map(|a| a.session.name.clone()).collect();
+        let ready: HashSet<String> = local
+            .iter()
+            .filter(|agent| agent.health != HealthState::Running)
+            .map(|agent| agent.session.name.clone())
+            .collect();
+        if self.mail_delivering.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let client = self.client.clone();
+        let ticker = self.ticker.clone();
+        let state_dir = state_dir.to_path_buf();
+        let delivering = self.mail_delivering.clone();
+        std::thread::spawn(move || {
+            match mailbox::due_in(&state_dir, &ready, &live) {
+                Ok(due) => {
+                    for (session, message) in due {
+                        let outcome = client.deliver_prompt(
+                            &session,
+                            &message.prompt(),
+                            &DeliveryOptions::default(),
+                        );
+                        if let Err(e) = &outcome {
+                            ticker.push(format!("mailbox: {} not delivered: {}", session, e));
+                        }
+                        if let Err(e) =
+                            mailbox::mark_in(&state_dir, &session, &message.id, &outcome)
+                        {
+                            ticker.push(format!("mailbox: {}", e));
+                        }
+                    }
+                }
+                Err(e) => ticker.push(format!("mailbox: {}", e)),
+            }
+            delivering.store(false, Ordering::SeqCst);
+        });
+    }
+
+    /// Every `orchestration.refresh_context_every` seconds, if the EA is
+    /// idle and the fleet changed since the last check, queue the condensed
+    /// memory.md snapshot in its mailbox. The snapshot captures panes, so it
+    /// is written on a background thread and picked up on a later refresh.
+    fn refresh_manager_context(&mut self, state_dir: &Path) {
+        if let Some(rx) = &self.context_snapshot {
+            match rx.try_recv() {
+                Ok(text) => {
+                    self.context_snapshot = None;
+                    self.queue_context_refresh(state_dir, text);
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+                Err(mpsc::TryRecvError::Disconnected) => self.context_snapshot = None,
+            }
+            return;
+        }
+        let Some(every) = self.config.orchestration.refresh_context_every else {
+            return;
+        };
+        let Some(manager) = &self.manager else {
+            return;
+        };
+        if manager.health == HealthState::Running
+            || self
+                .context_refresh
+                .as_ref()
+                .is_some_and(|(at, _)| at.elapsed() < Duration::from_secs(every))
+        {
+            return;
+        }
+        // `agents` is the whole active-EA fleet; the view filter only
+        // applies when rendering
+        let (agents, manager) = (self.agents.clone(), manager.clone());
+        let events = self.scheduler.list_by_ea(self.active_ea);
+        let manager_session = self.manager_session_name();
+        let client = self.client.clone();
+        let state_dir = state_dir.to_path_buf();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            memory::write_memory_to(
+                &state_dir,
+                &agents,
+                Some(&manager),
+                &manager_session,
+                &client,
+                &events,
+            );
+            let _ = tx.send(memory::context_refresh_in(&state_dir));
+        });
+        self.context_snapshot = Some(rx);
+    }
+
+    /// Queue a finished context snapshot if it differs from the last one,
+    /// replacing a refresh the EA has not picked up yet.
+    fn queue_context_refresh(&mut self, state_dir: &Path, text: String) {
+        // The first check only takes a baseline: the EA got memory.md in its
+        // prompt at startup
+        let changed = matches!(&self.context_refresh, Some((_, sent)) if *sent != text);
+        if changed {
+            let session = self.manager_session_name();
+            if let Err(e) =
+                mailbox::replace_in(state_dir, &session, "omar", memory::CONTEXT_REFRESH, &text)
+            {
+                self.ticker.push(format!("context refresh: {}", e));
+                return;
+            }
+        }
+        self.context_refresh = Some((Instant::now(), text));
+    }
+
+    /// Follow the active EA's workers and, once every one has completed or
+    /// been killed with nothing left queued, write a run report.
+    fn track_run(&mut self, state_dir: &Path) {
+        if self.run.ea != self.active_ea {
+            self.run = RunTracker {
+                ea: self.active_ea,
+                ..RunTracker::default()
+            };
+        }
+        let now = chrono::Utc::now().timestamp();
+        let tasks = memory::load_worker_tasks_from(state_dir);
+        let results = memory::load_agent_results_from(state_dir);
+        // Every local worker, including those the view filter hides
+        let live: Vec<(String, Option<i64>)> = self
+            .agents
+            .iter()
+            .filter(|a| a.session.host.is_none() && !a.is_unresolved)
+            .map(|a| (a.session.name.clone(), a.session.created))
+            .collect();
+        self.run
+            .settled
+            .retain(|name| live.iter().any(|(live, _)| live == name));
+        for (name, created) in &live {
+            let complete = results
+                .get(name)
+                .filter(|result| result.kind == ResultKind::Complete);
+            // Already done when first seen: reported before, or finished
+            // while the dashboard was down
+            if self.run.settled.contains(name)
+                || (complete.is_some() && !self.run.workers.contains_key(name))
+            {
+                self.run.settled.insert(name.clone());
+                continue;
+            }
+            let worker = self
+                .run
+                .workers
+                .entry(name.clone())
+                .or_insert_with(|| RunWorker {
+                    task: String::new(),
+                    started_at: created.unwrap_or(now),
+                    ended_at: None,
+                    outcome: None,
+                    output: String::new(),
+                    cost_usd: None,
+                    timeline: Vec::new(),
+                });
+            if let Some(task) = tasks.get(name) {
+                worker.task = task.clone();
+            }
+            if let Some(timeline) = self.latest.history.get(name) {
+                worker.timeline = timeline.clone();
+            }
+            if let Some(cost) = self.run_costs.get(name) {
+                worker.cost_usd = Some(*cost);
+            }
+            match complete {
+                Some(result) => {
+                    if worker.outcome.is_none() {
+                        worker.ended_at = chrono::DateTime::parse_from_rfc3339(&result.detected_at)
+                            .map(|at| at.timestamp())
+                            .ok()
+                            .or(Some(now));
+                    }
+                    worker.outcome = Some(Outcome::Complete);
+                    worker.output = result.detail.clone();
+                }
+                _ => {
+                    worker.outcome = None;
+                    worker.ended_at = None;
+                }
+            }
+        }
+        for (name, worker) in self.run.workers.iter_mut() {
+            if worker.outcome.is_none() && !live.iter().any(|(live, _)| live == name) {
+                worker.outcome = Some(Outcome::Killed);
+                worker.ended_at = Some(now);
+            }
+        }
+        if !self.run.is_finished() || !spawn_queue::load_queue_in(state_dir).is_empty() {
+            return;
+        }
+        let ea_name = self
+            .registered_eas
+            .iter()
+            .find(|ea| ea.id == self.active_ea)
+            .map_or_else(|| self.active_ea.to_string(), |ea| ea.name.clone());
+        match runs::write_report(&self.omar_dir, &self.run, &ea_name, now) {
+            Ok(path) => self.ticker.push(format!("run report: {}", path.display())),
+            Err(e) => self.ticker.push(format!("run report: {}", e)),
+        }
+        let reported = std::mem::take(&mut self.run.workers);
+        self.run.settled.extend(reported.into_keys());
+    }
+
+    /// Track a fleet drain; once every worker has finished (or the deadline
+    /// passed), save memory and quit, which stops all OMAR sessions.
+    fn check_drain(&mut self, state_dir: &Path) {
+        self.drain = drain::load(&self.omar_dir);
+        let Some(state) = self.drain.clone() else {
+            return;
+        };
+        self.drain_waiting = drain::fleet_active_workers(
+            &self.omar_dir,
+            &self.base_prefix,
+            self.config.dashboard.multiplexer,
+        );
+        let Some(outcome) = state.outcome(self.drain_waiting, chrono::Utc::now()) else {
+            return;
+        };
+        let events = self.scheduler.list_by_ea(self.active_ea);
+        memory::write_memory_to(
+            state_dir,
+            &self.agents,
+            self.manager.as_ref(),
+            &self.manager_session_name(),
+            &self.client,
+            &events,
+        );
+        if let Err(e) = drain::cancel(&self.omar_dir) {
+            self.ticker.push(format!("drain: {}", e));
+        }
+        self.ticker.push(match outcome {
+            DrainOutcome::WorkersFinished => "drain: all workers finished, stopping".to_string(),
+            DrainOutcome::DeadlineReached => format!(
+                "drain: deadline reached with {} worker(s) running, stopping",
+                self.drain_waiting
+            ),
+        });
+        self.should_quit = true;
+    }
+
+    /// Open the shutdown dialog over the workers of every EA (TUI `Q`).
+    pub fn open_shutdown_dialog(&mut self) {
+        self.shutdown_dialog = Some(fleet_workers(
+            &self.latest.sessions,
+            &self.latest.health,
+            &self.registered_eas,
+            &self.base_prefix,
+        ));
+    }
+
+    /// Quit with `choice` from the shutdown dialog.
+    pub fn choose_shutdown(&mut self, choice: ShutdownChoice) {
+        self.shutdown_dialog = None;
+        self.shutdown = Some(choice);
+        self.reset_on_quit = choice == ShutdownChoice::KillAll;
+        self.should_quit = true;
+    }
+
+    /// Run once the dashboard has left the terminal: save memory, then stop
+    /// the agents the shutdown choice covers. Returns a summary to print.
+    pub fn shut_down(&mut self) -> String {
+        let choice = self.shutdown.unwrap_or(ShutdownChoice::KillAll);
+        let events = self.scheduler.list_by_ea(self.active_ea);
+        memory::write_memory_to(
+            &self.state_dir(),
+            &self.agents,
+            self.manager.as_ref(),
+            &self.manager_session_name(),
+            &self.client,
+            &events,
+        );
+
+        // Kill ALL OMAR EA sessions (managers + workers), even if registry
+        // and tmux are temporarily out of sync.
+        let client = TmuxClient::new("");
+        if choice == ShutdownChoice::KillAll {
+            let mut stopped = 0;
+            if let Ok(sessions) = client.list_all_sessions() {
+                for session in sessions {
+                    if session.name.starts_with(&self.base_prefix)
+                        && client.kill_session(&session.name).is_ok()
+                    {
+                        stopped += 1;
+                    }
+                }
+            }
+            crate::sandbox::proxy::stop();
+            return format!("omar: stopped {} EA and agent session(s)", stopped);
+        }
+
+        let snapshot = client.list_all_sessions().unwrap_or_default();
+        let workers = fleet_workers(
+            &snapshot,
+            &self.latest.health,
+            &self.registered_eas,
+            &self.base_prefix,
+        );
+        let mut stopped = Vec::new();
+        let mut running = Vec::new();
+        for (session, health) in workers {
+            if choice.stops(health) && client.kill_session(&session).is_ok() {
+                if let Some(info) = self
+                    .registered_eas
+                    .iter()
+                    .find(|info| session.starts_with(&ea::ea_prefix(info.id, &self.base_prefix)))
+                {
+                    let state_dir = ea::ea_state_dir(info.id, &self.omar_dir);
+                    memory::forget_agents_in(&state_dir, std::slice::from_ref(&session));
+                }
+                stopped.push(session);
+            } else {
+                running.push(session);
+            }
+        }
+        let mut summary = String::new();
+        if !stopped.is_empty() {
+            summary.push_str(&format!(
+                "omar: stopped {} idle worker(s): {}\n",
+                stopped.len(),
+                stopped.join(", ")
+            ));
+        }
+        if running.is_empty() {
+            summary.push_str("omar: no workers left running; EAs keep running");
+        } else {
+            summary.push_str(&format!(
+                "omar: left {} worker(s) running: {}\nRun omar again to pick them up.",
+                running.len(),
+                running.join(", ")
+            ));
+        }
+        summary
+    }
+
+    /// Start a drain, or cancel the one in progress (TUI `Z`).
+    pub fn toggle_drain(&mut self) {
+        let result = if self.drain.is_some() {
+            drain::cancel(&self.omar_dir).map(|_| "Drain cancelled")
+        } else {
+            drain::start(&self.omar_dir, None)
+                .map(|_| "Draining: no new agents; exiting when workers finish")
+        };
+        match result {
+            Ok(key) => self.set_status_tr(key),
+            Err(e) => self.set_status_trf("Error: {}", &[&e]),
+        }
+        self.drain = drain::load(&self.omar_dir);
+    }
+
+    /// Why the queued spawn at `index` has not started yet.
+    pub fn queue_reason(&self, index: usize) -> String {
+        if let Some(wait) = self.spawn_queue.get(index).and_then(QueuedSpawn::wait_for) {
+            return format!("waiting for {}", wait.describe());
+        }
+        match self.config.agent.max_concurrent {
+            Some(max) if self.pool_active >= max => {
+                format!("concurrency cap ({}/{} active)", self.pool_active, max)
+            }
+            _ if index > 0 => "behind earlier requests".to_string(),
+            _ => "starting".to_string(),
+        }
+    }
+
+    /// Cancel the queued spawn selected in the queue panel.
+    pub fn cancel_selected_queued(&mut self) {
+        let Some(agent) = self.selected_queued_agent() else {
+            return;
+        };
+        let result = crate::mcp::with_state_lock(&self.state_dir(), |dir| {
+            spawn_queue::remove_in(dir, &agent)
+        });
+        match result {
+            Ok(true) => self.set_status_trf("Cancelled queued spawn: {}", &[&agent]),
+            Ok(false) => self.set_status_trf("{} already left the queue", &[&agent]),
+            Err(e) => self.set_status_trf("Error: {}", &[&e]),
+        }
+        self.reload_spawn_queue();
+    }
+
+    /// Raise the priority of the queued spawn selected in the queue panel.
+    pub fn bump_selected_queued(&mut self) {
+        let Some(agent) = self.selected_queued_agent() else {
+            return;
+        };
+        let result =
+            crate::mcp::with_state_lock(&self.state_dir(), |dir| spawn_queue::bump_in(dir, &agent));
+        match result {
+            Ok(Some(position)) => {
+                self.set_status_trf("Bumped {} to position {}", &[&agent, &position]);
+                self.reload_spawn_queue();
+                self.queue_selected = Some(position - 1);
+            }
+            Ok(None) => {
+                self.set_status_trf("{} already left the queue", &[&agent]);
+                self.reload_spawn_queue();
+            }
+            Err(e) => self.set_status_trf("Error: {}", &[&e]),
+        }
+    }
+
+    fn selected_queued_agent(&self) -> Option<String> {
+        self.spawn_queue
+            .get(self.queue_selected?)
+            .map(|queued| queued.agent.clone())
+    }
+
+    fn reload_spawn_queue(&mut self) {
+        self.spawn_queue = spawn_queue::load_queue_in(&self.state_dir());
+        self.spawn_queue_len = self.spawn_queue.len();
+        if let Some(selected) = self.queue_selected.as_mut() {
+            *selected = (*selected).min(self.spawn_queue_len.saturating_sub(1));
+        }
+    }
+
+    /// Re-read health and readiness patterns and `[[hosts]]` when
+    /// config.toml changes, so `set_health_patterns` reaches dashboard
+    /// spawns and new hosts are reachable without a restart.
+    fn reload_config(&mut self) {
+        let path = self.omar_dir.join("config.toml");
+        let current = crate::snapshot::modified(&path);
+        if current.is_none() || current == self.config_modified {
+            return;
+        }
+        self.config_modified = current;
+        // A half-written or invalid file keeps the patterns in use
+        if let Ok(config) = Config::load(Some(&path.to_string_lossy())) {
+            self.config.health = config.health;
+            self.config.agent.readiness = config.agent.readiness;
+            if config.hosts != self.config.hosts {
+                self.config.hosts = config.hosts;
+                self.remote = remote_tmux_for(&self.config, self.client.prefix());
+            }
+        }
+    }
+
+    /// Reconcile the active EA's state with surviving sessions after a
+    /// restart, and ask to resume workers whose sessions are gone, plus the
+    /// unfinished ones in the EA's `omar save` file.
+    pub fn restore_previous_run(&mut self) -> Result<()> {
+        let live: Vec<String> = self
+            .client
+            .list_sessions()?
+            .into_iter()
+            .map(|session| session.name)
+            .collect();
+        let state_dir = self.state_dir();
+        let reconciliation = restore::reconcile(&state_dir, &live, &self.manager_session_name());
+        for session in &reconciliation.adopted {
+            self.ticker.push(format!(
+                "Restore: adopted orphaned session {}",
+                self.short_session_name(session)
+            ));
+        }
+        for session in &reconciliation.reparented {
+            self.ticker.push(format!(
+                "Restore: {} now reports to the EA (parent is gone)",
+                self.short_session_name(session)
+            ));
+        }
+        self.resumable = reconciliation.resumable;
+        let saved = restore::saved_path(&self.omar_dir, self.active_ea);
+        if let Ok(fleet) = restore::load_fleet(&saved) {
+            let prefix = self.active_session_prefix();
+            let manager_session = self.manager_session_name();
+            for agent in fleet.pending(&state_dir, &prefix, &live) {
+                let agent = fleet.resumable(agent, &prefix, &manager_session);
+                if !self.resumable.iter().any(|r| r.session == agent.session) {
+                    self.resumable.push(agent);
+                }
+            }
+        }
+        if self.resumable.is_empty() {
+            return Ok(());
+        }
+        if self.config.agent.auto_resume {
+            self.resume_previous_run();
+        } else if self.pending_confirm.is_none() {
+            self.pending_confirm = Some(ConfirmAction::ResumeRun);
+        }
+        Ok(())
+    }
+
+    /// Re-spawn every resumable worker with its recorded command, workdir,
+    /// sandbox, role, and project, and re-send its task. The EA's save file is consumed
+    /// once every worker is back.
+    pub fn resume_previous_run(&mut self) {
+        let manager_session = self.manager_session_name();
+        let mut resumed = 0;
+        let mut failed = 0;
+        for agent in std::mem::take(&mut self.resumable) {
+            let name = self.short_session_name(&agent.session).to_string();
+            let parent = if agent.parent == manager_session {
+                "ea".to_string()
+            } else {
+                self.short_session_name(&agent.parent).to_string()
+            };
+            let launch = agent.launch.unwrap_or_else(|| memory::AgentLaunch {
+                command: self.config.agent.default_command.clone(),
+                workdir: self.resolved_default_workdir(),
+                sandboxed: true,
+                security: Vec::new(),
+                pod: None,
+            });
+            let request = SpawnRequest {
+                name: Some(name.clone()),
+                command: launch.command,
+                workdir: launch.workdir,
+                task: (!agent.task.is_empty()).then_some(agent.task),
+                parent,
+                sandbox: launch.sandboxed,
+                profile: None,
+            };
+            match self.spawn_from_request(&request) {
+                Ok(session) => {
+                    let mut update = memory::AgentUpdate::new(&session).role(agent.role);
+                    if let Some(project) = agent.project {
+                        update = update.project(project);
+                    }
+                    if let Err(e) = update.save_in(&self.state_dir()) {
+                        self.ticker.push(format!("Restore: {}: {}", name, e));
+                    }
+                    resumed += 1;
+                }
+                Err(e) => {
+                    failed += 1;
+                    self.ticker.push(format!("Restore: {} failed: {}", name, e));
+                }
+            }
+        }
+        if failed == 0 {
+            let _ = std::fs::remove_file(restore::saved_path(&self.omar_dir, self.active_ea));
+        }
+        self.set_status_trf("Resumed {} agent(s) from the previous run", &[&resumed]);
+        let _ = self.refresh();
+    }
+
+    /// Decline resuming: forget the previous run's unfinished workers and
+    /// the EA's save file, so neither is offered again.
+    pub fn dismiss_previous_run(&mut self) {
+        restore::dismiss(&self.state_dir(), &std::mem::take(&mut self.resumable));
+        let _ = std::fs::remove_file(restore::saved_path(&self.omar_dir, self.active_ea));
+    }
+
+    fn spawn_scheduled(
+        &mut self,
+        schedule: &ScheduleConfig,
+        now: chrono::DateTime<chrono::Local>,
+    ) -> Result<String> {
+        let command = match schedule.backend.as_deref() {
+            Some(backend) => {
+                crate::config::resolve_backend(backend).map_err(|e| anyhow::anyhow!(e))?
+            }
+            None => self.config.agent.default_command.clone(),
+        };
+        let workdir = match schedule.workdir.as_deref() {
+            Some(dir) => crate::config::expand_tilde(dir)
+                .to_string_lossy()
+                .to_string(),
+            None => self.resolved_default_workdir(),
+        };
+        let request = SpawnRequest {
+            name: Some(schedules::agent_name(schedule, now)),
+            command,
+            workdir,
+            task: Some(schedule.task.clone()),
+            parent: "ea".to_string(),
+            sandbox: true,
+            profile: None,
+        };
+        self.spawn_from_request(&request)
+    }
+
+    /// Open the review dialog once for each run that newly reaches a gated
+    /// stage, unless another popup is in the way.
+    fn surface_pending_approval(&mut self) {
+        if self.has_popup() {
+            return;
+        }
+        let pending = self.pipeline_runs.iter().find(|run| {
+            run.status == RunStatus::AwaitingApproval && !self.approvals_surfaced.contains(&run.id)
+        });
+        if let Some(id) = pending.map(|run| run.id) {
+            self.approvals_surfaced.insert(id);
+            self.approval_review = Some(id);
+        }
+    }
+
+    /// Review the oldest run awaiting approval.
+    pub fn open_approval_review(&mut self) {
+        match self
+            .pipeline_runs
+            .iter()
+            .find(|run| run.status == RunStatus::AwaitingApproval)
+        {
+            Some(run) => self.approval_review = Some(run.id),
+            None => self.set_status_tr("No pipeline stages awaiting approval"),
+        }
+    }
+
+    pub fn approval_review_run(&self) -> Option<&PipelineRun> {
+        let id = self.approval_review?;
+        self.pipeline_runs.iter().find(|run| run.id == id)
+    }
+
+    /// Approve or reject the reviewed stage. The next refresh spawns an
+    /// approved stage.
+    pub fn resolve_approval_review(&mut self, approve: bool) -> Result<()> {
+        let Some(id) = self.approval_review.take() else {
+            return Ok(());
+        };
+        let run = pipeline::resolve_approval_in(&self.state_dir(), id, approve, None)?;
+        self.ticker.push(format!("pipeline {}", run.progress()));
+        if let Some(existing) = self.pipeline_runs.iter_mut().find(|r| r.id == id) {
+            *existing = run;
+        }
+        Ok(())
+    }
+
+    fn spawn_pipeline_stage(&mut self, run: &PipelineRun, idx: usize) -> Result<String> {
+        let command = match run.stages[idx].backend.as_deref() {
+            Some(backend) => {
+                crate::config::resolve_backend(backend).map_err(|e| anyhow::anyhow!(e))?
+            }
+            None => self.config.agent.default_command.clone(),
+        };
+        let request = SpawnRequest {
+            name: Some(run.stage_agent_name(idx)),
+            command,
+            workdir: self.resolved_default_workdir(),
+            task: Some(run.stage_task(idx)),
+            parent: "ea".to_string(),
+            sandbox: true,
+            profile: None,
+        };
+        self.spawn_from_request(&request)
+    }
+
+    /// Report spawned/exited/health/result changes to automation scripts and
+    /// run due interval hooks. Returns the actions they queued.
+    fn send_notifications(&mut self, new_results: &[(String, AgentResult)]) {
+        let now = chrono::Utc::now().timestamp();
+        let agents: Vec<(String, String, HealthState, i64, i64)> = self
+            .agents
+            .iter()
+            .map(|agent| {
+                let backend = self.latest.backends.get(&agent.session.name);
+                (
+                    agent.session.name.clone(),
+                    self.short_session_name(&agent.session.name).to_string(),
+                    agent.health,
+                    now - agent.session.activity,
+                    self.config
+                        .health
+                        .idle_critical_for(backend.map(String::as_str)),
+                )
+            })
+            .collect();
+        let new_results: Vec<(String, String, AgentResult)> = new_results
+            .iter()
+            .map(|(session, result)| {
+                (
+                    session.clone(),
+                    self.short_session_name(session).to_string(),
+                    result.clone(),
+                )
+            })
+            .collect();
+        for notification in self.notifier.collect(&agents, &new_results) {
+            self.bell_pending |= self.notifier.wants_bell(&notification);
+            let state = self
+                .agents
+                .iter()
+                .find(|agent| agent.session.name == notification.session)
+                .map_or("missing", |agent| agent.health.as_str());
+            // Only webhooks carry the output snippet; skip the capture otherwise
+            let last_output = self
+                .notifier
+                .wants_webhook(&notification)
+                .then(|| {
+                    self.client
+                        .capture_pane_plain(&notification.session, 30)
+                        .ok()
+                })
+                .flatten()
+                .map(|output| {
+                    let cleaned = crate::mcp::clean_human_output(&output);
+                    let lines: Vec<&str> = cleaned.lines().collect();
+                    lines[lines.len().saturating_sub(10)..].join("\n")
+                });
+            self.notifier
+                .send(notification, state, last_output, &self.ticker);
+        }
+    }
+
+    /// Every `TAMPER_CHECK_INTERVAL`, verify each sandboxed local worker
+    /// still runs under the runtime and flags it was launched with. Alerts
+    /// once per session, with a notification and an audit log entry.
+    fn check_sandbox_tamper(&mut self, state_dir: &Path) {
+        if self.demo.is_some()
+            || self
+                .tamper_checked_at
+                .is_some_and(|at| at.elapsed() < TAMPER_CHECK_INTERVAL)
+        {
+            return;
+        }
+        self.tamper_checked_at = Some(Instant::now());
+        let launches = memory::load_agent_launches_from(state_dir);
+        self.tamper_alerted
+            .retain(|session| launches.contains_key(session));
+        let proc_dir = Path::new("/proc");
+        let mut alerts = Vec::new();
+        for agent in self.agents.iter().filter(|a| a.session.host.is_none()) {
+            let session = &agent.session.name;
+            let Some(launch) = launches.get(session) else {
+                continue;
+            };
+            if self.tamper_alerted.contains(session) {
+                continue;
+            }
+            if let Some(detail) =
+                crate::sandbox::tamper::check(proc_dir, agent.session.pane_pid, &launch.security)
+            {
+                alerts.push((session.clone(), agent.health, detail));
+            }
+        }
+        let traces = memory::load_agent_traces_from(state_dir);
+        for (session, health, detail) in alerts {
+            let name = self.short_session_name(&session).to_string();
+            self.tamper_alerted.insert(session.clone());
+            self.ticker.push(format!("sandbox: {} {}", name, detail));
+            self.set_status_trf("⚠ Sandbox of {} was modified: {}", &[&name, &detail]);
+            if let Err(e) = crate::mcp::append_action_log(
+                state_dir,
+                self.active_ea,
+                &name,
+                "sandbox_tamper",
+                &detail,
+                traces.get(&session).map(String::as_str),
+            ) {
+                self.ticker.push(format!("audit log failed: {}", e));
+            }
+            let notification = self.notifier.sandbox_alert(&session, &name, &detail);
+            self.bell_pending |= self.notifier.wants_bell(&notification);
+            self.notifier
+                .send(notification, health.as_str(), None, &self.ticker);
+        }
+    }
+
+    /// Send each worker idle for `health.escalate_stuck_after` seconds, once
+    /// per idle stretch, to the nearest ancestor that is not stuck itself,
+    /// with its last 30 lines and what the receiver can do about it.
+    fn escalate_stuck(&mut self, state_dir: &Path) {
+        let Some(limit) = self.config.health.escalate_stuck_after else {
+            return;
+        };
+        if self.demo.is_some() {
+            return;
+        }
+        let now = chrono::Utc::now().timestamp();
+        let stuck: HashMap<String, i64> = self
+            .agents
+            .iter()
+            .filter(|agent| agent.health == HealthState::Idle && !agent.is_unresolved)
+            .map(|agent| (agent.session.name.clone(), now - agent.session.activity))
+            .filter(|(_, idle_secs)| *idle_secs >= limit as i64)
+            .collect();
+        self.escalated.retain(|session| stuck.contains_key(session));
+        let manager_session = self.manager_session_name();
+        let parents = memory::load_agent_parents_from(state_dir);
+        let traces = memory::load_agent_traces_from(state_dir);
+        let mut due: Vec<(&String, &i64)> = stuck
+            .iter()
+            .filter(|(session, _)| !self.escalated.contains(*session))
+            .collect();
+        due.sort();
+        for (session, idle_secs) in due {
+            let name = self.short_session_name(session).to_string();
+            let target = escalation_target(session, &parents, &stuck, &manager_session);
+            // A parent that has exited cannot act on it
+            let receiver = if !self.agents.iter().any(|a| a.session.name == target) {
+                naming::EA.to_string()
+            } else {
+                self.short_session_name(&target).to_string()
+            };
+            let output = self
+                .client_for_session(session)
+                .capture_pane_plain(session, 30)
+                .map(|output| crate::mcp::clean_human_output(&output))
+                .unwrap_or_default();
+            let payload = format!(
+                "[STUCK] {} has been idle for {}m. Last output:\n{}\n\n\
+                 Options: send_message {} a nudge or missing context; \
+                 get_agent {} for more output; \
+                 kill_agent {} and spawn_agent a replacement with a clearer task; \
+                 or report it up if you cannot unblock it.",
+                name,
+                idle_secs / 60,
+                output.trim(),
+                name,
+                name,
+                name
+            );
+            self.schedule_now(name.clone(), receiver.clone(), payload);
+            self.escalated.insert(session.clone());
+            let detail = format!("idle {}m, escalated to {}", idle_secs / 60, receiver);
+            self.ticker.push(format!("stuck: {} {}", name, detail));
+            if let Err(e) = crate::mcp::append_action_log(
+                state_dir,
+                self.active_ea,
+                &name,
+                "escalate_stuck",
+                &detail,
+                traces.get(session).map(String::as_str),
+            ) {
+                self.ticker.push(format!("audit log failed: {}", e));
+            }
+        }
+    }
+
+    /// Kill idle or finished workers quiet for `agent.auto_kill_idle_after`
+    /// seconds, after archiving their transcript and telling their parent.
+    fn auto_kill_idle(&mut self, state_dir: &Path) {
+        let Some(limit) = self.config.agent.auto_kill_idle_after else {
+            return;
+        };
+        if self.demo.is_some() {
+            return;
+        }
+        let parents = memory::load_agent_parents_from(state_dir);
+        let idle = idle_workers(
+            &self.agents,
+            &parents,
+            &memory::load_agent_roles_from(state_dir),
+            chrono::Utc::now().timestamp(),
+            limit,
+        );
+        let manager_session = self.manager_session_name();
+        let traces = memory::load_agent_traces_from(state_dir);
+        for (session, idle_secs) in idle {
+            let name = self.short_session_name(&session).to_string();
+            let pane = self
+                .client_for_session(&session)
+                .capture_pane_plain(&session, 2000)
+                .unwrap_or_default();
+            let archived = match transcript::archive(&session, &name, &pane) {
+                Ok(path) => format!("transcript archived at {}", path.display()),
+                Err(e) => format!("transcript not archived: {}", e),
+            };
+            let detail = format!("idle for {}m, shut down; {}", idle_secs / 60, archived);
+            let receiver = match parents.get(&session) {
+                Some(parent) if *parent != manager_session => {
+                    self.short_session_name(parent).to_string()
+                }
+                _ => "ea".to_string(),
+            };
+            self.schedule_now(
+                name.clone(),
+                receiver,
+                format!("[AUTO KILLED] {} {}", name, detail),
+            );
+            if let Err(e) = self.kill_agent_session(&session) {
+                self.ticker
+                    .push(format!("auto-kill of {} failed: {}", name, e));
+                continue;
+            }
+            self.agents.retain(|agent| agent.session.name != session);
+            self.ticker.push(format!("auto-kill: {} {}", name, detail));
+            if let Err(e) = crate::mcp::append_action_log(
+                state_dir,
+                self.active_ea,
+                &name,
+                "auto_kill_idle",
+                &detail,
+                traces.get(&session).map(String::as_str),
+            ) {
+                self.ticker.push(format!("audit log failed: {}", e));
+            }
+        }
+    }
+
+    fn run_script_hooks(&mut self, new_results: &[(String, AgentResult)]) -> Vec<ScriptAction> {
+        let Some(host) = self.scripts.as_mut() else {
+            return Vec::new();
+        };
+        let current: HashMap<String, HealthState> = self
+            .manager
+            .iter()
+            .chain(self.agents.iter())
+            .map(|a| (a.session.name.clone(), a.health))
+            .collect();
+        let manager_session = ea::ea_manager_session(self.active_ea, &self.base_prefix);
+        let prefix = ea::ea_prefix(self.active_ea, &self.base_prefix);
+        let short = |session: &str| naming::address(session, &manager_session, &prefix);
+
+        let mut events = Vec::new();
+        if let Some(previous) = self.script_health.replace(current.clone()) {
+            for (session, health) in &current {
+                match previous.get(session) {
+                    None => events.push(ScriptEvent::new("spawned", short(session))),
+                    Some(prev) if prev != health => events.push(
+                        ScriptEvent::new("health", short(session))
+                            .with("from", prev.as_str())
+                            .with("to", health.as_str()),
+                    ),
+                    _ => {}
+                }
+            }
+            for session in previous.keys().filter(|s| !current.contains_key(*s)) {
+                events.push(ScriptEvent::new("exited", short(session)));
+            }
+            for (session, result) in new_results {
+                events.push(
+                    ScriptEvent::new("result", short(session))
+                        .with("result", result.kind.as_str())
+                        .with("detail", result.detail.clone()),
+                );
+            }
+        }
+
+        let mut outcomes = Vec::new();
+        for event in &events {
+            outcomes.extend(host.on_event(event));
+        }
+        outcomes.extend(host.tick());
+        outcomes
+            .into_iter()
+            .filter_map(|outcome| match outcome {
+                Ok(action) => Some(action),
+                Err(error) => {
+                    self.ticker.push(error);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn apply_script_action(&mut self, action: ScriptAction) {
+        let outcome = match action {
+            ScriptAction::Spawn { name, task } => self
+                .spawn_named_agent(&name, task.as_deref())
+                .map(|_| format!("script spawned {}", name)),
+            ScriptAction::Send { name, text } => {
+                self.schedule_now("script".to_string(), name.clone(), text);
+                Ok(format!("script queued message for {}", name))
+            }
+            ScriptAction::Kill { name } => {
+                let session = naming::session_name(&self.active_session_prefix(), &name);
+                self.kill_agent_session(&session)
+                    .map(|_| format!("script killed {}", name))
+            }
+            ScriptAction::Notify { text } => {
+                self.set_status(text.clone());
+                Ok(text)
+            }
+        };
+        match outcome {
+            Ok(message) => self.ticker.push(message),
+            Err(e) => self.ticker.push(format!("script action failed: {}", e)),
+        }
+    }
+
+    fn apply_dashboard_launch_handoff(&mut self) -> Result<()> {
+        let Some(handoff) = ea::take_dashboard_launch_handoff(&self.omar_dir) else {
+            return Ok(());
+        };
+
+        // Capture the pre-handoff backend so we only restart the manager when
+        // the user actually switched backends. `omar -a claude` while already
+        // on claude shouldn't kill the running EA — that throws away the
+        // backend's in-memory conversation for nothing.
+        let backend_changed = self.default_command != handoff.default_command;
+
+        self.config.agent.default_command = handoff.default_command.clone();
+        self.config.agent.default_workdir = handoff.default_workdir.clone();
+        self.default_command = handoff.default_command;
+        self.default_workdir = handoff.default_workdir;
+        self.activate_ea_local(handoff.active_ea)?;
+        ea::save_active_ea(&self.omar_dir, handoff.active_ea)?;
+
+        if handoff.restart_manager && backend_changed {
+            let manager_session = ea::ea_manager_session(handoff.active_ea, &self.base_prefix);
+            if self.client.has_session(&manager_session)? {
+                self.client.kill_session(&manager_session)?;
+                self.manager = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// Build the startup command attempts for the manager.
+    ///
+    /// In strict mode we issue exactly one launch command so behavior matches
+    /// main and does not silently degrade to alternate variants.
+    fn manager_startup_attempts(&self) -> Vec<(String, bool)> {
+        vec![(self.default_command.clone(), true)]
+    }
+
+    /// Ensure manager session exists, start if not
+    fn ensure_manager(&mut self) -> Result<()> {
+        if self.demo.is_some() {
+            return Ok(());
+        }
+        let canonical_manager_session = self.manager_session_name();
+        let manager_session = canonical_manager_session.clone();
+
+        if self.client.has_session(&manager_session)? {
+            if self.client.session_has_live_pane(&manager_session)? {
+                return Ok(());
+            }
+            let _ = self.client.kill_session(&manager_session);
+        }
+
+        // Reload registry on cache miss so we have the latest EA names
+        self.registered_eas = ea::load_registry(&self.omar_dir);
+
+        // Get EA name for prompt
+        let ea_name = self
+            .registered_eas
+            .iter()
+            .find(|ea| ea.id == self.active_ea)
+            .map(|ea| ea.name.as_str())
+            .unwrap_or("Default");
+
+        let workdir = self.default_workdir.clone();
+
+        let (default_command, inject_prompt) = self
+            .manager_startup_attempts()
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| (self.default_command.clone(), true));
+
+        let context = self.mcp_context(default_command.clone());
+
+        let (cmd, workspace_cwd) = if inject_prompt {
+            crate::manager::build_ea_command(
+                &default_command,
+                self.active_ea,
+                ea_name,
+                &self.omar_dir,
+                &context,
+            )
+        } else {
+            (default_command.clone(), None)
+        };
+
+        // For backends whose manager prompt is now loaded from an auto-
+        // discovered file (codex `AGENTS.md`, agy MCP plugin config, opencode
+        // `AGENTS.md`), build_ea_command returns the workspace dir we must
+        // launch in. Fall back to the user's workdir for claude/cursor.
+        let launch_cwd = workspace_cwd
+            .as_ref()
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or(workdir);
+
+        self.client
+            .new_session(&manager_session, &cmd, Some(&launch_cwd))
+            .map_err(|err| {
+                let msg = format!(
+                    "tmux failed to start manager '{}' (cwd '{}') with command '{}': {}",
+                    manager_session, launch_cwd, default_command, err
+                );
+                anyhow::anyhow!(msg)
+            })?;
+        transcript::start(&self.client, &manager_session);
+
+        let state_dir = self.state_dir();
+        let events = self.scheduler.list_by_ea(self.active_ea);
+        memory::write_memory_to(
+            &state_dir,
+            &self.agents,
+            None,
+            &manager_session,
+            &self.client,
+            &events,
+        );
+        Ok(())
+    }
+
+    /// Get default command
+    pub fn default_command(&self) -> &str {
+        &self.default_command
+    }
+
+    /// Get the worker_tasks map (for display)
+    pub fn worker_tasks(&self) -> &HashMap<String, String> {
+        &self.worker_tasks
+    }
+
+    /// Tags of `session`, empty when it has none
+    pub fn agent_priority(&self, session: &str) -> Priority {
+        self.priorities
+            .read()
+            .ok()
+            .and_then(|p| p.get(session).copied())
+            .unwrap_or_default()
+    }
+
+    /// Cycle the selected agent's refresh priority: normal → hot → cold.
+    pub fn cycle_selected_priority(&mut self) {
+        let Some(session) = self.selected_agent().map(|a| a.session.name.clone()) else {
+            return;
+        };
+        let priority = self.agent_priority(&session).next();
+        memory::save_agent_priority_in(&self.state_dir(), &session, priority);
+        if let Ok(mut shared) = self.priorities.write() {
+            if priority == Priority::Normal {
+                shared.remove(&session);
+            } else {
+                shared.insert(session.clone(), priority);
+            }
+        }
+        self.set_status_trf(
+            "{} is now {} priority",
+            &[&self.short_session_name(&session), &priority.as_str()],
+        );
+    }
+
+    /// Show the selected agent's next (or previous) pane on its card. The
+    /// agent's own pane shows its task as usual.
+    pub fn cycle_selected_pane(&mut self, forward: bool) {
+        let Some(agent) = self.selected_agent() else {
+            return;
+        };
+        let session = agent.session.name.clone();
+        if agent.session.host.is_some() {
+            self.set_status_tr("Pane cycling is only available for local agents");
+            return;
+        }
+        let panes = self.client.list_pane_ids(&session).unwrap_or_default();
+        if panes.len() < 2 {
+            self.pane_views.remove(&session);
+            self.set_status_trf(
+                "{} has no extra panes",
+                &[&self.short_session_name(&session)],
+            );
+            return;
+        }
+        let current = self.pane_views.get(&session).map_or(0, |(_, i)| *i);
+        let next = if forward {
+            (current + 1) % panes.len()
+        } else {
+            (current + panes.len() - 1) % panes.len()
+        };
+        if next == 0 {
+            self.pane_views.remove(&session);
+        } else {
+            self.pane_views.insert(session, (panes, next));
+        }
+    }
+
+    /// The auxiliary pane in view on `session`'s card: its id, its position
+    /// (1-based, counting the agent's own pane), and the pane count.
+    pub fn pane_view(&self, session: &str) -> Option<(&str, usize, usize)> {
+        self.pane_views
+            .get(session)
+            .map(|(panes, i)| (panes[*i].as_str(), i + 1, panes.len()))
+    }
+
+    pub fn agent_tags(&self, session: &str) -> &[String] {
+        self.agent_tags.get(session).map_or(&[], Vec::as_slice)
+    }
+
+    /// The user's note on the agent
+    pub fn agent_note(&self, session: &str) -> Option<&str> {
+        self.agent_notes.get(session).map(String::as_str)
+    }
+
+    /// The agent's latest self-reported progress
+    pub fn agent_progress(&self, session: &str) -> Option<&memory::ProgressReport> {
+        self.agent_progress.get(session)
+    }
+
+    /// Whether the dashboard filter shows `agent`. Filtering only hides
+    /// agents from the views; `self.agents` always holds the whole fleet.
+    fn passes_filter(&self, agent: &AgentInfo) -> bool {
+        self.filter.is_empty()
+            || matches_filter(
+                &agent.session.name,
+                self.agent_tags
+                    .get(&agent.session.name)
+                    .map_or(&[], Vec::as_slice),
+                &self.filter,
+            )
+    }
+
+    /// Compute indices into self.agents for focus_parent's direct children
+    /// that pass the filter
+    fn compute_focus_child_indices(&self) -> Vec<usize> {
+        let manager_session = self.manager_session_name();
+        let mut indices = Vec::new();
+        if self.focus_parent == manager_session {
+            // Root view: show agents that are direct children of EA, plus orphans
+            for (i, agent) in self.agents.iter().enumerate() {
+                if !self.passes_filter(agent) {
+                    continue;
+                }
+                let parent = self.agent_parents.get(&agent.session.name);
                 match parent {
                     // Explicit child of EA (manager session)
                     Some(p) if *p == manager_session => indices.push(i),
@@ -628,6 +2924,9 @@ impl App {
         } else {
             // Non-root: show agents whose parent matches focus_parent
             for (i, agent) in self.agents.iter().enumerate() {
+                if !self.passes_filter(agent) {
+                    continue;
+                }
                 if let Some(parent) = self.agent_parents.get(&agent.session.name) {
                     if *parent == self.focus_parent {
                         indices.push(i);
@@ -659,6 +2958,20 @@ impl App {
     }
 
     /// Count children for a given agent
+    /// Worst health among `session_name`'s descendants, if it has any.
+    pub fn team_health(&self, session_name: &str) -> Option<HealthState> {
+        self.team_health.get(session_name).copied()
+    }
+
+    /// Recent health transitions of a local session, oldest first.
+    pub fn health_history(&self, session_name: &str) -> &[HealthTransition] {
+        self.latest
+            .history
+            .get(session_name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
     pub fn child_count(&self, session_name: &str) -> usize {
         if session_name == self.manager_session_name() {
             // Count PMs + orphans
@@ -675,7 +2988,7 @@ impl App {
         let session_name = if self.manager_selected {
             // On EA: hint to select a child if children exist, otherwise silent
             if !self.focus_child_indices.is_empty() {
-                self.set_status("Highlight sub-agents to drill into them");
+                self.set_status_tr("Highlight sub-agents to drill into them");
             }
             return;
         } else {
@@ -684,11 +2997,11 @@ impl App {
                 if let Some(agent) = self.agents.get(idx) {
                     agent.session.name.clone()
                 } else {
-                    self.set_status("No agent selected");
+                    self.set_status_tr("No agent selected");
                     return;
                 }
             } else {
-                self.set_status("No agent selected");
+                self.set_status_tr("No agent selected");
                 return;
             }
         };
@@ -703,13 +3016,13 @@ impl App {
         self.manager_selected = self.focus_child_indices.is_empty();
 
         let short = self.short_session_name(&session_name);
-        self.set_status(format!("Viewing: {}", short));
+        self.set_status_trf("Viewing: {}", &[&short]);
     }
 
     /// Drill up to the parent view (Esc). Returns true if drilled up, false if at root.
     pub fn drill_up(&mut self) -> bool {
         if self.focus_stack.is_empty() {
-            self.set_status("Already at the top level");
+            self.set_status_tr("Already at the top level");
             return false;
         }
         self.focus_parent = self.focus_stack.pop().expect("checked is_empty above");
@@ -756,38 +3069,144 @@ impl App {
                 self.selected -= 1;
             }
         } else {
-            self.manager_selected = true;
+            self.manager_selected = true;
+        }
+    }
+
+    /// Positions in the focus children where a group starts: every agent
+    /// with sub-agents of its own (a PM) heads a group, and the first
+    /// childless agent heads the Unassigned group.
+    pub fn group_starts(&self) -> Vec<usize> {
+        let mut starts = Vec::new();
+        let mut unassigned = false;
+        for (pos, &idx) in self.focus_child_indices.iter().enumerate() {
+            let Some(agent) = self.agents.get(idx) else {
+                continue;
+            };
+            if self.child_count(&agent.session.name) > 0 {
+                starts.push(pos);
+            } else if !unassigned {
+                unassigned = true;
+                starts.push(pos);
+            }
+        }
+        starts
+    }
+
+    /// Jump selection to the next group (J), wrapping to the focus parent.
+    pub fn next_group(&mut self) {
+        let starts = self.group_starts();
+        let target = if self.manager_selected {
+            starts.first().copied()
+        } else {
+            starts.into_iter().find(|&pos| pos > self.selected)
+        };
+        match target {
+            Some(pos) => {
+                self.manager_selected = false;
+                self.selected = pos;
+            }
+            None => self.manager_selected = true,
+        }
+    }
+
+    /// Jump selection to the previous group (K), wrapping to the focus parent.
+    pub fn previous_group(&mut self) {
+        let starts = self.group_starts();
+        let target = if self.manager_selected {
+            starts.last().copied()
+        } else {
+            starts.into_iter().rev().find(|&pos| pos < self.selected)
+        };
+        match target {
+            Some(pos) => {
+                self.manager_selected = false;
+                self.selected = pos;
+            }
+            None => self.manager_selected = true,
+        }
+    }
+
+    /// Sidebar panels currently drawn, top to bottom.
+    pub fn sidebar_panels(&self) -> Vec<SidebarPanel> {
+        let mut panels = Vec::new();
+        if self.ui.show_projects {
+            panels.push(SidebarPanel::Projects);
+        }
+        if self.config.dashboard.show_event_queue && self.ui.shows_sidebar() {
+            panels.push(SidebarPanel::Events);
+        }
+        if self.ui.show_command_tree {
+            panels.push(SidebarPanel::ChainOfCommand);
         }
+        panels
     }
 
-    /// Move sidebar focus to the next panel, skipping Events when hidden.
+    /// Move sidebar focus to the next panel, skipping hidden ones.
     pub fn sidebar_next(&mut self) {
-        self.sidebar_panel = match self.sidebar_panel {
-            SidebarPanel::Projects => {
-                if self.config.dashboard.show_event_queue {
-                    SidebarPanel::Events
-                } else {
-                    SidebarPanel::ChainOfCommand
-                }
-            }
-            SidebarPanel::Events => SidebarPanel::ChainOfCommand,
-            SidebarPanel::ChainOfCommand => SidebarPanel::Projects,
-        };
+        self.step_sidebar_panel(1);
     }
 
-    /// Move sidebar focus to the previous panel, skipping Events when hidden.
+    /// Move sidebar focus to the previous panel, skipping hidden ones.
     pub fn sidebar_previous(&mut self) {
-        self.sidebar_panel = match self.sidebar_panel {
-            SidebarPanel::Projects => SidebarPanel::ChainOfCommand,
-            SidebarPanel::Events => SidebarPanel::Projects,
-            SidebarPanel::ChainOfCommand => {
-                if self.config.dashboard.show_event_queue {
-                    SidebarPanel::Events
-                } else {
-                    SidebarPanel::Projects
-                }
-            }
+        self.step_sidebar_panel(-1);
+    }
+
+    fn step_sidebar_panel(&mut self, step: isize) {
+        let panels = self.sidebar_panels();
+        if panels.is_empty() {
+            return;
+        }
+        let n = panels.len() as isize;
+        let current = panels
+            .iter()
+            .position(|panel| *panel == self.sidebar_panel)
+            .map_or(0, |i| (i as isize + step).rem_euclid(n));
+        self.sidebar_panel = panels[current as usize];
+    }
+
+    /// Focus the sidebar, if it is shown.
+    pub fn focus_sidebar(&mut self) {
+        self.sidebar_focused = self.ui.shows_sidebar();
+    }
+
+    /// Switch to the next layout preset, showing its panels.
+    pub fn cycle_layout_preset(&mut self) {
+        self.ui = UiState::for_preset(self.ui.preset.next());
+        self.layout_changed();
+        self.set_status_trf("Layout: {}", &[&self.ui.preset.as_str()]);
+    }
+
+    pub fn toggle_panel(&mut self, panel: LayoutPanel) {
+        let shown = self.ui.toggle(panel);
+        self.layout_changed();
+        let lang = self.config.dashboard.language;
+        let name = match panel {
+            LayoutPanel::Projects => tr(lang, "Projects panel"),
+            LayoutPanel::EaPanel => tr(lang, "EA panel"),
+            LayoutPanel::CommandTree => tr(lang, "Chain of command"),
         };
+        if shown {
+            self.set_status_trf("{} shown", &[&name]);
+        } else {
+            self.set_status_trf("{} hidden", &[&name]);
+        }
+    }
+
+    /// Keep focus on a visible panel and remember the layout.
+    fn layout_changed(&mut self) {
+        let panels = self.sidebar_panels();
+        if !panels.contains(&self.sidebar_panel) {
+            if let Some(first) = panels.first() {
+                self.sidebar_panel = *first;
+            }
+        }
+        if panels.is_empty() {
+            self.sidebar_focused = false;
+        }
+        if let Err(e) = ui_state::save(&self.omar_dir, &self.ui) {
+            self.ticker.push(format!("Could not save layout: {}", e));
+        }
     }
 
     /// Grid column count (matches render_agent_grid logic).
@@ -847,13 +3266,8 @@ impl App {
 
     /// Get the short name (receiver name) of the selected agent.
     pub fn selected_agent_short_name(&self) -> Option<String> {
-        self.selected_agent().map(|a| {
-            a.session
-                .name
-                .strip_prefix(self.client.prefix())
-                .unwrap_or(&a.session.name)
-                .to_string()
-        })
+        self.selected_agent()
+            .map(|a| naming::short_name(self.client.prefix(), &a.session.name).to_string())
     }
 
     /// Receiver-side name for the selected agent, suitable for matching the
@@ -874,6 +3288,330 @@ impl App {
         ))
     }
 
+    /// Open the message popup targeting the selected agent.
+    pub fn open_message_input(&mut self) {
+        match self.selected_agent().map(|a| a.session.name.clone()) {
+            Some(session) => {
+                self.message_target = Some(session);
+                self.message_input.clear();
+            }
+            None => self.set_status_tr("No agent selected"),
+        }
+    }
+
+    /// Trial-run the sandbox and limits wrappers in the background unless
+    /// these settings already passed. Failures are re-checked every start.
+    pub fn start_sandbox_preflight(&mut self) {
+        let (sandbox, limits) = (self.config.sandbox.clone(), self.config.limits.clone());
+        if crate::sandbox::shares_host_credentials(&sandbox) {
+            self.ticker.push(
+                "sandbox: workers can read host credentials (~/.claude, ~/.config/opencode, ...); \
+                 set sandbox.credentials = \"off\" to hide them"
+                    .to_string(),
+            );
+        }
+        if !preflight::needs_preflight(&sandbox, &limits) {
+            return;
+        }
+        if preflight::cached(&self.omar_dir, &sandbox, &limits).is_some_and(|p| p.error.is_none()) {
+            return;
+        }
+        let (tx, rx) = mpsc::channel();
+        let omar_dir = self.omar_dir.clone();
+        std::thread::spawn(move || {
+            let _ = tx.send(preflight::run_and_record(&omar_dir, &sandbox, &limits));
+        });
+        self.sandbox_preflight = Some(rx);
+        self.set_persistent_warning_if_clear_or_same(SANDBOX_CHECKING);
+    }
+
+    /// Pick up a finished sandbox preflight.
+    pub fn poll_sandbox_preflight(&mut self) {
+        let Some(rx) = &self.sandbox_preflight else {
+            return;
+        };
+        let Ok(result) = rx.try_recv() else {
+            return;
+        };
+        self.sandbox_preflight = None;
+        self.clear_persistent_warning_if(SANDBOX_CHECKING);
+        match result.error {
+            Some(error) => {
+                self.set_persistent_warning(format!("⚠ Sandbox check failed: {}", error));
+                self.sandbox_preflight_error = Some(error);
+            }
+            None => self.set_status_tr("Sandbox check passed"),
+        }
+    }
+
+    /// Open the exec popup for the selected worker.
+    pub fn open_exec_popup(&mut self) {
+        match self.selected_agent().map(|a| a.session.name.clone()) {
+            Some(target) => {
+                self.exec_popup = Some(ExecPopup {
+                    target,
+                    ..Default::default()
+                });
+            }
+            None => self.set_status_tr("No agent selected"),
+        }
+    }
+
+    /// Run the exec popup's command in the worker's sandbox and keep the
+    /// result on screen. An empty command opens a sandbox shell instead.
+    pub fn run_exec_popup(&mut self) -> Result<()> {
+        let state_dir = self.state_dir();
+        let Some(popup) = self.exec_popup.as_mut() else {
+            return Ok(());
+        };
+        let launch = exec::launch_for(&state_dir, &popup.target)?;
+        let command = std::mem::take(&mut popup.input);
+        if command.trim().is_empty() {
+            let target = popup.target.clone();
+            self.exec_popup = None;
+            exec::open_shell_popup(&self.config.sandbox, &launch)?;
+            self.set_status_trf("Opened a shell in {}'s sandbox", &[&target]);
+            return Ok(());
+        }
+        let output = exec::run(
+            &self.config.sandbox,
+            &launch,
+            &command,
+            exec::DASHBOARD_TIMEOUT,
+        )?;
+        popup.last = Some((command, output));
+        Ok(())
+    }
+
+    /// Open the note editor on the selected agent, starting from its note.
+    pub fn open_note_input(&mut self) {
+        match self.selected_agent().map(|a| a.session.name.clone()) {
+            Some(session) => {
+                self.note_input = self.agent_note(&session).unwrap_or_default().to_string();
+                self.note_target = Some(session);
+            }
+            None => self.set_status_tr("No agent selected"),
+        }
+    }
+
+    pub fn cancel_note_input(&mut self) {
+        self.note_target = None;
+        self.note_input.clear();
+    }
+
+    /// Save the note editor's text (empty removes the note) and close it.
+    pub fn save_note_input(&mut self) {
+        let Some(target) = self.note_target.take() else {
+            return;
+        };
+        let note = std::mem::take(&mut self.note_input);
+        let state_dir = self.state_dir();
+        memory::save_agent_note_in(&state_dir, &target, &note);
+        self.agent_notes = memory::load_agent_notes_from(&state_dir);
+        let name = self.short_session_name(&target);
+        if note.trim().is_empty() {
+            self.set_status_trf("Removed note on {}", &[&name]);
+        } else {
+            self.set_status_trf("Saved note on {}", &[&name]);
+        }
+    }
+
+    /// Close the message popup without sending.
+    pub fn cancel_message_input(&mut self) {
+        self.message_target = None;
+        self.message_input.clear();
+    }
+
+    /// Send the message popup's text to its target as one literal paste
+    /// followed by Enter, then close the popup.
+    pub fn send_message_input(&mut self) -> Result<()> {
+        let Some(target) = self.message_target.take() else {
+            return Ok(());
+        };
+        let text = std::mem::take(&mut self.message_input);
+        if text.trim().is_empty() {
+            return Ok(());
+        }
+        let client = self.client_for_session(&target);
+        client.send_keys_literal(&target, &text)?;
+        client.send_keys(&target, "Enter")?;
+        self.set_status_trf("Sent message to {}", &[&self.short_session_name(&target)]);
+        Ok(())
+    }
+
+    /// Handle a bracketed paste. Open text inputs take the text whole (the
+    /// single-line ones with newlines folded to spaces); with no popup open
+    /// it goes to the zoomed or selected agent as one bracketed paste with
+    /// its newlines kept, so a pasted newline cannot submit half of it.
+    pub fn handle_paste(&mut self, text: &str) -> Result<()> {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        let line = text.replace('\n', " ");
+        if self.message_target.is_some() {
+            self.message_input.push_str(&text);
+        } else if self.note_target.is_some() {
+            self.note_input.push_str(&text);
+        } else if self.project_input_mode {
+            self.project_input.push_str(&line);
+        } else if self.ea_input_mode {
+            self.ea_input.push_str(&line);
+        } else if let Some(form) = self.spawn_form.as_mut() {
+            form.paste(&line);
+        } else if let Some(query) = self.search_input.as_mut() {
+            query.push_str(&line);
+        } else if let Some(filter) = self.filter_input.as_mut() {
+            filter.push_str(&line);
+        } else if let Some(popup) = self.exec_popup.as_mut() {
+            popup.input.push_str(&line);
+        } else if self.attention_selected.is_some() {
+            self.attention_input.push_str(&line);
+        } else if let Some(buffer) = self.settings_edit_buffer.as_mut() {
+            buffer.push_str(&line);
+        } else if !self.has_popup() {
+            let target = match &self.zoomed {
+                Some(session) => session.clone(),
+                None => match self.selected_agent() {
+                    Some(agent) => agent.session.name.clone(),
+                    None => {
+                        self.set_status_tr("No agent selected");
+                        return Ok(());
+                    }
+                },
+            };
+            if self.is_demo() {
+                self.set_status_tr("Paste is disabled in demo mode");
+                return Ok(());
+            }
+            self.client_for_session(&target)
+                .paste_text(&target, &text)?;
+            self.set_status_trf(
+                "Pasted {} chars into {}",
+                &[&text.chars().count(), &self.short_session_name(&target)],
+            );
+        }
+        Ok(())
+    }
+
+    /// Agents waiting on a human, most urgent first, with what they asked:
+    /// blocked ones, and idle ones whose last line is a question.
+    pub fn attention_agents(&self) -> Vec<(&AgentInfo, String)> {
+        let mut waiting: Vec<(&AgentInfo, String)> = self
+            .manager
+            .iter()
+            .chain(self.agents.iter().filter(|agent| self.passes_filter(agent)))
+            .filter_map(|agent| {
+                let name = &agent.session.name;
+                match (agent.health, self.latest.questions.get(name)) {
+                    (HealthState::Blocked | HealthState::Idle, Some(question)) => {
+                        Some((agent, question.clone()))
+                    }
+                    (HealthState::Blocked, None) => {
+                        let detail = self.latest.results.get(name).map(|r| r.detail.clone());
+                        Some((agent, detail.unwrap_or_default()))
+                    }
+                    _ => None,
+                }
+            })
+            .collect();
+        waiting.sort_by_key(|(agent, _)| std::cmp::Reverse(agent.health.attention_rank()));
+        waiting
+    }
+
+    pub fn open_attention_panel(&mut self) {
+        self.attention_selected = Some(0);
+        self.attention_input.clear();
+    }
+
+    /// Send the quick reply to the agent selected in the "Needs attention"
+    /// panel. The panel stays open for the next one.
+    pub fn send_attention_reply(&mut self) -> Result<()> {
+        let text = self.attention_input.trim().to_string();
+        let target = self.attention_selected.and_then(|selected| {
+            self.attention_agents()
+                .get(selected)
+                .map(|(agent, _)| agent.session.name.clone())
+        });
+        let (Some(target), false) = (target, text.is_empty()) else {
+            return Ok(());
+        };
+        let client = self.client_for_session(&target);
+        client.send_keys_literal(&target, &text)?;
+        client.send_keys(&target, "Enter")?;
+        self.attention_input.clear();
+        self.set_status_trf("Replied to {}", &[&self.short_session_name(&target)]);
+        Ok(())
+    }
+
+    /// Search the scrollback of the EA and every agent in the active EA and
+    /// open the results view.
+    pub fn run_search(&mut self, query: &str) -> Result<()> {
+        let pattern = search::compile_query(query)?;
+        let mut sessions: Vec<String> = self
+            .manager
+            .iter()
+            .map(|m| m.session.name.clone())
+            .collect();
+        sessions.extend(self.agents.iter().map(|a| a.session.name.clone()));
+        let matches = search::search_sessions(&self.client, &sessions, &pattern, 1, 500);
+        self.search_results = Some((query.trim().to_string(), matches));
+        self.search_scroll = 0;
+        Ok(())
+    }
+
+    /// Open or close the firehose view. Opening seeds it with recent output.
+    pub fn toggle_firehose(&mut self) {
+        if self.firehose.take().is_none() {
+            self.firehose = Some(Firehose::default());
+            self.firehose_scroll = 0;
+            self.poll_firehose();
+        }
+    }
+
+    /// Expand the selected agent's card to the whole screen, or restore
+    /// the grid.
+    pub fn toggle_zoom(&mut self) {
+        if self.zoomed_agent().is_some() {
+            self.zoomed = None;
+            return;
+        }
+        match self.selected_agent() {
+            Some(agent) => {
+                self.zoomed = Some(agent.session.name.clone());
+                self.zoom_scroll = 0;
+            }
+            None => self.set_status_tr("No agent selected to zoom"),
+        }
+    }
+
+    /// The zoomed agent, while it is still running.
+    pub fn zoomed_agent(&self) -> Option<&AgentInfo> {
+        let session = self.zoomed.as_deref()?;
+        self.manager
+            .iter()
+            .chain(self.agents.iter())
+            .find(|agent| agent.session.name == session)
+    }
+
+    fn poll_firehose(&mut self) {
+        let sessions: Vec<String> = self
+            .manager
+            .iter()
+            .chain(self.agents.iter())
+            .map(|a| a.session.name.clone())
+            .collect();
+        if let Some(firehose) = self.firehose.as_mut() {
+            firehose.poll(&self.client, &sessions);
+        }
+    }
+
+    /// Display name for a session: "ea" for the manager, short name otherwise.
+    pub fn display_session_name<'a>(&self, session_name: &'a str) -> &'a str {
+        if session_name == self.manager_session_name() {
+            "ea"
+        } else {
+            self.short_session_name(session_name)
+        }
+    }
+
     /// Attach to the selected agent via popup
     pub fn attach_selected(&self) -> Result<()> {
         if let Some(agent) = self.selected_agent() {
@@ -889,38 +3627,26 @@ impl App {
         if let Some(agent) = self.selected_agent() {
             // Safety: don't kill attached sessions (user's terminal)
             if self
-                .client
+                .client_for_session(&agent.session.name)
                 .ensure_session_not_attached(&agent.session.name)
                 .is_err()
             {
-                self.set_status("Cannot kill attached session");
+                self.set_status_tr("Cannot kill attached session");
                 self.pending_confirm = None;
                 return Ok(());
             }
 
             // Safety: don't kill manager from 'd' key (use separate mechanism)
             if agent.session.name == manager_session {
-                self.status_message = Some("Cannot kill manager with 'd'".to_string());
+                self.set_status_tr("Cannot kill manager with 'd'");
                 self.pending_confirm = None;
                 return Ok(());
             }
 
             let name = agent.session.name.clone();
             let state_dir = self.state_dir();
-
-            let short_name = name
-                .strip_prefix(self.client.prefix())
-                .unwrap_or(&name)
-                .to_string();
-            // Cancel any scheduled events targeting this agent (the outer
-            // main-loop handler also cancels; this keeps kill_selected
-            // self-contained for any other caller).
-            self.scheduler
-                .cancel_by_receiver_and_ea(&short_name, self.active_ea);
-
-            self.client.kill_session(&name)?;
-            memory::remove_agent_parent_in(&state_dir, &name);
-            self.status_message = Some(format!("Killed agent: {}", name));
+            self.kill_agent_session(&name)?;
+            self.set_status_trf("Killed agent: {}", &[&name]);
             self.refresh()?;
             let events = self.scheduler.list_by_ea(self.active_ea);
             memory::write_memory_to(
@@ -936,6 +3662,81 @@ impl App {
         Ok(())
     }
 
+    /// The selected agent and its live descendants, deepest first. Empty
+    /// for the manager.
+    pub fn selected_group(&self) -> Vec<String> {
+        let Some(agent) = self.selected_agent() else {
+            return Vec::new();
+        };
+        if agent.session.name == self.manager_session_name() {
+            return Vec::new();
+        }
+        memory::team_of(&self.agent_parents, &agent.session.name)
+            .into_iter()
+            .filter(|session| self.agents.iter().any(|a| &a.session.name == session))
+            .collect()
+    }
+
+    /// Kill the selected agent and its whole subtree, or nothing if any
+    /// member is attached.
+    pub fn kill_selected_group(&mut self) -> Result<()> {
+        self.pending_confirm = None;
+        let group = self.selected_group();
+        if group.is_empty() {
+            self.set_status_tr("Cannot kill the manager's group");
+            return Ok(());
+        }
+        if let Some(attached) = group.iter().find(|session| {
+            self.client_for_session(session)
+                .ensure_session_not_attached(session)
+                .is_err()
+        }) {
+            self.set_status_trf(
+                "Cannot kill group: {} is attached",
+                &[&self.short_session_name(attached)],
+            );
+            return Ok(());
+        }
+        let state_dir = self.state_dir();
+        let team = memory::team_of(&self.agent_parents, &group[group.len() - 1]);
+        for session in &group {
+            let short_name = self.short_session_name(session).to_string();
+            self.scheduler
+                .cancel_by_receiver_and_ea(&short_name, self.active_ea);
+            self.client_for_session(session).kill_session(session)?;
+        }
+        memory::forget_agents_in(&state_dir, &team);
+        self.set_status_trf("Killed {} agent(s)", &[&group.len()]);
+        self.refresh()?;
+        let manager_session = self.manager_session_name();
+        let events = self.scheduler.list_by_ea(self.active_ea);
+        memory::write_memory_to(
+            &state_dir,
+            &self.agents,
+            self.manager.as_ref(),
+            &manager_session,
+            &self.client,
+            &events,
+        );
+        Ok(())
+    }
+
+    /// Kill a worker session and drop its hierarchy/result metadata and any
+    /// scheduled events targeting it. Refuses the manager.
+    pub fn kill_agent_session(&mut self, session: &str) -> Result<()> {
+        if session == self.manager_session_name() {
+            anyhow::bail!("Cannot kill the manager session");
+        }
+        let state_dir = self.state_dir();
+        let short_name = self.short_session_name(session).to_string();
+        self.scheduler
+            .cancel_by_receiver_and_ea(&short_name, self.active_ea);
+        self.client_for_session(session).kill_session(session)?;
+        memory::remove_agent_parent_in(&state_dir, session);
+        memory::remove_agent_result_in(&state_dir, session);
+        Ok(())
+    }
+
     /// Generate a unique agent name (within the active EA's namespace).
     pub fn generate_agent_name(&self) -> String {
         let mut existing: std::collections::HashSet<String> =
@@ -948,35 +3749,228 @@ impl App {
         next_agent_name(&self.active_session_prefix(), &existing_refs)
     }
 
-    /// Spawn a new agent with default settings
-    pub fn spawn_agent(&mut self) -> Result<()> {
-        // Refresh first to get current state
+    /// Browse the selected agent's working directory.
+    pub fn open_file_browser(&mut self) {
+        let Some(agent) = self.selected_agent() else {
+            return;
+        };
+        if let Some(host) = agent.session.host.clone() {
+            self.set_status_trf("File browser is not available for agents on {}", &[&host]);
+            return;
+        }
+        let session = agent.session.name.clone();
+        match self.client.get_pane_path(&session) {
+            Ok(path) if !path.is_empty() => {
+                let name = self.short_session_name(&session).to_string();
+                self.file_browser = Some(FileBrowser::new(name, PathBuf::from(path)));
+            }
+            _ => self.set_status_tr("Could not determine the agent's working directory"),
+        }
+    }
+
+    /// Open the spawn wizard prefilled with the configured defaults and the
+    /// focused parent.
+    pub fn open_spawn_form(&mut self) {
+        let manager_session = self.manager_session_name();
+        let mut parents = vec!["ea".to_string()];
+        parents.extend(
+            self.agents
+                .iter()
+                .map(|a| self.short_session_name(&a.session.name).to_string()),
+        );
+        let parent = if self.focus_parent == manager_session {
+            "ea".to_string()
+        } else {
+            self.short_session_name(&self.focus_parent).to_string()
+        };
+        self.spawn_form = Some(
+            SpawnForm::new(
+                &self.config.agent.default_command,
+                &self.resolved_default_workdir(),
+                parents,
+                &parent,
+                self.config.sandbox.runtime != "none",
+            )
+            .with_history(crate::metrics::load_history())
+            .with_profiles(self.config.profiles.clone())
+            .sharing_credentials(crate::sandbox::shares_host_credentials(
+                &self.config.sandbox,
+            )),
+        );
+    }
+
+    /// Spawn from the wizard. Validation errors keep the form open.
+    pub fn submit_spawn_form(&mut self) -> Result<()> {
+        let Some(form) = self.spawn_form.as_ref() else {
+            return Ok(());
+        };
+        let request = match form.request() {
+            Ok(request) => request,
+            Err(e) => {
+                self.set_status_trf("Error: {}", &[&e]);
+                return Ok(());
+            }
+        };
+        self.spawn_form = None;
+        self.refresh()?;
+        let name = self.spawn_from_request(&request)?;
+        let short_name = self.short_session_name(&name).to_string();
+        self.set_status_trf("Spawned agent: {}", &[&short_name]);
         self.refresh()?;
 
-        let workdir = if self.config.agent.default_workdir == "." {
-            std::env::current_dir()
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_else(|_| ".".to_string())
+        if let Some(pos) = focus_view_index(&self.agents, &self.focus_child_indices, &name) {
+            self.selected = pos;
+            self.manager_selected = false;
+        }
+
+        let state_dir = self.state_dir();
+        let manager_session = self.manager_session_name();
+        let events = self.scheduler.list_by_ea(self.active_ea);
+        memory::write_memory_to(
+            &state_dir,
+            &self.agents,
+            self.manager.as_ref(),
+            &manager_session,
+            &self.client,
+            &events,
+        );
+
+        Ok(())
+    }
+
+    /// Spawn `name` with the default command under the EA manager. A task is
+    /// delivered from a background thread once the backend is ready.
+    pub fn spawn_named_agent(&mut self, name: &str, task: Option<&str>) -> Result<String> {
+        let request = SpawnRequest {
+            name: Some(name.to_string()),
+            command: self.config.agent.default_command.clone(),
+            workdir: self.resolved_default_workdir(),
+            task: task.map(str::to_string),
+            parent: "ea".to_string(),
+            sandbox: true,
+            profile: None,
+        };
+        self.spawn_from_request(&request)
+    }
+
+    /// Create the session for `request` and record its parent and task.
+    /// Does not refresh. Returns the full session name.
+    pub fn spawn_from_request(&mut self, request: &SpawnRequest) -> Result<String> {
+        if drain::load(&self.omar_dir).is_some() {
+            anyhow::bail!("OMAR is draining; no new agents are started");
+        }
+        if let (true, Some(error)) = (request.sandbox, &self.sandbox_preflight_error) {
+            anyhow::bail!(
+                "Sandbox check failed: {}. Fix [sandbox] or spawn unsandboxed",
+                error
+            );
+        }
+        let profile = match request.profile.as_deref() {
+            Some(name) => Some(
+                self.config
+                    .profile(name)
+                    .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", name))?
+                    .clone(),
+            ),
+            None => None,
+        };
+        let sandbox = match &profile {
+            Some(profile) => profile.sandbox_config(&self.config.sandbox),
+            None => self.config.sandbox.clone(),
+        };
+        let env = profile.map(|p| p.env_pairs()).unwrap_or_default();
+        let command = if request.sandbox {
+            crate::sandbox::wrap_with_secrets(
+                &sandbox,
+                &self.config.limits,
+                &request.command,
+                &request.workdir,
+                &env,
+            )?
         } else {
-            self.config.agent.default_workdir.clone()
+            crate::secrets::inject(
+                &env,
+                &crate::sandbox::apply_limits(&self.config.limits, &request.command)?,
+            )?
         };
+        let pod = (request.sandbox && sandbox.runtime.trim() == "kubernetes")
+            .then(|| crate::sandbox::KubernetesProvider::pod_in(&command))
+            .flatten();
+        let parent_session = naming::resolve_address(
+            &request.parent,
+            &self.manager_session_name(),
+            &self.active_session_prefix(),
+        );
+
+        let session = match request.name.as_deref() {
+            Some(name) => {
+                let session = naming::session_name(&self.active_session_prefix(), name);
+                if self.client.has_session(&session).unwrap_or(false) {
+                    crate::secrets::discard(&command);
+                    anyhow::bail!("Agent '{}' already exists", name);
+                }
+                self.client
+                    .new_session(&session, &command, Some(&request.workdir))
+                    .map(|()| session)
+            }
+            None => self.new_session_with_generated_name(&command, &request.workdir),
+        }
+        .inspect_err(|_| crate::secrets::discard(&command))?;
+        transcript::start(&self.client, &session);
+
+        let state_dir = self.state_dir();
+        if let Some(file) = crate::secrets::env_file(&command) {
+            memory::save_agent_env_file_in(&state_dir, &session, file);
+        }
+        let mut update = memory::AgentUpdate::new(&session)
+            .parent(&parent_session)
+            .clear_completed()
+            .launch(&memory::AgentLaunch {
+                command: request.command.clone(),
+                workdir: request.workdir.clone(),
+                sandboxed: request.sandbox,
+                security: if request.sandbox {
+                    crate::sandbox::tamper::expected_security(&sandbox)?
+                } else {
+                    Vec::new()
+                },
+                pod,
+            })
+            .task(request.task.as_deref().unwrap_or("dashboard-manual spawn"));
+        if request.task.is_some() && self.config.agent.checkpoint_workdir {
+            match checkpoint::create(Path::new(&request.workdir), &session) {
+                Ok(saved) => update = update.checkpoint(&saved),
+                Err(e) => self.ticker.push(format!("checkpoint skipped: {}", e)),
+            }
+        }
+        update.save_in(&state_dir)?;
+        if let Some(task) = request.task.clone() {
+            let client = self.client.clone();
+            let ticker = self.ticker.clone();
+            let session = session.clone();
+            let readiness = self.config.agent.readiness.clone();
+            std::thread::spawn(move || {
+                if let Err(e) =
+                    manager::deliver_task_when_ready(&client, &session, &command, &task, &readiness)
+                {
+                    ticker.push(e.to_string());
+                }
+            });
+        }
+        Ok(session)
+    }
 
-        let mut name = None;
+    /// Allocate an auto-generated name, retrying when another spawner wins
+    /// the race for it.
+    fn new_session_with_generated_name(&mut self, command: &str, workdir: &str) -> Result<String> {
         for _ in 0..5 {
             let candidate = self.generate_agent_name();
             if self.client.has_session(&candidate).unwrap_or(false) {
                 self.refresh()?;
                 continue;
             }
-            match self.client.new_session(
-                &candidate,
-                &self.config.agent.default_command,
-                Some(&workdir),
-            ) {
-                Ok(()) => {
-                    name = Some(candidate);
-                    break;
-                }
+            match self.client.new_session(&candidate, command, Some(workdir)) {
+                Ok(()) => return Ok(candidate),
                 Err(err) if err.to_string().contains("duplicate session") => {
                     self.refresh()?;
                     continue;
@@ -984,36 +3978,17 @@ impl App {
                 Err(err) => return Err(err),
             }
         }
-        let name = name.ok_or_else(|| anyhow::anyhow!("Unable to allocate a unique agent name"))?;
-
-        let state_dir = self.state_dir();
-        memory::save_agent_parent_in(&state_dir, &name, &self.focus_parent);
-
-        let short_name = self.short_session_name(&name).to_string();
-
-        let state_dir = self.state_dir();
-        memory::save_worker_task_in(&state_dir, &name, "dashboard-manual spawn");
-
-        self.set_status(format!("Spawned agent: {}", short_name));
-        self.refresh()?;
+        anyhow::bail!("Unable to allocate a unique agent name")
+    }
 
-        if let Some(pos) = focus_view_index(&self.agents, &self.focus_child_indices, &name) {
-            self.selected = pos;
-            self.manager_selected = false;
+    fn resolved_default_workdir(&self) -> String {
+        if self.config.agent.default_workdir == "." {
+            std::env::current_dir()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_else(|_| ".".to_string())
+        } else {
+            self.config.agent.default_workdir.clone()
         }
-
-        let manager_session = self.manager_session_name();
-        let events = self.scheduler.list_by_ea(self.active_ea);
-        memory::write_memory_to(
-            &state_dir,
-            &self.agents,
-            self.manager.as_ref(),
-            &manager_session,
-            &self.client,
-            &events,
-        );
-
-        Ok(())
     }
 
     /// Set status message (persists for 3 seconds before auto-clearing)
@@ -1022,6 +3997,16 @@ impl App {
         self.status_set_at = Some(Instant::now());
     }
 
+    /// Set a status message from a catalog key, in the dashboard language
+    pub fn set_status_tr(&mut self, key: &'static str) {
+        self.set_status(tr(self.config.dashboard.language, key));
+    }
+
+    /// Set a status message from a catalog template filled with `args`
+    pub fn set_status_trf(&mut self, key: &'static str, args: &[&dyn std::fmt::Display]) {
+        self.set_status(trf(self.config.dashboard.language, key, args));
+    }
+
     /// Set a persistent warning that survives clear_status() calls
     pub fn set_persistent_warning(&mut self, msg: impl Into<String>) {
         let msg = msg.into();
@@ -1062,27 +4047,20 @@ impl App {
         }
     }
 
-    /// Get counts by health state: (running, idle)
+    /// Get counts by health state.
     /// Includes manager in the count
-    pub fn health_counts(&self) -> (usize, usize) {
-        let mut running = 0;
-        let mut idle = 0;
-
-        for agent in &self.agents {
+    pub fn health_counts(&self) -> HealthCounts {
+        let mut counts = HealthCounts::default();
+        for agent in self.agents.iter().chain(self.manager.as_ref()) {
             match agent.health {
-                HealthState::Running => running += 1,
-                HealthState::Idle => idle += 1,
-            }
-        }
-
-        if let Some(ref manager) = self.manager {
-            match manager.health {
-                HealthState::Running => running += 1,
-                HealthState::Idle => idle += 1,
+                HealthState::Running => counts.running += 1,
+                HealthState::Idle => counts.idle += 1,
+                HealthState::Finished => counts.finished += 1,
+                HealthState::Blocked => counts.blocked += 1,
+                HealthState::RateLimited => counts.rate_limited += 1,
             }
         }
-
-        (running, idle)
+        counts
     }
 
     /// Get total agent count (including manager)
@@ -1092,12 +4070,29 @@ impl App {
 
     /// Get focus parent pane output (more lines for display)
     pub fn get_focus_parent_output(&self, lines: i32) -> Result<String> {
-        self.client.capture_pane(&self.focus_parent, lines)
+        self.get_agent_output(&self.focus_parent, lines)
     }
 
     /// Get agent pane output by session name
     pub fn get_agent_output(&self, session: &str, lines: i32) -> Result<String> {
-        self.client.capture_pane(session, lines)
+        if let Some(fleet) = &self.demo {
+            return fleet
+                .output(session, lines)
+                .ok_or_else(|| anyhow::anyhow!("No demo session '{}'", session));
+        }
+        let capture = || {
+            self.client_for_session(session)
+                .capture_pane(session, lines)
+        };
+        match self
+            .manager
+            .iter()
+            .chain(&self.agents)
+            .find(|agent| agent.session.name == session)
+        {
+            Some(agent) => self.captures.capture(&agent.session, lines, capture),
+            None => capture(),
+        }
     }
 
     /// Add a project and update memory (EA-scoped)
@@ -1131,11 +4126,10 @@ impl App {
             })
             .collect();
         if !active_sessions.is_empty() {
-            self.set_status(format!(
+            self.set_status_trf(
                 "Project {} still has active agents: {}",
-                id,
-                active_sessions.join(", ")
-            ));
+                &[&id, &active_sessions.join(", ")],
+            );
             return;
         }
         let _ = projects::remove_project_in(&state_dir, id);
@@ -1167,16 +4161,35 @@ impl App {
             .find(|ea| ea.id == ea_id)
             .map(|ea| ea.name.clone())
             .unwrap_or_else(|| format!("EA {}", ea_id));
-        self.set_status(format!("Switched to {}", ea_label));
+        self.set_status_trf("Switched to {}", &[&ea_label]);
         self.ea_input_mode = false;
         self.ea_input.clear();
         self.project_input_mode = false;
+        self.message_target = None;
+        self.message_input.clear();
+        self.note_target = None;
+        self.note_input.clear();
+        self.search_input = None;
+        self.filter_input = None;
+        self.exec_popup = None;
+        self.search_results = None;
+        self.spawn_form = None;
+        self.approval_review = None;
+        self.approvals_surfaced.clear();
+        self.file_browser = None;
+        self.firehose = None;
+        self.zoomed = None;
+        self.script_health = None;
+        self.rate_limited.clear();
+        self.notifier = Notifier::new(&self.config.notifications);
         self.show_help = false;
         self.show_events = false;
         self.show_debug_console = false;
         self.show_settings = false;
         self.settings_edit_buffer = None;
         self.sidebar_popup = None;
+        self.queue_selected = None;
+        self.attention_selected = None;
         self.pending_confirm = None;
         Ok(())
     }
@@ -1190,7 +4203,7 @@ impl App {
         self.active_ea = ea_id;
         let new_prefix = ea::ea_prefix(ea_id, &self.base_prefix);
         self.client = TmuxClient::new(&new_prefix);
-        self.health_checker = HealthChecker::new(self.client.clone(), self.health_threshold);
+        self.remote = remote_tmux_for(&self.config, &new_prefix);
         self.focus_parent = ea::ea_manager_session(ea_id, &self.base_prefix);
         self.focus_stack.clear();
         self.selected = 0;
@@ -1218,7 +4231,7 @@ impl App {
         let next_idx = (current_idx + 1) % self.registered_eas.len();
         let next_ea = self.registered_eas[next_idx].id;
         if let Err(e) = self.switch_ea(next_ea) {
-            self.set_status(format!("Error switching EA: {}", e));
+            self.set_status_trf("Error switching EA: {}", &[&e]);
         }
     }
 
@@ -1243,7 +4256,7 @@ impl App {
         };
         let prev_ea = self.registered_eas[prev_idx].id;
         if let Err(e) = self.switch_ea(prev_ea) {
-            self.set_status(format!("Error switching EA: {}", e));
+            self.set_status_trf("Error switching EA: {}", &[&e]);
         }
     }
 
@@ -1252,11 +4265,11 @@ impl App {
         match ea::register_ea(&self.omar_dir, &name, desc.as_deref()) {
             Ok(ea_id) => {
                 self.registered_eas = ea::load_registry(&self.omar_dir);
-                self.set_status(format!("Created EA {}: {}", ea_id, name));
+                self.set_status_trf("Created EA {}: {}", &[&ea_id, &name]);
                 Ok(ea_id)
             }
             Err(e) => {
-                self.set_status(format!("Error creating EA: {}", e));
+                self.set_status_trf("Error creating EA: {}", &[&e]);
                 Err(e)
             }
         }
@@ -1266,7 +4279,7 @@ impl App {
     /// Blocked if it is the last EA. Switches to the lowest remaining EA when active.
     pub fn delete_ea(&mut self, ea_id: EaId) -> Result<()> {
         if self.registered_eas.len() <= 1 {
-            self.set_status("Cannot delete the only EA");
+            self.set_status_tr("Cannot delete the only EA");
             self.pending_confirm = None;
             return Ok(());
         }
@@ -1296,7 +4309,7 @@ impl App {
                 .iter()
                 .any(|session| session.name == *session_name && session.attached)
             {
-                self.set_status("Cannot delete attached session");
+                self.set_status_tr("Cannot delete attached session");
                 self.pending_confirm = None;
                 return Ok(());
             }
@@ -1337,12 +4350,10 @@ impl App {
             self.switch_ea(next_id)?;
         }
 
-        self.set_status(format!(
+        self.set_status_trf(
             "Deleted EA {} ({} workers, {} events)",
-            ea_id,
-            sessions_to_delete.len(),
-            events_cancelled
-        ));
+            &[&ea_id, &sessions_to_delete.len(), &events_cancelled],
+        );
         self.pending_confirm = None;
         Ok(())
     }
@@ -1375,6 +4386,8 @@ pub fn build_tree(
         is_last_sibling: true,
         ancestor_is_last: vec![],
         is_unresolved: false,
+        team_health: None,
+        role: AgentRole::Ea,
     });
 
     // Build a children map: parent_session -> vec of child agents
@@ -1418,11 +4431,7 @@ pub fn build_tree(
             for (idx, child) in children.iter().enumerate() {
                 let sibling_idx = start_idx + idx;
                 let is_last = sibling_idx == total_siblings - 1;
-                let short = child
-                    .session
-                    .name
-                    .strip_prefix(session_prefix)
-                    .unwrap_or(&child.session.name);
+                let short = naming::short_name(session_prefix, &child.session.name);
 
                 nodes.push(CommandTreeNode {
                     name: short.to_string(),
@@ -1432,6 +4441,8 @@ pub fn build_tree(
                     is_last_sibling: is_last,
                     ancestor_is_last: ancestor_is_last.to_vec(),
                     is_unresolved: child.is_unresolved,
+                    team_health: None,
+                    role: AgentRole::Worker,
                 });
 
                 // Recurse into this child's children
@@ -1473,11 +4484,7 @@ pub fn build_tree(
     // Add orphan agents (no parent or dead parent) under EA
     if !orphans.is_empty() {
         for (orphan_idx, orphan) in orphans.iter().enumerate() {
-            let short = orphan
-                .session
-                .name
-                .strip_prefix(session_prefix)
-                .unwrap_or(&orphan.session.name);
+            let short = naming::short_name(session_prefix, &orphan.session.name);
             let sibling_idx = ea_direct_count + orphan_idx;
             nodes.push(CommandTreeNode {
                 name: short.to_string(),
@@ -1487,6 +4494,8 @@ pub fn build_tree(
                 is_last_sibling: sibling_idx == total_root_children - 1,
                 ancestor_is_last: vec![true],
                 is_unresolved: orphan.is_unresolved,
+                team_health: None,
+                role: AgentRole::Worker,
             });
 
             // Orphans can also have children
@@ -1509,6 +4518,14 @@ pub fn build_tree(
         }
     }
 
+    let health: HashMap<String, HealthState> = agents
+        .iter()
+        .map(|agent| (agent.session.name.clone(), agent.health))
+        .collect();
+    let teams = team_health(&health, agent_parents);
+    for node in &mut nodes {
+        node.team_health = teams.get(&node.session_name).copied();
+    }
     nodes
 }
 
@@ -1523,14 +4540,7 @@ pub(crate) fn popup_receiver_name_for(
     manager_session_name: &str,
     prefix: &str,
 ) -> String {
-    if selected_session_name == manager_session_name {
-        "ea".to_string()
-    } else {
-        selected_session_name
-            .strip_prefix(prefix)
-            .unwrap_or(selected_session_name)
-            .to_string()
-    }
+    naming::address(selected_session_name, manager_session_name, prefix)
 }
 
 /// Position of the agent named `name` within `focus_child_indices`, so the
@@ -1546,13 +4556,59 @@ fn focus_view_index(
         .position(|&i| agents.get(i).is_some_and(|a| a.session.name == name))
 }
 
+/// Nearest ancestor of `session` that is not in `stuck`, or the manager.
+fn escalation_target<V>(
+    session: &str,
+    parents: &HashMap<String, String>,
+    stuck: &HashMap<String, V>,
+    manager_session: &str,
+) -> String {
+    let mut current = session;
+    let mut hops = 0;
+    while let Some(parent) = parents.get(current) {
+        if parent == manager_session || !stuck.contains_key(parent) || hops > parents.len() {
+            return parent.clone();
+        }
+        current = parent;
+        hops += 1;
+    }
+    manager_session.to_string()
+}
+
+/// Record `screen`, the `send` blocks now on `sender`'s screen, and return
+/// those not on it at the last scan. A sender first seen after the baseline
+/// (a new PM) has all its blocks relayed.
+fn new_relays(
+    relayed: &mut HashMap<String, HashSet<(String, String)>>,
+    baseline: bool,
+    sender: &str,
+    screen: Vec<(String, String)>,
+) -> Vec<(String, String)> {
+    let known = relayed.get(sender);
+    let mut seen = HashSet::new();
+    let fresh = screen
+        .into_iter()
+        .filter(|key| seen.insert(key.clone()))
+        .filter(|key| !baseline && known.is_none_or(|known| !known.contains(key)))
+        .collect();
+    relayed.insert(sender.to_string(), seen);
+    fresh
+}
+
+/// Whether an agent passes the dashboard filter: its name contains
+/// `filter`, or one of its tags equals it, ignoring case.
+fn matches_filter(name: &str, tags: &[String], filter: &str) -> bool {
+    name.to_lowercase().contains(&filter.to_lowercase())
+        || tags.iter().any(|tag| tag.eq_ignore_ascii_case(filter))
+}
+
 /// Build the next unique agent name for `prefix`, skipping names already in
 /// `existing`. Must use the EA-scoped prefix: `refresh()` filters
 /// `self.agents` by that prefix, so a name built from the base prefix is
 /// invisible to the dashboard.
 fn next_agent_name(prefix: &str, existing: &std::collections::HashSet<&str>) -> String {
     for i in 1..1000 {
-        let candidate = format!("{}{}", prefix, i);
+        let candidate = naming::session_name(prefix, &i.to_string());
         if !existing.contains(candidate.as_str()) {
             return candidate;
         }
@@ -1589,7 +4645,7 @@ enum ParseSessionOwner {
 }
 
 fn parse_ea_session_owner(session_name: &str, base_prefix: &str) -> Option<ParseSessionOwner> {
-    let rest = session_name.strip_prefix(base_prefix)?;
+    let rest = naming::try_short_name(base_prefix, session_name)?;
 
     // Manager sessions are explicit: "omar-agent-ea-<id>".
     if let Some(raw_id) = rest.strip_prefix("ea-") {
@@ -1622,6 +4678,75 @@ mod tests {
     /// Manager session name used in tests (EA 0 with "omar-agent-" prefix)
     const TEST_MANAGER: &str = "omar-agent-ea-0";
 
+    #[test]
+    fn shutdown_lists_workers_of_every_ea_and_kill_idle_spares_busy_ones() {
+        let eas: Vec<EaInfo> = [0, 2]
+            .into_iter()
+            .map(|id| EaInfo {
+                id,
+                name: format!("ea{}", id),
+                description: None,
+                created_at: 0,
+            })
+            .collect();
+        let sessions: Vec<Session> = [
+            TEST_MANAGER,
+            "omar-agent-0-api",
+            "omar-agent-2-docs",
+            "omar-agent-2-new",
+            "omar-agent-5-stray",
+            "omar-dashboard",
+        ]
+        .into_iter()
+        .map(|name| Session::new(name.to_string(), 0, false, 1))
+        .collect();
+        let health = HashMap::from([
+            ("omar-agent-0-api".to_string(), HealthState::Running),
+            ("omar-agent-2-docs".to_string(), HealthState::Finished),
+        ]);
+
+        let workers = fleet_workers(&sessions, &health, &eas, "omar-agent-");
+        assert_eq!(
+            workers,
+            vec![
+                ("omar-agent-0-api".to_string(), HealthState::Running),
+                ("omar-agent-2-docs".to_string(), HealthState::Finished),
+                ("omar-agent-2-new".to_string(), HealthState::Running),
+            ]
+        );
+        let idle = ShutdownChoice::KillIdle;
+        assert!(!idle.stops(HealthState::Running));
+        assert!(!idle.stops(HealthState::Blocked));
+        assert!(!idle.stops(HealthState::RateLimited));
+        assert!(idle.stops(HealthState::Finished) && idle.stops(HealthState::Idle));
+        assert!(!ShutdownChoice::KeepRunning.stops(HealthState::Idle));
+        assert!(ShutdownChoice::KillAll.stops(HealthState::Running));
+    }
+
+    #[test]
+    fn idle_workers_include_finished_ones_and_spare_supervisors() {
+        let mut agents = vec![
+            make_agent("omar-agent-0-done", HealthState::Finished),
+            make_agent("omar-agent-0-quiet", HealthState::Idle),
+            make_agent("omar-agent-0-pm", HealthState::Idle),
+            make_agent("omar-agent-0-stuck", HealthState::Blocked),
+            make_agent("omar-agent-0-busy", HealthState::Running),
+        ];
+        agents[1].session.activity = 500;
+        let roles = HashMap::from([("omar-agent-0-pm".to_string(), AgentRole::Pm)]);
+        let idle = idle_workers(&agents, &HashMap::new(), &roles, 1000, 600);
+        assert_eq!(idle, vec![("omar-agent-0-done".to_string(), 1000)]);
+
+        let idle = idle_workers(&agents, &HashMap::new(), &roles, 1100, 600);
+        assert_eq!(
+            idle,
+            vec![
+                ("omar-agent-0-done".to_string(), 1100),
+                ("omar-agent-0-quiet".to_string(), 600),
+            ]
+        );
+    }
+
     fn env_lock() -> std::sync::MutexGuard<'static, ()> {
         crate::test_env_lock()
     }
@@ -1675,6 +4800,9 @@ mod tests {
                 activity: 0,
                 attached: false,
                 pane_pid: 0,
+                host: None,
+                stamp: None,
+                created: None,
             },
             health,
             is_unresolved: false,
@@ -1758,6 +4886,67 @@ mod tests {
         );
     }
 
+    #[test]
+    fn group_jumps_visit_each_pm_then_unassigned_then_parent() {
+        let config = test_config_with_prefix(format!("omar-test-{}-", uuid::Uuid::new_v4()));
+        let mut app = App::new(&config, TickerBuffer::new(), Arc::new(Scheduler::new()));
+        app.agents = ["api", "w1", "w2", "ui", "w3"]
+            .iter()
+            .map(|name| make_agent(name, HealthState::Running))
+            .collect();
+        app.agent_parents = HashMap::from([
+            ("api-worker".to_string(), "api".to_string()),
+            ("ui-worker".to_string(), "ui".to_string()),
+        ]);
+        app.focus_child_indices = (0..5).collect();
+        app.manager_selected = true;
+
+        assert_eq!(app.group_starts(), vec![0, 1, 3]);
+        let mut visited = Vec::new();
+        for _ in 0..4 {
+            app.next_group();
+            visited.push((!app.manager_selected).then_some(app.selected));
+        }
+        assert_eq!(visited, vec![Some(0), Some(1), Some(3), None]);
+
+        app.manager_selected = false;
+        app.selected = 2;
+        app.previous_group();
+        assert_eq!((app.manager_selected, app.selected), (false, 1));
+        app.selected = 0;
+        app.previous_group();
+        assert!(app.manager_selected);
+    }
+
+    #[test]
+    fn zoom_follows_the_selected_agent_and_clears_when_it_exits() {
+        let config = test_config_with_prefix(format!("omar-test-{}-", uuid::Uuid::new_v4()));
+        let mut app = App::new(&config, TickerBuffer::new(), Arc::new(Scheduler::new()));
+        app.agents = ["api", "ui"]
+            .iter()
+            .map(|name| make_agent(name, HealthState::Running))
+            .collect();
+        app.focus_child_indices = vec![0, 1];
+        app.manager_selected = false;
+        app.selected = 1;
+        app.zoom_scroll = 5;
+
+        app.toggle_zoom();
+        assert_eq!(
+            app.zoomed_agent().map(|a| a.session.name.as_str()),
+            Some("ui")
+        );
+        assert_eq!(app.zoom_scroll, 0);
+        app.toggle_zoom();
+        assert!(app.zoomed.is_none());
+
+        // Once the zoomed agent is gone, one press zooms the new selection
+        app.zoomed = Some("gone".to_string());
+        app.selected = 0;
+        app.toggle_zoom();
+        assert_eq!(app.zoomed.as_deref(), Some("api"));
+    }
+
     #[test]
     fn manager_startup_attempts_is_single_attempt_for_all_backends() {
         let config = test_config_with_prefix(format!("omar-test-{}-", uuid::Uuid::new_v4()));
@@ -1974,9 +5163,30 @@ mod tests {
             agent: AgentConfig {
                 default_command: "true".to_string(),
                 default_workdir: ".".to_string(),
+                max_concurrent: None,
+                readiness: crate::config::ReadinessConfig::default(),
+                checkpoint_workdir: false,
+                auto_kill_idle_after: None,
+                trace_in_prompt: false,
+                env: std::collections::BTreeMap::new(),
+                auto_resume: false,
             },
             metrics: MetricsConfig::default(),
             slack_bridge: crate::config::SlackBridgeConfig::default(),
+            sandbox: crate::config::SandboxConfig::default(),
+            limits: crate::config::LimitsConfig::default(),
+            api: crate::config::ApiConfig::default(),
+            secrets: crate::config::SecretsConfig::default(),
+            prompts: crate::config::PromptsConfig::default(),
+            github: crate::config::GithubConfig::default(),
+            scripting: crate::config::ScriptingConfig::default(),
+            notifications: crate::config::NotificationsConfig::default(),
+            theme: crate::config::ThemeConfig::default(),
+            orchestration: crate::config::OrchestrationConfig::default(),
+            pipelines: Default::default(),
+            schedules: Vec::new(),
+            hosts: Vec::new(),
+            profiles: Vec::new(),
         }
     }
 
@@ -2121,6 +5331,178 @@ mod tests {
             .status();
     }
 
+    #[test]
+    fn send_message_input_delivers_multiline_text_and_closes_popup() {
+        let _env_lock = env_lock();
+        if !std::process::Command::new("tmux")
+            .arg("-V")
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+        {
+            eprintln!("Skipping test: tmux not available");
+            return;
+        }
+
+        let dir = tempfile::tempdir().expect("temp dir");
+        let _home = HomeEnvGuard::set(dir.path());
+        let tmux_server = format!("omar-app-message-{}", uuid::Uuid::new_v4());
+        let _tmux = TmuxServerEnvGuard::set(&tmux_server);
+        let config = test_config_with_prefix(format!("omar-test-{}-", uuid::Uuid::new_v4()));
+        let session = format!("{}0-reader", config.dashboard.session_prefix);
+        let client = TmuxClient::new(&config.dashboard.session_prefix);
+        if client.new_session(&session, "cat", None).is_err() {
+            eprintln!("Skipping test: failed to create tmux session");
+            return;
+        }
+
+        let mut app = App::new(&config, TickerBuffer::new(), Arc::new(Scheduler::new()));
+        app.message_target = Some(session.clone());
+        app.handle_paste("first line\r\nsecond line").unwrap();
+        assert_eq!(app.message_input, "first line\nsecond line");
+        assert!(app.has_popup());
+        app.send_message_input().expect("send should succeed");
+        assert!(app.message_target.is_none());
+        assert!(app.message_input.is_empty());
+
+        let mut pane = String::new();
+        for _ in 0..30 {
+            pane = client.capture_pane_plain(&session, 200).unwrap_or_default();
+            if pane.contains("first line") && pane.contains("second line") {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        let _ = std::process::Command::new("tmux")
+            .args(["-L", &tmux_server, "kill-server"])
+            .status();
+        assert!(
+            pane.contains("first line") && pane.contains("second line"),
+            "message text should reach the target pane: {pane:?}"
+        );
+    }
+
+    #[test]
+    fn long_multiline_paste_reaches_the_agent_intact() {
+        let _env_lock = env_lock();
+        if !std::process::Command::new("tmux")
+            .arg("-V")
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+        {
+            eprintln!("Skipping test: tmux not available");
+            return;
+        }
+
+        let dir = tempfile::tempdir().expect("temp dir");
+        let _home = HomeEnvGuard::set(dir.path());
+        let tmux_server = format!("omar-app-paste-{}", uuid::Uuid::new_v4());
+        let _tmux = TmuxServerEnvGuard::set(&tmux_server);
+        let config = test_config_with_prefix(format!("omar-test-{}-", uuid::Uuid::new_v4()));
+        let session = format!("{}0-reader", config.dashboard.session_prefix);
+        let received = dir.path().join("received.txt");
+        let client = TmuxClient::new(&config.dashboard.session_prefix);
+        // Without ICRNL, as in a raw-mode TUI, a newline turned into a CR
+        // would never end the line
+        let reader = format!("stty -icrnl -echo; cat > '{}'", received.display());
+        if client.new_session(&session, &reader, None).is_err() {
+            eprintln!("Skipping test: failed to create tmux session");
+            return;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(200));
+
+        let text: String = (0..60)
+            .map(|i| format!("line {i:02} of a pasted stack trace, padded out to length\n"))
+            .collect();
+        assert!(text.len() > TmuxClient::LARGE_PAYLOAD_THRESHOLD);
+        let mut app = App::new(&config, TickerBuffer::new(), Arc::new(Scheduler::new()));
+        app.zoomed = Some(session.clone());
+        app.handle_paste(&text).unwrap();
+
+        let mut got = String::new();
+        for _ in 0..30 {
+            got = std::fs::read_to_string(&received).unwrap_or_default();
+            if got.len() >= text.len() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        let _ = std::process::Command::new("tmux")
+            .args(["-L", &tmux_server, "kill-server"])
+            .status();
+        assert_eq!(got, text);
+    }
+
+    #[test]
+    fn collect_agent_results_persists_marker_and_notifies_parent_once() {
+        let _env_lock = env_lock();
+        if !std::process::Command::new("tmux")
+            .arg("-V")
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+        {
+            eprintln!("Skipping test: tmux not available");
+            return;
+        }
+
+        let dir = tempfile::tempdir().expect("temp dir");
+        let _home = HomeEnvGuard::set(dir.path());
+        let tmux_server = format!("omar-app-result-{}", uuid::Uuid::new_v4());
+        let _tmux = TmuxServerEnvGuard::set(&tmux_server);
+        let config = test_config_with_prefix(format!("omar-test-{}-", uuid::Uuid::new_v4()));
+        let session = format!("{}0-worker", config.dashboard.session_prefix);
+        let client = TmuxClient::new(&config.dashboard.session_prefix);
+        if client
+            .new_session(
+                &session,
+                "printf '[BLOCKED: need token]\\n'; sleep 30",
+                None,
+            )
+            .is_err()
+        {
+            eprintln!("Skipping test: failed to create tmux session");
+            return;
+        }
+
+        let mut app = App::new(&config, TickerBuffer::new(), Arc::new(Scheduler::new()));
+        let mut health = HealthState::Running;
+        for _ in 0..30 {
+            app.poll_snapshot();
+            health = app
+                .latest
+                .health
+                .get(&session)
+                .copied()
+                .unwrap_or(HealthState::Running);
+            if health == HealthState::Blocked {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        let _ = std::process::Command::new("tmux")
+            .args(["-L", &tmux_server, "kill-server"])
+            .status();
+        assert_eq!(health, HealthState::Blocked);
+
+        app.agents = vec![make_agent(&session, health)];
+        let state_dir = app.state_dir();
+        app.collect_agent_results(&state_dir);
+        app.collect_agent_results(&state_dir);
+
+        let results = memory::load_agent_results_from(&state_dir);
+        assert_eq!(results[&session].detail, "need token");
+        let events = app.scheduler.list_by_ea(app.active_ea);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].receiver, "ea");
+        assert_eq!(events[0].sender, "worker");
+        assert_eq!(
+            events[0].payload,
+            "[CHILD RESULT] worker blocked: need token"
+        );
+    }
+
     #[test]
     fn app_startup_resumes_existing_registry_and_preserves_state() {
         let dir = tempfile::tempdir().unwrap();
@@ -2277,6 +5659,7 @@ default_workdir = "."
             &[projects::Project {
                 id: 3,
                 name: "Keep project".to_string(),
+                items: Vec::new(),
             }],
         )
         .unwrap();
@@ -2545,6 +5928,147 @@ default_workdir = "."
         assert_eq!(next_agent_name(ea_prefix, &existing), "omar-agent-0-3");
     }
 
+    #[test]
+    fn stuck_worker_escalates_past_stuck_parents_to_the_ea() {
+        let parents = HashMap::from([
+            ("pm".to_string(), TEST_MANAGER.to_string()),
+            ("api".to_string(), "pm".to_string()),
+            ("orphan".to_string(), "gone".to_string()),
+        ]);
+        let stuck = HashMap::from([("api".to_string(), 600)]);
+        assert_eq!(
+            escalation_target("api", &parents, &stuck, TEST_MANAGER),
+            "pm"
+        );
+        let stuck = HashMap::from([("api".to_string(), 600), ("pm".to_string(), 900)]);
+        assert_eq!(
+            escalation_target("api", &parents, &stuck, TEST_MANAGER),
+            TEST_MANAGER
+        );
+        assert_eq!(
+            escalation_target("solo", &parents, &stuck, TEST_MANAGER),
+            TEST_MANAGER
+        );
+    }
+
+    #[test]
+    fn relays_are_tracked_per_sender() {
+        let block = |target: &str, message: &str| (target.to_string(), message.to_string());
+        let mut relayed = HashMap::new();
+        // The baseline scan relays nothing
+        assert!(new_relays(&mut relayed, true, "ea", vec![block("api", "old")]).is_empty());
+        let screen = vec![
+            block("api", "old"),
+            block("api", "rebase"),
+            block("api", "rebase"),
+        ];
+        assert_eq!(
+            new_relays(&mut relayed, false, "ea", screen.clone()),
+            vec![block("api", "rebase")]
+        );
+        assert!(new_relays(&mut relayed, false, "ea", screen).is_empty());
+        // A PM that appears later has its blocks relayed, without touching
+        // the EA's record
+        assert_eq!(
+            new_relays(&mut relayed, false, "pm", vec![block("ui", "start")]),
+            vec![block("ui", "start")]
+        );
+        assert_eq!(relayed["ea"].len(), 2);
+    }
+
+    #[test]
+    fn filter_matches_name_substring_or_exact_tag() {
+        let tags = vec!["frontend".to_string(), "P1".to_string()];
+        assert!(matches_filter("omar-agent-0-ui", &tags, "UI"));
+        assert!(matches_filter("omar-agent-0-ui", &tags, "Frontend"));
+        assert!(matches_filter("omar-agent-0-ui", &tags, "p1"));
+        assert!(!matches_filter("omar-agent-0-ui", &tags, "front"));
+        assert!(!matches_filter("omar-agent-0-api", &[], "frontend"));
+    }
+
+    #[test]
+    fn track_run_settles_completes_and_reports_killed_workers() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config_with_prefix(format!("omar-test-{}-", uuid::Uuid::new_v4()));
+        let mut app = App::new_with_omar_dir(
+            &config,
+            TickerBuffer::new(),
+            Arc::new(Scheduler::new()),
+            dir.path().to_path_buf(),
+        );
+        let state_dir = app.state_dir();
+        let complete = |detail: &str| AgentResult {
+            kind: ResultKind::Complete,
+            detail: detail.to_string(),
+            detected_at: "1970-01-01T00:20:00Z".to_string(),
+        };
+        // "old" finished before the dashboard first saw it
+        memory::save_agent_result_in(&state_dir, "old", &complete("earlier"));
+        let mut api = make_agent("api", HealthState::Running);
+        api.session.created = Some(1_000);
+        app.agents = vec![
+            make_agent("old", HealthState::Finished),
+            api,
+            make_agent("db", HealthState::Running),
+        ];
+        app.track_run(&state_dir);
+        assert!(app.run.settled.contains("old"));
+        assert_eq!(app.run.workers.len(), 2);
+        assert_eq!(app.run.workers["api"].started_at, 1_000);
+
+        memory::save_agent_result_in(&state_dir, "api", &complete("shipped"));
+        app.track_run(&state_dir);
+        assert_eq!(app.run.workers["api"].outcome, Some(Outcome::Complete));
+        assert_eq!(app.run.workers["api"].ended_at, Some(1_200));
+        assert_eq!(app.run.workers["db"].outcome, None);
+        assert!(runs::list_runs(dir.path()).is_empty());
+
+        // Killed: gone from the fleet without completing
+        app.agents.retain(|agent| agent.session.name != "db");
+        app.track_run(&state_dir);
+        let reports = runs::list_runs(dir.path());
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0]
+            .summary
+            .starts_with("2 workers: 1 complete, 1 killed"));
+        assert!(app.run.workers.is_empty());
+
+        // Reported workers are not reported again
+        app.track_run(&state_dir);
+        assert!(app.run.workers.is_empty());
+    }
+
+    #[test]
+    fn paste_goes_to_the_focused_input() {
+        let config = test_config_with_prefix(format!("omar-test-{}-", uuid::Uuid::new_v4()));
+        let mut app = App::new(&config, TickerBuffer::new(), Arc::new(Scheduler::new()));
+        let mut form = SpawnForm::new("claude", "/tmp", vec!["ea".to_string()], "ea", false);
+        form.field = crate::spawn_form::SpawnField::Task;
+        app.spawn_form = Some(form);
+        app.handle_paste("fix the\nlogin flow").unwrap();
+        assert_eq!(app.spawn_form.take().unwrap().task, "fix the login flow");
+
+        app.settings_edit_buffer = Some(String::new());
+        app.handle_paste("Research").unwrap();
+        assert_eq!(app.settings_edit_buffer.as_deref(), Some("Research"));
+    }
+
+    #[test]
+    fn filter_hides_agents_from_the_view_but_keeps_the_fleet() {
+        let config = test_config_with_prefix(format!("omar-test-{}-", uuid::Uuid::new_v4()));
+        let mut app = App::new(&config, TickerBuffer::new(), Arc::new(Scheduler::new()));
+        app.agents = ["api", "ui", "db"]
+            .iter()
+            .map(|name| make_agent(name, HealthState::Blocked))
+            .collect();
+        app.agent_tags = HashMap::from([("db".to_string(), vec!["ui".to_string()])]);
+        app.filter = "ui".to_string();
+
+        assert_eq!(app.compute_focus_child_indices(), vec![1, 2]);
+        assert_eq!(app.attention_agents().len(), 2);
+        assert_eq!(app.agents.len(), 3);
+    }
+
     #[test]
     fn spawn_bookkeeping_persists_parent_under_focus_and_selects_in_view() {
         use crate::memory;
@@ -2659,13 +6183,11 @@ default_workdir = "."
             if *session == TEST_MANAGER {
                 continue;
             }
-            let short = session.strip_prefix(session_prefix).unwrap_or(session);
+            let short = naming::short_name(session_prefix, session);
             crumbs.push(short.to_string());
         }
         if focus_parent != TEST_MANAGER {
-            let short = focus_parent
-                .strip_prefix(session_prefix)
-                .unwrap_or(&focus_parent);
+            let short = naming::short_name(session_prefix, &focus_parent);
             crumbs.push(short.to_string());
         }
         assert_eq!(crumbs, vec!["EA"]);
@@ -2678,13 +6200,11 @@ default_workdir = "."
             if *session == TEST_MANAGER {
                 continue;
             }
-            let short = session.strip_prefix(session_prefix).unwrap_or(session);
+            let short = naming::short_name(session_prefix, session);
             crumbs.push(short.to_string());
         }
         if focus_parent != TEST_MANAGER {
-            let short = focus_parent
-                .strip_prefix(session_prefix)
-                .unwrap_or(&focus_parent);
+            let short = naming::short_name(session_prefix, &focus_parent);
             crumbs.push(short.to_string());
         }
         assert_eq!(crumbs, vec!["EA", "rest-api"]);
@@ -0,0 +1,238 @@
+//! `omar bench` — timings for the dashboard refresh, health checks, and
+//! MCP requests against a fleet of dummy sessions
+//!
+//! The fleet runs under its own session prefix with state in a throwaway
+//! directory, so a bench never sees or disturbs real agents. Run it before
+//! and after a change to compare the tables.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use serde_json::json;
+
+use crate::app::App;
+use crate::config::Config;
+use crate::demo;
+use crate::ea;
+use crate::manager::McpLaunchContext;
+use crate::mcp::ToolCaller;
+use crate::paths;
+use crate::scheduler::{events_store_path, Scheduler, TickerBuffer};
+use crate::tmux::{tmux_command, HealthChecker, TmuxClient};
+
+/// Workers print a line every second, so health checks see both output
+/// changes and quiet panes.
+const WORKER_COMMAND: &str = "while :; do date; sleep 1; done";
+
+pub struct BenchOptions {
+    pub sessions: usize,
+    pub rounds: usize,
+    pub requests: usize,
+}
+
+/// Timings for one measured operation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchRow {
+    pub name: String,
+    pub samples: Vec<Duration>,
+    pub errors: usize,
+}
+
+impl BenchRow {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            samples: Vec::new(),
+            errors: 0,
+        }
+    }
+
+    /// Sample at quantile `q` (0.0–1.0), nearest-rank.
+    pub fn percentile(&self, q: f64) -> Duration {
+        let mut sorted = self.samples.clone();
+        sorted.sort();
+        let rank = (q * sorted.len() as f64).ceil() as usize;
+        sorted
+            .get(rank.saturating_sub(1))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Operations per second over the summed sample time.
+    pub fn throughput(&self) -> f64 {
+        let total: Duration = self.samples.iter().sum();
+        if total.is_zero() {
+            return 0.0;
+        }
+        self.samples.len() as f64 / total.as_secs_f64()
+    }
+}
+
+/// Kills the fleet and removes its state when the bench ends, even on error.
+struct Fleet {
+    client: TmuxClient,
+    omar_dir: PathBuf,
+}
+
+impl Drop for Fleet {
+    fn drop(&mut self) {
+        for session in self.client.list_sessions().unwrap_or_default() {
+            let _ = self.client.kill_session(&session.name);
+        }
+        let _ = std::fs::remove_dir_all(&self.omar_dir);
+    }
+}
+
+pub fn run(config: Config, options: &BenchOptions) -> Result<Vec<BenchRow>> {
+    let tmux_ok = tmux_command()
+        .arg("-V")
+        .output()
+        .is_ok_and(|o| o.status.success());
+    if !tmux_ok {
+        bail!("omar bench needs tmux");
+    }
+    let run_id = uuid::Uuid::new_v4().simple().to_string();
+    let mut config = demo::demo_config(config);
+    config.dashboard.session_prefix = format!("omar-bench-{}-", &run_id[..8]);
+    let omar_dir = paths::private_temp_dir()?.join(format!("bench-{}", run_id));
+    let registered = ea::ensure_default_ea(&omar_dir)?;
+    let ea_id = ea::resolve_active_ea(&omar_dir, &registered);
+    let prefix = ea::ea_prefix(ea_id, &config.dashboard.session_prefix);
+    let fleet = Fleet {
+        client: TmuxClient::new(config.dashboard.session_prefix.clone()),
+        omar_dir: omar_dir.clone(),
+    };
+
+    // A placeholder manager keeps the dashboard from launching a real one
+    let manager = ea::ea_manager_session(ea_id, &config.dashboard.session_prefix);
+    fleet.client.new_session(&manager, "sh", None)?;
+    let workers: Vec<String> = (1..=options.sessions)
+        .map(|i| format!("{}bench-{}", prefix, i))
+        .collect();
+    for worker in &workers {
+        fleet.client.new_session(worker, WORKER_COMMAND, None)?;
+    }
+    eprintln!(
+        "Spawned {} dummy sessions; measuring {} rounds and {} requests",
+        workers.len(),
+        options.rounds,
+        options.requests
+    );
+    // Let every pane print before the first sample
+    std::thread::sleep(Duration::from_millis(1500));
+
+    let mut refresh = BenchRow::new("refresh");
+    let scheduler = Arc::new(Scheduler::with_store(events_store_path(&omar_dir)));
+    let mut app = App::new_with_omar_dir(&config, TickerBuffer::new(), scheduler, omar_dir.clone());
+    for _ in 0..options.rounds {
+        let start = Instant::now();
+        if app.refresh().is_err() {
+            refresh.errors += 1;
+        }
+        refresh.samples.push(start.elapsed());
+    }
+
+    let mut health = BenchRow::new("health check");
+    let mut checker = HealthChecker::new(
+        Arc::new(TmuxClient::new(prefix.clone())),
+        config.health.idle_warning,
+    );
+    for _ in 0..options.rounds {
+        for worker in &workers {
+            let start = Instant::now();
+            checker.check(worker);
+            health.samples.push(start.elapsed());
+        }
+    }
+
+    let caller = ToolCaller::new(McpLaunchContext {
+        omar_dir,
+        ea_id,
+        session_prefix: config.dashboard.session_prefix.clone(),
+        default_command: WORKER_COMMAND.to_string(),
+        default_workdir: config.agent.default_workdir.clone(),
+        health_idle_warning: config.health.idle_warning,
+        tmux_server: None,
+        sandbox: Default::default(),
+        limits: Default::default(),
+        prompts: Default::default(),
+    });
+    let target = workers
+        .first()
+        .map(|w| crate::naming::short_name(&prefix, w).to_string())
+        .unwrap_or_default();
+    let mut list = BenchRow::new("list_agents");
+    let mut get = BenchRow::new("get_agent");
+    for i in 0..options.requests {
+        let (row, tool, args) = if i % 2 == 0 {
+            (&mut list, "list_agents", json!({}))
+        } else {
+            (&mut get, "get_agent", json!({ "name": target }))
+        };
+        let start = Instant::now();
+        let result = caller.call(tool, args);
+        row.samples.push(start.elapsed());
+        if result.is_err() {
+            row.errors += 1;
+        }
+    }
+
+    drop(fleet);
+    Ok(vec![refresh, health, list, get])
+}
+
+/// Results as an aligned text table.
+pub fn format_table(rows: &[BenchRow]) -> String {
+    let mut out = format!(
+        "{:<14} {:>7} {:>10} {:>10} {:>10} {:>10} {:>7}\n",
+        "Benchmark", "Samples", "p50", "p95", "Max", "Ops/sec", "Errors"
+    );
+    for row in rows {
+        out.push_str(&format!(
+            "{:<14} {:>7} {:>10} {:>10} {:>10} {:>10.1} {:>7}\n",
+            row.name,
+            row.samples.len(),
+            format_duration(row.percentile(0.5)),
+            format_duration(row.percentile(0.95)),
+            format_duration(row.percentile(1.0)),
+            row.throughput(),
+            row.errors,
+        ));
+    }
+    out
+}
+
+fn format_duration(d: Duration) -> String {
+    if d >= Duration::from_secs(1) {
+        format!("{:.2}s", d.as_secs_f64())
+    } else {
+        format!("{:.1}ms", d.as_secs_f64() * 1000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_throughput_and_table() {
+        let mut row = BenchRow::new("refresh");
+        row.samples = [40, 10, 30, 20]
+            .into_iter()
+            .map(Duration::from_millis)
+            .collect();
+        assert_eq!(row.percentile(0.5), Duration::from_millis(20));
+        assert_eq!(row.percentile(0.95), Duration::from_millis(40));
+        assert_eq!(row.percentile(1.0), Duration::from_millis(40));
+        assert!((row.throughput() - 40.0).abs() < 1e-9);
+        assert_eq!(BenchRow::new("empty").throughput(), 0.0);
+
+        let table = format_table(&[row]);
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines[0].starts_with("Benchmark"));
+        assert!(lines[1].starts_with("refresh"));
+        assert!(lines[1].contains("20.0ms") && lines[1].contains("40.0"));
+    }
+}
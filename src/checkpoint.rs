@@ -0,0 +1,274 @@
+//! Workspace checkpoints — an undo for a worker that trashes its tree
+//!
+//! With `agent.checkpoint_workdir` on, the worker's workdir is committed to
+//! `refs/omar/checkpoints/<session>` before its task is delivered: tracked
+//! and untracked files alike (minus ignored ones), through a scratch index
+//! so the user's index, branch, and stash are left alone. Paths outside the
+//! workdir are recorded as they are in HEAD. `rollback_agent` restores the
+//! workdir to that commit, removes files created in it since, and puts the
+//! workdir's index entries back as they were; HEAD stays where the worker
+//! left it, and nothing outside the workdir is touched.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Top level of the repository the workdir belongs to
+    pub repo: String,
+    /// The workdir relative to `repo` ("" for the top level)
+    #[serde(default)]
+    pub workdir: String,
+    pub commit: String,
+    /// Tree of the user's index when the checkpoint was taken (`None` when
+    /// it had unmerged entries)
+    #[serde(default)]
+    pub index: Option<String>,
+    pub created_at: i64,
+}
+
+impl Checkpoint {
+    /// Pathspec limiting git commands to the workdir.
+    fn pathspec(&self) -> &str {
+        match self.workdir.trim_end_matches('/') {
+            "" => ".",
+            dir => dir,
+        }
+    }
+}
+
+/// What a rollback changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Rollback {
+    pub restored: usize,
+    pub removed: usize,
+}
+
+fn git(repo: &Path, args: &[&str], index: Option<&Path>) -> Result<String> {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C").arg(repo).args(args);
+    if let Some(index) = index {
+        cmd.env("GIT_INDEX_FILE", index);
+    }
+    let output = cmd.output().context("Failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_string())
+}
+
+/// Commit the state of `workdir` for `session`.
+pub fn create(workdir: &Path, session: &str) -> Result<Checkpoint> {
+    let repo = PathBuf::from(
+        git(workdir, &["rev-parse", "--show-toplevel"], None)
+            .map_err(|_| anyhow!("{} is not in a git repository", workdir.display()))?,
+    );
+    let prefix = git(workdir, &["rev-parse", "--show-prefix"], None)?;
+    let pathspec = match prefix.trim_end_matches('/') {
+        "" => ".",
+        dir => dir,
+    };
+    let git_dir = git(&repo, &["rev-parse", "--absolute-git-dir"], None)?;
+    let head = git(&repo, &["rev-parse", "--verify", "-q", "HEAD"], None).ok();
+    let scratch =
+        Path::new(&git_dir).join(format!("omar-checkpoint-{}.index", uuid::Uuid::new_v4()));
+    let tree = match head.as_deref() {
+        Some(head) => git(&repo, &["read-tree", head], Some(&scratch)),
+        None => git(&repo, &["read-tree", "--empty"], Some(&scratch)),
+    }
+    .and_then(|_| git(&repo, &["add", "-A", "--", pathspec], Some(&scratch)))
+    .and_then(|_| git(&repo, &["write-tree"], Some(&scratch)));
+    let _ = std::fs::remove_file(&scratch);
+    let tree = tree?;
+    let index = git(&repo, &["write-tree"], None).ok();
+
+    let message = format!("omar checkpoint before {}", session);
+    let mut args = vec!["commit-tree", &tree, "-m", &message];
+    if let Some(head) = head.as_deref() {
+        args.extend(["-p", head]);
+    }
+    let commit = git(
+        &repo,
+        &[
+            &["-c", "user.name=omar", "-c", "user.email=omar@localhost"][..],
+            &args,
+        ]
+        .concat(),
+        None,
+    )?;
+    git(
+        &repo,
+        &[
+            "update-ref",
+            &format!("refs/omar/checkpoints/{}", session),
+            &commit,
+        ],
+        None,
+    )?;
+    Ok(Checkpoint {
+        repo: repo.display().to_string(),
+        workdir: prefix,
+        commit,
+        index,
+        created_at: chrono::Utc::now().timestamp(),
+    })
+}
+
+/// Restore the workdir to `checkpoint`, removing files it did not have.
+pub fn rollback(checkpoint: &Checkpoint) -> Result<Rollback> {
+    let repo = Path::new(&checkpoint.repo);
+    let pathspec = checkpoint.pathspec();
+    let nul_list = |out: String| -> Vec<String> {
+        out.split('\0')
+            .filter(|p| !p.is_empty())
+            .map(String::from)
+            .collect()
+    };
+    let saved = nul_list(git(
+        repo,
+        &[
+            "ls-tree",
+            "-r",
+            "-z",
+            "--name-only",
+            &checkpoint.commit,
+            "--",
+            pathspec,
+        ],
+        None,
+    )?);
+    let current = nul_list(git(
+        repo,
+        &[
+            "ls-files",
+            "-z",
+            "--cached",
+            "--others",
+            "--exclude-standard",
+            "--",
+            pathspec,
+        ],
+        None,
+    )?);
+
+    let keep: HashSet<&String> = saved.iter().collect();
+    let mut removed = 0;
+    for path in current.iter().filter(|path| !keep.contains(path)) {
+        if std::fs::remove_file(repo.join(path)).is_ok() {
+            removed += 1;
+        }
+    }
+    if !saved.is_empty() {
+        git(
+            repo,
+            &["checkout", &checkpoint.commit, "--", pathspec],
+            None,
+        )?;
+    }
+    // `checkout` staged the restored files; put the workdir's index entries
+    // back as they were at the checkpoint
+    let restore_index = match checkpoint.index.as_deref() {
+        Some(tree) => git(repo, &["reset", "-q", tree, "--", pathspec], None),
+        None if git(repo, &["rev-parse", "--verify", "-q", "HEAD"], None).is_ok() => {
+            git(repo, &["reset", "-q", "--", pathspec], None)
+        }
+        None => git(
+            repo,
+            &[
+                "rm",
+                "-r",
+                "-q",
+                "--cached",
+                "--ignore-unmatch",
+                "--",
+                pathspec,
+            ],
+            None,
+        ),
+    };
+    restore_index?;
+    Ok(Rollback {
+        restored: saved.len(),
+        removed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rollback_restores_workdir_and_leaves_the_rest() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        let run = |args: &[&str]| git(repo, args, None).unwrap();
+        run(&["init", "-q"]);
+        run(&["config", "user.name", "test"]);
+        run(&["config", "user.email", "test@example.com"]);
+        std::fs::write(repo.join(".gitignore"), "target/\n").unwrap();
+        std::fs::write(repo.join("a.txt"), "original\n").unwrap();
+        std::fs::create_dir(repo.join("src")).unwrap();
+        std::fs::write(repo.join("src/lib.rs"), "fn kept() {}\n").unwrap();
+        run(&["add", "."]);
+        run(&["commit", "-qm", "init"]);
+        // The user's staged work, inside and outside the workdir
+        std::fs::write(repo.join("a.txt"), "staged\n").unwrap();
+        std::fs::write(repo.join("src/lib.rs"), "fn staged() {}\n").unwrap();
+        run(&["add", "a.txt", "src/lib.rs"]);
+        std::fs::write(repo.join("src/notes.txt"), "untracked\n").unwrap();
+
+        let checkpoint = create(&repo.join("src"), "omar-agent-0-api").unwrap();
+        assert_eq!(checkpoint.workdir, "src/");
+        assert_eq!(
+            run(&["rev-parse", "refs/omar/checkpoints/omar-agent-0-api"]),
+            checkpoint.commit
+        );
+        assert_eq!(
+            run(&["show", &format!("{}:a.txt", checkpoint.commit)]),
+            "original"
+        );
+        assert!(run(&["status", "--porcelain"]).contains("?? src/notes.txt"));
+
+        std::fs::write(repo.join("src/lib.rs"), "trashed\n").unwrap();
+        std::fs::remove_file(repo.join("src/notes.txt")).unwrap();
+        std::fs::write(repo.join("src/junk.txt"), "junk\n").unwrap();
+        std::fs::create_dir(repo.join("src/target")).unwrap();
+        std::fs::write(repo.join("src/target/out"), "build\n").unwrap();
+        run(&["add", "src/junk.txt"]);
+        std::fs::write(repo.join("outside.txt"), "user file\n").unwrap();
+
+        let result = rollback(&checkpoint).unwrap();
+        assert_eq!(
+            result,
+            Rollback {
+                restored: 2,
+                removed: 1
+            }
+        );
+        assert_eq!(
+            std::fs::read_to_string(repo.join("src/lib.rs")).unwrap(),
+            "fn staged() {}\n"
+        );
+        assert!(repo.join("src/notes.txt").exists());
+        assert!(!repo.join("src/junk.txt").exists());
+        assert!(repo.join("src/target/out").exists());
+        assert!(repo.join("outside.txt").exists());
+        let status = run(&["status", "--porcelain"]);
+        assert!(status.contains("M  a.txt"), "{}", status);
+        assert!(status.contains("M  src/lib.rs"), "{}", status);
+        assert!(status.contains("?? src/notes.txt"), "{}", status);
+        assert!(!status.contains("junk"), "{}", status);
+
+        assert!(create(&tempfile::tempdir().unwrap().path().join("."), "x").is_err());
+    }
+}
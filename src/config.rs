@@ -2,7 +2,8 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 use crate::backend_probe;
 
@@ -22,6 +23,116 @@ pub struct Config {
 
     #[serde(default)]
     pub slack_bridge: SlackBridgeConfig,
+
+    #[serde(default)]
+    pub sandbox: SandboxConfig,
+
+    /// CPU priority and affinity for worker agents (`[limits]`)
+    #[serde(default)]
+    pub limits: LimitsConfig,
+
+    /// Where `omar web` listens (`[api]`)
+    #[serde(default)]
+    pub api: ApiConfig,
+
+    /// Named secrets `spawn_agent` can inject into a worker (`[secrets]`)
+    #[serde(default)]
+    pub secrets: SecretsConfig,
+
+    /// EA and worker system prompt templates (`[prompts]`)
+    #[serde(default)]
+    pub prompts: PromptsConfig,
+
+    /// Import labeled GitHub issues as projects (`[github]`)
+    #[serde(default)]
+    pub github: GithubConfig,
+
+    #[serde(default)]
+    pub scripting: ScriptingConfig,
+
+    /// Desktop notifications for health transitions (`[notifications]`)
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+
+    /// Dashboard colors (`[theme]`)
+    #[serde(default)]
+    pub theme: ThemeConfig,
+
+    /// Built-in stages around worker results (`[orchestration]`)
+    #[serde(default)]
+    pub orchestration: OrchestrationConfig,
+
+    /// Named pipelines of sequential agent stages
+    #[serde(default)]
+    pub pipelines: BTreeMap<String, PipelineConfig>,
+
+    /// Recurring cron-style agent spawns (`[[schedules]]`)
+    #[serde(default)]
+    pub schedules: Vec<ScheduleConfig>,
+
+    /// Remote machines whose tmux agents the dashboard aggregates
+    #[serde(default)]
+    pub hosts: Vec<HostConfig>,
+
+    /// Named spawn presets (`[[profiles]]`), picked with `profile` on
+    /// `spawn_agent` or in the spawn wizard
+    #[serde(default)]
+    pub profiles: Vec<ProfileConfig>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileConfig {
+    /// Name to select the profile by (e.g. "frontend")
+    pub name: String,
+
+    /// Agent command (defaults to `agent.default_command`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+
+    /// Working directory (defaults to `agent.default_workdir`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workdir: Option<String>,
+
+    /// Sandbox runtime for this profile's agents, overriding
+    /// `sandbox.runtime`: "none", "bwrap", "firejail", or "kubernetes"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<String>,
+
+    /// Environment variables set in the agent's session
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+impl ProfileConfig {
+    /// `sandbox` with this profile's runtime override applied.
+    pub fn sandbox_config(&self, sandbox: &SandboxConfig) -> SandboxConfig {
+        let mut sandbox = sandbox.clone();
+        if let Some(runtime) = &self.sandbox {
+            sandbox.runtime = runtime.clone();
+        }
+        sandbox
+    }
+
+    /// `env` as name/value pairs, for `secrets::inject`.
+    pub fn env_pairs(&self) -> Vec<(String, String)> {
+        self.env
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostConfig {
+    /// Name shown on agent cards (e.g. "build1")
+    pub name: String,
+
+    /// ssh destination (e.g. "ci@build1.internal")
+    pub ssh: String,
+
+    /// SSH `ControlPath` to multiplex polls over one connection
+    #[serde(default)]
+    pub control_path: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +156,206 @@ pub struct DashboardConfig {
     /// Show inspirational quotes in the status bar
     #[serde(default)]
     pub show_quotes: bool,
+
+    /// Session backend for CLI spawn/kill/send/health, drain counts, the
+    /// dashboard poller and MCP agent listing and kills
+    #[serde(default)]
+    pub multiplexer: Multiplexer,
+
+    /// WSL distribution for `multiplexer = "wsl"`; the default one if unset
+    #[serde(default)]
+    pub wsl_distro: Option<String>,
+
+    /// Zone for times shown in the TUI: `local`, `utc`, or a fixed offset
+    /// like `+05:30`. APIs always report RFC3339 UTC.
+    #[serde(default)]
+    pub timezone: Timezone,
+
+    /// Language of dashboard text (`en`, `es`)
+    #[serde(default)]
+    pub language: Language,
+
+    /// `grouped` also links each agent's window into a tmux session per
+    /// project, so native tmux tooling shows the hierarchy
+    #[serde(default)]
+    pub layout: SessionLayout,
+
+    /// Starting panel arrangement: `standard`, `grid-only`, `ea-focus`, or
+    /// `tree-focus`. Changes made from the dashboard are kept in
+    /// `~/.omar/ui_state.json` and win over this and the panel flags below.
+    #[serde(default)]
+    pub layout_preset: LayoutPreset,
+
+    /// Show the projects panel in the sidebar
+    #[serde(default = "default_true")]
+    pub show_projects: bool,
+
+    /// Show the EA panel under the agent grid
+    #[serde(default = "default_true")]
+    pub show_ea_panel: bool,
+
+    /// Show the chain-of-command tree in the sidebar
+    #[serde(default = "default_true")]
+    pub show_command_tree: bool,
+}
+
+/// How the dashboard divides the screen between its panels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LayoutPreset {
+    /// Sidebar, agent grid over a third-height EA panel
+    #[default]
+    Standard,
+    /// Agent grid alone
+    GridOnly,
+    /// EA panel takes two thirds of the main area
+    EaFocus,
+    /// Wider sidebar, mostly chain-of-command tree
+    TreeFocus,
+}
+
+impl LayoutPreset {
+    pub fn next(self) -> Self {
+        match self {
+            LayoutPreset::Standard => LayoutPreset::GridOnly,
+            LayoutPreset::GridOnly => LayoutPreset::EaFocus,
+            LayoutPreset::EaFocus => LayoutPreset::TreeFocus,
+            LayoutPreset::TreeFocus => LayoutPreset::Standard,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LayoutPreset::Standard => "standard",
+            LayoutPreset::GridOnly => "grid-only",
+            LayoutPreset::EaFocus => "ea-focus",
+            LayoutPreset::TreeFocus => "tree-focus",
+        }
+    }
+}
+
+/// How agent sessions are arranged in tmux.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionLayout {
+    /// One session per agent
+    #[default]
+    Flat,
+    /// Agent sessions plus a session per project linking their windows
+    Grouped,
+}
+
+/// Dashboard UI language.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    Es,
+}
+
+/// Terminal multiplexer hosting agent sessions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Multiplexer {
+    #[default]
+    Tmux,
+    Screen,
+    Zellij,
+    Wezterm,
+    /// tmux inside WSL, for a dashboard running natively on Windows
+    Wsl,
+}
+
+/// Time zone for rendering timestamps in the dashboard and CLI.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Timezone {
+    #[default]
+    Local,
+    Utc,
+    Fixed(chrono::FixedOffset),
+}
+
+impl Timezone {
+    /// Format a UTC instant in this zone.
+    pub fn format(&self, at: chrono::DateTime<chrono::Utc>, fmt: &str) -> String {
+        match self {
+            Timezone::Local => at.with_timezone(&chrono::Local).format(fmt).to_string(),
+            Timezone::Utc => at.format(fmt).to_string(),
+            Timezone::Fixed(offset) => at.with_timezone(offset).format(fmt).to_string(),
+        }
+    }
+
+    /// Clock time for a nanosecond Unix timestamp; includes the date unless
+    /// it falls on today (in this zone).
+    pub fn format_ns(&self, ns: u64) -> String {
+        let at = chrono::DateTime::from_timestamp_nanos(ns as i64);
+        let now = chrono::Utc::now();
+        if self.format(at, "%F") == self.format(now, "%F") {
+            self.format(at, "%H:%M:%S")
+        } else {
+            self.format(at, "%m-%d %H:%M")
+        }
+    }
+}
+
+impl TryFrom<String> for Timezone {
+    type Error = String;
+
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+        match value.to_ascii_lowercase().as_str() {
+            "local" => Ok(Timezone::Local),
+            "utc" | "z" => Ok(Timezone::Utc),
+            other => chrono::DateTime::parse_from_rfc3339(&format!("2000-01-01T00:00:00{}", other))
+                .map(|t| Timezone::Fixed(*t.offset()))
+                .map_err(|_| {
+                    format!(
+                        "invalid timezone '{}' (expected local, utc, or an offset like +05:30)",
+                        value
+                    )
+                }),
+        }
+    }
+}
+
+impl From<Timezone> for String {
+    fn from(zone: Timezone) -> Self {
+        match zone {
+            Timezone::Local => "local".to_string(),
+            Timezone::Utc => "utc".to_string(),
+            Timezone::Fixed(offset) => offset.to_string(),
+        }
+    }
+}
+
+/// Dashboard color overrides: color names (`"blue"`), hex (`"#005f87"`),
+/// or 256-color indices. Unset keys keep the built-in palette.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub running: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished: Option<String>,
+    /// Blocked/stuck agents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocked: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limited: Option<String>,
+    /// Focused panels and selected cards
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selection: Option<String>,
+    /// Popup borders and labels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub border: Option<String>,
+    /// Unfocused borders and secondary text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dim: Option<String>,
+    /// Unfocused EA panel border
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ea_panel: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,6 +371,94 @@ pub struct HealthConfig {
     /// Patterns in output that indicate an error
     #[serde(default = "default_error_patterns")]
     pub error_patterns: Vec<String>,
+
+    /// Wake an agent's parent when it prints a completion marker
+    #[serde(default = "default_true")]
+    pub notify_parent_on_result: bool,
+
+    /// Seconds a worker may sit idle before its parent is sent its recent
+    /// output and options, or the EA when the parent is stuck as well;
+    /// unset disables escalation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escalate_stuck_after: Option<u64>,
+
+    /// External health classifier (`[health.plugin]`)
+    #[serde(default)]
+    pub plugin: Option<HealthPluginConfig>,
+
+    /// Shorthand for `[health.plugin] command` with the default timeout;
+    /// `[health.plugin]` wins when both are set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checker_command: Option<String>,
+
+    /// Provider rate-limit detection (`[health.rate_limit]`)
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+
+    /// Overrides keyed by backend (pane command), e.g.
+    /// `[health.backends.opencode]`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub backends: BTreeMap<String, BackendHealthConfig>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackendHealthConfig {
+    /// Replaces `health.idle_warning` for this backend
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_warning: Option<i64>,
+
+    /// Replaces `health.idle_critical` for this backend
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_critical: Option<i64>,
+
+    /// Case-insensitive regexes on the last lines of the pane that mean the
+    /// agent is working even while its screen is unchanged, e.g. a spinner
+    /// label
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub working_patterns: Vec<String>,
+
+    /// Regexes that mean it is waiting at its prompt even while its screen
+    /// keeps changing, e.g. a ticking clock in the status bar
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub waiting_patterns: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// Case-insensitive regexes that mark an agent rate-limited, for every
+    /// backend
+    #[serde(default = "default_rate_limit_patterns")]
+    pub patterns: Vec<String>,
+
+    /// Extra patterns keyed by backend (pane command), e.g. `codex = [...]`
+    #[serde(default)]
+    pub backends: BTreeMap<String, Vec<String>>,
+
+    /// Hold scheduled events for a rate-limited agent until its cooldown ends
+    #[serde(default = "default_true")]
+    pub pause_dispatch: bool,
+
+    /// Send `resume_message` to the agent once the cooldown elapses
+    #[serde(default = "default_true")]
+    pub auto_resume: bool,
+
+    /// Cooldown used when the message does not state one
+    #[serde(default = "default_rate_limit_cooldown_secs")]
+    pub default_cooldown_secs: u64,
+
+    #[serde(default = "default_rate_limit_resume_message")]
+    pub resume_message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthPluginConfig {
+    /// Shell command fed recent pane output on stdin; prints a JSON verdict
+    /// such as `{"state": "blocked"}`
+    pub command: String,
+
+    /// Milliseconds before the command is killed and its verdict ignored
+    #[serde(default = "default_health_plugin_timeout_ms")]
+    pub timeout_ms: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,6 +470,58 @@ pub struct AgentConfig {
     /// Default working directory
     #[serde(default = "default_workdir")]
     pub default_workdir: String,
+
+    /// Most workers per EA running at once; further `spawn_agent` requests
+    /// are queued (unset = unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent: Option<usize>,
+
+    /// How a new agent is judged ready for its task (`[agent.readiness]`)
+    #[serde(default)]
+    pub readiness: ReadinessConfig,
+
+    /// Commit the worker's git workdir to a checkpoint ref before sending
+    /// its task, so `rollback_agent` can undo its changes
+    #[serde(default)]
+    pub checkpoint_workdir: bool,
+
+    /// Seconds a worker may sit idle or finished (not blocked on a
+    /// question, no live children) before its transcript is archived, its
+    /// parent told, and its session killed (unset = never). PMs and
+    /// reviewers are never auto-killed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_kill_idle_after: Option<u64>,
+
+    /// Append each worker's trace ID to its first prompt, so it can quote
+    /// the ID in its reports
+    #[serde(default)]
+    pub trace_in_prompt: bool,
+
+    /// Environment variables set in every worker's session
+    /// (`[agent.env]`); a spawn's own `env` overrides them
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+
+    /// Resume the previous run's unfinished workers on startup without
+    /// asking first
+    #[serde(default)]
+    pub auto_resume: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadinessConfig {
+    /// Ready-prompt regex keyed by backend (`claude`, `codex`, ...),
+    /// replacing that backend's built-in markers
+    #[serde(default)]
+    pub patterns: BTreeMap<String, String>,
+
+    /// Seconds to wait for the ready prompt before sending the task anyway
+    #[serde(default = "default_readiness_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Failed pane reads tolerated before the spawn is marked failed
+    #[serde(default = "default_readiness_retries")]
+    pub retries: u32,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -78,6 +529,11 @@ pub struct MetricsConfig {
     /// Enable global spawn metrics sink at ~/.omar/metrics/spawn_metrics.jsonl
     #[serde(default)]
     pub spawn_metrics_enabled: bool,
+
+    /// USD per hour of agent runtime by backend (pane command, e.g.
+    /// "claude"), used to price plan estimates
+    #[serde(default)]
+    pub cost_per_hour: BTreeMap<String, f64>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -89,6 +545,331 @@ pub struct SlackBridgeConfig {
     pub active_ea: Option<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SandboxConfig {
+    /// Sandbox runtime for worker agents: "none", "bwrap", "firejail", or
+    /// "kubernetes"
+    #[serde(default = "default_sandbox_runtime")]
+    pub runtime: String,
+
+    /// Network mode inside the sandbox: "host" (shared), "none" (isolated),
+    /// or "proxy" (HTTP(S) through a local proxy allowing `[sandbox.proxy]`
+    /// domains only)
+    #[serde(default = "default_sandbox_network")]
+    pub network: String,
+
+    /// Host paths bind-mounted writable on top of the read-only root.
+    /// Missing paths are skipped. The agent workdir is always writable.
+    #[serde(default = "default_sandbox_writable_paths")]
+    pub writable_paths: Vec<String>,
+
+    /// GPUs workers can use: "all", or indices such as "0,1". Unset keeps
+    /// each runtime's default (none under bwrap, the host's under firejail).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gpus: Option<String>,
+
+    /// Host agent credentials (`~/.claude`, `~/.config/opencode`, ...) in
+    /// the sandbox: "on" (shared), "off" (hidden), or a directory laid out
+    /// like `$HOME` to use in their place (bwrap only)
+    #[serde(default = "default_sandbox_credentials")]
+    pub credentials: String,
+
+    /// Pod settings for the "kubernetes" runtime (`[sandbox.kubernetes]`)
+    #[serde(default)]
+    pub kubernetes: KubernetesConfig,
+
+    /// Allowlisting proxy used by `network = "proxy"` (`[sandbox.proxy]`)
+    #[serde(default)]
+    pub proxy: ProxyConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    /// Loopback address inside the sandbox that `HTTP(S)_PROXY` point at,
+    /// bridged to the host proxy's socket
+    #[serde(default = "default_proxy_listen")]
+    pub listen: String,
+
+    /// Domains workers may reach. A domain also allows its subdomains.
+    #[serde(default = "default_proxy_allow")]
+    pub allow: Vec<String>,
+}
+
+/// Where and how big the pods of the "kubernetes" sandbox runtime are.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KubernetesConfig {
+    /// Container image with the agent CLIs installed (required)
+    #[serde(default)]
+    pub image: String,
+
+    /// Namespace to create pods in; kubectl's current one when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+
+    /// kubeconfig context to use; kubectl's current one when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+
+    /// Node labels a pod must match, e.g. `{ pool = "agents" }`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub node_selector: BTreeMap<String, String>,
+
+    /// CPU request per agent, in Kubernetes quantity form (e.g. "4")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<String>,
+
+    /// Memory request per agent, e.g. "8Gi"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<String>,
+
+    /// PersistentVolumeClaim mounted at the worker's workdir path, e.g. a
+    /// shared export of the repos the agents work on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume_claim: Option<String>,
+
+    /// Size cap of a writable per-pod scratch volume, e.g. "20Gi". The pod
+    /// is evicted if it writes more; the volume goes with the pod.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scratch_size: Option<String>,
+
+    /// Where the scratch volume is mounted ("/scratch" when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scratch_path: Option<String>,
+}
+
+/// Applied to sandboxed and unsandboxed workers alike, so a large fleet
+/// leaves room for the user's interactive session.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitsConfig {
+    /// Niceness to start workers at, -20 (highest priority) to 19
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nice: Option<i32>,
+
+    /// CPUs workers may run on, in `taskset -c` form (e.g. "2-7" or "0,2,4")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpuset: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiConfig {
+    /// Unix socket to serve the API on, e.g. "~/.omar/api.sock". Access is
+    /// limited to the socket's owner instead of a token. Unix hosts only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub socket: Option<String>,
+
+    /// Also listen on TCP (`--host`/`--port`) when a socket is set
+    #[serde(default = "default_true")]
+    pub tcp: bool,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            socket: None,
+            tcp: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecretsConfig {
+    /// `KEY=VALUE` file holding secrets by name, e.g. "~/.omar/secrets.env"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_file: Option<String>,
+
+    /// Secret name -> `pass:<path>`, `op://vault/item/field`, or `env:<VAR>`
+    #[serde(default)]
+    pub sources: BTreeMap<String, String>,
+}
+
+/// Replacements for the built-in system prompts. The files are templates:
+/// `{{EA_ID}}`, `{{EA_NAME}}`, `{{OMAR_DIR}}`, `{{SESSION_PREFIX}}` and the
+/// worker's `{{TASK}}`/`{{PARENT_NAME}}` are filled in at launch, as is
+/// `{{NAME}}` for each entry of `vars`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptsConfig {
+    /// EA prompt file used instead of the built-in executive-assistant.md
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ea: Option<String>,
+
+    /// Worker prompt file used instead of the built-in agent.md
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+
+    /// Extra placeholders; names are letters, digits, and underscores
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub vars: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GithubConfig {
+    /// "owner/name" to import issues from; unset disables the sync
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+
+    /// API token as a secret reference: `env:<VAR>`, `pass:<path>`, or
+    /// `op://...`
+    #[serde(default = "default_github_token")]
+    pub token: String,
+
+    /// Open issues carrying this label become projects
+    #[serde(default = "default_github_label")]
+    pub label: String,
+
+    /// Seconds between syncs while the dashboard runs
+    #[serde(default = "default_github_sync_interval_secs")]
+    pub sync_interval_secs: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PipelineConfig {
+    pub stages: Vec<PipelineStageConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PipelineStageConfig {
+    /// Stage role, also used in the stage agent's name (e.g. "plan")
+    pub role: String,
+
+    /// Task template; `{{input}}` is the pipeline input and `{{previous}}`
+    /// the prior stage's result
+    pub prompt: String,
+
+    /// Backend preset for this stage (defaults to `agent.default_command`)
+    #[serde(default)]
+    pub backend: Option<String>,
+
+    /// Pause the run before this stage until a human approves it
+    #[serde(default)]
+    pub requires_approval: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduleConfig {
+    /// Unique name, also the prefix of spawned agents (e.g. "nightly-tests")
+    pub name: String,
+
+    /// Five-field cron expression in local time (e.g. "0 2 * * *")
+    pub cron: String,
+
+    /// Task given to each spawned worker
+    pub task: String,
+
+    /// Backend preset (defaults to `agent.default_command`)
+    #[serde(default)]
+    pub backend: Option<String>,
+
+    /// Working directory (defaults to `agent.default_workdir`)
+    #[serde(default)]
+    pub workdir: Option<String>,
+
+    /// EA that runs the schedule (config schedules only; API-added ones
+    /// belong to the EA they were added in)
+    #[serde(default)]
+    pub ea: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptingConfig {
+    /// Rhai automation scripts loaded by the dashboard
+    #[serde(default)]
+    pub scripts: Vec<String>,
+
+    /// Seconds between `every_interval` hook calls
+    #[serde(default = "default_scripting_interval_secs")]
+    pub interval_secs: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationsConfig {
+    /// Master switch; off by default
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Notify when an agent prints `[BLOCKED: ...]` or `[NEED INPUT: ...]`
+    #[serde(default = "default_true")]
+    pub on_waiting: bool,
+
+    /// Notify when an agent stays idle for `health.idle_critical` seconds,
+    /// or its sandbox no longer matches how omar launched it
+    #[serde(default = "default_true")]
+    pub on_stuck: bool,
+
+    /// Notify when an agent prints `[TASK COMPLETE]`
+    #[serde(default = "default_true")]
+    pub on_complete: bool,
+
+    /// Notify, and show a dashboard banner, when the last stage of a
+    /// pipeline run completes
+    #[serde(default = "default_true")]
+    pub on_plan_complete: bool,
+
+    /// Ring the bell in the dashboard's tmux window for the events above
+    /// while another window is in view, so tmux flags it in the status
+    /// line. Independent of `enabled`.
+    #[serde(default = "default_true")]
+    pub tmux_bell: bool,
+
+    /// Shell command run instead of notify-send/osascript, with
+    /// `OMAR_NOTIFY_TITLE` and `OMAR_NOTIFY_BODY` in its environment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+
+    /// JSON POSTs to a Slack/Discord-compatible URL
+    /// (`[notifications.webhook]`), independent of `enabled`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<WebhookConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrchestrationConfig {
+    /// Have a reviewer agent sign off on each worker's `[TASK COMPLETE]`
+    /// before the worker's parent is told
+    #[serde(default)]
+    pub review: bool,
+
+    /// Backend preset for reviewers (defaults to `agent.default_command`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviewer_backend: Option<String>,
+
+    /// Review rounds before an unapproved result is passed on anyway
+    #[serde(default = "default_max_review_rounds")]
+    pub max_review_rounds: u32,
+
+    /// Seconds between re-sending the EA a condensed memory.md while it is
+    /// idle, when the state changed since the last one (unset = never)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_context_every: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+
+    /// Events to post: complete, blocked, need_input, stuck, sandbox,
+    /// plan_completed
+    #[serde(default = "default_webhook_events")]
+    pub events: Vec<String>,
+}
+
+fn default_webhook_events() -> Vec<String> {
+    [
+        "complete",
+        "blocked",
+        "need_input",
+        "stuck",
+        "sandbox",
+        "plan_completed",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+fn default_max_review_rounds() -> u32 {
+    3
+}
+
 fn default_true() -> bool {
     true
 }
@@ -109,6 +890,39 @@ fn default_idle_critical() -> i64 {
     300
 }
 
+fn default_health_plugin_timeout_ms() -> u64 {
+    2000
+}
+
+fn default_readiness_timeout_secs() -> u64 {
+    45
+}
+
+fn default_readiness_retries() -> u32 {
+    3
+}
+
+fn default_rate_limit_patterns() -> Vec<String> {
+    [
+        r"rate[ _-]?limit(ed|_error| reached| exceeded)",
+        r"too many requests",
+        r"usage limit reached",
+        r"hit your (usage )?limit",
+        r"quota exceeded",
+    ]
+    .iter()
+    .map(|p| p.to_string())
+    .collect()
+}
+
+fn default_rate_limit_cooldown_secs() -> u64 {
+    300
+}
+
+fn default_rate_limit_resume_message() -> String {
+    "The rate-limit cooldown has elapsed. Please continue your task.".to_string()
+}
+
 fn default_error_patterns() -> Vec<String> {
     vec![
         "error".to_string(),
@@ -173,6 +987,60 @@ fn default_workdir() -> String {
     ".".to_string()
 }
 
+fn default_scripting_interval_secs() -> u64 {
+    60
+}
+
+fn default_sandbox_runtime() -> String {
+    "none".to_string()
+}
+
+fn default_proxy_listen() -> String {
+    "127.0.0.1:3129".to_string()
+}
+
+fn default_proxy_allow() -> Vec<String> {
+    vec!["api.anthropic.com".to_string(), "github.com".to_string()]
+}
+
+fn default_sandbox_network() -> String {
+    "host".to_string()
+}
+
+fn default_github_token() -> String {
+    "env:GITHUB_TOKEN".to_string()
+}
+
+fn default_github_label() -> String {
+    "omar".to_string()
+}
+
+fn default_github_sync_interval_secs() -> u64 {
+    300
+}
+
+fn default_sandbox_credentials() -> String {
+    "on".to_string()
+}
+
+/// Backend credential/state dirs plus `~/.omar` so the in-sandbox MCP
+/// server can still update EA state.
+fn default_sandbox_writable_paths() -> Vec<String> {
+    [
+        "~/.omar",
+        "~/.claude",
+        "~/.claude.json",
+        "~/.codex",
+        "~/.config/opencode",
+        "~/.local/share/opencode",
+        "~/.cursor",
+        "~/.gemini",
+    ]
+    .iter()
+    .map(|p| p.to_string())
+    .collect()
+}
+
 impl Default for DashboardConfig {
     fn default() -> Self {
         Self {
@@ -181,6 +1049,15 @@ impl Default for DashboardConfig {
             show_event_queue: true,
             sidebar_right: true,
             show_quotes: false,
+            multiplexer: Multiplexer::Tmux,
+            wsl_distro: None,
+            timezone: Timezone::Local,
+            language: Language::En,
+            layout: SessionLayout::Flat,
+            layout_preset: LayoutPreset::Standard,
+            show_projects: true,
+            show_ea_panel: true,
+            show_command_tree: true,
         }
     }
 }
@@ -191,6 +1068,57 @@ impl Default for HealthConfig {
             idle_warning: default_idle_warning(),
             idle_critical: default_idle_critical(),
             error_patterns: default_error_patterns(),
+            notify_parent_on_result: true,
+            escalate_stuck_after: None,
+            plugin: None,
+            checker_command: None,
+            rate_limit: RateLimitConfig::default(),
+            backends: BTreeMap::new(),
+        }
+    }
+}
+
+impl HealthConfig {
+    /// The external classifier from `[health.plugin]` or `checker_command`.
+    pub fn checker(&self) -> Option<HealthPluginConfig> {
+        self.plugin.clone().or_else(|| {
+            self.checker_command
+                .as_ref()
+                .map(|command| HealthPluginConfig {
+                    command: command.clone(),
+                    timeout_ms: default_health_plugin_timeout_ms(),
+                })
+        })
+    }
+
+    /// `idle_warning`, or `backend`'s override of it.
+    pub fn idle_warning_for(&self, backend: Option<&str>) -> i64 {
+        self.backend(backend)
+            .and_then(|b| b.idle_warning)
+            .unwrap_or(self.idle_warning)
+    }
+
+    /// `idle_critical`, or `backend`'s override of it.
+    pub fn idle_critical_for(&self, backend: Option<&str>) -> i64 {
+        self.backend(backend)
+            .and_then(|b| b.idle_critical)
+            .unwrap_or(self.idle_critical)
+    }
+
+    fn backend(&self, backend: Option<&str>) -> Option<&BackendHealthConfig> {
+        self.backends.get(backend?)
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            patterns: default_rate_limit_patterns(),
+            backends: BTreeMap::new(),
+            pause_dispatch: true,
+            auto_resume: true,
+            default_cooldown_secs: default_rate_limit_cooldown_secs(),
+            resume_message: default_rate_limit_resume_message(),
         }
     }
 }
@@ -200,6 +1128,92 @@ impl Default for AgentConfig {
         Self {
             default_command: default_command(),
             default_workdir: default_workdir(),
+            max_concurrent: None,
+            readiness: ReadinessConfig::default(),
+            checkpoint_workdir: false,
+            auto_kill_idle_after: None,
+            trace_in_prompt: false,
+            env: BTreeMap::new(),
+            auto_resume: false,
+        }
+    }
+}
+
+impl Default for ReadinessConfig {
+    fn default() -> Self {
+        Self {
+            patterns: BTreeMap::new(),
+            timeout_secs: default_readiness_timeout_secs(),
+            retries: default_readiness_retries(),
+        }
+    }
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        Self {
+            runtime: default_sandbox_runtime(),
+            network: default_sandbox_network(),
+            writable_paths: default_sandbox_writable_paths(),
+            gpus: None,
+            credentials: default_sandbox_credentials(),
+            kubernetes: KubernetesConfig::default(),
+            proxy: ProxyConfig::default(),
+        }
+    }
+}
+
+impl Default for ProxyConfig {
+    fn default() -> Self {
+        Self {
+            listen: default_proxy_listen(),
+            allow: default_proxy_allow(),
+        }
+    }
+}
+
+impl Default for GithubConfig {
+    fn default() -> Self {
+        Self {
+            repo: None,
+            token: default_github_token(),
+            label: default_github_label(),
+            sync_interval_secs: default_github_sync_interval_secs(),
+        }
+    }
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            on_waiting: true,
+            on_stuck: true,
+            on_complete: true,
+            on_plan_complete: true,
+            tmux_bell: true,
+            command: None,
+            webhook: None,
+        }
+    }
+}
+
+impl Default for OrchestrationConfig {
+    fn default() -> Self {
+        Self {
+            review: false,
+            reviewer_backend: None,
+            max_review_rounds: default_max_review_rounds(),
+            refresh_context_every: None,
+        }
+    }
+}
+
+impl Default for ScriptingConfig {
+    fn default() -> Self {
+        Self {
+            scripts: Vec::new(),
+            interval_secs: default_scripting_interval_secs(),
         }
     }
 }
@@ -255,6 +1269,44 @@ impl Config {
         }
     }
 
+    /// Save only the setting at `index`, so keys changed by another process
+    /// since this config was loaded are kept.
+    fn save_setting(&self, index: usize) -> Result<()> {
+        edit_file(&Self::default_path(), |doc| {
+            let (table, key, value) = match index {
+                0 => (
+                    "dashboard",
+                    "show_event_queue",
+                    toml_edit::value(self.dashboard.show_event_queue),
+                ),
+                1 => (
+                    "dashboard",
+                    "sidebar_right",
+                    toml_edit::value(self.dashboard.sidebar_right),
+                ),
+                2 => (
+                    "dashboard",
+                    "show_quotes",
+                    toml_edit::value(self.dashboard.show_quotes),
+                ),
+                3 => (
+                    "slack_bridge",
+                    "active_ea",
+                    match &self.slack_bridge.active_ea {
+                        Some(ea) => toml_edit::value(ea.as_str()),
+                        None => toml_edit::Item::None,
+                    },
+                ),
+                _ => return,
+            };
+            section(doc, &[table])[key] = value;
+        })
+    }
+
+    pub fn profile(&self, name: &str) -> Option<&ProfileConfig> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
     /// Number of settings exposed in the dashboard panel.
     pub fn settings_count(&self) -> usize {
         4
@@ -291,7 +1343,7 @@ impl Config {
             2 => self.dashboard.show_quotes = !self.dashboard.show_quotes,
             _ => return,
         }
-        self.save();
+        let _ = self.save_setting(index);
     }
 
     /// Set a text-typed setting by index and save. An empty string clears
@@ -310,11 +1362,54 @@ impl Config {
             }
             _ => return false,
         }
-        self.save();
+        let _ = self.save_setting(index);
         true
     }
 }
 
+/// Apply `edit` to the config file at `path`, keeping every other key and
+/// comment as written. A missing file starts empty.
+pub fn edit_file(path: &Path, edit: impl FnOnce(&mut toml_edit::DocumentMut)) -> Result<()> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).context("Failed to read config file"),
+    };
+    let mut doc: toml_edit::DocumentMut =
+        contents.parse().context("Failed to parse config file")?;
+    edit(&mut doc);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("toml.tmp");
+    std::fs::write(&tmp, doc.to_string()).context("Failed to write config file")?;
+    std::fs::rename(&tmp, path).context("Failed to write config file")?;
+    Ok(())
+}
+
+/// The `[a.b]` table at `path` in `doc`, created if missing. Inline tables
+/// are expanded so keys added to them are written.
+pub fn section<'a>(doc: &'a mut toml_edit::DocumentMut, path: &[&str]) -> &'a mut toml_edit::Table {
+    let mut table = doc.as_table_mut();
+    for key in path {
+        let item = table.entry(key).or_insert_with(|| {
+            let mut new = toml_edit::Table::new();
+            new.set_implicit(true);
+            toml_edit::Item::Table(new)
+        });
+        if let Some(inline) = item.as_inline_table() {
+            *item = toml_edit::Item::Table(inline.clone().into_table());
+        } else if !item.is_table() {
+            *item = toml_edit::table();
+        }
+        table = match item {
+            toml_edit::Item::Table(next) => next,
+            _ => unreachable!("replaced with a table above"),
+        };
+    }
+    table
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SettingItem<'a> {
     Toggle { label: &'a str, value: bool },
@@ -327,7 +1422,7 @@ impl SettingItem<'_> {
     }
 }
 
-fn expand_tilde(path: &str) -> PathBuf {
+pub(crate) fn expand_tilde(path: &str) -> PathBuf {
     if let Some(stripped) = path.strip_prefix("~/") {
         if let Some(home) = dirs::home_dir() {
             return home.join(stripped);
@@ -359,6 +1454,7 @@ mod tests {
         assert_eq!(config.dashboard.session_prefix, "omar-agent-");
         assert!(config.dashboard.show_event_queue);
         assert!(config.dashboard.sidebar_right);
+        assert_eq!(config.dashboard.multiplexer, Multiplexer::Tmux);
         assert_eq!(config.health.idle_warning, 15);
         assert_eq!(config.health.idle_critical, 300);
         assert!(!config.metrics.spawn_metrics_enabled);
@@ -496,6 +1592,17 @@ sidebar_right = false
         assert_eq!(cmd.as_deref(), Some("agy --dangerously-skip-permissions"));
     }
 
+    #[test]
+    fn test_parse_timezone() {
+        let config: Config = toml::from_str("[dashboard]\ntimezone = \"+05:30\"\n").unwrap();
+        let at = chrono::DateTime::from_timestamp(0, 0).unwrap();
+        assert_eq!(config.dashboard.timezone.format(at, "%H:%M"), "05:30");
+        assert_eq!(String::from(config.dashboard.timezone), "+05:30");
+        assert_eq!(Timezone::try_from("UTC".to_string()), Ok(Timezone::Utc));
+        assert_eq!(Config::default().dashboard.timezone, Timezone::Local);
+        assert!(toml::from_str::<Config>("[dashboard]\ntimezone = \"Mars\"\n").is_err());
+    }
+
     #[test]
     fn test_parse_config() {
         let toml = r#"
@@ -504,6 +1611,9 @@ refresh_interval = 5
 session_prefix = "test-"
 show_event_queue = false
 sidebar_right = false
+multiplexer = "zellij"
+language = "es"
+layout = "grouped"
 
 [health]
 idle_warning = 30
@@ -518,6 +1628,9 @@ default_command = "bash"
         assert_eq!(config.dashboard.session_prefix, "test-");
         assert!(!config.dashboard.show_event_queue);
         assert!(!config.dashboard.sidebar_right);
+        assert_eq!(config.dashboard.multiplexer, Multiplexer::Zellij);
+        assert_eq!(config.dashboard.language, Language::Es);
+        assert_eq!(config.dashboard.layout, SessionLayout::Grouped);
         assert_eq!(config.health.idle_warning, 30);
         assert_eq!(config.health.error_patterns, vec!["error", "panic"]);
     }
@@ -598,9 +1711,237 @@ default_command = "aider --yes"
         let toml = r#"
 [metrics]
 spawn_metrics_enabled = true
+cost_per_hour = { claude = 6.5 }
 "#;
         let config: Config = toml::from_str(toml).unwrap();
         assert!(config.metrics.spawn_metrics_enabled);
+        assert_eq!(config.metrics.cost_per_hour["claude"], 6.5);
+        assert!(Config::default().metrics.cost_per_hour.is_empty());
+    }
+
+    #[test]
+    fn test_parse_sandbox_config() {
+        let config = Config::default();
+        assert_eq!(config.sandbox.runtime, "none");
+        assert_eq!(config.sandbox.network, "host");
+        assert_eq!(config.sandbox.credentials, "on");
+
+        let toml = r#"
+[sandbox]
+runtime = "bwrap"
+network = "none"
+writable_paths = ["~/.claude"]
+gpus = "0,1"
+credentials = "off"
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.sandbox.runtime, "bwrap");
+        assert_eq!(config.sandbox.network, "none");
+        assert_eq!(config.sandbox.writable_paths, vec!["~/.claude"]);
+        assert_eq!(config.sandbox.gpus.as_deref(), Some("0,1"));
+        assert_eq!(config.sandbox.credentials, "off");
+
+        let toml = r#"
+[sandbox]
+runtime = "kubernetes"
+
+[sandbox.kubernetes]
+image = "ghcr.io/acme/agents:latest"
+namespace = "agents"
+node_selector = { pool = "highcpu" }
+cpu = "8"
+memory = "16Gi"
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        let kubernetes = &config.sandbox.kubernetes;
+        assert_eq!(kubernetes.image, "ghcr.io/acme/agents:latest");
+        assert_eq!(kubernetes.namespace.as_deref(), Some("agents"));
+        assert_eq!(kubernetes.node_selector["pool"], "highcpu");
+        assert_eq!(kubernetes.cpu.as_deref(), Some("8"));
+        assert_eq!(kubernetes.memory.as_deref(), Some("16Gi"));
+        assert!(kubernetes.volume_claim.is_none());
+    }
+
+    #[test]
+    fn test_parse_limits_config() {
+        assert_eq!(Config::default().limits, LimitsConfig::default());
+
+        let toml = r#"
+[limits]
+nice = 10
+cpuset = "2-7"
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.limits.nice, Some(10));
+        assert_eq!(config.limits.cpuset.as_deref(), Some("2-7"));
+    }
+
+    #[test]
+    fn test_parse_health_plugin_config() {
+        assert!(Config::default().health.plugin.is_none());
+
+        let toml = r#"
+[health.plugin]
+command = "~/bin/classify"
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        let plugin = config.health.plugin.unwrap();
+        assert_eq!(plugin.command, "~/bin/classify");
+        assert_eq!(plugin.timeout_ms, 2000);
+        assert_eq!(config.health.idle_warning, default_idle_warning());
+
+        let config: Config =
+            toml::from_str("[health]\nchecker_command = \"./verdict.sh\"\n").unwrap();
+        let checker = config.health.checker().unwrap();
+        assert_eq!(checker.command, "./verdict.sh");
+        assert_eq!(checker.timeout_ms, 2000);
+    }
+
+    #[test]
+    fn test_parse_pipelines_config() {
+        let toml = r#"
+[pipelines.feature]
+stages = [
+  { role = "plan", prompt = "Plan: {{input}}" },
+  { role = "implement", prompt = "Implement: {{previous}}", backend = "codex" },
+  { role = "deploy", prompt = "Deploy", requires_approval = true },
+]
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        let stages = &config.pipelines["feature"].stages;
+        assert_eq!(stages.len(), 3);
+        assert_eq!(stages[0].role, "plan");
+        assert_eq!(stages[0].backend, None);
+        assert_eq!(stages[1].backend.as_deref(), Some("codex"));
+        assert!(!stages[1].requires_approval);
+        assert!(stages[2].requires_approval);
+        assert!(Config::default().pipelines.is_empty());
+    }
+
+    #[test]
+    fn test_parse_schedules_config() {
+        let toml = r#"
+[[schedules]]
+name = "nightly-tests"
+cron = "0 2 * * *"
+task = "Run the test suite and summarize failures"
+backend = "codex"
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.schedules.len(), 1);
+        assert_eq!(config.schedules[0].cron, "0 2 * * *");
+        assert_eq!(config.schedules[0].backend.as_deref(), Some("codex"));
+        assert_eq!(config.schedules[0].workdir, None);
+        assert_eq!(config.schedules[0].ea, 0);
+    }
+
+    #[test]
+    fn test_parse_rate_limit_config() {
+        let toml = r#"
+[health.rate_limit]
+auto_resume = false
+default_cooldown_secs = 60
+
+[health.rate_limit.backends]
+codex = ["stream disconnected"]
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        let rate_limit = config.health.rate_limit;
+        assert!(!rate_limit.auto_resume);
+        assert!(rate_limit.pause_dispatch);
+        assert_eq!(rate_limit.default_cooldown_secs, 60);
+        assert_eq!(rate_limit.patterns, default_rate_limit_patterns());
+        assert_eq!(rate_limit.backends["codex"], vec!["stream disconnected"]);
+    }
+
+    #[test]
+    fn test_parse_readiness_config() {
+        let toml = r#"
+[agent.readiness]
+timeout_secs = 20
+
+[agent.readiness.patterns]
+codex = "^› "
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        let readiness = config.agent.readiness;
+        assert_eq!(readiness.timeout_secs, 20);
+        assert_eq!(readiness.retries, 3);
+        assert_eq!(readiness.patterns["codex"], "^› ");
+        assert_eq!(Config::default().agent.readiness.timeout_secs, 45);
+    }
+
+    #[test]
+    fn test_parse_agent_env_config() {
+        let toml = r#"
+[agent.env]
+GIT_AUTHOR_NAME = "omar-bot"
+FEATURE_X = "1"
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.agent.env["GIT_AUTHOR_NAME"], "omar-bot");
+        assert_eq!(config.agent.env.len(), 2);
+        assert!(Config::default().agent.env.is_empty());
+    }
+
+    #[test]
+    fn test_parse_notifications_webhook_config() {
+        let toml = r#"
+[notifications.webhook]
+url = "https://hooks.slack.com/services/T000/B000/XXX"
+events = ["blocked", "stuck"]
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert!(!config.notifications.enabled);
+        let webhook = config.notifications.webhook.unwrap();
+        assert_eq!(webhook.events, vec!["blocked", "stuck"]);
+        assert!(Config::default().notifications.webhook.is_none());
+    }
+
+    #[test]
+    fn test_parse_hosts_config() {
+        let toml = r#"
+[[hosts]]
+name = "build1"
+ssh = "ci@build1"
+control_path = "~/.ssh/omar-%r@%h"
+
+[[hosts]]
+name = "build2"
+ssh = "build2"
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.hosts.len(), 2);
+        assert_eq!(
+            config.hosts[0].control_path.as_deref(),
+            Some("~/.ssh/omar-%r@%h")
+        );
+        assert_eq!(config.hosts[1].control_path, None);
+        assert!(Config::default().hosts.is_empty());
+    }
+
+    #[test]
+    fn test_parse_profiles_config() {
+        let toml = r#"
+[sandbox]
+runtime = "bwrap"
+
+[[profiles]]
+name = "frontend"
+command = "codex"
+workdir = "~/src/web"
+sandbox = "none"
+env = { NODE_VERSION = "20" }
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        let profile = config.profile("frontend").unwrap();
+        assert_eq!(profile.command.as_deref(), Some("codex"));
+        assert_eq!(profile.sandbox_config(&config.sandbox).runtime, "none");
+        assert_eq!(
+            profile.env_pairs(),
+            vec![("NODE_VERSION".to_string(), "20".to_string())]
+        );
+        assert!(config.profile("backend").is_none());
     }
 
     #[test]
@@ -0,0 +1,375 @@
+//! Demo mode (`omar --demo`) — the dashboard against a simulated fleet
+//!
+//! `DemoFleet` stands in for tmux: a fixed cast of agents whose output and
+//! health follow looping scripts (running, idle, blocked, rate-limited,
+//! finished, and one that flaps), with the EA proposing a plan. State lives
+//! in a throwaway directory, so nothing touches `~/.omar`, tmux, or an LLM
+//! backend. Keys that would act on real sessions are disabled.
+
+use std::path::Path;
+
+use chrono::Utc;
+use crossterm::event::KeyCode;
+
+use crate::config::Config;
+use crate::ea::{self, EaId};
+use crate::memory;
+use crate::projects;
+use crate::scheduler::{ScheduledEvent, Scheduler};
+use crate::snapshot::Snapshot;
+use crate::tmux::{
+    detect_result, HealthState, HealthTransition, RateLimit, Session, HEALTH_HISTORY_LEN,
+};
+
+const DEMO_EA: EaId = 0;
+
+/// Seconds between scripted output lines while an agent is running.
+const LINE_INTERVAL_SECS: i64 = 3;
+
+struct DemoAgent {
+    name: &'static str,
+    /// Parent agent name; `None` reports to the EA
+    parent: Option<&'static str>,
+    task: &'static str,
+    /// Health script, looped: each state and how long it lasts
+    cycle: &'static [(HealthState, i64)],
+    lines: &'static [&'static str],
+    /// Final lines while Finished, Blocked, or RateLimited
+    marker: &'static str,
+}
+
+const CAST: &[DemoAgent] = &[
+    DemoAgent {
+        name: "api",
+        parent: None,
+        task: "Add pagination to the /orders endpoint and cover it with tests",
+        cycle: &[
+            (HealthState::Running, 40),
+            (HealthState::Idle, 10),
+            (HealthState::Running, 30),
+            (HealthState::Finished, 40),
+        ],
+        lines: &[
+            "Reading src/routes/orders.rs",
+            "Adding `page` and `per_page` query parameters",
+            "Updating OrderRepository::list to take a cursor",
+            "Running cargo test orders::",
+            "test orders::paginates_by_cursor ... ok",
+            "test orders::rejects_oversized_pages ... ok",
+        ],
+        marker: "[TASK COMPLETE]\n- /orders paginates by cursor\n- 6 new tests pass",
+    },
+    DemoAgent {
+        name: "frontend",
+        parent: None,
+        task: "Build the order history page against the paginated API",
+        cycle: &[(HealthState::Running, 50), (HealthState::Idle, 15)],
+        lines: &[
+            "Scaffolding OrderHistory.tsx",
+            "Wiring useInfiniteQuery to /orders?page=",
+            "Styling the empty state",
+            "npm run lint -- --fix",
+            "Rendering 20 rows per page",
+        ],
+        marker: "",
+    },
+    DemoAgent {
+        name: "frontend-tests",
+        parent: Some("frontend"),
+        task: "Write Playwright tests for infinite scrolling on order history",
+        cycle: &[
+            (HealthState::Running, 8),
+            (HealthState::Idle, 6),
+            (HealthState::Running, 5),
+            (HealthState::Blocked, 9),
+        ],
+        lines: &[
+            "npx playwright test order-history",
+            "Retrying flaky selector [data-testid=row-20]",
+        ],
+        marker: "[BLOCKED: staging login needs a test account]",
+    },
+    DemoAgent {
+        name: "migrations",
+        parent: None,
+        task: "Add an index on orders(created_at, id) for cursor pagination",
+        cycle: &[(HealthState::Running, 25), (HealthState::RateLimited, 35)],
+        lines: &[
+            "Writing migrations/0042_orders_cursor_index.sql",
+            "Checking the plan with EXPLAIN ANALYZE",
+        ],
+        marker: "API Error: rate limit exceeded, try again in 5 minutes",
+    },
+    DemoAgent {
+        name: "docs",
+        parent: None,
+        task: "Document the pagination parameters in the API reference",
+        cycle: &[(HealthState::Idle, 20), (HealthState::Running, 20)],
+        lines: &[
+            "Updating docs/api/orders.md",
+            "Adding a cursor pagination example",
+        ],
+        marker: "",
+    },
+];
+
+const MANAGER_OUTPUT: &str = r#"> Plan the order history feature
+
+I'll split this into four workers.
+
+```json
+{"type": "plan",
+ "description": "Ship paginated order history",
+ "agents": [
+  {"name": "api", "role": "backend", "task": "Add pagination to /orders"},
+  {"name": "frontend", "role": "frontend", "task": "Build the order history page", "depends_on": ["api"]},
+  {"name": "migrations", "role": "dba", "task": "Index orders for cursor pagination"},
+  {"name": "docs", "role": "writer", "task": "Document the pagination parameters"}
+ ]}
+```
+
+Waiting for approval. Workers report back here."#;
+
+/// Scripted stand-in for tmux.
+pub struct DemoFleet {
+    started: i64,
+    prefix: String,
+    manager: String,
+}
+
+impl DemoFleet {
+    /// Seed `omar_dir` (a throwaway directory) with the cast's tasks,
+    /// hierarchy, a project, and a recurring event.
+    pub fn new(config: &Config, omar_dir: &Path, scheduler: &Scheduler) -> Self {
+        let base_prefix = &config.dashboard.session_prefix;
+        let fleet = Self {
+            started: Utc::now().timestamp(),
+            prefix: ea::ea_prefix(DEMO_EA, base_prefix),
+            manager: ea::ea_manager_session(DEMO_EA, base_prefix),
+        };
+        let _ = ea::ensure_default_ea(omar_dir);
+        let state_dir = ea::ea_state_dir(DEMO_EA, omar_dir);
+        let _ = std::fs::create_dir_all(&state_dir);
+        let _ = projects::add_project_in(&state_dir, "Order history");
+        for agent in CAST {
+            let session = fleet.session(agent.name);
+            let parent = agent
+                .parent
+                .map(|parent| fleet.session(parent))
+                .unwrap_or_else(|| fleet.manager.clone());
+            memory::save_worker_task_in(&state_dir, &session, agent.task);
+            memory::save_agent_parent_in(&state_dir, &session, &parent);
+        }
+        let now_ns = Utc::now().timestamp_nanos_opt().unwrap_or_default() as u64;
+        scheduler.insert(ScheduledEvent {
+            id: "demo-status-check".to_string(),
+            sender: "ea".to_string(),
+            receiver: "ea".to_string(),
+            timestamp: now_ns + 90_000_000_000,
+            payload: "Check on the workers and summarize progress".to_string(),
+            created_at: now_ns,
+            recurring_ns: Some(300_000_000_000),
+            ea_id: DEMO_EA,
+        });
+        fleet
+    }
+
+    fn session(&self, name: &str) -> String {
+        crate::naming::session_name(&self.prefix, name)
+    }
+
+    fn agent(&self, session: &str) -> Option<&'static DemoAgent> {
+        let name = crate::naming::try_short_name(&self.prefix, session)?;
+        CAST.iter().find(|agent| agent.name == name)
+    }
+
+    /// Every demo session at `now` (Unix seconds).
+    pub fn snapshot(&self, now: i64) -> Snapshot {
+        let mut snapshot = Snapshot::default();
+        snapshot
+            .sessions
+            .push(Session::new(self.manager.clone(), now, false, 0));
+        snapshot
+            .health
+            .insert(self.manager.clone(), HealthState::Idle);
+        let elapsed = now - self.started;
+        for agent in CAST {
+            let session = self.session(agent.name);
+            let history = transitions(agent.cycle, self.started, elapsed);
+            let (state, since) = history
+                .last()
+                .map(|t| (t.state, t.at))
+                .unwrap_or((HealthState::Running, self.started));
+            let activity = if state == HealthState::Running {
+                now
+            } else {
+                since
+            };
+            snapshot
+                .sessions
+                .push(Session::new(session.clone(), activity, false, 0));
+            let output = agent_output(agent, state, elapsed, 50);
+            match state {
+                HealthState::Finished | HealthState::Blocked => {
+                    if let Some(mut result) = detect_result(&output) {
+                        result.detected_at = chrono::DateTime::from_timestamp(since, 0)
+                            .map(|at| at.to_rfc3339())
+                            .unwrap_or_default();
+                        snapshot.results.insert(session.clone(), result);
+                    }
+                }
+                HealthState::RateLimited => {
+                    snapshot.rate_limits.insert(
+                        session.clone(),
+                        RateLimit {
+                            message: agent.marker.to_string(),
+                            cooldown: Some(std::time::Duration::from_secs(300)),
+                        },
+                    );
+                }
+                _ => {}
+            }
+            snapshot.health.insert(session.clone(), state);
+            snapshot.history.insert(session, history);
+        }
+        snapshot
+    }
+
+    /// Pane text for a demo session, like `capture-pane`.
+    pub fn output(&self, session: &str, lines: i32) -> Option<String> {
+        if session == self.manager {
+            return Some(tail(MANAGER_OUTPUT, lines));
+        }
+        let agent = self.agent(session)?;
+        let elapsed = Utc::now().timestamp() - self.started;
+        let state = transitions(agent.cycle, self.started, elapsed)
+            .last()
+            .map_or(HealthState::Running, |t| t.state);
+        Some(agent_output(agent, state, elapsed, lines))
+    }
+}
+
+/// Health transitions of a looping `cycle` over `elapsed` seconds, keeping
+/// the most recent `HEALTH_HISTORY_LEN`.
+fn transitions(cycle: &[(HealthState, i64)], started: i64, elapsed: i64) -> Vec<HealthTransition> {
+    let mut history: Vec<HealthTransition> = Vec::new();
+    let period: i64 = cycle.iter().map(|(_, secs)| secs).sum();
+    if period <= 0 {
+        return history;
+    }
+    // Only the last few periods can be kept anyway
+    let keep_periods = (HEALTH_HISTORY_LEN / cycle.len()).max(1) as i64 + 1;
+    let first_period = (elapsed / period - keep_periods).max(0);
+    let mut at = first_period * period;
+    'outer: loop {
+        for (state, secs) in cycle {
+            if at > elapsed {
+                break 'outer;
+            }
+            if history.last().map(|t| t.state) != Some(*state) {
+                history.push(HealthTransition {
+                    at: started + at,
+                    state: *state,
+                });
+            }
+            at += secs;
+        }
+    }
+    let excess = history.len().saturating_sub(HEALTH_HISTORY_LEN);
+    history.drain(..excess);
+    history
+}
+
+fn agent_output(agent: &DemoAgent, state: HealthState, elapsed: i64, lines: i32) -> String {
+    let shown = (elapsed / LINE_INTERVAL_SECS) as usize;
+    let mut text: Vec<&str> = (0..=shown)
+        .map(|i| agent.lines[i % agent.lines.len()])
+        .collect();
+    if matches!(
+        state,
+        HealthState::Finished | HealthState::Blocked | HealthState::RateLimited
+    ) && !agent.marker.is_empty()
+    {
+        text.push(agent.marker);
+    }
+    tail(&text.join("\n"), lines)
+}
+
+fn tail(text: &str, lines: i32) -> String {
+    let all: Vec<&str> = text.lines().collect();
+    let keep = lines.max(0) as usize;
+    all[all.len().saturating_sub(keep)..].join("\n")
+}
+
+/// Keys that act on real sessions, config, or tmux.
+pub fn blocks_key(code: KeyCode) -> bool {
+    matches!(
+        code,
+        KeyCode::Enter
+            | KeyCode::Char(
+                'n' | 'N'
+                    | 'd'
+                    | 'D'
+                    | 'T'
+                    | 'p'
+                    | 'm'
+                    | 'A'
+                    | 'f'
+                    | 'S'
+                    | 'Z'
+                    | 'F'
+                    | 'L'
+                    | 'H'
+                    | 'x'
+            )
+    )
+}
+
+/// Config for the demo: nothing that reaches outside the simulation.
+pub fn demo_config(mut config: Config) -> Config {
+    config.notifications = Default::default();
+    config.notifications.enabled = false;
+    config.schedules.clear();
+    config.hosts.clear();
+    config.scripting = Default::default();
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fleet_cycles_through_scripted_states() {
+        let temp = tempfile::tempdir().unwrap();
+        let scheduler = Scheduler::new();
+        let config = demo_config(Config::default());
+        let fleet = DemoFleet::new(&config, temp.path(), &scheduler);
+        let state_dir = ea::ea_state_dir(DEMO_EA, temp.path());
+        assert_eq!(memory::load_worker_tasks_from(&state_dir).len(), CAST.len());
+        assert_eq!(scheduler.list_by_ea(DEMO_EA).len(), 1);
+
+        let start = fleet.snapshot(fleet.started);
+        assert_eq!(start.sessions.len(), CAST.len() + 1);
+        let api = fleet.session("api");
+        assert_eq!(start.health[&api], HealthState::Running);
+
+        // api finishes after 80s and reports its completion marker
+        let later = fleet.snapshot(fleet.started + 85);
+        assert_eq!(later.health[&api], HealthState::Finished);
+        assert!(later.results.contains_key(&api));
+        assert!(later.history[&api].len() >= 3);
+        let migrations = fleet.session("migrations");
+        assert_eq!(later.health[&migrations], HealthState::RateLimited);
+        assert!(later.rate_limits.contains_key(&migrations));
+
+        // Hours in, history stays bounded
+        let hours = fleet.snapshot(fleet.started + 4 * 3600);
+        assert!(hours.history[&fleet.session("frontend-tests")].len() <= HEALTH_HISTORY_LEN);
+        assert!(fleet
+            .output(&fleet.manager, 50)
+            .unwrap()
+            .contains("\"type\": \"plan\""));
+        assert!(blocks_key(KeyCode::Char('d')) && !blocks_key(KeyCode::Char('j')));
+    }
+}
@@ -0,0 +1,127 @@
+//! Drain mode — orderly wind-down of the whole fleet
+//!
+//! While `~/.omar/drain.json` exists no new agents are spawned (MCP
+//! `spawn_agent`, the spawn queue, schedules, and dashboard spawns all
+//! refuse). The dashboard waits for running workers in every EA to finish,
+//! or for the optional deadline, then saves memory, stops all OMAR sessions,
+//! and exits.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Multiplexer;
+use crate::ea;
+use crate::spawn_queue;
+use crate::tmux;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DrainState {
+    pub started_at: DateTime<Utc>,
+    /// Stop anyway at this time, even with workers still running
+    pub deadline: Option<DateTime<Utc>>,
+}
+
+/// Why a drain is complete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrainOutcome {
+    WorkersFinished,
+    DeadlineReached,
+}
+
+impl DrainState {
+    pub fn outcome(&self, active_workers: usize, now: DateTime<Utc>) -> Option<DrainOutcome> {
+        if active_workers == 0 {
+            Some(DrainOutcome::WorkersFinished)
+        } else if self.deadline.is_some_and(|deadline| now >= deadline) {
+            Some(DrainOutcome::DeadlineReached)
+        } else {
+            None
+        }
+    }
+}
+
+fn drain_path(omar_dir: &Path) -> PathBuf {
+    omar_dir.join("drain.json")
+}
+
+pub fn load(omar_dir: &Path) -> Option<DrainState> {
+    fs::read_to_string(drain_path(omar_dir))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+}
+
+/// Start draining, or move the deadline of a drain already in progress.
+pub fn start(omar_dir: &Path, deadline_secs: Option<u64>) -> Result<DrainState> {
+    let now = Utc::now();
+    let state = DrainState {
+        started_at: load(omar_dir).map_or(now, |existing| existing.started_at),
+        deadline: deadline_secs.map(|secs| now + chrono::Duration::seconds(secs as i64)),
+    };
+    fs::create_dir_all(omar_dir)?;
+    let path = drain_path(omar_dir);
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(&state)?)?;
+    fs::rename(&tmp, &path)?;
+    Ok(state)
+}
+
+/// Stop draining. Returns false if no drain was in progress.
+pub fn cancel(omar_dir: &Path) -> Result<bool> {
+    match fs::remove_file(drain_path(omar_dir)) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Workers holding a pool slot across every registered EA.
+pub fn fleet_active_workers(omar_dir: &Path, base_prefix: &str, multiplexer: Multiplexer) -> usize {
+    ea::load_registry(omar_dir)
+        .iter()
+        .map(|info| {
+            spawn_queue::active_workers(
+                tmux::mux_for(multiplexer, ea::ea_prefix(info.id, base_prefix)).as_ref(),
+                &ea::ea_manager_session(info.id, base_prefix),
+                &ea::ea_state_dir(info.id, omar_dir),
+            )
+            .unwrap_or(0)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drain_lifecycle_and_outcome() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(load(dir.path()).is_none());
+        let open_ended = start(dir.path(), None).unwrap();
+        assert_eq!(load(dir.path()), Some(open_ended.clone()));
+
+        let now = Utc::now();
+        assert_eq!(open_ended.outcome(2, now + chrono::Duration::days(1)), None);
+        assert_eq!(
+            open_ended.outcome(0, now),
+            Some(DrainOutcome::WorkersFinished)
+        );
+
+        // Setting a deadline keeps the original start time
+        let bounded = start(dir.path(), Some(60)).unwrap();
+        assert_eq!(bounded.started_at, open_ended.started_at);
+        assert_eq!(bounded.outcome(2, now), None);
+        assert_eq!(
+            bounded.outcome(2, now + chrono::Duration::seconds(120)),
+            Some(DrainOutcome::DeadlineReached)
+        );
+
+        assert!(cancel(dir.path()).unwrap());
+        assert!(!cancel(dir.path()).unwrap());
+        assert!(load(dir.path()).is_none());
+    }
+}
@@ -59,6 +59,28 @@ pub fn ea_manager_session(ea_id: EaId, base_prefix: &str) -> String {
     format!("{}ea-{}", base_prefix, ea_id)
 }
 
+/// The tmux session grouping an EA's agents of one project (`grouped`
+/// layout). It sits outside the base prefix so it is never read as an agent.
+/// EA 0, project "Auth API": "omar-agent@0-auth-api"
+pub fn ea_group_session(ea_id: EaId, base_prefix: &str, group: &str) -> String {
+    let slug: String = group
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!(
+        "{}@{}-{}",
+        base_prefix.trim_end_matches('-'),
+        ea_id,
+        slug.trim_matches('-')
+    )
+}
+
 /// Directory for an EA's state files.
 /// EA 0: ~/.omar/ea/0/
 /// EA 1: ~/.omar/ea/1/
@@ -388,6 +410,13 @@ mod tests {
         assert_eq!(ea_manager_session(1, "omar-agent-"), "omar-agent-ea-1");
     }
 
+    #[test]
+    fn test_ea_group_session_stays_outside_the_agent_prefix() {
+        let group = ea_group_session(0, "omar-agent-", "Auth API");
+        assert_eq!(group, "omar-agent@0-auth-api");
+        assert!(!group.starts_with("omar-agent-"));
+    }
+
     #[test]
     fn test_ea_state_dir() {
         let base = PathBuf::from("/home/user/.omar");
@@ -14,12 +14,15 @@ pub enum AppEvent {
     /// Terminal was resized
     #[allow(dead_code)]
     Resize(u16, u16),
+    /// Text pasted into the terminal, delivered whole by bracketed paste
+    Paste(String),
 }
 
 fn app_event_from_crossterm(event: Event) -> Option<AppEvent> {
     match event {
         Event::Key(key) => Some(AppEvent::Key(key)),
         Event::Resize(w, h) => Some(AppEvent::Resize(w, h)),
+        Event::Paste(text) => Some(AppEvent::Paste(text)),
         _ => None,
     }
 }
@@ -256,4 +259,13 @@ mod tests {
         }
         assert!(pending.is_none());
     }
+
+    #[test]
+    fn bracketed_paste_arrives_as_one_event() {
+        let event = app_event_from_crossterm(Event::Paste("line one\nline two".to_string()));
+        match event {
+            Some(AppEvent::Paste(text)) => assert_eq!(text, "line one\nline two"),
+            _ => panic!("expected paste event"),
+        }
+    }
 }
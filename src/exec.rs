@@ -0,0 +1,293 @@
+//! Commands and shells in a worker's sandbox (`exec_agent`, `x` on the
+//! dashboard)
+//!
+//! Runs through the same `[sandbox]` wrapper the worker was launched with,
+//! in its recorded workdir, so the command sees the filesystem the worker
+//! sees — its workdir writable, the rest of the host read-only — without
+//! attaching to the agent's pane. Under the kubernetes runtime the command
+//! runs in the worker's own pod through `kubectl exec`. Workers launched
+//! unsandboxed run the command directly in their workdir.
+
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+
+use crate::config::{LimitsConfig, SandboxConfig};
+use crate::memory::{self, AgentLaunch};
+use crate::sandbox;
+use crate::tmux::tmux_command;
+
+/// Output beyond this many bytes per stream is cut from the front.
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// Commands run from the dashboard popup block the UI, so they get less
+/// time than `exec_agent` calls.
+pub const DASHBOARD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long output is still read once the command has exited. Anything it
+/// left running in the background may hold the pipes open indefinitely.
+const PIPE_GRACE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExecOutput {
+    /// Whether the command ran inside the sandbox runtime
+    pub sandboxed: bool,
+    /// `None` when the command was killed (timeout or signal)
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Dashboard popup for running commands in one worker's sandbox.
+#[derive(Debug, Clone, Default)]
+pub struct ExecPopup {
+    /// Full session name of the worker
+    pub target: String,
+    /// Command being typed
+    pub input: String,
+    /// Last command run and its result
+    pub last: Option<(String, ExecOutput)>,
+}
+
+/// The recorded launch of `session`. Only workers spawned by omar have one.
+pub fn launch_for(state_dir: &Path, session: &str) -> Result<AgentLaunch> {
+    memory::load_agent_launches_from(state_dir)
+        .remove(session)
+        .ok_or_else(|| {
+            anyhow!(
+                "No recorded launch for {}; cannot find its sandbox",
+                session
+            )
+        })
+}
+
+/// `command` wrapped for the worker's sandbox, and whether it is sandboxed.
+/// `tty` is for interactive shells.
+fn wrap(
+    sandbox_config: &SandboxConfig,
+    launch: &AgentLaunch,
+    command: &str,
+    tty: bool,
+) -> Result<(String, bool)> {
+    if !launch.sandboxed || sandbox::provider_for(sandbox_config)?.is_none() {
+        return Ok((command.to_string(), false));
+    }
+    if sandbox_config.runtime.trim() == "kubernetes" {
+        // Wrapping would schedule a fresh pod instead of the worker's own
+        let pod = launch.pod.as_deref().ok_or_else(|| {
+            anyhow!("No recorded pod for this worker; respawn it to exec into its sandbox")
+        })?;
+        let provider = sandbox::KubernetesProvider::new(sandbox_config.kubernetes.clone())?;
+        return Ok((
+            provider.exec_command(pod, command, &launch.workdir, tty),
+            true,
+        ));
+    }
+    let wrapped = sandbox::wrap_agent_command(
+        sandbox_config,
+        &LimitsConfig::default(),
+        command,
+        &launch.workdir,
+    )?;
+    Ok((wrapped, true))
+}
+
+/// Run `command` in the worker's sandbox, killing it and everything it
+/// started after `timeout`.
+pub fn run(
+    sandbox_config: &SandboxConfig,
+    launch: &AgentLaunch,
+    command: &str,
+    timeout: Duration,
+) -> Result<ExecOutput> {
+    let (wrapped, sandboxed) = wrap(sandbox_config, launch, command, false)?;
+    let mut shell = Command::new("sh");
+    shell
+        .args(["-c", &wrapped])
+        .current_dir(&launch.workdir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Its own process group, so a timeout reaches the sandbox runtime and
+    // the command under it rather than only `sh`
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut shell, 0);
+    let mut child = shell
+        .spawn()
+        .with_context(|| format!("Failed to run command in {}", launch.workdir))?;
+    let stdout = Capture::start(child.stdout.take());
+    let stderr = Capture::start(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let (status, timed_out) = loop {
+        if let Some(status) = child.try_wait()? {
+            break (Some(status), false);
+        }
+        if Instant::now() >= deadline {
+            #[cfg(unix)]
+            // SAFETY: killpg only sends a signal; the group is the child's own
+            unsafe {
+                libc::killpg(child.id() as libc::pid_t, libc::SIGKILL);
+            }
+            let _ = child.kill();
+            let _ = child.wait();
+            break (None, true);
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+    let until = Instant::now() + PIPE_GRACE;
+    Ok(ExecOutput {
+        sandboxed,
+        exit_code: status.and_then(|s| s.code()),
+        timed_out,
+        stdout: stdout.collect(until),
+        stderr: stderr.collect(until),
+    })
+}
+
+/// One output stream, read on its own thread.
+struct Capture {
+    buf: Arc<Mutex<Vec<u8>>>,
+    done: mpsc::Receiver<()>,
+}
+
+impl Capture {
+    fn start(stream: Option<impl Read + Send + 'static>) -> Self {
+        let buf = Arc::new(Mutex::new(Vec::new()));
+        let (tx, done) = mpsc::channel();
+        let sink = buf.clone();
+        thread::spawn(move || {
+            if let Some(mut stream) = stream {
+                let mut chunk = [0u8; 8192];
+                while let Ok(n @ 1..) = stream.read(&mut chunk) {
+                    sink.lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .extend_from_slice(&chunk[..n]);
+                }
+            }
+            let _ = tx.send(());
+        });
+        Self { buf, done }
+    }
+
+    /// What was read by EOF or `until`, whichever comes first.
+    fn collect(self, until: Instant) -> String {
+        let _ = self
+            .done
+            .recv_timeout(until.saturating_duration_since(Instant::now()));
+        let buf = self.buf.lock().unwrap_or_else(|e| e.into_inner());
+        tail_bytes(&String::from_utf8_lossy(&buf), MAX_OUTPUT_BYTES)
+    }
+}
+
+/// The last `max` bytes of `text`, cut at a character boundary.
+fn tail_bytes(text: &str, max: usize) -> String {
+    if text.len() <= max {
+        return text.to_string();
+    }
+    let mut start = text.len() - max;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    text[start..].to_string()
+}
+
+/// Open an interactive shell in the worker's sandbox as a tmux popup over
+/// the dashboard. Returns once the popup is open; it closes when the shell
+/// exits.
+pub fn open_shell_popup(sandbox_config: &SandboxConfig, launch: &AgentLaunch) -> Result<()> {
+    if std::env::var_os("TMUX").is_none() {
+        return Err(anyhow!(
+            "A sandbox shell needs the dashboard to run inside tmux"
+        ));
+    }
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "sh".to_string());
+    let (wrapped, _) = wrap(sandbox_config, launch, &shell, true)?;
+    let mut popup = tmux_command();
+    popup.args([
+        "display-popup",
+        "-E",
+        "-w",
+        "90%",
+        "-h",
+        "90%",
+        "-d",
+        &launch.workdir,
+        &wrapped,
+    ]);
+    let mut child = popup.spawn().context("Failed to open tmux popup")?;
+    thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_in_unsandboxed_workdir_with_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "written by worker\n").unwrap();
+        let launch = AgentLaunch {
+            command: "claude".to_string(),
+            workdir: dir.path().display().to_string(),
+            sandboxed: false,
+            security: Vec::new(),
+            pod: None,
+        };
+        let sandbox_config = SandboxConfig::default();
+
+        let output = run(
+            &sandbox_config,
+            &launch,
+            "cat notes.txt; echo oops >&2; exit 3",
+            Duration::from_secs(10),
+        )
+        .unwrap();
+        assert!(!output.sandboxed && !output.timed_out);
+        assert_eq!(output.exit_code, Some(3));
+        assert_eq!(output.stdout, "written by worker\n");
+        assert_eq!(output.stderr, "oops\n");
+
+        let output = run(
+            &sandbox_config,
+            &launch,
+            "sleep 5",
+            Duration::from_millis(100),
+        )
+        .unwrap();
+        assert!(output.timed_out);
+        assert_eq!(output.exit_code, None);
+
+        // Background children neither outlive a timeout nor hold the
+        // output pipes open past the command's exit
+        let started = Instant::now();
+        let output = run(
+            &sandbox_config,
+            &launch,
+            "sleep 30 & wait",
+            Duration::from_millis(100),
+        )
+        .unwrap();
+        assert!(output.timed_out);
+        let output = run(
+            &sandbox_config,
+            &launch,
+            "(sleep 30 &); echo done",
+            Duration::from_secs(10),
+        )
+        .unwrap();
+        assert_eq!(output.stdout, "done\n");
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        assert_eq!(tail_bytes("héllo", 4), "llo");
+        assert!(launch_for(dir.path(), "omar-agent-0-api").is_err());
+    }
+}
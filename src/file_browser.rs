@@ -0,0 +1,346 @@
+//! Read-only browser and file viewer over an agent's working directory
+//! (dashboard `f` popup, `list_agent_files` and `read_agent_file` MCP tools)
+
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use ratatui::text::Line;
+
+/// Largest prefix of a file the viewer loads.
+pub const MAX_VIEW_BYTES: u64 = 256 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry {
+    pub name: String,
+    pub is_dir: bool,
+    /// Size in bytes (0 for directories).
+    pub size: u64,
+}
+
+/// Resolve `rel` inside `root`, refusing paths that escape it (via `..`,
+/// absolute paths, or symlinks).
+pub fn resolve_in_root(root: &Path, rel: &str) -> Result<PathBuf> {
+    let rel = Path::new(rel.trim());
+    if rel.components().any(|c| {
+        matches!(
+            c,
+            Component::ParentDir | Component::RootDir | Component::Prefix(_)
+        )
+    }) {
+        return Err(anyhow!(
+            "Path '{}' escapes the agent workdir",
+            rel.display()
+        ));
+    }
+    let root = root.canonicalize()?;
+    let path = root.join(rel).canonicalize()?;
+    if !path.starts_with(&root) {
+        return Err(anyhow!(
+            "Path '{}' escapes the agent workdir",
+            rel.display()
+        ));
+    }
+    Ok(path)
+}
+
+/// Entries of `rel` under `root`: directories first, then files, by name.
+pub fn list_dir(root: &Path, rel: &str) -> Result<Vec<FileEntry>> {
+    let dir = resolve_in_root(root, rel)?;
+    let mut entries: Vec<FileEntry> = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let meta = fs::metadata(entry.path()).ok();
+            let is_dir = meta.as_ref().is_some_and(|m| m.is_dir());
+            FileEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                is_dir,
+                size: meta.filter(|_| !is_dir).map_or(0, |m| m.len()),
+            }
+        })
+        .collect();
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    Ok(entries)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileContent {
+    pub text: String,
+    /// Full file size in bytes.
+    pub size: u64,
+    /// True when only the first `max_bytes` were read.
+    pub truncated: bool,
+}
+
+/// Read up to `max_bytes` of a text file under `root`. Binary files (NUL
+/// bytes near the start) are refused.
+pub fn read_file(root: &Path, rel: &str, max_bytes: u64) -> Result<FileContent> {
+    let path = resolve_in_root(root, rel)?;
+    let size = fs::metadata(&path)?.len();
+    if path.is_dir() {
+        return Err(anyhow!("'{}' is a directory", rel));
+    }
+    let mut bytes = Vec::new();
+    fs::File::open(&path)?
+        .take(max_bytes)
+        .read_to_end(&mut bytes)?;
+    if bytes.iter().take(8192).any(|b| *b == 0) {
+        return Err(anyhow!("'{}' looks like a binary file", rel));
+    }
+    Ok(FileContent {
+        text: String::from_utf8_lossy(&bytes).to_string(),
+        size,
+        truncated: size > max_bytes,
+    })
+}
+
+/// Read-only viewer opened from the browser.
+#[derive(Debug, Clone)]
+pub struct FileViewer {
+    /// Path relative to the agent workdir.
+    pub path: String,
+    pub size: u64,
+    pub truncated: bool,
+    /// Highlighted lines.
+    pub lines: Vec<Line<'static>>,
+    pub scroll: usize,
+}
+
+impl FileViewer {
+    pub fn scroll_by(&mut self, delta: isize, page: usize) {
+        let max = self.lines.len().saturating_sub(page);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max);
+    }
+}
+
+/// Dashboard popup state.
+#[derive(Debug, Clone)]
+pub struct FileBrowser {
+    /// Short agent name shown in the title.
+    pub agent: String,
+    pub root: PathBuf,
+    /// Directory being shown, relative to `root` ("" = root).
+    pub rel: PathBuf,
+    pub entries: Vec<FileEntry>,
+    pub selected: usize,
+    pub error: Option<String>,
+    /// Open file, shown over the listing.
+    pub viewer: Option<FileViewer>,
+}
+
+impl FileBrowser {
+    pub fn new(agent: String, root: PathBuf) -> Self {
+        let mut browser = Self {
+            agent,
+            root,
+            rel: PathBuf::new(),
+            entries: Vec::new(),
+            selected: 0,
+            error: None,
+            viewer: None,
+        };
+        browser.reload();
+        browser
+    }
+
+    pub fn reload(&mut self) {
+        match list_dir(&self.root, &self.rel.to_string_lossy()) {
+            Ok(entries) => {
+                self.entries = entries;
+                self.error = None;
+            }
+            Err(e) => {
+                self.entries.clear();
+                self.error = Some(e.to_string());
+            }
+        }
+        self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+    }
+
+    pub fn move_selection(&mut self, down: bool) {
+        if down {
+            if self.selected + 1 < self.entries.len() {
+                self.selected += 1;
+            }
+        } else {
+            self.selected = self.selected.saturating_sub(1);
+        }
+    }
+
+    pub fn selected_entry(&self) -> Option<&FileEntry> {
+        self.entries.get(self.selected)
+    }
+
+    /// Open the selected entry: descend into a directory or view a file.
+    pub fn open_selected(&mut self) {
+        if self.enter() {
+            return;
+        }
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        let rel = self.rel.join(&entry.name).to_string_lossy().to_string();
+        match read_file(&self.root, &rel, MAX_VIEW_BYTES) {
+            Ok(content) => {
+                self.viewer = Some(FileViewer {
+                    lines: crate::ui::highlight::highlight(&rel, &content.text),
+                    path: rel,
+                    size: content.size,
+                    truncated: content.truncated,
+                    scroll: 0,
+                });
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    /// Descend into the selected directory. Returns false for files.
+    pub fn enter(&mut self) -> bool {
+        let Some(name) = self
+            .selected_entry()
+            .filter(|e| e.is_dir)
+            .map(|e| e.name.clone())
+        else {
+            return false;
+        };
+        self.rel.push(name);
+        self.selected = 0;
+        self.reload();
+        true
+    }
+
+    /// Go to the parent directory, keeping the directory we left selected.
+    pub fn up(&mut self) {
+        let Some(left) = self
+            .rel
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+        else {
+            return;
+        };
+        self.rel.pop();
+        self.reload();
+        self.selected = self
+            .entries
+            .iter()
+            .position(|e| e.name == left)
+            .unwrap_or(0);
+    }
+
+    /// Title path, e.g. `~/repo/src`.
+    pub fn display_path(&self) -> String {
+        let path = self.root.join(&self.rel);
+        match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok().map(Path::to_path_buf))
+        {
+            Some(rest) => format!("~/{}", rest.display()),
+            None => path.display().to_string(),
+        }
+    }
+}
+
+/// Human-readable size, e.g. `1.2K`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["K", "M", "G", "T"];
+    if bytes < 1024 {
+        return format!("{}B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1}{}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src/bin")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(dir.path().join("README.md"), "hi").unwrap();
+        fs::create_dir(dir.path().join("docs")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_list_dir_sorts_dirs_first_and_refuses_escapes() {
+        let dir = tree();
+        let names: Vec<String> = list_dir(dir.path(), "")
+            .unwrap()
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        assert_eq!(names, vec!["docs", "src", "README.md"]);
+        let src = list_dir(dir.path(), "src").unwrap();
+        assert_eq!(src[1].size, 13);
+
+        assert!(list_dir(dir.path(), "../").is_err());
+        assert!(list_dir(dir.path(), "/etc").is_err());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink("/", dir.path().join("escape")).unwrap();
+            assert!(list_dir(dir.path(), "escape").is_err());
+        }
+    }
+
+    #[test]
+    fn test_browser_navigation() {
+        let dir = tree();
+        let mut browser = FileBrowser::new("api".to_string(), dir.path().to_path_buf());
+        browser.move_selection(true);
+        assert!(browser.enter());
+        assert_eq!(browser.rel, PathBuf::from("src"));
+        assert_eq!(browser.selected_entry().unwrap().name, "bin");
+        browser.move_selection(true);
+        assert!(!browser.enter(), "files are not entered");
+        browser.up();
+        assert_eq!(browser.rel, PathBuf::new());
+        assert_eq!(browser.selected_entry().unwrap().name, "src");
+        browser.up();
+        assert_eq!(browser.rel, PathBuf::new());
+    }
+
+    #[test]
+    fn test_read_file_caps_size_and_refuses_binary() {
+        let dir = tree();
+        let full = read_file(dir.path(), "src/main.rs", MAX_VIEW_BYTES).unwrap();
+        assert_eq!(full.text, "fn main() {}\n");
+        assert!(!full.truncated);
+
+        let capped = read_file(dir.path(), "src/main.rs", 4).unwrap();
+        assert_eq!(
+            (capped.text.as_str(), capped.size, capped.truncated),
+            ("fn m", 13, true)
+        );
+
+        fs::write(dir.path().join("blob.bin"), [0u8, 1, 2]).unwrap();
+        assert!(read_file(dir.path(), "blob.bin", 100).is_err());
+        assert!(read_file(dir.path(), "src", 100).is_err());
+        assert!(read_file(dir.path(), "../etc/passwd", 100).is_err());
+    }
+
+    #[test]
+    fn test_open_selected_views_files() {
+        let dir = tree();
+        let mut browser = FileBrowser::new("api".to_string(), dir.path().to_path_buf());
+        browser.selected = 2;
+        browser.open_selected();
+        let viewer = browser.viewer.as_mut().unwrap();
+        assert_eq!(viewer.path, "README.md");
+        assert_eq!(viewer.lines.len(), 1);
+        viewer.scroll_by(5, 1);
+        assert_eq!(viewer.scroll, 0);
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512B");
+        assert_eq!(format_size(1536), "1.5K");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0M");
+    }
+}
@@ -0,0 +1,165 @@
+//! Firehose — interleaved tail of new output lines across agent sessions
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::tmux::Mux;
+
+/// Lines captured per pane on each poll.
+pub const FIREHOSE_CAPTURE_LINES: i32 = 200;
+
+/// Lines retained in the interleaved buffer.
+pub const FIREHOSE_CAPACITY: usize = 2000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+    /// Monotonic sequence number, usable as a stream cursor.
+    pub seq: u64,
+    /// Full tmux session name.
+    pub session: String,
+    pub text: String,
+}
+
+/// Diffs successive pane captures and keeps the appended lines in arrival
+/// order, like `docker compose logs -f`.
+pub struct Firehose {
+    last: HashMap<String, Vec<String>>,
+    lines: VecDeque<LogLine>,
+    next_seq: u64,
+    capacity: usize,
+}
+
+impl Default for Firehose {
+    fn default() -> Self {
+        Self::new(FIREHOSE_CAPACITY)
+    }
+}
+
+impl Firehose {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            last: HashMap::new(),
+            lines: VecDeque::new(),
+            next_seq: 0,
+            capacity,
+        }
+    }
+
+    /// Capture every session and append its new lines. Sessions that are no
+    /// longer listed are forgotten.
+    pub fn poll(&mut self, client: &dyn Mux, sessions: &[String]) {
+        self.last.retain(|name, _| sessions.contains(name));
+        for session in sessions {
+            if let Ok(output) = client.capture_pane_plain(session, FIREHOSE_CAPTURE_LINES) {
+                self.ingest(session, &output);
+            }
+        }
+    }
+
+    /// Record a capture of `session`, appending lines not seen last time.
+    pub fn ingest(&mut self, session: &str, output: &str) {
+        let current = pane_lines(output);
+        let prev = self.last.get(session).map(Vec::as_slice).unwrap_or(&[]);
+        let fresh: Vec<String> = new_lines(prev, &current).into_iter().cloned().collect();
+        for text in fresh {
+            self.lines.push_back(LogLine {
+                seq: self.next_seq,
+                session: session.to_string(),
+                text,
+            });
+            self.next_seq += 1;
+        }
+        while self.lines.len() > self.capacity {
+            self.lines.pop_front();
+        }
+        self.last.insert(session.to_string(), current);
+    }
+
+    pub fn lines(&self) -> &VecDeque<LogLine> {
+        &self.lines
+    }
+
+    /// Lines with `seq >= cursor`.
+    pub fn since(&self, cursor: u64) -> impl Iterator<Item = &LogLine> {
+        self.lines.iter().filter(move |line| line.seq >= cursor)
+    }
+
+    /// Cursor for the next unseen line.
+    pub fn next_seq(&self) -> u64 {
+        self.next_seq
+    }
+}
+
+/// Trimmed pane lines with trailing blank rows dropped.
+fn pane_lines(output: &str) -> Vec<String> {
+    let mut lines: Vec<String> = output
+        .lines()
+        .map(|line| line.trim_end().to_string())
+        .collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Lines of `current` appended since `prev`.
+///
+/// Scrolled output is found by overlapping the tail of `prev` with the head
+/// of `current`. Full-screen redraws (spinners, input boxes) break the
+/// overlap; then only lines absent from `prev` are reported.
+fn new_lines<'a>(prev: &[String], current: &'a [String]) -> Vec<&'a String> {
+    for shift in 0..prev.len() {
+        let overlap = &prev[shift..];
+        if overlap.len() <= current.len() && current[..overlap.len()] == *overlap {
+            return current[overlap.len()..].iter().collect();
+        }
+    }
+    let seen: HashSet<&String> = prev.iter().collect();
+    current
+        .iter()
+        .filter(|line| !line.is_empty() && !seen.contains(line))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_new_lines_follows_scrolling_output() {
+        let prev = lines("a\nb\nc");
+        assert_eq!(new_lines(&prev, &lines("a\nb\nc\nd")), vec!["d"]);
+        assert_eq!(new_lines(&prev, &lines("b\nc\nd\ne")), vec!["d", "e"]);
+        assert!(new_lines(&prev, &lines("a\nb\nc")).is_empty());
+    }
+
+    #[test]
+    fn test_new_lines_reports_unseen_lines_after_redraw() {
+        let prev = lines("header\n✻ Thinking (3s)\n> ");
+        let current = lines("header\n✻ Thinking (4s)\n> ");
+        assert_eq!(new_lines(&prev, &current), vec!["✻ Thinking (4s)"]);
+    }
+
+    #[test]
+    fn test_ingest_interleaves_sessions_and_caps_buffer() {
+        let mut hose = Firehose::new(3);
+        hose.ingest("api", "one\n\n\n");
+        hose.ingest("web", "alpha\n");
+        hose.ingest("api", "one\ntwo\nthree\n");
+
+        let got: Vec<(&str, &str)> = hose
+            .lines()
+            .iter()
+            .map(|l| (l.session.as_str(), l.text.as_str()))
+            .collect();
+        assert_eq!(
+            got,
+            vec![("web", "alpha"), ("api", "two"), ("api", "three")]
+        );
+        assert_eq!(hose.next_seq(), 4);
+        assert_eq!(hose.since(3).count(), 1);
+    }
+}
@@ -0,0 +1,322 @@
+//! GitHub issue intake (`[github]`)
+//!
+//! Open issues in `github.repo` carrying `github.label` are imported as
+//! projects named `#<number> <title>`, with the issue body's task list as
+//! the project checklist. The EA is told about each import and spawns
+//! workers against the project as usual; `complete_project` posts its
+//! summary back as an issue comment. Links live in
+//! `<state_dir>/github_issues.json` and outlive the project, so a completed
+//! issue that is still open is not imported again.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::config::GithubConfig;
+use crate::projects;
+use crate::secrets;
+
+const API: &str = "https://api.github.com";
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    #[serde(default)]
+    pub body: Option<String>,
+    pub html_url: String,
+    /// Set on pull requests, which the issues API also lists
+    #[serde(default)]
+    pub pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IssueLink {
+    pub project_id: usize,
+    pub url: String,
+    /// The project was completed; its id may since have been reused
+    #[serde(default)]
+    pub completed: bool,
+}
+
+/// An issue imported by a sync.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Imported {
+    pub number: u64,
+    pub project_id: usize,
+    pub title: String,
+    pub url: String,
+}
+
+pub struct GithubClient {
+    repo: String,
+    token: String,
+    label: String,
+    http: reqwest::blocking::Client,
+}
+
+impl GithubClient {
+    /// `None` when `github.repo` is unset.
+    pub fn from_config(config: &GithubConfig) -> Result<Option<Self>> {
+        let Some(repo) = config
+            .repo
+            .as_deref()
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+        else {
+            return Ok(None);
+        };
+        if repo.split('/').count() != 2 {
+            return Err(anyhow!(
+                "github.repo must be \"owner/name\", got '{}'",
+                repo
+            ));
+        }
+        Ok(Some(Self {
+            repo: repo.to_string(),
+            token: secrets::read_source("github.token", &config.token)?,
+            label: config.label.clone(),
+            http: reqwest::blocking::Client::builder()
+                .timeout(TIMEOUT)
+                .user_agent("omar")
+                .build()?,
+        }))
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::blocking::RequestBuilder {
+        self.http
+            .request(method, format!("{}/repos/{}{}", API, self.repo, path))
+            .bearer_auth(&self.token)
+            .header("Accept", "application/vnd.github+json")
+    }
+
+    /// Open issues (not pull requests) carrying the configured label.
+    pub fn labeled_issues(&self) -> Result<Vec<Issue>> {
+        let response = self
+            .request(reqwest::Method::GET, "/issues")
+            .query(&[
+                ("labels", self.label.as_str()),
+                ("state", "open"),
+                ("per_page", "100"),
+            ])
+            .send()
+            .context("GitHub request failed")?;
+        if !response.status().is_success() {
+            return Err(anyhow!("GitHub returned HTTP {}", response.status()));
+        }
+        let issues: Vec<Issue> = response.json()?;
+        Ok(issues
+            .into_iter()
+            .filter(|issue| issue.pull_request.is_none())
+            .collect())
+    }
+
+    pub fn comment(&self, number: u64, body: &str) -> Result<()> {
+        let response = self
+            .request(
+                reqwest::Method::POST,
+                &format!("/issues/{}/comments", number),
+            )
+            .json(&json!({ "body": body }))
+            .send()
+            .context("GitHub request failed")?;
+        if !response.status().is_success() {
+            return Err(anyhow!("GitHub returned HTTP {}", response.status()));
+        }
+        Ok(())
+    }
+}
+
+/// Fetch the labeled issues and import the new ones. `Ok(None)` when
+/// `github.repo` is unset.
+pub fn sync_in(state_dir: &Path, config: &GithubConfig) -> Result<Option<Vec<Imported>>> {
+    let Some(client) = GithubClient::from_config(config)? else {
+        return Ok(None);
+    };
+    let issues = client.labeled_issues()?;
+    crate::mcp::with_state_lock(state_dir, |state_dir| import_in(state_dir, &issues)).map(Some)
+}
+
+/// Run `f` on a thread of its own and wait for it. `reqwest::blocking`
+/// panics when its client is built or dropped inside an async runtime,
+/// and the MCP server answers tool calls from inside one.
+pub fn off_runtime<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    std::thread::spawn(f)
+        .join()
+        .map_err(|_| anyhow!("GitHub request thread panicked"))?
+}
+
+/// What the EA is told about an imported issue.
+pub fn import_event(imported: &Imported) -> String {
+    format!(
+        "[GITHUB ISSUE] #{} {} was imported as project {} ({}). Spawn workers against project_id {}; \
+         pass a summary to complete_project to post it on the issue.",
+        imported.number, imported.title, imported.project_id, imported.url, imported.project_id
+    )
+}
+
+fn links_path(state_dir: &Path) -> PathBuf {
+    state_dir.join("github_issues.json")
+}
+
+/// Issue number -> the project it was imported as.
+pub fn load_links_from(state_dir: &Path) -> BTreeMap<u64, IssueLink> {
+    std::fs::read_to_string(links_path(state_dir))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save_links(state_dir: &Path, links: &BTreeMap<u64, IssueLink>) -> Result<()> {
+    let path = links_path(state_dir);
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(links)?)?;
+    std::fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// Import the issues not already linked to a project. Callers hold the MCP
+/// state lock, since this adds projects.
+pub fn import_in(state_dir: &Path, issues: &[Issue]) -> Result<Vec<Imported>> {
+    let mut links = load_links_from(state_dir);
+    let mut imported = Vec::new();
+    for issue in issues {
+        if links.contains_key(&issue.number) {
+            continue;
+        }
+        let name = format!("#{} {}", issue.number, issue.title.trim());
+        let project_id = projects::add_project_in(state_dir, &name)?;
+        for (number, item) in issue
+            .body
+            .as_deref()
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| projects::parse_item(line.trim()))
+            .enumerate()
+        {
+            projects::add_item_in(state_dir, project_id, &item.text)?;
+            if item.done {
+                projects::set_item_in(state_dir, project_id, number + 1, Some(true))?;
+            }
+        }
+        links.insert(
+            issue.number,
+            IssueLink {
+                project_id,
+                url: issue.html_url.clone(),
+                completed: false,
+            },
+        );
+        save_links(state_dir, &links)?;
+        imported.push(Imported {
+            number: issue.number,
+            project_id,
+            title: issue.title.trim().to_string(),
+            url: issue.html_url.clone(),
+        });
+    }
+    Ok(imported)
+}
+
+/// The open issue `project_id` was imported from, as (number, url).
+pub fn issue_for_project_in(state_dir: &Path, project_id: usize) -> Option<(u64, String)> {
+    load_links_from(state_dir)
+        .into_iter()
+        .find(|(_, link)| link.project_id == project_id && !link.completed)
+        .map(|(number, link)| (number, link.url))
+}
+
+/// Record that issue `number`'s project was completed. Callers hold the MCP
+/// state lock.
+pub fn mark_completed_in(state_dir: &Path, number: u64) -> Result<()> {
+    let mut links = load_links_from(state_dir);
+    if let Some(link) = links.get_mut(&number) {
+        link.completed = true;
+        save_links(state_dir, &links)?;
+    }
+    Ok(())
+}
+
+/// Text of the comment posted when a linked project completes.
+pub fn completion_comment(project: &projects::Project, summary: Option<&str>) -> String {
+    let mut body = String::from("Completed by omar.");
+    if let Some(summary) = summary.map(str::trim).filter(|s| !s.is_empty()) {
+        body.push_str("\n\n");
+        body.push_str(summary);
+    }
+    if let Some((done, total)) = project.progress() {
+        body.push_str(&format!("\n\nChecklist: {}/{} done.", done, total));
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(number: u64, title: &str, body: &str) -> Issue {
+        Issue {
+            number,
+            title: title.to_string(),
+            body: Some(body.to_string()),
+            html_url: format!("https://github.com/o/r/issues/{}", number),
+            pull_request: None,
+        }
+    }
+
+    #[test]
+    fn test_import_links_issues_once_with_checklists() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = dir.path();
+        let issues = vec![
+            issue(
+                12,
+                "Add OAuth login",
+                "Steps:\n- [x] Pick provider\n- [ ] Callback route\n",
+            ),
+            issue(15, " Fix flaky CI ", ""),
+        ];
+
+        let imported = import_in(state, &issues).unwrap();
+        assert_eq!(
+            imported
+                .iter()
+                .map(|i| (i.number, i.project_id))
+                .collect::<Vec<_>>(),
+            vec![(12, 1), (15, 2)]
+        );
+        let loaded = projects::load_projects_from(state);
+        assert_eq!(loaded[0].name, "#12 Add OAuth login");
+        assert_eq!(loaded[0].progress(), Some((1, 2)));
+        assert_eq!(loaded[1].name, "#15 Fix flaky CI");
+
+        assert_eq!(
+            issue_for_project_in(state, 1),
+            Some((12, "https://github.com/o/r/issues/12".to_string()))
+        );
+        // Still open after completing: not imported again, and a project
+        // that reuses the id is not linked to it
+        projects::remove_project_in(state, 2).unwrap();
+        mark_completed_in(state, 15).unwrap();
+        assert!(import_in(state, &issues).unwrap().is_empty());
+        assert_eq!(projects::add_project_in(state, "Unrelated").unwrap(), 2);
+        assert_eq!(issue_for_project_in(state, 2), None);
+
+        assert_eq!(
+            completion_comment(&loaded[0], Some("Shipped in #40")),
+            "Completed by omar.\n\nShipped in #40\n\nChecklist: 1/2 done."
+        );
+        assert!(GithubClient::from_config(&GithubConfig::default())
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,101 @@
+//! Health history — recent state transitions per agent, so flapping
+//! between working and stuck is visible (agent card sparklines, the
+//! `get_agent_history` MCP tool)
+//!
+//! The dashboard's poller records transitions and mirrors them into
+//! `~/.omar/health_history.json`, keyed by session name, for the MCP server.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::tmux::{HealthState, HealthTransition};
+
+/// Span of the agent card sparkline.
+pub const TIMELINE_WINDOW_SECS: i64 = 600;
+
+pub type History = HashMap<String, Vec<HealthTransition>>;
+
+fn history_path(omar_dir: &Path) -> PathBuf {
+    omar_dir.join("health_history.json")
+}
+
+pub fn load(omar_dir: &Path) -> History {
+    fs::read_to_string(history_path(omar_dir))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(omar_dir: &Path, history: &History) -> Result<()> {
+    fs::create_dir_all(omar_dir)?;
+    let path = history_path(omar_dir);
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string(history)?)?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// State in each of `cells` equal slices of the `window_secs` before
+/// `now`, oldest first; `None` before the first recorded transition.
+pub fn timeline(
+    history: &[HealthTransition],
+    now: i64,
+    window_secs: i64,
+    cells: usize,
+) -> Vec<Option<HealthState>> {
+    let cells_i = cells.max(1) as i64;
+    (0..cells_i)
+        .map(|cell| {
+            // End of this slice
+            let at = now - window_secs + window_secs * (cell + 1) / cells_i;
+            history
+                .iter()
+                .take_while(|t| t.at <= at)
+                .last()
+                .map(|t| t.state)
+        })
+        .take(cells)
+        .collect()
+}
+
+/// Transitions at or after `since`.
+pub fn changes_since(history: &[HealthTransition], since: i64) -> usize {
+    history.iter().filter(|t| t.at >= since).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(at: i64, state: HealthState) -> HealthTransition {
+        HealthTransition { at, state }
+    }
+
+    #[test]
+    fn test_timeline_and_round_trip() {
+        let history = vec![
+            at(50, HealthState::Running),
+            at(70, HealthState::Idle),
+            at(90, HealthState::Running),
+        ];
+        assert_eq!(
+            timeline(&history, 100, 100, 4),
+            vec![
+                None,
+                Some(HealthState::Running),
+                Some(HealthState::Idle),
+                Some(HealthState::Running),
+            ]
+        );
+        assert_eq!(changes_since(&history, 60), 2);
+
+        let temp = tempfile::tempdir().unwrap();
+        assert!(load(temp.path()).is_empty());
+        let saved: History = [("omar-agent-api".to_string(), history)].into();
+        save(temp.path(), &saved).unwrap();
+        assert_eq!(load(temp.path()), saved);
+    }
+}
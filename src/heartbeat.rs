@@ -0,0 +1,210 @@
+//! Agent heartbeats — machine-oriented health for external supervisors
+//! (`omar health`, the `get_agent_heartbeat` MCP tool)
+//!
+//! Field meanings are stable so monitors can key on them:
+//! - `state`: `running`, `idle`, `finished`, `blocked`, `rate_limited`, or
+//!   `missing` when the session does not exist
+//! - `last_change`: last pane output (RFC 3339), `idle_secs` seconds since
+//! - `session_alive`: the tmux session exists; `pane_alive`: its process
+//!   is still running; `producing_output`: output within `idle_warning`
+//! - `stuck`: alive but blocked, or silent for `idle_critical` or longer
+//!   without having finished
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::config::HealthConfig;
+use crate::mcp::HealthRules;
+use crate::tmux::Mux;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Heartbeat {
+    pub agent: String,
+    pub state: &'static str,
+    pub last_change: Option<String>,
+    pub idle_secs: Option<i64>,
+    pub session_alive: bool,
+    pub pane_alive: bool,
+    pub producing_output: bool,
+    pub stuck: bool,
+}
+
+/// Conditions a supervisor can ask to fail on (`--fail-if`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailIf {
+    Missing,
+    Dead,
+    Idle,
+    Blocked,
+    Stuck,
+    RateLimited,
+}
+
+impl FailIf {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FailIf::Missing => "missing",
+            FailIf::Dead => "dead",
+            FailIf::Idle => "idle",
+            FailIf::Blocked => "blocked",
+            FailIf::Stuck => "stuck",
+            FailIf::RateLimited => "rate_limited",
+        }
+    }
+
+    /// Parse a comma-separated list, e.g. `stuck,missing`.
+    pub fn parse_list(value: &str) -> Result<Vec<Self>> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(|part| match part {
+                "missing" => Ok(FailIf::Missing),
+                "dead" => Ok(FailIf::Dead),
+                "idle" => Ok(FailIf::Idle),
+                "blocked" => Ok(FailIf::Blocked),
+                "stuck" => Ok(FailIf::Stuck),
+                "rate_limited" => Ok(FailIf::RateLimited),
+                other => Err(anyhow!(
+                    "Unknown fail_if condition '{}' (expected missing, dead, idle, blocked, stuck, rate_limited)",
+                    other
+                )),
+            })
+            .collect()
+    }
+}
+
+impl Heartbeat {
+    /// Requested conditions that currently hold.
+    pub fn failures(&self, conditions: &[FailIf]) -> Vec<&'static str> {
+        conditions
+            .iter()
+            .filter(|condition| match condition {
+                FailIf::Missing => !self.session_alive,
+                FailIf::Dead => !self.pane_alive,
+                FailIf::Idle => self.session_alive && !self.producing_output,
+                FailIf::Blocked => self.state == "blocked",
+                FailIf::Stuck => self.stuck,
+                FailIf::RateLimited => self.state == "rate_limited",
+            })
+            .map(|condition| condition.as_str())
+            .collect()
+    }
+
+    /// The heartbeat plus `ok` and the `failed` conditions.
+    pub fn report(&self, conditions: &[FailIf]) -> Value {
+        let failed = self.failures(conditions);
+        let mut value = serde_json::to_value(self).unwrap_or_else(|_| json!({}));
+        value["ok"] = json!(failed.is_empty());
+        value["failed"] = json!(failed);
+        value
+    }
+}
+
+pub struct Thresholds {
+    pub idle_warning: i64,
+    pub idle_critical: i64,
+}
+
+impl Thresholds {
+    /// `[health]` thresholds with `backend`'s overrides applied.
+    pub fn for_backend(health: &HealthConfig, backend: Option<&str>) -> Self {
+        Self {
+            idle_warning: health.idle_warning_for(backend),
+            idle_critical: health.idle_critical_for(backend),
+        }
+    }
+}
+
+/// Probe one agent session. `agent` is the display name and `backend` its
+/// pane command, which selects per-backend thresholds and patterns.
+pub fn probe(
+    client: &dyn Mux,
+    session: &str,
+    agent: &str,
+    rules: &HealthRules,
+    backend: Option<&str>,
+) -> Heartbeat {
+    let thresholds = &Thresholds::for_backend(&rules.config, backend);
+    if !client.has_session(session).unwrap_or(false) {
+        return Heartbeat {
+            agent: agent.to_string(),
+            state: "missing",
+            last_change: None,
+            idle_secs: None,
+            session_alive: false,
+            pane_alive: false,
+            producing_output: false,
+            stuck: false,
+        };
+    }
+    let activity = client.get_pane_activity(session).unwrap_or_default();
+    let output =
+        crate::mcp::clean_human_output(&client.capture_pane_plain(session, 50).unwrap_or_default());
+    let state = crate::mcp::health_from_output(activity, &output, rules, backend);
+    let pane_alive = client.session_has_live_pane(session).unwrap_or(false);
+    from_parts(
+        agent,
+        state,
+        activity,
+        pane_alive,
+        thresholds,
+        chrono::Utc::now(),
+    )
+}
+
+fn from_parts(
+    agent: &str,
+    state: &'static str,
+    activity: i64,
+    pane_alive: bool,
+    thresholds: &Thresholds,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Heartbeat {
+    let idle_secs = (now.timestamp() - activity).max(0);
+    let stuck = pane_alive
+        && state != "finished"
+        && (state == "blocked" || idle_secs >= thresholds.idle_critical);
+    Heartbeat {
+        agent: agent.to_string(),
+        state,
+        last_change: chrono::DateTime::from_timestamp(activity, 0).map(|t| t.to_rfc3339()),
+        idle_secs: Some(idle_secs),
+        session_alive: true,
+        pane_alive,
+        producing_output: idle_secs <= thresholds.idle_warning,
+        stuck,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heartbeat_stuck_and_failures() {
+        let thresholds = Thresholds {
+            idle_warning: 15,
+            idle_critical: 300,
+        };
+        let now = chrono::Utc::now();
+        let at = |secs_ago: i64| now.timestamp() - secs_ago;
+
+        let busy = from_parts("api", "running", at(5), true, &thresholds, now);
+        assert!(busy.producing_output && !busy.stuck);
+        assert_eq!(busy.idle_secs, Some(5));
+
+        let silent = from_parts("api", "idle", at(600), true, &thresholds, now);
+        assert!(silent.stuck);
+        let done = from_parts("api", "finished", at(600), true, &thresholds, now);
+        assert!(!done.stuck);
+        let blocked = from_parts("api", "blocked", at(20), true, &thresholds, now);
+        assert!(blocked.stuck);
+
+        let conditions = FailIf::parse_list("stuck, missing,idle").unwrap();
+        assert!(busy.failures(&conditions).is_empty());
+        assert_eq!(silent.failures(&conditions), vec!["stuck", "idle"]);
+        assert!(FailIf::parse_list("stuck,bogus").is_err());
+    }
+}
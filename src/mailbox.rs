@@ -0,0 +1,301 @@
+//! Per-agent mailboxes — messages queued by `send_message` and delivered by
+//! the dashboard only while the target is waiting for input
+//!
+//! `send_input` types straight into the pane, which corrupts the prompt of
+//! an agent that is mid-generation. Mailbox messages wait in
+//! `<state_dir>/mailboxes.json` until the target is idle, blocked, or
+//! finished, then go in one at a time, oldest first. Messages to an agent
+//! that exits first are marked failed. Read-modify-write access is
+//! serialized with the MCP state lock, since the MCP server enqueues and the
+//! dashboard delivers.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::mcp::with_state_lock;
+
+/// Delivered and failed messages kept per mailbox.
+const HISTORY: usize = 50;
+
+/// How long a message may wait for its agent to show up in the dashboard
+/// (it may have just been spawned) before it is failed as orphaned.
+const ORPHAN_GRACE_SECS: i64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageStatus {
+    Queued,
+    Delivered,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Message {
+    pub id: String,
+    /// Sender's agent name, or "user"
+    pub from: String,
+    pub text: String,
+    pub status: MessageStatus,
+    pub queued_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivered_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Message {
+    fn orphaned(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.status == MessageStatus::Queued
+            && chrono::DateTime::parse_from_rfc3339(&self.queued_at).map_or(true, |at| {
+                (now - at.with_timezone(&chrono::Utc)).num_seconds() >= ORPHAN_GRACE_SECS
+            })
+    }
+
+    /// The text typed into the target's pane.
+    pub fn prompt(&self) -> String {
+        format!("MESSAGE FROM {}:\n{}", self.from, self.text)
+    }
+}
+
+type Mailboxes = HashMap<String, Vec<Message>>;
+
+fn mailboxes_path(state_dir: &Path) -> PathBuf {
+    state_dir.join("mailboxes.json")
+}
+
+fn load(state_dir: &Path) -> Mailboxes {
+    fs::read_to_string(mailboxes_path(state_dir))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save(state_dir: &Path, mailboxes: &Mailboxes) -> Result<()> {
+    let path = mailboxes_path(state_dir);
+    let tmp = path.with_extension("json.tmp");
+    fs::create_dir_all(state_dir)?;
+    fs::write(&tmp, serde_json::to_string_pretty(mailboxes)?)?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// Move `old`'s mailbox to `new`. Caller holds the state lock.
+pub fn rename_session(state_dir: &Path, old: &str, new: &str) -> Result<()> {
+    let mut mailboxes = load(state_dir);
+    if let Some(mailbox) = mailboxes.remove(old) {
+        mailboxes.insert(new.to_string(), mailbox);
+        save(state_dir, &mailboxes)?;
+    }
+    Ok(())
+}
+
+/// Queue `text` for `session`. Returns the message and its 1-based position
+/// among the session's queued messages.
+pub fn enqueue_in(
+    state_dir: &Path,
+    session: &str,
+    from: &str,
+    text: &str,
+) -> Result<(Message, usize)> {
+    push(state_dir, session, from, text, None)
+}
+
+/// Queue `text` for `session` in place of any still-queued message from
+/// `from` that starts with `prefix`, so a newer snapshot supersedes a stale
+/// one instead of queuing behind it.
+pub fn replace_in(
+    state_dir: &Path,
+    session: &str,
+    from: &str,
+    prefix: &str,
+    text: &str,
+) -> Result<(Message, usize)> {
+    push(state_dir, session, from, text, Some(prefix))
+}
+
+fn push(
+    state_dir: &Path,
+    session: &str,
+    from: &str,
+    text: &str,
+    replacing: Option<&str>,
+) -> Result<(Message, usize)> {
+    with_state_lock(state_dir, |state_dir| {
+        let mut mailboxes = load(state_dir);
+        let mailbox = mailboxes.entry(session.to_string()).or_default();
+        if let Some(prefix) = replacing {
+            mailbox.retain(|m| {
+                m.status != MessageStatus::Queued || m.from != from || !m.text.starts_with(prefix)
+            });
+        }
+        let message = Message {
+            id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+            from: from.to_string(),
+            text: text.to_string(),
+            status: MessageStatus::Queued,
+            queued_at: chrono::Utc::now().to_rfc3339(),
+            delivered_at: None,
+            error: None,
+        };
+        mailbox.push(message.clone());
+        let position = mailbox
+            .iter()
+            .filter(|m| m.status == MessageStatus::Queued)
+            .count();
+        save(state_dir, &mailboxes)?;
+        Ok((message, position))
+    })
+}
+
+/// Whether any mailbox has been written, so the dashboard can skip
+/// delivery passes until one is.
+pub fn exists_in(state_dir: &Path) -> bool {
+    mailboxes_path(state_dir).exists()
+}
+
+/// Every mailbox, keyed by recipient session.
+pub fn all_in(state_dir: &Path) -> HashMap<String, Vec<Message>> {
+    load(state_dir)
+}
+
+/// Every message kept for `session`, oldest first.
+pub fn messages_in(state_dir: &Path, session: &str) -> Vec<Message> {
+    load(state_dir).remove(session).unwrap_or_default()
+}
+
+/// The oldest queued message of each session in `ready`. Queued messages
+/// for sessions not in `live` are marked failed once past the grace period.
+pub fn due_in(
+    state_dir: &Path,
+    ready: &HashSet<String>,
+    live: &HashSet<String>,
+) -> Result<Vec<(String, Message)>> {
+    let now = chrono::Utc::now();
+    let mailboxes = load(state_dir);
+    let orphaned = mailboxes.iter().any(|(session, messages)| {
+        !live.contains(session) && messages.iter().any(|m| m.orphaned(now))
+    });
+    if orphaned {
+        with_state_lock(state_dir, |state_dir| {
+            let mut mailboxes = load(state_dir);
+            for (_, messages) in mailboxes.iter_mut().filter(|(s, _)| !live.contains(*s)) {
+                for message in messages.iter_mut().filter(|m| m.orphaned(now)) {
+                    message.status = MessageStatus::Failed;
+                    message.error = Some("agent exited before delivery".to_string());
+                }
+            }
+            save(state_dir, &mailboxes)
+        })?;
+    }
+    Ok(mailboxes
+        .into_iter()
+        .filter(|(session, _)| ready.contains(session))
+        .filter_map(|(session, messages)| {
+            let next = messages
+                .into_iter()
+                .find(|m| m.status == MessageStatus::Queued)?;
+            Some((session, next))
+        })
+        .collect())
+}
+
+/// Record the outcome of delivering message `id` to `session`.
+pub fn mark_in(state_dir: &Path, session: &str, id: &str, outcome: &Result<()>) -> Result<()> {
+    with_state_lock(state_dir, |state_dir| {
+        let mut mailboxes = load(state_dir);
+        let mailbox = mailboxes
+            .get_mut(session)
+            .ok_or_else(|| anyhow!("No mailbox for {}", session))?;
+        if let Some(message) = mailbox.iter_mut().find(|m| m.id == id) {
+            match outcome {
+                Ok(()) => {
+                    message.status = MessageStatus::Delivered;
+                    message.delivered_at = Some(chrono::Utc::now().to_rfc3339());
+                }
+                Err(e) => {
+                    message.status = MessageStatus::Failed;
+                    message.error = Some(e.to_string());
+                }
+            }
+        }
+        let done = mailbox
+            .iter()
+            .filter(|m| m.status != MessageStatus::Queued)
+            .count();
+        let mut excess = done.saturating_sub(HISTORY);
+        mailbox.retain(|m| {
+            let drop = excess > 0 && m.status != MessageStatus::Queued;
+            excess -= drop as usize;
+            !drop
+        });
+        save(state_dir, &mailboxes)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_deliver_and_fail_orphans() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = dir.path();
+        let (first, pos) = enqueue_in(state, "omar-agent-0-api", "pm", "rebase on main").unwrap();
+        assert_eq!(pos, 1);
+        let (_, pos) = enqueue_in(state, "omar-agent-0-api", "user", "then run tests").unwrap();
+        assert_eq!(pos, 2);
+        enqueue_in(state, "omar-agent-0-gone", "pm", "hello").unwrap();
+        assert_eq!(first.prompt(), "MESSAGE FROM pm:\nrebase on main");
+
+        let live: HashSet<String> = ["omar-agent-0-api".to_string()].into();
+        // Busy agents get nothing; an absent agent's message waits out the grace period
+        assert!(due_in(state, &HashSet::new(), &live).unwrap().is_empty());
+        assert_eq!(
+            messages_in(state, "omar-agent-0-gone")[0].status,
+            MessageStatus::Queued
+        );
+        let mut mailboxes = load(state);
+        mailboxes.get_mut("omar-agent-0-gone").unwrap()[0].queued_at =
+            (chrono::Utc::now() - chrono::Duration::seconds(ORPHAN_GRACE_SECS)).to_rfc3339();
+        save(state, &mailboxes).unwrap();
+        due_in(state, &HashSet::new(), &live).unwrap();
+        let gone = messages_in(state, "omar-agent-0-gone");
+        assert_eq!(gone[0].status, MessageStatus::Failed);
+
+        let due = due_in(state, &live, &live).unwrap();
+        assert_eq!(due, vec![("omar-agent-0-api".to_string(), first.clone())]);
+        mark_in(state, "omar-agent-0-api", &first.id, &Ok(())).unwrap();
+        let due = due_in(state, &live, &live).unwrap();
+        assert_eq!(due[0].1.text, "then run tests");
+
+        let messages = messages_in(state, "omar-agent-0-api");
+        assert_eq!(messages[0].status, MessageStatus::Delivered);
+        assert!(messages[0].delivered_at.is_some());
+        assert_eq!(messages[1].status, MessageStatus::Queued);
+    }
+
+    #[test]
+    fn test_replace_drops_only_the_stale_queued_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = dir.path();
+        let session = "omar-ea-0";
+        let (sent, _) = enqueue_in(state, session, "omar", "[REFRESH] v1").unwrap();
+        mark_in(state, session, &sent.id, &Ok(())).unwrap();
+        enqueue_in(state, session, "omar", "[REFRESH] v2").unwrap();
+        enqueue_in(state, session, "user", "[REFRESH] from the user").unwrap();
+        let (_, pos) = replace_in(state, session, "omar", "[REFRESH]", "[REFRESH] v3").unwrap();
+        assert_eq!(pos, 2);
+        let texts: Vec<String> = messages_in(state, session)
+            .into_iter()
+            .map(|m| m.text)
+            .collect();
+        assert_eq!(
+            texts,
+            ["[REFRESH] v1", "[REFRESH] from the user", "[REFRESH] v3"]
+        );
+    }
+}
@@ -5,14 +5,15 @@ pub mod protocol;
 use anyhow::Result;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
-use std::thread;
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+use crate::config::{self, LimitsConfig, PromptsConfig, ReadinessConfig, SandboxConfig};
 use crate::ea::{self, EaId};
 use crate::memory;
 use crate::metrics;
-use crate::tmux::{DeliveryOptions, TmuxClient};
+use crate::tmux::{DeliveryOptions, ReadinessProbe, TmuxClient};
+use crate::transcript;
 use protocol::{parse_manager_message, ManagerMessage, ProposedAgent};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -25,12 +26,22 @@ pub struct McpLaunchContext {
     pub health_idle_warning: i64,
     #[serde(default)]
     pub tmux_server: Option<String>,
+    #[serde(default)]
+    pub sandbox: SandboxConfig,
+    #[serde(default)]
+    pub limits: LimitsConfig,
+    #[serde(default)]
+    pub prompts: PromptsConfig,
 }
 
 #[derive(Debug, Clone)]
 pub struct ManagerRuntimeOptions {
     pub default_workdir: String,
     pub health_idle_warning: i64,
+    pub sandbox: SandboxConfig,
+    pub limits: LimitsConfig,
+    pub readiness: ReadinessConfig,
+    pub prompts: PromptsConfig,
 }
 
 // Embed prompt files at compile time so they work regardless of CWD.
@@ -71,6 +82,7 @@ const EMBEDDED_PROMPTS: &[(&str, &str)] = &[
 ///
 /// Prompts are shared templates containing `{{EA_ID}}` placeholders.
 /// Substitution happens at spawn time in `build_ea_command` / `build_agent_command`.
+/// Edits here are overwritten; point `[prompts]` at a copy to customize.
 pub fn prompts_dir(omar_dir: &Path) -> PathBuf {
     let dir = omar_dir.join("prompts");
     std::fs::create_dir_all(&dir).ok();
@@ -84,6 +96,55 @@ pub fn prompts_dir(omar_dir: &Path) -> PathBuf {
     dir
 }
 
+/// Template for the built-in prompt `name`: the `[prompts]` override when
+/// one is configured, otherwise the embedded copy.
+pub fn prompt_template(context: &McpLaunchContext, name: &str) -> PathBuf {
+    let configured = match name {
+        "executive-assistant.md" => context.prompts.ea.as_deref(),
+        "agent.md" => context.prompts.agent.as_deref(),
+        _ => None,
+    };
+    match configured {
+        Some(path) => config::expand_tilde(path),
+        None => prompts_dir(&context.omar_dir).join(name),
+    }
+}
+
+/// Placeholders filled from the launch context rather than per spawn:
+/// `{{OMAR_DIR}}`, `{{SESSION_PREFIX}}`, and each `[prompts.vars]` entry.
+pub fn context_substitutions(context: &McpLaunchContext) -> Vec<(String, String)> {
+    let mut substitutions = vec![
+        (
+            "{{OMAR_DIR}}".to_string(),
+            context.omar_dir.display().to_string(),
+        ),
+        (
+            "{{SESSION_PREFIX}}".to_string(),
+            context.session_prefix.clone(),
+        ),
+    ];
+    substitutions.extend(
+        context
+            .prompts
+            .vars
+            .iter()
+            .filter(|(name, _)| {
+                !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            })
+            .map(|(name, value)| (format!("{{{{{}}}}}", name), value.clone())),
+    );
+    substitutions
+}
+
+/// Fill the context placeholders in an already-read prompt.
+pub fn render_context(text: &str, context: &McpLaunchContext) -> String {
+    context_substitutions(context)
+        .iter()
+        .fold(text.to_string(), |text, (pattern, value)| {
+            text.replace(pattern, value)
+        })
+}
+
 /// Escape a string for use in a sed replacement (with `|` as delimiter).
 ///
 /// The sed expression is wrapped in single quotes in the generated shell command
@@ -172,6 +233,24 @@ pub fn command_backend_name(command: &str) -> Option<&'static str> {
     detect_backend(command).map(BackendKind::canonical_name)
 }
 
+/// Wait for a freshly spawned agent to be ready, then paste `task` via a
+/// tmux buffer so multi-kilobyte specs arrive intact. Returns false when the
+/// ready prompt timed out (the task is delivered anyway).
+pub fn deliver_task_when_ready(
+    client: &TmuxClient,
+    session: &str,
+    command: &str,
+    task: &str,
+    readiness: &ReadinessConfig,
+) -> Result<bool> {
+    let ready = ReadinessProbe::from_config(command_backend_name(command), readiness)
+        .wait(client, session)?;
+    client
+        .deliver_prompt(session, task, &DeliveryOptions::default())
+        .map_err(|e| anyhow::anyhow!("failed to deliver task to {}: {}", session, e))?;
+    Ok(ready)
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ManagerEnsureResult {
     AlreadyRunning,
@@ -723,6 +802,21 @@ pub fn build_agent_command(
     let base_command = ensure_codex_runtime_flags(base_command);
     let path_str = prompt_file.display().to_string();
     let prompt_path = shell_single_quote(&path_str);
+    // Only the context placeholders the template uses, to keep sed short
+    let template = std::fs::read_to_string(prompt_file).unwrap_or_default();
+    let context_substitutions: Vec<(String, String)> = context_substitutions(mcp_context)
+        .into_iter()
+        .filter(|(pattern, _)| template.contains(pattern.as_str()))
+        .collect();
+    let substitutions: Vec<(&str, &str)> = substitutions
+        .iter()
+        .copied()
+        .chain(
+            context_substitutions
+                .iter()
+                .map(|(pattern, value)| (pattern.as_str(), value.as_str())),
+        )
+        .collect();
     let shell_expr = if substitutions.is_empty() {
         format!("$(cat {})", prompt_path)
     } else {
@@ -766,7 +860,7 @@ pub fn build_agent_command(
             cmd
         }
         Some(BackendKind::Cursor) => {
-            let rendered = materialize_prompt_file(prompt_file, substitutions);
+            let rendered = materialize_prompt_file(prompt_file, &substitutions);
             let _ = ensure_cursor_mcp_config(mcp_context);
             // Cursor Agent currently exposes no per-session tool deny flag in
             // interactive mode; the prompt-level wake policy is the enforcement
@@ -831,7 +925,7 @@ pub fn build_ea_command(
     omar_dir: &Path,
     mcp_context: &McpLaunchContext,
 ) -> (String, Option<PathBuf>) {
-    let prompt_file = prompts_dir(omar_dir).join("executive-assistant.md");
+    let prompt_file = prompt_template(mcp_context, "executive-assistant.md");
     let state_dir = ea::ea_state_dir(ea_id, omar_dir);
     let mem = memory::load_memory_from(&state_dir);
 
@@ -867,7 +961,7 @@ pub fn build_ea_command(
 
     match backend {
         Some(BackendKind::Claude) => {
-            let resolved = combined
+            let resolved = render_context(&combined, mcp_context)
                 .replace("{{EA_ID}}", &ea_id.to_string())
                 .replace("{{EA_NAME}}", ea_name);
             std::fs::write(&combined_path, &resolved).ok();
@@ -995,6 +1089,9 @@ pub fn ensure_manager_session(
             default_workdir: options.default_workdir.clone(),
             health_idle_warning: options.health_idle_warning,
             tmux_server: current_tmux_server(),
+            sandbox: options.sandbox.clone(),
+            limits: options.limits.clone(),
+            prompts: options.prompts.clone(),
         },
     );
 
@@ -1005,9 +1102,13 @@ pub fn ensure_manager_session(
         None => std::env::current_dir()?.to_string_lossy().into_owned(),
     };
     client.new_session(&session, &cmd, Some(&cwd))?;
+    transcript::start(client, &session);
 
-    // Give it time to start
-    thread::sleep(Duration::from_secs(2));
+    if !ReadinessProbe::from_config(command_backend_name(command), &options.readiness)
+        .wait(client, &session)?
+    {
+        println!("Manager is slow to start; continuing anyway.");
+    }
     Ok((session, result))
 }
 
@@ -1063,7 +1164,15 @@ pub fn run_manager_orchestration(
                 check_manager_output(client, &session)?;
             }
             "approve" | "y" => {
-                approve_plan(client, command, &session, ea_id, omar_dir, base_prefix)?;
+                approve_plan(
+                    client,
+                    command,
+                    &session,
+                    ea_id,
+                    omar_dir,
+                    base_prefix,
+                    options,
+                )?;
             }
             "reject" | "n" => {
                 reject_plan(client, &session)?;
@@ -1130,7 +1239,7 @@ fn show_status(client: &TmuxClient, session: &str) -> Result<()> {
         println!("\nWorkers:");
         for s in sessions {
             let output = client.capture_pane(&s.name, 1).unwrap_or_default();
-            let short_name = s.name.strip_prefix(client.prefix()).unwrap_or(&s.name);
+            let short_name = crate::naming::short_name(client.prefix(), &s.name);
             println!("  {}: {}", short_name, output.trim());
         }
     }
@@ -1150,12 +1259,20 @@ fn check_manager_output(client: &TmuxClient, session: &str) -> Result<()> {
                 println!("\n=== Proposed Plan ===");
                 println!("Goal: {}\n", description);
                 println!("Agents:");
-                for (i, agent) in agents.iter().enumerate() {
+                let estimates = protocol::estimate_plan(&agents, &metrics::load_history());
+                for (i, (agent, estimate)) in agents.iter().zip(&estimates.agents).enumerate() {
                     println!("  {}. {} ({})", i + 1, agent.name, agent.role);
                     println!("     Task: {}", agent.task);
                     if !agent.depends_on.is_empty() {
                         println!("     Depends on: {}", agent.depends_on.join(", "));
                     }
+                    match estimate {
+                        Some(estimate) => println!("     Estimate: {}", estimate.summary()),
+                        None => println!("     Estimate: no similar runs yet"),
+                    }
+                }
+                if let Some(total) = estimates.total_summary() {
+                    println!("\nEstimated total: {}", total);
                 }
                 println!("\nApprove this plan? Use 'approve' or 'reject'");
             }
@@ -1180,43 +1297,120 @@ fn check_manager_output(client: &TmuxClient, session: &str) -> Result<()> {
     Ok(())
 }
 
-fn approve_plan(
+/// The plan the manager last proposed, if its recent output holds one.
+pub fn pending_plan(
+    client: &TmuxClient,
+    session: &str,
+) -> Result<Option<(String, Vec<ProposedAgent>)>> {
+    let output = client.capture_pane(session, 50)?;
+    Ok(match parse_manager_message(&output) {
+        Some(ManagerMessage::Plan {
+            description,
+            agents,
+        }) => Some((description, agents)),
+        _ => None,
+    })
+}
+
+/// Outcome of approving a plan.
+#[derive(Debug, Default)]
+pub struct PlanApproval {
+    pub description: String,
+    pub spawned: Vec<String>,
+    /// Agents whose session already existed
+    pub skipped: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Spawn the workers of the manager's pending plan and tell the manager.
+/// `None` when there is no plan to approve.
+pub fn approve_pending_plan(
     client: &TmuxClient,
     command: &str,
     session: &str,
     ea_id: EaId,
     omar_dir: &Path,
     base_prefix: &str,
-) -> Result<()> {
-    let output = client.capture_pane(session, 50)?;
-
-    if let Some(ManagerMessage::Plan {
+    options: &ManagerRuntimeOptions,
+) -> Result<Option<PlanApproval>> {
+    let Some((description, agents)) = pending_plan(client, session)? else {
+        return Ok(None);
+    };
+    let mut approval = PlanApproval {
         description,
-        agents,
-    }) = parse_manager_message(&output)
-    {
-        println!("\nApproving plan: {}", description);
-        println!("Spawning {} worker agents...\n", agents.len());
-
-        for agent in &agents {
-            spawn_worker(client, agent, command, ea_id, omar_dir, base_prefix)?;
+        ..PlanApproval::default()
+    };
+    for agent in &agents {
+        let spawned = spawn_worker(
+            client,
+            agent,
+            command,
+            ea_id,
+            omar_dir,
+            base_prefix,
+            options,
+            &mut approval.warnings,
+        )?;
+        if spawned {
+            approval.spawned.push(agent.name.clone());
+        } else {
+            approval.skipped.push(agent.name.clone());
         }
+    }
 
-        // Notify manager that plan was approved
-        let approval_msg = format!(
-            "Plan approved. {} agents spawned: {}",
-            agents.len(),
-            agents
-                .iter()
-                .map(|a| a.name.as_str())
-                .collect::<Vec<_>>()
-                .join(", ")
-        );
-        send_to_manager(client, session, &approval_msg)?;
+    // Notify manager that plan was approved
+    let approval_msg = format!(
+        "Plan approved. {} agents spawned: {}",
+        agents.len(),
+        agents
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    client.deliver_prompt(session, &approval_msg, &DeliveryOptions::default())?;
+    Ok(Some(approval))
+}
 
-        println!("\nAll agents spawned. Use 'status' to monitor progress.");
-    } else {
-        println!("No plan found to approve. Use 'check' to see manager output.");
+/// Tell the manager its plan was rejected and why.
+pub fn reject_pending_plan(client: &TmuxClient, session: &str, reason: &str) -> Result<String> {
+    let message = format!("Plan rejected. Reason: {}", reason.trim());
+    client.deliver_prompt(session, &message, &DeliveryOptions::default())?;
+    Ok(message)
+}
+
+fn approve_plan(
+    client: &TmuxClient,
+    command: &str,
+    session: &str,
+    ea_id: EaId,
+    omar_dir: &Path,
+    base_prefix: &str,
+    options: &ManagerRuntimeOptions,
+) -> Result<()> {
+    match approve_pending_plan(
+        client,
+        command,
+        session,
+        ea_id,
+        omar_dir,
+        base_prefix,
+        options,
+    )? {
+        Some(approval) => {
+            println!("\nApproved plan: {}", approval.description);
+            for warning in &approval.warnings {
+                println!("  {}", warning);
+            }
+            for name in &approval.skipped {
+                println!("  {} - already exists, skipping", name);
+            }
+            for name in &approval.spawned {
+                println!("  {} - spawned", name);
+            }
+            println!("\nAll agents spawned. Use 'status' to monitor progress.");
+        }
+        None => println!("No plan found to approve. Use 'check' to see manager output."),
     }
 
     Ok(())
@@ -1229,11 +1423,8 @@ fn reject_plan(client: &TmuxClient, session: &str) -> Result<()> {
     let mut reason = String::new();
     io::stdin().read_line(&mut reason)?;
 
-    send_to_manager(
-        client,
-        session,
-        &format!("Plan rejected. Reason: {}", reason.trim()),
-    )?;
+    let message = reject_pending_plan(client, session, &reason)?;
+    println!("Sent to manager: {}", message);
     println!("Rejection sent to manager.");
 
     Ok(())
@@ -1246,7 +1437,7 @@ fn send_to_manager(client: &TmuxClient, session: &str, message: &str) -> Result<
 }
 
 fn send_to_agent(client: &TmuxClient, agent: &str, message: &str) -> Result<()> {
-    let session_name = format!("{}{}", client.prefix(), agent);
+    let session_name = crate::naming::session_name(client.prefix(), agent);
 
     if !client.has_session(&session_name)? {
         println!("Agent '{}' not found.", agent);
@@ -1258,6 +1449,9 @@ fn send_to_agent(client: &TmuxClient, agent: &str, message: &str) -> Result<()>
     Ok(())
 }
 
+/// Start one plan worker; false when its session already exists. Never
+/// prints, since the MCP server speaks over stdout.
+#[allow(clippy::too_many_arguments)]
 fn spawn_worker(
     client: &TmuxClient,
     agent: &ProposedAgent,
@@ -1265,66 +1459,53 @@ fn spawn_worker(
     ea_id: EaId,
     omar_dir: &Path,
     base_prefix: &str,
-) -> Result<()> {
-    let session_name = format!("{}{}", client.prefix(), agent.name);
+    options: &ManagerRuntimeOptions,
+    warnings: &mut Vec<String>,
+) -> Result<bool> {
+    let session_name = crate::naming::session_name(client.prefix(), &agent.name);
 
     if client.has_session(&session_name)? {
-        println!("  {} - already exists, skipping", agent.name);
-        return Ok(());
+        return Ok(false);
     }
 
     // Build command with worker system prompt (template vars substituted via sed)
     let parent_name = "ea";
-    let prompt_file = prompts_dir(omar_dir).join("agent.md");
+    let mcp_context = McpLaunchContext {
+        omar_dir: omar_dir.to_path_buf(),
+        ea_id,
+        session_prefix: base_prefix.to_string(),
+        default_command: command.to_string(),
+        default_workdir: ".".to_string(),
+        health_idle_warning: 15,
+        tmux_server: current_tmux_server(),
+        sandbox: options.sandbox.clone(),
+        limits: options.limits.clone(),
+        prompts: options.prompts.clone(),
+    };
+    let prompt_file = prompt_template(&mcp_context, "agent.md");
     let cmd = build_agent_command(
         command,
         &prompt_file,
         &[("{{TASK}}", &agent.task), ("{{EA_ID}}", &ea_id.to_string())],
-        &McpLaunchContext {
-            omar_dir: omar_dir.to_path_buf(),
-            ea_id,
-            session_prefix: base_prefix.to_string(),
-            default_command: command.to_string(),
-            default_workdir: ".".to_string(),
-            health_idle_warning: 15,
-            tmux_server: current_tmux_server(),
-        },
+        &mcp_context,
     );
 
     // Create worker session — system prompt set at process start
-    client.new_session(
-        &session_name,
-        &cmd,
-        Some(&std::env::current_dir()?.to_string_lossy()),
-    )?;
-
-    // Wait for backend readiness when possible, then deliver an explicit
-    // first task message so workers begin execution deterministically.
-    // If markers succeed, the TUI is proven ready; skip require_initial_change
-    // (a fresh Claude Code banner stays pixel-stable after drawing, so any
-    // extra "wait for a change" would time out).
-    let markers_proved_ready = if let Some(kind) = detect_backend(command) {
-        let markers = crate::tmux::backend_readiness_markers(kind.canonical_name());
-        if markers.is_empty() {
-            false
-        } else {
-            let detected = client.wait_for_markers(
-                &session_name,
-                markers,
-                Duration::from_secs(60),
-                Duration::from_millis(250),
-            );
-            if !detected {
-                println!(
-                    "  {} - readiness markers timed out; attempting delivery anyway",
-                    agent.name
-                );
-            }
-            detected
-        }
-    } else {
-        false
-    };
+    let cwd = std::env::current_dir()?.to_string_lossy().into_owned();
+    let cmd = crate::sandbox::wrap_agent_command(&options.sandbox, &options.limits, &cmd, &cwd)?;
+    client.new_session(&session_name, &cmd, Some(&cwd))?;
+    transcript::start(client, &session_name);
+
+    // Wait for the backend's ready prompt, then deliver an explicit first
+    // task message so workers begin execution deterministically.
+    let ready = ReadinessProbe::from_config(command_backend_name(command), &options.readiness)
+        .wait(client, &session_name)?;
+    if !ready {
+        warnings.push(format!(
+            "{} - ready prompt timed out; attempting delivery anyway",
+            agent.name
+        ));
+    }
 
     // opencode has no system-prompt flag, so build_agent_command spawns it
     // bare. Inline the rendered agent.md content here so the worker receives
@@ -1339,7 +1520,11 @@ fn spawn_worker(
             &[("{{TASK}}", &agent.task), ("{{EA_ID}}", &ea_id.to_string())],
         );
         let body = std::fs::read_to_string(&rendered).unwrap_or_default();
-        format!("{}\n\n---\n\n{}", body, header)
+        format!(
+            "{}\n\n---\n\n{}",
+            render_context(&body, &mcp_context),
+            header
+        )
     } else {
         header
     };
@@ -1350,7 +1535,7 @@ fn spawn_worker(
         max_retries: 4,
         poll_interval: Duration::from_millis(120),
         retry_delay: Duration::from_millis(250),
-        require_initial_change: !markers_proved_ready,
+        require_initial_change: !ready,
     };
     client
         .deliver_prompt(&session_name, &initial_msg, &opts)
@@ -1358,16 +1543,12 @@ fn spawn_worker(
 
     // Persist worker task description to EA-scoped state dir
     let state_dir = ea::ea_state_dir(ea_id, omar_dir);
-    memory::save_worker_task_in(&state_dir, &session_name, &agent.task);
-    memory::save_agent_parent_in(
-        &state_dir,
-        &session_name,
-        &ea::ea_manager_session(ea_id, base_prefix),
-    );
+    memory::AgentUpdate::new(&session_name)
+        .task(&agent.task)
+        .parent(&ea::ea_manager_session(ea_id, base_prefix))
+        .save_in(&state_dir)?;
 
-    println!("  {} - spawned ({})", agent.name, agent.role);
-
-    Ok(())
+    Ok(true)
 }
 
 #[cfg(test)]
@@ -1420,6 +1601,9 @@ mod tests {
             default_workdir: ".".to_string(),
             health_idle_warning: 15,
             tmux_server: None,
+            sandbox: SandboxConfig::default(),
+            limits: LimitsConfig::default(),
+            prompts: PromptsConfig::default(),
         }
     }
 
@@ -1862,6 +2046,9 @@ mod tests {
                 default_workdir: ".".to_string(),
                 health_idle_warning: 15,
                 tmux_server: None,
+                sandbox: SandboxConfig::default(),
+                limits: LimitsConfig::default(),
+                prompts: PromptsConfig::default(),
             },
         );
 
@@ -1903,6 +2090,48 @@ mod tests {
         assert!(pdir.join("agent.md").exists());
     }
 
+    #[test]
+    fn test_configured_prompt_template_is_rendered_with_context() {
+        let dir = tempfile::tempdir().unwrap();
+        let omar_dir = dir.path();
+        let custom = omar_dir.join("my-ea.md");
+        std::fs::write(
+            &custom,
+            "EA {{EA_ID}} in {{OMAR_DIR}}, workers named {{SESSION_PREFIX}}*. {{TEAM}} {{BAD-NAME}}",
+        )
+        .unwrap();
+        let mut context = test_mcp_context(omar_dir);
+        context.prompts.ea = Some(custom.display().to_string());
+        context.prompts.vars = [
+            ("TEAM".to_string(), "Team: infra".to_string()),
+            ("BAD-NAME".to_string(), "x".to_string()),
+        ]
+        .into();
+        assert_eq!(prompt_template(&context, "executive-assistant.md"), custom);
+        assert_eq!(
+            prompt_template(&context, "agent.md"),
+            omar_dir.join("prompts/agent.md")
+        );
+
+        let _ = build_ea_command("claude", 0, "Default", omar_dir, &context);
+        let combined = ea::ea_state_dir(0, omar_dir).join("ea_prompt_combined.md");
+        assert_eq!(
+            std::fs::read_to_string(combined).unwrap(),
+            format!(
+                "EA 0 in {}, workers named omar-agent-*. Team: infra {{{{BAD-NAME}}}}",
+                omar_dir.display()
+            )
+        );
+
+        let cmd = build_agent_command("codex", &custom, &[], &context);
+        assert!(
+            cmd.contains("s|{{SESSION_PREFIX}}|omar-agent-|g"),
+            "{}",
+            cmd
+        );
+        assert!(!cmd.contains("BAD-NAME|"), "{}", cmd);
+    }
+
     #[test]
     fn embedded_prompts_accept_namespaced_and_plain_omar_tool_names() {
         for prompt in [PROMPT_EA, PROMPT_AGENT] {
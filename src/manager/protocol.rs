@@ -4,6 +4,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::metrics::{self, AgentRunRecord, Estimate};
+
 /// A proposed agent in a plan
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProposedAgent {
@@ -14,6 +16,47 @@ pub struct ProposedAgent {
     pub depends_on: Vec<String>,
 }
 
+/// Per-agent estimates for a proposed plan, in plan order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanEstimate {
+    pub agents: Vec<Option<Estimate>>,
+}
+
+impl PlanEstimate {
+    /// Summed agent time and cost over the agents with an estimate.
+    pub fn total_summary(&self) -> Option<String> {
+        let known: Vec<&Estimate> = self.agents.iter().flatten().collect();
+        if known.is_empty() {
+            return None;
+        }
+        let secs: u64 = known.iter().map(|e| e.duration_secs).sum();
+        let costs: Vec<f64> = known.iter().filter_map(|e| e.cost_usd).collect();
+        let cost = if costs.is_empty() {
+            String::new()
+        } else {
+            format!(", ~${:.2}", costs.iter().sum::<f64>())
+        };
+        Some(format!(
+            "~{} agent time{} ({}/{} agents estimated)",
+            metrics::format_duration(secs),
+            cost,
+            known.len(),
+            self.agents.len()
+        ))
+    }
+}
+
+/// Estimate each proposed agent by its name (workers are spawned under it),
+/// then its role, then its task.
+pub fn estimate_plan(agents: &[ProposedAgent], history: &[AgentRunRecord]) -> PlanEstimate {
+    PlanEstimate {
+        agents: agents
+            .iter()
+            .map(|agent| metrics::estimate(history, &[&agent.name, &agent.role], &agent.task))
+            .collect(),
+    }
+}
+
 /// Messages from manager to OMA
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -58,6 +101,40 @@ pub fn parse_manager_message(text: &str) -> Option<ManagerMessage> {
     None
 }
 
+/// Every `send` message in `text`, oldest first: each ```json block and
+/// each line holding a whole JSON object.
+pub fn parse_send_messages(text: &str) -> Vec<(String, String)> {
+    let mut candidates: Vec<&str> = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("```json") {
+        candidates.extend(
+            rest[..start]
+                .lines()
+                .map(str::trim)
+                .filter(|line| line.starts_with('{') && line.ends_with('}')),
+        );
+        let body = &rest[start + 7..];
+        let Some(end) = body.find("```") else {
+            rest = body;
+            break;
+        };
+        candidates.push(body[..end].trim());
+        rest = &body[end + 3..];
+    }
+    candidates.extend(
+        rest.lines()
+            .map(str::trim)
+            .filter(|line| line.starts_with('{') && line.ends_with('}')),
+    );
+    candidates
+        .into_iter()
+        .filter_map(|json| match serde_json::from_str(json) {
+            Ok(ManagerMessage::Send { target, message }) => Some((target, message)),
+            _ => None,
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,6 +172,43 @@ Let me know if this looks good!
         }
     }
 
+    #[test]
+    fn test_estimate_plan_totals_known_agents() {
+        let history: Vec<AgentRunRecord> = [("api", 600, Some(1.0)), ("db", 1200, None)]
+            .iter()
+            .map(|(role, secs, cost)| AgentRunRecord {
+                role: role.to_string(),
+                task: String::new(),
+                backend: "claude".to_string(),
+                duration_secs: *secs,
+                cost_usd: *cost,
+                result: "complete".to_string(),
+                finished_at: String::new(),
+            })
+            .collect();
+        let agent = |name: &str, role: &str| ProposedAgent {
+            name: name.to_string(),
+            role: role.to_string(),
+            task: "Create endpoints".to_string(),
+            depends_on: Vec::new(),
+        };
+        let plan = [
+            agent("api-2", "API Developer"),
+            agent("schema", "DB"),
+            agent("ui", "Frontend"),
+        ];
+
+        let estimate = estimate_plan(&plan, &history);
+        assert_eq!(estimate.agents[0].as_ref().unwrap().duration_secs, 600);
+        assert_eq!(estimate.agents[1].as_ref().unwrap().duration_secs, 1200);
+        assert!(estimate.agents[2].is_none());
+        assert_eq!(
+            estimate.total_summary().as_deref(),
+            Some("~30m agent time, ~$1.00 (2/3 agents estimated)")
+        );
+        assert_eq!(estimate_plan(&plan, &[]).total_summary(), None);
+    }
+
     #[test]
     fn test_parse_send() {
         let text = r#"{"type": "send", "target": "api", "message": "Add /users endpoint"}"#;
@@ -108,4 +222,23 @@ Let me know if this looks good!
             _ => panic!("Expected Send message"),
         }
     }
+
+    #[test]
+    fn test_parse_send_messages_finds_every_send() {
+        let text = r#"Routing work:
+{"type": "send", "target": "api", "message": "Add /users"}
+```json
+{"type": "send", "target": "ui",
+ "message": "Render the list"}
+```
+{"type": "query", "target": "db"}
+"#;
+        assert_eq!(
+            parse_send_messages(text),
+            vec![
+                ("api".to_string(), "Add /users".to_string()),
+                ("ui".to_string(), "Render the list".to_string()),
+            ]
+        );
+    }
 }
@@ -9,27 +9,57 @@ use serde_json::{json, Value};
 use std::fs::{self, OpenOptions};
 use std::io::{self, BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
-use std::sync::OnceLock;
+use std::sync::{Arc, Mutex, OnceLock};
 use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
 use crate::app::AgentInfo;
 use crate::backend_probe;
+use crate::checkpoint;
 use crate::computer;
 use crate::config;
+use crate::drain;
 use crate::ea::{self, EaId};
+use crate::exec;
+use crate::file_browser;
+use crate::firehose::Firehose;
+use crate::github;
+use crate::health_history;
+use crate::heartbeat;
+use crate::mailbox;
+use crate::manager::protocol::{estimate_plan, ProposedAgent};
 use crate::manager::{self, McpLaunchContext};
-use crate::memory;
+use crate::memory::{self, AgentRole};
 use crate::metrics;
+use crate::naming::{self, AgentId};
+use crate::pipeline;
 use crate::process::{pid_alive, pid_file_is_stale};
 use crate::projects;
+use crate::runs;
+use crate::sandbox;
 use crate::scheduler::{self, ScheduledEvent};
-use crate::tmux::{DeliveryOptions, HealthChecker, TmuxClient};
+use crate::schedules;
+use crate::search;
+use crate::secrets;
+use crate::snapshot::Priority;
+use crate::spawn_queue::{self, WaitFor, WaitState};
+use crate::templates;
+use crate::tmux::{
+    self, detect_question, detect_result, team_health, Activity, ActivityDetector, DeliveryOptions,
+    HealthChecker, HealthState, Mux, RateLimitDetector, ReadinessProbe, RemoteHost, ResultKind,
+    SpawnStatus, TmuxClient,
+};
+use crate::topology;
+use crate::transcript;
+use crate::validation::ValidationError;
 
 const JSONRPC_VERSION: &str = "2.0";
 const PROTOCOL_VERSION: &str = "2024-11-05";
 const INITIAL_PROMPT_DELIVERY_STATUS_TIMEOUT: Duration = Duration::from_millis(1500);
+/// Directory under an agent's workdir that `send_file` writes into.
+const SEND_FILE_DIR: &str = ".omar-context";
+pub(crate) const SEND_FILE_MAX_BYTES: u64 = 10 * 1024 * 1024;
 const SERVER_INSTRUCTIONS: &str = concat!(
     "OMAR provides orchestration tools for executive assistant and worker sessions. ",
     "Use these tools for agent delegation, project tracking, scheduled wake-ups, ",
@@ -213,6 +243,39 @@ fn now_rfc3339() -> String {
     chrono::Utc::now().to_rfc3339()
 }
 
+/// A project with its numbered checklist and progress, as the project
+/// tools return it.
+fn project_json(project: &projects::Project) -> Value {
+    let items: Vec<Value> = project
+        .items
+        .iter()
+        .enumerate()
+        .map(|(idx, item)| json!({ "item": idx + 1, "text": item.text, "done": item.done }))
+        .collect();
+    let progress = project
+        .progress()
+        .map(|(done, total)| json!({ "done": done, "total": total, "percent": project.percent() }));
+    json!({
+        "id": project.id,
+        "name": project.name,
+        "items": items,
+        "progress": progress,
+    })
+}
+
+/// RFC3339 UTC for a Unix timestamp in seconds (`None` when unset or out of range).
+fn secs_rfc3339(secs: i64) -> Option<String> {
+    (secs > 0)
+        .then(|| chrono::DateTime::from_timestamp(secs, 0))
+        .flatten()
+        .map(|t| t.to_rfc3339())
+}
+
+/// RFC3339 UTC for a Unix timestamp in nanoseconds.
+fn ns_rfc3339(ns: u64) -> String {
+    chrono::DateTime::from_timestamp_nanos(ns as i64).to_rfc3339()
+}
+
 fn infer_backend_name(explicit_backend: Option<&str>, command: &str) -> String {
     fn normalize(s: &str) -> Option<&'static str> {
         match s.trim().to_ascii_lowercase().as_str() {
@@ -246,6 +309,16 @@ fn infer_backend_name(explicit_backend: Option<&str>, command: &str) -> String {
     "unknown".to_string()
 }
 
+/// Whether an agent supervises its project: spawned with role pm, or with
+/// no stored role and named like a PM, which is how PMs were recognized
+/// before roles were stored.
+fn is_project_supervisor(role: Option<&AgentRole>, short_name: &str) -> bool {
+    match role {
+        Some(role) => *role == AgentRole::Pm,
+        None => looks_like_supervisor_name(short_name),
+    }
+}
+
 fn looks_like_supervisor_name(name: &str) -> bool {
     let lower = name.to_ascii_lowercase();
     let tokens: Vec<&str> = lower
@@ -335,7 +408,7 @@ fn last_output_line(output: &str) -> String {
         .collect()
 }
 
-fn clean_human_output(output: &str) -> String {
+pub(crate) fn clean_human_output(output: &str) -> String {
     static ANSI_RE: OnceLock<Regex> = OnceLock::new();
     static ESCAPED_ANSI_RE: OnceLock<Regex> = OnceLock::new();
     static CONTROL_RE: OnceLock<Regex> = OnceLock::new();
@@ -383,6 +456,125 @@ fn health_from_activity(activity: i64, idle_warning: i64) -> &'static str {
     }
 }
 
+/// `[health]` with its patterns compiled, so agents are classified with
+/// their backend's thresholds and patterns as the dashboard does.
+pub(crate) struct HealthRules {
+    pub(crate) config: config::HealthConfig,
+    activity: ActivityDetector,
+    rate_limits: RateLimitDetector,
+}
+
+impl HealthRules {
+    pub(crate) fn new(config: config::HealthConfig) -> Self {
+        Self {
+            activity: ActivityDetector::from_config(&config),
+            rate_limits: RateLimitDetector::from_config(&config.rate_limit),
+            config,
+        }
+    }
+
+    /// Pane command of `session`, looked up only when some setting is
+    /// keyed by backend.
+    fn backend(&self, client: &dyn Mux, session: &str) -> Option<String> {
+        if self.config.backends.is_empty() && !self.rate_limits.has_backend_patterns() {
+            return None;
+        }
+        client.get_pane_command(session).ok()
+    }
+
+    /// `health_from_output` for `session`, resolving its backend.
+    fn classify(
+        &self,
+        client: &dyn Mux,
+        session: &str,
+        activity: i64,
+        output: &str,
+    ) -> &'static str {
+        let backend = self.backend(client, session);
+        health_from_output(activity, output, self, backend.as_deref())
+    }
+}
+
+/// Activity-based health, refined to finished/blocked when an idle pane
+/// ends on a completion marker, or rate_limited when recent output shows a
+/// provider rate-limit message. `backend`'s working/waiting patterns
+/// override the activity timestamp.
+pub(crate) fn health_from_output(
+    activity: i64,
+    output: &str,
+    rules: &HealthRules,
+    backend: Option<&str>,
+) -> &'static str {
+    if rules.rate_limits.detect(backend, output).is_some() {
+        return HealthState::RateLimited.as_str();
+    }
+    let health = match rules.activity.detect(backend, output) {
+        Some(Activity::Working) => HealthState::Running.as_str(),
+        Some(Activity::Waiting) => HealthState::Idle.as_str(),
+        None => health_from_activity(activity, rules.config.idle_warning_for(backend)),
+    };
+    if health != HealthState::Idle.as_str() {
+        return health;
+    }
+    match detect_result(output).map(|result| result.kind) {
+        Some(ResultKind::Complete) => HealthState::Finished.as_str(),
+        Some(ResultKind::Blocked | ResultKind::NeedInput) => HealthState::Blocked.as_str(),
+        None => health,
+    }
+}
+
+/// The question a quiet agent is waiting on; `None` while it works.
+fn pending_question(health: &str, output: &str) -> Option<String> {
+    matches!(health, "idle" | "blocked")
+        .then(|| detect_question(output))
+        .flatten()
+}
+
+/// Append an entry to the EA's `action_log.jsonl` audit trail. Returns the
+/// log path.
+pub(crate) fn append_action_log(
+    state_dir: &Path,
+    ea_id: EaId,
+    agent_name: &str,
+    action: &str,
+    justification: &str,
+    trace_id: Option<&str>,
+) -> Result<PathBuf> {
+    let _lock = FileLock::acquire(lock_path_for_state_dir(state_dir))?;
+    append_action_log_locked(
+        state_dir,
+        ea_id,
+        agent_name,
+        action,
+        justification,
+        trace_id,
+    )
+}
+
+/// `append_action_log` for callers already holding the state lock.
+fn append_action_log_locked(
+    state_dir: &Path,
+    ea_id: EaId,
+    agent_name: &str,
+    action: &str,
+    justification: &str,
+    trace_id: Option<&str>,
+) -> Result<PathBuf> {
+    let path = state_dir.join("action_log.jsonl");
+    fs::create_dir_all(state_dir).ok();
+    let line = serde_json::to_string(&json!({
+        "timestamp": now_rfc3339(),
+        "ea_id": ea_id,
+        "agent_name": agent_name,
+        "action": action,
+        "justification": justification,
+        "trace_id": trace_id,
+    }))?;
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", line)?;
+    Ok(path)
+}
+
 fn lock_path_for_state_dir(state_dir: &Path) -> PathBuf {
     state_dir.join(".mcp-state.lock")
 }
@@ -460,6 +652,12 @@ fn apply_context_environment(context: &McpLaunchContext) {
 /// backend launch and need a default context derived from the current
 /// config + active EA.
 pub fn run_server_with_default_context() -> Result<()> {
+    OmarMcpServer::new(default_context()?).run()
+}
+
+/// Context for the active EA under `$OMAR_DIR` (default `~/.omar`), built
+/// from its config.toml.
+pub fn default_context() -> Result<McpLaunchContext> {
     let omar_dir = std::env::var_os("OMAR_DIR")
         .map(PathBuf::from)
         .unwrap_or_else(|| {
@@ -472,7 +670,7 @@ pub fn run_server_with_default_context() -> Result<()> {
         .with_context(|| format!("Failed to load omar config for {}", omar_dir.display()))?;
     let registered = ea::ensure_default_ea(&omar_dir)?;
     let ea_id = resolve_default_context_ea(&omar_dir, &registered)?;
-    let context = McpLaunchContext {
+    Ok(McpLaunchContext {
         omar_dir,
         ea_id,
         session_prefix: config.dashboard.session_prefix,
@@ -483,8 +681,76 @@ pub fn run_server_with_default_context() -> Result<()> {
             .ok()
             .map(|server| server.trim().to_string())
             .filter(|server| !server.is_empty()),
+        sandbox: config.sandbox,
+        limits: config.limits,
+        prompts: config.prompts,
+    })
+}
+
+/// Tool calls answered in-process, without JSON-RPC framing or stdio.
+/// Used by `omar bench` and `omar web`.
+pub struct ToolCaller(OmarMcpServer);
+
+impl ToolCaller {
+    pub fn new(context: McpLaunchContext) -> Self {
+        Self(OmarMcpServer::new(context))
+    }
+
+    /// Call `name` as `tools/call` would, returning its structured result
+    /// or the tool's error message. Validation failures come back as a
+    /// `ValidationError`.
+    pub fn call(&self, name: &str, arguments: Value) -> Result<Value> {
+        let mut response = self.0.call_tool(ToolCallRequest {
+            name: name.to_string(),
+            arguments,
+        });
+        if response["isError"] == true {
+            if let Ok(invalid) =
+                serde_json::from_value::<ValidationError>(response["structuredContent"].take())
+            {
+                return Err(invalid.into());
+            }
+            let message = response["content"][0]["text"].as_str().unwrap_or_default();
+            return Err(anyhow!("{}", message));
+        }
+        Ok(response["structuredContent"].take())
+    }
+}
+
+/// Start the oldest queued spawn of `context`'s EA if the worker pool has
+/// room. Returns the agent name and spawn outcome, or `None` when nothing
+/// was started. Called by the dashboard off the UI thread.
+pub fn start_next_queued_spawn(
+    context: McpLaunchContext,
+    max_concurrent: Option<usize>,
+) -> Option<(String, Result<Value>)> {
+    let server = OmarMcpServer::new(context);
+    let state_dir = server.state_dir();
+    let next = {
+        let _lock = FileLock::acquire(lock_path_for_state_dir(state_dir)).ok()?;
+        if let Some(max) = max_concurrent {
+            let active =
+                spawn_queue::active_workers(&server.client(), server.manager_session(), state_dir)
+                    .ok()?;
+            if active >= max {
+                return None;
+            }
+        }
+        spawn_queue::pop_first_in(state_dir, |queued| {
+            queued.wait_for().is_none_or(|wait| server.wait_met(&wait))
+        })
+        .ok()??
     };
-    OmarMcpServer::new(context).run()
+    let outcome = server.spawn_agent_with(next.args, false);
+    Some((next.agent, outcome))
+}
+
+/// Run `edit` on an EA state directory while holding the MCP state lock,
+/// so dashboard edits to shared files (e.g. the spawn queue) cannot
+/// interleave with tool calls.
+pub fn with_state_lock<T>(state_dir: &Path, edit: impl FnOnce(&Path) -> Result<T>) -> Result<T> {
+    let _lock = FileLock::acquire(lock_path_for_state_dir(state_dir))?;
+    edit(state_dir)
 }
 
 /// Pick the EA id for a default-context server. Honors `OMAR_EA_ID` so
@@ -515,6 +781,7 @@ struct OmarMcpServer {
     session_prefix: String,
     manager_session: String,
     scheduler: scheduler::Scheduler,
+    firehose: Mutex<Firehose>,
 }
 
 impl OmarMcpServer {
@@ -530,6 +797,7 @@ impl OmarMcpServer {
             session_prefix,
             manager_session,
             scheduler,
+            firehose: Mutex::new(Firehose::default()),
         }
     }
 
@@ -593,7 +861,7 @@ impl OmarMcpServer {
                     "instructions": SERVER_INSTRUCTIONS,
                 }),
             ),
-            "tools/list" => ok_response(id, json!({ "tools": tool_definitions() })),
+            "tools/list" => ok_response(id, tool_schema_json()),
             "tools/call" => match serde_json::from_value::<ToolCallRequest>(request.params) {
                 Ok(call) => ok_response(id, self.call_tool(call)),
                 Err(err) => {
@@ -620,14 +888,51 @@ impl OmarMcpServer {
             "switch_ea" => self.switch_ea(call.arguments),
             "create_ea" => self.create_ea(call.arguments),
             "delete_ea" => self.delete_ea(call.arguments),
-            "list_agents" => self.list_agents(),
+            "list_agents" => self.list_agents(call.arguments),
+            "get_topology" => self.get_topology(call.arguments),
             "get_agent" => self.get_agent(call.arguments),
             "get_agent_summary" => self.get_agent_summary(call.arguments),
+            "get_agent_heartbeat" => self.get_agent_heartbeat(call.arguments),
+            "get_agent_history" => self.get_agent_history(call.arguments),
+            "exec_agent" => self.exec_agent(call.arguments),
+            "rollback_agent" => self.rollback_agent(call.arguments),
             "update_agent_status" => self.update_agent_status(call.arguments),
+            "report_progress" => self.report_progress(call.arguments),
+            "set_agent_note" => self.set_agent_note(call.arguments),
+            "set_agent_priority" => self.set_agent_priority(call.arguments),
+            "get_health_patterns" => self.get_health_patterns(),
+            "set_health_patterns" => self.set_health_patterns(call.arguments),
             "spawn_agent" => self.spawn_agent(call.arguments),
             "kill_agent" => self.kill_agent(call.arguments),
+            "rename_agent" => self.rename_agent(call.arguments),
+            "drain_fleet" => self.drain_fleet(call.arguments),
             "send_input" => self.send_input(call.arguments),
+            "send_message" => self.send_message(call.arguments),
+            "send_file" => self.send_file(call.arguments),
+            "get_messages" => self.get_messages(call.arguments),
+            "search_output" => self.search_output(call.arguments),
+            "tail_logs" => self.tail_logs(call.arguments),
+            "run_pipeline" => self.run_pipeline(call.arguments),
+            "list_pipelines" => self.list_pipelines(),
+            "list_runs" => self.list_runs(),
+            "refresh_manager_context" => self.refresh_manager_context(),
+            "list_pending_approvals" => self.list_pending_approvals(),
+            "list_schedules" => self.list_schedules(),
+            "get_memory_history" => self.get_memory_history(call.arguments),
+            "add_schedule" => self.add_schedule(call.arguments),
+            "remove_schedule" => self.remove_schedule(call.arguments),
+            "estimate_plan" => self.estimate_plan(call.arguments),
+            "get_plan" => self.get_plan(),
+            "approve_plan" => self.approve_plan(),
+            "reject_plan" => self.reject_plan(call.arguments),
+            "list_agent_files" => self.list_agent_files(call.arguments),
+            "read_agent_file" => self.read_agent_file(call.arguments),
+            "get_agent_transcript" => self.get_agent_transcript(call.arguments),
+            "resolve_approval" => self.resolve_approval(call.arguments),
             "list_projects" => self.list_projects(),
+            "add_project_item" => self.add_project_item(call.arguments),
+            "toggle_project_item" => self.toggle_project_item(call.arguments),
+            "sync_github_issues" => self.sync_github_issues(),
             "add_project" => self.add_project(call.arguments),
             "complete_project" => self.complete_project(call.arguments),
             "schedule_omar_event" => self.schedule_omar_event(call.arguments),
@@ -678,25 +983,31 @@ impl OmarMcpServer {
     }
 
     fn qualified_session_name(&self, short_or_full: &str) -> Result<String> {
-        let prefix = self.session_prefix();
-        let manager = self.manager_session();
-        if short_or_full == manager || short_or_full.starts_with(prefix) {
-            Ok(short_or_full.to_string())
-        } else {
-            Ok(format!("{}{}", prefix, short_or_full))
+        if short_or_full == self.manager_session() {
+            return Ok(short_or_full.to_string());
         }
+        Ok(naming::session_name(self.session_prefix(), short_or_full))
     }
 
     fn display_name<'a>(&self, session_name: &'a str) -> &'a str {
-        session_name
-            .strip_prefix(self.session_prefix())
-            .unwrap_or(session_name)
+        naming::short_name(self.session_prefix(), session_name)
     }
 
     fn client(&self) -> TmuxClient {
         TmuxClient::new(self.session_prefix())
     }
 
+    /// Local client on `dashboard.multiplexer`, for listing, checking and
+    /// killing agents. Prompt delivery and other tmux-only features stay
+    /// on `client`.
+    fn mux(&self) -> Arc<dyn Mux> {
+        let multiplexer = self
+            .load_config()
+            .map(|config| config.dashboard.multiplexer)
+            .unwrap_or_default();
+        tmux::mux_for(multiplexer, self.session_prefix())
+    }
+
     fn scheduler(&self) -> &scheduler::Scheduler {
         &self.scheduler
     }
@@ -705,7 +1016,7 @@ impl OmarMcpServer {
         let state_dir = self.state_dir();
         let prefix = self.session_prefix();
         let manager_session = self.manager_session();
-        let client = TmuxClient::new(prefix);
+        let client = tmux::mux_for(self.load_config()?.dashboard.multiplexer, prefix);
         let mut checker = HealthChecker::new(client.clone(), self.context.health_idle_warning);
         let sessions = client.list_sessions().unwrap_or_default();
         let mut manager = None;
@@ -727,7 +1038,7 @@ impl OmarMcpServer {
             &agents,
             manager.as_ref(),
             manager_session,
-            &client,
+            client.as_ref(),
             &self.scheduler().list_by_ea(self.ea_id()),
         );
         Ok(())
@@ -839,7 +1150,7 @@ impl OmarMcpServer {
 
         let prefix = ea::ea_prefix(args.ea_id, &self.context.session_prefix);
         let manager_session = ea::ea_manager_session(args.ea_id, &self.context.session_prefix);
-        let client = TmuxClient::new(&prefix);
+        let client = tmux::mux_for(self.load_config()?.dashboard.multiplexer, &prefix);
         let sessions = client.list_sessions().unwrap_or_default();
         let all_sessions = client.list_all_sessions().unwrap_or_default();
         let mut session_names = Vec::new();
@@ -901,24 +1212,176 @@ impl OmarMcpServer {
         }))
     }
 
-    fn list_agents(&self) -> Result<Value> {
-        let client = self.client();
+    fn list_agents(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            tag: Option<String>,
+        }
+        // Clients may call list_agents without any arguments
+        let tag = serde_json::from_value::<Option<Args>>(args)?.and_then(|args| args.tag);
         let manager_session = self.manager_session();
-        let sessions = client.list_sessions()?;
-        let agents: Vec<Value> = sessions
-            .iter()
-            .filter(|s| s.name != manager_session)
-            .map(|s| {
+        let local = self.mux();
+        let mut listings = vec![(local.list_sessions()?, local)];
+        // Unreachable hosts are skipped rather than failing the listing.
+        for remote in self.remote_clients() {
+            if let Ok(sessions) = remote.list_sessions() {
+                listings.push((sessions, remote));
+            }
+        }
+        let rules = self.health_rules();
+        let spawn_statuses = memory::load_spawn_statuses_from(self.state_dir());
+        let agent_tags = memory::load_agent_tags_from(self.state_dir());
+        let priorities = memory::load_agent_priorities_from(self.state_dir());
+        let progress = memory::load_agent_progress_from(self.state_dir());
+        let notes = memory::load_agent_notes_from(self.state_dir());
+        let roles = memory::load_agent_roles_from(self.state_dir());
+        let traces = memory::load_agent_traces_from(self.state_dir());
+        let no_tags = Vec::new();
+        let mut agents = Vec::new();
+        let mut health = std::collections::HashMap::new();
+        for (sessions, client) in &listings {
+            for s in sessions.iter().filter(|s| s.name != manager_session) {
+                let tags = agent_tags.get(&s.name).unwrap_or(&no_tags);
+                if let Some(tag) = tag.as_deref() {
+                    if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                        continue;
+                    }
+                }
                 let output =
                     clean_human_output(&client.capture_pane_plain(&s.name, 50).unwrap_or_default());
+                let state = rules.classify(client.as_ref(), &s.name, s.activity, &output);
+                if let Some(state) = HealthState::parse(state) {
+                    health.insert(s.name.clone(), state);
+                }
+                agents.push((
+                    s.name.clone(),
+                    json!({
+                        "id": self.display_name(&s.name),
+                        "host": s.host,
+                        "health": state,
+                        "spawn_status": spawn_statuses.get(&s.name),
+                        "tags": tags,
+                        "priority": priorities.get(&s.name).copied().unwrap_or_default(),
+                        "role": roles.get(&s.name).copied().unwrap_or_default(),
+                        "trace_id": traces.get(&s.name),
+                        "last_activity": secs_rfc3339(s.activity),
+                        "last_output": last_output_line(&output),
+                        "pending_question": pending_question(state, &output),
+                        "progress": progress.get(&s.name),
+                        "note": notes.get(&s.name),
+                    }),
+                ));
+            }
+        }
+        let teams = team_health(&health, &memory::load_agent_parents_from(self.state_dir()));
+        let agents: Vec<Value> = agents
+            .into_iter()
+            .map(|(session, mut agent)| {
+                if let Some(team) = teams.get(&session) {
+                    agent["team_health"] = json!(team.as_str());
+                }
+                agent
+            })
+            .collect();
+        let queued: Vec<Value> = spawn_queue::load_queue_in(self.state_dir())
+            .iter()
+            .enumerate()
+            .map(|(idx, queued)| {
                 json!({
-                    "id": self.display_name(&s.name),
-                    "health": health_from_activity(s.activity, self.context.health_idle_warning),
-                    "last_output": last_output_line(&output),
+                    "id": queued.agent,
+                    "queue_position": idx + 1,
+                    "queued_at": queued.queued_at,
+                    "wait_for": queued.wait_for(),
                 })
             })
             .collect();
-        Ok(json!({ "agents": agents }))
+        Ok(json!({ "agents": agents, "queued": queued }))
+    }
+
+    fn get_topology(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            #[serde(default)]
+            format: String,
+        }
+        let format = serde_json::from_value::<Option<Args>>(args)?
+            .map(|args| args.format)
+            .unwrap_or_default();
+        let format = topology::Format::parse(&format)?;
+        let short = |session: &str| {
+            if session == self.manager_session() {
+                topology::EA.to_string()
+            } else {
+                self.display_name(session).to_string()
+            }
+        };
+        let listing = self.list_agents(Value::Null)?;
+        let agents: Vec<(String, Option<String>)> = listing["agents"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|agent| {
+                (
+                    agent["id"].as_str().unwrap_or_default().to_string(),
+                    agent["health"].as_str().map(String::from),
+                )
+            })
+            .collect();
+        let parents = memory::load_agent_parents_from(self.state_dir())
+            .iter()
+            .map(|(child, parent)| (short(child), short(parent)))
+            .collect();
+        let depends: Vec<(String, String)> = pipeline::load_runs_from(self.state_dir())
+            .iter()
+            .flat_map(|run| {
+                run.stages
+                    .windows(2)
+                    .filter_map(|pair| {
+                        Some((
+                            short(pair[1].agent.as_deref()?),
+                            short(pair[0].agent.as_deref()?),
+                        ))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        let messages: Vec<(String, String)> = mailbox::all_in(self.state_dir())
+            .iter()
+            .flat_map(|(session, messages)| {
+                messages
+                    .iter()
+                    .map(|message| (message.from.clone(), short(session)))
+            })
+            .collect();
+        let graph = topology::Topology::build(&agents, &parents, &depends, &messages);
+        let mut result = json!({ "nodes": graph.nodes, "edges": graph.edges });
+        match format {
+            topology::Format::Json => {}
+            topology::Format::Dot => result["graph"] = json!(graph.to_dot()),
+            topology::Format::Mermaid => result["graph"] = json!(graph.to_mermaid()),
+        }
+        Ok(result)
+    }
+
+    /// Clients for the `[[hosts]]` in config.toml, scoped to this EA.
+    fn remote_clients(&self) -> Vec<Arc<dyn Mux>> {
+        let hosts = self.load_config().map(|c| c.hosts).unwrap_or_default();
+        hosts
+            .into_iter()
+            .map(|host| -> Arc<dyn Mux> {
+                // Hosts are always reached through tmux over SSH
+                Arc::new(TmuxClient::new(self.session_prefix()).with_remote(
+                    RemoteHost {
+                        name: host.name,
+                        ssh: host.ssh,
+                        control_path:
+                            host.control_path.map(|path| {
+                                config::expand_tilde(&path).to_string_lossy().to_string()
+                            }),
+                    },
+                ))
+            })
+            .collect()
     }
 
     fn get_agent(&self, args: Value) -> Result<Value> {
@@ -927,21 +1390,89 @@ impl OmarMcpServer {
             name: String,
         }
         let args: Args = serde_json::from_value(args)?;
-        let client = self.client();
         let session_name = self.qualified_session_name(&args.name)?;
+        let local = self.mux();
+        let client = if local.has_session(&session_name).unwrap_or(false) {
+            local
+        } else {
+            self.remote_clients()
+                .into_iter()
+                .find(|remote| remote.has_session(&session_name).unwrap_or(false))
+                .unwrap_or(local)
+        };
         let output_tail = client
             .capture_pane_plain(&session_name, 200)
             .map_err(|_| anyhow!("Agent '{}' not found", args.name))?;
         let output_tail = clean_human_output(&output_tail);
         let activity = client.get_pane_activity(&session_name).unwrap_or_default();
+        let health =
+            self.health_rules()
+                .classify(client.as_ref(), &session_name, activity, &output_tail);
         Ok(json!({
             "id": self.display_name(&session_name),
-            "health": health_from_activity(activity, self.context.health_idle_warning),
+            "host": client.host(),
+            "health": health,
+            "spawn_status": memory::load_spawn_statuses_from(self.state_dir()).remove(&session_name),
+            "tags": memory::load_agent_tags_from(self.state_dir()).remove(&session_name).unwrap_or_default(),
+            "priority": memory::load_agent_priorities_from(self.state_dir()).remove(&session_name).unwrap_or_default(),
+            "role": memory::load_agent_roles_from(self.state_dir()).remove(&session_name).unwrap_or_default(),
+            "trace_id": memory::load_agent_traces_from(self.state_dir()).remove(&session_name),
+            "last_activity": secs_rfc3339(activity),
             "last_output": last_output_line(&output_tail),
+            "pending_question": pending_question(health, &output_tail),
+            "progress": memory::load_agent_progress_from(self.state_dir()).remove(&session_name),
+            "note": memory::load_agent_notes_from(self.state_dir()).remove(&session_name),
             "output_tail": output_tail,
         }))
     }
 
+    fn exec_agent(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            name: String,
+            command: String,
+            timeout_secs: Option<u64>,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        if args.command.trim().is_empty() {
+            return Err(anyhow!("exec_agent requires a non-empty 'command'"));
+        }
+        let session_name = self.qualified_session_name(&args.name)?;
+        let launch = exec::launch_for(self.state_dir(), &session_name)?;
+        let timeout = Duration::from_secs(args.timeout_secs.unwrap_or(30).clamp(1, 600));
+        let output = exec::run(&self.context.sandbox, &launch, &args.command, timeout)?;
+        let mut value = serde_json::to_value(output)?;
+        value["id"] = json!(self.display_name(&session_name));
+        value["workdir"] = json!(launch.workdir);
+        Ok(value)
+    }
+
+    fn rollback_agent(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            name: String,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        let session_name = self.qualified_session_name(&args.name)?;
+        let saved = memory::load_agent_checkpoints_from(self.state_dir())
+            .remove(&session_name)
+            .ok_or_else(|| {
+                anyhow!(
+                    "No checkpoint for '{}'; set agent.checkpoint_workdir = true before spawning",
+                    args.name
+                )
+            })?;
+        let result = checkpoint::rollback(&saved)?;
+        Ok(json!({
+            "id": self.display_name(&session_name),
+            "repo": saved.repo,
+            "checkpoint": saved.commit,
+            "checkpoint_at": secs_rfc3339(saved.created_at),
+            "restored": result.restored,
+            "removed": result.removed,
+        }))
+    }
+
     fn get_agent_summary(&self, args: Value) -> Result<Value> {
         #[derive(Deserialize)]
         struct Args {
@@ -949,7 +1480,7 @@ impl OmarMcpServer {
         }
         let args: Args = serde_json::from_value(args)?;
         let state_dir = self.state_dir();
-        let client = self.client();
+        let client = self.mux();
         let session_name = self.qualified_session_name(&args.name)?;
         if !client.has_session(&session_name).unwrap_or(false) {
             return Err(anyhow!("Agent '{}' not found", args.name));
@@ -971,16 +1502,112 @@ impl OmarMcpServer {
             })
             .collect();
         let activity = client.get_pane_activity(&session_name).unwrap_or(0);
-        let health = health_from_activity(activity, self.context.health_idle_warning);
+        let output = clean_human_output(
+            &client
+                .capture_pane_plain(&session_name, 50)
+                .unwrap_or_default(),
+        );
+        let health =
+            self.health_rules()
+                .classify(client.as_ref(), &session_name, activity, &output);
+        let result = memory::load_agent_results_from(state_dir).remove(&session_name);
+        let team_health = self.team_health_of(client.as_ref(), &session_name, &agent_parents);
         Ok(json!({
             "id": short_name,
             "health": health,
+            "team_health": team_health,
             "task": task,
             "status": memory::load_agent_status_in(state_dir, &session_name),
+            "progress": memory::load_agent_progress_from(state_dir).remove(&session_name),
+            "result": result,
             "children": children,
         }))
     }
 
+    fn get_agent_heartbeat(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            name: String,
+            #[serde(default)]
+            fail_if: Option<String>,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        let conditions = heartbeat::FailIf::parse_list(args.fail_if.as_deref().unwrap_or(""))?;
+        let session_name = self.qualified_session_name(&args.name)?;
+        let rules = HealthRules::new(self.load_config()?.health);
+        let client = self.mux();
+        let backend = client.get_pane_command(&session_name).ok();
+        let heartbeat = heartbeat::probe(
+            client.as_ref(),
+            &session_name,
+            self.display_name(&session_name),
+            &rules,
+            backend.as_deref(),
+        );
+        Ok(heartbeat.report(&conditions))
+    }
+
+    fn get_agent_history(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            name: String,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        let session_name = self.qualified_session_name(&args.name)?;
+        let history = health_history::load(&self.context.omar_dir)
+            .remove(&session_name)
+            .unwrap_or_default();
+        if history.is_empty() && !self.client().has_session(&session_name).unwrap_or(false) {
+            return Err(anyhow!("Agent '{}' not found", args.name));
+        }
+        let since = chrono::Utc::now().timestamp() - health_history::TIMELINE_WINDOW_SECS;
+        Ok(json!({
+            "id": self.display_name(&session_name),
+            "state": history.last().map(|t| t.state.as_str()),
+            "changes_last_10m": health_history::changes_since(&history, since),
+            "transitions": history.iter().map(|t| json!({
+                "at": secs_rfc3339(t.at),
+                "state": t.state.as_str(),
+            })).collect::<Vec<_>>(),
+        }))
+    }
+
+    /// Worst health among `session`'s local descendants, if it has any.
+    fn team_health_of(
+        &self,
+        client: &dyn Mux,
+        session: &str,
+        parents: &std::collections::HashMap<String, String>,
+    ) -> Option<&'static str> {
+        let is_descendant = |name: &str| {
+            let mut current = parents.get(name);
+            for _ in 0..parents.len() {
+                match current {
+                    Some(parent) if parent == session => return true,
+                    Some(parent) => current = parents.get(parent),
+                    None => return false,
+                }
+            }
+            false
+        };
+        let rules = self.health_rules();
+        let health = client
+            .list_sessions()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| is_descendant(&s.name))
+            .filter_map(|s| {
+                let output =
+                    clean_human_output(&client.capture_pane_plain(&s.name, 50).unwrap_or_default());
+                let state = rules.classify(client, &s.name, s.activity, &output);
+                Some((s.name, HealthState::parse(state)?))
+            })
+            .collect();
+        team_health(&health, parents)
+            .get(session)
+            .map(|team| team.as_str())
+    }
+
     fn update_agent_status(&self, args: Value) -> Result<Value> {
         #[derive(Deserialize)]
         struct Args {
@@ -1000,85 +1627,474 @@ impl OmarMcpServer {
         Ok(json!({ "status": "updated" }))
     }
 
-    /// The single MCP spawn path. Requires an existing `project_id`.
-    fn spawn_agent(&self, args: Value) -> Result<Value> {
+    fn set_agent_note(&self, args: Value) -> Result<Value> {
         #[derive(Deserialize)]
         struct Args {
             name: String,
-            #[serde(deserialize_with = "flex_int::deserialize_usize")]
-            project_id: usize,
-            task: Option<String>,
-            workdir: Option<String>,
-            command: Option<String>,
-            backend: Option<String>,
-            model: Option<String>,
-            reasoning_effort: Option<String>,
-            parent: Option<String>,
+            note: String,
         }
         let args: Args = serde_json::from_value(args)?;
-        let spawn_start = std::time::Instant::now();
         let state_dir = self.state_dir();
-        let ea_id = self.ea_id();
-        let prefix = self.session_prefix();
-        let manager_session = self.manager_session();
-        let client = self.client();
-
-        let lock_wait_start = std::time::Instant::now();
         let _lock = FileLock::acquire(lock_path_for_state_dir(state_dir))?;
-        let spawn_lock_wait_ms = lock_wait_start.elapsed().as_millis() as u64;
-
-        // Project must already exist. add_project owns creation; this path
-        // never auto-creates.
-        let project = projects::find_project_in(state_dir, args.project_id).ok_or_else(|| {
-            anyhow!(
-                "Project '{}' not found. Call add_project first to register a project.",
-                args.project_id
-            )
-        })?;
-        let project_id = project.id;
-        let project_name = project.name.clone();
-
-        let task = args
-            .task
-            .as_deref()
-            .map(str::trim)
-            .filter(|task| !task.is_empty())
-            .ok_or_else(|| anyhow!("spawn_agent requires a non-empty 'task'"))?
-            .to_string();
+        let session_name = self.qualified_session_name(&args.name)?;
+        if !self.client().has_session(&session_name).unwrap_or(false) {
+            return Err(anyhow!("Agent '{}' not found", args.name));
+        }
+        memory::save_agent_note_in(state_dir, &session_name, &args.note);
+        self.refresh_memory_locked()?;
+        let cleared = args.note.trim().is_empty();
+        Ok(json!({ "status": if cleared { "cleared" } else { "updated" } }))
+    }
 
-        let parent = match args.parent.as_deref().map(str::trim) {
-            Some("") => return Err(anyhow!("spawn_agent parent must not be empty")),
-            Some(parent) => Some(parent.to_string()),
-            None => None,
+    fn report_progress(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            name: String,
+            percent: Option<u8>,
+            step: Option<String>,
+            #[serde(default)]
+            blockers: Vec<String>,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        if args.percent.is_some_and(|percent| percent > 100) {
+            return Err(anyhow!("percent must be between 0 and 100"));
+        }
+        let step = args
+            .step
+            .map(|step| step.trim().to_string())
+            .filter(|step| !step.is_empty());
+        let blockers: Vec<String> = args
+            .blockers
+            .iter()
+            .map(|blocker| blocker.trim().to_string())
+            .filter(|blocker| !blocker.is_empty())
+            .collect();
+        if args.percent.is_none() && step.is_none() && blockers.is_empty() {
+            return Err(anyhow!("Report at least one of percent, step, or blockers"));
+        }
+        let session_name = self.qualified_session_name(&args.name)?;
+        if !self.client().has_session(&session_name).unwrap_or(false) {
+            return Err(anyhow!("Agent '{}' not found", args.name));
+        }
+        let report = memory::ProgressReport {
+            percent: args.percent,
+            step,
+            blockers,
+            reported_at: chrono::Utc::now().to_rfc3339(),
         };
-        self.validate_spawn_parent(project_id, parent.as_deref())?;
+        memory::save_agent_progress_in(self.state_dir(), &session_name, &report);
+        Ok(json!({ "status": "reported", "progress": report }))
+    }
 
-        let session_name = match args.name.trim() {
-            n if !n.is_empty() => {
-                let stripped = n.strip_prefix(prefix).unwrap_or(n);
-                format!("{}{}", prefix, stripped)
-            }
-            _ => generate_agent_name_in_ea(prefix),
-        };
-        let short_name = self.display_name(&session_name).to_string();
-        let parent_session = match parent.as_deref() {
-            Some("ea") | None => manager_session.to_string(),
-            Some(p) => self.qualified_session_name(p)?,
-        };
-        let prompt_parent = if parent_session == manager_session {
-            "ea".to_string()
-        } else {
-            self.display_name(&parent_session).to_string()
-        };
+    fn set_agent_priority(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            name: String,
+            priority: String,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        let priority = Priority::parse(&args.priority)?;
+        let state_dir = self.state_dir();
+        let _lock = FileLock::acquire(lock_path_for_state_dir(state_dir))?;
+        let session_name = self.qualified_session_name(&args.name)?;
+        if !self.client().has_session(&session_name).unwrap_or(false) {
+            return Err(anyhow!("Agent '{}' not found", args.name));
+        }
+        memory::save_agent_priority_in(state_dir, &session_name, priority);
+        Ok(json!({ "id": self.display_name(&session_name), "priority": priority }))
+    }
 
-        if args.backend.is_some() && args.command.is_some() {
-            return Err(anyhow!("Cannot specify both 'backend' and 'command'"));
+    fn get_health_patterns(&self) -> Result<Value> {
+        Ok(health_patterns_json(&self.load_config()?))
+    }
+
+    /// Replace the given pattern lists in config.toml, leaving the rest of
+    /// the file as written. The dashboard picks them up on its next
+    /// refresh; spawns and `get_agent` read the config on every call.
+    fn set_health_patterns(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Args {
+            rate_limit: Option<Vec<String>>,
+            rate_limit_backends: Option<std::collections::BTreeMap<String, Vec<String>>>,
+            ready: Option<std::collections::BTreeMap<String, String>>,
         }
-        let mut base_command = if let Some(backend) = args.backend.as_deref() {
-            config::resolve_backend(backend).map_err(|err| anyhow!(err))?
+        let args: Args = serde_json::from_value(args).map_err(ValidationError::from_serde)?;
+        let mut invalid = ValidationError::default();
+        let blank = |pattern: &String| pattern.trim().is_empty();
+        if args.rate_limit.iter().flatten().any(blank) {
+            invalid.push("rate_limit", "invalid", "Patterns must not be empty");
+        }
+        if args
+            .rate_limit_backends
+            .iter()
+            .flatten()
+            .any(|(_, p)| p.iter().any(blank))
+        {
+            invalid.push(
+                "rate_limit_backends",
+                "invalid",
+                "Patterns must not be empty",
+            );
+        }
+        if args.ready.iter().flatten().any(|(_, p)| blank(p)) {
+            invalid.push("ready", "invalid", "Patterns must not be empty");
+        }
+        invalid.check()?;
+
+        let path = self.context.omar_dir.join("config.toml");
+        config::edit_file(&path, |doc| {
+            let array = |patterns: &[String]| {
+                toml_edit::value(patterns.iter().collect::<toml_edit::Array>())
+            };
+            if let Some(patterns) = &args.rate_limit {
+                config::section(doc, &["health", "rate_limit"])["patterns"] = array(patterns);
+            }
+            if let Some(backends) = &args.rate_limit_backends {
+                let table = config::section(doc, &["health", "rate_limit", "backends"]);
+                table.clear();
+                for (backend, patterns) in backends {
+                    table[backend.as_str()] = array(patterns);
+                }
+            }
+            if let Some(ready) = &args.ready {
+                let table = config::section(doc, &["agent", "readiness", "patterns"]);
+                table.clear();
+                for (backend, pattern) in ready {
+                    table[backend.as_str()] = toml_edit::value(pattern.as_str());
+                }
+            }
+        })?;
+        Ok(health_patterns_json(&self.load_config()?))
+    }
+
+    /// The single MCP spawn path. Requires an existing `project_id`.
+    fn spawn_agent(&self, args: Value) -> Result<Value> {
+        self.spawn_agent_with(args, true)
+    }
+
+    /// Whether a spawn's `wait_for` dependency is met in this EA.
+    fn wait_met(&self, wait: &WaitFor) -> bool {
+        let state_dir = self.state_dir();
+        let results = memory::load_agent_results_from(state_dir);
+        let client = self.client();
+        wait.is_met(|agent, state| {
+            let Ok(session) = self.qualified_session_name(agent) else {
+                return false;
+            };
+            match state {
+                WaitState::Complete => {
+                    results
+                        .get(&session)
+                        .is_some_and(|result| matches!(result.kind, ResultKind::Complete))
+                        || memory::has_completed_in(state_dir, &session)
+                }
+                WaitState::Exited => {
+                    !client.has_session(&session).unwrap_or(true)
+                        && !spawn_queue::load_queue_in(state_dir)
+                            .iter()
+                            .any(|queued| queued.agent == agent)
+                }
+            }
+        })
+    }
+
+    /// Whether `agent` can satisfy a `wait_for`: it is live, queued, or
+    /// already completed. Anything else is most likely a typo.
+    fn wait_target_known(&self, client: &TmuxClient, state_dir: &Path, agent: &str) -> bool {
+        let Ok(session) = self.qualified_session_name(agent) else {
+            return false;
+        };
+        client.has_session(&session).unwrap_or(false)
+            || memory::has_completed_in(state_dir, &session)
+            || spawn_queue::load_queue_in(state_dir)
+                .iter()
+                .any(|queued| queued.agent == agent)
+    }
+
+    /// `gated` applies `agent.max_concurrent`; queued requests are replayed
+    /// ungated once a slot is free.
+    fn spawn_agent_with(&self, args: Value, gated: bool) -> Result<Value> {
+        let raw_args = args.clone();
+        #[derive(Deserialize)]
+        struct Args {
+            name: String,
+            #[serde(deserialize_with = "flex_int::deserialize_usize")]
+            project_id: usize,
+            task: Option<String>,
+            workdir: Option<String>,
+            command: Option<String>,
+            backend: Option<String>,
+            model: Option<String>,
+            reasoning_effort: Option<String>,
+            parent: Option<String>,
+            #[serde(default)]
+            tags: Vec<String>,
+            priority: Option<String>,
+            #[serde(default)]
+            secrets: Vec<String>,
+            #[serde(default)]
+            panes: Vec<String>,
+            trace_id: Option<String>,
+            profile: Option<String>,
+            template: Option<String>,
+            #[serde(default)]
+            template_vars: std::collections::BTreeMap<String, String>,
+            #[serde(default)]
+            env: std::collections::BTreeMap<String, String>,
+            role: Option<String>,
+            wait_for: Option<WaitFor>,
+        }
+        let args: Args = serde_json::from_value(args).map_err(ValidationError::from_serde)?;
+        if drain::load(&self.context.omar_dir).is_some() {
+            return Err(anyhow!(
+                "OMAR is draining: no new agents are started until the drain is cancelled"
+            ));
+        }
+        let spawn_start = std::time::Instant::now();
+        let state_dir = self.state_dir();
+        let ea_id = self.ea_id();
+        let prefix = self.session_prefix();
+        let manager_session = self.manager_session();
+        let client = self.client();
+
+        let lock_wait_start = std::time::Instant::now();
+        let _lock = FileLock::acquire(lock_path_for_state_dir(state_dir))?;
+        let spawn_lock_wait_ms = lock_wait_start.elapsed().as_millis() as u64;
+
+        // Check every field before acting, so one error lists everything
+        // wrong with the request
+        let mut invalid = ValidationError::default();
+        let id = match args.name.trim() {
+            n if !n.is_empty() => AgentId::resolve(prefix, n),
+            _ => generate_agent_name_in_ea(prefix),
+        };
+        let short_name = id.short().to_string();
+        let session_name = id.into_session();
+        if short_name
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '.' | ':'))
+        {
+            invalid.push(
+                "name",
+                "invalid",
+                format!(
+                    "Agent name '{}' must not contain whitespace, '.' or ':'",
+                    short_name
+                ),
+            );
+        } else if client.has_session(&session_name).unwrap_or(false) {
+            invalid.push(
+                "name",
+                "exists",
+                format!("Agent '{}' already exists", short_name),
+            );
+        }
+
+        // Project must already exist. add_project owns creation; this path
+        // never auto-creates.
+        let project = projects::find_project_in(state_dir, args.project_id);
+        if project.is_none() {
+            invalid.push(
+                "project_id",
+                "not_found",
+                format!(
+                    "Project '{}' not found. Call add_project first to register a project.",
+                    args.project_id
+                ),
+            );
+        }
+        let task = args
+            .task
+            .as_deref()
+            .map(str::trim)
+            .filter(|task| !task.is_empty());
+        if task.is_none() {
+            invalid.push("task", "missing", "spawn_agent requires a non-empty 'task'");
+        }
+        let parent = args.parent.as_deref().map(str::trim);
+        if parent == Some("") {
+            invalid.push("parent", "invalid", "spawn_agent parent must not be empty");
+        } else if project.is_some() {
+            if let Err(e) = self.validate_spawn_parent(args.project_id, parent) {
+                invalid.push("parent", "invalid", e.to_string());
+            }
+        }
+
+        if args.backend.is_some() && args.command.is_some() {
+            invalid.push(
+                "command",
+                "conflict",
+                "Cannot specify both 'backend' and 'command'",
+            );
+        } else if let Some(Err(e)) = args.backend.as_deref().map(config::resolve_backend) {
+            invalid.push("backend", "invalid", e);
+        }
+        if let Some(Err(e)) = args.model.as_deref().map(validate_model_name) {
+            invalid.push("model", "invalid", e.to_string());
+        }
+        if let Some(effort) = args.reasoning_effort.as_deref() {
+            if let Err(e) = validate_reasoning_effort(effort) {
+                invalid.push("reasoning_effort", "invalid", e.to_string());
+            } else if args.command.is_some() || args.backend.as_deref() != Some("codex") {
+                invalid.push(
+                    "reasoning_effort",
+                    "conflict",
+                    "reasoning_effort is only supported when spawn_agent uses backend='codex'.",
+                );
+            }
+        }
+        let settings = self.load_config()?;
+        let profile = match args.profile.as_deref() {
+            Some(name) => {
+                let profile = settings.profile(name).cloned();
+                if profile.is_none() {
+                    let names: Vec<&str> =
+                        settings.profiles.iter().map(|p| p.name.as_str()).collect();
+                    invalid.push(
+                        "profile",
+                        "not_found",
+                        format!(
+                            "Profile '{}' not found. Configured: {}",
+                            name,
+                            if names.is_empty() {
+                                "none".to_string()
+                            } else {
+                                names.join(", ")
+                            }
+                        ),
+                    );
+                }
+                profile
+            }
+            None => None,
+        };
+        let requested_workdir = args
+            .workdir
+            .clone()
+            .or_else(|| profile.as_ref().and_then(|p| p.workdir.clone()));
+        if let Some(workdir) = requested_workdir.as_deref() {
+            if !config::expand_tilde(workdir).is_dir() {
+                invalid.push(
+                    "workdir",
+                    "not_found",
+                    format!("Working directory '{}' does not exist", workdir),
+                );
+            }
+        }
+        let priority = match args.priority.as_deref().map(Priority::parse).transpose() {
+            Ok(priority) => priority.unwrap_or_default(),
+            Err(e) => {
+                invalid.push("priority", "invalid", e.to_string());
+                Priority::Normal
+            }
+        };
+        let role = match args.role.as_deref().map(AgentRole::parse).transpose() {
+            Ok(role) => role.unwrap_or_default(),
+            Err(e) => {
+                invalid.push("role", "invalid", e.to_string());
+                AgentRole::Worker
+            }
+        };
+        if let Err(e) = secrets::check_names(&settings.secrets, &args.secrets) {
+            invalid.push("secrets", "invalid", e.to_string());
+        }
+        if args.panes.iter().any(|pane| pane.trim().is_empty()) {
+            invalid.push("panes", "invalid", "Pane commands must not be empty");
+        }
+        if let Some(wait) = &args.wait_for {
+            if let Err(e) = wait.validate() {
+                invalid.push("wait_for", "invalid", e);
+            } else if wait.agent.as_deref().map(str::trim) == Some(short_name.as_str()) {
+                invalid.push("wait_for", "invalid", "An agent cannot wait for itself");
+            } else if let Some(agent) = wait.agent.as_deref().map(str::trim).filter(|_| gated) {
+                // Replayed requests skip this: an `exited` target is gone by then
+                if !self.wait_target_known(&client, state_dir, agent) {
+                    invalid.push(
+                        "wait_for",
+                        "not_found",
+                        format!("Agent '{}' is not running, queued, or complete", agent),
+                    );
+                }
+            }
+        }
+        let mut env = settings.agent.env.clone();
+        env.extend(args.env.clone());
+        if let Some(key) = env.keys().find(|key| !secrets::is_env_name(key)) {
+            invalid.push(
+                "env",
+                "invalid",
+                format!("'{}' is not a valid environment variable name", key),
+            );
+        }
+        if let Some(trace_id) = args.trace_id.as_deref() {
+            if !valid_trace_id(trace_id) {
+                invalid.push(
+                    "trace_id",
+                    "invalid",
+                    "trace_id must be 1-64 letters, digits, '-' or '_'",
+                );
+            }
+        }
+        // Render the template against the directory the worker will run in
+        let rendered_task = match args.template.as_deref() {
+            Some(name) => match templates::load(&self.context.omar_dir, name) {
+                Ok(template) => {
+                    let repo = requested_workdir
+                        .as_deref()
+                        .map(config::expand_tilde)
+                        .unwrap_or_else(|| PathBuf::from(&self.context.default_workdir));
+                    let mut vars = templates::builtin_vars(task.unwrap_or_default(), &repo);
+                    vars.extend(args.template_vars.clone());
+                    match templates::render(&template, &vars) {
+                        Ok(rendered) => Some(rendered),
+                        Err(e) => {
+                            invalid.push("template_vars", "missing", e.to_string());
+                            None
+                        }
+                    }
+                }
+                Err(e) => {
+                    invalid.push("template", "not_found", e.to_string());
+                    None
+                }
+            },
+            None => None,
+        };
+        invalid.check()?;
+
+        let project_id = args.project_id;
+        let project_name = project.map(|p| p.name).unwrap_or_default();
+        let task = task.unwrap_or_default().to_string();
+        // What the worker is told to do; `task` stays the short summary
+        let prompt_task = rendered_task.unwrap_or_else(|| task.clone());
+        let parent = parent.map(str::to_string);
+
+        let mut tags: Vec<String> = Vec::new();
+        for tag in args.tags.iter().map(|tag| tag.trim()) {
+            if !tag.is_empty() && !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.to_string());
+            }
+        }
+        let parent_session = naming::resolve_address(
+            parent.as_deref().unwrap_or(naming::EA),
+            manager_session,
+            prefix,
+        );
+        let prompt_parent = naming::address(&parent_session, manager_session, prefix);
+        // Workers spawned by a traced worker join its trace
+        let trace_id = args
+            .trace_id
+            .clone()
+            .or_else(|| memory::load_agent_traces_from(state_dir).remove(&parent_session))
+            .unwrap_or_else(new_trace_id);
+
+        let mut base_command = if let Some(backend) = args.backend.as_deref() {
+            config::resolve_backend(backend).map_err(|err| anyhow!(err))?
         } else {
             args.command
                 .clone()
+                .or_else(|| profile.as_ref().and_then(|p| p.command.clone()))
                 .unwrap_or_else(|| self.context.default_command.clone())
         };
         base_command = apply_spawn_agent_command_overrides(
@@ -1092,17 +2108,23 @@ impl OmarMcpServer {
         let backend_name = infer_backend_name(args.backend.as_deref(), &base_command);
         let supports_prompt_delivery = supports_initial_prompt_delivery(&backend_name);
 
-        let workdir = args
-            .workdir
-            .unwrap_or_else(|| self.context.default_workdir.clone());
+        let workdir = match (args.workdir, requested_workdir) {
+            (Some(workdir), _) => workdir,
+            (None, Some(workdir)) => config::expand_tilde(&workdir).to_string_lossy().to_string(),
+            (None, None) => self.context.default_workdir.clone(),
+        };
+        let sandbox_config = match &profile {
+            Some(profile) => profile.sandbox_config(&self.context.sandbox),
+            None => self.context.sandbox.clone(),
+        };
         let command = if supports_prompt_delivery {
-            let prompt_file = manager::prompts_dir(&self.context.omar_dir).join("agent.md");
+            let prompt_file = manager::prompt_template(&self.context, "agent.md");
             manager::build_agent_command(
                 &base_command,
                 &prompt_file,
                 &[
                     ("{{PARENT_NAME}}", &prompt_parent),
-                    ("{{TASK}}", &task),
+                    ("{{TASK}}", &prompt_task),
                     ("{{EA_ID}}", &ea_id.to_string()),
                 ],
                 &self.context,
@@ -1111,91 +2133,224 @@ impl OmarMcpServer {
             base_command.clone()
         };
 
-        if client.has_session(&session_name).unwrap_or(false) {
-            return Err(anyhow!("Agent '{}' already exists", short_name));
+        let mut launch = memory::AgentLaunch {
+            command: command.clone(),
+            workdir: workdir.clone(),
+            sandboxed: sandbox::provider_for(&sandbox_config)?.is_some(),
+            security: sandbox::tamper::expected_security(&sandbox_config)?,
+            pod: None,
+        };
+        if let Some(error) = sandbox::preflight::cached(
+            &self.context.omar_dir,
+            &sandbox_config,
+            &self.context.limits,
+        )
+        .and_then(|p| p.error)
+        {
+            return Err(anyhow!(
+                "Sandbox check failed: {}. Fix [sandbox] or [limits] in config.toml",
+                error
+            ));
+        }
+        // Set after `launch` is recorded, so values such as API keys are
+        // not saved with it. Sandboxes do not all inherit the session's
+        // environment, so sandboxed workers export it inside the sandbox.
+        let env: Vec<(String, String)> = env.into_iter().collect();
+        let (command, session_env) = if launch.sandboxed {
+            (sandbox::export_env(&env, &command), Vec::new())
+        } else {
+            (command, env)
+        };
+
+        // Queued copies carry the dependency resolved against the workdir
+        let wait_for = args
+            .wait_for
+            .map(|wait| wait.resolved(&config::expand_tilde(&workdir)));
+        let mut raw_args = raw_args;
+        if let Some(wait) = &wait_for {
+            raw_args["wait_for"] = json!(wait);
+        }
+        if gated {
+            if let Some(wait) = wait_for.as_ref().filter(|wait| !self.wait_met(wait)) {
+                let position = spawn_queue::enqueue_in(state_dir, &short_name, raw_args)?;
+                return Ok(json!({
+                    "project_id": project_id,
+                    "project_name": project_name,
+                    "agent_name": short_name,
+                    "status": "waiting",
+                    "wait_for": wait,
+                    "queue_position": position,
+                }));
+            }
+            if let Some(max) = self.load_config()?.agent.max_concurrent {
+                let active = spawn_queue::active_workers(&client, manager_session, state_dir)?;
+                // Requests still waiting on a dependency do not hold the line
+                let waiting = spawn_queue::load_queue_in(state_dir)
+                    .iter()
+                    .filter(|queued| queued.wait_for().is_none())
+                    .count();
+                // Join the back of an existing queue even if a slot just
+                // freed, so queued requests start in order.
+                if active >= max || waiting > 0 {
+                    let position = spawn_queue::enqueue_in(state_dir, &short_name, raw_args)?;
+                    return Ok(json!({
+                        "project_id": project_id,
+                        "project_name": project_name,
+                        "agent_name": short_name,
+                        "status": "queued",
+                        "queue_position": position,
+                        "active_workers": active,
+                        "max_concurrent": max,
+                    }));
+                }
+            }
         }
         let tmux_spawn_start = std::time::Instant::now();
-        client.new_session(&session_name, &command, Some(&workdir))?;
+        let mut injected = profile.as_ref().map(|p| p.env_pairs()).unwrap_or_default();
+        injected.extend(secrets::resolve(&settings.secrets, &args.secrets)?);
+        let command = sandbox::wrap_with_secrets(
+            &sandbox_config,
+            &self.context.limits,
+            &command,
+            &workdir,
+            &injected,
+        )?;
+        if sandbox_config.runtime.trim() == "kubernetes" {
+            launch.pod = sandbox::KubernetesProvider::pod_in(&command);
+        }
+        if let Err(e) =
+            client.new_session_with_env(&session_name, &command, Some(&workdir), &session_env)
+        {
+            secrets::discard(&command);
+            return Err(e);
+        }
+        if let Some(file) = secrets::env_file(&command) {
+            memory::save_agent_env_file_in(state_dir, &session_name, file);
+        }
+        for pane in &args.panes {
+            if let Err(e) = client.split_pane(&session_name, pane.trim(), Some(&workdir)) {
+                let _ = client.kill_session(&session_name);
+                return Err(anyhow!("Failed to start pane '{}': {}", pane.trim(), e));
+            }
+        }
+        let _ = transcript::mark_trace(&session_name, &trace_id);
+        transcript::start(&client, &session_name);
         let tmux_spawn_ms = tmux_spawn_start.elapsed().as_millis() as u64;
         metrics::record_backend_bootstrap(&backend_name);
 
-        memory::save_agent_parent_in(state_dir, &session_name, &parent_session);
-        memory::save_worker_task_in(state_dir, &session_name, &task);
-        memory::save_agent_project_in(state_dir, &session_name, project_id);
+        let checkpoint = (supports_prompt_delivery && self.load_config()?.agent.checkpoint_workdir)
+            .then(|| checkpoint::create(Path::new(&workdir), &session_name));
+        let initial_status = if supports_prompt_delivery {
+            SpawnStatus::WaitingForAgent
+        } else {
+            SpawnStatus::Ready
+        };
+        let mut update = memory::AgentUpdate::new(&session_name)
+            .parent(&parent_session)
+            .task(&task)
+            .project(project_id)
+            .launch(&launch)
+            .clear_completed()
+            .tags(&tags)
+            .priority(priority)
+            .role(role)
+            .trace(&trace_id)
+            .spawn_status(initial_status);
+        if let Some(Ok(saved)) = &checkpoint {
+            update = update.checkpoint(saved);
+        }
+        if let Err(e) = update.save_in(state_dir) {
+            let _ = client.kill_session(&session_name);
+            return Err(e.context(format!("Failed to record agent '{}'", short_name)));
+        }
+        append_action_log_locked(
+            state_dir,
+            ea_id,
+            &short_name,
+            "spawn_agent",
+            &task,
+            Some(&trace_id),
+        )?;
+
+        let checkpoint = checkpoint.map(|created| match created {
+            Ok(saved) => json!({ "commit": saved.commit }),
+            Err(e) => json!({ "error": e.to_string() }),
+        });
 
-        let initial_prompt_delivery = if !supports_prompt_delivery {
-            "metadata_only".to_string()
+        let (spawn_status, initial_prompt_delivery) = if !supports_prompt_delivery {
+            (SpawnStatus::Ready, "metadata_only".to_string())
         } else {
             let client2 = client.clone();
             let session2 = session_name.clone();
-            let header = format!(
+            let mut header = format!(
                 "YOUR NAME: {}\nYOUR PARENT: {}\nYOUR TASK: {}",
-                short_name, prompt_parent, task
+                short_name, prompt_parent, prompt_task
             );
+            if self.load_config()?.agent.trace_in_prompt {
+                header.push_str(&format!(
+                    "\n\nTRACE ID: {} (quote it in your result marker)",
+                    trace_id
+                ));
+            }
             // opencode has no system-prompt flag, so build_agent_command
             // spawns it bare. Inline the rendered agent.md content here so
             // the worker receives instructions plus the YOUR NAME header
             // in a single user message. Other backends already received
             // agent.md via their respective system-prompt flags.
             let first_message = if backend_name == "opencode" {
-                let prompt_file = manager::prompts_dir(&self.context.omar_dir).join("agent.md");
-                let content = std::fs::read_to_string(&prompt_file)
-                    .unwrap_or_default()
+                let prompt_file = manager::prompt_template(&self.context, "agent.md");
+                let content = std::fs::read_to_string(&prompt_file).unwrap_or_default();
+                let content = manager::render_context(&content, &self.context)
                     .replace("{{PARENT_NAME}}", &prompt_parent)
-                    .replace("{{TASK}}", &task)
+                    .replace("{{TASK}}", &prompt_task)
                     .replace("{{EA_ID}}", &ea_id.to_string());
                 format!("{}\n\n---\n\n{}", content, header)
             } else {
                 header
             };
             let backend_name2 = backend_name.clone();
-            let readiness_markers = crate::tmux::backend_readiness_markers(&backend_name).to_vec();
+            let probe = ReadinessProbe::from_config(
+                Some(&backend_name),
+                &self
+                    .load_config()
+                    .map(|config| config.agent.readiness)
+                    .unwrap_or_default(),
+            );
+            let state_dir2 = state_dir.to_path_buf();
             let (delivery_tx, delivery_rx) = std::sync::mpsc::channel();
             thread::spawn(move || {
                 let delivery_start = std::time::Instant::now();
-                let readiness = if !readiness_markers.is_empty() {
-                    let ready = client2.wait_for_markers(
+                let outcome = probe.wait(&client2, &session2).and_then(|ready| {
+                    let opts = DeliveryOptions::default();
+                    let delivery = client2.deliver_prompt(&session2, &first_message, &opts);
+                    metrics::record_prompt_delivery(
+                        ea_id,
                         &session2,
-                        &readiness_markers,
-                        Duration::from_secs(45),
-                        Duration::from_millis(250),
+                        &backend_name2,
+                        delivery_start.elapsed().as_millis() as u64,
+                        delivery.is_ok(),
                     );
-                    if ready {
-                        Ok(())
-                    } else {
-                        Err(anyhow!("backend readiness markers timed out"))
-                    }
-                } else {
-                    client2.wait_for_stable(
-                        &session2,
-                        Duration::from_millis(500),
-                        Duration::from_secs(8),
-                        Duration::from_millis(120),
-                        false,
-                    )
-                };
-                let opts = DeliveryOptions::default();
-                let delivery = client2.deliver_prompt(&session2, &first_message, &opts);
-                let delivery_ok = delivery.is_ok();
-                metrics::record_prompt_delivery(
-                    ea_id,
-                    &session2,
-                    &backend_name2,
-                    delivery_start.elapsed().as_millis() as u64,
-                    delivery_ok,
-                );
-                let status = match (readiness, delivery) {
-                    (Ok(()), Ok(())) => "delivered".to_string(),
-                    (Err(readiness_err), Ok(())) => {
-                        format!("delivered_after_readiness_warning: {}", readiness_err)
-                    }
-                    (_, Err(delivery_err)) => format!("failed: {}", delivery_err),
+                    delivery.map(|()| ready)
+                });
+                let result = match outcome {
+                    Ok(true) => (SpawnStatus::Ready, "delivered".to_string()),
+                    Ok(false) => (
+                        SpawnStatus::Ready,
+                        "delivered_after_readiness_warning: ready prompt timed out".to_string(),
+                    ),
+                    Err(e) => (SpawnStatus::Failed, format!("failed: {}", e)),
                 };
-                let _ = delivery_tx.send(status);
+                memory::save_spawn_status_in(&state_dir2, &session2, result.0);
+                let _ = delivery_tx.send(result);
             });
             delivery_rx
                 .recv_timeout(INITIAL_PROMPT_DELIVERY_STATUS_TIMEOUT)
-                .unwrap_or_else(|_| "pending_background_delivery".to_string())
+                .unwrap_or_else(|_| {
+                    (
+                        SpawnStatus::WaitingForAgent,
+                        "pending_background_delivery".to_string(),
+                    )
+                })
         };
 
         metrics::record_agent_spawn(metrics::AgentSpawnMetric {
@@ -1214,8 +2369,15 @@ impl OmarMcpServer {
             "project_id": project_id,
             "project_name": project_name,
             "agent_name": short_name,
+            "role": role,
             "status": "running",
+            "spawn_status": spawn_status,
             "initial_prompt_delivery": initial_prompt_delivery,
+            "checkpoint": checkpoint,
+            "secrets": args.secrets,
+            "panes": args.panes.len(),
+            "trace_id": trace_id,
+            "profile": args.profile,
         }))
     }
 
@@ -1268,13 +2430,14 @@ impl OmarMcpServer {
         agent_projects: &std::collections::HashMap<String, usize>,
     ) -> Vec<String> {
         let client = self.client();
+        let roles = memory::load_agent_roles_from(self.state_dir());
         let mut supervisors = Vec::new();
         for (session_name, session_project_id) in agent_projects {
             if *session_project_id != project_id {
                 continue;
             }
             let short_name = self.display_name(session_name);
-            if !looks_like_supervisor_name(short_name) {
+            if !is_project_supervisor(roles.get(session_name), short_name) {
                 continue;
             }
             if client.has_session(session_name).unwrap_or(false) {
@@ -1289,20 +2452,31 @@ impl OmarMcpServer {
         #[derive(Deserialize)]
         struct Args {
             name: String,
+            #[serde(default)]
+            group: bool,
         }
         let args: Args = serde_json::from_value(args)?;
         let state_dir = self.state_dir();
         let _lock = FileLock::acquire(lock_path_for_state_dir(state_dir))?;
-        let client = self.client();
+        let client = self.mux();
         let session_name = self.qualified_session_name(&args.name)?;
         let manager_session = self.manager_session();
         if session_name == manager_session {
             return Err(anyhow!("Cannot kill manager via MCP"));
         }
+        if !client.has_session(&session_name).unwrap_or(false)
+            && spawn_queue::remove_in(state_dir, self.display_name(&session_name))?
+        {
+            return Ok(json!({ "status": "dequeued" }));
+        }
+        if args.group {
+            return self.kill_group(client.as_ref(), &session_name);
+        }
         let _session = client.ensure_session_not_attached(&session_name)?;
         client.kill_session(&session_name)?;
         memory::remove_agent_parent_in(state_dir, &session_name);
         memory::remove_agent_project_in(state_dir, &session_name);
+        memory::remove_agent_result_in(state_dir, &session_name);
         let short_name = self.display_name(&session_name).to_string();
         let events_cancelled = self
             .scheduler()
@@ -1315,49 +2489,952 @@ impl OmarMcpServer {
         }))
     }
 
-    fn send_input(&self, args: Value) -> Result<Value> {
+    /// Rename a worker's session and every record keyed by it, then tell
+    /// the worker and its children the new name. A `note` is saved in the
+    /// same transaction as the records, after the rename is validated.
+    fn rename_agent(&self, args: Value) -> Result<Value> {
         #[derive(Deserialize)]
         struct Args {
             name: String,
-            text: String,
-            #[serde(default)]
-            enter: bool,
+            new_name: String,
+            note: Option<String>,
         }
-        let args: Args = serde_json::from_value(args)?;
+        let args: Args = serde_json::from_value(args).map_err(ValidationError::from_serde)?;
+        let state_dir = self.state_dir();
+        let _lock = FileLock::acquire(lock_path_for_state_dir(state_dir))?;
         let client = self.client();
+        let prefix = self.session_prefix();
         let session_name = self.qualified_session_name(&args.name)?;
+        if session_name == self.manager_session() {
+            return Err(anyhow!("Cannot rename the manager"));
+        }
         if !client.has_session(&session_name).unwrap_or(false) {
             return Err(anyhow!("Agent '{}' not found", args.name));
         }
-        client.send_keys_literal(&session_name, &args.text)?;
-        if args.enter {
-            thread::sleep(Duration::from_millis(100));
-            client.send_keys(&session_name, "Enter")?;
+        let id = AgentId::resolve(prefix, args.new_name.trim());
+        let mut invalid = ValidationError::default();
+        if id.short().is_empty()
+            || id
+                .short()
+                .chars()
+                .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '.' | ':'))
+        {
+            invalid.push(
+                "new_name",
+                "invalid",
+                "Agent names must be non-empty without whitespace, '.' or ':'",
+            );
+        } else if id.session() == session_name || client.has_session(id.session()).unwrap_or(false)
+        {
+            invalid.push(
+                "new_name",
+                "exists",
+                format!("Agent '{}' already exists", id.short()),
+            );
         }
-        Ok(json!({ "status": "sent" }))
+        invalid.check()?;
+
+        let old_name = self.display_name(&session_name).to_string();
+        let new_name = id.short().to_string();
+        let children: Vec<String> = memory::load_agent_parents_from(state_dir)
+            .into_iter()
+            .filter(|(_, parent)| *parent == session_name)
+            .map(|(child, _)| self.display_name(&child).to_string())
+            .collect();
+        let mut update = memory::AgentUpdate::new(id.session());
+        if let Some(note) = &args.note {
+            update = update.note(note);
+        }
+        client.rename_session(&session_name, id.session())?;
+        if let Err(e) = update.rename_from_in(state_dir, &session_name) {
+            let _ = client.rename_session(id.session(), &session_name);
+            return Err(e);
+        }
+        mailbox::rename_session(state_dir, &session_name, id.session())?;
+        transcript::rename(&session_name, id.session());
+        let ea_id = self.ea_id();
+        let events_readdressed = self
+            .scheduler()
+            .rename_receiver(&old_name, &new_name, ea_id);
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        let mut notices = vec![(
+            new_name.clone(),
+            format!(
+                "[RENAMED] You are now '{}' (was '{}'). Use the new name from now on.",
+                new_name, old_name
+            ),
+        )];
+        notices.extend(children.into_iter().map(|child| {
+            (
+                child,
+                format!(
+                    "[PARENT RENAMED] Your parent '{}' is now '{}'. Report to the new name.",
+                    old_name, new_name
+                ),
+            )
+        }));
+        for (receiver, payload) in notices {
+            self.scheduler().insert(ScheduledEvent {
+                id: Uuid::new_v4().to_string(),
+                sender: "ea".to_string(),
+                receiver,
+                timestamp: now,
+                payload,
+                created_at: now,
+                recurring_ns: None,
+                ea_id,
+            });
+        }
+        let trace_id = memory::load_agent_traces_from(state_dir).remove(id.session());
+        append_action_log_locked(
+            state_dir,
+            ea_id,
+            &new_name,
+            "rename_agent",
+            &format!("renamed from {}", old_name),
+            trace_id.as_deref(),
+        )?;
+
+        self.refresh_memory_locked()?;
+        Ok(json!({
+            "id": new_name,
+            "previous": old_name,
+            "events_readdressed": events_readdressed,
+        }))
     }
 
-    fn schedule_omar_event(&self, args: Value) -> Result<Value> {
+    /// Kill `root` and its descendants. Caller holds the state lock.
+    fn kill_group(&self, client: &dyn Mux, root: &str) -> Result<Value> {
+        let state_dir = self.state_dir();
+        let team = memory::team_of(&memory::load_agent_parents_from(state_dir), root);
+        let live: Vec<String> = client
+            .list_sessions()?
+            .into_iter()
+            .filter(|session| team.contains(&session.name))
+            .map(|session| {
+                if session.attached {
+                    return Err(anyhow!(
+                        "Cannot kill group: {} is attached",
+                        self.display_name(&session.name)
+                    ));
+                }
+                Ok(session.name)
+            })
+            .collect::<Result<_>>()?;
+        if live.is_empty() {
+            return Err(anyhow!("Agent '{}' not found", self.display_name(root)));
+        }
+        let mut killed = Vec::new();
+        let mut events_cancelled = 0;
+        for session in team.iter().filter(|session| live.contains(session)) {
+            client.kill_session(session)?;
+            let short_name = self.display_name(session).to_string();
+            events_cancelled += self
+                .scheduler()
+                .cancel_by_receiver_and_ea(&short_name, self.ea_id());
+            killed.push(short_name);
+        }
+        memory::forget_agents_in(state_dir, &team);
+        self.refresh_memory_locked()?;
+        Ok(json!({
+            "status": "killed",
+            "killed": killed,
+            "events_cancelled": events_cancelled,
+        }))
+    }
+
+    fn drain_fleet(&self, args: Value) -> Result<Value> {
         #[derive(Deserialize)]
         struct Args {
-            receiver: String,
-            payload: String,
-            sender: Option<String>,
-            #[serde(default, deserialize_with = "flex_int::deserialize_opt_u64")]
-            timestamp_ns: Option<u64>,
-            #[serde(default, deserialize_with = "flex_int::deserialize_opt_u64")]
-            delay_seconds: Option<u64>,
-            #[serde(default, deserialize_with = "flex_int::deserialize_opt_u64")]
-            delay_ns: Option<u64>,
-            #[serde(default, deserialize_with = "flex_int::deserialize_opt_u64")]
-            recurring_seconds: Option<u64>,
+            #[serde(default)]
+            action: Option<String>,
             #[serde(default, deserialize_with = "flex_int::deserialize_opt_u64")]
-            recurring_ns: Option<u64>,
+            deadline_secs: Option<u64>,
         }
         let args: Args = serde_json::from_value(args)?;
-        let base = now_ns();
-        let delay_ns =
-            scheduler::combine_seconds_and_ns(args.delay_seconds, args.delay_ns).unwrap_or(0);
+        let omar_dir = &self.context.omar_dir;
+        let state = match args.action.as_deref().unwrap_or("start") {
+            "start" => Some(drain::start(omar_dir, args.deadline_secs)?),
+            "status" => drain::load(omar_dir),
+            "cancel" => {
+                drain::cancel(omar_dir)?;
+                None
+            }
+            other => {
+                return Err(anyhow!(
+                    "Unknown action '{}' (expected start, status, cancel)",
+                    other
+                ))
+            }
+        };
+        Ok(json!({
+            "draining": state.is_some(),
+            "started_at": state.as_ref().map(|s| s.started_at.to_rfc3339()),
+            "deadline": state.as_ref().and_then(|s| s.deadline).map(|d| d.to_rfc3339()),
+            "active_workers": drain::fleet_active_workers(
+                omar_dir,
+                &self.context.session_prefix,
+                self.load_config()?.dashboard.multiplexer,
+            ),
+        }))
+    }
+
+    fn send_input(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            name: String,
+            text: String,
+            #[serde(default)]
+            enter: bool,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        let client = self.client();
+        let session_name = self.qualified_session_name(&args.name)?;
+        if !client.has_session(&session_name).unwrap_or(false) {
+            return Err(anyhow!("Agent '{}' not found", args.name));
+        }
+        client.send_keys_literal(&session_name, &args.text)?;
+        if args.enter {
+            thread::sleep(Duration::from_millis(100));
+            client.send_keys(&session_name, "Enter")?;
+        }
+        Ok(json!({ "status": "sent" }))
+    }
+
+    fn send_message(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            name: String,
+            text: String,
+            #[serde(default)]
+            from: Option<String>,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        if args.text.trim().is_empty() {
+            return Err(anyhow!("Message text is empty"));
+        }
+        let session_name = self.qualified_session_name(&args.name)?;
+        if !self.client().has_session(&session_name).unwrap_or(false) {
+            return Err(anyhow!("Agent '{}' not found", args.name));
+        }
+        let from = args.from.as_deref().unwrap_or("user");
+        let (message, position) =
+            mailbox::enqueue_in(self.state_dir(), &session_name, from, &args.text)?;
+        Ok(json!({
+            "id": message.id,
+            "status": message.status,
+            "position": position,
+        }))
+    }
+
+    /// Write `content` (or a copy of the file at `path`) under the agent's
+    /// workdir and queue a short message pointing at it, so long context
+    /// never goes through send-keys.
+    fn send_file(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Args {
+            name: String,
+            #[serde(default)]
+            content: Option<String>,
+            #[serde(default)]
+            path: Option<String>,
+            #[serde(default)]
+            filename: Option<String>,
+            #[serde(default)]
+            note: Option<String>,
+            #[serde(default)]
+            from: Option<String>,
+        }
+        let args: Args = serde_json::from_value(args).map_err(ValidationError::from_serde)?;
+        let mut invalid = ValidationError::default();
+        let source = match (&args.content, &args.path) {
+            (Some(_), Some(_)) => {
+                invalid.push("path", "conflict", "Pass either content or path, not both");
+                None
+            }
+            (None, None) => {
+                invalid.push(
+                    "content",
+                    "missing",
+                    "Pass the text as content or a file as path",
+                );
+                None
+            }
+            (None, Some(path)) => {
+                let path = config::expand_tilde(path);
+                match fs::metadata(&path) {
+                    Ok(meta) if meta.is_file() && meta.len() <= SEND_FILE_MAX_BYTES => Some(path),
+                    Ok(meta) if meta.is_file() => {
+                        invalid.push(
+                            "path",
+                            "invalid",
+                            format!("File is larger than {} bytes", SEND_FILE_MAX_BYTES),
+                        );
+                        None
+                    }
+                    _ => {
+                        invalid.push("path", "not_found", "No such file");
+                        None
+                    }
+                }
+            }
+            (Some(content), None) => {
+                if content.len() as u64 > SEND_FILE_MAX_BYTES {
+                    invalid.push(
+                        "content",
+                        "invalid",
+                        format!("Content is larger than {} bytes", SEND_FILE_MAX_BYTES),
+                    );
+                }
+                None
+            }
+        };
+        let stem = args
+            .filename
+            .as_deref()
+            .or_else(|| source.as_deref().and_then(|p| p.file_name()?.to_str()))
+            .unwrap_or("context.md");
+        if stem.is_empty()
+            || stem.starts_with('.')
+            || !stem
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+        {
+            invalid.push(
+                "filename",
+                "invalid",
+                "Use letters, digits, '.', '-' and '_' only",
+            );
+        }
+        invalid.check()?;
+
+        let session_name = self.qualified_session_name(&args.name)?;
+        let workdir = self.agent_workdir(&args.name)?;
+        let dir = workdir.join(SEND_FILE_DIR);
+        fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        // Keep delivered files out of the agent's commits
+        let ignore = dir.join(".gitignore");
+        if !ignore.exists() {
+            fs::write(&ignore, "*\n")?;
+        }
+        let file_name = format!("{}-{}", &Uuid::new_v4().simple().to_string()[..8], stem);
+        let dest = dir.join(&file_name);
+        let bytes = match (&args.content, &source) {
+            (Some(content), _) => {
+                fs::write(&dest, content)?;
+                content.len() as u64
+            }
+            (None, Some(source)) => fs::copy(source, &dest)
+                .with_context(|| format!("Failed to copy {}", source.display()))?,
+            (None, None) => unreachable!("validated above"),
+        };
+
+        let relative = format!("{}/{}", SEND_FILE_DIR, file_name);
+        let mut text = args.note.unwrap_or_default().trim().to_string();
+        if !text.is_empty() {
+            text.push_str("\n\n");
+        }
+        text.push_str(&format!(
+            "Read {} ({} bytes) for the full context before continuing.",
+            relative, bytes
+        ));
+        let from = args.from.as_deref().unwrap_or("user");
+        let (message, position) =
+            mailbox::enqueue_in(self.state_dir(), &session_name, from, &text)?;
+        Ok(json!({
+            "id": message.id,
+            "status": message.status,
+            "position": position,
+            "path": dest.display().to_string(),
+            "bytes": bytes,
+        }))
+    }
+
+    fn get_messages(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            name: String,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        let session_name = self.qualified_session_name(&args.name)?;
+        Ok(json!({
+            "id": args.name,
+            "messages": mailbox::messages_in(self.state_dir(), &session_name),
+        }))
+    }
+
+    fn search_output(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            pattern: String,
+            #[serde(default, deserialize_with = "flex_int::deserialize_opt_u64")]
+            context_lines: Option<u64>,
+            #[serde(default, deserialize_with = "flex_int::deserialize_opt_u64")]
+            limit: Option<u64>,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        let pattern = search::compile_query(&args.pattern)?;
+        let context_lines = args.context_lines.unwrap_or(2).min(10) as usize;
+        let limit = args.limit.unwrap_or(50).clamp(1, 200) as usize;
+        let client = self.client();
+        let manager_session = self.manager_session();
+        let sessions = self.ea_sessions(&client)?;
+        let matches: Vec<Value> =
+            search::search_sessions(&client, &sessions, &pattern, context_lines, limit)
+                .into_iter()
+                .map(|m| {
+                    let agent = if m.session == manager_session {
+                        "ea"
+                    } else {
+                        self.display_name(&m.session)
+                    };
+                    let last_activity = client
+                        .get_pane_activity(&m.session)
+                        .ok()
+                        .and_then(secs_rfc3339);
+                    json!({
+                        "agent": agent,
+                        "line": m.line,
+                        "text": m.text,
+                        "context": m.context,
+                        "last_activity": last_activity,
+                    })
+                })
+                .collect();
+        Ok(json!({
+            "pattern": args.pattern,
+            "truncated": matches.len() >= limit,
+            "matches": matches,
+        }))
+    }
+
+    fn tail_logs(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            #[serde(default, deserialize_with = "flex_int::deserialize_opt_u64")]
+            cursor: Option<u64>,
+            #[serde(default, deserialize_with = "flex_int::deserialize_opt_u64")]
+            limit: Option<u64>,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        let limit = args.limit.unwrap_or(200).clamp(1, 1000) as usize;
+        let client = self.client();
+        let manager_session = self.manager_session();
+        let sessions = self.ea_sessions(&client)?;
+        let mut firehose = self.firehose.lock().unwrap_or_else(|e| e.into_inner());
+        firehose.poll(&client, &sessions);
+        let pending: Vec<_> = firehose.since(args.cursor.unwrap_or(0)).collect();
+        let skipped = pending.len().saturating_sub(limit);
+        let lines: Vec<Value> = pending[skipped..]
+            .iter()
+            .map(|line| {
+                let agent = if line.session == manager_session {
+                    "ea"
+                } else {
+                    self.display_name(&line.session)
+                };
+                json!({ "agent": agent, "text": line.text })
+            })
+            .collect();
+        Ok(json!({
+            "lines": lines,
+            "skipped": skipped,
+            "cursor": firehose.next_seq(),
+        }))
+    }
+
+    fn load_config(&self) -> Result<config::Config> {
+        let path = self.context.omar_dir.join("config.toml");
+        config::Config::load(Some(&path.to_string_lossy()))
+    }
+
+    /// `[health]` from config.toml, or the defaults with the launch
+    /// threshold when it does not load.
+    fn health_rules(&self) -> HealthRules {
+        HealthRules::new(self.load_config().map(|c| c.health).unwrap_or_else(|_| {
+            config::HealthConfig {
+                idle_warning: self.context.health_idle_warning,
+                ..Default::default()
+            }
+        }))
+    }
+
+    fn pipeline_configs(
+        &self,
+    ) -> Result<std::collections::BTreeMap<String, config::PipelineConfig>> {
+        Ok(self.load_config()?.pipelines)
+    }
+
+    fn run_pipeline(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            pipeline: String,
+            input: String,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        let pipelines = self.pipeline_configs()?;
+        let definition = pipelines.get(&args.pipeline).ok_or_else(|| {
+            anyhow!(
+                "Pipeline '{}' not found. Defined: {}",
+                args.pipeline,
+                pipelines.keys().cloned().collect::<Vec<_>>().join(", ")
+            )
+        })?;
+        let run =
+            pipeline::start_run_in(self.state_dir(), &args.pipeline, definition, &args.input)?;
+        Ok(json!({
+            "run_id": run.id,
+            "pipeline": run.pipeline,
+            "stages": run.stages.iter().map(|stage| stage.role.as_str()).collect::<Vec<_>>(),
+            "status": run.status.as_str(),
+        }))
+    }
+
+    /// Rewrite memory.md and queue its condensed form for the EA, delivered
+    /// by the dashboard once the EA is waiting for input.
+    fn refresh_manager_context(&self) -> Result<Value> {
+        let state_dir = self.state_dir();
+        let manager_session = self.manager_session();
+        if !self.client().has_session(manager_session).unwrap_or(false) {
+            return Err(anyhow!("The EA manager is not running"));
+        }
+        {
+            let _lock = FileLock::acquire(lock_path_for_state_dir(state_dir))?;
+            self.refresh_memory_locked()?;
+        }
+        let text = memory::context_refresh_in(state_dir);
+        let (message, position) = mailbox::replace_in(
+            state_dir,
+            manager_session,
+            "omar",
+            memory::CONTEXT_REFRESH,
+            &text,
+        )?;
+        Ok(json!({
+            "id": message.id,
+            "status": message.status,
+            "position": position,
+        }))
+    }
+
+    fn list_runs(&self) -> Result<Value> {
+        Ok(json!({ "runs": runs::list_runs(&self.context.omar_dir) }))
+    }
+
+    fn list_pipelines(&self) -> Result<Value> {
+        let pipelines: Vec<Value> = self
+            .pipeline_configs()?
+            .iter()
+            .map(|(name, definition)| {
+                json!({
+                    "name": name,
+                    "stages": definition.stages.iter().map(|stage| stage.role.as_str()).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        let runs: Vec<Value> = pipeline::load_runs_from(self.state_dir())
+            .iter()
+            .map(|run| {
+                json!({
+                    "run_id": run.id,
+                    "pipeline": run.pipeline,
+                    "status": run.status.as_str(),
+                    "progress": run.progress(),
+                    "detail": run.detail,
+                    "stages": run.stages.iter().map(|stage| json!({
+                        "role": stage.role,
+                        "agent": stage.agent.as_deref().map(|agent| self.display_name(agent)),
+                        "result": stage.result,
+                    })).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        Ok(json!({ "pipelines": pipelines, "runs": runs }))
+    }
+
+    fn get_memory_history(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            #[serde(default)]
+            diff: bool,
+            #[serde(default)]
+            from: Option<String>,
+            #[serde(default)]
+            to: Option<String>,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        let snapshots: Vec<Value> = memory::list_memory_history_in(self.state_dir())
+            .iter()
+            .map(|snapshot| {
+                json!({
+                    "id": snapshot.id,
+                    "taken_at": snapshot.taken_at().map(|t| t.to_rfc3339()),
+                    "bytes": snapshot.bytes,
+                })
+            })
+            .collect();
+        let mut out = json!({ "snapshots": snapshots });
+        if args.diff || args.from.is_some() || args.to.is_some() {
+            out["diff"] = json!(memory::diff_memory_snapshots_in(
+                self.state_dir(),
+                args.from.as_deref(),
+                args.to.as_deref()
+            )?);
+        }
+        Ok(out)
+    }
+
+    fn list_schedules(&self) -> Result<Value> {
+        let config = self.load_config()?;
+        let configured: Vec<&config::ScheduleConfig> = config
+            .schedules
+            .iter()
+            .filter(|s| s.ea == self.ea_id())
+            .collect();
+        let last_runs = schedules::load_last_runs_in(self.state_dir());
+        let now = chrono::Local::now();
+        let entries: Vec<Value> = schedules::for_ea(&config.schedules, self.ea_id(), self.state_dir())
+            .iter()
+            .map(|schedule| {
+                json!({
+                    "name": schedule.name,
+                    "cron": schedule.cron,
+                    "task": schedule.task,
+                    "backend": schedule.backend,
+                    "workdir": schedule.workdir,
+                    "source": if configured.iter().any(|c| c.name == schedule.name) { "config" } else { "api" },
+                    "next_run": schedules::next_fire(schedule, now).map(|t| t.with_timezone(&chrono::Utc).to_rfc3339()),
+                    "last_run": last_runs.get(&schedule.name).map(|t| t.to_rfc3339()),
+                })
+            })
+            .collect();
+        Ok(json!({ "schedules": entries }))
+    }
+
+    fn add_schedule(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            name: String,
+            cron: String,
+            task: String,
+            #[serde(default)]
+            backend: Option<String>,
+            #[serde(default)]
+            workdir: Option<String>,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        if let Some(backend) = args.backend.as_deref() {
+            config::resolve_backend(backend).map_err(|e| anyhow!(e))?;
+        }
+        let config = self.load_config()?;
+        let configured: Vec<config::ScheduleConfig> = config
+            .schedules
+            .into_iter()
+            .filter(|s| s.ea == self.ea_id())
+            .collect();
+        let schedule = config::ScheduleConfig {
+            name: args.name,
+            cron: args.cron,
+            task: args.task,
+            backend: args.backend,
+            workdir: args.workdir,
+            ea: self.ea_id(),
+        };
+        schedules::add_in(self.state_dir(), schedule.clone(), &configured)?;
+        Ok(json!({
+            "name": schedule.name,
+            "cron": schedule.cron,
+            "next_run": schedules::next_fire(&schedule, chrono::Local::now()).map(|t| t.with_timezone(&chrono::Utc).to_rfc3339()),
+        }))
+    }
+
+    fn remove_schedule(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            name: String,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        if schedules::remove_in(self.state_dir(), &args.name)? {
+            return Ok(json!({ "removed": args.name }));
+        }
+        let configured = self
+            .load_config()?
+            .schedules
+            .iter()
+            .any(|s| s.ea == self.ea_id() && s.name == args.name);
+        if configured {
+            Err(anyhow!(
+                "Schedule '{}' is defined in config.toml; remove it there",
+                args.name
+            ))
+        } else {
+            Err(anyhow!("Schedule '{}' not found", args.name))
+        }
+    }
+
+    /// Working directory of a local agent, used as the file browsing root.
+    fn agent_workdir(&self, name: &str) -> Result<PathBuf> {
+        let session_name = self.qualified_session_name(name)?;
+        let client = self.client();
+        if !client.has_session(&session_name).unwrap_or(false) {
+            return Err(anyhow!("Agent '{}' not found", name));
+        }
+        let path = client.get_pane_path(&session_name)?;
+        if path.is_empty() {
+            return Err(anyhow!("Could not determine the workdir of '{}'", name));
+        }
+        Ok(PathBuf::from(path))
+    }
+
+    fn list_agent_files(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            name: String,
+            #[serde(default)]
+            path: String,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        let root = self.agent_workdir(&args.name)?;
+        let entries: Vec<Value> = file_browser::list_dir(&root, &args.path)?
+            .into_iter()
+            .map(|entry| {
+                json!({
+                    "name": entry.name,
+                    "type": if entry.is_dir { "dir" } else { "file" },
+                    "size": entry.size,
+                })
+            })
+            .collect();
+        Ok(json!({
+            "root": root.display().to_string(),
+            "path": args.path,
+            "entries": entries,
+        }))
+    }
+
+    fn read_agent_file(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            name: String,
+            path: String,
+            #[serde(default, deserialize_with = "flex_int::deserialize_opt_u64")]
+            max_bytes: Option<u64>,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        let root = self.agent_workdir(&args.name)?;
+        let max_bytes = args
+            .max_bytes
+            .unwrap_or(file_browser::MAX_VIEW_BYTES)
+            .min(file_browser::MAX_VIEW_BYTES);
+        let content = file_browser::read_file(&root, &args.path, max_bytes)?;
+        Ok(json!({
+            "path": args.path,
+            "size": content.size,
+            "truncated": content.truncated,
+            "content": content.text,
+        }))
+    }
+
+    fn get_agent_transcript(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            name: String,
+            #[serde(default, deserialize_with = "flex_int::deserialize_opt_u64")]
+            max_lines: Option<u64>,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        let session_name = self.qualified_session_name(&args.name)?;
+        let agent = self.display_name(&session_name).to_string();
+        let markdown = transcript::export(
+            &session_name,
+            &agent,
+            args.max_lines.map(|max| max as usize),
+        )?;
+        Ok(json!({ "id": agent, "markdown": markdown }))
+    }
+
+    fn get_plan(&self) -> Result<Value> {
+        let plan = manager::pending_plan(&self.client(), self.manager_session())?;
+        let Some((description, agents)) = plan else {
+            return Ok(json!({ "plan": null }));
+        };
+        let estimates = estimate_plan(&agents, &metrics::load_history());
+        let proposed: Vec<Value> = agents
+            .iter()
+            .zip(&estimates.agents)
+            .map(|(agent, estimate)| {
+                json!({
+                    "name": agent.name,
+                    "role": agent.role,
+                    "task": agent.task,
+                    "depends_on": agent.depends_on,
+                    "estimate": estimate.as_ref().map(|e| e.summary()),
+                })
+            })
+            .collect();
+        Ok(json!({
+            "plan": {
+                "description": description,
+                "agents": proposed,
+                "estimated_total": estimates.total_summary(),
+            }
+        }))
+    }
+
+    fn approve_plan(&self) -> Result<Value> {
+        if drain::load(&self.context.omar_dir).is_some() {
+            return Err(anyhow!(
+                "OMAR is draining: no new agents are started until the drain is cancelled"
+            ));
+        }
+        let approval = manager::approve_pending_plan(
+            &self.client(),
+            &self.context.default_command,
+            self.manager_session(),
+            self.ea_id(),
+            &self.context.omar_dir,
+            &self.context.session_prefix,
+            &manager::ManagerRuntimeOptions {
+                default_workdir: self.context.default_workdir.clone(),
+                health_idle_warning: self.context.health_idle_warning,
+                sandbox: self.context.sandbox.clone(),
+                limits: self.context.limits.clone(),
+                readiness: self.load_config()?.agent.readiness,
+                prompts: self.context.prompts.clone(),
+            },
+        )?
+        .ok_or_else(|| anyhow!("No plan found in the manager's recent output"))?;
+        let _lock = FileLock::acquire(lock_path_for_state_dir(self.state_dir()))?;
+        self.refresh_memory_locked()?;
+        Ok(json!({
+            "status": "approved",
+            "description": approval.description,
+            "spawned": approval.spawned,
+            "skipped": approval.skipped,
+            "warnings": approval.warnings,
+        }))
+    }
+
+    fn reject_plan(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            reason: String,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        let manager_session = self.manager_session();
+        if manager::pending_plan(&self.client(), manager_session)?.is_none() {
+            return Err(anyhow!("No plan found in the manager's recent output"));
+        }
+        let message = manager::reject_pending_plan(&self.client(), manager_session, &args.reason)?;
+        Ok(json!({ "status": "rejected", "sent": message }))
+    }
+
+    fn estimate_plan(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            agents: Vec<ProposedAgent>,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        let estimates = estimate_plan(&args.agents, &metrics::load_history());
+        let agents: Vec<Value> = args
+            .agents
+            .iter()
+            .zip(&estimates.agents)
+            .map(|(agent, estimate)| {
+                json!({
+                    "name": agent.name,
+                    "duration_secs": estimate.as_ref().map(|e| e.duration_secs),
+                    "cost_usd": estimate.as_ref().and_then(|e| e.cost_usd),
+                    "samples": estimate.as_ref().map_or(0, |e| e.samples),
+                    "summary": estimate.as_ref().map(|e| e.summary()),
+                })
+            })
+            .collect();
+        Ok(json!({ "agents": agents, "total": estimates.total_summary() }))
+    }
+
+    fn list_pending_approvals(&self) -> Result<Value> {
+        let approvals: Vec<Value> = pipeline::load_runs_from(self.state_dir())
+            .iter()
+            .filter(|run| run.status == pipeline::RunStatus::AwaitingApproval)
+            .map(|run| {
+                json!({
+                    "run_id": run.id,
+                    "pipeline": run.pipeline,
+                    "stage": run.stages[run.current].role,
+                    "task": run.stage_task(run.current),
+                    "previous_result": run
+                        .current
+                        .checked_sub(1)
+                        .and_then(|prev| run.stages[prev].result.as_deref()),
+                })
+            })
+            .collect();
+        Ok(json!({ "approvals": approvals }))
+    }
+
+    fn resolve_approval(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            #[serde(deserialize_with = "flex_int::deserialize_usize")]
+            run_id: usize,
+            approve: bool,
+            reason: Option<String>,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        let run = pipeline::resolve_approval_in(
+            self.state_dir(),
+            args.run_id as u64,
+            args.approve,
+            args.reason.as_deref(),
+        )?;
+        Ok(json!({
+            "run_id": run.id,
+            "status": run.status.as_str(),
+            "progress": run.progress(),
+        }))
+    }
+
+    /// The EA manager followed by every other session in this EA.
+    fn ea_sessions(&self, client: &TmuxClient) -> Result<Vec<String>> {
+        let manager_session = self.manager_session();
+        let mut sessions = vec![manager_session.to_string()];
+        sessions.extend(
+            client
+                .list_sessions()?
+                .into_iter()
+                .map(|s| s.name)
+                .filter(|name| name != manager_session),
+        );
+        Ok(sessions)
+    }
+
+    fn schedule_omar_event(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            receiver: String,
+            payload: String,
+            sender: Option<String>,
+            #[serde(default, deserialize_with = "flex_int::deserialize_opt_u64")]
+            timestamp_ns: Option<u64>,
+            #[serde(default, deserialize_with = "flex_int::deserialize_opt_u64")]
+            delay_seconds: Option<u64>,
+            #[serde(default, deserialize_with = "flex_int::deserialize_opt_u64")]
+            delay_ns: Option<u64>,
+            #[serde(default, deserialize_with = "flex_int::deserialize_opt_u64")]
+            recurring_seconds: Option<u64>,
+            #[serde(default, deserialize_with = "flex_int::deserialize_opt_u64")]
+            recurring_ns: Option<u64>,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        let base = now_ns();
+        let delay_ns =
+            scheduler::combine_seconds_and_ns(args.delay_seconds, args.delay_ns).unwrap_or(0);
         let recurring_ns =
             scheduler::combine_seconds_and_ns(args.recurring_seconds, args.recurring_ns);
         let event = ScheduledEvent {
@@ -1381,6 +3458,7 @@ impl OmarMcpServer {
             "sender": event.sender,
             "receiver": event.receiver,
             "timestamp_ns": event.timestamp,
+            "fires_at": ns_rfc3339(event.timestamp),
             "recurring_ns": event.recurring_ns,
         }))
     }
@@ -1395,8 +3473,9 @@ impl OmarMcpServer {
                     "sender": event.sender,
                     "receiver": event.receiver,
                     "timestamp_ns": event.timestamp,
+                    "fires_at": ns_rfc3339(event.timestamp),
                     "payload": event.payload,
-                    "created_at": event.created_at,
+                    "created_at": ns_rfc3339(event.created_at),
                     "recurring_ns": event.recurring_ns,
                     "ea_id": event.ea_id,
                 })
@@ -1429,13 +3508,58 @@ impl OmarMcpServer {
         }
     }
 
-    fn list_projects(&self) -> Result<Value> {
+    fn list_projects(&self) -> Result<Value> {
+        let state_dir = self.state_dir();
+        let projects: Vec<Value> = projects::load_projects_from(state_dir)
+            .into_iter()
+            .map(|project| {
+                let mut value = project_json(&project);
+                if let Some((number, url)) = github::issue_for_project_in(state_dir, project.id) {
+                    value["github_issue"] = json!({ "number": number, "url": url });
+                }
+                value
+            })
+            .collect();
+        Ok(json!({ "projects": projects }))
+    }
+
+    fn add_project_item(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            #[serde(deserialize_with = "flex_int::deserialize_usize")]
+            project_id: usize,
+            text: String,
+        }
+        let args: Args = serde_json::from_value(args)?;
+        let text = args.text.trim();
+        if text.is_empty() {
+            return Err(anyhow!("Checklist item text must not be empty"));
+        }
+        let state_dir = self.state_dir();
+        let _lock = FileLock::acquire(lock_path_for_state_dir(state_dir))?;
+        let item = projects::add_item_in(state_dir, args.project_id, text)?;
+        self.refresh_memory_locked()?;
+        let project = projects::find_project_in(state_dir, args.project_id)
+            .ok_or_else(|| anyhow!("Project '{}' not found", args.project_id))?;
+        Ok(json!({ "item": item, "project": project_json(&project) }))
+    }
+
+    fn toggle_project_item(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            #[serde(deserialize_with = "flex_int::deserialize_usize")]
+            project_id: usize,
+            #[serde(deserialize_with = "flex_int::deserialize_usize")]
+            item: usize,
+            #[serde(default)]
+            done: Option<bool>,
+        }
+        let args: Args = serde_json::from_value(args)?;
         let state_dir = self.state_dir();
-        let projects: Vec<Value> = projects::load_projects_from(state_dir)
-            .into_iter()
-            .map(|project| json!({ "id": project.id, "name": project.name }))
-            .collect();
-        Ok(json!({ "projects": projects }))
+        let _lock = FileLock::acquire(lock_path_for_state_dir(state_dir))?;
+        let project = projects::set_item_in(state_dir, args.project_id, args.item, args.done)?;
+        self.refresh_memory_locked()?;
+        Ok(json!({ "item": args.item, "project": project_json(&project) }))
     }
 
     fn add_project(&self, args: Value) -> Result<Value> {
@@ -1463,10 +3587,12 @@ impl OmarMcpServer {
         struct Args {
             #[serde(deserialize_with = "flex_int::deserialize_usize")]
             project_id: usize,
+            #[serde(default)]
+            summary: Option<String>,
         }
         let args: Args = serde_json::from_value(args)?;
         let state_dir = self.state_dir();
-        let _lock = FileLock::acquire(lock_path_for_state_dir(state_dir))?;
+        let lock = FileLock::acquire(lock_path_for_state_dir(state_dir))?;
         let project = projects::find_project_in(state_dir, args.project_id)
             .ok_or_else(|| anyhow!("Project '{}' not found", args.project_id))?;
         let client = self.client();
@@ -1496,12 +3622,60 @@ impl OmarMcpServer {
                 args.project_id
             ));
         }
+        let issue = github::issue_for_project_in(state_dir, project.id);
+        if let Some((number, _)) = issue {
+            github::mark_completed_in(state_dir, number)?;
+        }
         self.refresh_memory_locked()?;
-        Ok(json!({
+        drop(lock);
+        let mut result = json!({
             "project_id": project.id,
             "name": project.name,
             "status": "completed",
-        }))
+        });
+        if let Some((number, url)) = issue {
+            let comment = github::completion_comment(&project, args.summary.as_deref());
+            let posted = self.load_config().and_then(|config| {
+                github::off_runtime(move || {
+                    github::GithubClient::from_config(&config.github)?
+                        .ok_or_else(|| anyhow!("github.repo is no longer set"))?
+                        .comment(number, &comment)
+                })
+            });
+            result["github_issue"] = json!({
+                "number": number,
+                "url": url,
+                "comment": match posted {
+                    Ok(()) => "posted".to_string(),
+                    Err(e) => format!("failed: {}", e),
+                },
+            });
+        }
+        Ok(result)
+    }
+
+    fn sync_github_issues(&self) -> Result<Value> {
+        let state_dir = self.state_dir();
+        let config = self.load_config()?.github;
+        let sync_dir = state_dir.to_path_buf();
+        let imported = github::off_runtime(move || github::sync_in(&sync_dir, &config))?
+            .ok_or_else(|| anyhow!("GitHub sync is off; set github.repo in config.toml"))?;
+        if !imported.is_empty() {
+            let _lock = FileLock::acquire(lock_path_for_state_dir(state_dir))?;
+            self.refresh_memory_locked()?;
+        }
+        let imported: Vec<Value> = imported
+            .iter()
+            .map(|issue| {
+                json!({
+                    "number": issue.number,
+                    "title": issue.title,
+                    "url": issue.url,
+                    "project_id": issue.project_id,
+                })
+            })
+            .collect();
+        Ok(json!({ "imported": imported }))
     }
 
     /// Queue a Slack reply for the `omar-slack-bridge` peer to pick up and
@@ -1550,21 +3724,21 @@ impl OmarMcpServer {
             agent_name: String,
             action: String,
             justification: String,
+            trace_id: Option<String>,
         }
         let args: Args = serde_json::from_value(args)?;
-        let state_dir = self.state_dir();
-        let _lock = FileLock::acquire(lock_path_for_state_dir(state_dir))?;
-        let path = state_dir.join("action_log.jsonl");
-        fs::create_dir_all(state_dir).ok();
-        let line = serde_json::to_string(&json!({
-            "timestamp": now_rfc3339(),
-            "ea_id": self.ea_id(),
-            "agent_name": args.agent_name,
-            "action": args.action,
-            "justification": args.justification,
-        }))?;
-        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
-        writeln!(file, "{}", line)?;
+        let trace_id = args.trace_id.clone().or_else(|| {
+            let session = self.qualified_session_name(&args.agent_name).ok()?;
+            memory::load_agent_traces_from(self.state_dir()).remove(&session)
+        });
+        let path = append_action_log(
+            self.state_dir(),
+            self.ea_id(),
+            &args.agent_name,
+            &args.action,
+            &args.justification,
+            trace_id.as_deref(),
+        )?;
         Ok(json!({ "status": "logged", "path": path }))
     }
 
@@ -1823,18 +3997,41 @@ impl OmarMcpServer {
     }
 }
 
-fn generate_agent_name_in_ea(prefix: &str) -> String {
+/// Output patterns that drive health detection: rate-limit messages (all
+/// backends, then per backend) and each backend's ready prompt.
+fn health_patterns_json(config: &config::Config) -> Value {
+    json!({
+        "rate_limit": config.health.rate_limit.patterns,
+        "rate_limit_backends": config.health.rate_limit.backends,
+        "ready": config.agent.readiness.patterns,
+    })
+}
+
+/// Correlation ID tying a spawn to its logs, transcript, and result.
+fn new_trace_id() -> String {
+    Uuid::new_v4().simple().to_string()[..12].to_string()
+}
+
+fn valid_trace_id(trace_id: &str) -> bool {
+    !trace_id.is_empty()
+        && trace_id.len() <= 64
+        && trace_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+}
+
+fn generate_agent_name_in_ea(prefix: &str) -> AgentId {
     for i in 1..1000 {
-        let name = format!("{}{}", prefix, i);
+        let id = AgentId::resolve(prefix, &i.to_string());
         let result = crate::tmux::tmux_command()
-            .args(["has-session", "-t", &name])
+            .args(["has-session", "-t", id.session()])
             .output();
         match result {
-            Ok(output) if !output.status.success() => return name,
+            Ok(output) if !output.status.success() => return id,
             _ => continue,
         }
     }
-    format!("{}{}", prefix, &Uuid::new_v4().to_string()[..8])
+    AgentId::resolve(prefix, &Uuid::new_v4().to_string()[..8])
 }
 
 fn read_message(reader: &mut impl BufRead) -> Result<Option<McpRead>> {
@@ -2007,14 +4204,72 @@ fn format_list_agents_text(value: &Value) -> String {
     lines.join("\n")
 }
 
+/// Validation failures also carry their `errors` array as structured
+/// content.
 fn tool_error(err: anyhow::Error) -> Value {
-    json!({
+    let mut response = json!({
         "content": [{
             "type": "text",
             "text": err.to_string(),
         }],
         "isError": true,
-    })
+    });
+    if let Some(invalid) = err.downcast_ref::<ValidationError>() {
+        response["structuredContent"] = json!(invalid);
+    }
+    response
+}
+
+/// The `tools/list` payload, for `omar mcp-schema`.
+pub fn tool_schema_json() -> Value {
+    json!({ "tools": tool_definitions() })
+}
+
+/// Markdown reference generated from the tool definitions, so docs and
+/// prompts can be regenerated instead of hand-maintained.
+pub fn tool_schema_markdown() -> String {
+    let mut out = String::from("# OMAR MCP tools\n");
+    for tool in tool_definitions() {
+        let name = tool["name"].as_str().unwrap_or_default();
+        let description = tool["description"].as_str().unwrap_or_default();
+        out.push_str(&format!("\n## `{}`\n\n{}\n", name, description));
+
+        let schema = &tool["inputSchema"];
+        let Some(properties) = schema["properties"].as_object().filter(|p| !p.is_empty()) else {
+            continue;
+        };
+        let required: Vec<&str> = schema["required"]
+            .as_array()
+            .map(|names| names.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        out.push_str("\n| Parameter | Type | Required | Description |\n|---|---|---|---|\n");
+        for (param, spec) in properties {
+            let kind = match &spec["type"] {
+                Value::String(kind) => kind.clone(),
+                Value::Array(kinds) => kinds
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(" \\| "),
+                _ => "any".to_string(),
+            };
+            out.push_str(&format!(
+                "| `{}` | {} | {} | {} |\n",
+                param,
+                kind,
+                if required.contains(&param.as_str()) {
+                    "yes"
+                } else {
+                    "no"
+                },
+                spec["description"]
+                    .as_str()
+                    .unwrap_or("")
+                    .replace('|', "\\|")
+            ));
+        }
+    }
+    out
 }
 
 fn tool_definitions() -> Vec<Value> {
@@ -2022,140 +4277,601 @@ fn tool_definitions() -> Vec<Value> {
     TOOLS
         .get_or_init(|| vec![
         tool(
-            "list_backends",
-            "List installed OMAR agent backends and their commands. Use before choosing a backend/model override when availability is unclear. Read-only and safe to retry.",
-            json!({"type":"object","properties":{},"additionalProperties":false}),
+            "list_backends",
+            "List installed OMAR agent backends and their commands. Use before choosing a backend/model override when availability is unclear. Read-only and safe to retry.",
+            json!({"type":"object","properties":{},"additionalProperties":false}),
+        ),
+        tool(
+            "list_eas",
+            "List registered Executive Assistants (EAs) visible to this OMAR state directory. Read-only and safe to retry.",
+            json!({"type":"object","properties":{},"additionalProperties":false}),
+        ),
+        tool(
+            "get_active_ea",
+            "Get the EA currently selected on disk for dashboard/CLI defaults. This MCP server remains pinned to its launch EA, so this is informational for this session. Read-only and safe to retry.",
+            json!({"type":"object","properties":{},"additionalProperties":false}),
+        ),
+        tool(
+            "switch_ea",
+            "Switch the persisted active-EA pointer read by the dashboard and future default CLI operations. Side effect: writes the active EA selection on disk. Does not change this MCP server's pinned EA context. Safe to retry with the same ea_id; fails if the EA does not exist.",
+            json!({
+                "type":"object",
+                "properties":{"ea_id":{"type":"integer"}},
+                "required":["ea_id"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "create_ea",
+            "Create a new EA registry entry and state directory. Use when a distinct team/context should own its own agents, projects, notes, and scheduled events. Side effect: persists a new EA. Not idempotent; retrying can create duplicates with the same name. Fails on invalid or empty names.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "name":{"type":"string"},
+                    "description":{"type":"string"}
+                },
+                "required":["name"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "delete_ea",
+            "Delete an EA, kill its sessions, remove its state, and cancel its scheduled events. Use only for intentional cleanup. Destructive and not retry-safe after success. Fails if this is the only remaining EA or the EA id is unknown.",
+            json!({
+                "type":"object",
+                "properties":{"ea_id":{"type":"integer"}},
+                "required":["ea_id"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "list_agents",
+            "List running agents in this MCP server's EA with health (running, idle, finished, blocked), tags, refresh priority, last-output summary, and pending_question (what an idle or blocked agent is asking, else null), including agents on configured [[hosts]] (host is null for local agents). Agents with children also report team_health, the most urgent state among their descendants. Pass tag to list only agents carrying it. Use for monitoring and straggler discovery. Read-only and safe to retry.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "tag":{"type":"string","description":"Only list agents with this tag (case-insensitive)."}
+                },
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "get_topology",
+            "Graph of this EA's fleet: the EA, PMs (agents with children), and workers with their health, plus edges for who manages whom, pipeline stage dependencies, and mailbox messages between agents. format \"dot\" or \"mermaid\" adds the graph as Graphviz or Mermaid source in graph, ready to embed in docs. Read-only and safe to retry.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "format":{"type":"string","enum":["json","dot","mermaid"],"description":"json (default) returns only nodes and edges."}
+                },
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "get_agent",
+            "Get detailed output tail for one running agent. Use to inspect a stuck or completed-looking worker before deciding whether to send input, wait, or kill it. Read-only and safe to retry. Fails if the agent is not running in this EA.",
+            json!({
+                "type":"object",
+                "properties":{"name":{"type":"string","description":"Short agent name without the session prefix."}},
+                "required":["name"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "exec_agent",
+            "Run a shell command in an agent's sandbox, in its workdir, and return exit_code, stdout, and stderr (each cut to its last 64 KiB). The command goes through the same [sandbox] runtime the agent was launched with, so it sees the files the agent wrote; agents launched unsandboxed run it directly in their workdir (sandboxed is false). Use to inspect a worker's output without attaching to its pane. Side effects are whatever the command does; safe to retry for read-only commands. Killed after timeout_secs (timed_out true, exit_code null). Fails if the agent was not spawned by omar or the sandbox runtime is missing.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "name":{"type":"string","description":"Short agent name without the session prefix."},
+                    "command":{"type":"string","description":"Shell command to run, e.g. 'git status' or 'cat out/report.md'."},
+                    "timeout_secs":{"type":"integer","minimum":1,"maximum":600,"description":"Kill the command after this many seconds. Default 30."}
+                },
+                "required":["name","command"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "rollback_agent",
+            "Undo a worker's changes to its git workdir: restore every file under the workdir to the checkpoint taken before its task was sent, delete files created there since (ignored files are left alone), and put the workdir's staged changes back as they were. Files outside the workdir, HEAD, and commits the worker made are not touched. Works after the worker is killed. Use when a worker damaged its tree; stop it first or it may keep writing. Destructive to uncommitted work made since the checkpoint; retrying restores the same checkpoint again. Fails if no checkpoint was taken (agent.checkpoint_workdir off, or the workdir is not in a git repository).",
+            json!({
+                "type":"object",
+                "properties":{"name":{"type":"string","description":"Short agent name without the session prefix."}},
+                "required":["name"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "get_agent_summary",
+            "Get one agent's tracked task, self-reported status, health, team_health (most urgent descendant state, null without children), last completion result, and child-agent summary without the full output tail. Use for lightweight monitoring. Read-only and safe to retry. Fails if the agent is not running in this EA.",
+            json!({
+                "type":"object",
+                "properties":{"name":{"type":"string","description":"Short agent name without the session prefix."}},
+                "required":["name"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "get_agent_history",
+            "Get an agent's recent health transitions (oldest first, each with an RFC3339 time and state) as recorded by the dashboard, plus how many changes happened in the last 10 minutes. Use to spot agents flapping between running and idle or blocked. Empty until the dashboard has observed the agent. Read-only and safe to retry.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "name":{"type":"string","description":"Short agent name without the session prefix."}
+                },
+                "required":["name"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "get_agent_heartbeat",
+            "Get machine-oriented health for one agent: state (running, idle, finished, blocked, rate_limited, or missing), last_change, idle_secs, and liveness booleans (session_alive, pane_alive, producing_output, stuck). With fail_if, ok is false and failed lists the conditions that hold. Read-only and safe to retry; a missing agent is reported, not an error.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "name":{"type":"string","description":"Short agent name without the session prefix."},
+                    "fail_if":{"type":"string","description":"Comma-separated conditions that make ok false: missing, dead, idle, blocked, stuck, rate_limited."}
+                },
+                "required":["name"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "update_agent_status",
+            "Update a running agent's one-line dashboard status. Use after meaningful milestones or when blocked. Side effect: writes status metadata for display/recovery. Safe to retry with the same status; later calls replace the displayed status. Fails if the agent name is invalid or not tracked.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "name":{"type":"string","description":"Your own agent name."},
+                    "status":{"type":"string","description":"One-line status shown in the dashboard, e.g. 'Writing auth module - 60% done'."}
+                },
+                "required":["name","status"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "set_agent_note",
+            "Attach a freeform note to an agent, replacing any earlier one; an empty note removes it. Notes are for people and the EA, not the agent: they are never sent to it, but are shown on its dashboard card, returned as note by list_agents/get_agent, and written under the agent in memory.md. Safe to retry. Fails if the agent is not running in this EA.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "name":{"type":"string","description":"Agent name."},
+                    "note":{"type":"string","description":"Note text; may span several lines. Empty to remove the note."}
+                },
+                "required":["name","note"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "report_progress",
+            "Report your own structured progress: percent done, the current step, and anything blocking you. Shown on your dashboard card, in the chain-of-command tree, and as progress in list_agents/get_agent. Each report replaces the previous one, so send the full picture; an empty blockers list clears them. Also served as POST /api/agents/<name>/report for agents without MCP. Safe to retry. Fails if the agent is not running in this EA or percent is over 100.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "name":{"type":"string","description":"Your own agent name."},
+                    "percent":{"type":"integer","minimum":0,"maximum":100,"description":"Estimated percent of the task done."},
+                    "step":{"type":"string","description":"What you are doing now, e.g. 'Writing migration tests'."},
+                    "blockers":{"type":"array","items":{"type":"string"},"description":"What stops you from continuing, if anything."}
+                },
+                "required":["name"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "set_agent_priority",
+            "Set how closely the dashboard monitors an agent. hot agents are health-checked every refresh against a deeper slice of their output; cold agents only every 10th refresh, keeping their last state in between; normal is the default. Use cold for long-running background workers in large fleets and hot for the few on the critical path. Safe to retry. Fails if the agent is not running in this EA.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "name":{"type":"string","description":"Short agent name without the session prefix."},
+                    "priority":{"type":"string","enum":["normal","hot","cold"]}
+                },
+                "required":["name","priority"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "get_health_patterns",
+            "Read the output patterns behind health detection: rate_limit (case-insensitive regexes marking any agent rate-limited), rate_limit_backends (extra regexes per backend), and ready (the regex per backend that shows its prompt is ready for a task). Read-only.",
+            json!({"type":"object","properties":{},"additionalProperties":false}),
+        ),
+        tool(
+            "set_health_patterns",
+            "Replace health detection patterns when a backend update changes its rate-limit or prompt text. Each field given replaces that whole list or map; omitted fields are kept. Saved to config.toml and applied without restart. Returns the patterns now in effect. Safe to retry.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "rate_limit":{"type":"array","items":{"type":"string"}},
+                    "rate_limit_backends":{"type":"object","additionalProperties":{"type":"array","items":{"type":"string"}}},
+                    "ready":{"type":"object","additionalProperties":{"type":"string"},"description":"Ready-prompt regex by backend, e.g. {\"codex\": \"^› \"}."}
+                },
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "spawn_agent",
+            "Spawn one tracked agent session in the current EA. Use for delegated work, PM/worker decomposition, or raw demo/bash windows. Requires an existing project_id; call list_projects/add_project first because spawn_agent never auto-creates projects. Side effects: creates a tmux session, records task/project/parent metadata, and delivers the initial task prompt once the backend shows its ready prompt, unless command starts a raw session. The result's spawn_status is waiting_for_agent while that is still pending, ready once the task was sent, or failed if the agent exited first; list_agents/get_agent report it afterwards. Not retry-safe with the same name after success; retry only after checking list_agents/get_agent. Common failures: project not found, duplicate agent name, invalid parent/project relationship, backend unavailable, or both backend and command set. Invalid fields are all reported in one error whose structuredContent.errors lists each as {field, code, message}; fix them all before retrying. When agent.max_concurrent workers are already running, the request is queued instead (status 'queued' with queue_position) and the dashboard starts it once a worker completes or is killed; kill_agent on a queued name dequeues it.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "name":{"type":"string","description":"Short agent name (session prefix is added automatically)."},
+                    "project_id":{"type":"integer","description":"Existing project id from add_project or list_projects. Required — spawn_agent does not auto-create projects."},
+                    "task":{"type":"string","description":"Delivered to the agent as their initial task and shown in the dashboard. What to build or do — no [TASK COMPLETE] or parent-wakeup instructions; those are already in every agent's system prompt."},
+                    "command":{"type":"string","description":"Raw command to run instead of a backend agent (e.g. 'bash' for a demo window). Mutually exclusive with backend."},
+                    "backend":{"type":"string","enum":["claude","codex","cursor","opencode","agy"],"description":"Backend agent command to launch. Mutually exclusive with command."},
+                    "model":{"type":"string","description":"Optional backend model override. Allowed characters are alphanumeric plus '-', '_', '.', '/'."},
+                    "reasoning_effort":{"type":"string","enum":["low","medium","high","xhigh"],"description":"Optional Codex reasoning effort override. Supported only with backend='codex'; appends a Codex config override such as -c model_reasoning_effort='\"high\"'."},
+                    "workdir":{"type":"string","description":"Working directory for the new session. Defaults to this MCP server's launch workdir."},
+                    "parent":{"type":"string","description":"Parent agent name for hierarchy tracking. Omit only for new EA-owned top-level work; use your own name for child tasks. Pass 'ea' only for intentional EA-owned work."},
+                    "tags":{"type":"array","items":{"type":"string"},"description":"Free-form labels such as 'frontend' or 'p1'. Filter on them with list_agents tag or the dashboard's / prompt."},
+                    "priority":{"type":"string","enum":["normal","hot","cold"],"description":"Dashboard refresh priority; see set_agent_priority. Defaults to normal."},
+                    "role":{"type":"string","enum":["pm","worker","reviewer"],"description":"The agent's place in the hierarchy, shown as a badge in the dashboard tree and returned by list_agents/get_agent. Use pm for an agent that will spawn and supervise its own workers. Defaults to worker."},
+                    "env":{"type":"object","additionalProperties":{"type":"string"},"description":"Environment variables for the agent's session, e.g. {'GIT_AUTHOR_NAME':'api-bot','FEATURE_X':'1'}. Overrides the user's [agent.env]. Use secrets, not env, for credentials the user has configured."},
+                    "secrets":{"type":"array","items":{"type":"string"},"description":"Names of secrets from the user's [secrets] config to set as environment variables of the same name in the agent's session, e.g. ['GITHUB_TOKEN']. Pass names only; never put secret values in the task. Fails if a name is not configured."},
+                    "panes":{"type":"array","items":{"type":"string"},"description":"Shell commands to run in extra panes of the agent's session, in its workdir, e.g. ['npm test -- --watch', 'tail -f build.log']. The dashboard cycles an agent card through them with { and }."},
+                    "profile":{"type":"string","description":"Name of a [[profiles]] entry in the user's config supplying the command, workdir, sandbox runtime, and environment variables. Explicit command, backend, and workdir take precedence."},
+                    "template":{"type":"string","description":"Name of a prompt template in ~/.omar/templates/<name>.md. It is rendered with {{task}}, {{repo}} (the workdir), {{conventions}} (the repo's CONVENTIONS.md or AGENTS.md) and template_vars, and delivered as the worker's task."},
+                    "template_vars":{"type":"object","additionalProperties":{"type":"string"},"description":"Extra values for {{name}} placeholders in the template."},
+                    "trace_id":{"type":"string","description":"Correlation ID for this spawn, returned in the response and recorded in the action log, the transcript, and get_agent. Defaults to the parent worker's trace, or a fresh ID."},
+                    "wait_for":{
+                        "type":"object",
+                        "description":"Hold the spawn until its input exists, so the worker does not start against missing interfaces: {path} waits for a file or directory (relative to workdir), {agent, state} for another agent in this EA to complete (the default) or exit. Until then the request waits in the spawn queue with status 'waiting' and is started by the dashboard once the dependency is met; kill_agent on the name cancels it.",
+                        "properties":{
+                            "path":{"type":"string","description":"File or directory that must exist, e.g. 'api/openapi.yaml'."},
+                            "agent":{"type":"string","description":"Agent name to wait for. It must be running, queued, or already complete."},
+                            "state":{"type":"string","enum":["complete","exited"],"description":"With agent: wait until it reports [TASK COMPLETE] (complete) or its session is gone (exited). Defaults to complete."}
+                        },
+                        "additionalProperties":false
+                    }
+                },
+                "required":["name","project_id","task"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "kill_agent",
+            "Kill a running worker/demo agent in this EA. Use for intentional cleanup, abandoned work, or replacement after inspection. group=true also kills every agent reporting to it (e.g. a PM and its workers), deepest first, refusing the whole group if any member is attached. Side effects: kills the tmux sessions, removes parent/project/result metadata, and cancels scheduled events for those agents. Not retry-safe after success; a second call fails because the agent no longer exists. Cannot kill the EA manager or attached sessions.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "name":{"type":"string"},
+                    "group":{"type":"boolean","description":"Also kill all descendants of this agent"}
+                },
+                "required":["name"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "rename_agent",
+            "Give a running worker a meaningful name, e.g. once an auto-named agent has a real task. Renames its tmux session and moves its parent link, task, project, tags, results, mailbox, transcript, and scheduled events to the new name; children are repointed. The worker and its children are told the new name. Fails if the agent is not running, is the manager, or new_name is taken or invalid.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "name":{"type":"string","description":"Current short agent name."},
+                    "new_name":{"type":"string","description":"New short name: no whitespace, '.' or ':'."},
+                    "note":{"type":"string","description":"Optional note to set with the rename, as set_agent_note; empty removes it."}
+                },
+                "required":["name","new_name"],
+                "additionalProperties":false
+            }),
         ),
         tool(
-            "list_eas",
-            "List registered Executive Assistants (EAs) visible to this OMAR state directory. Read-only and safe to retry.",
-            json!({"type":"object","properties":{},"additionalProperties":false}),
+            "drain_fleet",
+            "Wind down the whole OMAR fleet in order: stop starting new agents (spawn_agent, the spawn queue, and schedules refuse), let running workers in every EA finish, then the dashboard saves memory, stops all agents and EAs, and exits. deadline_secs stops anyway after that many seconds. action status reports progress; cancel resumes normal dispatch. Starting twice only updates the deadline.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "action":{"type":"string","enum":["start","status","cancel"],"description":"Default start."},
+                    "deadline_secs":{"type":"integer","minimum":1,"description":"Stop remaining workers after this many seconds (start only)."}
+                },
+                "additionalProperties":false
+            }),
         ),
         tool(
-            "get_active_ea",
-            "Get the EA currently selected on disk for dashboard/CLI defaults. This MCP server remains pinned to its launch EA, so this is informational for this session. Read-only and safe to retry.",
-            json!({"type":"object","properties":{},"additionalProperties":false}),
+            "send_input",
+            "Send text to a running agent or raw demo session. Use for follow-up instructions, concrete unblocking messages, or demo commands. Side effect: injects text into the target tmux pane and optionally presses Enter. Not generally retry-safe because duplicate input may execute twice. Fails if the target agent is not running.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "name":{"type":"string","description":"Short target agent/session name."},
+                    "text":{"type":"string","description":"Literal text to send."},
+                    "enter":{"type":"boolean","description":"Whether to press Enter after text. Defaults to false."}
+                },
+                "required":["name","text"],
+                "additionalProperties":false
+            }),
         ),
         tool(
-            "switch_ea",
-            "Switch the persisted active-EA pointer read by the dashboard and future default CLI operations. Side effect: writes the active EA selection on disk. Does not change this MCP server's pinned EA context. Safe to retry with the same ea_id; fails if the EA does not exist.",
+            "send_message",
+            "Queue a message in an agent's mailbox. Unlike send_input, the dashboard types it in only once the agent is idle or waiting for input, never mid-generation; queued messages go in one at a time, oldest first. Side effect: appends to the mailbox. Not retry-safe; a retry queues a duplicate. Fails if the target agent is not running.",
             json!({
                 "type":"object",
-                "properties":{"ea_id":{"type":"integer"}},
-                "required":["ea_id"],
+                "properties":{
+                    "name":{"type":"string","description":"Short target agent/session name."},
+                    "text":{"type":"string","description":"Message text."},
+                    "from":{"type":"string","description":"Sender shown in the delivered message, usually your agent name. Defaults to \"user\"."}
+                },
+                "required":["name","text"],
                 "additionalProperties":false
             }),
         ),
         tool(
-            "create_ea",
-            "Create a new EA registry entry and state directory. Use when a distinct team/context should own its own agents, projects, notes, and scheduled events. Side effect: persists a new EA. Not idempotent; retrying can create duplicates with the same name. Fails on invalid or empty names.",
+            "send_file",
+            "Hand an agent a large piece of context as a file instead of typing it in. Writes content (or a copy of the host file at path) to .omar-context/ in the agent's working directory, then queues a short mailbox message, like send_message, telling the agent to read it. Side effect: creates a file and appends to the mailbox. Not retry-safe; a retry writes another file and queues another message. Fails if the agent is not running or the content exceeds 10 MB.",
             json!({
                 "type":"object",
                 "properties":{
-                    "name":{"type":"string"},
-                    "description":{"type":"string"}
+                    "name":{"type":"string","description":"Short target agent/session name."},
+                    "content":{"type":"string","description":"Text to deliver. Give this or path."},
+                    "path":{"type":"string","description":"Host file to copy to the agent. Give this or content."},
+                    "filename":{"type":"string","description":"Name for the delivered file (letters, digits, '.', '-', '_'). Defaults to path's name or context.md."},
+                    "note":{"type":"string","description":"Short message sent ahead of the pointer to the file."},
+                    "from":{"type":"string","description":"Sender shown in the delivered message. Defaults to \"user\"."}
                 },
                 "required":["name"],
                 "additionalProperties":false
             }),
         ),
         tool(
-            "delete_ea",
-            "Delete an EA, kill its sessions, remove its state, and cancel its scheduled events. Use only for intentional cleanup. Destructive and not retry-safe after success. Fails if this is the only remaining EA or the EA id is unknown.",
+            "get_messages",
+            "List an agent's mailbox, oldest first, with each message's delivery status (queued, delivered, failed), queue and delivery times, and any delivery error. Keeps the last 50 delivered or failed messages. Read-only and safe to retry.",
             json!({
                 "type":"object",
-                "properties":{"ea_id":{"type":"integer"}},
-                "required":["ea_id"],
+                "properties":{
+                    "name":{"type":"string","description":"Short target agent/session name."}
+                },
+                "required":["name"],
                 "additionalProperties":false
             }),
         ),
         tool(
-            "list_agents",
-            "List running agents in this MCP server's EA with health and last-output summary. Use for monitoring and straggler discovery. Read-only and safe to retry.",
+            "search_output",
+            "Search the scrollback of every agent in this EA (including the EA itself) for a pattern, e.g. which worker touched payments.rs. Case-insensitive regex; invalid regexes are matched literally. Returns agent, line number within the scrollback, matching text, surrounding context, and the agent's last activity time. Read-only and safe to retry.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "pattern":{"type":"string","description":"Regex or literal text to find."},
+                    "context_lines":{"type":"integer","description":"Lines of context around each match (default 2, max 10)."},
+                    "limit":{"type":"integer","description":"Maximum matches to return (default 50, max 200)."}
+                },
+                "required":["pattern"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "tail_logs",
+            "Poll an interleaved tail of new output lines from the EA and every agent in it, like `docker compose logs -f`. The first call returns recent output; pass the returned cursor on the next call to get only lines appended since. Lines are diffed from pane captures, so redrawn TUI rows may repeat. Read-only and safe to retry.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "cursor":{"type":"integer","description":"Cursor returned by the previous call; omit to start from the buffered tail."},
+                    "limit":{"type":"integer","description":"Maximum lines to return, newest kept (default 200, max 1000)."}
+                },
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "run_pipeline",
+            "Start a named pipeline from [pipelines.<name>] in config.toml. Stages run sequentially as agents (e.g. plan → implement → test → review); each stage's [TASK COMPLETE] summary is substituted for {{previous}} in the next stage's prompt, and input for {{input}}. The dashboard advances the run; poll list_pipelines for progress.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "pipeline":{"type":"string","description":"Pipeline name as defined in config.toml."},
+                    "input":{"type":"string","description":"Pipeline input substituted for {{input}} in stage prompts."}
+                },
+                "required":["pipeline","input"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "get_memory_history",
+            "List saved versions of this EA's memory.md (the state snapshot a restarted EA resumes from; the last 20 distinct versions are kept) and optionally diff two of them to see what changed. Read-only and safe to retry.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "diff":{"type":"boolean","description":"Include a unified diff (default: between the latest two versions)."},
+                    "from":{"type":"string","description":"Older snapshot id to diff from (implies diff)."},
+                    "to":{"type":"string","description":"Newer snapshot id to diff to (implies diff; default: latest)."}
+                },
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "list_schedules",
+            "List this EA's recurring scheduled spawns ([[schedules]] in config.toml and ones added with add_schedule) with their next and last run times. Read-only and safe to retry.",
             json!({"type":"object","properties":{},"additionalProperties":false}),
         ),
         tool(
-            "get_agent",
-            "Get detailed output tail for one running agent. Use to inspect a stuck or completed-looking worker before deciding whether to send input, wait, or kill it. Read-only and safe to retry. Fails if the agent is not running in this EA.",
+            "add_schedule",
+            "Add a recurring cron-style spawn: every time the cron expression fires, the dashboard spawns a fresh worker named '<name>-<MMDD-HHMM>' under the EA with the given task (e.g. cron '0 2 * * *' to run the test suite nightly at 2am local time). Requires the dashboard to be running.",
             json!({
                 "type":"object",
-                "properties":{"name":{"type":"string","description":"Short agent name without the session prefix."}},
-                "required":["name"],
+                "properties":{
+                    "name":{"type":"string","description":"Unique schedule name; prefix of spawned agent names."},
+                    "cron":{"type":"string","description":"Five-field cron expression in local time: minute hour day-of-month month day-of-week."},
+                    "task":{"type":"string","description":"Task given to each spawned worker."},
+                    "backend":{"type":"string","description":"Backend preset (default: agent.default_command)."},
+                    "workdir":{"type":"string","description":"Working directory (default: agent.default_workdir)."}
+                },
+                "required":["name","cron","task"],
                 "additionalProperties":false
             }),
         ),
         tool(
-            "get_agent_summary",
-            "Get one agent's tracked task, self-reported status, health, and child-agent summary without the full output tail. Use for lightweight monitoring. Read-only and safe to retry. Fails if the agent is not running in this EA.",
+            "remove_schedule",
+            "Remove a schedule added with add_schedule. Schedules from config.toml must be removed there.",
             json!({
                 "type":"object",
-                "properties":{"name":{"type":"string","description":"Short agent name without the session prefix."}},
+                "properties":{
+                    "name":{"type":"string","description":"Schedule name."}
+                },
                 "required":["name"],
                 "additionalProperties":false
             }),
         ),
         tool(
-            "update_agent_status",
-            "Update a running agent's one-line dashboard status. Use after meaningful milestones or when blocked. Side effect: writes status metadata for display/recovery. Safe to retry with the same status; later calls replace the displayed status. Fails if the agent name is invalid or not tracked.",
+            "list_pipelines",
+            "List pipeline definitions and this EA's pipeline runs with per-stage agents and results. Read-only and safe to retry.",
+            json!({"type":"object","properties":{},"additionalProperties":false}),
+        ),
+        tool(
+            "list_agent_files",
+            "List a directory inside an agent's working directory (its tmux pane's current path) to see what the agent created. Paths are relative to that root and cannot escape it. Local agents only. Read-only and safe to retry.",
             json!({
                 "type":"object",
                 "properties":{
-                    "name":{"type":"string","description":"Your own agent name."},
-                    "status":{"type":"string","description":"One-line status shown in the dashboard, e.g. 'Writing auth module - 60% done'."}
+                    "name":{"type":"string","description":"Agent short name or full session name."},
+                    "path":{"type":"string","description":"Directory relative to the agent workdir (default: the root)."}
                 },
-                "required":["name","status"],
+                "required":["name"],
                 "additionalProperties":false
             }),
         ),
         tool(
-            "spawn_agent",
-            "Spawn one tracked agent session in the current EA. Use for delegated work, PM/worker decomposition, or raw demo/bash windows. Requires an existing project_id; call list_projects/add_project first because spawn_agent never auto-creates projects. Side effects: creates a tmux session, records task/project/parent metadata, and delivers the initial task prompt unless command starts a raw session. Not retry-safe with the same name after success; retry only after checking list_agents/get_agent. Common failures: project not found, duplicate agent name, invalid parent/project relationship, backend unavailable, or both backend and command set.",
+            "read_agent_file",
+            "Read a text file inside an agent's working directory. Reads at most 256 KiB (`truncated` is set when the file is larger); binary files are refused. Local agents only. Read-only and safe to retry.",
             json!({
                 "type":"object",
                 "properties":{
-                    "name":{"type":"string","description":"Short agent name (session prefix is added automatically)."},
-                    "project_id":{"type":"integer","description":"Existing project id from add_project or list_projects. Required — spawn_agent does not auto-create projects."},
-                    "task":{"type":"string","description":"Delivered to the agent as their initial task and shown in the dashboard. What to build or do — no [TASK COMPLETE] or parent-wakeup instructions; those are already in every agent's system prompt."},
-                    "command":{"type":"string","description":"Raw command to run instead of a backend agent (e.g. 'bash' for a demo window). Mutually exclusive with backend."},
-                    "backend":{"type":"string","enum":["claude","codex","cursor","opencode","agy"],"description":"Backend agent command to launch. Mutually exclusive with command."},
-                    "model":{"type":"string","description":"Optional backend model override. Allowed characters are alphanumeric plus '-', '_', '.', '/'."},
-                    "reasoning_effort":{"type":"string","enum":["low","medium","high","xhigh"],"description":"Optional Codex reasoning effort override. Supported only with backend='codex'; appends a Codex config override such as -c model_reasoning_effort='\"high\"'."},
-                    "workdir":{"type":"string","description":"Working directory for the new session. Defaults to this MCP server's launch workdir."},
-                    "parent":{"type":"string","description":"Parent agent name for hierarchy tracking. Omit only for new EA-owned top-level work; use your own name for child tasks. Pass 'ea' only for intentional EA-owned work."}
+                    "name":{"type":"string","description":"Agent short name or full session name."},
+                    "path":{"type":"string","description":"File path relative to the agent workdir."},
+                    "max_bytes":{"type":"integer","minimum":1,"description":"Read at most this many bytes (default and cap: 262144)."}
                 },
-                "required":["name","project_id","task"],
+                "required":["name","path"],
                 "additionalProperties":false
             }),
         ),
         tool(
-            "kill_agent",
-            "Kill a running worker/demo agent in this EA. Use for intentional cleanup, abandoned work, or replacement after inspection. Side effects: kills the tmux session, removes parent/project metadata, and cancels scheduled events for that agent. Not retry-safe after success; a second call fails because the agent no longer exists. Cannot kill the EA manager or attached sessions.",
+            "get_agent_transcript",
+            "Return an agent's full output history as cleaned (ANSI-stripped), timestamped Markdown, assembled from its pane log. Works after the agent has exited. Read-only and safe to retry.",
             json!({
                 "type":"object",
-                "properties":{"name":{"type":"string"}},
+                "properties":{
+                    "name":{"type":"string","description":"Agent short name or full session name."},
+                    "max_lines":{"type":"integer","description":"Keep only the most recent N transcript lines (default: all)."}
+                },
                 "required":["name"],
                 "additionalProperties":false
             }),
         ),
         tool(
-            "send_input",
-            "Send text to a running agent or raw demo session. Use for follow-up instructions, concrete unblocking messages, or demo commands. Side effect: injects text into the target tmux pane and optionally presses Enter. Not generally retry-safe because duplicate input may execute twice. Fails if the target agent is not running.",
+            "get_plan",
+            "Get the plan the EA manager last proposed (a `plan` message in its recent output): description, proposed agents with role, task, dependencies, and a duration/cost estimate from past runs. plan is null when none is pending. Read-only and safe to retry.",
+            json!({
+                "type":"object",
+                "properties":{},
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "approve_plan",
+            "Approve the manager's pending plan: spawns each proposed worker (existing sessions are skipped) and tells the manager which agents started. Refused while draining. Side effect: starts agents; retrying re-notifies the manager but does not duplicate workers.",
+            json!({
+                "type":"object",
+                "properties":{},
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "reject_plan",
+            "Reject the manager's pending plan and send it the reason so it can revise. Side effect: types a message into the manager session; retrying sends it again.",
             json!({
                 "type":"object",
                 "properties":{
-                    "name":{"type":"string","description":"Short target agent/session name."},
-                    "text":{"type":"string","description":"Literal text to send."},
-                    "enter":{"type":"boolean","description":"Whether to press Enter after text. Defaults to false."}
+                    "reason":{"type":"string","description":"Why the plan was rejected, sent to the manager."}
                 },
-                "required":["name","text"],
+                "required":["reason"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "estimate_plan",
+            "Estimate duration and cost for each agent of a proposed plan from past runs with the same name/role or a similar task. Include the estimates when presenting a plan for approval. Read-only and safe to retry.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "agents":{
+                        "type":"array",
+                        "items":{
+                            "type":"object",
+                            "properties":{
+                                "name":{"type":"string","description":"Proposed agent name."},
+                                "role":{"type":"string","description":"Short role description."},
+                                "task":{"type":"string","description":"Task the agent would receive."}
+                            },
+                            "required":["name","role","task"]
+                        }
+                    }
+                },
+                "required":["agents"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "list_pending_approvals",
+            "List pipeline runs paused before a requires_approval stage, with the stage's task and the previous stage's result. Read-only and safe to retry.",
+            json!({"type":"object","properties":{},"additionalProperties":false}),
+        ),
+        tool(
+            "resolve_approval",
+            "Approve or reject the stage a pipeline run is waiting on. Approving lets the dashboard spawn the stage; rejecting fails the run with the optional reason. Only call this on explicit human sign-off.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "run_id":{"type":"integer","description":"Run id from list_pending_approvals."},
+                    "approve":{"type":"boolean","description":"true to continue the run, false to reject it."},
+                    "reason":{"type":"string","description":"Optional note recorded on rejection."}
+                },
+                "required":["run_id","approve"],
                 "additionalProperties":false
             }),
         ),
+        tool(
+            "refresh_manager_context",
+            "Re-send the EA a condensed, freshly written memory.md (active projects, agents, and scheduled events) so a long-lived EA stops relying on a stale view of which workers exist. Queued in the EA's mailbox and typed in by the dashboard only once the EA is waiting for input; returns the message id, status, and queue position. Side effect: rewrites memory.md. Safe to retry, though each call queues another snapshot. Fails when the EA manager is not running.",
+            json!({"type":"object","properties":{},"additionalProperties":false}),
+        ),
+        tool(
+            "list_runs",
+            "List reports of past runs, newest first: each has id (its end time), path to the markdown report, and a one-line summary of workers, outcomes, duration, and cost. The dashboard writes a report once every worker of a plan has completed or been killed; read the file for per-worker tasks, health timelines, and final outputs. Read-only and safe to retry.",
+            json!({"type":"object","properties":{},"additionalProperties":false}),
+        ),
         tool(
             "list_projects",
-            "List tracked projects in this EA. Use before spawning agents to reuse an existing project when the work belongs to the same initiative. Read-only and safe to retry.",
+            "List tracked projects in this EA with their checklist items and progress (done, total, percent; null without a checklist). Use before spawning agents to reuse an existing project when the work belongs to the same initiative. Read-only and safe to retry.",
             json!({"type":"object","properties":{},"additionalProperties":false}),
         ),
+        tool(
+            "sync_github_issues",
+            "Import open GitHub issues carrying the [github] label from github.repo as projects now, instead of waiting for the dashboard's periodic sync. Each becomes a project named '#<number> <title>' with the issue's task list as its checklist. Side effect: adds projects. Safe to retry; already-imported issues are skipped. Fails when github.repo is unset or GitHub is unreachable.",
+            json!({"type":"object","properties":{},"additionalProperties":false}),
+        ),
+        tool(
+            "add_project_item",
+            "Append an unchecked checklist item to a project, e.g. one milestone of a multi-day effort. Returns the item's 1-based number and the updated project. Side effect: persists to the project list. Not idempotent; a retry adds a duplicate item. Fails if project_id is unknown.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "project_id":{"type":"integer","description":"Project id from add_project or list_projects."},
+                    "text":{"type":"string","description":"Checklist item text."}
+                },
+                "required":["project_id","text"],
+                "additionalProperties":false
+            }),
+        ),
+        tool(
+            "toggle_project_item",
+            "Check or uncheck a project's checklist item. Pass done to set it explicitly (safe to retry); omit it to flip the current state (not retry-safe). Returns the updated project with its progress. Fails if project_id or item is unknown.",
+            json!({
+                "type":"object",
+                "properties":{
+                    "project_id":{"type":"integer","description":"Project id from add_project or list_projects."},
+                    "item":{"type":"integer","minimum":1,"description":"1-based item number from list_projects."},
+                    "done":{"type":"boolean","description":"Checked state to set. Omit to toggle."}
+                },
+                "required":["project_id","item"],
+                "additionalProperties":false
+            }),
+        ),
         tool(
             "add_project",
             "Register a new project bucket in the current EA and return its project_id for spawn_agent. Use once per user initiative or reusable workstream. Side effect: persists project metadata. Not idempotent; retrying can create duplicate project names, so call list_projects after uncertain results. Projects are not auto-created or auto-removed by agent lifecycle.",
@@ -2170,11 +4886,12 @@ fn tool_definitions() -> Vec<Value> {
         ),
         tool(
             "complete_project",
-            "Remove a project from the current EA registry after its tracked agents are no longer running. Use when a user initiative is complete or intentionally abandoned. Side effect: deletes project metadata only; it does not kill agents. Not retry-safe after success because the project is gone. Fails if tracked agents are still running or project_id is unknown.",
+            "Remove a project from the current EA registry after its tracked agents are no longer running. Use when a user initiative is complete or intentionally abandoned. Side effect: deletes project metadata only; it does not kill agents. For a project imported from a GitHub issue, also comments on the issue (the issue stays open). Not retry-safe after success because the project is gone. Fails if tracked agents are still running or project_id is unknown.",
             json!({
                 "type":"object",
                 "properties":{
-                    "project_id":{"type":"integer","description":"Project id from add_project or list_projects. Fails while tracked agents for this project are still running."}
+                    "project_id":{"type":"integer","description":"Project id from add_project or list_projects. Fails while tracked agents for this project are still running."},
+                    "summary":{"type":"string","description":"What was done. For a project imported from a GitHub issue, posted as an issue comment."}
                 },
                 "required":["project_id"],
                 "additionalProperties":false
@@ -2222,7 +4939,8 @@ fn tool_definitions() -> Vec<Value> {
                 "properties":{
                     "agent_name":{"type":"string","description":"Your own agent name."},
                     "action":{"type":"string","description":"Short label for the action being justified."},
-                    "justification":{"type":"string","description":"Why this action serves the user's goal."}
+                    "justification":{"type":"string","description":"Why this action serves the user's goal."},
+                    "trace_id":{"type":"string","description":"Trace ID to file the entry under. Defaults to agent_name's trace from spawn_agent."}
                 },
                 "required":["agent_name","action","justification"],
                 "additionalProperties":false
@@ -2377,6 +5095,9 @@ mod tests {
             default_workdir: ".".to_string(),
             health_idle_warning: 15,
             tmux_server: None,
+            sandbox: crate::config::SandboxConfig::default(),
+            limits: crate::config::LimitsConfig::default(),
+            prompts: crate::config::PromptsConfig::default(),
         }
     }
 
@@ -2448,6 +5169,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn supervisors_without_a_stored_role_are_found_by_name() {
+        assert!(is_project_supervisor(Some(&AgentRole::Pm), "lead"));
+        assert!(!is_project_supervisor(Some(&AgentRole::Worker), "api-pm"));
+        assert!(is_project_supervisor(None, "api-pm"));
+        assert!(is_project_supervisor(None, "Project-Manager"));
+        assert!(!is_project_supervisor(None, "npm-upgrade"));
+    }
+
     #[test]
     fn infer_backend_name_recognizes_agy() {
         assert_eq!(infer_backend_name(Some("agy"), "ignored"), "agy");
@@ -2457,6 +5187,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tool_schema_markdown_documents_every_tool() {
+        let markdown = tool_schema_markdown();
+        for tool in tool_definitions() {
+            let name = tool["name"].as_str().unwrap();
+            assert!(
+                markdown.contains(&format!("## `{}`", name)),
+                "missing {name}"
+            );
+        }
+        assert!(markdown.contains("| `pattern` | string | yes | Regex or literal text to find. |"));
+        assert!(markdown.contains("| `context_lines` | integer | no |"));
+    }
+
+    #[test]
+    fn sync_github_issues_runs_inside_the_async_runtime() {
+        let server = OmarMcpServer::new(test_context());
+        std::fs::create_dir_all(&server.context.omar_dir).unwrap();
+        std::env::set_var("OMAR_MCP_TEST_GITHUB_TOKEN", "not-a-token");
+        std::fs::write(
+            server.context.omar_dir.join("config.toml"),
+            "[github]\nrepo = \"omar-test/none\"\ntoken = \"env:OMAR_MCP_TEST_GITHUB_TOKEN\"\n",
+        )
+        .unwrap();
+        // The server answers tool calls inside `block_on`, where building or
+        // dropping a blocking reqwest client panics.
+        let result = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(async { server.sync_github_issues() });
+        assert!(result.is_err(), "bogus token cannot sync: {result:?}");
+        let _ = std::fs::remove_dir_all(&server.context.omar_dir);
+    }
+
+    #[test]
+    fn approve_plan_is_refused_while_draining() {
+        let server = OmarMcpServer::new(test_context());
+        drain::start(&server.context.omar_dir, None).unwrap();
+        let err = server.approve_plan().unwrap_err();
+        assert!(
+            err.to_string().contains("draining"),
+            "unexpected error: {err}"
+        );
+        let _ = std::fs::remove_dir_all(&server.context.omar_dir);
+    }
+
     #[test]
     fn list_backends_includes_agy() {
         let server = OmarMcpServer::new(test_context());
@@ -2669,6 +5446,63 @@ mod tests {
         assert_eq!(ea::load_registry(&context.omar_dir).len(), 2);
     }
 
+    #[test]
+    fn rename_with_a_note_applies_both_or_neither() {
+        if !std::process::Command::new("tmux")
+            .arg("-V")
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+        {
+            eprintln!("Skipping test: tmux not available");
+            return;
+        }
+
+        let _lock = env_lock();
+        let _guard = EnvVarGuard::unset("OMAR_TMUX_SERVER");
+        let tmux_server = format!("omar-mcp-rename-{}", Uuid::new_v4());
+        let mut context = test_context();
+        context.tmux_server = Some(tmux_server.clone());
+        apply_context_environment(&context);
+        let server = OmarMcpServer::new(context.clone());
+        ea::ensure_default_ea(&context.omar_dir).unwrap();
+        let state_dir = server.state_dir().to_path_buf();
+        std::fs::create_dir_all(&state_dir).unwrap();
+
+        let session = naming::session_name(server.session_prefix(), "3");
+        let _ = std::process::Command::new("tmux")
+            .args(["-L", &tmux_server, "new-session", "-d", "-s", &session])
+            .args(["sleep", "600"])
+            .status()
+            .expect("create worker session");
+        memory::save_agent_note_in(&state_dir, &session, "old note");
+
+        // A rejected rename leaves the note alone
+        let rejected = server.rename_agent(json!({
+            "name": "3",
+            "new_name": "bad name",
+            "note": "new note",
+        }));
+        assert!(rejected.is_err());
+        assert_eq!(
+            memory::load_agent_notes_from(&state_dir)[&session],
+            "old note"
+        );
+
+        server
+            .rename_agent(json!({ "name": "3", "new_name": "api", "note": "new note" }))
+            .unwrap();
+        let renamed = naming::session_name(server.session_prefix(), "api");
+        let notes = memory::load_agent_notes_from(&state_dir);
+        assert_eq!(notes[&renamed], "new note");
+        assert!(!notes.contains_key(&session));
+
+        let _ = std::process::Command::new("tmux")
+            .args(["-L", &tmux_server, "kill-server"])
+            .status();
+        let _ = std::fs::remove_dir_all(&context.omar_dir);
+    }
+
     #[test]
     fn delete_ea_rejects_attached_manager_before_worker_cleanup() {
         if !std::process::Command::new("tmux")
@@ -3000,4 +5834,33 @@ mod tests {
         let output = "[TASK COMPLETE]\n\n\n\nSummary:\n- done\n\n\n\n";
         assert!(ready_to_complete_tail(output, 10));
     }
+
+    #[test]
+    fn health_from_output_applies_backend_thresholds_and_patterns() {
+        let mut health = config::HealthConfig::default();
+        health.backends.insert(
+            "opencode".to_string(),
+            config::BackendHealthConfig {
+                idle_warning: Some(600),
+                working_patterns: vec!["esc interrupt".to_string()],
+                ..Default::default()
+            },
+        );
+        let rules = HealthRules::new(health);
+        let quiet = chrono::Utc::now().timestamp() - 120;
+        assert_eq!(health_from_output(quiet, "> ", &rules, None), "idle");
+        assert_eq!(
+            health_from_output(quiet, "> ", &rules, Some("opencode")),
+            "running"
+        );
+        let stale = quiet - 3600;
+        assert_eq!(
+            health_from_output(stale, "Building  esc interrupt", &rules, Some("opencode")),
+            "running"
+        );
+        assert_eq!(
+            health_from_output(stale, "> ", &rules, Some("opencode")),
+            "idle"
+        );
+    }
 }
@@ -10,18 +10,25 @@ use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
 use crate::app::AgentInfo;
+use crate::checkpoint::Checkpoint;
 use crate::ea::EaId;
+use crate::pipeline;
 use crate::projects;
+use crate::review;
 use crate::scheduler::ScheduledEvent;
-use crate::tmux::TmuxClient;
+use crate::snapshot::Priority;
+use crate::state_store::{AgentField, AgentWrite, SqliteStore, StateStore};
+use crate::tmux::{AgentResult, Mux, SpawnStatus};
 use uuid::Uuid;
 
-/// Per-file-type mutexes to serialize concurrent read-modify-write operations.
-/// These are process-global (not per-EA) which is sufficient since all EAs
-/// run in the same process and operate on separate state_dir paths.
-static WORKER_TASKS_LOCK: Mutex<()> = Mutex::new(());
-static AGENT_PARENTS_LOCK: Mutex<()> = Mutex::new(());
-static AGENT_PROJECTS_LOCK: Mutex<()> = Mutex::new(());
+/// Serializes read-modify-write of `agent_env_files.json`, which only the
+/// dashboard process touches. Per-agent records are shared with the MCP
+/// server process, so they live in the transactional `state_store`.
+static ENV_FILES_LOCK: Mutex<()> = Mutex::new(());
+
+/// Grace period before a session's env file counts as abandoned, so a
+/// sweep from a snapshot taken just before the spawn leaves it alone
+const ENV_FILE_GRACE_SECS: i64 = 60;
 
 /// Generic JSON helpers
 fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Option<T> {
@@ -32,7 +39,7 @@ fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Option<T> {
 
 /// Write JSON atomically: write to a unique sibling temp file then rename into place.
 /// This prevents partial writes from being visible to concurrent readers.
-fn write_json<T: serde::Serialize>(path: &Path, data: &T) {
+pub(crate) fn write_json<T: serde::Serialize>(path: &Path, data: &T) {
     if let Ok(json) = serde_json::to_string_pretty(data) {
         write_text_atomic(path, &json);
     }
@@ -56,91 +63,452 @@ fn write_text_atomic(path: &Path, text: &str) {
     }
 }
 
-/// Save a worker's task description (upsert)
-pub fn save_worker_task_in(state_dir: &Path, session: &str, task: &str) {
-    let path = state_dir.join("worker_tasks.json");
-    let _guard = WORKER_TASKS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
-    let mut tasks = load_worker_tasks_inner(state_dir);
-    tasks.insert(session.to_string(), task.to_string());
-    write_json(&path, &tasks);
+/// Apply `write` to the EA's state store; best-effort, like `write_json`.
+fn with_store(state_dir: &Path, write: impl FnOnce(&SqliteStore) -> anyhow::Result<()>) {
+    let _ = SqliteStore::open(state_dir).and_then(|store| write(&store));
 }
 
-/// Load all worker task mappings for an EA
-pub fn load_worker_tasks_from(state_dir: &Path) -> HashMap<String, String> {
-    let _guard = WORKER_TASKS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
-    load_worker_tasks_inner(state_dir)
+/// Every agent's `field`, from the state store or, when it cannot be
+/// opened, the field's export.
+fn load_field<T: serde::de::DeserializeOwned>(
+    state_dir: &Path,
+    field: AgentField,
+) -> HashMap<String, T> {
+    match SqliteStore::open(state_dir).and_then(|store| store.agent_field(field)) {
+        Ok(values) => values
+            .into_iter()
+            .filter_map(|(session, value)| Some((session, serde_json::from_value(value).ok()?)))
+            .collect(),
+        Err(_) => read_json(&state_dir.join(field.export())).unwrap_or_default(),
+    }
+}
+
+/// Several of one agent's records, saved in one state store transaction,
+/// so a spawn never leaves half of them behind.
+#[derive(Debug, Clone)]
+pub struct AgentUpdate {
+    session: String,
+    writes: Vec<AgentWrite>,
+}
+
+impl AgentUpdate {
+    pub fn new(session: &str) -> Self {
+        Self {
+            session: session.to_string(),
+            writes: Vec::new(),
+        }
+    }
+
+    fn set(mut self, field: AgentField, value: impl serde::Serialize) -> Self {
+        if let Ok(value) = serde_json::to_value(value) {
+            self.writes.push(AgentWrite::Set(field, value));
+        }
+        self
+    }
+
+    fn remove(mut self, field: AgentField) -> Self {
+        self.writes.push(AgentWrite::Remove(field));
+        self
+    }
+
+    pub fn task(mut self, task: &str) -> Self {
+        self.writes.push(AgentWrite::Task(task.to_string()));
+        self
+    }
+
+    pub fn parent(mut self, parent: &str) -> Self {
+        self.writes.push(AgentWrite::Parent(parent.to_string()));
+        self
+    }
+
+    pub fn project(self, project_id: usize) -> Self {
+        self.set(AgentField::Project, project_id)
+    }
+
+    pub fn launch(self, launch: &AgentLaunch) -> Self {
+        self.set(AgentField::Launch, launch)
+    }
+
+    pub fn spawn_status(self, status: SpawnStatus) -> Self {
+        self.set(AgentField::SpawnStatus, status)
+    }
+
+    /// Replaces any tags it had; none removes the entry.
+    pub fn tags(self, tags: &[String]) -> Self {
+        if tags.is_empty() {
+            self.remove(AgentField::Tags)
+        } else {
+            self.set(AgentField::Tags, tags)
+        }
+    }
+
+    /// An empty note removes it.
+    pub fn note(self, note: &str) -> Self {
+        match note.trim() {
+            "" => self.remove(AgentField::Note),
+            note => self.set(AgentField::Note, note),
+        }
+    }
+
+    /// Replaces the previous report.
+    pub fn progress(self, report: &ProgressReport) -> Self {
+        self.set(AgentField::Progress, report)
+    }
+
+    /// Worker removes the entry.
+    pub fn role(self, role: AgentRole) -> Self {
+        if role == AgentRole::Worker {
+            self.remove(AgentField::Role)
+        } else {
+            self.set(AgentField::Role, role)
+        }
+    }
+
+    /// Normal removes the entry.
+    pub fn priority(self, priority: Priority) -> Self {
+        if priority == Priority::Normal {
+            self.remove(AgentField::Priority)
+        } else {
+            self.set(AgentField::Priority, priority)
+        }
+    }
+
+    pub fn trace(self, trace_id: &str) -> Self {
+        self.set(AgentField::Trace, trace_id)
+    }
+
+    pub fn checkpoint(self, checkpoint: &Checkpoint) -> Self {
+        self.set(AgentField::Checkpoint, checkpoint)
+    }
+
+    /// A `[TASK COMPLETE]` result also marks the agent completed.
+    pub fn result(self, result: &AgentResult) -> Self {
+        let update = self.set(AgentField::Result, result);
+        if result.kind == crate::tmux::ResultKind::Complete {
+            update.set(AgentField::Completed, &result.detected_at)
+        } else {
+            update
+        }
+    }
+
+    /// Forget a completion, so a new agent reusing the name must complete
+    /// again.
+    pub fn clear_completed(self) -> Self {
+        self.remove(AgentField::Completed)
+    }
+
+    pub fn save_in(self, state_dir: &Path) -> anyhow::Result<()> {
+        SqliteStore::open(state_dir)?.update_agent(&self.session, &self.writes)
+    }
+
+    /// Move every entry keyed by session `old` to this update's session,
+    /// repoint children whose parent was `old`, and save the update, in one
+    /// state store transaction. The status file, pipeline stages run by
+    /// `old` and its open reviews move with it. Fails, before anything
+    /// moves, if the state store cannot be updated.
+    pub fn rename_from_in(self, state_dir: &Path, old: &str) -> anyhow::Result<()> {
+        let new = self.session.as_str();
+        SqliteStore::open(state_dir)?.rename_agent(old, new, &self.writes)?;
+        let status = |session: &str| state_dir.join("status").join(format!("{}.md", session));
+        let _ = fs::rename(status(old), status(new));
+        pipeline::rename_agent_in(state_dir, old, new);
+        review::rename_agent_in(state_dir, old, new);
+        Ok(())
+    }
 }
 
-/// Inner (lock-free) loader — only call while holding `WORKER_TASKS_LOCK`.
-fn load_worker_tasks_inner(state_dir: &Path) -> HashMap<String, String> {
-    let path = state_dir.join("worker_tasks.json");
-    read_json(&path).unwrap_or_default()
+/// Save a worker's task description (upsert)
+pub fn save_worker_task_in(state_dir: &Path, session: &str, task: &str) {
+    with_store(state_dir, |store| store.set_worker_task(session, task));
 }
 
-/// Save an agent->project mapping (upsert)
-pub fn save_agent_project_in(state_dir: &Path, session: &str, project_id: usize) {
-    let path = state_dir.join("agent_projects.json");
-    let _guard = AGENT_PROJECTS_LOCK
-        .lock()
-        .unwrap_or_else(|e| e.into_inner());
-    let mut projects = load_agent_projects_inner(state_dir);
-    projects.insert(session.to_string(), project_id);
-    write_json(&path, &projects);
+/// Load all worker task mappings for an EA
+pub fn load_worker_tasks_from(state_dir: &Path) -> HashMap<String, String> {
+    SqliteStore::open(state_dir)
+        .and_then(|store| store.worker_tasks())
+        .unwrap_or_else(|_| read_json(&state_dir.join("worker_tasks.json")).unwrap_or_default())
 }
 
 /// Load all agent->project mappings for an EA
 pub fn load_agent_projects_from(state_dir: &Path) -> HashMap<String, usize> {
-    let _guard = AGENT_PROJECTS_LOCK
-        .lock()
-        .unwrap_or_else(|e| e.into_inner());
-    load_agent_projects_inner(state_dir)
-}
-
-/// Inner (lock-free) loader — only call while holding `AGENT_PROJECTS_LOCK`.
-fn load_agent_projects_inner(state_dir: &Path) -> HashMap<String, usize> {
-    let path = state_dir.join("agent_projects.json");
-    read_json(&path).unwrap_or_default()
+    load_field(state_dir, AgentField::Project)
 }
 
 /// Remove an agent->project mapping
 pub fn remove_agent_project_in(state_dir: &Path, session: &str) {
-    let path = state_dir.join("agent_projects.json");
-    let _guard = AGENT_PROJECTS_LOCK
-        .lock()
-        .unwrap_or_else(|e| e.into_inner());
-    let mut projects = load_agent_projects_inner(state_dir);
-    projects.remove(session);
-    write_json(&path, &projects);
+    let _ = AgentUpdate::new(session)
+        .remove(AgentField::Project)
+        .save_in(state_dir);
 }
 
 /// Save a child->parent mapping (upsert)
 pub fn save_agent_parent_in(state_dir: &Path, child: &str, parent: &str) {
-    let path = state_dir.join("agent_parents.json");
-    let _guard = AGENT_PARENTS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
-    let mut parents = load_agent_parents_inner(state_dir);
-    parents.insert(child.to_string(), parent.to_string());
-    write_json(&path, &parents);
+    with_store(state_dir, |store| store.set_agent_parent(child, parent));
 }
 
 /// Load all child->parent mappings for an EA
 pub fn load_agent_parents_from(state_dir: &Path) -> HashMap<String, String> {
-    let _guard = AGENT_PARENTS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
-    load_agent_parents_inner(state_dir)
-}
-
-/// Inner (lock-free) loader — only call while holding `AGENT_PARENTS_LOCK`.
-fn load_agent_parents_inner(state_dir: &Path) -> HashMap<String, String> {
-    let path = state_dir.join("agent_parents.json");
-    read_json(&path).unwrap_or_default()
+    SqliteStore::open(state_dir)
+        .and_then(|store| store.agent_parents())
+        .unwrap_or_else(|_| read_json(&state_dir.join("agent_parents.json")).unwrap_or_default())
 }
 
 /// Remove a child->parent mapping
 pub fn remove_agent_parent_in(state_dir: &Path, child: &str) {
-    let path = state_dir.join("agent_parents.json");
-    let _guard = AGENT_PARENTS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
-    let mut parents = load_agent_parents_inner(state_dir);
-    parents.remove(child);
-    write_json(&path, &parents);
+    with_store(state_dir, |store| {
+        store.remove_agent_parents(&[child.to_string()])
+    });
+}
+
+/// `root` and everyone reporting to it, directly or transitively, deepest
+/// first so children are torn down before their parent.
+pub fn team_of(parents: &HashMap<String, String>, root: &str) -> Vec<String> {
+    let mut team = vec![root.to_string()];
+    let mut idx = 0;
+    while idx < team.len() {
+        let mut children: Vec<String> = parents
+            .iter()
+            .filter(|(child, parent)| **parent == team[idx] && !team.contains(child))
+            .map(|(child, _)| child.clone())
+            .collect();
+        children.sort();
+        team.extend(children);
+        idx += 1;
+    }
+    team.reverse();
+    team
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct EnvFile {
+    path: PathBuf,
+    saved_at: i64,
+}
+
+/// Remember the secrets file `session`'s shell loads, so it is removed
+/// even if the shell never runs to delete it.
+pub fn save_agent_env_file_in(state_dir: &Path, session: &str, path: &Path) {
+    let _guard = ENV_FILES_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let file = state_dir.join("agent_env_files.json");
+    let mut all: HashMap<String, EnvFile> = read_json(&file).unwrap_or_default();
+    all.insert(
+        session.to_string(),
+        EnvFile {
+            path: path.to_path_buf(),
+            saved_at: chrono::Utc::now().timestamp(),
+        },
+    );
+    write_json(&file, &all);
+}
+
+/// Delete the env files of sessions no longer in `live`.
+pub fn sweep_agent_env_files_in(state_dir: &Path, live: &[String], now: i64) {
+    let _guard = ENV_FILES_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let file = state_dir.join("agent_env_files.json");
+    let Some(mut all) = read_json::<HashMap<String, EnvFile>>(&file) else {
+        return;
+    };
+    let before = all.len();
+    all.retain(|session, env| {
+        let gone = !live.contains(session) && now - env.saved_at >= ENV_FILE_GRACE_SECS;
+        if gone {
+            let _ = fs::remove_file(&env.path);
+        }
+        !gone
+    });
+    if all.len() != before {
+        write_json(&file, &all);
+    }
+}
+
+/// Drop hierarchy, project, spawn status, tag, progress, note, priority,
+/// role, trace, and result entries for several agents, in one transaction.
+pub fn forget_agents_in(state_dir: &Path, sessions: &[String]) {
+    with_store(state_dir, |store| {
+        store.forget_agents(
+            sessions,
+            &[
+                AgentField::Project,
+                AgentField::SpawnStatus,
+                AgentField::Tags,
+                AgentField::Progress,
+                AgentField::Note,
+                AgentField::Priority,
+                AgentField::Role,
+                AgentField::Trace,
+                AgentField::Result,
+            ],
+        )
+    });
+}
+
+/// How a worker was launched, kept so it can be re-created after a crash
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AgentLaunch {
+    /// Backend command before sandbox wrapping
+    pub command: String,
+    pub workdir: String,
+    pub sandboxed: bool,
+    /// Runtime binary followed by its security flags, checked against the
+    /// live process by `sandbox::tamper` (empty when unsandboxed)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub security: Vec<String>,
+    /// Pod the worker runs in under the kubernetes runtime
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pod: Option<String>,
+}
+
+/// Load all recorded worker launches for an EA
+pub fn load_agent_launches_from(state_dir: &Path) -> HashMap<String, AgentLaunch> {
+    load_field(state_dir, AgentField::Launch)
+}
+
+/// Record how far a spawned worker has got towards receiving its task
+pub fn save_spawn_status_in(state_dir: &Path, session: &str, status: SpawnStatus) {
+    let _ = AgentUpdate::new(session)
+        .spawn_status(status)
+        .save_in(state_dir);
+}
+
+/// Load the spawn status of every worker spawned through the API
+pub fn load_spawn_statuses_from(state_dir: &Path) -> HashMap<String, SpawnStatus> {
+    load_field(state_dir, AgentField::SpawnStatus)
+}
+
+/// Load the tags of every tagged agent
+pub fn load_agent_tags_from(state_dir: &Path) -> HashMap<String, Vec<String>> {
+    load_field(state_dir, AgentField::Tags)
+}
+
+/// Save the user's note on an agent; an empty note removes it
+pub fn save_agent_note_in(state_dir: &Path, session: &str, note: &str) {
+    let _ = AgentUpdate::new(session).note(note).save_in(state_dir);
+}
+
+/// Load the notes of every annotated agent
+pub fn load_agent_notes_from(state_dir: &Path) -> HashMap<String, String> {
+    load_field(state_dir, AgentField::Note)
+}
+
+/// Structured progress an agent reported about itself
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ProgressReport {
+    /// Percent done, 0-100
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub percent: Option<u8>,
+    /// What it is doing now
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<String>,
+    /// What stops it, if anything
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blockers: Vec<String>,
+    /// RFC3339
+    pub reported_at: String,
+}
+
+/// Save an agent's latest progress report (replaces the previous one)
+pub fn save_agent_progress_in(state_dir: &Path, session: &str, report: &ProgressReport) {
+    let _ = AgentUpdate::new(session)
+        .progress(report)
+        .save_in(state_dir);
+}
+
+/// Load the latest progress report of every agent that sent one
+pub fn load_agent_progress_from(state_dir: &Path) -> HashMap<String, ProgressReport> {
+    load_field(state_dir, AgentField::Progress)
+}
+
+/// An agent's place in the hierarchy, chosen when it is spawned
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentRole {
+    /// The EA's manager session; never spawned
+    Ea,
+    /// Supervises workers of a project
+    Pm,
+    #[default]
+    Worker,
+    /// Checks other agents' work
+    Reviewer,
+}
+
+impl AgentRole {
+    /// Parse a role an agent can be spawned with.
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        match value.trim() {
+            "pm" => Ok(AgentRole::Pm),
+            "worker" => Ok(AgentRole::Worker),
+            "reviewer" => Ok(AgentRole::Reviewer),
+            other => Err(anyhow::anyhow!(
+                "Unknown role '{}'. Supported: pm, worker, reviewer",
+                other
+            )),
+        }
+    }
+
+    /// Tree badge; plain workers and the EA root go without one.
+    pub fn badge(&self) -> Option<&'static str> {
+        match self {
+            AgentRole::Pm => Some("[PM]"),
+            AgentRole::Reviewer => Some("[REV]"),
+            AgentRole::Ea | AgentRole::Worker => None,
+        }
+    }
+}
+
+/// Save an agent's role; worker removes the entry
+pub fn save_agent_role_in(state_dir: &Path, session: &str, role: AgentRole) {
+    let _ = AgentUpdate::new(session).role(role).save_in(state_dir);
+}
+
+/// Load the role of every agent that is not a plain worker
+pub fn load_agent_roles_from(state_dir: &Path) -> HashMap<String, AgentRole> {
+    load_field(state_dir, AgentField::Role)
+}
+
+/// Save an agent's refresh priority; normal removes the entry
+pub fn save_agent_priority_in(state_dir: &Path, session: &str, priority: Priority) {
+    let _ = AgentUpdate::new(session)
+        .priority(priority)
+        .save_in(state_dir);
+}
+
+/// Load the priority of every agent that is not normal
+pub fn load_agent_priorities_from(state_dir: &Path) -> HashMap<String, Priority> {
+    load_field(state_dir, AgentField::Priority)
+}
+
+/// Load the trace ID of every traced agent
+pub fn load_agent_traces_from(state_dir: &Path) -> HashMap<String, String> {
+    load_field(state_dir, AgentField::Trace)
+}
+
+/// Load every recorded workspace checkpoint
+pub fn load_agent_checkpoints_from(state_dir: &Path) -> HashMap<String, Checkpoint> {
+    load_field(state_dir, AgentField::Checkpoint)
+}
+
+/// Save an agent's completion result (upsert)
+pub fn save_agent_result_in(state_dir: &Path, session: &str, result: &AgentResult) {
+    let _ = AgentUpdate::new(session).result(result).save_in(state_dir);
+}
+
+/// Whether the session printed `[TASK COMPLETE]`. Unlike its result, this
+/// outlives a kill, so `wait_for` dependents still start afterwards.
+pub fn has_completed_in(state_dir: &Path, session: &str) -> bool {
+    load_field::<String>(state_dir, AgentField::Completed).contains_key(session)
+}
+
+/// Load all agent completion results for an EA
+pub fn load_agent_results_from(state_dir: &Path) -> HashMap<String, AgentResult> {
+    load_field(state_dir, AgentField::Result)
+}
+
+/// Remove an agent's completion result
+pub fn remove_agent_result_in(state_dir: &Path, session: &str) {
+    let _ = AgentUpdate::new(session)
+        .remove(AgentField::Result)
+        .save_in(state_dir);
 }
 
 /// Load an agent's self-reported status
@@ -163,10 +531,52 @@ pub fn save_agent_status_in(state_dir: &Path, session_name: &str, status: &str)
 
 /// Load the memory file contents (empty string if missing)
 pub fn load_memory_from(state_dir: &Path) -> String {
-    let path = state_dir.join("memory.md");
+    let path = memory_path_in(state_dir);
     truncate_for_prompt(fs::read_to_string(&path).unwrap_or_default())
 }
 
+/// Sections of memory.md re-sent to a running EA by a context refresh; the
+/// EA knows its own status and screen.
+const REFRESH_SECTIONS: [&str; 3] = ["Active Projects", "Active Agents", "Scheduled Events"];
+
+/// The refresh sections of a memory.md snapshot.
+pub fn condense_memory(text: &str) -> String {
+    let mut out = String::new();
+    let mut keep = false;
+    for line in text.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            keep = REFRESH_SECTIONS.contains(&heading.trim());
+        } else if line.starts_with("# ") {
+            keep = false;
+        }
+        if keep {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out.trim_end().to_string()
+}
+
+/// Opening tag of a context refresh message; a newer refresh replaces a
+/// queued one that starts with it.
+pub const CONTEXT_REFRESH: &str = "[CONTEXT REFRESH]";
+
+/// Message sent to the EA by a context refresh, built from the current
+/// memory.md. Write memory first so the snapshot is fresh.
+pub fn context_refresh_in(state_dir: &Path) -> String {
+    let condensed = condense_memory(&load_memory_from(state_dir));
+    let body = if condensed.is_empty() {
+        "No active projects, agents, or scheduled events.".to_string()
+    } else {
+        condensed
+    };
+    format!(
+        "{} Current OMAR state; it replaces any earlier view of \
+         which projects and workers exist. No reply needed.\n\n{}",
+        CONTEXT_REFRESH, body
+    )
+}
+
 /// Maximum bytes of free-form context (memory snapshot or manager notes) that
 /// will be inlined into the EA system prompt. Linux exec(3) limits any single
 /// argv element to roughly 128 KB (32 pages × 4 KB) regardless of `ARG_MAX`,
@@ -228,7 +638,7 @@ pub fn write_memory_to(
     agents: &[AgentInfo],
     manager: Option<&AgentInfo>,
     manager_session: &str,
-    client: &TmuxClient,
+    client: &dyn Mux,
     events: &[ScheduledEvent],
 ) {
     let project_list = projects::load_projects_from(state_dir);
@@ -236,10 +646,9 @@ pub fn write_memory_to(
     // Read task metadata without pruning. Session discovery can lag tmux
     // creation during large fan-outs; pruning here can erase valid tasks.
     // Explicit kill/delete paths own cleanup.
-    let worker_tasks = {
-        let _guard = WORKER_TASKS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
-        load_worker_tasks_inner(state_dir)
-    };
+    let worker_tasks = load_worker_tasks_from(state_dir);
+    let agent_results = load_agent_results_from(state_dir);
+    let agent_notes = load_agent_notes_from(state_dir);
 
     let mut out = String::from("# OMAR State\n\n");
 
@@ -247,7 +656,16 @@ pub fn write_memory_to(
     if !project_list.is_empty() {
         out.push_str("## Active Projects\n");
         for p in &project_list {
-            out.push_str(&format!("{}. {}\n", p.id, p.name));
+            match p.progress() {
+                Some((done, total)) => {
+                    out.push_str(&format!("{}. {} ({}/{})\n", p.id, p.name, done, total))
+                }
+                None => out.push_str(&format!("{}. {}\n", p.id, p.name)),
+            }
+            for item in &p.items {
+                let mark = if item.done { "x" } else { " " };
+                out.push_str(&format!("   - [{}] {}\n", mark, item.text));
+            }
         }
         out.push('\n');
     }
@@ -265,6 +683,18 @@ pub fn write_memory_to(
                 "- {} ({}): {}\n",
                 agent.session.name, health, task_desc
             ));
+            if let Some(result) = agent_results.get(&agent.session.name) {
+                out.push_str(&format!(
+                    "  result [{}]: {}\n",
+                    result.kind.as_str(),
+                    result.detail
+                ));
+            }
+            if let Some(note) = agent_notes.get(&agent.session.name) {
+                for line in note.lines() {
+                    out.push_str(&format!("  note: {}\n", line));
+                }
+            }
         }
         out.push('\n');
     }
@@ -314,9 +744,199 @@ pub fn write_memory_to(
         }
     }
 
-    let path = state_dir.join("memory.md");
+    let path = memory_path_in(state_dir);
     fs::create_dir_all(state_dir).ok();
     write_text_atomic(&path, &out);
+    record_memory_snapshot_in(state_dir, &out);
+}
+
+pub fn memory_path_in(state_dir: &Path) -> PathBuf {
+    state_dir.join("memory.md")
+}
+
+/// Sections `write_memory_to` produces, in order.
+const MEMORY_SECTIONS: [&str; 5] = [
+    "Active Projects",
+    "Active Agents",
+    "Scheduled Events",
+    "Manager",
+    "Manager's Recent Context",
+];
+
+/// Check a hand-edited memory.md against the structure `write_memory_to`
+/// produces. Returns one message per problem (empty when valid).
+pub fn validate_memory(text: &str) -> Vec<String> {
+    let mut errors = Vec::new();
+    if text.lines().find(|l| !l.trim().is_empty()) != Some("# OMAR State") {
+        errors.push("First line must be '# OMAR State'".to_string());
+    }
+    let mut seen: Vec<&str> = Vec::new();
+    let mut section = "";
+    for (idx, line) in text.lines().enumerate() {
+        let lineno = idx + 1;
+        if let Some(heading) = line.strip_prefix("## ") {
+            let heading = heading.trim();
+            if !MEMORY_SECTIONS.contains(&heading) {
+                errors.push(format!(
+                    "line {}: unknown section '{}' (expected one of: {})",
+                    lineno,
+                    heading,
+                    MEMORY_SECTIONS.join(", ")
+                ));
+            } else if seen.contains(&heading) {
+                errors.push(format!("line {}: duplicate section '{}'", lineno, heading));
+            }
+            seen.push(heading);
+            section = heading;
+            continue;
+        }
+        if line.trim().is_empty() || line.starts_with("# ") {
+            continue;
+        }
+        let ok = match section {
+            "Active Projects" => {
+                line.split_once(". ")
+                    .is_some_and(|(id, _)| id.parse::<usize>().is_ok())
+                    || (line.starts_with(' ') && projects::parse_item(line.trim_start()).is_some())
+            }
+            "Active Agents" | "Scheduled Events" => {
+                line.starts_with("- ") || line.starts_with("  ")
+            }
+            "Manager" => line.starts_with("- "),
+            "Manager's Recent Context" => line.starts_with('>'),
+            // Already reported as an unknown section
+            _ => !section.is_empty(),
+        };
+        if !ok {
+            let expected = match section {
+                "" => "a '## ' section heading",
+                "Active Projects" => "'<id>. <name>' or an indented '- [ ] <item>'",
+                "Manager's Recent Context" => "a '> ' quoted line",
+                _ => "a '- ' list item",
+            };
+            errors.push(format!("line {}: expected {}", lineno, expected));
+        }
+    }
+    if !seen.contains(&"Manager") {
+        errors.push("Missing '## Manager' section".to_string());
+    }
+    errors
+}
+
+/// Replace memory.md with a validated, hand-edited version.
+pub fn write_edited_memory_in(state_dir: &Path, text: &str) -> anyhow::Result<()> {
+    let errors = validate_memory(text);
+    if !errors.is_empty() {
+        anyhow::bail!("Invalid memory.md:\n{}", errors.join("\n"));
+    }
+    write_text_atomic(&memory_path_in(state_dir), text);
+    record_memory_snapshot_in(state_dir, text);
+    Ok(())
+}
+
+/// Number of memory.md versions kept in `<state_dir>/memory_history/`.
+pub const MEMORY_HISTORY_LIMIT: usize = 20;
+
+/// One saved version of memory.md. `id` is its UTC write time
+/// (`20260417T020000.123Z`), so ids sort chronologically.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemorySnapshot {
+    pub id: String,
+    pub bytes: u64,
+}
+
+impl MemorySnapshot {
+    pub fn taken_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        chrono::NaiveDateTime::parse_from_str(&self.id, "%Y%m%dT%H%M%S%.3fZ")
+            .ok()
+            .map(|t| t.and_utc())
+    }
+}
+
+fn memory_history_dir(state_dir: &Path) -> PathBuf {
+    state_dir.join("memory_history")
+}
+
+/// Saved memory.md versions, oldest first.
+pub fn list_memory_history_in(state_dir: &Path) -> Vec<MemorySnapshot> {
+    let Ok(entries) = fs::read_dir(memory_history_dir(state_dir)) else {
+        return Vec::new();
+    };
+    let mut snapshots: Vec<MemorySnapshot> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let id = name.strip_suffix(".md")?.to_string();
+            let bytes = entry.metadata().ok()?.len();
+            Some(MemorySnapshot { id, bytes })
+        })
+        .filter(|snapshot| snapshot.taken_at().is_some())
+        .collect();
+    snapshots.sort_by(|a, b| a.id.cmp(&b.id));
+    snapshots
+}
+
+pub fn load_memory_snapshot_in(state_dir: &Path, id: &str) -> Option<String> {
+    if id.contains(['/', '\\']) {
+        return None;
+    }
+    fs::read_to_string(memory_history_dir(state_dir).join(format!("{}.md", id))).ok()
+}
+
+/// Save `text` as a new version unless it matches the latest one, keeping
+/// only the newest `MEMORY_HISTORY_LIMIT` versions.
+fn record_memory_snapshot_in(state_dir: &Path, text: &str) {
+    let history = list_memory_history_in(state_dir);
+    if let Some(latest) = history.last() {
+        if load_memory_snapshot_in(state_dir, &latest.id).as_deref() == Some(text) {
+            return;
+        }
+    }
+    let id = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string();
+    let dir = memory_history_dir(state_dir);
+    write_text_atomic(&dir.join(format!("{}.md", id)), text);
+    let history = list_memory_history_in(state_dir);
+    let excess = history.len().saturating_sub(MEMORY_HISTORY_LIMIT);
+    for old in &history[..excess] {
+        let _ = fs::remove_file(dir.join(format!("{}.md", old.id)));
+    }
+}
+
+/// Unified diff between two memory versions. `from`/`to` are snapshot ids;
+/// by default `to` is the latest version and `from` the one before it.
+pub fn diff_memory_snapshots_in(
+    state_dir: &Path,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> anyhow::Result<String> {
+    let history = list_memory_history_in(state_dir);
+    let position = |id: &str| {
+        history
+            .iter()
+            .position(|snapshot| snapshot.id == id)
+            .ok_or_else(|| anyhow::anyhow!("No memory snapshot '{}'", id))
+    };
+    let to_idx = match to {
+        Some(id) => position(id)?,
+        None => history
+            .len()
+            .checked_sub(1)
+            .ok_or_else(|| anyhow::anyhow!("No memory snapshots recorded yet"))?,
+    };
+    let from_idx = match from {
+        Some(id) => position(id)?,
+        None => to_idx
+            .checked_sub(1)
+            .ok_or_else(|| anyhow::anyhow!("Only one memory snapshot recorded"))?,
+    };
+    let (from_id, to_id) = (&history[from_idx].id, &history[to_idx].id);
+    let old = load_memory_snapshot_in(state_dir, from_id).unwrap_or_default();
+    let new = load_memory_snapshot_in(state_dir, to_id).unwrap_or_default();
+    Ok(similar::TextDiff::from_lines(&old, &new)
+        .unified_diff()
+        .context_radius(2)
+        .header(from_id, to_id)
+        .to_string())
 }
 
 /// Clear runtime/transient EA state that should not leak across dashboard sessions.
@@ -332,6 +952,19 @@ pub fn clear_runtime_state_in(state_dir: &Path) {
 mod tests {
     #[allow(unused_imports)]
     use super::*;
+    use crate::tmux::TmuxClient;
+
+    #[test]
+    fn test_condense_memory_keeps_fleet_sections() {
+        let text = "# OMAR State\n\n## Active Projects\n1. API\n\n## Active Agents\n- omar-agent-api (running): build\n  note: hi\n\n## Manager\n- Status: Running\n\n## Manager's Recent Context\n> typing\n";
+        assert_eq!(
+            condense_memory(text),
+            "## Active Projects\n1. API\n\n## Active Agents\n- omar-agent-api (running): build\n  note: hi"
+        );
+        let dir = tempfile::tempdir().unwrap();
+        assert!(context_refresh_in(dir.path())
+            .ends_with("No active projects, agents, or scheduled events."));
+    }
 
     #[test]
     fn manager_notes_path_is_ea_scoped() {
@@ -369,6 +1002,87 @@ mod tests {
         assert!(load_manager_notes(dir.path(), 0).is_empty());
     }
 
+    #[test]
+    fn rename_moves_records_and_repoints_children() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = dir.path();
+        AgentUpdate::new("omar-agent-0-3")
+            .parent("ea")
+            .task("build the API")
+            .tags(&["backend".to_string()])
+            .role(AgentRole::Pm)
+            .trace("7f3a9c")
+            .save_in(state)
+            .unwrap();
+        save_agent_parent_in(state, "omar-agent-0-helper", "omar-agent-0-3");
+        save_agent_role_in(state, "omar-agent-0-helper", AgentRole::Worker);
+        save_agent_status_in(state, "omar-agent-0-3", "halfway");
+        let report = ProgressReport {
+            percent: Some(40),
+            step: Some("routes".to_string()),
+            blockers: vec![],
+            reported_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        save_agent_progress_in(state, "omar-agent-0-3", &report);
+        save_agent_note_in(state, "omar-agent-0-3", "  ask before touching auth\n");
+        // Left behind by an earlier agent named "api"
+        save_worker_task_in(state, "omar-agent-0-api", "stale task");
+        save_agent_parent_in(state, "omar-agent-0-api", "ea");
+        save_agent_priority_in(state, "omar-agent-0-api", Priority::Hot);
+
+        AgentUpdate::new("omar-agent-0-api")
+            .rename_from_in(state, "omar-agent-0-3")
+            .unwrap();
+
+        let parents = load_agent_parents_from(state);
+        assert_eq!(parents["omar-agent-0-api"], "ea");
+        assert_eq!(parents["omar-agent-0-helper"], "omar-agent-0-api");
+        assert!(!parents.contains_key("omar-agent-0-3"));
+        assert_eq!(
+            load_worker_tasks_from(state)["omar-agent-0-api"],
+            "build the API"
+        );
+        assert_eq!(load_agent_tags_from(state)["omar-agent-0-api"], ["backend"]);
+        let roles = load_agent_roles_from(state);
+        assert_eq!(roles["omar-agent-0-api"], AgentRole::Pm);
+        assert!(!roles.contains_key("omar-agent-0-helper"));
+        assert_eq!(load_agent_traces_from(state)["omar-agent-0-api"], "7f3a9c");
+        assert_eq!(
+            load_agent_status_in(state, "omar-agent-0-api").as_deref(),
+            Some("halfway")
+        );
+        assert_eq!(load_agent_progress_from(state)["omar-agent-0-api"], report);
+        assert_eq!(
+            load_agent_notes_from(state)["omar-agent-0-api"],
+            "ask before touching auth"
+        );
+        assert!(load_agent_priorities_from(state).is_empty());
+        // The JSON exports follow the store
+        let exported: HashMap<String, String> = read_json(&state.join("agent_notes.json")).unwrap();
+        assert_eq!(exported["omar-agent-0-api"], "ask before touching auth");
+        forget_agents_in(state, &["omar-agent-0-api".to_string()]);
+        assert!(load_agent_progress_from(state).is_empty());
+        assert!(load_agent_notes_from(state).is_empty());
+    }
+
+    #[test]
+    fn team_of_is_deepest_first_and_forget_clears_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = dir.path();
+        save_agent_parent_in(state, "pm", "ea");
+        save_agent_parent_in(state, "w1", "pm");
+        save_agent_parent_in(state, "w2", "pm");
+        save_agent_parent_in(state, "w1-helper", "w1");
+        save_agent_parent_in(state, "other", "ea");
+        let parents = load_agent_parents_from(state);
+        assert_eq!(team_of(&parents, "pm"), vec!["w1-helper", "w2", "w1", "pm"]);
+        assert_eq!(team_of(&parents, "other"), vec!["other"]);
+
+        forget_agents_in(state, &team_of(&parents, "pm"));
+        let left: Vec<String> = load_agent_parents_from(state).into_keys().collect();
+        assert_eq!(left, vec!["other"]);
+    }
+
     #[test]
     fn clear_runtime_state_only_removes_status_files() {
         let dir = tempfile::tempdir().unwrap();
@@ -402,6 +1116,144 @@ mod tests {
         );
     }
 
+    #[test]
+    fn memory_history_dedupes_prunes_and_diffs() {
+        let dir = tempfile::tempdir().unwrap();
+        record_memory_snapshot_in(dir.path(), "# OMAR State\n- api: running\n");
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        record_memory_snapshot_in(dir.path(), "# OMAR State\n- api: running\n");
+        assert_eq!(list_memory_history_in(dir.path()).len(), 1);
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        record_memory_snapshot_in(dir.path(), "# OMAR State\n- api: finished\n");
+
+        let diff = diff_memory_snapshots_in(dir.path(), None, None).unwrap();
+        assert!(
+            diff.contains("-- api: running\n+- api: finished"),
+            "{}",
+            diff
+        );
+        assert!(diff_memory_snapshots_in(dir.path(), Some("nope"), None).is_err());
+
+        for i in 0..MEMORY_HISTORY_LIMIT {
+            std::thread::sleep(std::time::Duration::from_millis(2));
+            record_memory_snapshot_in(dir.path(), &format!("version {}\n", i));
+        }
+        let history = list_memory_history_in(dir.path());
+        assert_eq!(history.len(), MEMORY_HISTORY_LIMIT);
+        assert_eq!(
+            load_memory_snapshot_in(dir.path(), &history[0].id).unwrap(),
+            "version 0\n"
+        );
+        assert!(history[0].taken_at().is_some());
+    }
+
+    #[test]
+    fn validate_memory_reports_structural_problems() {
+        let valid = "# OMAR State\n\n## Active Projects\n1. API\n\n## Active Agents\n- omar-agent-0-api (running): build\n  result [done]: ok\n\n## Manager\n- Status: Running\n\n## Manager's Recent Context\n> hi\n";
+        assert!(validate_memory(valid).is_empty());
+
+        let errors = validate_memory("## Agents\n- x\n## Manager\nrunning\n## Manager\n");
+        assert_eq!(errors.len(), 4, "{:?}", errors);
+        assert!(errors[0].contains("# OMAR State"));
+        assert!(errors[1].contains("unknown section 'Agents'"));
+        assert!(errors[2].contains("line 4"));
+        assert!(errors[3].contains("duplicate"));
+        assert!(
+            validate_memory("# OMAR State\n").contains(&"Missing '## Manager' section".to_string())
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        assert!(write_edited_memory_in(dir.path(), "nope").is_err());
+        write_edited_memory_in(dir.path(), valid).unwrap();
+        assert_eq!(
+            fs::read_to_string(memory_path_in(dir.path())).unwrap(),
+            valid
+        );
+        assert_eq!(list_memory_history_in(dir.path()).len(), 1);
+    }
+
+    #[test]
+    fn written_memory_with_checklists_validates() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = dir.path();
+        let id = projects::add_project_in(state, "API").unwrap();
+        projects::add_item_in(state, id, "Schema").unwrap();
+        projects::add_item_in(state, id, "Endpoints").unwrap();
+        projects::set_item_in(state, id, 1, Some(true)).unwrap();
+        let client = TmuxClient::new(format!("omar-test-{}-", Uuid::new_v4()));
+        write_memory_to(state, &[], None, "omar-ea-0", &client, &[]);
+        let text = load_memory_from(state);
+        assert!(
+            text.contains("   - [x] Schema\n   - [ ] Endpoints\n"),
+            "{}",
+            text
+        );
+        assert_eq!(validate_memory(&text), Vec::<String>::new());
+        assert!(validate_memory(
+            "# OMAR State\n## Active Projects\n- [ ] loose\n## Manager\n- x\n"
+        )
+        .iter()
+        .any(|e| e.contains("line 3")));
+    }
+
+    #[test]
+    fn agent_results_round_trip_and_remove() {
+        use crate::tmux::ResultKind;
+
+        let dir = tempfile::tempdir().unwrap();
+        let result = AgentResult {
+            kind: ResultKind::Blocked,
+            detail: "missing credentials".to_string(),
+            detected_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        save_agent_result_in(dir.path(), "omar-agent-0-api", &result);
+
+        let loaded = load_agent_results_from(dir.path());
+        assert_eq!(loaded.get("omar-agent-0-api"), Some(&result));
+
+        remove_agent_result_in(dir.path(), "omar-agent-0-api");
+        assert!(load_agent_results_from(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn env_files_are_swept_once_their_session_is_gone() {
+        let dir = tempfile::tempdir().unwrap();
+        let env = dir.path().join("secrets.env");
+        fs::write(&env, "TOKEN=x\n").unwrap();
+        save_agent_env_file_in(dir.path(), "omar-agent-0-api", &env);
+        let now = chrono::Utc::now().timestamp();
+
+        sweep_agent_env_files_in(dir.path(), &[], now);
+        assert!(env.exists(), "kept during the grace period");
+        let live = vec!["omar-agent-0-api".to_string()];
+        sweep_agent_env_files_in(dir.path(), &live, now + ENV_FILE_GRACE_SECS);
+        assert!(env.exists());
+        sweep_agent_env_files_in(dir.path(), &[], now + ENV_FILE_GRACE_SECS);
+        assert!(!env.exists());
+    }
+
+    #[test]
+    fn completion_outlives_the_result() {
+        use crate::tmux::ResultKind;
+
+        let dir = tempfile::tempdir().unwrap();
+        let result = AgentResult {
+            kind: ResultKind::Complete,
+            detail: "schema done".to_string(),
+            detected_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        save_agent_result_in(dir.path(), "omar-agent-0-schema", &result);
+        remove_agent_result_in(dir.path(), "omar-agent-0-schema");
+        assert!(has_completed_in(dir.path(), "omar-agent-0-schema"));
+        assert!(!has_completed_in(dir.path(), "omar-agent-0-api"));
+
+        AgentUpdate::new("omar-agent-0-schema")
+            .clear_completed()
+            .save_in(dir.path())
+            .unwrap();
+        assert!(!has_completed_in(dir.path(), "omar-agent-0-schema"));
+    }
+
     #[test]
     fn scheduled_event_format_includes_period_and_payload() {
         // Verify the format string used in write_memory includes exact details
@@ -1,10 +1,13 @@
+use std::collections::HashSet;
 use std::fs::OpenOptions;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Mutex, OnceLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use serde::{Deserialize, Serialize};
+
 static METRICS_ENABLED: AtomicBool = AtomicBool::new(false);
 static METRICS_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
 
@@ -147,3 +150,218 @@ pub fn record_prompt_delivery(
         }),
     );
 }
+
+/// A finished agent run, kept so plan proposals can be annotated with
+/// duration and cost estimates. Unlike the spawn metrics sink this history
+/// is always recorded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentRunRecord {
+    /// Normalized role, see [`role_of`].
+    pub role: String,
+    #[serde(default)]
+    pub task: String,
+    #[serde(default)]
+    pub backend: String,
+    pub duration_secs: u64,
+    /// Duration priced at `metrics.cost_per_hour` for the backend, if set.
+    #[serde(default)]
+    pub cost_usd: Option<f64>,
+    /// Result kind reported by the agent ("complete", "blocked", ...).
+    pub result: String,
+    pub finished_at: String,
+}
+
+/// Estimated duration and cost for a proposed agent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    pub duration_secs: u64,
+    pub cost_usd: Option<f64>,
+    /// Number of historical runs the estimate is based on.
+    pub samples: usize,
+}
+
+impl Estimate {
+    /// e.g. `~12m, ~$0.40 (5 similar runs)`
+    pub fn summary(&self) -> String {
+        let duration = format_duration(self.duration_secs);
+        let runs = if self.samples == 1 { "run" } else { "runs" };
+        match self.cost_usd {
+            Some(cost) => format!(
+                "~{}, ~${:.2} ({} similar {})",
+                duration, cost, self.samples, runs
+            ),
+            None => format!("~{} ({} similar {})", duration, self.samples, runs),
+        }
+    }
+}
+
+pub fn format_duration(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        _ => format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+/// Minimum task-word overlap (Jaccard) for a run with a different role to
+/// count as similar.
+const TASK_SIMILARITY_THRESHOLD: f64 = 0.3;
+
+fn history_path() -> PathBuf {
+    sink_path().with_file_name("agent_history.jsonl")
+}
+
+/// Role key for an agent name: lowercased, with numeric segments dropped so
+/// `api-2` and `api-7` share history.
+pub fn role_of(name: &str) -> String {
+    name.to_lowercase()
+        .split(['-', '_', ' '])
+        .filter(|part| !part.is_empty() && !part.chars().all(|c| c.is_ascii_digit()))
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+pub fn record_agent_run(record: &AgentRunRecord) {
+    let lock = METRICS_LOCK.get_or_init(|| Mutex::new(()));
+    let Ok(_guard) = lock.lock() else {
+        return;
+    };
+    append_run_to(&history_path(), record);
+}
+
+fn append_run_to(path: &Path, record: &AgentRunRecord) {
+    if let Some(parent) = path.parent() {
+        if std::fs::create_dir_all(parent).is_err() {
+            return;
+        }
+    }
+    let Ok(line) = serde_json::to_string(record) else {
+        return;
+    };
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+        let _ = writeln!(file, "{}", line);
+    }
+}
+
+pub fn load_history() -> Vec<AgentRunRecord> {
+    load_history_from(&history_path())
+}
+
+fn load_history_from(path: &Path) -> Vec<AgentRunRecord> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Estimate a proposed agent from completed runs matching the first of
+/// `roles` that has any, or failing that, runs with a similar task. `None`
+/// without any such runs.
+pub fn estimate(history: &[AgentRunRecord], roles: &[&str], task: &str) -> Option<Estimate> {
+    let completed = history.iter().filter(|run| run.result == "complete");
+    let mut similar: Vec<&AgentRunRecord> = Vec::new();
+    for role in roles
+        .iter()
+        .map(|role| role_of(role))
+        .filter(|r| !r.is_empty())
+    {
+        similar = completed.clone().filter(|run| run.role == role).collect();
+        if !similar.is_empty() {
+            break;
+        }
+    }
+    if similar.is_empty() {
+        let words = task_words(task);
+        similar = completed
+            .filter(|run| jaccard(&words, &task_words(&run.task)) >= TASK_SIMILARITY_THRESHOLD)
+            .collect();
+    }
+    if similar.is_empty() {
+        return None;
+    }
+
+    let mut durations: Vec<u64> = similar.iter().map(|run| run.duration_secs).collect();
+    durations.sort_unstable();
+    let costs: Vec<f64> = similar.iter().filter_map(|run| run.cost_usd).collect();
+    Some(Estimate {
+        duration_secs: durations[durations.len() / 2],
+        cost_usd: (!costs.is_empty()).then(|| costs.iter().sum::<f64>() / costs.len() as f64),
+        samples: similar.len(),
+    })
+}
+
+fn task_words(task: &str) -> HashSet<String> {
+    task.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.len() >= 4)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / a.union(b).count() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(role: &str, task: &str, duration_secs: u64, cost: Option<f64>) -> AgentRunRecord {
+        AgentRunRecord {
+            role: role.to_string(),
+            task: task.to_string(),
+            backend: "claude".to_string(),
+            duration_secs,
+            cost_usd: cost,
+            result: "complete".to_string(),
+            finished_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_role_of_drops_numeric_segments() {
+        assert_eq!(role_of("API-2"), "api");
+        assert_eq!(role_of("feature-3-plan"), "feature-plan");
+        assert_eq!(role_of("42"), "");
+    }
+
+    #[test]
+    fn test_estimate_prefers_role_then_task_similarity() {
+        let mut blocked = run("api", "x", 9999, None);
+        blocked.result = "blocked".to_string();
+        let history = vec![
+            run("api", "build login endpoint", 600, Some(1.0)),
+            run("api", "build logout endpoint", 1200, None),
+            run("api", "add rate limiting", 1800, Some(3.0)),
+            blocked,
+            run("docs", "write migration guide for postgres", 300, None),
+        ];
+
+        let by_role = estimate(&history, &["backend", "api-4"], "anything").unwrap();
+        assert_eq!(by_role.duration_secs, 1200);
+        assert_eq!(by_role.cost_usd, Some(2.0));
+        assert_eq!(by_role.samples, 3);
+        assert_eq!(by_role.summary(), "~20m, ~$2.00 (3 similar runs)");
+
+        let by_task =
+            estimate(&history, &["writer"], "Write the postgres migration guide").unwrap();
+        assert_eq!((by_task.duration_secs, by_task.samples), (300, 1));
+        assert_eq!(by_task.summary(), "~5m (1 similar run)");
+
+        assert!(estimate(&history, &["frontend"], "style the navbar").is_none());
+    }
+
+    #[test]
+    fn test_history_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metrics").join("agent_history.jsonl");
+        let record = run("api", "task", 60, Some(0.5));
+        append_run_to(&path, &record);
+        append_run_to(&path, &record);
+        assert_eq!(load_history_from(&path), vec![record.clone(), record]);
+        assert!(load_history_from(&dir.path().join("missing")).is_empty());
+    }
+}
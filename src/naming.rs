@@ -0,0 +1,118 @@
+//! Agent naming — the one place session prefixes are added and stripped
+//!
+//! Worker sessions are named `<base_prefix><ea_id>-<short>` (see
+//! `ea::ea_prefix`), e.g. `omar-agent-0-api`, while users, the EA, and the
+//! API use the short form `api`. An `AgentId` carries both forms. Resolving
+//! accepts either, and a name that already carries the prefix is taken as
+//! is, so a layer can no longer prefix twice or forget to strip. Agents
+//! address their EA as `"ea"` rather than by its session name.
+
+use std::fmt;
+
+/// How agents address their EA.
+pub const EA: &str = "ea";
+
+/// A worker's full session name, with its short name inside it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AgentId {
+    session: String,
+    /// Byte offset of the short name in `session`
+    short_start: usize,
+}
+
+impl AgentId {
+    /// From a short or full name of an agent in the EA with `prefix`.
+    pub fn resolve(prefix: &str, name: &str) -> Self {
+        let short = name.strip_prefix(prefix).unwrap_or(name);
+        Self {
+            session: format!("{}{}", prefix, short),
+            short_start: prefix.len(),
+        }
+    }
+
+    pub fn session(&self) -> &str {
+        &self.session
+    }
+
+    pub fn short(&self) -> &str {
+        &self.session[self.short_start..]
+    }
+
+    pub fn into_session(self) -> String {
+        self.session
+    }
+}
+
+/// Shows the short name.
+impl fmt::Display for AgentId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.short())
+    }
+}
+
+/// Full session name of `name`, short or full, in the EA with `prefix`.
+pub fn session_name(prefix: &str, name: &str) -> String {
+    AgentId::resolve(prefix, name).into_session()
+}
+
+/// Short name of `session`, or all of it when it lies outside the EA with
+/// `prefix`.
+pub fn short_name<'a>(prefix: &str, session: &'a str) -> &'a str {
+    try_short_name(prefix, session).unwrap_or(session)
+}
+
+/// Short name of `session`, or `None` when it lies outside the EA with
+/// `prefix`.
+pub fn try_short_name<'a>(prefix: &str, session: &'a str) -> Option<&'a str> {
+    session.strip_prefix(prefix)
+}
+
+/// How agents address `session`: `"ea"` for the manager, otherwise its
+/// short name.
+pub fn address(session: &str, manager_session: &str, prefix: &str) -> String {
+    if session == manager_session {
+        EA.to_string()
+    } else {
+        short_name(prefix, session).to_string()
+    }
+}
+
+/// Session behind an address: the manager for `"ea"` or its own session
+/// name, otherwise the agent's full session name.
+pub fn resolve_address(address: &str, manager_session: &str, prefix: &str) -> String {
+    if address == EA || address == manager_session {
+        manager_session.to_string()
+    } else {
+        session_name(prefix, address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_never_double_prefixes() {
+        let prefix = "omar-agent-0-";
+        let short = AgentId::resolve(prefix, "api");
+        let full = AgentId::resolve(prefix, "omar-agent-0-api");
+        assert_eq!(short, full);
+        assert_eq!(full.session(), "omar-agent-0-api");
+        assert_eq!(full.short(), "api");
+        assert_eq!(full.to_string(), "api");
+
+        assert_eq!(short_name(prefix, "omar-agent-1-docs"), "omar-agent-1-docs");
+        assert_eq!(short_name(prefix, "omar-agent-0-api"), "api");
+        assert_eq!(try_short_name(prefix, "omar-agent-1-docs"), None);
+
+        let manager = "omar-agent-ea-0";
+        assert_eq!(address(manager, manager, prefix), "ea");
+        assert_eq!(address("omar-agent-0-api", manager, prefix), "api");
+        assert_eq!(resolve_address("ea", manager, prefix), manager);
+        assert_eq!(resolve_address(manager, manager, prefix), manager);
+        assert_eq!(
+            resolve_address("omar-agent-0-api", manager, prefix),
+            "omar-agent-0-api"
+        );
+    }
+}
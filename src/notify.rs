@@ -0,0 +1,402 @@
+//! Notifications — alert the user when an agent needs attention while the
+//! dashboard runs in a background window or nobody is at the terminal
+//!
+//! Fires on `[BLOCKED]`/`[NEED INPUT]` and `[TASK COMPLETE]` markers and when
+//! an agent has been idle for `health.idle_critical` seconds, or its
+//! backend's override of it (once per idle stretch), when a worker's sandbox
+//! was tampered with, and when a pipeline run finishes its last stage.
+//! Desktop delivery uses `notify-send` on Linux and `osascript` on macOS, or
+//! `notifications.command` when set; `[notifications.webhook]` POSTs JSON to
+//! a Slack/Discord-compatible URL. Both run in background threads. With
+//! `notifications.tmux_bell`, the dashboard also rings the bell in its own
+//! tmux window when that window is not the one in view.
+
+use std::collections::HashSet;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use serde_json::json;
+
+use crate::config::NotificationsConfig;
+use crate::scheduler::TickerBuffer;
+use crate::tmux::{AgentResult, HealthState, ResultKind};
+
+/// Webhook POST timeout, so a slow endpoint cannot pile up threads.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// Webhook event name: complete, blocked, need_input, stuck, sandbox,
+    /// or plan_completed
+    pub event: &'static str,
+    /// Full session name
+    pub session: String,
+    /// Short agent name
+    pub agent: String,
+    pub title: String,
+    pub body: String,
+}
+
+pub struct Notifier {
+    config: NotificationsConfig,
+    /// Agents already reported stuck in their current idle stretch
+    stuck: HashSet<String>,
+}
+
+impl Notifier {
+    pub fn new(config: &NotificationsConfig) -> Self {
+        Self {
+            config: config.clone(),
+            stuck: HashSet::new(),
+        }
+    }
+
+    /// Notifications due this refresh. `agents` are (session, name, health,
+    /// idle seconds, seconds after which idle counts as stuck);
+    /// `new_results` are (session, name, marker) first seen this refresh.
+    pub fn collect(
+        &mut self,
+        agents: &[(String, String, HealthState, i64, i64)],
+        new_results: &[(String, String, AgentResult)],
+    ) -> Vec<Notification> {
+        let mut notifications = Vec::new();
+        for (session, name, result) in new_results {
+            let title = match result.kind {
+                ResultKind::Complete => "finished",
+                ResultKind::Blocked => "is blocked",
+                ResultKind::NeedInput => "needs input",
+            };
+            notifications.push(Notification {
+                event: result.kind.as_str(),
+                session: session.clone(),
+                agent: name.clone(),
+                title: format!("omar: {} {}", name, title),
+                body: result.detail.clone(),
+            });
+        }
+
+        self.stuck
+            .retain(|session| agents.iter().any(|(agent, ..)| agent == session));
+        for (session, name, health, idle_secs, idle_critical) in agents {
+            let stuck = *health == HealthState::Idle && *idle_secs >= *idle_critical;
+            if !stuck {
+                self.stuck.remove(session);
+            } else if self.stuck.insert(session.clone()) {
+                notifications.push(Notification {
+                    event: "stuck",
+                    session: session.clone(),
+                    agent: name.clone(),
+                    title: format!("omar: {} looks stuck", name),
+                    body: format!("No output for {} minutes", idle_secs / 60),
+                });
+            }
+        }
+        notifications
+            .retain(|n| self.wants_desktop(n) || self.wants_webhook(n) || self.wants_bell(n));
+        notifications
+    }
+
+    /// Alert for a worker whose sandbox no longer matches its launch.
+    pub fn sandbox_alert(&self, session: &str, name: &str, detail: &str) -> Notification {
+        Notification {
+            event: "sandbox",
+            session: session.to_string(),
+            agent: name.to_string(),
+            title: format!("omar: {} sandbox was modified", name),
+            body: detail.to_string(),
+        }
+    }
+
+    /// Celebration for a pipeline run whose last stage agent, `session`,
+    /// just completed; `summary` carries duration and cost.
+    pub fn plan_completed(&self, session: &str, pipeline: &str, summary: &str) -> Notification {
+        Notification {
+            event: "plan_completed",
+            session: session.to_string(),
+            agent: pipeline.to_string(),
+            title: format!("omar: pipeline {} finished", pipeline),
+            body: summary.to_string(),
+        }
+    }
+
+    fn event_enabled(&self, notification: &Notification) -> bool {
+        match notification.event {
+            "complete" => self.config.on_complete,
+            "plan_completed" => self.config.on_plan_complete,
+            "stuck" | "sandbox" => self.config.on_stuck,
+            _ => self.config.on_waiting,
+        }
+    }
+
+    fn wants_desktop(&self, notification: &Notification) -> bool {
+        self.config.enabled && self.event_enabled(notification)
+    }
+
+    /// Whether to ring the dashboard's tmux bell; see [`ring_tmux_bell`].
+    pub fn wants_bell(&self, notification: &Notification) -> bool {
+        self.config.tmux_bell && self.event_enabled(notification)
+    }
+
+    pub fn wants_webhook(&self, notification: &Notification) -> bool {
+        self.config
+            .webhook
+            .as_ref()
+            .is_some_and(|webhook| webhook.events.iter().any(|e| e == notification.event))
+    }
+
+    /// Deliver without blocking the caller. `last_output` is included in
+    /// webhook payloads. Desktop failures are ignored; webhook failures go
+    /// to the ticker.
+    pub fn send(
+        &self,
+        notification: Notification,
+        state: &'static str,
+        last_output: Option<String>,
+        ticker: &TickerBuffer,
+    ) {
+        if self.wants_webhook(&notification) {
+            if let Some(webhook) = self.config.webhook.as_ref() {
+                let url = webhook.url.clone();
+                let payload = webhook_payload(&notification, state, last_output.as_deref());
+                let ticker = ticker.clone();
+                let agent = notification.agent.clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = post_webhook(&url, &payload) {
+                        ticker.push(format!("webhook for {} failed: {}", agent, e));
+                    }
+                });
+            }
+        }
+        if self.wants_desktop(&notification) {
+            let command = self.config.command.clone();
+            std::thread::spawn(move || desktop_notify(command, &notification));
+        }
+    }
+}
+
+/// Structured fields plus `text` (Slack) and `content` (Discord), so the
+/// same payload works for either incoming-webhook flavor.
+fn webhook_payload(
+    notification: &Notification,
+    state: &str,
+    last_output: Option<&str>,
+) -> serde_json::Value {
+    let mut text = notification.title.clone();
+    if !notification.body.is_empty() {
+        text.push_str(": ");
+        text.push_str(&notification.body);
+    }
+    json!({
+        "event": notification.event,
+        "agent": notification.agent,
+        "state": state,
+        "detail": notification.body,
+        "last_output": last_output,
+        "text": text,
+        "content": text,
+    })
+}
+
+fn post_webhook(url: &str, payload: &serde_json::Value) -> anyhow::Result<()> {
+    let response = reqwest::blocking::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()?
+        .post(url)
+        .json(payload)
+        .send()?;
+    if !response.status().is_success() {
+        anyhow::bail!("HTTP {}", response.status());
+    }
+    Ok(())
+}
+
+fn desktop_notify(command: Option<String>, notification: &Notification) {
+    let mut cmd = match command {
+        Some(command) => {
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", &command])
+                .env("OMAR_NOTIFY_TITLE", &notification.title)
+                .env("OMAR_NOTIFY_BODY", &notification.body);
+            cmd
+        }
+        None if cfg!(target_os = "macos") => {
+            let mut cmd = Command::new("osascript");
+            cmd.args([
+                "-e",
+                &format!(
+                    "display notification {} with title {}",
+                    applescript_string(&notification.body),
+                    applescript_string(&notification.title)
+                ),
+            ]);
+            cmd
+        }
+        None => {
+            let mut cmd = Command::new("notify-send");
+            cmd.args(["--app-name=omar", &notification.title, &notification.body]);
+            cmd
+        }
+    };
+    let _ = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+/// Ring the terminal bell from the dashboard's pane unless its window is the
+/// active one of an attached session. tmux then sets the window's bell flag
+/// (`monitor-bell`, on by default), which clears when the user visits it.
+/// Call between renders: the bell goes to the dashboard's own stdout.
+pub fn ring_tmux_bell() {
+    let Ok(pane) = std::env::var("TMUX_PANE") else {
+        return;
+    };
+    let focused = crate::tmux::tmux_command()
+        .args([
+            "display-message",
+            "-p",
+            "-t",
+            &pane,
+            "#{window_active} #{session_attached}",
+        ])
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .is_some_and(|output| in_view(&String::from_utf8_lossy(&output.stdout)));
+    if !focused {
+        let mut stdout = std::io::stdout();
+        let _ = stdout.write_all(b"\x07").and_then(|()| stdout.flush());
+    }
+}
+
+/// Parse `#{window_active} #{session_attached}`: the window is in view when
+/// it is active and some client is attached to its session.
+fn in_view(flags: &str) -> bool {
+    let mut parts = flags.split_whitespace();
+    parts.next() == Some("1")
+        && parts
+            .next()
+            .and_then(|n| n.parse::<u32>().ok())
+            .unwrap_or(0)
+            > 0
+}
+
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WebhookConfig;
+
+    fn result(name: &str, kind: ResultKind) -> (String, String, AgentResult) {
+        (
+            format!("omar-agent-0-{}", name),
+            name.to_string(),
+            AgentResult {
+                kind,
+                detail: "details".to_string(),
+                detected_at: String::new(),
+            },
+        )
+    }
+
+    fn idle(secs: i64) -> Vec<(String, String, HealthState, i64, i64)> {
+        vec![(
+            "omar-agent-0-api".to_string(),
+            "api".to_string(),
+            HealthState::Idle,
+            secs,
+            300,
+        )]
+    }
+
+    #[test]
+    fn test_collect_respects_toggles_and_reports_stuck_once() {
+        let config = NotificationsConfig {
+            enabled: true,
+            on_complete: false,
+            ..NotificationsConfig::default()
+        };
+        let mut notifier = Notifier::new(&config);
+        let results = vec![
+            result("api", ResultKind::NeedInput),
+            result("docs", ResultKind::Complete),
+        ];
+        let titles: Vec<String> = notifier
+            .collect(&[], &results)
+            .into_iter()
+            .map(|n| n.title)
+            .collect();
+        assert_eq!(titles, vec!["omar: api needs input"]);
+
+        assert!(notifier.collect(&idle(100), &[]).is_empty());
+        assert_eq!(notifier.collect(&idle(400), &[]).len(), 1);
+        assert!(notifier.collect(&idle(460), &[]).is_empty());
+        // Output resets the stretch
+        let mut running = idle(0);
+        running[0].2 = HealthState::Running;
+        notifier.collect(&running, &[]);
+        assert_eq!(notifier.collect(&idle(400), &[]).len(), 1);
+
+        let quiet = NotificationsConfig {
+            tmux_bell: false,
+            ..NotificationsConfig::default()
+        };
+        let mut disabled = Notifier::new(&quiet);
+        assert!(disabled.collect(&idle(400), &results).is_empty());
+    }
+
+    #[test]
+    fn test_tmux_bell_follows_event_toggles() {
+        let config = NotificationsConfig {
+            on_complete: false,
+            ..NotificationsConfig::default()
+        };
+        let mut notifier = Notifier::new(&config);
+        let results = vec![
+            result("api", ResultKind::Blocked),
+            result("docs", ResultKind::Complete),
+        ];
+        let notifications = notifier.collect(&[], &results);
+        assert_eq!(notifications.len(), 1);
+        assert!(notifier.wants_bell(&notifications[0]));
+        assert!(!notifier.wants_desktop(&notifications[0]));
+
+        assert!(in_view("1 1\n"));
+        assert!(!in_view("0 1\n"));
+        assert!(!in_view("1 0\n"));
+        assert!(!in_view(""));
+    }
+
+    #[test]
+    fn test_webhook_filters_events_and_builds_payload() {
+        let config = NotificationsConfig {
+            webhook: Some(WebhookConfig {
+                url: "http://127.0.0.1:9/hook".to_string(),
+                events: vec!["complete".to_string()],
+            }),
+            tmux_bell: false,
+            ..NotificationsConfig::default()
+        };
+        let mut notifier = Notifier::new(&config);
+        let results = vec![
+            result("api", ResultKind::Blocked),
+            result("docs", ResultKind::Complete),
+        ];
+        let notifications = notifier.collect(&idle(400), &results);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].event, "complete");
+
+        let payload = webhook_payload(&notifications[0], "finished", Some("all green"));
+        assert_eq!(payload["agent"], "docs");
+        assert_eq!(payload["state"], "finished");
+        assert_eq!(payload["last_output"], "all green");
+        assert_eq!(payload["text"], "omar: docs finished: details");
+        assert_eq!(payload["content"], payload["text"]);
+    }
+}
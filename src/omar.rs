@@ -1,20 +1,52 @@
 mod app;
 mod backend_probe;
+mod bench;
+mod checkpoint;
 mod computer;
 mod config;
+mod demo;
+mod drain;
 mod ea;
 mod event;
+mod exec;
+mod file_browser;
+mod firehose;
+mod github;
+mod health_history;
+mod heartbeat;
+mod mailbox;
 mod manager;
 mod mcp;
 mod memory;
 mod metrics;
+mod naming;
+mod notify;
 mod panic_hook;
 mod paths;
+mod pipeline;
 mod process;
 mod projects;
+mod restore;
+mod review;
+mod runs;
+mod sandbox;
 mod scheduler;
+mod schedules;
+mod scripting;
+mod search;
+mod secrets;
+mod snapshot;
+mod spawn_form;
+mod spawn_queue;
+mod state_store;
+mod templates;
 mod tmux;
+mod topology;
+mod transcript;
 mod ui;
+mod ui_state;
+mod validation;
+mod web;
 
 use std::io;
 use std::path::PathBuf;
@@ -22,11 +54,13 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::engine::{ArgValueCandidates, CompletionCandidate};
+use clap_complete::env::{CompleteEnv, EnvCompleter};
 use crossterm::{
     event::{
-        KeyCode, KeyModifiers, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
-        PushKeyboardEnhancementFlags,
+        DisableBracketedPaste, EnableBracketedPaste, KeyCode, KeyModifiers,
+        KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
     },
     execute,
     terminal::{
@@ -40,7 +74,7 @@ use tokio::sync::Mutex;
 use app::App;
 use config::Config;
 use event::{AppEvent, EventHandler};
-use tmux::{tmux_command, TmuxClient};
+use tmux::{tmux_command, DeliveryOptions, TmuxClient};
 
 #[cfg(test)]
 pub(crate) fn test_env_lock() -> std::sync::MutexGuard<'static, ()> {
@@ -76,6 +110,10 @@ struct Cli {
     /// Enable global spawn metrics logging sink
     #[arg(long, global = true)]
     spawn_metrics: bool,
+
+    /// Run the dashboard against a simulated fleet (no tmux or agent backends)
+    #[arg(long)]
+    demo: bool,
 }
 
 #[derive(Subcommand)]
@@ -93,6 +131,10 @@ enum Commands {
         /// Working directory
         #[arg(short, long)]
         workdir: Option<String>,
+
+        /// Initial task delivered once the agent is ready ("-" reads stdin)
+        #[arg(short, long)]
+        task: Option<String>,
     },
 
     /// List agent sessions in the target EA
@@ -105,7 +147,16 @@ enum Commands {
     /// Kill an agent session
     Kill {
         /// Name of the session to kill
-        name: String,
+        #[arg(required_unless_present = "all", add = ArgValueCandidates::new(agent_name_candidates))]
+        name: Option<String>,
+
+        /// Also kill every agent reporting to NAME (e.g. a PM's workers)
+        #[arg(long)]
+        group: bool,
+
+        /// Kill every worker in the EA (the EA manager keeps running)
+        #[arg(long, conflicts_with_all = ["name", "group"])]
+        all: bool,
     },
 
     /// Configure tmux for optimal omar experience
@@ -124,6 +175,12 @@ enum Commands {
         action: EventAction,
     },
 
+    /// Inspect saved versions of the target EA's memory.md
+    Memory {
+        #[command(subcommand)]
+        action: MemoryAction,
+    },
+
     /// Start the OMAR MCP server over stdio
     McpServer {
         /// Path to a serialized MCP server context JSON file. When omitted,
@@ -133,6 +190,145 @@ enum Commands {
         #[arg(long)]
         context_file: Option<String>,
     },
+
+    /// Attach to an agent's tmux session (switches client inside tmux)
+    Attach {
+        /// Agent name (short or full session name; "ea" for the manager)
+        #[arg(add = ArgValueCandidates::new(agent_name_candidates))]
+        name: String,
+    },
+
+    /// Type text into an agent's pane
+    Send {
+        /// Agent name (short or full session name; "ea" for the manager)
+        #[arg(add = ArgValueCandidates::new(agent_name_candidates))]
+        name: String,
+
+        /// Text to send ("-" reads stdin)
+        text: String,
+
+        /// Press Enter after the text
+        #[arg(long)]
+        enter: bool,
+    },
+
+    /// Print an agent's heartbeat as JSON (exits 1 when a --fail-if
+    /// condition holds)
+    Health {
+        /// Agent name (short or full session name; "ea" for the manager)
+        name: String,
+
+        /// Comma-separated conditions to fail on: missing, dead, idle,
+        /// blocked, stuck, rate_limited
+        #[arg(long)]
+        fail_if: Option<String>,
+    },
+
+    /// Export an agent's transcript as cleaned, timestamped Markdown
+    Export {
+        /// Agent name (short or full session name)
+        agent: String,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Save the EA's workers (names, tasks, parents, launch commands) so they
+    /// can be started again after the tmux server goes away
+    Save {
+        /// Write to this file instead of ~/.omar/saved/ea<ID>.json
+        #[arg(short, long)]
+        file: Option<String>,
+    },
+
+    /// Start the unfinished workers of a saved fleet that are not running,
+    /// parents first, and re-send their tasks
+    Restore {
+        /// Read this file instead of ~/.omar/saved/ea<ID>.json
+        #[arg(short, long)]
+        file: Option<String>,
+    },
+
+    /// Run the allowlisting proxy of `sandbox.network = "proxy"` (started
+    /// on demand by sandboxed launches)
+    #[command(hide = true)]
+    SandboxProxy {
+        /// Comma-separated domains to allow
+        #[arg(long, value_delimiter = ',')]
+        allow: Vec<String>,
+
+        /// Run the in-sandbox bridge to the proxy's socket instead
+        #[arg(long, requires = "listen")]
+        bridge: bool,
+
+        /// Loopback address the bridge listens on
+        #[arg(long)]
+        listen: Option<String>,
+    },
+
+    /// Append timestamped stdin lines to a transcript log (used by tmux
+    /// pipe-pane)
+    #[command(hide = true)]
+    PaneLog { path: String },
+
+    /// Print the MCP tool schema (JSON, or a Markdown reference)
+    McpSchema {
+        /// Emit a Markdown reference instead of JSON
+        #[arg(long)]
+        markdown: bool,
+    },
+
+    /// Time refresh, health checks, and MCP requests against dummy sessions
+    Bench {
+        /// Number of dummy agent sessions to spawn
+        #[arg(long, default_value_t = 20)]
+        sessions: usize,
+
+        /// Refreshes and health-check passes to time
+        #[arg(long, default_value_t = 10)]
+        rounds: usize,
+
+        /// MCP requests to time (alternating list_agents and get_agent)
+        #[arg(long, default_value_t = 200)]
+        requests: usize,
+    },
+
+    /// Print the active EA's fleet as a graph: EA → PMs → workers with
+    /// health, pipeline dependencies, and mailbox messages
+    Topo {
+        /// mermaid, dot, or json
+        #[arg(long, default_value = "mermaid")]
+        format: String,
+    },
+
+    /// List past run reports (written to ~/.omar/runs when a plan's
+    /// workers have all completed or been killed)
+    Runs,
+
+    /// Serve a web dashboard for the active EA's agents
+    Web {
+        /// Address to listen on (use 0.0.0.0 to reach it from a phone)
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+
+        #[arg(long, default_value_t = 8787)]
+        port: u16,
+    },
+
+    /// Print a shell completion script (e.g. `source <(omar completions
+    /// bash)`); agent names are completed from live tmux sessions
+    Completions {
+        #[arg(value_enum)]
+        shell: CompletionShell,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
 }
 
 #[derive(Subcommand)]
@@ -143,6 +339,25 @@ enum ManagerAction {
     Orchestrate,
 }
 
+#[derive(Subcommand)]
+enum MemoryAction {
+    /// Edit memory.md in $EDITOR, validate it, and optionally send it to
+    /// the running EA
+    Edit,
+    /// List saved memory.md versions, oldest first
+    History,
+    /// Show what changed between two versions (default: the latest two)
+    Diff {
+        /// Older snapshot id (default: the one before --to)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Newer snapshot id (default: the latest)
+        #[arg(long)]
+        to: Option<String>,
+    },
+}
+
 #[derive(Subcommand)]
 enum EventAction {
     /// Schedule an event for an agent or the EA
@@ -191,6 +406,10 @@ enum EventAction {
 }
 
 fn main() -> Result<()> {
+    // The scripts from `omar completions` call back into the binary with
+    // COMPLETE set; answer and exit before touching config or tmux state.
+    CompleteEnv::with_factory(Cli::command).complete();
+
     // Install the persisted-panic hook FIRST, before tokio builds its
     // runtime (and spawns worker threads). If the tmux parent dies it
     // takes the stderr pane with it (see issue #118), so panics need
@@ -221,6 +440,9 @@ async fn async_main() -> Result<()> {
         config.save_to_path(&Config::resolve_path(cli.config.as_deref()));
     }
     metrics::configure(config.metrics.spawn_metrics_enabled);
+    if config.dashboard.multiplexer == config::Multiplexer::Wsl {
+        tmux::wsl::configure(config.dashboard.wsl_distro.as_deref());
+    }
     let omar_dir = omar_dir();
     let defer_active_ea_save = cli.command.is_none() && cli.agent.is_some();
 
@@ -239,12 +461,38 @@ async fn async_main() -> Result<()> {
             name,
             command,
             workdir,
+            task,
         }) => {
+            // Read stdin before spawning so a bad pipe never leaves a
+            // task-less session behind.
+            let task = read_task_arg(task, io::stdin().lock())?;
             let target = resolve_cli_ea(&omar_dir, cli.ea.as_deref())?;
             let client =
                 TmuxClient::new(ea::ea_prefix(target.id, &config.dashboard.session_prefix));
             let cmd = command.unwrap_or_else(|| config.agent.default_command.clone());
-            spawn_agent(&client, &name, &cmd, workdir.as_deref())
+            let mux = tmux::mux_for(config.dashboard.multiplexer, client.prefix());
+            spawn_agent(
+                mux.as_ref(),
+                &name,
+                &cmd,
+                workdir.as_deref(),
+                &config.sandbox,
+                &config.limits,
+            )?;
+            match task {
+                Some(task) => {
+                    let state_dir = ea::ea_state_dir(target.id, &omar_dir);
+                    deliver_cli_task(
+                        &client,
+                        &name,
+                        &cmd,
+                        &task,
+                        &state_dir,
+                        &config.agent.readiness,
+                    )
+                }
+                None => Ok(()),
+            }
         }
         Some(Commands::List { all_eas }) => {
             if all_eas {
@@ -254,16 +502,29 @@ async fn async_main() -> Result<()> {
                 list_agents_for_ea(&config.dashboard.session_prefix, &target)
             }
         }
-        Some(Commands::Kill { name }) => {
+        Some(Commands::Kill { name, group, .. }) => {
             let target = resolve_cli_ea(&omar_dir, cli.ea.as_deref())?;
-            let client =
-                TmuxClient::new(ea::ea_prefix(target.id, &config.dashboard.session_prefix));
-            kill_agent(
-                &client,
-                &name,
-                &scheduler::Scheduler::with_store(scheduler::events_store_path(&omar_dir)),
-                target.id,
-            )
+            let mux = tmux::mux_for(
+                config.dashboard.multiplexer,
+                ea::ea_prefix(target.id, &config.dashboard.session_prefix),
+            );
+            let scheduler =
+                scheduler::Scheduler::with_store(scheduler::events_store_path(&omar_dir));
+            let state_dir = ea::ea_state_dir(target.id, &omar_dir);
+            match name {
+                Some(name) if group => {
+                    let root = naming::session_name(mux.prefix(), &name);
+                    let team = memory::team_of(&memory::load_agent_parents_from(&state_dir), &root);
+                    kill_agents(mux.as_ref(), &team, &scheduler, target.id, &state_dir)
+                }
+                Some(name) => kill_agent(mux.as_ref(), &name, &scheduler, target.id),
+                // clap only allows a missing NAME with --all
+                None => {
+                    let all: Vec<String> =
+                        mux.list_sessions()?.into_iter().map(|s| s.name).collect();
+                    kill_agents(mux.as_ref(), &all, &scheduler, target.id, &state_dir)
+                }
+            }
         }
         Some(Commands::SetupTmux) => setup_tmux(),
         Some(Commands::Manager { action }) => {
@@ -281,6 +542,10 @@ async fn async_main() -> Result<()> {
                     &manager::ManagerRuntimeOptions {
                         default_workdir: config.agent.default_workdir.clone(),
                         health_idle_warning: config.health.idle_warning,
+                        sandbox: config.sandbox.clone(),
+                        limits: config.limits.clone(),
+                        readiness: config.agent.readiness.clone(),
+                        prompts: config.prompts.clone(),
                     },
                 ),
                 Some(ManagerAction::Orchestrate) => manager::run_manager_orchestration(
@@ -293,6 +558,10 @@ async fn async_main() -> Result<()> {
                     &manager::ManagerRuntimeOptions {
                         default_workdir: config.agent.default_workdir.clone(),
                         health_idle_warning: config.health.idle_warning,
+                        sandbox: config.sandbox.clone(),
+                        limits: config.limits.clone(),
+                        readiness: config.agent.readiness.clone(),
+                        prompts: config.prompts.clone(),
                     },
                 ),
             }
@@ -323,7 +592,9 @@ async fn async_main() -> Result<()> {
                     every_seconds,
                     every_ns,
                 ),
-                EventAction::List => list_cli_events(&scheduler, target.id),
+                EventAction::List => {
+                    list_cli_events(&scheduler, target.id, config.dashboard.timezone)
+                }
                 EventAction::Cancel { id } => cancel_cli_event(&scheduler, target.id, &id),
             }
         }
@@ -331,6 +602,256 @@ async fn async_main() -> Result<()> {
             Some(path) => mcp::run_server_from_context_file(PathBuf::from(path)),
             None => mcp::run_server_with_default_context(),
         },
+        Some(Commands::Memory { action }) => {
+            let target = resolve_cli_ea(&omar_dir, cli.ea.as_deref())?;
+            let state_dir = ea::ea_state_dir(target.id, &omar_dir);
+            match action {
+                MemoryAction::Edit => {
+                    let client =
+                        TmuxClient::new(ea::ea_prefix(target.id, &config.dashboard.session_prefix));
+                    let manager_session =
+                        ea::ea_manager_session(target.id, &config.dashboard.session_prefix);
+                    edit_cli_memory(&state_dir, &client, &manager_session)?;
+                }
+                MemoryAction::History => {
+                    let history = memory::list_memory_history_in(&state_dir);
+                    if history.is_empty() {
+                        println!("No memory snapshots recorded for EA {}", target.id);
+                    }
+                    for snapshot in history {
+                        let taken_at = snapshot
+                            .taken_at()
+                            .map(|t| config.dashboard.timezone.format(t, "%Y-%m-%d %H:%M:%S"))
+                            .unwrap_or_default();
+                        println!(
+                            "{:<22} {}  {:>7} bytes",
+                            snapshot.id, taken_at, snapshot.bytes
+                        );
+                    }
+                }
+                MemoryAction::Diff { from, to } => {
+                    let diff = memory::diff_memory_snapshots_in(
+                        &state_dir,
+                        from.as_deref(),
+                        to.as_deref(),
+                    )?;
+                    if diff.is_empty() {
+                        println!("No changes");
+                    } else {
+                        print!("{}", diff);
+                    }
+                }
+            }
+            Ok(())
+        }
+        Some(Commands::Attach { name }) => {
+            let target = resolve_cli_ea(&omar_dir, cli.ea.as_deref())?;
+            let (client, session) =
+                resolve_cli_session(target.id, &config.dashboard.session_prefix, &name);
+            if !client.has_session(&session)? {
+                anyhow::bail!("Agent '{}' not found", name);
+            }
+            if std::env::var_os("TMUX").is_some() {
+                client.switch_client(&session)
+            } else {
+                client.attach_session(&session)
+            }
+        }
+        Some(Commands::Send { name, text, enter }) => {
+            let text = if text == "-" {
+                let mut buf = String::new();
+                io::Read::read_to_string(&mut io::stdin().lock(), &mut buf)?;
+                buf.trim_end_matches('\n').to_string()
+            } else {
+                text
+            };
+            let target = resolve_cli_ea(&omar_dir, cli.ea.as_deref())?;
+            let (client, session) =
+                resolve_cli_session(target.id, &config.dashboard.session_prefix, &name);
+            let mux = tmux::mux_for(config.dashboard.multiplexer, client.prefix());
+            if !mux.has_session(&session)? {
+                anyhow::bail!("Agent '{}' not found", name);
+            }
+            mux.send_keys_literal(&session, &text)?;
+            if enter {
+                std::thread::sleep(std::time::Duration::from_millis(100));
+                mux.send_keys(&session, "Enter")?;
+            }
+            Ok(())
+        }
+        Some(Commands::Health { name, fail_if }) => {
+            let conditions = heartbeat::FailIf::parse_list(fail_if.as_deref().unwrap_or(""))?;
+            let target = resolve_cli_ea(&omar_dir, cli.ea.as_deref())?;
+            let (client, session) =
+                resolve_cli_session(target.id, &config.dashboard.session_prefix, &name);
+            let short = naming::short_name(client.prefix(), &session);
+            let mux = tmux::mux_for(config.dashboard.multiplexer, client.prefix());
+            let heartbeat = heartbeat::probe(
+                mux.as_ref(),
+                &session,
+                short,
+                &mcp::HealthRules::new(config.health.clone()),
+                client.get_pane_command(&session).ok().as_deref(),
+            );
+            let report = heartbeat.report(&conditions);
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            if report["ok"] == false {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        Some(Commands::Export { agent, output }) => {
+            let target = resolve_cli_ea(&omar_dir, cli.ea.as_deref())?;
+            let (client, session) =
+                resolve_cli_session(target.id, &config.dashboard.session_prefix, &agent);
+            let short = naming::short_name(client.prefix(), &session);
+            let markdown = transcript::export(&session, short, None)?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, markdown)?;
+                    eprintln!("Wrote transcript for {} to {}", short, path);
+                }
+                None => print!("{}", markdown),
+            }
+            Ok(())
+        }
+        Some(Commands::Save { file }) => {
+            let target = resolve_cli_ea(&omar_dir, cli.ea.as_deref())?;
+            let base_prefix = &config.dashboard.session_prefix;
+            let client = TmuxClient::new(ea::ea_prefix(target.id, base_prefix));
+            let live: Vec<String> = client
+                .list_sessions()?
+                .into_iter()
+                .map(|session| session.name)
+                .collect();
+            let fleet = restore::save_fleet(
+                &ea::ea_state_dir(target.id, &omar_dir),
+                target.id,
+                &live,
+                client.prefix(),
+                &ea::ea_manager_session(target.id, base_prefix),
+            );
+            let path = file
+                .map(PathBuf::from)
+                .unwrap_or_else(|| restore::saved_path(&omar_dir, target.id));
+            restore::write_fleet(&path, &fleet)?;
+            println!(
+                "Saved {} agent(s) ({} unfinished) to {}",
+                fleet.agents.len(),
+                fleet.agents.iter().filter(|agent| !agent.finished).count(),
+                path.display()
+            );
+            Ok(())
+        }
+        Some(Commands::Restore { file }) => {
+            let target = resolve_cli_ea(&omar_dir, cli.ea.as_deref())?;
+            let default_path = restore::saved_path(&omar_dir, target.id);
+            let path = file.map(PathBuf::from).unwrap_or(default_path.clone());
+            let fleet = restore::load_fleet(&path)?;
+            restore_fleet(&config, &omar_dir, target.id, &fleet)?;
+            // Consume the EA's own save file; an explicit --file is the user's
+            if path == default_path {
+                let _ = std::fs::remove_file(&path);
+            }
+            Ok(())
+        }
+        Some(Commands::SandboxProxy {
+            allow,
+            bridge,
+            listen,
+        }) => match listen {
+            Some(listen) if bridge => sandbox::proxy::bridge(&listen).await,
+            _ => sandbox::proxy::serve(allow).await,
+        },
+        Some(Commands::PaneLog { path }) => {
+            transcript::run_pane_log(std::path::Path::new(&path), io::stdin().lock())
+        }
+        Some(Commands::McpSchema { markdown }) => {
+            if markdown {
+                print!("{}", mcp::tool_schema_markdown());
+            } else {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&mcp::tool_schema_json())?
+                );
+            }
+            Ok(())
+        }
+        Some(Commands::Bench {
+            sessions,
+            rounds,
+            requests,
+        }) => {
+            let options = bench::BenchOptions {
+                sessions,
+                rounds,
+                requests,
+            };
+            let rows = tokio::task::spawn_blocking(move || bench::run(config, &options)).await??;
+            print!("{}", bench::format_table(&rows));
+            Ok(())
+        }
+        Some(Commands::Topo { format }) => {
+            let caller = mcp::ToolCaller::new(mcp::default_context()?);
+            let result = caller.call("get_topology", serde_json::json!({ "format": format }))?;
+            match result["graph"].as_str() {
+                Some(graph) => print!("{}", graph),
+                None => println!("{}", serde_json::to_string_pretty(&result)?),
+            }
+            Ok(())
+        }
+        Some(Commands::Runs) => {
+            let runs = runs::list_runs(&omar_dir);
+            if runs.is_empty() {
+                println!("No run reports yet.");
+            }
+            for run in runs {
+                println!("{}  {}\n    {}", run.id, run.summary, run.path);
+            }
+            Ok(())
+        }
+        Some(Commands::Completions { shell }) => {
+            write_completions(shell, &mut io::stdout().lock())?;
+            Ok(())
+        }
+        Some(Commands::Web { host, port }) => {
+            let caller = Arc::new(mcp::ToolCaller::new(mcp::default_context()?));
+            let socket = config.api.socket.as_deref().map(config::expand_tilde);
+            let unix = socket.map(|path| {
+                eprintln!("Serving the API on {}", path.display());
+                let router = web::build_router(web::WebState {
+                    caller: caller.clone(),
+                    token: None,
+                });
+                tokio::spawn(async move { web::serve_unix(&path, router).await })
+            });
+            match unix {
+                Some(unix) if !config.api.tcp => unix.await??,
+                unix => {
+                    let token = uuid::Uuid::new_v4().simple().to_string();
+                    let state = web::WebState {
+                        caller,
+                        token: Some(token.clone()),
+                    };
+                    let listener = tokio::net::TcpListener::bind((host.as_str(), port)).await?;
+                    eprintln!(
+                        "Serving the web dashboard at http://{}/?token={}",
+                        listener.local_addr()?,
+                        token
+                    );
+                    let tcp = axum::serve(listener, web::build_router(state));
+                    match unix {
+                        Some(unix) => tokio::select! {
+                            result = tcp => result?,
+                            result = unix => result??,
+                        },
+                        None => tcp.await?,
+                    }
+                }
+            }
+            Ok(())
+        }
+        None if cli.demo => run_dashboard(config, true).await,
         None => {
             if cli.agent.is_some() {
                 let (target, created) =
@@ -350,6 +871,10 @@ async fn async_main() -> Result<()> {
                     &manager::ManagerRuntimeOptions {
                         default_workdir: config.agent.default_workdir.clone(),
                         health_idle_warning: config.health.idle_warning,
+                        sandbox: config.sandbox.clone(),
+                        limits: config.limits.clone(),
+                        readiness: config.agent.readiness.clone(),
+                        prompts: config.prompts.clone(),
                     },
                 )?;
                 match result {
@@ -367,7 +892,7 @@ async fn async_main() -> Result<()> {
                 let target = resolve_cli_ea(&omar_dir, cli.ea.as_deref())?;
                 relaunch_in_tmux(&config, &omar_dir, target.id, cli.agent.is_some())
             } else {
-                run_dashboard(config).await
+                run_dashboard(config, false).await
             }
         }
     }
@@ -383,6 +908,15 @@ fn resolve_cli_ea(omar_dir: &std::path::Path, selector: Option<&str>) -> Result<
     Ok(ea::resolve_or_create_ea_selector(omar_dir, selector)?.0)
 }
 
+/// Client for an EA and the full session name of `name` in it: "ea" is the
+/// manager, a prefixed name is kept as is, anything else gets the prefix.
+fn resolve_cli_session(ea_id: ea::EaId, base_prefix: &str, name: &str) -> (TmuxClient, String) {
+    let prefix = ea::ea_prefix(ea_id, base_prefix);
+    let manager_session = ea::ea_manager_session(ea_id, base_prefix);
+    let session = naming::resolve_address(name, &manager_session, &prefix);
+    (TmuxClient::new(prefix), session)
+}
+
 fn list_agents_for_ea(base_prefix: &str, ea_info: &ea::EaInfo) -> Result<()> {
     let prefix = ea::ea_prefix(ea_info.id, base_prefix);
     let manager_session = ea::ea_manager_session(ea_info.id, base_prefix);
@@ -450,46 +984,290 @@ fn sessions_for_ea(base_prefix: &str, ea_id: ea::EaId) -> Result<Vec<tmux::Sessi
     Ok(sessions)
 }
 
-fn display_cli_session_name(session_name: &str, prefix: &str, manager_session: &str) -> String {
-    if session_name == manager_session {
-        "ea".to_string()
-    } else {
-        session_name
-            .strip_prefix(prefix)
-            .unwrap_or(session_name)
-            .to_string()
+/// Registration script that hands completion to `COMPLETE=<shell> omar`.
+fn write_completions(shell: CompletionShell, out: &mut dyn io::Write) -> io::Result<()> {
+    let completer: &dyn EnvCompleter = match shell {
+        CompletionShell::Bash => &clap_complete::env::Bash,
+        CompletionShell::Zsh => &clap_complete::env::Zsh,
+        CompletionShell::Fish => &clap_complete::env::Fish,
+    };
+    completer.write_registration("COMPLETE", "omar", "omar", "omar", out)
+}
+
+/// Live agent names in the active EA, for completing NAME arguments.
+fn agent_name_candidates() -> Vec<CompletionCandidate> {
+    let Ok(config) = Config::load(None) else {
+        return Vec::new();
+    };
+    if config.dashboard.multiplexer == config::Multiplexer::Wsl {
+        tmux::wsl::configure(config.dashboard.wsl_distro.as_deref());
     }
+    let base_prefix = &config.dashboard.session_prefix;
+    let Ok(target) = ea::resolve_ea_selector(&omar_dir(), None) else {
+        return Vec::new();
+    };
+    let prefix = ea::ea_prefix(target.id, base_prefix);
+    let manager_session = ea::ea_manager_session(target.id, base_prefix);
+    sessions_for_ea(base_prefix, target.id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|session| {
+            CompletionCandidate::new(display_cli_session_name(
+                &session.name,
+                &prefix,
+                &manager_session,
+            ))
+        })
+        .collect()
+}
+
+fn display_cli_session_name(session_name: &str, prefix: &str, manager_session: &str) -> String {
+    naming::address(session_name, manager_session, prefix)
 }
 
 fn spawn_agent(
-    client: &TmuxClient,
+    client: &dyn tmux::Mux,
     name: &str,
     command: &str,
     workdir: Option<&str>,
+    sandbox: &config::SandboxConfig,
+    limits: &config::LimitsConfig,
 ) -> Result<()> {
-    let full_name = format!("{}{}", client.prefix(), name);
+    let full_name = naming::session_name(client.prefix(), name);
 
     if client.has_session(&full_name)? {
         anyhow::bail!("Session '{}' already exists", name);
     }
 
-    client.new_session(&full_name, command, workdir)?;
+    let command = sandbox::wrap_agent_command(sandbox, limits, command, workdir.unwrap_or("."))?;
+    client.new_session(&full_name, &command, workdir)?;
+    transcript::start(client, &full_name);
     println!("Spawned agent: {}", name);
     Ok(())
 }
 
-fn kill_agent(
+/// Start the pending workers of `fleet` in EA `ea_id`, in saved order so
+/// parents exist before their children.
+fn restore_fleet(
+    config: &Config,
+    omar_dir: &std::path::Path,
+    ea_id: ea::EaId,
+    fleet: &restore::SavedFleet,
+) -> Result<()> {
+    let base_prefix = &config.dashboard.session_prefix;
+    let client = TmuxClient::new(ea::ea_prefix(ea_id, base_prefix));
+    let mux = tmux::mux_for(config.dashboard.multiplexer, client.prefix());
+    let manager_session = ea::ea_manager_session(ea_id, base_prefix);
+    let state_dir = ea::ea_state_dir(ea_id, omar_dir);
+    let live: Vec<String> = client
+        .list_sessions()?
+        .into_iter()
+        .map(|session| session.name)
+        .collect();
+    let pending = fleet.pending(&state_dir, client.prefix(), &live);
+    if pending.is_empty() {
+        println!("Nothing to restore");
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for agent in pending {
+        let launch = agent.launch.clone().unwrap_or_else(|| memory::AgentLaunch {
+            command: config.agent.default_command.clone(),
+            workdir: config.agent.default_workdir.clone(),
+            sandboxed: true,
+            security: Vec::new(),
+            pod: None,
+        });
+        let sandbox = if launch.sandboxed {
+            config.sandbox.clone()
+        } else {
+            config::SandboxConfig {
+                runtime: "none".to_string(),
+                ..config.sandbox.clone()
+            }
+        };
+        let restored = spawn_agent(
+            mux.as_ref(),
+            &agent.name,
+            &launch.command,
+            Some(&launch.workdir),
+            &sandbox,
+            &config.limits,
+        )
+        .and_then(|()| {
+            let session = naming::session_name(client.prefix(), &agent.name);
+            let parent = naming::resolve_address(&agent.parent, &manager_session, client.prefix());
+            let mut update = memory::AgentUpdate::new(&session)
+                .parent(&parent)
+                .launch(&launch)
+                .role(agent.role);
+            if let Some(project) = agent.project {
+                update = update.project(project);
+            }
+            update.save_in(&state_dir)?;
+            match &agent.task {
+                Some(task) => deliver_cli_task(
+                    &client,
+                    &agent.name,
+                    &launch.command,
+                    task,
+                    &state_dir,
+                    &config.agent.readiness,
+                ),
+                None => Ok(()),
+            }
+        });
+        if let Err(e) = restored {
+            eprintln!("{}: {}", agent.name, e);
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("{} agent(s) could not be restored", failed);
+    }
+    Ok(())
+}
+
+/// Resolve `--task`: `-` reads the whole task from `stdin`, anything else is
+/// taken literally. Empty tasks are rejected.
+fn read_task_arg(task: Option<String>, mut stdin: impl io::Read) -> Result<Option<String>> {
+    let Some(task) = task else {
+        return Ok(None);
+    };
+    let task = if task == "-" {
+        let mut buf = String::new();
+        stdin
+            .read_to_string(&mut buf)
+            .map_err(|e| anyhow::anyhow!("Failed to read task from stdin: {}", e))?;
+        buf
+    } else {
+        task
+    };
+    let task = task.trim();
+    if task.is_empty() {
+        anyhow::bail!("--task must not be empty");
+    }
+    Ok(Some(task.to_string()))
+}
+
+/// Deliver a `--task` to a freshly spawned agent and record it.
+fn deliver_cli_task(
     client: &TmuxClient,
     name: &str,
+    command: &str,
+    task: &str,
+    state_dir: &std::path::Path,
+    readiness: &config::ReadinessConfig,
+) -> Result<()> {
+    let full_name = naming::session_name(client.prefix(), name);
+    if !manager::deliver_task_when_ready(client, &full_name, command, task, readiness)? {
+        eprintln!("{}: ready prompt timed out; task delivered anyway", name);
+    }
+    memory::save_worker_task_in(state_dir, &full_name, task);
+    println!("Delivered task to {} ({} bytes)", name, task.len());
+    Ok(())
+}
+
+fn prompt_yes_no(question: &str, default_yes: bool) -> Result<bool> {
+    use std::io::Write;
+    print!(
+        "{} {} ",
+        question,
+        if default_yes { "[Y/n]" } else { "[y/N]" }
+    );
+    std::io::stdout().flush()?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(match input.trim().to_lowercase().as_str() {
+        "" => default_yes,
+        answer => answer == "y" || answer == "yes",
+    })
+}
+
+/// `omar memory edit`: round-trip memory.md through `$VISUAL`/`$EDITOR`
+/// until it validates, then offer to hand the corrected state to the EA.
+fn edit_cli_memory(
+    state_dir: &std::path::Path,
+    client: &TmuxClient,
+    manager_session: &str,
+) -> Result<()> {
+    let path = memory::memory_path_in(state_dir);
+    let original = std::fs::read_to_string(&path).map_err(|_| {
+        anyhow::anyhow!(
+            "No memory.md at {} yet; it is written once the EA has state",
+            path.display()
+        )
+    })?;
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let draft = state_dir.join(".memory.edit.md");
+    std::fs::write(&draft, &original)?;
+
+    let edited = loop {
+        // Through sh so editors configured with flags ("code --wait") work.
+        let status = std::process::Command::new("sh")
+            .args(["-c", &format!("{} \"$1\"", editor), "sh"])
+            .arg(&draft)
+            .status()?;
+        if !status.success() {
+            let _ = std::fs::remove_file(&draft);
+            anyhow::bail!("Editor '{}' exited with {}", editor, status);
+        }
+        let text = std::fs::read_to_string(&draft)?;
+        let errors = memory::validate_memory(&text);
+        if errors.is_empty() {
+            break text;
+        }
+        eprintln!("memory.md is invalid:");
+        for error in &errors {
+            eprintln!("  {}", error);
+        }
+        if !prompt_yes_no("Re-open the editor?", true)? {
+            let _ = std::fs::remove_file(&draft);
+            println!("Discarded changes.");
+            return Ok(());
+        }
+    };
+    let _ = std::fs::remove_file(&draft);
+    if edited == original {
+        println!("No changes.");
+        return Ok(());
+    }
+    memory::write_edited_memory_in(state_dir, &edited)?;
+    println!("Saved {}", path.display());
+
+    if client.has_session(manager_session).unwrap_or(false)
+        && prompt_yes_no("Send the corrected state to the running EA?", false)?
+    {
+        let message = format!(
+            "[OMAR] The user corrected your state snapshot (memory.md). Treat it as authoritative and update your assumptions:\n\n{}",
+            edited
+        );
+        client.deliver_prompt(manager_session, &message, &DeliveryOptions::default())?;
+        println!("Delivered to the EA.");
+    }
+    Ok(())
+}
+
+fn kill_agent(
+    client: &dyn tmux::Mux,
+    name: &str,
     scheduler: &scheduler::Scheduler,
     ea_id: ea::EaId,
 ) -> Result<()> {
-    let full_name = format!("{}{}", client.prefix(), name);
-
-    if !client.has_session(&full_name)? {
-        anyhow::bail!("Session '{}' not found", name);
+    let full_name = naming::session_name(client.prefix(), name);
+
+    let session = client
+        .list_sessions()?
+        .into_iter()
+        .find(|session| session.name == full_name);
+    match session {
+        None => anyhow::bail!("Session '{}' not found", name),
+        Some(session) if session.attached => anyhow::bail!("Cannot kill attached session"),
+        Some(_) => {}
     }
-    let _ = client.ensure_session_not_attached(&full_name)?;
 
     client.kill_session(&full_name)?;
     let _ = scheduler.cancel_by_receiver_and_ea(name, ea_id);
@@ -497,6 +1275,41 @@ fn kill_agent(
     Ok(())
 }
 
+/// Kill the live sessions among `sessions` (in order) and forget their
+/// hierarchy. Refuses everything if any of them is attached.
+fn kill_agents(
+    client: &dyn tmux::Mux,
+    sessions: &[String],
+    scheduler: &scheduler::Scheduler,
+    ea_id: ea::EaId,
+    state_dir: &std::path::Path,
+) -> Result<()> {
+    let live = client.list_sessions()?;
+    if let Some(attached) = live
+        .iter()
+        .find(|s| s.attached && sessions.contains(&s.name))
+    {
+        anyhow::bail!("Cannot kill attached session '{}'", attached.name);
+    }
+    let mut killed = Vec::new();
+    for session in sessions {
+        if !live.iter().any(|s| &s.name == session) {
+            continue;
+        }
+        client.kill_session(session)?;
+        let name = naming::short_name(client.prefix(), session);
+        let _ = scheduler.cancel_by_receiver_and_ea(name, ea_id);
+        killed.push(name.to_string());
+    }
+    memory::forget_agents_in(state_dir, sessions);
+    if killed.is_empty() {
+        println!("No agents to kill");
+    } else {
+        println!("Killed {} agent(s): {}", killed.len(), killed.join(", "));
+    }
+    Ok(())
+}
+
 fn now_ns() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -541,7 +1354,11 @@ fn schedule_cli_event(
     Ok(())
 }
 
-fn list_cli_events(scheduler: &scheduler::Scheduler, ea_id: ea::EaId) -> Result<()> {
+fn list_cli_events(
+    scheduler: &scheduler::Scheduler,
+    ea_id: ea::EaId,
+    timezone: config::Timezone,
+) -> Result<()> {
     let mut events = scheduler.list_by_ea(ea_id);
     if events.is_empty() {
         println!("No scheduled events found for EA {}", ea_id);
@@ -549,8 +1366,8 @@ fn list_cli_events(scheduler: &scheduler::Scheduler, ea_id: ea::EaId) -> Result<
     }
     events.sort_by_key(|event| (event.timestamp, event.created_at));
     println!(
-        "{:<36} {:<14} {:<14} {:<18} {:<12} PAYLOAD",
-        "ID", "SENDER", "RECEIVER", "TIMESTAMP_NS", "RECURRING"
+        "{:<36} {:<14} {:<14} {:<14} {:<12} PAYLOAD",
+        "ID", "SENDER", "RECEIVER", "FIRES_AT", "RECURRING"
     );
     println!("{}", "-".repeat(120));
     for event in events {
@@ -559,8 +1376,13 @@ fn list_cli_events(scheduler: &scheduler::Scheduler, ea_id: ea::EaId) -> Result<
             .map(|ns| ns.to_string())
             .unwrap_or_else(|| "-".to_string());
         println!(
-            "{:<36} {:<14} {:<14} {:<18} {:<12} {}",
-            event.id, event.sender, event.receiver, event.timestamp, recurring, event.payload
+            "{:<36} {:<14} {:<14} {:<14} {:<12} {}",
+            event.id,
+            event.sender,
+            event.receiver,
+            timezone.format_ns(event.timestamp),
+            recurring,
+            event.payload
         );
     }
     Ok(())
@@ -595,8 +1417,6 @@ fn relaunch_in_tmux(
     active_ea: ea::EaId,
     restart_manager: bool,
 ) -> Result<()> {
-    use std::os::unix::process::CommandExt;
-
     let client = TmuxClient::new("");
     let exe = std::env::current_exe()?;
     let args: Vec<String> = std::env::args().skip(1).collect();
@@ -632,8 +1452,20 @@ fn relaunch_in_tmux(
     cmd.args(&args);
 
     // exec() replaces the current process; only returns on error
-    let err = cmd.exec();
-    anyhow::bail!("Failed to launch tmux: {}", err)
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        let err = cmd.exec();
+        anyhow::bail!("Failed to launch tmux: {}", err)
+    }
+    // No exec elsewhere: wait for tmux and pass on its exit status
+    #[cfg(not(unix))]
+    {
+        let status = cmd
+            .status()
+            .map_err(|e| anyhow::anyhow!("Failed to launch tmux: {}", e))?;
+        std::process::exit(status.code().unwrap_or(1))
+    }
 }
 
 /// Recommended tmux settings for omar, keyed by option name.
@@ -934,45 +1766,60 @@ fn kill_child_gracefully(child: &mut std::process::Child, timeout: Duration) {
     let _ = child.wait();
 }
 
-async fn run_dashboard(config: Config) -> Result<()> {
+async fn run_dashboard(config: Config, demo: bool) -> Result<()> {
     // Some shells/dev tools export NO_COLOR globally. That disables all ANSI
     // styling and makes the TUI monochrome. The dashboard is explicitly color-coded.
     if std::env::var_os("NO_COLOR").is_some() {
         std::env::remove_var("NO_COLOR");
     }
 
+    // Demo state lives in a throwaway directory, removed on exit
+    let demo_dir = if demo {
+        Some(paths::private_temp_dir()?.join(format!("demo-{}", uuid::Uuid::new_v4())))
+    } else {
+        None
+    };
+    let config = if demo {
+        demo::demo_config(config)
+    } else {
+        config
+    };
+
     // Create the ticker buffer and scheduler, then spawn the event loop
     let ticker = scheduler::TickerBuffer::new();
-    let omar_dir = omar_dir();
+    let omar_dir = demo_dir.clone().unwrap_or_else(omar_dir);
     let scheduler = Arc::new(scheduler::Scheduler::with_store(
         scheduler::events_store_path(&omar_dir),
     ));
     let popup_receiver = scheduler::new_popup_receiver();
     let base_prefix = config.dashboard.session_prefix.clone();
-    tokio::spawn(scheduler::run_event_loop(
-        scheduler.clone(),
-        ticker.clone(),
-        popup_receiver.clone(),
-        base_prefix,
-    ));
+    if !demo {
+        tokio::spawn(scheduler::run_event_loop(
+            scheduler.clone(),
+            ticker.clone(),
+            popup_receiver.clone(),
+            base_prefix,
+        ));
+    }
 
     // Create SINGLE shared App instance for the dashboard/runtime state.
-    let shared_app = Arc::new(Mutex::new(App::new(
-        &config,
-        ticker.clone(),
-        scheduler.clone(),
-    )));
+    let app = if demo {
+        App::new_demo(&config, ticker.clone(), scheduler.clone(), omar_dir.clone())
+    } else {
+        App::new(&config, ticker.clone(), scheduler.clone())
+    };
+    let shared_app = Arc::new(Mutex::new(app));
 
     // Spawn Slack bridge if configured
-    let mut slack_bridge = spawn_slack_bridge();
+    let mut slack_bridge = if demo { None } else { spawn_slack_bridge() };
 
     // Spawn computer-use bridge if X11 is available
-    let mut computer_bridge = spawn_computer_bridge();
+    let mut computer_bridge = if demo { None } else { spawn_computer_bridge() };
 
     // Initialize terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableBracketedPaste)?;
     // Enable keyboard enhancement where supported (improves key reporting).
     let keyboard_enhanced = supports_keyboard_enhancement().unwrap_or(false);
     if keyboard_enhanced {
@@ -988,15 +1835,15 @@ async fn run_dashboard(config: Config) -> Result<()> {
     {
         let mut app = shared_app.lock().await;
         match (slack_bridge.is_some(), computer_bridge.is_some()) {
-            (true, true) => app.set_status("Slack & computer bridges started"),
-            (true, false) => app.set_status("Slack bridge started"),
-            (false, true) => app.set_status("Computer bridge started"),
+            (true, true) => app.set_status_tr("Slack & computer bridges started"),
+            (true, false) => app.set_status_tr("Slack bridge started"),
+            (false, true) => app.set_status_tr("Computer bridge started"),
             _ => {}
         }
     }
 
     // Warn if tmux config is missing recommended settings
-    {
+    if !demo {
         let mut app = shared_app.lock().await;
         sync_tmux_setup_warning(&mut app);
     }
@@ -1005,8 +1852,17 @@ async fn run_dashboard(config: Config) -> Result<()> {
     {
         let mut app = shared_app.lock().await;
         if let Err(e) = app.refresh() {
-            app.set_status(format!("Error: {}", e));
+            app.set_status_trf("Error: {}", &[&e]);
+        }
+        if demo {
+            app.set_status_tr("Demo mode: simulated agents, actions are disabled");
+        } else {
+            if let Err(e) = app.restore_previous_run() {
+                app.set_status_trf("Restore failed: {}", &[&e]);
+            }
+            app.start_sandbox_preflight();
         }
+        app.start_background_polling();
     }
 
     // Event loop — locks shared_app per-phase (render, then handle).
@@ -1016,10 +1872,13 @@ async fn run_dashboard(config: Config) -> Result<()> {
     let mut tick_count: u64 = 0;
 
     loop {
-        // Phase 1: Render (brief lock — read-only access to App)
+        // Phase 1: Render (brief lock — read-only apart from the pending bell)
         {
-            let app = shared_app.lock().await;
+            let mut app = shared_app.lock().await;
             terminal.draw(|f| ui::render(f, &app))?;
+            if app.take_bell() {
+                notify::ring_tmux_bell();
+            }
         }
         // Lock released — API calls can proceed during event wait
 
@@ -1043,7 +1902,7 @@ async fn run_dashboard(config: Config) -> Result<()> {
                                 let name = app.project_input.clone();
                                 if !name.trim().is_empty() {
                                     app.add_project(name.trim());
-                                    app.set_status("Project added");
+                                    app.set_status_tr("Project added");
                                 }
                                 app.project_input_mode = false;
                                 app.project_input.clear();
@@ -1070,7 +1929,7 @@ async fn run_dashboard(config: Config) -> Result<()> {
                                 let name = app.ea_input.clone();
                                 if !name.trim().is_empty() {
                                     if let Err(e) = app.create_ea(name.trim().to_string(), None) {
-                                        app.set_status(format!("Error: {}", e));
+                                        app.set_status_trf("Error: {}", &[&e]);
                                     }
                                 }
                                 app.ea_input_mode = false;
@@ -1087,30 +1946,351 @@ async fn run_dashboard(config: Config) -> Result<()> {
                         continue;
                     }
 
-                    // Handle confirmation dialog (kill, quit, or delete EA)
+                    // Handle message input popup (multi-line; Alt/Shift+Enter
+                    // inserts a newline, Enter sends)
+                    if app.message_target.is_some() {
+                        match key.code {
+                            KeyCode::Esc => {
+                                app.cancel_message_input();
+                            }
+                            KeyCode::Enter
+                                if key
+                                    .modifiers
+                                    .intersects(KeyModifiers::ALT | KeyModifiers::SHIFT) =>
+                            {
+                                app.message_input.push('\n');
+                            }
+                            KeyCode::Enter => {
+                                if let Err(e) = app.send_message_input() {
+                                    app.set_status_trf("Error: {}", &[&e]);
+                                }
+                            }
+                            KeyCode::Backspace => {
+                                app.message_input.pop();
+                            }
+                            KeyCode::Char(c) => {
+                                app.message_input.push(c);
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // Handle note editor (same keys as the message popup)
+                    if app.note_target.is_some() {
+                        match key.code {
+                            KeyCode::Esc => {
+                                app.cancel_note_input();
+                            }
+                            KeyCode::Enter
+                                if key
+                                    .modifiers
+                                    .intersects(KeyModifiers::ALT | KeyModifiers::SHIFT) =>
+                            {
+                                app.note_input.push('\n');
+                            }
+                            KeyCode::Enter => {
+                                app.save_note_input();
+                            }
+                            KeyCode::Backspace => {
+                                app.note_input.pop();
+                            }
+                            KeyCode::Char(c) => {
+                                app.note_input.push(c);
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // Handle spawn wizard (Tab completes the workdir field)
+                    if let Some(form) = app.spawn_form.as_mut() {
+                        use spawn_form::SpawnField;
+                        match key.code {
+                            KeyCode::Esc => {
+                                app.spawn_form = None;
+                            }
+                            KeyCode::Enter => {
+                                if let Err(e) = app.submit_spawn_form() {
+                                    app.set_status_trf("Error: {}", &[&e]);
+                                }
+                            }
+                            KeyCode::Tab if form.field == SpawnField::Workdir => {
+                                form.complete_workdir();
+                            }
+                            KeyCode::Down | KeyCode::Tab => form.next_field(),
+                            KeyCode::Up | KeyCode::BackTab => form.prev_field(),
+                            KeyCode::Left => form.cycle(false),
+                            KeyCode::Right => form.cycle(true),
+                            KeyCode::Backspace => form.backspace(),
+                            KeyCode::Char(c) if !c.is_control() => form.insert(c),
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // Handle workdir file viewer (over the browser)
+                    if let Some(viewer) = app
+                        .file_browser
+                        .as_mut()
+                        .and_then(|browser| browser.viewer.as_mut())
+                    {
+                        // Body height of the 90% viewer popup
+                        let page = (terminal.size()?.height * 9 / 10).saturating_sub(2) as usize;
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Char('q') => {
+                                if let Some(browser) = app.file_browser.as_mut() {
+                                    browser.viewer = None;
+                                }
+                            }
+                            KeyCode::Down | KeyCode::Char('j') => viewer.scroll_by(1, page),
+                            KeyCode::Up | KeyCode::Char('k') => viewer.scroll_by(-1, page),
+                            KeyCode::PageDown | KeyCode::Char(' ') => {
+                                viewer.scroll_by(page as isize, page)
+                            }
+                            KeyCode::PageUp => viewer.scroll_by(-(page as isize), page),
+                            KeyCode::Char('g') | KeyCode::Home => viewer.scroll = 0,
+                            KeyCode::Char('G') | KeyCode::End => viewer.scroll_by(isize::MAX, page),
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // Handle workdir file browser
+                    if let Some(browser) = app.file_browser.as_mut() {
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Char('f') => {
+                                app.file_browser = None;
+                            }
+                            KeyCode::Down | KeyCode::Char('j') => browser.move_selection(true),
+                            KeyCode::Up | KeyCode::Char('k') => browser.move_selection(false),
+                            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => {
+                                browser.open_selected();
+                            }
+                            KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') => {
+                                browser.up();
+                            }
+                            KeyCode::Char('r') => browser.reload(),
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // Handle pipeline approval review dialog
+                    if app.approval_review.is_some() {
+                        match key.code {
+                            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                                if let Err(e) = app.resolve_approval_review(true) {
+                                    app.set_status_trf("Error: {}", &[&e]);
+                                }
+                            }
+                            KeyCode::Char('x') | KeyCode::Char('X') => {
+                                if let Err(e) = app.resolve_approval_review(false) {
+                                    app.set_status_trf("Error: {}", &[&e]);
+                                }
+                            }
+                            KeyCode::Esc | KeyCode::Char('A') => {
+                                app.approval_review = None;
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // Handle search-all query prompt
+                    if let Some(query) = app.search_input.as_mut() {
+                        match key.code {
+                            KeyCode::Esc => {
+                                app.search_input = None;
+                            }
+                            KeyCode::Enter => {
+                                let query = app.search_input.take().unwrap_or_default();
+                                if !query.trim().is_empty() {
+                                    if let Err(e) = app.run_search(&query) {
+                                        app.set_status_trf("Error: {}", &[&e]);
+                                    }
+                                }
+                            }
+                            KeyCode::Backspace => {
+                                query.pop();
+                            }
+                            KeyCode::Char(c) if !c.is_control() => {
+                                query.push(c);
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // Handle sandbox exec popup
+                    if let Some(popup) = app.exec_popup.as_mut() {
+                        match key.code {
+                            KeyCode::Esc => {
+                                app.exec_popup = None;
+                            }
+                            KeyCode::Enter => {
+                                if let Err(e) = app.run_exec_popup() {
+                                    app.set_status_trf("Error: {}", &[&e]);
+                                }
+                            }
+                            KeyCode::Backspace => {
+                                popup.input.pop();
+                            }
+                            KeyCode::Char(c) if !c.is_control() => {
+                                popup.input.push(c);
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // Handle agent filter prompt
+                    if let Some(filter) = app.filter_input.as_mut() {
+                        match key.code {
+                            KeyCode::Esc => {
+                                app.filter_input = None;
+                            }
+                            KeyCode::Enter => {
+                                let filter = app.filter_input.take().unwrap_or_default();
+                                app.filter = filter.trim().to_string();
+                                if let Err(e) = app.refresh() {
+                                    app.set_status_trf("Error: {}", &[&e]);
+                                }
+                            }
+                            KeyCode::Backspace => {
+                                filter.pop();
+                            }
+                            KeyCode::Char(c) if !c.is_control() => {
+                                filter.push(c);
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // Handle search-all results view
+                    if app.search_results.is_some() {
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('F') => {
+                                app.search_results = None;
+                            }
+                            KeyCode::Down | KeyCode::Char('j') => {
+                                app.search_scroll = app.search_scroll.saturating_add(1);
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                app.search_scroll = app.search_scroll.saturating_sub(1);
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // Handle zoomed agent view
+                    if app.zoomed_agent().is_some() {
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Char('z') => {
+                                app.toggle_zoom();
+                            }
+                            KeyCode::Down | KeyCode::Char('j') => {
+                                app.zoom_scroll = app.zoom_scroll.saturating_sub(1);
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                app.zoom_scroll = app.zoom_scroll.saturating_add(1);
+                            }
+                            KeyCode::PageDown => {
+                                app.zoom_scroll = app.zoom_scroll.saturating_sub(10);
+                            }
+                            KeyCode::PageUp => {
+                                app.zoom_scroll = app.zoom_scroll.saturating_add(10);
+                            }
+                            KeyCode::Char('G') => {
+                                app.zoom_scroll = 0;
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // Handle firehose log view
+                    if app.firehose.is_some() {
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Char('L') => {
+                                app.toggle_firehose();
+                            }
+                            KeyCode::Down | KeyCode::Char('j') => {
+                                app.firehose_scroll = app.firehose_scroll.saturating_sub(1);
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                app.firehose_scroll = app.firehose_scroll.saturating_add(1);
+                            }
+                            KeyCode::PageDown => {
+                                app.firehose_scroll = app.firehose_scroll.saturating_sub(10);
+                            }
+                            KeyCode::PageUp => {
+                                app.firehose_scroll = app.firehose_scroll.saturating_add(10);
+                            }
+                            KeyCode::Char('G') => {
+                                app.firehose_scroll = 0;
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // Handle shutdown dialog: choose what happens to the agents
+                    if app.shutdown_dialog.is_some() {
+                        match key.code {
+                            KeyCode::Char('k') => {
+                                app.choose_shutdown(app::ShutdownChoice::KeepRunning)
+                            }
+                            KeyCode::Char('a') => app.choose_shutdown(app::ShutdownChoice::KillAll),
+                            KeyCode::Char('i') => {
+                                app.choose_shutdown(app::ShutdownChoice::KillIdle)
+                            }
+                            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('q') => {
+                                app.shutdown_dialog = None;
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // Handle confirmation dialog (kill or delete EA)
                     if let Some(action) = app.pending_confirm {
                         match key.code {
                             KeyCode::Char('y') | KeyCode::Char('Y') => match action {
                                 app::ConfirmAction::Kill => {
                                     let short_name = app.selected_agent_short_name();
                                     if let Err(e) = app.kill_selected() {
-                                        app.set_status(format!("Error: {}", e));
+                                        app.set_status_trf("Error: {}", &[&e]);
                                     } else if let Some(name) = short_name {
                                         scheduler.cancel_by_receiver_and_ea(&name, app.active_ea);
                                     }
                                 }
-                                app::ConfirmAction::ResetQuit => {
-                                    app.reset_on_quit = true;
-                                    app.should_quit = true;
+                                app::ConfirmAction::KillGroup => {
+                                    if let Err(e) = app.kill_selected_group() {
+                                        app.set_status_trf("Error: {}", &[&e]);
+                                    }
                                 }
                                 app::ConfirmAction::DeleteEa => {
                                     let ea_id = app.active_ea;
                                     if let Err(e) = app.delete_ea(ea_id) {
-                                        app.set_status(format!("Error: {}", e));
+                                        app.set_status_trf("Error: {}", &[&e]);
                                     }
                                 }
+                                app::ConfirmAction::ResumeRun => {
+                                    app.pending_confirm = None;
+                                    app.resume_previous_run();
+                                }
+                                app::ConfirmAction::Drain => {
+                                    app.pending_confirm = None;
+                                    app.toggle_drain();
+                                }
                             },
                             _ => {
+                                if action == app::ConfirmAction::ResumeRun {
+                                    app.dismiss_previous_run();
+                                }
                                 app.pending_confirm = None;
                             }
                         }
@@ -1134,6 +2314,56 @@ async fn run_dashboard(config: Config) -> Result<()> {
                         continue;
                     }
 
+                    // Handle spawn queue panel
+                    if let Some(selected) = app.queue_selected {
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Char('W') | KeyCode::Char('q') => {
+                                app.queue_selected = None;
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                app.queue_selected = Some(selected.saturating_sub(1));
+                            }
+                            KeyCode::Down | KeyCode::Char('j') => {
+                                let last = app.spawn_queue.len().saturating_sub(1);
+                                app.queue_selected = Some((selected + 1).min(last));
+                            }
+                            KeyCode::Char('x') | KeyCode::Delete => app.cancel_selected_queued(),
+                            KeyCode::Char('+') | KeyCode::Char('b') => app.bump_selected_queued(),
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // Handle "Needs attention" panel (letters go to the reply)
+                    if let Some(selected) = app.attention_selected {
+                        match key.code {
+                            KeyCode::Esc => {
+                                app.attention_selected = None;
+                                app.attention_input.clear();
+                            }
+                            KeyCode::Up => {
+                                app.attention_selected = Some(selected.saturating_sub(1));
+                            }
+                            KeyCode::Down => {
+                                let last = app.attention_agents().len().saturating_sub(1);
+                                app.attention_selected = Some((selected + 1).min(last));
+                            }
+                            KeyCode::Enter => {
+                                if let Err(e) = app.send_attention_reply() {
+                                    app.set_status_trf("Error: {}", &[&e]);
+                                }
+                            }
+                            KeyCode::Backspace => {
+                                app.attention_input.pop();
+                            }
+                            KeyCode::Char(c) => {
+                                app.attention_input.push(c);
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+
                     // Handle debug console popup
                     if app.show_debug_console {
                         match key.code {
@@ -1225,10 +2455,18 @@ async fn run_dashboard(config: Config) -> Result<()> {
                         continue;
                     }
 
+                    if app.is_demo() && demo::blocks_key(key.code) {
+                        app.set_status_tr("Demo mode: actions on agents are disabled");
+                        continue;
+                    }
+
                     // Normal key handling
                     match key.code {
+                        KeyCode::Char('Q') if app.is_demo() => {
+                            app.should_quit = true;
+                        }
                         KeyCode::Char('Q') => {
-                            app.pending_confirm = Some(app::ConfirmAction::ResetQuit);
+                            app.open_shutdown_dialog();
                         }
                         KeyCode::Esc => {
                             app.drill_up();
@@ -1248,7 +2486,7 @@ async fn run_dashboard(config: Config) -> Result<()> {
                             if app.config.dashboard.sidebar_right {
                                 // Sidebar is on the right: try grid right first, then sidebar
                                 if !app.grid_right() {
-                                    app.sidebar_focused = true;
+                                    app.focus_sidebar();
                                 }
                             } else {
                                 // Sidebar is on the left: try grid right (no fallback)
@@ -1267,7 +2505,7 @@ async fn run_dashboard(config: Config) -> Result<()> {
                             } else {
                                 // Sidebar is on the left: try grid left first, then sidebar
                                 if !app.grid_left() {
-                                    app.sidebar_focused = true;
+                                    app.focus_sidebar();
                                 }
                             }
                         }
@@ -1277,6 +2515,18 @@ async fn run_dashboard(config: Config) -> Result<()> {
                         KeyCode::Char('[') => {
                             app.cycle_previous_ea();
                         }
+                        KeyCode::Char('j') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            app.next_group();
+                        }
+                        KeyCode::Char('k') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            app.previous_group();
+                        }
+                        KeyCode::Char('J') => {
+                            app.next_group();
+                        }
+                        KeyCode::Char('K') => {
+                            app.previous_group();
+                        }
                         KeyCode::Char('j') | KeyCode::Down => {
                             if app.sidebar_focused {
                                 app.sidebar_next();
@@ -1301,7 +2551,7 @@ async fn run_dashboard(config: Config) -> Result<()> {
                             } else {
                                 // Sidebar on left: left means try grid left, then sidebar
                                 if !app.grid_left() {
-                                    app.sidebar_focused = true;
+                                    app.focus_sidebar();
                                 }
                             }
                         }
@@ -1310,7 +2560,7 @@ async fn run_dashboard(config: Config) -> Result<()> {
                             if app.config.dashboard.sidebar_right {
                                 // Sidebar on right: right means try grid right, then sidebar
                                 if !app.grid_right() {
-                                    app.sidebar_focused = true;
+                                    app.focus_sidebar();
                                 }
                             } else {
                                 // Sidebar on left: right means try grid right, no sidebar fallback
@@ -1332,16 +2582,17 @@ async fn run_dashboard(config: Config) -> Result<()> {
                             }
 
                             if let Err(e) = app.refresh() {
-                                app.set_status(format!("Error: {}", e));
+                                app.set_status_trf("Error: {}", &[&e]);
                                 continue;
                             }
 
                             let selected_popup_receiver = app
                                 .selected_popup_receiver_name()
                                 .map(|name| (name, app.active_ea));
-                            let popup_info = app
-                                .selected_agent()
-                                .map(|a| (a.session.name.clone(), app.client().clone()));
+                            let popup_info = app.selected_agent().map(|a| {
+                                let client = app.client_for_session(&a.session.name).clone();
+                                (a.session.name.clone(), client)
+                            });
 
                             // Tell the scheduler which agent popup is open so it
                             // defers events for that receiver until the popup closes.
@@ -1364,17 +2615,17 @@ async fn run_dashboard(config: Config) -> Result<()> {
 
                                     let mut app = shared_app.lock().await;
                                     match popup_result {
-                                        Err(e) => app.set_status(format!("Error: {}", e)),
+                                        Err(e) => app.set_status_trf("Error: {}", &[&e]),
                                         Ok(()) if !session_live => {
                                             let _ = app.refresh();
-                                            app.set_status(format!(
+                                            app.set_status_trf(
                                                 "{} exited; press Enter to restart it",
-                                                session_name
-                                            ));
+                                                &[&session_name],
+                                            );
                                         }
                                         Ok(()) => {
                                             if let Err(e) = app.refresh() {
-                                                app.set_status(format!("Error: {}", e));
+                                                app.set_status_trf("Error: {}", &[&e]);
                                             }
                                         }
                                     }
@@ -1391,14 +2642,22 @@ async fn run_dashboard(config: Config) -> Result<()> {
                                     );
                                 }
                                 disable_raw_mode()?;
-                                execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+                                execute!(
+                                    terminal.backend_mut(),
+                                    DisableBracketedPaste,
+                                    LeaveAlternateScreen
+                                )?;
 
                                 let app = shared_app.lock().await;
                                 let result = app.attach_selected();
                                 drop(app);
 
                                 // Restore terminal
-                                execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+                                execute!(
+                                    terminal.backend_mut(),
+                                    EnterAlternateScreen,
+                                    EnableBracketedPaste
+                                )?;
                                 enable_raw_mode()?;
                                 if keyboard_enhanced {
                                     let _ = execute!(
@@ -1413,7 +2672,7 @@ async fn run_dashboard(config: Config) -> Result<()> {
 
                                 if let Err(e) = result {
                                     let mut app = shared_app.lock().await;
-                                    app.set_status(format!("Error: {}", e));
+                                    app.set_status_trf("Error: {}", &[&e]);
                                 }
                             }
 
@@ -1421,13 +2680,14 @@ async fn run_dashboard(config: Config) -> Result<()> {
                             *popup_receiver.lock().unwrap() = None;
                         }
                         KeyCode::Char('n') => {
-                            if let Err(e) = app.spawn_agent() {
-                                app.set_status(format!("Error: {}", e));
-                            }
+                            app.open_spawn_form();
                         }
                         KeyCode::Char('d') if app.selected_agent().is_some() => {
                             app.pending_confirm = Some(app::ConfirmAction::Kill);
                         }
+                        KeyCode::Char('T') if app.selected_agent().is_some() => {
+                            app.pending_confirm = Some(app::ConfirmAction::KillGroup);
+                        }
                         KeyCode::Char('N') => {
                             // Open EA name prompt to spawn a new EA
                             app.ea_input_mode = true;
@@ -1437,17 +2697,50 @@ async fn run_dashboard(config: Config) -> Result<()> {
                             if app.registered_eas.len() > 1 {
                                 app.pending_confirm = Some(app::ConfirmAction::DeleteEa);
                             } else {
-                                app.set_status("Cannot delete the only EA");
+                                app.set_status_tr("Cannot delete the only EA");
                             }
                         }
                         KeyCode::Char('p') => {
                             app.project_input_mode = true;
                         }
+                        KeyCode::Char('m') => {
+                            app.open_message_input();
+                        }
+                        KeyCode::Char('a') => {
+                            app.open_note_input();
+                        }
+                        KeyCode::Char('F') => {
+                            app.search_input = Some(String::new());
+                        }
+                        KeyCode::Char('/') => {
+                            app.filter_input = Some(app.filter.clone());
+                        }
+                        KeyCode::Char('x') => {
+                            app.open_exec_popup();
+                        }
+                        KeyCode::Char('L') => {
+                            app.toggle_firehose();
+                        }
+                        KeyCode::Char('H') => {
+                            app.cycle_selected_priority();
+                        }
+                        KeyCode::Char('}') => {
+                            app.cycle_selected_pane(true);
+                        }
+                        KeyCode::Char('{') => {
+                            app.cycle_selected_pane(false);
+                        }
+                        KeyCode::Char('A') => {
+                            app.open_approval_review();
+                        }
+                        KeyCode::Char('f') => {
+                            app.open_file_browser();
+                        }
                         KeyCode::Char('r') => {
                             if let Err(e) = app.refresh() {
-                                app.set_status(format!("Error: {}", e));
+                                app.set_status_trf("Error: {}", &[&e]);
                             } else {
-                                app.set_status("Refreshed");
+                                app.set_status_tr("Refreshed");
                             }
                         }
                         KeyCode::Char('e') => {
@@ -1459,13 +2752,41 @@ async fn run_dashboard(config: Config) -> Result<()> {
                         KeyCode::Char('G') => {
                             app.show_debug_console = true;
                         }
+                        KeyCode::Char('W') => {
+                            app.queue_selected = Some(0);
+                        }
+                        KeyCode::Char('!') => {
+                            app.open_attention_panel();
+                        }
+                        KeyCode::Char('Z') => {
+                            app.pending_confirm = Some(app::ConfirmAction::Drain);
+                        }
                         // Detach from tmux — dashboard + agents keep running
-                        KeyCode::Char('z') if std::env::var("TMUX").is_ok() => {
+                        KeyCode::Char('z')
+                            if key.modifiers.contains(KeyModifiers::CONTROL)
+                                && std::env::var("TMUX").is_ok()
+                                && !app.is_demo() =>
+                        {
                             let _ = tmux_command().args(["detach-client"]).status();
                         }
+                        KeyCode::Char('z') if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            app.toggle_zoom();
+                        }
                         KeyCode::Char('S') => {
                             app.show_settings = true;
                         }
+                        KeyCode::Char('V') => {
+                            app.cycle_layout_preset();
+                        }
+                        KeyCode::Char('P') => {
+                            app.toggle_panel(ui_state::LayoutPanel::Projects);
+                        }
+                        KeyCode::Char('E') => {
+                            app.toggle_panel(ui_state::LayoutPanel::EaPanel);
+                        }
+                        KeyCode::Char('C') => {
+                            app.toggle_panel(ui_state::LayoutPanel::CommandTree);
+                        }
                         KeyCode::Char('?') => {
                             app.show_help = !app.show_help;
                         }
@@ -1484,13 +2805,14 @@ async fn run_dashboard(config: Config) -> Result<()> {
                     // Fix V2: EA-scoped events instead of global list
                     app.scheduled_events = scheduler.list_by_ea(app.active_ea);
                     app.scheduled_events.sort_by_key(|e| e.timestamp);
+                    app.poll_sandbox_preflight();
 
                     // Skip refresh while a popup/input overlay is active
                     // to avoid interrupting user input.
                     if !app.has_popup() {
                         app.clear_status();
                         if let Err(e) = app.refresh() {
-                            app.set_status(format!("Error: {}", e));
+                            app.set_status_trf("Error: {}", &[&e]);
                         }
                     }
 
@@ -1514,6 +2836,12 @@ async fn run_dashboard(config: Config) -> Result<()> {
                     let mut app = shared_app.lock().await;
                     app.ticker_offset = app.ticker_offset.wrapping_add(1);
                 }
+                AppEvent::Paste(text) => {
+                    let mut app = shared_app.lock().await;
+                    if let Err(e) = app.handle_paste(&text) {
+                        app.set_status_trf("Paste failed: {}", &[&e]);
+                    }
+                }
                 AppEvent::Resize(_, _) => {
                     // Terminal will handle resize automatically
                 }
@@ -1535,23 +2863,19 @@ async fn run_dashboard(config: Config) -> Result<()> {
         let _ = execute!(terminal.backend_mut(), PopKeyboardEnhancementFlags);
     }
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-
-    // Kill ALL OMAR EA sessions on quit (managers + workers), even if
-    // registry and tmux are temporarily out of sync.
-    {
-        let app = shared_app.lock().await;
-        let client = TmuxClient::new("");
-        let base_prefix = app.base_prefix.clone();
-
-        if let Ok(sessions) = client.list_all_sessions() {
-            for session in sessions {
-                if session.name.starts_with(&base_prefix) {
-                    let _ = client.kill_session(&session.name);
-                }
-            }
-        }
-    }
+    execute!(
+        terminal.backend_mut(),
+        DisableBracketedPaste,
+        LeaveAlternateScreen
+    )?;
+
+    // Stop the agents the shutdown dialog chose (all of them for a drain)
+    let summary = if demo {
+        None
+    } else {
+        let mut app = shared_app.lock().await;
+        Some(app.shut_down())
+    };
 
     // Kill Slack bridge on exit
     if let Some(ref mut child) = slack_bridge {
@@ -1570,6 +2894,12 @@ async fn run_dashboard(config: Config) -> Result<()> {
     if reset_on_quit {
         purge_persisted_runtime_state_on_quit(&omar_dir)?;
     }
+    if let Some(dir) = demo_dir {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+    if let Some(summary) = summary {
+        println!("{}", summary);
+    }
 
     Ok(())
 }
@@ -1721,6 +3051,54 @@ mod tests {
     /// which keeps Shift+Enter working in Claude panes while leaving the
     /// dashboard on legacy xterm encoding (where Shift+Tab → `\x1b[Z` →
     /// `KeyCode::BackTab`). Do not flip back to `always`.
+    #[test]
+    fn completions_register_each_shell_and_complete_subcommands() {
+        for (shell, expected) in [
+            (CompletionShell::Bash, "COMPLETE=\"bash\""),
+            (CompletionShell::Zsh, "COMPLETE=\"zsh\""),
+            (CompletionShell::Fish, "COMPLETE=fish"),
+        ] {
+            let mut out = Vec::new();
+            write_completions(shell, &mut out).unwrap();
+            let script = String::from_utf8(out).unwrap();
+            assert!(script.contains(expected), "{}", script);
+        }
+
+        let candidates = clap_complete::engine::complete(
+            &mut Cli::command(),
+            vec!["omar".into(), "comp".into()],
+            1,
+            None,
+        )
+        .unwrap();
+        let values: Vec<_> = candidates.iter().map(|c| c.get_value()).collect();
+        assert_eq!(values, ["completions"]);
+    }
+
+    #[test]
+    fn resolve_cli_session_qualifies_short_names() {
+        let (client, session) = resolve_cli_session(1, "omar-agent-", "api");
+        assert_eq!(client.prefix(), "omar-agent-1-");
+        assert_eq!(session, "omar-agent-1-api");
+        let (_, session) = resolve_cli_session(1, "omar-agent-", "omar-agent-1-api");
+        assert_eq!(session, "omar-agent-1-api");
+        let (_, session) = resolve_cli_session(1, "omar-agent-", "ea");
+        assert_eq!(session, ea::ea_manager_session(1, "omar-agent-"));
+    }
+
+    #[test]
+    fn read_task_arg_reads_stdin_for_dash() {
+        let spec = "# Spec\n\nline with 'quotes' and \"doubles\"\n".repeat(200);
+        let task = read_task_arg(Some("-".to_string()), spec.as_bytes()).unwrap();
+        assert_eq!(task.as_deref(), Some(spec.trim()));
+
+        let literal = read_task_arg(Some("fix the bug".to_string()), io::empty()).unwrap();
+        assert_eq!(literal.as_deref(), Some("fix the bug"));
+
+        assert_eq!(read_task_arg(None, io::empty()).unwrap(), None);
+        assert!(read_task_arg(Some("-".to_string()), "  \n".as_bytes()).is_err());
+    }
+
     #[test]
     fn tmux_extended_keys_recommendation_is_on_not_always() {
         let entry = TMUX_RECOMMENDED
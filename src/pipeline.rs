@@ -0,0 +1,513 @@
+//! Pipelines — named chains of agent stages where each stage's result feeds
+//! the next stage's task
+//!
+//! Runs are started by the `run_pipeline` MCP tool and persisted one file
+//! per run under `<state_dir>/pipelines/`. The dashboard advances them on
+//! refresh: it spawns the current stage's agent and moves on when that agent
+//! prints `[TASK COMPLETE]`. Stages marked `requires_approval` hold the run
+//! in `AwaitingApproval` until a human signs off from the dashboard or the
+//! `resolve_approval` MCP tool.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::PipelineConfig;
+use crate::tmux::{AgentResult, ResultKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Running,
+    AwaitingApproval,
+    Completed,
+    Blocked,
+    Failed,
+}
+
+impl RunStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RunStatus::Running => "running",
+            RunStatus::AwaitingApproval => "awaiting_approval",
+            RunStatus::Completed => "completed",
+            RunStatus::Blocked => "blocked",
+            RunStatus::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageRun {
+    pub role: String,
+    pub prompt: String,
+    #[serde(default)]
+    pub backend: Option<String>,
+    #[serde(default)]
+    pub requires_approval: bool,
+    /// Set once a human signs off on a gated stage.
+    #[serde(default)]
+    pub approved: bool,
+    /// Full session name once the stage agent has been spawned.
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// Result detail reported by the stage agent.
+    #[serde(default)]
+    pub result: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PipelineRun {
+    pub id: u64,
+    pub pipeline: String,
+    pub input: String,
+    pub status: RunStatus,
+    /// Index of the stage in progress (== stages.len() once completed).
+    pub current: usize,
+    pub stages: Vec<StageRun>,
+    /// Why the run stopped, for blocked/failed runs.
+    #[serde(default)]
+    pub detail: Option<String>,
+    pub created_at: String,
+}
+
+impl PipelineRun {
+    /// Advance past finished stages. Returns the index of a stage whose agent
+    /// should be spawned now, if any. `live` holds the running sessions.
+    pub fn advance(
+        &mut self,
+        results: &HashMap<String, AgentResult>,
+        live: &HashSet<String>,
+    ) -> Option<usize> {
+        if self.status != RunStatus::Running {
+            return None;
+        }
+        let stage = self.stages.get_mut(self.current)?;
+        let Some(agent) = stage.agent.as_deref() else {
+            return self.start_current();
+        };
+        match results.get(agent) {
+            Some(result) if result.kind == ResultKind::Complete => {
+                stage.result = Some(result.detail.clone());
+                self.current += 1;
+                if self.current == self.stages.len() {
+                    self.status = RunStatus::Completed;
+                    None
+                } else {
+                    self.start_current()
+                }
+            }
+            Some(result) => {
+                self.status = RunStatus::Blocked;
+                self.detail = Some(format!("{}: {}", stage.role, result.detail));
+                None
+            }
+            None if !live.contains(agent) => {
+                self.status = RunStatus::Failed;
+                self.detail = Some(format!("{}: agent exited without a result", stage.role));
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// One-line wrap-up for a completed run, e.g. `review#3 finished: 2
+    /// stages in 12m, ~$0.40`. `cost_usd` is omitted when unknown.
+    pub fn completion_summary(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+        cost_usd: Option<f64>,
+    ) -> String {
+        let duration = chrono::DateTime::parse_from_rfc3339(&self.created_at)
+            .map(|created| (now.timestamp() - created.timestamp()).max(0) as u64)
+            .unwrap_or_default();
+        let stages = if self.stages.len() == 1 {
+            "stage"
+        } else {
+            "stages"
+        };
+        let mut summary = format!(
+            "{}#{} finished: {} {} in {}",
+            self.pipeline,
+            self.id,
+            self.stages.len(),
+            stages,
+            crate::metrics::format_duration(duration)
+        );
+        if let Some(cost) = cost_usd {
+            summary.push_str(&format!(", ~${:.2}", cost));
+        }
+        summary
+    }
+
+    /// The current stage if it may be spawned; gated stages park the run.
+    fn start_current(&mut self) -> Option<usize> {
+        let stage = &self.stages[self.current];
+        if stage.requires_approval && !stage.approved {
+            self.status = RunStatus::AwaitingApproval;
+            return None;
+        }
+        Some(self.current)
+    }
+
+    /// Sign off on (or reject) the stage awaiting approval.
+    pub fn resolve_approval(&mut self, approve: bool, reason: Option<&str>) -> Result<()> {
+        if self.status != RunStatus::AwaitingApproval {
+            return Err(anyhow!(
+                "Run {}#{} is {}, not awaiting approval",
+                self.pipeline,
+                self.id,
+                self.status.as_str()
+            ));
+        }
+        let stage = &mut self.stages[self.current];
+        if approve {
+            stage.approved = true;
+            self.status = RunStatus::Running;
+        } else {
+            self.status = RunStatus::Failed;
+            self.detail = Some(match reason {
+                Some(reason) => format!("{}: rejected: {}", stage.role, reason),
+                None => format!("{}: rejected", stage.role),
+            });
+        }
+        Ok(())
+    }
+
+    /// Task text for stage `idx`, with `{{input}}` and `{{previous}}` filled.
+    pub fn stage_task(&self, idx: usize) -> String {
+        let previous = idx
+            .checked_sub(1)
+            .and_then(|prev| self.stages.get(prev))
+            .and_then(|stage| stage.result.as_deref())
+            .unwrap_or("");
+        self.stages[idx]
+            .prompt
+            .replace("{{input}}", &self.input)
+            .replace("{{previous}}", previous)
+    }
+
+    /// Short agent name for stage `idx`, e.g. `feature-3-plan`.
+    pub fn stage_agent_name(&self, idx: usize) -> String {
+        sanitize(&format!(
+            "{}-{}-{}",
+            self.pipeline, self.id, self.stages[idx].role
+        ))
+    }
+
+    /// One-line progress summary, e.g. `feature#3 2/4 implement`.
+    pub fn progress(&self) -> String {
+        match self.status {
+            RunStatus::Running => format!(
+                "{}#{} {}/{} {}",
+                self.pipeline,
+                self.id,
+                self.current + 1,
+                self.stages.len(),
+                self.stages[self.current].role
+            ),
+            RunStatus::AwaitingApproval => format!(
+                "{}#{} {}/{} {} awaiting approval",
+                self.pipeline,
+                self.id,
+                self.current + 1,
+                self.stages.len(),
+                self.stages[self.current].role
+            ),
+            status => format!("{}#{} {}", self.pipeline, self.id, status.as_str()),
+        }
+    }
+}
+
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+fn runs_dir(state_dir: &Path) -> PathBuf {
+    state_dir.join("pipelines")
+}
+
+fn run_path(state_dir: &Path, id: u64) -> PathBuf {
+    runs_dir(state_dir).join(format!("{}.json", id))
+}
+
+/// Record a new run of `pipeline`. Each run gets its own file so the MCP
+/// server and the dashboard never rewrite each other's runs.
+pub fn start_run_in(
+    state_dir: &Path,
+    name: &str,
+    pipeline: &PipelineConfig,
+    input: &str,
+) -> Result<PipelineRun> {
+    if pipeline.stages.is_empty() {
+        return Err(anyhow!("Pipeline '{}' has no stages", name));
+    }
+    fs::create_dir_all(runs_dir(state_dir))?;
+    let mut id = load_runs_from(state_dir).last().map_or(1, |run| run.id + 1);
+    let run = loop {
+        let run = PipelineRun {
+            id,
+            pipeline: name.to_string(),
+            input: input.to_string(),
+            status: RunStatus::Running,
+            current: 0,
+            stages: pipeline
+                .stages
+                .iter()
+                .map(|stage| StageRun {
+                    role: stage.role.clone(),
+                    prompt: stage.prompt.clone(),
+                    backend: stage.backend.clone(),
+                    requires_approval: stage.requires_approval,
+                    approved: false,
+                    agent: None,
+                    result: None,
+                })
+                .collect(),
+            detail: None,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(run_path(state_dir, id))
+        {
+            Ok(file) => {
+                serde_json::to_writer_pretty(file, &run)?;
+                break run;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => id += 1,
+            Err(e) => return Err(e.into()),
+        }
+    };
+    Ok(run)
+}
+
+/// All runs for an EA, oldest first.
+pub fn load_runs_from(state_dir: &Path) -> Vec<PipelineRun> {
+    let Ok(entries) = fs::read_dir(runs_dir(state_dir)) else {
+        return Vec::new();
+    };
+    let mut runs: Vec<PipelineRun> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|text| serde_json::from_str(&text).ok())
+        .collect();
+    runs.sort_by_key(|run| run.id);
+    runs
+}
+
+/// Approve or reject the gated stage of run `id` and persist the result.
+pub fn resolve_approval_in(
+    state_dir: &Path,
+    id: u64,
+    approve: bool,
+    reason: Option<&str>,
+) -> Result<PipelineRun> {
+    let mut run = load_runs_from(state_dir)
+        .into_iter()
+        .find(|run| run.id == id)
+        .ok_or_else(|| anyhow!("Pipeline run {} not found", id))?;
+    run.resolve_approval(approve, reason)?;
+    save_run_in(state_dir, &run);
+    Ok(run)
+}
+
+/// Point stages run by session `old` at `new`, so a renamed stage agent
+/// is still found when its run advances.
+pub fn rename_agent_in(state_dir: &Path, old: &str, new: &str) {
+    for mut run in load_runs_from(state_dir) {
+        let mut renamed = false;
+        for stage in &mut run.stages {
+            if stage.agent.as_deref() == Some(old) {
+                stage.agent = Some(new.to_string());
+                renamed = true;
+            }
+        }
+        if renamed {
+            save_run_in(state_dir, &run);
+        }
+    }
+}
+
+pub fn save_run_in(state_dir: &Path, run: &PipelineRun) {
+    let path = run_path(state_dir, run.id);
+    let tmp = path.with_extension("json.tmp");
+    if let Ok(json) = serde_json::to_string_pretty(run) {
+        if fs::write(&tmp, json).is_ok() {
+            let _ = fs::rename(&tmp, &path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PipelineStageConfig;
+
+    fn pipeline() -> PipelineConfig {
+        PipelineConfig {
+            stages: ["plan", "implement"]
+                .iter()
+                .map(|role| PipelineStageConfig {
+                    role: role.to_string(),
+                    prompt: format!("{} {{{{input}}}} after {{{{previous}}}}", role),
+                    backend: None,
+                    requires_approval: false,
+                })
+                .collect(),
+        }
+    }
+
+    fn result(kind: ResultKind, detail: &str) -> AgentResult {
+        AgentResult {
+            kind,
+            detail: detail.to_string(),
+            detected_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_runs_persist_with_increasing_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = start_run_in(dir.path(), "feature", &pipeline(), "login").unwrap();
+        let second = start_run_in(dir.path(), "feature", &pipeline(), "logout").unwrap();
+        assert_eq!((first.id, second.id), (1, 2));
+
+        let mut run = load_runs_from(dir.path()).remove(0);
+        run.status = RunStatus::Failed;
+        save_run_in(dir.path(), &run);
+        let runs = load_runs_from(dir.path());
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].status, RunStatus::Failed);
+
+        assert!(start_run_in(dir.path(), "empty", &PipelineConfig::default(), "").is_err());
+    }
+
+    #[test]
+    fn test_advance_feeds_results_into_next_stage() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut run = start_run_in(dir.path(), "feature", &pipeline(), "login").unwrap();
+        let mut results = HashMap::new();
+        let mut live = HashSet::new();
+
+        assert_eq!(run.advance(&results, &live), Some(0));
+        assert_eq!(run.stage_task(0), "plan login after ");
+        assert_eq!(run.stage_agent_name(0), "feature-1-plan");
+        run.stages[0].agent = Some("omar-agent-0-feature-1-plan".to_string());
+        live.insert("omar-agent-0-feature-1-plan".to_string());
+        assert_eq!(run.advance(&results, &live), None);
+
+        results.insert(
+            "omar-agent-0-feature-1-plan".to_string(),
+            result(ResultKind::Complete, "3 steps"),
+        );
+        assert_eq!(run.advance(&results, &live), Some(1));
+        assert_eq!(run.stage_task(1), "implement login after 3 steps");
+        assert_eq!(run.progress(), "feature#1 2/2 implement");
+
+        run.stages[1].agent = Some("omar-agent-0-feature-1-implement".to_string());
+        results.insert(
+            "omar-agent-0-feature-1-implement".to_string(),
+            result(ResultKind::Complete, "done"),
+        );
+        assert_eq!(run.advance(&results, &live), None);
+        assert_eq!(run.status, RunStatus::Completed);
+    }
+
+    #[test]
+    fn test_renamed_stage_agent_keeps_the_run_going() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut run = start_run_in(dir.path(), "feature", &pipeline(), "login").unwrap();
+        run.stages[0].agent = Some("omar-agent-0-feature-1-plan".to_string());
+        save_run_in(dir.path(), &run);
+
+        crate::memory::AgentUpdate::new("omar-agent-0-planner")
+            .rename_from_in(dir.path(), "omar-agent-0-feature-1-plan")
+            .unwrap();
+
+        let mut run = load_runs_from(dir.path()).remove(0);
+        assert_eq!(run.stages[0].agent.as_deref(), Some("omar-agent-0-planner"));
+        let live = HashSet::from(["omar-agent-0-planner".to_string()]);
+        assert_eq!(run.advance(&HashMap::new(), &live), None);
+        assert_eq!(run.status, RunStatus::Running);
+    }
+
+    #[test]
+    fn test_gated_stage_waits_for_approval() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut gated = pipeline();
+        gated.stages[1].requires_approval = true;
+        let mut run = start_run_in(dir.path(), "feature", &gated, "x").unwrap();
+        run.stages[0].agent = Some("plan".to_string());
+        let mut results = HashMap::new();
+        results.insert("plan".to_string(), result(ResultKind::Complete, "ok"));
+        let live = HashSet::new();
+
+        assert_eq!(run.advance(&results, &live), None);
+        assert_eq!(run.status, RunStatus::AwaitingApproval);
+        assert_eq!(run.progress(), "feature#1 2/2 implement awaiting approval");
+        assert_eq!(run.advance(&results, &live), None, "stays parked");
+        save_run_in(dir.path(), &run);
+
+        let approved = resolve_approval_in(dir.path(), run.id, true, None).unwrap();
+        assert_eq!(approved.status, RunStatus::Running);
+        let mut run = load_runs_from(dir.path()).remove(0);
+        assert_eq!(run.advance(&results, &live), Some(1));
+        assert!(resolve_approval_in(dir.path(), run.id, true, None).is_err());
+
+        let mut rejected = start_run_in(dir.path(), "feature", &gated, "y").unwrap();
+        rejected.current = 1;
+        rejected.status = RunStatus::AwaitingApproval;
+        rejected.resolve_approval(false, Some("not today")).unwrap();
+        assert_eq!(rejected.status, RunStatus::Failed);
+        assert_eq!(
+            rejected.detail.as_deref(),
+            Some("implement: rejected: not today")
+        );
+    }
+
+    #[test]
+    fn test_completion_summary_reports_duration_and_cost() {
+        let dir = tempfile::tempdir().unwrap();
+        let run = start_run_in(dir.path(), "review", &pipeline(), "PR 12").unwrap();
+        let created = chrono::DateTime::parse_from_rfc3339(&run.created_at).unwrap();
+        let now = created.to_utc() + chrono::Duration::minutes(12);
+        assert_eq!(
+            run.completion_summary(now, Some(0.4)),
+            "review#1 finished: 2 stages in 12m, ~$0.40"
+        );
+        assert_eq!(
+            run.completion_summary(now, None),
+            "review#1 finished: 2 stages in 12m"
+        );
+    }
+
+    #[test]
+    fn test_advance_stops_on_blocked_or_vanished_agent() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut run = start_run_in(dir.path(), "feature", &pipeline(), "x").unwrap();
+        run.stages[0].agent = Some("s".to_string());
+        let mut results = HashMap::new();
+        results.insert("s".to_string(), result(ResultKind::Blocked, "no creds"));
+        assert_eq!(run.advance(&results, &HashSet::new()), None);
+        assert_eq!(run.status, RunStatus::Blocked);
+        assert_eq!(run.detail.as_deref(), Some("plan: no creds"));
+
+        let mut run = start_run_in(dir.path(), "feature", &pipeline(), "x").unwrap();
+        run.stages[0].agent = Some("gone".to_string());
+        assert_eq!(run.advance(&HashMap::new(), &HashSet::new()), None);
+        assert_eq!(run.status, RunStatus::Failed);
+    }
+}
@@ -1,24 +1,47 @@
-//! Project management — CRUD on per-EA tasks.md
+//! Project management — CRUD on per-EA projects
 //!
-//! File format: numbered lines like `1. Project name`
+//! Projects live in the EA's state store, which exports them to tasks.md.
+//! File format: numbered lines like `1. Project name`, each optionally
+//! followed by indented checklist items (`   - [ ] Write schema`,
+//! `   - [x] Add endpoints`).
 //! IDs are stable: deletion does NOT renumber remaining items.
 //! New projects get max_existing_id + 1.
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
 use uuid::Uuid;
 
-/// Mutex to serialize concurrent read-modify-write on tasks.md.
-/// Same pattern as WORKER_TASKS_LOCK in memory.rs.
-static PROJECTS_LOCK: Mutex<()> = Mutex::new(());
+use crate::state_store::{SqliteStore, StateStore};
 
 #[derive(Debug, Clone)]
 pub struct Project {
     pub id: usize,
     pub name: String,
+    pub items: Vec<ChecklistItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecklistItem {
+    pub done: bool,
+    pub text: String,
+}
+
+impl Project {
+    /// (done, total) checklist items, or None without a checklist.
+    pub fn progress(&self) -> Option<(usize, usize)> {
+        if self.items.is_empty() {
+            return None;
+        }
+        let done = self.items.iter().filter(|item| item.done).count();
+        Some((done, self.items.len()))
+    }
+
+    /// Whole-number completion percentage, or None without a checklist.
+    pub fn percent(&self) -> Option<usize> {
+        self.progress().map(|(done, total)| done * 100 / total)
+    }
 }
 
 /// Path to the projects file for an EA
@@ -29,19 +52,56 @@ pub fn projects_path_in(state_dir: &Path) -> PathBuf {
 
 /// Load projects from an EA's state directory
 pub fn load_projects_from(state_dir: &Path) -> Vec<Project> {
-    let path = projects_path_in(state_dir);
-    let content = match fs::read_to_string(&path) {
-        Ok(c) => c,
-        Err(_) => return Vec::new(),
+    SqliteStore::open(state_dir)
+        .and_then(|store| store.projects())
+        .unwrap_or_else(|_| {
+            parse_projects(&fs::read_to_string(projects_path_in(state_dir)).unwrap_or_default())
+        })
+}
+
+/// Apply `change` to an EA's projects in one store transaction.
+fn update_projects_in<T>(
+    state_dir: &Path,
+    change: impl FnOnce(&mut Vec<Project>) -> Result<T>,
+) -> Result<T> {
+    let mut change = Some(change);
+    let mut output = None;
+    SqliteStore::open(state_dir)?.update_projects(&mut |projects| {
+        if let Some(change) = change.take() {
+            output = Some(change(projects)?);
+        }
+        Ok(())
+    })?;
+    output.ok_or_else(|| anyhow!("project update did not run"))
+}
+
+/// Parse a `- [ ] text` / `- [x] text` checklist line.
+pub fn parse_item(line: &str) -> Option<ChecklistItem> {
+    let rest = line.strip_prefix("- [")?;
+    let (mark, text) = rest.split_once("] ")?;
+    let done = match mark {
+        " " => false,
+        "x" | "X" => true,
+        _ => return None,
     };
-    parse_projects(&content)
+    let text = text.trim();
+    (!text.is_empty()).then(|| ChecklistItem {
+        done,
+        text: text.to_string(),
+    })
 }
 
 /// Parse project lines from content
-fn parse_projects(content: &str) -> Vec<Project> {
-    let mut projects = Vec::new();
+pub(crate) fn parse_projects(content: &str) -> Vec<Project> {
+    let mut projects: Vec<Project> = Vec::new();
     for line in content.lines() {
         let trimmed = line.trim();
+        if let Some(item) = parse_item(trimmed) {
+            if let Some(project) = projects.last_mut() {
+                project.items.push(item);
+            }
+            continue;
+        }
         // Match lines like "1. Project name" — preserve the actual numeric ID
         if let Some(dot_pos) = trimmed.find(". ") {
             let num_str = &trimmed[..dot_pos];
@@ -52,6 +112,7 @@ fn parse_projects(content: &str) -> Vec<Project> {
                         projects.push(Project {
                             id,
                             name: name.to_string(),
+                            items: Vec::new(),
                         });
                     }
                 }
@@ -62,18 +123,35 @@ fn parse_projects(content: &str) -> Vec<Project> {
 }
 
 /// Save projects to an EA's state directory (IDs are preserved, not renumbered)
+#[cfg(test)]
 pub fn save_projects_to(state_dir: &Path, projects: &[Project]) -> Result<()> {
-    let path = projects_path_in(state_dir);
-    let content: String = projects
-        .iter()
-        .map(|p| format!("{}. {}", p.id, p.name))
-        .collect::<Vec<_>>()
-        .join("\n");
-    let content = if content.is_empty() {
+    update_projects_in(state_dir, |saved| {
+        *saved = projects.to_vec();
+        Ok(())
+    })
+}
+
+/// The tasks.md text for `projects`.
+pub(crate) fn render_projects(projects: &[Project]) -> String {
+    let content: String =
+        projects
+            .iter()
+            .flat_map(|p| {
+                std::iter::once(format!("{}. {}", p.id, p.name)).chain(p.items.iter().map(|item| {
+                    format!("   - [{}] {}", if item.done { "x" } else { " " }, item.text)
+                }))
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    if content.is_empty() {
         String::new()
     } else {
         format!("{}\n", content)
-    };
+    }
+}
+
+/// Atomically replace the tasks.md export at `path`.
+pub(crate) fn write_export(path: &Path, content: &str) -> Result<()> {
     let file_name = path
         .file_name()
         .and_then(|name| name.to_str())
@@ -85,7 +163,7 @@ pub fn save_projects_to(state_dir: &Path, projects: &[Project]) -> Result<()> {
         .open(&tmp)?;
     file.write_all(content.as_bytes())?;
     file.sync_all()?;
-    if let Err(err) = fs::rename(&tmp, &path) {
+    if let Err(err) = fs::rename(&tmp, path) {
         let _ = fs::remove_file(&tmp);
         return Err(err.into());
     }
@@ -94,15 +172,52 @@ pub fn save_projects_to(state_dir: &Path, projects: &[Project]) -> Result<()> {
 
 /// Add a project to an EA, returns new id
 pub fn add_project_in(state_dir: &Path, name: &str) -> Result<usize> {
-    let _guard = PROJECTS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
-    let mut projects = load_projects_from(state_dir);
-    let id = projects.iter().map(|p| p.id).max().unwrap_or(0) + 1;
-    projects.push(Project {
-        id,
-        name: name.to_string(),
-    });
-    save_projects_to(state_dir, &projects)?;
-    Ok(id)
+    update_projects_in(state_dir, |projects| {
+        let id = projects.iter().map(|p| p.id).max().unwrap_or(0) + 1;
+        projects.push(Project {
+            id,
+            name: name.to_string(),
+            items: Vec::new(),
+        });
+        Ok(id)
+    })
+}
+
+/// Append a checklist item to a project, returns its 1-based number
+pub fn add_item_in(state_dir: &Path, id: usize, text: &str) -> Result<usize> {
+    update_projects_in(state_dir, |projects| {
+        let project = projects
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| anyhow!("Project '{}' not found", id))?;
+        project.items.push(ChecklistItem {
+            done: false,
+            text: text.to_string(),
+        });
+        Ok(project.items.len())
+    })
+}
+
+/// Check or uncheck item `number` (1-based) of a project; `None` flips it.
+/// Returns the project as saved.
+pub fn set_item_in(
+    state_dir: &Path,
+    id: usize,
+    number: usize,
+    done: Option<bool>,
+) -> Result<Project> {
+    update_projects_in(state_dir, |projects| {
+        let project = projects
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| anyhow!("Project '{}' not found", id))?;
+        let item = number
+            .checked_sub(1)
+            .and_then(|idx| project.items.get_mut(idx))
+            .ok_or_else(|| anyhow!("Project '{}' has no item {}", id, number))?;
+        item.done = done.unwrap_or(!item.done);
+        Ok(project.clone())
+    })
 }
 
 /// Find a project by id. None if not present.
@@ -114,15 +229,11 @@ pub fn find_project_in(state_dir: &Path, id: usize) -> Option<Project> {
 
 /// Remove a project by id from an EA, returns whether it was found
 pub fn remove_project_in(state_dir: &Path, id: usize) -> Result<bool> {
-    let _guard = PROJECTS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
-    let mut projects = load_projects_from(state_dir);
-    if let Some(pos) = projects.iter().position(|p| p.id == id) {
-        projects.remove(pos);
-        save_projects_to(state_dir, &projects)?;
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    update_projects_in(state_dir, |projects| {
+        let before = projects.len();
+        projects.retain(|p| p.id != id);
+        Ok(projects.len() != before)
+    })
 }
 
 #[cfg(test)]
@@ -141,6 +252,35 @@ mod tests {
         assert_eq!(projects[2].id, 3);
     }
 
+    #[test]
+    fn test_checklist_roundtrip_and_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_dir = dir.path();
+        fs::write(
+            projects_path_in(state_dir),
+            "1. Build REST API\n   - [x] Schema\n   - [ ] Endpoints\n- [ ] stray\n2. Set up CI\n",
+        )
+        .unwrap();
+        let loaded = load_projects_from(state_dir);
+        assert_eq!(loaded[0].items.len(), 3);
+        assert_eq!(loaded[0].progress(), Some((1, 3)));
+        assert_eq!(loaded[1].progress(), None);
+
+        assert_eq!(add_item_in(state_dir, 2, "Lint job").unwrap(), 1);
+        assert!(add_item_in(state_dir, 9, "nope").is_err());
+        let project = set_item_in(state_dir, 1, 2, None).unwrap();
+        assert_eq!(project.percent(), Some(66));
+        let project = set_item_in(state_dir, 1, 2, Some(true)).unwrap();
+        assert!(project.items[1].done);
+        assert!(set_item_in(state_dir, 1, 0, None).is_err());
+
+        assert_eq!(
+            fs::read_to_string(projects_path_in(state_dir)).unwrap(),
+            "1. Build REST API\n   - [x] Schema\n   - [x] Endpoints\n   - [ ] stray\n\
+             2. Set up CI\n   - [ ] Lint job\n"
+        );
+    }
+
     #[test]
     fn test_parse_empty() {
         assert!(parse_projects("").is_empty());
@@ -0,0 +1,387 @@
+//! Startup reconciliation — bring an EA's `~/.omar` state back in line with
+//! the tmux sessions that survived a dashboard crash or restart
+//!
+//! Live worker sessions without a parent are adopted by the manager, and
+//! children of parents that are gone for good are re-parented to it. Workers
+//! that still have a parent mapping and task but no session (the tmux server
+//! died, or the host rebooted) are offered for resume; killed workers drop
+//! their parent mapping and finished ones report `[TASK COMPLETE]`, so
+//! neither is resumed.
+//!
+//! `omar save` also writes the EA's worker definitions to
+//! `~/.omar/saved/ea<id>.json`. `omar restore` re-spawns the unfinished ones
+//! from it, and the dashboard offers them on startup along with the
+//! resumable workers found in state. The file is deleted once its workers
+//! are all restored, or when the user declines.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::ea::EaId;
+use crate::memory::{self, AgentLaunch, AgentRole};
+use crate::naming;
+use crate::tmux::ResultKind;
+
+/// A worker from the previous run whose session is gone mid-task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResumableAgent {
+    pub session: String,
+    pub parent: String,
+    pub task: String,
+    /// `None` for workers spawned before launches were recorded
+    pub launch: Option<AgentLaunch>,
+    pub role: AgentRole,
+    pub project: Option<usize>,
+}
+
+#[derive(Debug, Default)]
+pub struct Reconciliation {
+    /// Live sessions that had no parent and now report to the manager
+    pub adopted: Vec<String>,
+    /// Live sessions whose parent was gone and now report to the manager
+    pub reparented: Vec<String>,
+    /// Parents before children, so each resumed parent exists first
+    pub resumable: Vec<ResumableAgent>,
+}
+
+/// Reconcile `state_dir` with `live` worker sessions, fixing hierarchy in
+/// place and returning what was changed plus what can be resumed.
+pub fn reconcile(state_dir: &Path, live: &[String], manager_session: &str) -> Reconciliation {
+    let live: HashSet<&str> = live
+        .iter()
+        .map(String::as_str)
+        .filter(|session| *session != manager_session)
+        .collect();
+    let parents = memory::load_agent_parents_from(state_dir);
+    let tasks = memory::load_worker_tasks_from(state_dir);
+    let results = memory::load_agent_results_from(state_dir);
+    let launches = memory::load_agent_launches_from(state_dir);
+    let roles = memory::load_agent_roles_from(state_dir);
+    let projects = memory::load_agent_projects_from(state_dir);
+
+    let mut resumable: Vec<ResumableAgent> = parents
+        .iter()
+        .filter(|(child, _)| !live.contains(child.as_str()) && child.as_str() != manager_session)
+        .filter(|(child, _)| {
+            !results
+                .get(*child)
+                .is_some_and(|result| matches!(result.kind, ResultKind::Complete))
+        })
+        .filter_map(|(child, parent)| {
+            Some(ResumableAgent {
+                session: child.clone(),
+                parent: parent.clone(),
+                task: tasks.get(child)?.clone(),
+                launch: launches.get(child).cloned(),
+                role: roles.get(child).copied().unwrap_or_default(),
+                project: projects.get(child).copied(),
+            })
+        })
+        .collect();
+    resumable.sort_by_key(|agent| (depth(&parents, &agent.session), agent.session.clone()));
+    let resumable_sessions: HashSet<&str> = resumable
+        .iter()
+        .map(|agent| agent.session.as_str())
+        .collect();
+
+    let mut reconciliation = Reconciliation::default();
+    let mut live_sorted: Vec<&str> = live.iter().copied().collect();
+    live_sorted.sort();
+    for session in live_sorted {
+        match parents.get(session) {
+            None => {
+                memory::save_agent_parent_in(state_dir, session, manager_session);
+                reconciliation.adopted.push(session.to_string());
+            }
+            Some(parent)
+                if parent != manager_session
+                    && !live.contains(parent.as_str())
+                    && !resumable_sessions.contains(parent.as_str()) =>
+            {
+                memory::save_agent_parent_in(state_dir, session, manager_session);
+                reconciliation.reparented.push(session.to_string());
+            }
+            Some(_) => {}
+        }
+    }
+    reconciliation.resumable = resumable;
+    reconciliation
+}
+
+fn depth(parents: &std::collections::HashMap<String, String>, session: &str) -> usize {
+    let mut depth = 0;
+    let mut current = session;
+    while let Some(parent) = parents.get(current) {
+        depth += 1;
+        if depth > parents.len() {
+            break;
+        }
+        current = parent;
+    }
+    depth
+}
+
+/// A worker as written by `omar save`, enough to start it again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedAgent {
+    /// Short name, e.g. "api"
+    pub name: String,
+    /// Short name of the parent, or "ea" for the manager
+    pub parent: String,
+    #[serde(default)]
+    pub task: Option<String>,
+    /// `None` for workers spawned before launches were recorded
+    #[serde(default)]
+    pub launch: Option<AgentLaunch>,
+    #[serde(default)]
+    pub role: AgentRole,
+    /// Project it was working on
+    #[serde(default)]
+    pub project: Option<usize>,
+    /// It reported `[TASK COMPLETE]`, so restoring skips it
+    #[serde(default)]
+    pub finished: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedFleet {
+    pub ea: EaId,
+    pub saved_at: String,
+    /// Parents before children
+    pub agents: Vec<SavedAgent>,
+}
+
+impl SavedFleet {
+    /// Saved workers to start again: unfinished, then or since the save,
+    /// and not already running.
+    pub fn pending(&self, state_dir: &Path, prefix: &str, live: &[String]) -> Vec<&SavedAgent> {
+        let results = memory::load_agent_results_from(state_dir);
+        self.agents
+            .iter()
+            .filter(|agent| !agent.finished)
+            .filter(|agent| {
+                let session = naming::session_name(prefix, &agent.name);
+                !live.contains(&session)
+                    && !memory::has_completed_in(state_dir, &session)
+                    && !results
+                        .get(&session)
+                        .is_some_and(|result| result.kind == ResultKind::Complete)
+            })
+            .collect()
+    }
+
+    /// `agent` in the form the dashboard's resume prompt takes.
+    pub fn resumable(
+        &self,
+        agent: &SavedAgent,
+        prefix: &str,
+        manager_session: &str,
+    ) -> ResumableAgent {
+        ResumableAgent {
+            session: naming::session_name(prefix, &agent.name),
+            parent: naming::resolve_address(&agent.parent, manager_session, prefix),
+            task: agent.task.clone().unwrap_or_default(),
+            launch: agent.launch.clone(),
+            role: agent.role,
+            project: agent.project,
+        }
+    }
+}
+
+/// Collect the definitions of `live` workers and of those the state still
+/// remembers, from `state_dir`.
+pub fn save_fleet(
+    state_dir: &Path,
+    ea: EaId,
+    live: &[String],
+    prefix: &str,
+    manager_session: &str,
+) -> SavedFleet {
+    let parents = memory::load_agent_parents_from(state_dir);
+    let tasks = memory::load_worker_tasks_from(state_dir);
+    let results = memory::load_agent_results_from(state_dir);
+    let launches = memory::load_agent_launches_from(state_dir);
+    let roles = memory::load_agent_roles_from(state_dir);
+    let projects = memory::load_agent_projects_from(state_dir);
+    let mut sessions: Vec<&String> = live
+        .iter()
+        .chain(parents.keys())
+        .filter(|session| *session != manager_session && session.starts_with(prefix))
+        .collect();
+    sessions.sort_by_key(|session| (depth(&parents, session), session.to_string()));
+    sessions.dedup();
+    let agents = sessions
+        .into_iter()
+        .map(|session| SavedAgent {
+            name: naming::short_name(prefix, session).to_string(),
+            parent: naming::address(
+                parents.get(session).map_or(manager_session, String::as_str),
+                manager_session,
+                prefix,
+            ),
+            task: tasks.get(session).cloned(),
+            launch: launches.get(session).cloned(),
+            role: roles.get(session).copied().unwrap_or_default(),
+            project: projects.get(session).copied(),
+            finished: results
+                .get(session)
+                .is_some_and(|result| result.kind == ResultKind::Complete),
+        })
+        .collect();
+    SavedFleet {
+        ea,
+        saved_at: chrono::Utc::now().to_rfc3339(),
+        agents,
+    }
+}
+
+pub fn saved_path(omar_dir: &Path, ea: EaId) -> PathBuf {
+    omar_dir.join("saved").join(format!("ea{}.json", ea))
+}
+
+pub fn write_fleet(path: &Path, fleet: &SavedFleet) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(fleet)?)?;
+    Ok(())
+}
+
+pub fn load_fleet(path: &Path) -> Result<SavedFleet> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Cannot read {}: {}", path.display(), e))?;
+    Ok(serde_json::from_str(&text)?)
+}
+
+/// Forget resumable workers the user chose not to resume.
+pub fn dismiss(state_dir: &Path, agents: &[ResumableAgent]) {
+    for agent in agents {
+        memory::remove_agent_parent_in(state_dir, &agent.session);
+        memory::remove_agent_result_in(state_dir, &agent.session);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tmux::AgentResult;
+
+    #[test]
+    fn test_reconcile_adopts_orphans_and_finds_unfinished_work() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = dir.path();
+        let manager = "omar-agent-ea-0";
+        let s = |name: &str| format!("omar-agent-0-{}", name);
+
+        // pm and its worker died mid-task; done finished; killed lost its parent
+        memory::save_agent_parent_in(state, &s("pm"), manager);
+        memory::save_agent_parent_in(state, &s("worker"), &s("pm"));
+        memory::save_agent_parent_in(state, &s("done"), manager);
+        memory::save_agent_parent_in(state, &s("lost-parent"), &s("gone"));
+        for name in ["pm", "worker", "done", "killed"] {
+            memory::save_worker_task_in(state, &s(name), &format!("task for {}", name));
+        }
+        memory::AgentUpdate::new(&s("pm"))
+            .role(AgentRole::Pm)
+            .project(2)
+            .save_in(state)
+            .unwrap();
+        memory::save_agent_result_in(
+            state,
+            &s("done"),
+            &AgentResult {
+                kind: ResultKind::Complete,
+                detail: String::new(),
+                detected_at: String::new(),
+            },
+        );
+
+        let live = vec![manager.to_string(), s("orphan"), s("lost-parent")];
+        let reconciliation = reconcile(state, &live, manager);
+        assert_eq!(reconciliation.adopted, vec![s("orphan")]);
+        assert_eq!(reconciliation.reparented, vec![s("lost-parent")]);
+        let resumable: Vec<&str> = reconciliation
+            .resumable
+            .iter()
+            .map(|agent| agent.session.as_str())
+            .collect();
+        assert_eq!(resumable, vec![s("pm"), s("worker")]);
+        assert_eq!(reconciliation.resumable[1].task, "task for worker");
+        assert_eq!(reconciliation.resumable[0].role, AgentRole::Pm);
+        assert_eq!(reconciliation.resumable[0].project, Some(2));
+
+        let parents = memory::load_agent_parents_from(state);
+        assert_eq!(parents.get(&s("orphan")).map(String::as_str), Some(manager));
+
+        dismiss(state, &reconciliation.resumable);
+        assert!(reconcile(state, &live, manager).resumable.is_empty());
+    }
+
+    #[test]
+    fn test_saved_fleet_restores_unfinished_workers_parents_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = dir.path();
+        let manager = "omar-agent-ea-0";
+        let prefix = "omar-agent-0-";
+        let s = |name: &str| format!("{}{}", prefix, name);
+        memory::save_agent_parent_in(state, &s("worker"), &s("pm"));
+        memory::save_agent_parent_in(state, &s("pm"), manager);
+        memory::save_agent_parent_in(state, &s("done"), manager);
+        memory::save_worker_task_in(state, &s("worker"), "fix login");
+        memory::AgentUpdate::new(&s("pm"))
+            .role(AgentRole::Pm)
+            .project(3)
+            .save_in(state)
+            .unwrap();
+        memory::save_agent_result_in(
+            state,
+            &s("done"),
+            &crate::tmux::AgentResult {
+                kind: ResultKind::Complete,
+                detail: String::new(),
+                detected_at: String::new(),
+            },
+        );
+
+        let live = vec![manager.to_string(), s("pm"), s("scratch")];
+        let fleet = save_fleet(state, 0, &live, prefix, manager);
+        let names: Vec<&str> = fleet.agents.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["scratch", "done", "pm", "worker"]);
+        assert_eq!(fleet.agents[3].parent, "pm");
+        assert!(fleet.agents[1].finished);
+
+        let path = saved_path(dir.path(), 0);
+        write_fleet(&path, &fleet).unwrap();
+        let fleet = load_fleet(&path).unwrap();
+        let pending: Vec<&str> = fleet
+            .pending(state, prefix, &live)
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect();
+        assert_eq!(pending, vec!["worker"]);
+        let worker = fleet.resumable(&fleet.agents[3], prefix, manager);
+        assert_eq!(worker.session, s("worker"));
+        assert_eq!(worker.parent, s("pm"));
+        assert_eq!(worker.task, "fix login");
+        assert_eq!(worker.role, AgentRole::Worker);
+        let pm = fleet.resumable(&fleet.agents[2], prefix, manager);
+        assert_eq!(pm.role, AgentRole::Pm);
+        assert_eq!(pm.project, Some(3));
+
+        // Completed after the save: no longer offered
+        memory::save_agent_result_in(
+            state,
+            &s("worker"),
+            &crate::tmux::AgentResult {
+                kind: ResultKind::Complete,
+                detail: String::new(),
+                detected_at: String::new(),
+            },
+        );
+        assert!(fleet.pending(state, prefix, &live).is_empty());
+    }
+}
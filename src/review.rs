@@ -0,0 +1,240 @@
+//! Review stage — a reviewer agent signs off on a worker's result before
+//! the worker's parent hears about it
+//!
+//! With `orchestration.review = true`, a worker's `[TASK COMPLETE]` does not
+//! go straight to its parent. The dashboard spawns a reviewer in the
+//! worker's directory with the task, the worker's summary, its diff stat,
+//! and its transcript path. A review whose first summary bullet is
+//! `- APPROVED` finishes the task; anything else is sent back to the worker,
+//! whose next `[TASK COMPLETE]` starts another round. Open reviews are kept in
+//! `<state_dir>/reviews.json`, keyed by worker session.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::tmux::{AgentResult, ResultKind};
+
+/// A worker result waiting on review.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Review {
+    /// Reviewer session for the round in progress; `None` while the worker
+    /// addresses feedback.
+    #[serde(default)]
+    pub reviewer: Option<String>,
+    /// Rounds started so far, including the current one.
+    pub round: u32,
+    /// The worker result under review, reported to the parent once approved.
+    pub result: AgentResult,
+}
+
+/// What a reviewer's own result means for the worker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Approved,
+    ChangesRequested(String),
+    /// The reviewer got stuck; the worker's result goes through unreviewed.
+    Failed(String),
+}
+
+pub fn verdict(result: &AgentResult) -> Verdict {
+    if result.kind != ResultKind::Complete {
+        return Verdict::Failed(result.detail.clone());
+    }
+    let detail = result.detail.trim();
+    let first_word = detail
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .find(|word| !word.is_empty())
+        .unwrap_or("");
+    if first_word.eq_ignore_ascii_case("approved") {
+        Verdict::Approved
+    } else {
+        Verdict::ChangesRequested(detail.to_string())
+    }
+}
+
+/// Task for the reviewer of `worker`'s latest result.
+pub fn reviewer_task(
+    worker: &str,
+    task: &str,
+    result: &AgentResult,
+    diff_stat: &str,
+    transcript: &Path,
+) -> String {
+    let diff_stat = if diff_stat.trim().is_empty() {
+        "(no uncommitted changes)"
+    } else {
+        diff_stat.trim()
+    };
+    format!(
+        "Review the work of agent {worker}. Its task was: {task}\n\
+         It reported:\n{summary}\n\
+         Changed files (git diff --stat, run `git diff` here for the full diff):\n{diff_stat}\n\
+         Its transcript is at {transcript}.\n\
+         Check that the change does what the task asked, is correct, and is tested. \
+         Do not edit files. When done, print [TASK COMPLETE] on its own line followed by \
+         `- ` bullet lines. The first bullet is `- APPROVED` if the work can ship, or \
+         `- CHANGES REQUESTED` followed by one bullet per fix needed.",
+        worker = worker,
+        task = task,
+        summary = result.detail.trim(),
+        diff_stat = diff_stat,
+        transcript = transcript.display(),
+    )
+}
+
+/// Message that hands a reviewer's feedback back to the worker. The
+/// round bullet keeps the next summary from matching the last one, which
+/// would otherwise be dropped as already reported.
+pub fn feedback_message(reviewer: &str, round: u32, feedback: &str) -> String {
+    format!(
+        "[REVIEW] {} requested changes:\n{}\nAddress them, then print [TASK COMPLETE] again \
+         with an updated summary whose first bullet is `- Review round {}: <what you changed>`.",
+        reviewer, feedback, round
+    )
+}
+
+/// `git diff --stat HEAD` in `workdir`; empty outside a repository.
+pub fn diff_stat(workdir: &str) -> String {
+    Command::new("git")
+        .args(["-C", workdir, "diff", "--stat", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+        .unwrap_or_default()
+}
+
+/// Open reviews, keyed by worker session. Only the dashboard writes them,
+/// apart from renames.
+pub fn load_reviews_from(state_dir: &Path) -> HashMap<String, Review> {
+    fs::read_to_string(state_dir.join("reviews.json"))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_reviews_in(state_dir: &Path, reviews: &HashMap<String, Review>) {
+    crate::memory::write_json(&state_dir.join("reviews.json"), reviews);
+}
+
+/// Move the review of worker `old` to `new`, and repoint reviews that
+/// `old` is the reviewer of.
+pub fn rename_agent_in(state_dir: &Path, old: &str, new: &str) {
+    let mut reviews = load_reviews_from(state_dir);
+    let mut renamed = false;
+    if let Some(review) = reviews.remove(old) {
+        reviews.insert(new.to_string(), review);
+        renamed = true;
+    }
+    for review in reviews.values_mut() {
+        if review.reviewer.as_deref() == Some(old) {
+            review.reviewer = Some(new.to_string());
+            renamed = true;
+        }
+    }
+    if renamed {
+        save_reviews_in(state_dir, &reviews);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(kind: ResultKind, detail: &str) -> AgentResult {
+        AgentResult {
+            kind,
+            detail: detail.to_string(),
+            detected_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_verdict_and_review_round_trip() {
+        assert_eq!(
+            verdict(&result(ResultKind::Complete, "**Approved** - looks good")),
+            Verdict::Approved
+        );
+        assert_eq!(
+            verdict(&result(
+                ResultKind::Complete,
+                "CHANGES REQUESTED\n- add a test"
+            )),
+            Verdict::ChangesRequested("CHANGES REQUESTED\n- add a test".to_string())
+        );
+        assert_eq!(
+            verdict(&result(ResultKind::Blocked, "no repo access")),
+            Verdict::Failed("no repo access".to_string())
+        );
+
+        let task = reviewer_task(
+            "api",
+            "add retries",
+            &result(ResultKind::Complete, "- retries added\n"),
+            "",
+            Path::new("/t/api.log"),
+        );
+        assert!(task.contains("Its task was: add retries"));
+        assert!(task.contains("(no uncommitted changes)"));
+        assert!(task.contains("/t/api.log"));
+
+        let dir = tempfile::tempdir().unwrap();
+        assert!(load_reviews_from(dir.path()).is_empty());
+        let mut reviews = HashMap::new();
+        reviews.insert(
+            "omar-agent-0-api".to_string(),
+            Review {
+                reviewer: Some("omar-agent-0-api-review-1".to_string()),
+                round: 1,
+                result: result(ResultKind::Complete, "done"),
+            },
+        );
+        save_reviews_in(dir.path(), &reviews);
+        assert_eq!(load_reviews_from(dir.path()), reviews);
+
+        crate::memory::AgentUpdate::new("omar-agent-0-auth")
+            .rename_from_in(dir.path(), "omar-agent-0-api")
+            .unwrap();
+        crate::memory::AgentUpdate::new("omar-agent-0-auth-review-1")
+            .rename_from_in(dir.path(), "omar-agent-0-api-review-1")
+            .unwrap();
+        let reviews = load_reviews_from(dir.path());
+        assert!(!reviews.contains_key("omar-agent-0-api"));
+        assert_eq!(
+            reviews["omar-agent-0-auth"].reviewer.as_deref(),
+            Some("omar-agent-0-auth-review-1")
+        );
+    }
+
+    #[test]
+    fn test_verdict_from_reviewer_pane() {
+        let pane = |bullets: &str| {
+            format!(
+                "⏺ Read the diff and ran the tests.\n\n⏺ [TASK COMPLETE]\n{}\n\n\
+                 ╭──────────────╮\n│ >            │\n╰──────────────╯\n  ? for shortcuts\n",
+                bullets
+            )
+        };
+        let approved =
+            crate::tmux::detect_result(&pane("- APPROVED\n- tests cover retries")).unwrap();
+        assert_eq!(verdict(&approved), Verdict::Approved);
+
+        let changes = crate::tmux::detect_result(&pane(
+            "  - CHANGES REQUESTED\n  - add a test for the timeout path",
+        ))
+        .unwrap();
+        assert_eq!(
+            verdict(&changes),
+            Verdict::ChangesRequested(
+                "CHANGES REQUESTED; add a test for the timeout path".to_string()
+            )
+        );
+
+        let message = feedback_message("api-review-1", 1, &changes.detail);
+        assert!(message.contains("`- Review round 1: <what you changed>`"));
+    }
+}
@@ -0,0 +1,263 @@
+//! Run reports — a markdown summary of each finished plan
+//!
+//! The dashboard tracks the active EA's workers as a run. Once every one
+//! has completed or been killed, it writes `~/.omar/runs/<timestamp>.md`
+//! with each worker's task, duration, health timeline, priced cost, and
+//! final output. `omar runs` and `GET /api/runs` list past reports.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::ea::EaId;
+use crate::metrics::format_duration;
+use crate::tmux::HealthTransition;
+
+const SUMMARY_PREFIX: &str = "**Summary:** ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Complete,
+    Killed,
+}
+
+impl Outcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Outcome::Complete => "complete",
+            Outcome::Killed => "killed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunWorker {
+    pub task: String,
+    pub started_at: i64,
+    /// When it completed or was seen gone; `None` while it is working.
+    pub ended_at: Option<i64>,
+    pub outcome: Option<Outcome>,
+    /// `[TASK COMPLETE]` summary.
+    pub output: String,
+    pub cost_usd: Option<f64>,
+    pub timeline: Vec<HealthTransition>,
+}
+
+/// Workers of one EA observed since the last report.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunTracker {
+    pub ea: EaId,
+    pub workers: BTreeMap<String, RunWorker>,
+    /// Live workers left out: already reported, or complete when first seen
+    pub settled: HashSet<String>,
+}
+
+impl RunTracker {
+    /// Every worker has completed or been killed.
+    pub fn is_finished(&self) -> bool {
+        !self.workers.is_empty() && self.workers.values().all(|w| w.outcome.is_some())
+    }
+}
+
+/// A past report, as listed by `omar runs` and `GET /api/runs`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunSummary {
+    pub id: String,
+    pub path: String,
+    pub summary: String,
+}
+
+pub fn runs_dir(omar_dir: &Path) -> PathBuf {
+    omar_dir.join("runs")
+}
+
+/// `running 0s → idle 4m → finished 12m`, offsets from `started_at`.
+fn render_timeline(timeline: &[HealthTransition], started_at: i64) -> String {
+    timeline
+        .iter()
+        .map(|t| {
+            let offset = (t.at - started_at).max(0) as u64;
+            format!("{} {}", t.state.as_str(), format_duration(offset))
+        })
+        .collect::<Vec<_>>()
+        .join(" → ")
+}
+
+pub fn render_report(run: &RunTracker, ea_name: &str, ended_at: i64) -> String {
+    let started_at = run
+        .workers
+        .values()
+        .map(|w| w.started_at)
+        .min()
+        .unwrap_or(ended_at);
+    let complete = run
+        .workers
+        .values()
+        .filter(|w| w.outcome == Some(Outcome::Complete))
+        .count();
+    let costs: Vec<f64> = run.workers.values().filter_map(|w| w.cost_usd).collect();
+    let mut summary = format!(
+        "{} workers: {} complete, {} killed · {}",
+        run.workers.len(),
+        complete,
+        run.workers.len() - complete,
+        format_duration((ended_at - started_at).max(0) as u64)
+    );
+    if !costs.is_empty() {
+        summary.push_str(&format!(" · ~${:.2}", costs.iter().sum::<f64>()));
+    }
+
+    let mut out = format!(
+        "# Run report: {}\n\n{}{}\n\n",
+        ea_name, SUMMARY_PREFIX, summary
+    );
+    out.push_str(&format!(
+        "Started {}, ended {}.\n",
+        secs_rfc3339(started_at),
+        secs_rfc3339(ended_at)
+    ));
+    for (name, worker) in &run.workers {
+        let ended = worker.ended_at.unwrap_or(ended_at);
+        out.push_str(&format!("\n## {}\n\n", name));
+        out.push_str(&format!(
+            "- Outcome: {}\n",
+            worker.outcome.map_or("unknown", Outcome::as_str)
+        ));
+        out.push_str(&format!(
+            "- Duration: {}\n",
+            format_duration((ended - worker.started_at).max(0) as u64)
+        ));
+        if let Some(cost) = worker.cost_usd {
+            out.push_str(&format!("- Cost: ~${:.2}\n", cost));
+        }
+        if !worker.task.is_empty() {
+            out.push_str(&format!("- Task: {}\n", worker.task.trim()));
+        }
+        if !worker.timeline.is_empty() {
+            out.push_str(&format!(
+                "- Health: {}\n",
+                render_timeline(&worker.timeline, worker.started_at)
+            ));
+        }
+        if !worker.output.trim().is_empty() {
+            out.push_str("\n```\n");
+            out.push_str(worker.output.trim());
+            out.push_str("\n```\n");
+        }
+    }
+    out
+}
+
+fn secs_rfc3339(secs: i64) -> String {
+    chrono::DateTime::from_timestamp(secs, 0)
+        .map(|at| at.to_rfc3339())
+        .unwrap_or_default()
+}
+
+/// Write the report for a finished run. Returns its path.
+pub fn write_report(
+    omar_dir: &Path,
+    run: &RunTracker,
+    ea_name: &str,
+    ended_at: i64,
+) -> Result<PathBuf> {
+    let dir = runs_dir(omar_dir);
+    fs::create_dir_all(&dir)?;
+    let stamp = chrono::DateTime::from_timestamp(ended_at, 0)
+        .unwrap_or_default()
+        .format("%Y%m%dT%H%M%SZ");
+    let path = dir.join(format!("{}.md", stamp));
+    fs::write(&path, render_report(run, ea_name, ended_at))?;
+    Ok(path)
+}
+
+/// Past reports, newest first.
+pub fn list_runs(omar_dir: &Path) -> Vec<RunSummary> {
+    let Ok(entries) = fs::read_dir(runs_dir(omar_dir)) else {
+        return Vec::new();
+    };
+    let mut runs: Vec<RunSummary> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|path| {
+            let text = fs::read_to_string(&path).ok()?;
+            let summary = text
+                .lines()
+                .find_map(|line| line.strip_prefix(SUMMARY_PREFIX))
+                .unwrap_or_default()
+                .to_string();
+            Some(RunSummary {
+                id: path.file_stem()?.to_string_lossy().into_owned(),
+                path: path.display().to_string(),
+                summary,
+            })
+        })
+        .collect();
+    runs.sort_by(|a, b| b.id.cmp(&a.id));
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tmux::HealthState;
+
+    #[test]
+    fn test_report_written_and_listed() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut run = RunTracker::default();
+        run.workers.insert(
+            "omar-agent-0-api".to_string(),
+            RunWorker {
+                task: "Build the API".to_string(),
+                started_at: 1_000,
+                ended_at: Some(1_600),
+                outcome: Some(Outcome::Complete),
+                output: "Added /users".to_string(),
+                cost_usd: Some(0.5),
+                timeline: vec![
+                    HealthTransition {
+                        at: 1_000,
+                        state: HealthState::Running,
+                    },
+                    HealthTransition {
+                        at: 1_300,
+                        state: HealthState::Finished,
+                    },
+                ],
+            },
+        );
+        run.workers.insert(
+            "omar-agent-0-db".to_string(),
+            RunWorker {
+                task: String::new(),
+                started_at: 1_100,
+                ended_at: None,
+                outcome: None,
+                output: String::new(),
+                cost_usd: None,
+                timeline: Vec::new(),
+            },
+        );
+        assert!(!run.is_finished());
+        run.workers.get_mut("omar-agent-0-db").unwrap().outcome = Some(Outcome::Killed);
+        assert!(run.is_finished());
+
+        let report = render_report(&run, "default", 1_900);
+        assert!(report.contains("**Summary:** 2 workers: 1 complete, 1 killed · 15m · ~$0.50"));
+        assert!(report.contains("- Health: running 0s → finished 5m"));
+        assert!(report.contains("- Duration: 10m"));
+        assert!(report.contains("Added /users"));
+
+        write_report(dir.path(), &run, "default", 1_900).unwrap();
+        write_report(dir.path(), &run, "default", 5_000).unwrap();
+        let runs = list_runs(dir.path());
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].id, "19700101T012320Z");
+        assert!(runs[1].summary.starts_with("2 workers"));
+    }
+}
@@ -0,0 +1,395 @@
+//! bubblewrap and firejail providers: read-only host root, private `/tmp`,
+//! writable workdir.
+
+use std::path::{Path, PathBuf};
+
+use super::{shell_single_quote, CredentialMounts, GpuAccess, NetworkMode, SandboxProvider};
+
+/// Host entries under `/tmp` a sandbox still sees through its private
+/// `/tmp`: the socket of the tmux server the agent's MCP server drives, and
+/// omar's private temp dir holding rendered prompts and secrets env files.
+/// Nothing else in the host's `/tmp` (other tmux servers, X11 or other
+/// sockets) is reachable.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostTmp {
+    pub tmux_socket: Option<PathBuf>,
+    pub private_dir: Option<PathBuf>,
+}
+
+impl HostTmp {
+    /// The entries of this process that live under `/tmp`; elsewhere the
+    /// read-only root already shows them.
+    pub fn resolve() -> Self {
+        let under_tmp = |path: PathBuf| Some(path).filter(|path| path.starts_with("/tmp"));
+        #[cfg(unix)]
+        let tmux_socket = under_tmp(crate::tmux::tmux_socket_path()).filter(|path| path.exists());
+        #[cfg(not(unix))]
+        let tmux_socket = None;
+        Self {
+            tmux_socket,
+            private_dir: crate::paths::private_temp_dir().ok().and_then(under_tmp),
+        }
+    }
+}
+
+/// `bwrap` with the host root mounted read-only and an empty tmpfs on
+/// `/tmp`, into which only the [`HostTmp`] entries are bound.
+pub struct BwrapProvider {
+    network: NetworkMode,
+    writable_paths: Vec<PathBuf>,
+    gpus: GpuAccess,
+    credentials: CredentialMounts,
+    host_tmp: HostTmp,
+}
+
+impl BwrapProvider {
+    pub fn new(network: NetworkMode, writable_paths: Vec<PathBuf>) -> Self {
+        Self {
+            network,
+            writable_paths,
+            gpus: GpuAccess::default(),
+            credentials: CredentialMounts::default(),
+            host_tmp: HostTmp::default(),
+        }
+    }
+
+    /// Bind these GPU device nodes into the sandbox's `/dev`.
+    pub fn with_gpus(mut self, gpus: GpuAccess) -> Self {
+        self.gpus = gpus;
+        self
+    }
+
+    /// Mask or replace host credentials per `sandbox.credentials`.
+    pub fn with_credentials(mut self, credentials: CredentialMounts) -> Self {
+        self.credentials = credentials;
+        self
+    }
+
+    /// Bind these host entries into the private `/tmp`.
+    pub fn with_host_tmp(mut self, host_tmp: HostTmp) -> Self {
+        self.host_tmp = host_tmp;
+        self
+    }
+}
+
+impl SandboxProvider for BwrapProvider {
+    fn name(&self) -> &'static str {
+        "bwrap"
+    }
+
+    fn security_flags(&self) -> Vec<String> {
+        let mut flags = vec![
+            "--die-with-parent".to_string(),
+            "--ro-bind / /".to_string(),
+            "--tmpfs /tmp".to_string(),
+        ];
+        if self.network.unshares_net() {
+            flags.push("--unshare-net".to_string());
+        }
+        flags
+    }
+
+    fn wrap_command(&self, command: &str, workdir: &Path) -> String {
+        let mut args = vec![
+            "bwrap".to_string(),
+            "--die-with-parent".to_string(),
+            "--ro-bind / /".to_string(),
+            "--dev /dev".to_string(),
+        ];
+        for device in &self.gpus.expose {
+            let device = shell_single_quote(&device.display().to_string());
+            args.push(format!("--dev-bind {} {}", device, device));
+        }
+        args.push("--proc /proc".to_string());
+        args.push("--tmpfs /tmp".to_string());
+        if let Some(socket) = &self.host_tmp.tmux_socket {
+            // tmux refuses a socket dir that others can read
+            if let Some(dir) = socket.parent() {
+                let dir = shell_single_quote(&dir.display().to_string());
+                args.push(format!("--perms 0700 --dir {}", dir));
+            }
+            let socket = shell_single_quote(&socket.display().to_string());
+            args.push(format!("--bind {} {}", socket, socket));
+        }
+        if let Some(dir) = &self.host_tmp.private_dir {
+            let dir = shell_single_quote(&dir.display().to_string());
+            args.push(format!("--bind {} {}", dir, dir));
+        }
+        for path in existing(&self.writable_paths) {
+            let path = shell_single_quote(&path.display().to_string());
+            args.push(format!("--bind {} {}", path, path));
+        }
+        // After the writable binds so these win over a credential dir
+        // listed in `writable_paths`
+        for path in &self.credentials.hide {
+            let quoted = shell_single_quote(&path.display().to_string());
+            if path.is_dir() {
+                args.push(format!("--tmpfs {}", quoted));
+            } else {
+                args.push(format!("--ro-bind /dev/null {}", quoted));
+            }
+        }
+        for (source, host) in &self.credentials.replace {
+            args.push(format!(
+                "--bind {} {}",
+                shell_single_quote(&source.display().to_string()),
+                shell_single_quote(&host.display().to_string())
+            ));
+        }
+        let workdir = shell_single_quote(&workdir.display().to_string());
+        args.push(format!("--bind {} {}", workdir, workdir));
+        if self.network.unshares_net() {
+            args.push("--unshare-net".to_string());
+        }
+        args.push(format!("--chdir {}", workdir));
+        args.push(format!("-- sh -c {}", shell_single_quote(command)));
+        args.join(" ")
+    }
+}
+
+/// `firejail` equivalent of [`BwrapProvider`] for hosts where unprivileged
+/// user namespaces are disabled. Whitelisting anything under `/tmp` gives
+/// the sandbox a tmpfs `/tmp` holding just those entries; with nothing to
+/// whitelist, `--private-tmp` does the same.
+pub struct FirejailProvider {
+    network: NetworkMode,
+    writable_paths: Vec<PathBuf>,
+    gpus: GpuAccess,
+    credentials: CredentialMounts,
+    host_tmp: HostTmp,
+}
+
+impl FirejailProvider {
+    pub fn new(network: NetworkMode, writable_paths: Vec<PathBuf>) -> Self {
+        Self {
+            network,
+            writable_paths,
+            gpus: GpuAccess::default(),
+            credentials: CredentialMounts::default(),
+            host_tmp: HostTmp::default(),
+        }
+    }
+
+    /// Blacklist the GPU device nodes `gpus` hides.
+    pub fn with_gpus(mut self, gpus: GpuAccess) -> Self {
+        self.gpus = gpus;
+        self
+    }
+
+    /// Blacklist the host credentials `credentials` hides. firejail cannot
+    /// mount replacements without root, so `replace` must be empty.
+    pub fn with_credentials(mut self, credentials: CredentialMounts) -> Self {
+        self.credentials = credentials;
+        self
+    }
+
+    /// Whitelist these host entries in the private `/tmp`.
+    pub fn with_host_tmp(mut self, host_tmp: HostTmp) -> Self {
+        self.host_tmp = host_tmp;
+        self
+    }
+}
+
+impl SandboxProvider for FirejailProvider {
+    fn name(&self) -> &'static str {
+        "firejail"
+    }
+
+    fn security_flags(&self) -> Vec<String> {
+        let mut flags = vec!["--read-only=/".to_string()];
+        if self.network.unshares_net() {
+            flags.push("--net=none".to_string());
+        }
+        flags
+    }
+
+    fn wrap_command(&self, command: &str, workdir: &Path) -> String {
+        let mut args = vec![
+            "firejail".to_string(),
+            "--quiet".to_string(),
+            "--noprofile".to_string(),
+            "--read-only=/".to_string(),
+        ];
+        let writable: Vec<&Path> = existing(&self.writable_paths)
+            .map(PathBuf::as_path)
+            .chain([workdir])
+            .collect();
+        let tmp_entries: Vec<&Path> = [&self.host_tmp.tmux_socket, &self.host_tmp.private_dir]
+            .into_iter()
+            .flatten()
+            .map(PathBuf::as_path)
+            .chain(writable.iter().copied())
+            .filter(|path| path.starts_with("/tmp"))
+            .collect();
+        if tmp_entries.is_empty() {
+            args.push("--private-tmp".to_string());
+        }
+        for path in tmp_entries {
+            args.push(shell_single_quote(&format!(
+                "--whitelist={}",
+                path.display()
+            )));
+        }
+        for path in writable {
+            args.push(shell_single_quote(&format!(
+                "--read-write={}",
+                path.display()
+            )));
+        }
+        for path in self.gpus.hide.iter().chain(&self.credentials.hide) {
+            args.push(shell_single_quote(&format!(
+                "--blacklist={}",
+                path.display()
+            )));
+        }
+        if self.network.unshares_net() {
+            args.push("--net=none".to_string());
+        }
+        args.push(format!("-- sh -c {}", shell_single_quote(command)));
+        args.join(" ")
+    }
+}
+
+/// Binding a missing path fails the whole launch, so skip those.
+fn existing(paths: &[PathBuf]) -> impl Iterator<Item = &PathBuf> {
+    paths.iter().filter(|path| path.exists())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bwrap_wraps_with_read_only_root_and_workdir() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("cache");
+        std::fs::create_dir(&cache).unwrap();
+        let missing = dir.path().join("missing");
+        let provider = BwrapProvider::new(NetworkMode::Host, vec![cache.clone(), missing.clone()]);
+
+        let cmd = provider.wrap_command("claude --print 'hi'", Path::new("/work/repo"));
+
+        assert!(cmd.starts_with("bwrap --die-with-parent --ro-bind / / "));
+        assert!(cmd.contains(&format!(
+            "--bind '{}' '{}'",
+            cache.display(),
+            cache.display()
+        )));
+        assert!(!cmd.contains(&missing.display().to_string()));
+        assert!(cmd.contains("--bind '/work/repo' '/work/repo' --chdir '/work/repo'"));
+        assert!(!cmd.contains("--unshare-net"));
+        assert!(cmd.ends_with("-- sh -c 'claude --print '\\''hi'\\'''"));
+    }
+
+    #[test]
+    fn test_bwrap_network_none_unshares_net() {
+        let provider = BwrapProvider::new(NetworkMode::None, Vec::new());
+        let cmd = provider.wrap_command("codex", Path::new("/w"));
+        assert!(cmd.contains("--unshare-net"));
+        let provider = BwrapProvider::new(NetworkMode::Proxy, Vec::new());
+        assert!(provider
+            .security_flags()
+            .contains(&"--unshare-net".to_string()));
+        let provider = FirejailProvider::new(NetworkMode::Proxy, Vec::new());
+        assert!(provider
+            .wrap_command("codex", Path::new("/w"))
+            .contains("--net=none"));
+    }
+
+    #[test]
+    fn test_gpu_devices_are_bound_or_blacklisted() {
+        let gpus = GpuAccess {
+            expose: vec![
+                PathBuf::from("/dev/nvidiactl"),
+                PathBuf::from("/dev/nvidia0"),
+            ],
+            hide: vec![PathBuf::from("/dev/nvidia1")],
+        };
+        let cmd = BwrapProvider::new(NetworkMode::Host, Vec::new())
+            .with_gpus(gpus.clone())
+            .wrap_command("ollama serve", Path::new("/w"));
+        assert!(cmd.contains(
+            "--dev /dev --dev-bind '/dev/nvidiactl' '/dev/nvidiactl' \
+             --dev-bind '/dev/nvidia0' '/dev/nvidia0' --proc /proc"
+        ));
+        assert!(!cmd.contains("nvidia1"));
+
+        let cmd = FirejailProvider::new(NetworkMode::Host, Vec::new())
+            .with_gpus(gpus)
+            .wrap_command("ollama serve", Path::new("/w"));
+        assert!(cmd.contains("'--blacklist=/dev/nvidia1'"));
+        assert!(!cmd.contains("nvidia0"));
+    }
+
+    #[test]
+    fn test_credentials_are_masked_or_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let claude = dir.path().join(".claude");
+        std::fs::create_dir(&claude).unwrap();
+        let claude_json = dir.path().join(".claude.json");
+        let replacement = dir.path().join("alt/.config/opencode");
+        let opencode = dir.path().join(".config/opencode");
+        let credentials = CredentialMounts {
+            hide: vec![claude.clone(), claude_json.clone()],
+            replace: vec![(replacement.clone(), opencode.clone())],
+        };
+
+        let cmd = BwrapProvider::new(NetworkMode::Host, vec![claude.clone()])
+            .with_credentials(credentials.clone())
+            .wrap_command("claude", Path::new("/w"));
+        let bind = format!("--bind '{}' '{}'", claude.display(), claude.display());
+        let tmpfs = format!("--tmpfs '{}'", claude.display());
+        assert!(cmd.find(&bind).unwrap() < cmd.find(&tmpfs).unwrap());
+        assert!(cmd.contains(&format!("--ro-bind /dev/null '{}'", claude_json.display())));
+        assert!(cmd.contains(&format!(
+            "--bind '{}' '{}'",
+            replacement.display(),
+            opencode.display()
+        )));
+
+        let cmd = FirejailProvider::new(NetworkMode::Host, Vec::new())
+            .with_credentials(credentials)
+            .wrap_command("claude", Path::new("/w"));
+        assert!(cmd.contains(&format!("'--blacklist={}'", claude.display())));
+        assert!(cmd.contains(&format!("'--blacklist={}'", claude_json.display())));
+    }
+
+    #[test]
+    fn test_firejail_wraps_with_read_only_root_and_workdir() {
+        let provider = FirejailProvider::new(NetworkMode::None, Vec::new());
+        let cmd = provider.wrap_command("opencode", Path::new("/work/repo"));
+        assert!(cmd.starts_with("firejail --quiet --noprofile --read-only=/ --private-tmp "));
+        assert!(cmd.contains("'--read-write=/work/repo'"));
+        assert!(!cmd.contains("--read-write=/tmp'"));
+        assert!(cmd.contains("--net=none"));
+        assert!(cmd.ends_with("-- sh -c 'opencode'"));
+    }
+
+    #[test]
+    fn test_tmp_is_private_except_the_tmux_socket_and_temp_dir() {
+        let host_tmp = HostTmp {
+            tmux_socket: Some(PathBuf::from("/tmp/tmux-1000/default")),
+            private_dir: Some(PathBuf::from("/tmp/omar-dev")),
+        };
+        let cmd = BwrapProvider::new(NetworkMode::Host, Vec::new())
+            .with_host_tmp(host_tmp.clone())
+            .wrap_command("claude", Path::new("/tmp/repo"));
+        assert!(!cmd.contains("--bind /tmp /tmp"));
+        let tmpfs = cmd.find("--tmpfs /tmp ").unwrap();
+        assert!(cmd[tmpfs..].contains(
+            "--tmpfs /tmp --perms 0700 --dir '/tmp/tmux-1000' \
+             --bind '/tmp/tmux-1000/default' '/tmp/tmux-1000/default' \
+             --bind '/tmp/omar-dev' '/tmp/omar-dev'"
+        ));
+        assert!(cmd.find("--bind '/tmp/repo' '/tmp/repo'").unwrap() > tmpfs);
+
+        let cmd = FirejailProvider::new(NetworkMode::Host, Vec::new())
+            .with_host_tmp(host_tmp)
+            .wrap_command("claude", Path::new("/tmp/repo"));
+        assert!(!cmd.contains("--private-tmp"));
+        assert!(cmd.contains(
+            "'--whitelist=/tmp/tmux-1000/default' '--whitelist=/tmp/omar-dev' \
+             '--whitelist=/tmp/repo' '--read-write=/tmp/repo'"
+        ));
+    }
+}
@@ -0,0 +1,108 @@
+//! `sandbox.credentials` — whether sandboxed workers see the host's agent
+//! credentials
+//!
+//! `"on"` shares them: the read-only root exposes them and the default
+//! `writable_paths` mount them writable. `"off"` hides them behind an empty
+//! tmpfs (bwrap) or a blacklist (firejail). Any other value is a directory
+//! laid out like `$HOME` whose `.claude`, `.config/opencode`, ... are
+//! mounted over the host's (bwrap only); host credentials without a
+//! counterpart there are hidden.
+
+use std::path::{Path, PathBuf};
+
+/// Backend credential and login state, relative to `$HOME`.
+pub const CREDENTIAL_PATHS: &[&str] = &[
+    ".claude",
+    ".claude.json",
+    ".codex",
+    ".config/opencode",
+    ".local/share/opencode",
+    ".cursor",
+    ".gemini",
+];
+
+/// Credential paths to hide from, or mount over in, the sandbox.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CredentialMounts {
+    pub hide: Vec<PathBuf>,
+    /// (source, host path it replaces)
+    pub replace: Vec<(PathBuf, PathBuf)>,
+}
+
+impl CredentialMounts {
+    /// Resolve `sandbox.credentials` against the host credentials in `home`.
+    /// `None` means they are shared.
+    pub fn resolve(credentials: &str, home: &Path) -> Option<Self> {
+        let source = match credentials.trim() {
+            "" | "on" => return None,
+            "off" => None,
+            dir => Some(crate::config::expand_tilde(dir)),
+        };
+        let mut mounts = Self::default();
+        for relative in CREDENTIAL_PATHS {
+            let host = home.join(relative);
+            if !host.exists() {
+                continue;
+            }
+            match source.as_ref().map(|dir| dir.join(relative)) {
+                Some(replacement) if replacement.exists() => {
+                    mounts.replace.push((replacement, host))
+                }
+                _ => mounts.hide.push(host),
+            }
+        }
+        Some(mounts)
+    }
+
+    /// Whether `path` is, or is inside, one of the host credential paths.
+    pub fn covers(path: &Path, home: &Path) -> bool {
+        CREDENTIAL_PATHS
+            .iter()
+            .any(|relative| path.starts_with(home.join(relative)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_hides_or_replaces_host_credentials() {
+        let home = tempfile::tempdir().unwrap();
+        let home = home.path();
+        std::fs::create_dir_all(home.join(".claude")).unwrap();
+        std::fs::write(home.join(".claude.json"), "{}").unwrap();
+        std::fs::create_dir_all(home.join(".config/opencode")).unwrap();
+
+        assert_eq!(CredentialMounts::resolve("on", home), None);
+        let off = CredentialMounts::resolve("off", home).unwrap();
+        assert_eq!(
+            off.hide,
+            vec![
+                home.join(".claude"),
+                home.join(".claude.json"),
+                home.join(".config/opencode")
+            ]
+        );
+        assert!(off.replace.is_empty());
+
+        let sandbox_home = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(sandbox_home.path().join(".claude")).unwrap();
+        let custom =
+            CredentialMounts::resolve(&sandbox_home.path().display().to_string(), home).unwrap();
+        assert_eq!(
+            custom.replace,
+            vec![(sandbox_home.path().join(".claude"), home.join(".claude"))]
+        );
+        assert_eq!(
+            custom.hide,
+            vec![home.join(".claude.json"), home.join(".config/opencode")]
+        );
+
+        assert!(CredentialMounts::covers(
+            &home.join(".claude/projects"),
+            home
+        ));
+        assert!(!CredentialMounts::covers(&home.join(".omar"), home));
+    }
+}
@@ -0,0 +1,158 @@
+//! `sandbox.gpus` — which GPU device nodes a sandboxed worker can open
+//!
+//! `"all"` exposes every NVIDIA, DRI, and KFD node; a list such as `"0,2"`
+//! exposes only those GPUs plus the shared control nodes. bwrap starts from
+//! an empty `/dev`, so the exposed nodes are bound in; firejail keeps the
+//! host `/dev`, so GPUs left out of a list are blacklisted instead.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+/// Nodes every GPU shares (driver control, unified memory, ROCm compute).
+const SHARED_NODES: &[&str] = &[
+    "nvidiactl",
+    "nvidia-uvm",
+    "nvidia-uvm-tools",
+    "nvidia-modeset",
+    "kfd",
+];
+
+/// Device nodes to expose to, and hide from, the sandbox.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GpuAccess {
+    pub expose: Vec<PathBuf>,
+    pub hide: Vec<PathBuf>,
+}
+
+impl GpuAccess {
+    /// Resolve `sandbox.gpus` against the device nodes present in `dev`.
+    pub fn resolve(gpus: Option<&str>, dev: &Path) -> Result<Self> {
+        let spec = gpus.map(str::trim).unwrap_or("");
+        if spec.is_empty() || spec == "none" {
+            return Ok(Self::default());
+        }
+        let selected: Option<Vec<u32>> = if spec == "all" {
+            None
+        } else {
+            let indices = spec
+                .split(',')
+                .map(|part| part.trim().parse::<u32>())
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|_| {
+                    anyhow!(
+                        "sandbox.gpus must be \"all\", \"none\", or GPU indices like \"0,1\", got '{}'",
+                        spec
+                    )
+                })?;
+            Some(indices)
+        };
+
+        let mut access = Self::default();
+        for name in SHARED_NODES {
+            let path = dev.join(name);
+            if path.exists() {
+                access.expose.push(path);
+            }
+        }
+        for (path, index) in per_gpu_nodes(dev) {
+            match &selected {
+                Some(indices) if !indices.contains(&index) => access.hide.push(path),
+                _ => access.expose.push(path),
+            }
+        }
+        Ok(access)
+    }
+}
+
+/// Per-GPU nodes and their GPU index: `nvidiaN`, `dri/cardN`, and
+/// `dri/renderD(128+N)`, sorted by path.
+fn per_gpu_nodes(dev: &Path) -> Vec<(PathBuf, u32)> {
+    let list = |dir: &Path| -> Vec<String> {
+        std::fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let mut nodes = Vec::new();
+    for name in list(dev) {
+        if let Some(index) = name.strip_prefix("nvidia").and_then(|n| n.parse().ok()) {
+            nodes.push((dev.join(&name), index));
+        }
+    }
+    let dri = dev.join("dri");
+    for name in list(&dri) {
+        let index = if let Some(n) = name.strip_prefix("card") {
+            n.parse().ok()
+        } else if let Some(n) = name.strip_prefix("renderD") {
+            n.parse::<u32>().ok().and_then(|n| n.checked_sub(128))
+        } else {
+            None
+        };
+        if let Some(index) = index {
+            nodes.push((dri.join(&name), index));
+        }
+    }
+    nodes.sort();
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_exposes_selected_gpus_and_hides_the_rest() {
+        let dev = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dev.path().join("dri")).unwrap();
+        for node in [
+            "nvidiactl",
+            "nvidia-uvm",
+            "nvidia0",
+            "nvidia1",
+            "dri/card0",
+            "dri/renderD128",
+            "dri/renderD129",
+            "null",
+        ] {
+            std::fs::write(dev.path().join(node), "").unwrap();
+        }
+        let names = |paths: &[PathBuf]| -> Vec<String> {
+            paths
+                .iter()
+                .map(|p| p.strip_prefix(dev.path()).unwrap().display().to_string())
+                .collect()
+        };
+
+        assert_eq!(
+            GpuAccess::resolve(None, dev.path()).unwrap(),
+            GpuAccess::default()
+        );
+        assert_eq!(
+            GpuAccess::resolve(Some("none"), dev.path()).unwrap(),
+            GpuAccess::default()
+        );
+
+        let all = GpuAccess::resolve(Some("all"), dev.path()).unwrap();
+        assert_eq!(all.expose.len(), 7);
+        assert!(all.hide.is_empty());
+
+        let first = GpuAccess::resolve(Some("0"), dev.path()).unwrap();
+        assert_eq!(
+            names(&first.expose),
+            vec![
+                "nvidiactl",
+                "nvidia-uvm",
+                "dri/card0",
+                "dri/renderD128",
+                "nvidia0"
+            ]
+        );
+        assert_eq!(names(&first.hide), vec!["dri/renderD129", "nvidia1"]);
+
+        assert!(GpuAccess::resolve(Some("gpu0"), dev.path()).is_err());
+    }
+}
@@ -0,0 +1,216 @@
+//! Kubernetes provider: one pod per agent, scheduled through `kubectl`.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use serde_json::json;
+
+use super::{shell_single_quote, SandboxProvider};
+use crate::config::KubernetesConfig;
+
+/// Runs the agent command in a fresh pod via `kubectl run --rm -it`, so the
+/// tmux pane stays attached to it. The pod is deleted when the command
+/// exits and, through a shell trap, when the session is killed.
+pub struct KubernetesProvider {
+    config: KubernetesConfig,
+}
+
+impl KubernetesProvider {
+    pub fn new(config: KubernetesConfig) -> Result<Self> {
+        if config.image.trim().is_empty() {
+            return Err(anyhow!(
+                "sandbox.runtime is \"kubernetes\" but sandbox.kubernetes.image is not set"
+            ));
+        }
+        Ok(Self { config })
+    }
+
+    /// `kubectl` followed by the namespace and context flags, if any.
+    fn kubectl(&self) -> Vec<String> {
+        let mut args = vec!["kubectl".to_string()];
+        if let Some(context) = &self.config.context {
+            args.push(shell_single_quote(&format!("--context={}", context)));
+        }
+        if let Some(namespace) = &self.config.namespace {
+            args.push(shell_single_quote(&format!("--namespace={}", namespace)));
+        }
+        args
+    }
+
+    /// Pod spec merged over the one `kubectl run` generates. The container
+    /// must carry the pod's name for the merge to apply to it.
+    fn overrides(&self, pod: &str, workdir: &Path) -> serde_json::Value {
+        let mut requests = serde_json::Map::new();
+        if let Some(cpu) = &self.config.cpu {
+            requests.insert("cpu".to_string(), json!(cpu));
+        }
+        if let Some(memory) = &self.config.memory {
+            requests.insert("memory".to_string(), json!(memory));
+        }
+        let workdir = workdir.display().to_string();
+        let mut container = json!({
+            "name": pod,
+            "image": self.config.image,
+            "stdin": true,
+            "tty": true,
+            "workingDir": workdir,
+            "resources": { "requests": requests },
+        });
+        let mut mounts = Vec::new();
+        let mut volumes = Vec::new();
+        if let Some(claim) = &self.config.volume_claim {
+            mounts.push(json!({ "name": "workdir", "mountPath": workdir }));
+            volumes.push(json!({
+                "name": "workdir",
+                "persistentVolumeClaim": { "claimName": claim },
+            }));
+        }
+        if let Some(size) = &self.config.scratch_size {
+            let path = self.config.scratch_path.as_deref().unwrap_or("/scratch");
+            mounts.push(json!({ "name": "scratch", "mountPath": path }));
+            volumes.push(json!({ "name": "scratch", "emptyDir": { "sizeLimit": size } }));
+        }
+        let mut spec = json!({ "containers": [] });
+        if !volumes.is_empty() {
+            container["volumeMounts"] = json!(mounts);
+            spec["volumes"] = json!(volumes);
+        }
+        if !self.config.node_selector.is_empty() {
+            spec["nodeSelector"] = json!(self.config.node_selector);
+        }
+        spec["containers"] = json!([container]);
+        json!({ "apiVersion": "v1", "spec": spec })
+    }
+
+    /// The pod a command wrapped by this provider runs in, recorded with
+    /// the worker's launch so later commands can reach it.
+    pub fn pod_in(wrapped: &str) -> Option<String> {
+        let mut words = wrapped.split_whitespace();
+        words.find(|word| *word == "run")?;
+        words
+            .next()
+            .filter(|pod| pod.starts_with("omar-"))
+            .map(str::to_string)
+    }
+
+    /// `command` run in the existing `pod` with `kubectl exec`, from
+    /// `workdir`. `tty` allocates a terminal for interactive shells.
+    pub fn exec_command(&self, pod: &str, command: &str, workdir: &str, tty: bool) -> String {
+        let mut exec = self.kubectl();
+        exec.extend(["exec".to_string(), "-i".to_string()]);
+        if tty {
+            exec.push("-t".to_string());
+        }
+        exec.push(shell_single_quote(pod));
+        let script = format!("cd {} && {}", shell_single_quote(workdir), command);
+        exec.push(format!("-- sh -c {}", shell_single_quote(&script)));
+        exec.join(" ")
+    }
+}
+
+impl SandboxProvider for KubernetesProvider {
+    fn name(&self) -> &'static str {
+        "kubectl"
+    }
+
+    fn probe_args(&self) -> &'static [&'static str] {
+        &["version", "--client"]
+    }
+
+    fn security_flags(&self) -> Vec<String> {
+        vec!["run".to_string(), "--restart=Never".to_string()]
+    }
+
+    fn wrap_command(&self, command: &str, workdir: &Path) -> String {
+        let pod = format!("omar-{}", &uuid::Uuid::new_v4().simple().to_string()[..12]);
+        let kubectl = self.kubectl().join(" ");
+        let cleanup = format!(
+            "{} delete pod {} --wait=false >/dev/null 2>&1",
+            kubectl, pod
+        );
+        let mut run = vec![
+            kubectl,
+            "run".to_string(),
+            pod.clone(),
+            shell_single_quote(&format!("--image={}", self.config.image)),
+            "--restart=Never".to_string(),
+            "--rm".to_string(),
+            "--quiet".to_string(),
+            "-i".to_string(),
+            "-t".to_string(),
+            shell_single_quote(&format!("--overrides={}", self.overrides(&pod, workdir))),
+            "--command".to_string(),
+        ];
+        run.push(format!("-- sh -c {}", shell_single_quote(command)));
+        format!(
+            "trap {} EXIT HUP INT TERM; {}",
+            shell_single_quote(&cleanup),
+            run.join(" ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kubernetes_runs_pod_with_requests_and_deletes_it() {
+        let config = KubernetesConfig {
+            image: "ghcr.io/acme/agents:latest".to_string(),
+            namespace: Some("agents".to_string()),
+            node_selector: [("pool".to_string(), "highcpu".to_string())].into(),
+            cpu: Some("8".to_string()),
+            memory: Some("16Gi".to_string()),
+            volume_claim: Some("repos".to_string()),
+            scratch_size: Some("20Gi".to_string()),
+            ..KubernetesConfig::default()
+        };
+        assert!(KubernetesProvider::new(KubernetesConfig::default()).is_err());
+        let provider = KubernetesProvider::new(config).unwrap();
+
+        let cmd = provider.wrap_command("claude --print 'hi'", Path::new("/work/repo"));
+        let pod = cmd
+            .split_whitespace()
+            .find(|word| word.starts_with("omar-"))
+            .unwrap();
+        assert!(cmd.starts_with(&format!(
+            "trap 'kubectl '\\''--namespace=agents'\\'' delete pod {} --wait=false",
+            pod
+        )));
+        assert!(cmd.contains(&format!(
+            "kubectl '--namespace=agents' run {} '--image=ghcr.io/acme/agents:latest' \
+             --restart=Never --rm",
+            pod
+        )));
+        assert!(cmd.ends_with("-- sh -c 'claude --print '\\''hi'\\'''"));
+
+        let overrides = provider.overrides(pod, Path::new("/work/repo"));
+        let container = &overrides["spec"]["containers"][0];
+        assert_eq!(container["name"], pod);
+        assert_eq!(container["workingDir"], "/work/repo");
+        assert_eq!(container["resources"]["requests"]["cpu"], "8");
+        assert_eq!(container["resources"]["requests"]["memory"], "16Gi");
+        assert_eq!(container["volumeMounts"][0]["mountPath"], "/work/repo");
+        assert_eq!(
+            overrides["spec"]["volumes"][0]["persistentVolumeClaim"]["claimName"],
+            "repos"
+        );
+        assert_eq!(container["volumeMounts"][1]["mountPath"], "/scratch");
+        assert_eq!(
+            overrides["spec"]["volumes"][1]["emptyDir"]["sizeLimit"],
+            "20Gi"
+        );
+        assert_eq!(overrides["spec"]["nodeSelector"]["pool"], "highcpu");
+
+        assert_eq!(KubernetesProvider::pod_in(&cmd).as_deref(), Some(pod));
+        assert_eq!(KubernetesProvider::pod_in("claude"), None);
+        assert_eq!(
+            provider.exec_command(pod, "ls", "/work/repo", false),
+            format!(
+                "kubectl '--namespace=agents' exec -i '{}' -- sh -c 'cd '\\''/work/repo'\\'' && ls'",
+                pod
+            )
+        );
+    }
+}
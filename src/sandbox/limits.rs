@@ -0,0 +1,92 @@
+//! `[limits]` — start workers under `nice` and `taskset`
+//!
+//! The wrappers go outside any sandbox runtime, so the niceness and CPU
+//! affinity carry over to the runtime and everything the agent starts.
+
+use anyhow::{anyhow, Result};
+
+use super::shell_single_quote;
+use crate::backend_probe;
+use crate::config::LimitsConfig;
+
+/// Wrap `command` with the configured niceness and CPU affinity. Returns it
+/// unchanged when no limits are set.
+pub fn apply_limits(limits: &LimitsConfig, command: &str) -> Result<String> {
+    let mut prefix = Vec::new();
+    if let Some(nice) = limits.nice {
+        if !(-20..=19).contains(&nice) {
+            return Err(anyhow!(
+                "limits.nice must be between -20 and 19, got {}",
+                nice
+            ));
+        }
+        prefix.push(format!("nice -n {}", nice));
+    }
+    if let Some(cpuset) = limits.cpuset.as_deref().map(str::trim) {
+        if !is_cpu_list(cpuset) {
+            return Err(anyhow!(
+                "limits.cpuset '{}' is not a CPU list like \"2-7\" or \"0,2,4\"",
+                cpuset
+            ));
+        }
+        if !backend_probe::backend_version_probe_succeeds("taskset") {
+            return Err(anyhow!(
+                "limits.cpuset is set but `taskset` is not available on PATH"
+            ));
+        }
+        prefix.push(format!("taskset -c {}", cpuset));
+    }
+    if prefix.is_empty() {
+        return Ok(command.to_string());
+    }
+    Ok(format!(
+        "{} sh -c {}",
+        prefix.join(" "),
+        shell_single_quote(command)
+    ))
+}
+
+/// Comma-separated CPU numbers and ranges, e.g. `0-3,8`.
+fn is_cpu_list(value: &str) -> bool {
+    let is_cpu = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    !value.is_empty()
+        && value.split(',').all(|part| match part.split_once('-') {
+            Some((start, end)) => is_cpu(start) && is_cpu(end),
+            None => is_cpu(part),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_limits_wraps_with_nice_and_validates() {
+        let none = LimitsConfig::default();
+        assert_eq!(apply_limits(&none, "claude").unwrap(), "claude");
+
+        let nice = LimitsConfig {
+            nice: Some(10),
+            cpuset: None,
+        };
+        assert_eq!(
+            apply_limits(&nice, "claude --print 'hi'").unwrap(),
+            "nice -n 10 sh -c 'claude --print '\\''hi'\\'''"
+        );
+        assert!(apply_limits(
+            &LimitsConfig {
+                nice: Some(25),
+                cpuset: None
+            },
+            "claude"
+        )
+        .is_err());
+
+        assert!(is_cpu_list("2-7"));
+        assert!(is_cpu_list("0,2,4-5"));
+        assert!(!is_cpu_list(""));
+        assert!(!is_cpu_list("1,,2"));
+        assert!(!is_cpu_list("0-"));
+        assert!(!is_cpu_list("all; rm -rf /"));
+    }
+}
@@ -0,0 +1,311 @@
+//! Sandbox providers — wrap worker agent commands in an isolation runtime
+
+mod bwrap;
+mod credentials;
+mod gpu;
+mod kubernetes;
+mod limits;
+pub mod preflight;
+pub mod proxy;
+pub mod tamper;
+
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+
+use crate::backend_probe;
+use crate::config::{self, LimitsConfig, SandboxConfig};
+
+pub use bwrap::{BwrapProvider, FirejailProvider, HostTmp};
+pub use credentials::CredentialMounts;
+pub use gpu::GpuAccess;
+pub use kubernetes::KubernetesProvider;
+pub use limits::apply_limits;
+
+/// Network mode applied inside the sandbox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkMode {
+    /// Share the host network namespace.
+    Host,
+    /// Fresh network namespace with loopback only.
+    None,
+    /// Fresh network namespace whose only way out is the allowlisting
+    /// HTTP(S) proxy, reached through a Unix socket.
+    Proxy,
+}
+
+impl NetworkMode {
+    /// Whether the sandbox gets its own network namespace.
+    pub fn unshares_net(self) -> bool {
+        self != NetworkMode::Host
+    }
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim() {
+            "" | "host" => Ok(NetworkMode::Host),
+            "none" => Ok(NetworkMode::None),
+            "proxy" => Ok(NetworkMode::Proxy),
+            other => Err(anyhow!(
+                "Unknown sandbox network mode '{}'. Supported: host, none, proxy",
+                other
+            )),
+        }
+    }
+}
+
+/// An isolation runtime that an agent command can be launched inside.
+pub trait SandboxProvider {
+    /// Binary the runtime is launched with, probed on PATH before use.
+    /// Matches `sandbox.runtime` except for "kubernetes" (`kubectl`).
+    fn name(&self) -> &'static str;
+
+    /// Arguments of a cheap invocation of `name` that succeeds when the
+    /// runtime is installed.
+    fn probe_args(&self) -> &'static [&'static str] {
+        &["--version"]
+    }
+
+    /// Arguments `wrap_command` always passes that carry the isolation
+    /// (read-only root, network mode), as they appear on its command line.
+    fn security_flags(&self) -> Vec<String>;
+
+    /// Wrap a shell command so it runs sandboxed with `workdir` writable.
+    /// `workdir` must be absolute.
+    fn wrap_command(&self, command: &str, workdir: &Path) -> String;
+}
+
+/// Build the provider selected by `sandbox.runtime`, or `None` when disabled.
+pub fn provider_for(config: &SandboxConfig) -> Result<Option<Box<dyn SandboxProvider>>> {
+    let network = NetworkMode::parse(&config.network)?;
+    let home = dirs::home_dir().unwrap_or_default();
+    let credentials = CredentialMounts::resolve(&config.credentials, &home);
+    let writable: Vec<PathBuf> = config
+        .writable_paths
+        .iter()
+        .map(|path| config::expand_tilde(path))
+        .filter(|path| credentials.is_none() || !CredentialMounts::covers(path, &home))
+        .collect();
+    let credentials = credentials.unwrap_or_default();
+    let gpus = GpuAccess::resolve(config.gpus.as_deref(), Path::new("/dev"))?;
+    match config.runtime.trim() {
+        "" | "none" => Ok(None),
+        // Pods never see the host's devices, network namespace, or files
+        "kubernetes" if network != NetworkMode::Host => Err(anyhow!(
+            "sandbox.network = \"{}\" is not supported by the kubernetes runtime; use a NetworkPolicy",
+            config.network.trim()
+        )),
+        "kubernetes" if config.gpus.is_some() || !credentials.replace.is_empty() => Err(anyhow!(
+            "sandbox.gpus and a sandbox.credentials directory are not supported by the kubernetes runtime; bake them into sandbox.kubernetes.image"
+        )),
+        "kubernetes" => Ok(Some(Box::new(KubernetesProvider::new(
+            config.kubernetes.clone(),
+        )?))),
+        "bwrap" => Ok(Some(Box::new(
+            BwrapProvider::new(network, writable)
+                .with_gpus(gpus)
+                .with_credentials(credentials)
+                .with_host_tmp(HostTmp::resolve()),
+        ))),
+        "firejail" if !credentials.replace.is_empty() => Err(anyhow!(
+            "sandbox.credentials can only point at a directory under bwrap; use \"on\" or \"off\" with firejail"
+        )),
+        "firejail" => Ok(Some(Box::new(
+            FirejailProvider::new(network, writable)
+                .with_gpus(gpus)
+                .with_credentials(credentials)
+                .with_host_tmp(HostTmp::resolve()),
+        ))),
+        other => Err(anyhow!(
+            "Unknown sandbox runtime '{}'. Supported: none, bwrap, firejail, kubernetes",
+            other
+        )),
+    }
+}
+
+/// Wrap `command` with the configured sandbox, then `[limits]`. Returns it
+/// unchanged when both are disabled, and errors if the selected runtime is
+/// missing so a misconfigured sandbox never silently falls back to running
+/// bare.
+pub fn wrap_agent_command(
+    config: &SandboxConfig,
+    limits: &LimitsConfig,
+    command: &str,
+    workdir: &str,
+) -> Result<String> {
+    let Some(provider) = provider_for(config)? else {
+        return apply_limits(limits, command);
+    };
+    if !backend_probe::command_succeeds_with_timeout(
+        provider.name(),
+        provider.probe_args(),
+        backend_probe::BACKEND_VERSION_PROBE_TIMEOUT,
+    ) {
+        return Err(anyhow!(
+            "sandbox.runtime is '{}' but `{}` is not available on PATH",
+            provider.name(),
+            provider.name()
+        ));
+    }
+    let command = if NetworkMode::parse(&config.network)? == NetworkMode::Proxy {
+        proxy::ensure_running(&config.proxy)?;
+        proxy::bridged_command(&config.proxy, command)?
+    } else {
+        command.to_string()
+    };
+    let workdir = std::fs::canonicalize(workdir).unwrap_or_else(|_| PathBuf::from(workdir));
+    apply_limits(limits, &provider.wrap_command(&command, &workdir))
+}
+
+/// Wrap `command` like `wrap_agent_command`, passing it `secrets` (and
+/// profile env). The host shell loads them from a private file before the
+/// sandbox starts, which bwrap and firejail inherit; a pod inherits nothing
+/// from that shell, so under kubernetes they are exported inside it.
+pub fn wrap_with_secrets(
+    config: &SandboxConfig,
+    limits: &LimitsConfig,
+    command: &str,
+    workdir: &str,
+    secrets: &[(String, String)],
+) -> Result<String> {
+    with_secrets(config, secrets, command, |command| {
+        wrap_agent_command(config, limits, command, workdir)
+    })
+}
+
+fn with_secrets(
+    config: &SandboxConfig,
+    secrets: &[(String, String)],
+    command: &str,
+    wrap: impl FnOnce(&str) -> Result<String>,
+) -> Result<String> {
+    if config.runtime.trim() == "kubernetes" {
+        wrap(&export_env(secrets, command))
+    } else {
+        crate::secrets::inject(secrets, &wrap(command)?)
+    }
+}
+
+/// Whether sandboxed workers would see the host's agent credentials, so
+/// the UI can say so.
+pub fn shares_host_credentials(config: &SandboxConfig) -> bool {
+    !matches!(config.runtime.trim(), "" | "none" | "kubernetes")
+        && matches!(config.credentials.trim(), "" | "on")
+}
+
+/// Prefix `command` with exports of `env`, for sandboxed workers whose
+/// runtime does not pass the tmux session's environment through.
+pub fn export_env(env: &[(String, String)], command: &str) -> String {
+    let mut exports: String = env
+        .iter()
+        .map(|(key, value)| format!("export {}={}; ", key, shell_single_quote(value)))
+        .collect();
+    exports.push_str(command);
+    exports
+}
+
+pub(crate) fn shell_single_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_env_quotes_values() {
+        let env = [
+            ("GIT_AUTHOR_NAME".to_string(), "O'Mar Bot".to_string()),
+            ("FEATURE_X".to_string(), "1".to_string()),
+        ];
+        assert_eq!(
+            export_env(&env, "claude"),
+            "export GIT_AUTHOR_NAME='O'\\''Mar Bot'; export FEATURE_X='1'; claude"
+        );
+        assert_eq!(export_env(&[], "claude"), "claude");
+    }
+
+    #[test]
+    fn test_provider_for_none_is_disabled() {
+        let config = SandboxConfig::default();
+        assert!(provider_for(&config).unwrap().is_none());
+        assert_eq!(
+            wrap_agent_command(&config, &LimitsConfig::default(), "claude", ".").unwrap(),
+            "claude"
+        );
+    }
+
+    #[test]
+    fn test_provider_for_selects_runtime() {
+        let mut config = SandboxConfig {
+            runtime: "bwrap".to_string(),
+            ..SandboxConfig::default()
+        };
+        assert_eq!(provider_for(&config).unwrap().unwrap().name(), "bwrap");
+        config.runtime = "firejail".to_string();
+        assert_eq!(provider_for(&config).unwrap().unwrap().name(), "firejail");
+        config.runtime = "kubernetes".to_string();
+        assert!(provider_for(&config).is_err());
+        config.kubernetes.image = "ubuntu:24.04".to_string();
+        assert_eq!(provider_for(&config).unwrap().unwrap().name(), "kubectl");
+    }
+
+    #[test]
+    fn test_secrets_reach_kubernetes_pods() {
+        let secrets = [("API_KEY".to_string(), "sk-123".to_string())];
+        let mut config = SandboxConfig {
+            runtime: "kubernetes".to_string(),
+            ..SandboxConfig::default()
+        };
+        config.kubernetes.image = "ubuntu:24.04".to_string();
+        let provider = KubernetesProvider::new(config.kubernetes.clone()).unwrap();
+        let wrapped = with_secrets(&config, &secrets, "claude", |command| {
+            Ok(provider.wrap_command(command, Path::new("/work")))
+        })
+        .unwrap();
+        let (_, in_pod) = wrapped.split_once("--command -- sh -c").unwrap();
+        assert!(in_pod.contains("export API_KEY="), "{wrapped}");
+        assert!(in_pod.contains("sk-123"), "{wrapped}");
+
+        // Other runtimes inherit them from the host shell, off the command line
+        config.runtime = "bwrap".to_string();
+        let wrapped = with_secrets(&config, &secrets, "claude", |command| {
+            Ok(format!("bwrap -- {}", command))
+        })
+        .unwrap();
+        assert!(!wrapped.contains("sk-123"));
+        assert!(wrapped.ends_with("; bwrap -- claude"));
+        crate::secrets::discard(&wrapped);
+    }
+
+    #[test]
+    fn test_shares_host_credentials_only_when_sandboxed_and_on() {
+        let mut config = SandboxConfig::default();
+        assert!(!shares_host_credentials(&config));
+        config.runtime = "bwrap".to_string();
+        assert!(shares_host_credentials(&config));
+        config.credentials = "off".to_string();
+        assert!(!shares_host_credentials(&config));
+    }
+
+    #[test]
+    fn test_provider_for_rejects_unknown_values() {
+        let config = SandboxConfig {
+            runtime: "docker-ish".to_string(),
+            ..SandboxConfig::default()
+        };
+        assert!(provider_for(&config).is_err());
+
+        let config = SandboxConfig {
+            runtime: "bwrap".to_string(),
+            network: "bridge".to_string(),
+            ..SandboxConfig::default()
+        };
+        assert!(provider_for(&config).is_err());
+
+        let config = SandboxConfig {
+            runtime: "bwrap".to_string(),
+            gpus: Some("first".to_string()),
+            ..SandboxConfig::default()
+        };
+        assert!(provider_for(&config).is_err());
+    }
+}
@@ -0,0 +1,153 @@
+//! Sandbox preflight — a trial run of the `[sandbox]` and `[limits]`
+//! wrappers before the first worker needs them
+//!
+//! A runtime that is installed can still fail at launch (user namespaces
+//! disabled, a bad bind path), and a worker that dies that way only shows a
+//! dead pane. The dashboard runs `true` through the wrapper in the
+//! background whenever the settings change, records the outcome in
+//! `sandbox_preflight.json`, and keeps a failure in the status bar.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{provider_for, wrap_agent_command};
+use crate::config::{LimitsConfig, SandboxConfig};
+use crate::paths;
+
+const PREFLIGHT_FILE: &str = "sandbox_preflight.json";
+
+/// The trial command gets this long before it counts as a failure.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Outcome of the last preflight, keyed by the settings it checked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Preflight {
+    pub settings: String,
+    /// `None` when the trial run succeeded, else why it failed
+    pub error: Option<String>,
+    pub checked_at: i64,
+}
+
+/// The settings a preflight covers, in a stable form for comparison.
+pub fn settings_key(sandbox: &SandboxConfig, limits: &LimitsConfig) -> String {
+    serde_json::json!({ "sandbox": sandbox, "limits": limits }).to_string()
+}
+
+/// Whether the settings wrap worker commands at all.
+pub fn needs_preflight(sandbox: &SandboxConfig, limits: &LimitsConfig) -> bool {
+    *limits != LimitsConfig::default() || !matches!(sandbox.runtime.trim(), "" | "none")
+}
+
+/// The recorded preflight for these settings, if they have been checked.
+pub fn cached(
+    state_dir: &Path,
+    sandbox: &SandboxConfig,
+    limits: &LimitsConfig,
+) -> Option<Preflight> {
+    let data = std::fs::read_to_string(state_dir.join(PREFLIGHT_FILE)).ok()?;
+    let preflight: Preflight = serde_json::from_str(&data).ok()?;
+    (preflight.settings == settings_key(sandbox, limits)).then_some(preflight)
+}
+
+/// Trial-run the wrappers and record the outcome.
+pub fn run_and_record(
+    state_dir: &Path,
+    sandbox: &SandboxConfig,
+    limits: &LimitsConfig,
+) -> Preflight {
+    let preflight = Preflight {
+        settings: settings_key(sandbox, limits),
+        error: trial_run(sandbox, limits).err().map(|e| format!("{:#}", e)),
+        checked_at: chrono::Utc::now().timestamp(),
+    };
+    if let Ok(data) = serde_json::to_string_pretty(&preflight) {
+        let _ = std::fs::create_dir_all(state_dir);
+        let _ = std::fs::write(state_dir.join(PREFLIGHT_FILE), data);
+    }
+    preflight
+}
+
+/// Run `true` through the sandbox and limits wrappers in a scratch workdir.
+fn trial_run(sandbox: &SandboxConfig, limits: &LimitsConfig) -> Result<()> {
+    let scratch = paths::private_temp_dir()?.join(format!("preflight-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&scratch).context("Failed to create a scratch workdir")?;
+    let result = trial_run_in(sandbox, limits, &scratch.to_string_lossy());
+    let _ = std::fs::remove_dir_all(&scratch);
+    result
+}
+
+fn trial_run_in(sandbox: &SandboxConfig, limits: &LimitsConfig, workdir: &str) -> Result<()> {
+    let command = if provider_for(sandbox)?.is_some() {
+        wrap_agent_command(sandbox, limits, "true", workdir)?
+    } else {
+        super::apply_limits(limits, "true")?
+    };
+    let mut child = Command::new("sh")
+        .args(["-c", &command])
+        .current_dir(workdir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to start the trial command")?;
+    let deadline = Instant::now() + TIMEOUT;
+    while child.try_wait()?.is_none() {
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(anyhow!(
+                "trial command did not exit within {}s",
+                TIMEOUT.as_secs()
+            ));
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    let output = child.wait_with_output()?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let reason = stderr.lines().rev().find(|l| !l.trim().is_empty());
+    Err(anyhow!(
+        "trial command failed ({}){}",
+        output.status,
+        reason
+            .map(|r| format!(": {}", r.trim()))
+            .unwrap_or_default()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preflight_records_outcome_per_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let sandbox = SandboxConfig::default();
+        let nice = LimitsConfig {
+            nice: Some(5),
+            cpuset: None,
+        };
+        assert!(!needs_preflight(&sandbox, &LimitsConfig::default()));
+        assert!(needs_preflight(&sandbox, &nice));
+        assert!(cached(dir.path(), &sandbox, &nice).is_none());
+
+        let ok = run_and_record(dir.path(), &sandbox, &nice);
+        assert_eq!(ok.error, None);
+        assert_eq!(cached(dir.path(), &sandbox, &nice), Some(ok));
+
+        let bad = LimitsConfig {
+            nice: Some(40),
+            cpuset: None,
+        };
+        assert!(cached(dir.path(), &sandbox, &bad).is_none());
+        let failed = run_and_record(dir.path(), &sandbox, &bad);
+        assert!(failed.error.unwrap().contains("limits.nice"));
+        assert!(cached(dir.path(), &sandbox, &nice).is_none());
+    }
+}
@@ -0,0 +1,470 @@
+//! Allowlisting HTTP(S) proxy for `sandbox.network = "proxy"`.
+//!
+//! Proxied workers run in their own network namespace with loopback only,
+//! so the proxy is their one way out. It tunnels (`CONNECT`) or forwards
+//! plain requests only to `[sandbox.proxy] allow` domains and answers 403
+//! otherwise. It runs on the host as `omar sandbox-proxy`, listening on the
+//! owner-only Unix socket `~/.omar/sandbox-proxy.sock`, which is visible
+//! inside the sandbox's read-only root. Inside, a bridge (`omar
+//! sandbox-proxy --bridge`) started before the agent listens on
+//! `[sandbox.proxy] listen` and relays each connection to that socket;
+//! `HTTP(S)_PROXY` point at the bridge.
+//!
+//! The host proxy is started by the first proxied launch and shared by
+//! later ones. It keeps the allowlist it was started with until it is
+//! killed (`stop`, run when the dashboard quits and kills all agents).
+//! Both ends need Unix sockets, so the proxy is unavailable elsewhere.
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Result};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::config::ProxyConfig;
+use crate::sandbox::shell_single_quote;
+
+/// Request method of the handshake `ensure_running` uses to tell our proxy
+/// from anything else bound to the socket path.
+const PING_METHOD: &str = "OMAR-PING";
+const PING_HEADER: &str = "X-Omar-Proxy";
+
+/// Request heads longer than this are refused.
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+const UNSUPPORTED: &str = "sandbox.network = \"proxy\" needs a Unix host";
+
+/// How long a launch waits for a freshly started proxy to listen.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Whether `host` is an allowed domain or a subdomain of one.
+pub fn host_allowed(allow: &[String], host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    allow.iter().any(|domain| {
+        let domain = domain
+            .trim()
+            .trim_start_matches("*.")
+            .trim_end_matches('.')
+            .to_ascii_lowercase();
+        !domain.is_empty()
+            && (host == domain
+                || host
+                    .strip_suffix(&domain)
+                    .is_some_and(|sub| sub.ends_with('.')))
+    })
+}
+
+/// Variables pointing a worker at the proxy, in both cases since tools
+/// disagree on which they read.
+pub fn proxy_env(config: &ProxyConfig) -> Vec<(String, String)> {
+    let url = format!("http://{}", config.listen);
+    let mut env: Vec<(String, String)> = ["HTTP_PROXY", "HTTPS_PROXY", "http_proxy", "https_proxy"]
+        .into_iter()
+        .map(|key| (key.to_string(), url.clone()))
+        .collect();
+    for key in ["NO_PROXY", "no_proxy"] {
+        env.push((key.to_string(), "localhost,127.0.0.1".to_string()));
+    }
+    env
+}
+
+/// `sandbox.proxy.listen`, which must be a loopback address.
+fn listen_addr(config: &ProxyConfig) -> Result<SocketAddr> {
+    let addr: SocketAddr = config.listen.parse().map_err(|_| {
+        anyhow!(
+            "sandbox.proxy.listen '{}' is not an address such as 127.0.0.1:3129",
+            config.listen
+        )
+    })?;
+    if !addr.ip().is_loopback() {
+        bail!("sandbox.proxy.listen must be a loopback address");
+    }
+    Ok(addr)
+}
+
+/// Prefix `command` with the start of the in-sandbox bridge (stopped when
+/// the command's shell exits) and exports of the proxy variables.
+pub fn bridged_command(config: &ProxyConfig, command: &str) -> Result<String> {
+    listen_addr(config)?;
+    let omar = std::env::current_exe()?;
+    Ok(format!(
+        "{} sandbox-proxy --bridge --listen {} & omar_proxy_bridge=$!; \
+         trap 'kill \"$omar_proxy_bridge\" 2>/dev/null' EXIT; {}",
+        shell_single_quote(&omar.display().to_string()),
+        shell_single_quote(&config.listen),
+        super::export_env(&proxy_env(config), command)
+    ))
+}
+
+/// Start the host proxy unless ours is already serving, and wait until it
+/// answers.
+pub fn ensure_running(config: &ProxyConfig) -> Result<()> {
+    listen_addr(config)?;
+    // Under `cargo test` the current executable is the test harness
+    if cfg!(test) || is_ours() {
+        return Ok(());
+    }
+    if !cfg!(unix) {
+        bail!("{}", UNSUPPORTED);
+    }
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .args(["sandbox-proxy", "--allow"])
+        .arg(config.allow.join(","))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Outlive the dashboard's process group
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    command.spawn()?;
+    let deadline = Instant::now() + STARTUP_TIMEOUT;
+    while Instant::now() < deadline {
+        if is_ours() {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    Err(anyhow!(
+        "sandbox proxy did not start on {}",
+        socket_path().display()
+    ))
+}
+
+/// Whether the socket is served by the proxy whose pid is on file: it must
+/// answer the handshake with that pid.
+#[cfg(unix)]
+fn is_ours() -> bool {
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream as StdUnixStream;
+
+    let Some(pid) = std::fs::read_to_string(pid_path())
+        .ok()
+        .and_then(|text| text.trim().parse::<u32>().ok())
+    else {
+        return false;
+    };
+    let Ok(mut stream) = StdUnixStream::connect(socket_path()) else {
+        return false;
+    };
+    let timeout = Some(Duration::from_millis(500));
+    if stream.set_read_timeout(timeout).is_err()
+        || stream
+            .write_all(format!("{} * HTTP/1.1\r\n\r\n", PING_METHOD).as_bytes())
+            .is_err()
+    {
+        return false;
+    }
+    let mut reply = String::new();
+    let _ = stream.take(1024).read_to_string(&mut reply);
+    ping_pid(&reply) == Some(pid)
+}
+
+#[cfg(not(unix))]
+fn is_ours() -> bool {
+    false
+}
+
+/// The pid in a handshake reply.
+fn ping_pid(reply: &str) -> Option<u32> {
+    reply.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case(PING_HEADER)
+            .then(|| value.trim().parse().ok())?
+    })
+}
+
+fn omar_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".omar")
+}
+
+fn pid_path() -> PathBuf {
+    omar_dir().join("sandbox-proxy.pid")
+}
+
+pub fn socket_path() -> PathBuf {
+    omar_dir().join("sandbox-proxy.sock")
+}
+
+/// Terminate the running proxy, if any. Returns whether one was stopped.
+pub fn stop() -> bool {
+    let path = pid_path();
+    let pid = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|text| text.trim().parse::<u32>().ok());
+    let _ = std::fs::remove_file(&path);
+    match pid {
+        Some(pid) if pid != 0 && crate::process::pid_alive(pid) => Command::new("kill")
+            .arg(pid.to_string())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success()),
+        _ => false,
+    }
+}
+
+/// Serve the host proxy on `socket_path()` until the process is killed.
+#[cfg(unix)]
+pub async fn serve(allow: Vec<String>) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::net::UnixListener;
+
+    let socket = socket_path();
+    let dir = omar_dir();
+    std::fs::create_dir_all(&dir)?;
+    // Only reached when no live proxy answered, so this is a stale socket
+    let _ = std::fs::remove_file(&socket);
+    let listener = UnixListener::bind(&socket)?;
+    std::fs::set_permissions(&socket, std::fs::Permissions::from_mode(0o600))?;
+    std::fs::write(pid_path(), std::process::id().to_string())?;
+    let allow = Arc::new(allow);
+    loop {
+        let (client, _) = listener.accept().await?;
+        let allow = allow.clone();
+        tokio::spawn(async move {
+            let _ = handle(client, &allow).await;
+        });
+    }
+}
+
+/// Run inside the sandbox: accept on `listen` and relay each connection to
+/// the host proxy's socket, until the process is killed.
+#[cfg(unix)]
+pub async fn bridge(listen: &str) -> Result<()> {
+    use tokio::net::{TcpListener, UnixStream};
+
+    let listener = TcpListener::bind(listen).await?;
+    let socket = socket_path();
+    loop {
+        let (mut client, _) = listener.accept().await?;
+        let socket = socket.clone();
+        tokio::spawn(async move {
+            if let Ok(mut upstream) = UnixStream::connect(&socket).await {
+                let _ = tokio::io::copy_bidirectional(&mut client, &mut upstream).await;
+            }
+        });
+    }
+}
+
+#[cfg(not(unix))]
+pub async fn serve(_allow: Vec<String>) -> Result<()> {
+    bail!("{}", UNSUPPORTED)
+}
+
+#[cfg(not(unix))]
+pub async fn bridge(_listen: &str) -> Result<()> {
+    bail!("{}", UNSUPPORTED)
+}
+
+async fn handle<S>(mut client: S, allow: &[String]) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (head, rest) = read_head(&mut client).await?;
+    if head.starts_with(PING_METHOD) {
+        let reply = format!(
+            "HTTP/1.1 200 OK\r\n{}: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            PING_HEADER,
+            std::process::id()
+        );
+        client.write_all(reply.as_bytes()).await?;
+        return Ok(());
+    }
+    let request = match parse_request(&head) {
+        Ok(request) => request,
+        Err(_) => {
+            client
+                .write_all(&status_response(400, "Bad Request"))
+                .await?;
+            return Ok(());
+        }
+    };
+    if !host_allowed(allow, &request.host) {
+        client.write_all(&status_response(403, "Forbidden")).await?;
+        return Ok(());
+    }
+    let Ok(mut upstream) = TcpStream::connect((request.host.as_str(), request.port)).await else {
+        client
+            .write_all(&status_response(502, "Bad Gateway"))
+            .await?;
+        return Ok(());
+    };
+    match &request.forward {
+        Some(head) => upstream.write_all(head.as_bytes()).await?,
+        None => {
+            client
+                .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+                .await?
+        }
+    }
+    upstream.write_all(&rest).await?;
+    tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+    Ok(())
+}
+
+/// Read up to the blank line ending the request head. Returns the head and
+/// whatever body bytes arrived with it.
+async fn read_head<S: AsyncRead + Unpin>(client: &mut S) -> Result<(String, Vec<u8>)> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            let rest = buf.split_off(end + 4);
+            return Ok((String::from_utf8_lossy(&buf).into_owned(), rest));
+        }
+        if buf.len() > MAX_HEAD_BYTES {
+            bail!("request head too long");
+        }
+        let n = client.read(&mut chunk).await?;
+        if n == 0 {
+            bail!("connection closed before the request head ended");
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+}
+
+fn status_response(code: u16, reason: &str) -> Vec<u8> {
+    format!(
+        "HTTP/1.1 {} {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        code, reason
+    )
+    .into_bytes()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ProxyRequest {
+    host: String,
+    port: u16,
+    /// Head to send upstream for a plain HTTP request; `None` for a
+    /// `CONNECT` tunnel
+    forward: Option<String>,
+}
+
+fn parse_request(head: &str) -> Result<ProxyRequest> {
+    let (line, headers) = head.split_once("\r\n").unwrap_or((head, ""));
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        bail!("malformed request line");
+    };
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let (host, port) = split_authority(target, 443)?;
+        return Ok(ProxyRequest {
+            host,
+            port,
+            forward: None,
+        });
+    }
+    let Some(target) = target.strip_prefix("http://") else {
+        bail!("expected an absolute http:// URL");
+    };
+    let (authority, path) = match target.find('/') {
+        Some(i) => target.split_at(i),
+        None => (target, "/"),
+    };
+    let (host, port) = split_authority(authority, 80)?;
+    let mut forward = format!("{} {} {}\r\n", method, path, version);
+    for header in headers.split("\r\n") {
+        if !header.to_ascii_lowercase().starts_with("proxy-") {
+            forward.push_str(header);
+            forward.push_str("\r\n");
+        }
+    }
+    Ok(ProxyRequest {
+        host,
+        port,
+        forward: Some(forward.trim_end_matches("\r\n").to_string() + "\r\n\r\n"),
+    })
+}
+
+/// `host[:port]`, with IPv6 literals in brackets.
+fn split_authority(authority: &str, default_port: u16) -> Result<(String, u16)> {
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => (host, Some(port)),
+        _ => (authority, None),
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if host.is_empty() {
+        bail!("missing host");
+    }
+    let port = match port {
+        Some(port) => port.parse().map_err(|_| anyhow!("bad port '{}'", port))?,
+        None => default_port,
+    };
+    Ok((host.to_string(), port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxy_allows_listed_domains_and_rewrites_plain_requests() {
+        let allow = vec!["api.anthropic.com".to_string(), "github.com".to_string()];
+        assert!(host_allowed(&allow, "api.anthropic.com"));
+        assert!(host_allowed(&allow, "codeload.GitHub.com."));
+        assert!(!host_allowed(&allow, "anthropic.com"));
+        assert!(!host_allowed(&allow, "evilgithub.com"));
+        assert!(!host_allowed(&allow, "127.0.0.1"));
+
+        assert_eq!(
+            parse_request("CONNECT github.com:443 HTTP/1.1\r\nHost: github.com:443\r\n\r\n")
+                .unwrap(),
+            ProxyRequest {
+                host: "github.com".to_string(),
+                port: 443,
+                forward: None,
+            }
+        );
+        let plain = parse_request(
+            "GET http://github.com/a?b=1 HTTP/1.1\r\nHost: github.com\r\nProxy-Connection: keep-alive\r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(plain.port, 80);
+        assert_eq!(
+            plain.forward.as_deref(),
+            Some("GET /a?b=1 HTTP/1.1\r\nHost: github.com\r\n\r\n")
+        );
+        assert_eq!(
+            split_authority("[::1]:8080", 80).unwrap(),
+            ("::1".to_string(), 8080)
+        );
+        assert!(parse_request("GET /relative HTTP/1.1\r\n\r\n").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handshake_names_the_proxy_pid() {
+        let (client, server) = tokio::io::duplex(4096);
+        tokio::spawn(async move { handle(server, &[]).await });
+        let mut client = client;
+        client
+            .write_all(format!("{} * HTTP/1.1\r\n\r\n", PING_METHOD).as_bytes())
+            .await
+            .unwrap();
+        let mut reply = String::new();
+        client.read_to_string(&mut reply).await.unwrap();
+        assert_eq!(ping_pid(&reply), Some(std::process::id()));
+        assert_eq!(ping_pid("HTTP/1.1 403 Forbidden\r\n\r\n"), None);
+
+        let config = ProxyConfig {
+            listen: "127.0.0.1:3129".to_string(),
+            allow: Vec::new(),
+        };
+        let command = bridged_command(&config, "claude").unwrap();
+        assert!(command.contains("sandbox-proxy --bridge --listen '127.0.0.1:3129' &"));
+        assert!(command.ends_with("export no_proxy='localhost,127.0.0.1'; claude"));
+        let config = ProxyConfig {
+            listen: "0.0.0.0:3129".to_string(),
+            allow: Vec::new(),
+        };
+        assert!(bridged_command(&config, "claude").is_err());
+    }
+}
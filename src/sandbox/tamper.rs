@@ -0,0 +1,204 @@
+//! Tamper detection for sandboxed workers
+//!
+//! Each sandboxed launch records the runtime and the flags that carry its
+//! isolation. The dashboard periodically walks each worker's pane process
+//! tree in `/proc` and flags a worker whose runtime process is gone (the
+//! pane was respawned or the session recreated outside omar) or was started
+//! without one of those flags. Hosts without `/proc` are not checked.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::Result;
+
+use super::provider_for;
+use crate::config::SandboxConfig;
+
+/// Runtime binary followed by its security flags, or empty when `config`
+/// leaves workers unsandboxed.
+pub fn expected_security(config: &SandboxConfig) -> Result<Vec<String>> {
+    Ok(provider_for(config)?
+        .map(|provider| {
+            let mut expected = vec![provider.name().to_string()];
+            expected.extend(provider.security_flags());
+            expected
+        })
+        .unwrap_or_default())
+}
+
+/// Why the process tree under `pane_pid` does not match `expected`, or
+/// `None` when it does (or cannot be inspected).
+pub fn check(proc_dir: &Path, pane_pid: u32, expected: &[String]) -> Option<String> {
+    let (runtime, flags) = expected.split_first()?;
+    let processes = read_processes(proc_dir);
+    if !processes.contains_key(&pane_pid) {
+        return None;
+    }
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (&pid, (ppid, _)) in &processes {
+        children.entry(*ppid).or_default().push(pid);
+    }
+
+    let mut stack = vec![pane_pid];
+    let mut missing: Option<Vec<&String>> = None;
+    while let Some(pid) = stack.pop() {
+        stack.extend(children.get(&pid).into_iter().flatten());
+        let argv = &processes[&pid].1;
+        let is_runtime = argv
+            .first()
+            .and_then(|arg0| Path::new(arg0).file_name())
+            .is_some_and(|name| name.to_string_lossy() == *runtime);
+        if !is_runtime {
+            continue;
+        }
+        let cmdline = argv.join(" ");
+        let absent: Vec<&String> = flags
+            .iter()
+            .filter(|f| !cmdline.contains(f.as_str()))
+            .collect();
+        if absent.is_empty() {
+            return None;
+        }
+        missing = Some(absent);
+    }
+    Some(match missing {
+        Some(absent) => format!(
+            "{} is running without {}",
+            runtime,
+            absent
+                .iter()
+                .map(|f| f.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        None => format!("no {} process under the pane", runtime),
+    })
+}
+
+/// pid -> (parent pid, argv) for every process in `proc_dir`.
+fn read_processes(proc_dir: &Path) -> HashMap<u32, (u32, Vec<String>)> {
+    let mut processes = HashMap::new();
+    let Ok(entries) = std::fs::read_dir(proc_dir) else {
+        return processes;
+    };
+    for entry in entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|n| n.parse().ok()) else {
+            continue;
+        };
+        let Ok(stat) = std::fs::read_to_string(entry.path().join("stat")) else {
+            continue;
+        };
+        // The command name may contain spaces and parens; fields resume
+        // after the last ')': state, then ppid
+        let Some(ppid) = stat
+            .rsplit_once(')')
+            .and_then(|(_, rest)| rest.split_whitespace().nth(1))
+            .and_then(|ppid| ppid.parse().ok())
+        else {
+            continue;
+        };
+        let argv = std::fs::read(entry.path().join("cmdline"))
+            .map(|raw| {
+                raw.split(|&b| b == 0)
+                    .filter(|arg| !arg.is_empty())
+                    .map(|arg| String::from_utf8_lossy(arg).to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        processes.insert(pid, (ppid, argv));
+    }
+    processes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add_process(proc_dir: &Path, pid: u32, ppid: u32, argv: &[&str]) {
+        let dir = proc_dir.join(pid.to_string());
+        std::fs::create_dir(&dir).unwrap();
+        let comm = Path::new(argv[0]).file_name().unwrap().to_string_lossy();
+        std::fs::write(
+            dir.join("stat"),
+            format!("{} ({}) S {} 1 1", pid, comm, ppid),
+        )
+        .unwrap();
+        std::fs::write(dir.join("cmdline"), argv.join("\0") + "\0").unwrap();
+    }
+
+    #[test]
+    fn test_check_finds_runtime_and_missing_flags() {
+        let config = SandboxConfig {
+            runtime: "bwrap".to_string(),
+            network: "none".to_string(),
+            ..SandboxConfig::default()
+        };
+        let expected = expected_security(&config).unwrap();
+        assert_eq!(
+            expected,
+            vec![
+                "bwrap",
+                "--die-with-parent",
+                "--ro-bind / /",
+                "--tmpfs /tmp",
+                "--unshare-net"
+            ]
+        );
+        assert!(expected_security(&SandboxConfig::default())
+            .unwrap()
+            .is_empty());
+
+        let proc_dir = tempfile::tempdir().unwrap();
+        let proc_dir = proc_dir.path();
+        add_process(
+            proc_dir,
+            100,
+            1,
+            &["sh", "-lc", "nice -n 5 sh -c 'bwrap ...'"],
+        );
+        add_process(
+            proc_dir,
+            101,
+            100,
+            &["nice", "-n", "5", "sh", "-c", "bwrap"],
+        );
+        add_process(
+            proc_dir,
+            102,
+            101,
+            &[
+                "/usr/bin/bwrap",
+                "--die-with-parent",
+                "--ro-bind",
+                "/",
+                "/",
+                "--tmpfs",
+                "/tmp",
+                "--unshare-net",
+                "--",
+                "sh",
+            ],
+        );
+        add_process(proc_dir, 200, 1, &["sh", "-lc", "claude"]);
+        add_process(proc_dir, 201, 200, &["claude"]);
+        add_process(proc_dir, 300, 1, &["sh"]);
+        add_process(
+            proc_dir,
+            301,
+            300,
+            &["bwrap", "--ro-bind", "/", "/", "--", "sh"],
+        );
+
+        assert_eq!(check(proc_dir, 100, &expected), None);
+        assert_eq!(
+            check(proc_dir, 200, &expected).as_deref(),
+            Some("no bwrap process under the pane")
+        );
+        assert_eq!(
+            check(proc_dir, 300, &expected).as_deref(),
+            Some("bwrap is running without --die-with-parent, --tmpfs /tmp, --unshare-net")
+        );
+        assert_eq!(check(proc_dir, 999, &expected), None);
+        assert_eq!(check(proc_dir, 200, &[]), None);
+    }
+}
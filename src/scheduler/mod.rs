@@ -95,6 +95,9 @@ pub struct Scheduler {
     queue: Mutex<BinaryHeap<ScheduledEvent>>,
     notify: Notify,
     store_path: Option<PathBuf>,
+    /// Receivers whose deliveries are held until a timestamp (ns), e.g.
+    /// while the agent is rate-limited. Kept in memory only.
+    paused: Mutex<HashMap<(String, u32), u64>>,
 }
 
 struct StoreLock {
@@ -183,6 +186,7 @@ impl Scheduler {
             queue: Mutex::new(BinaryHeap::new()),
             notify: Notify::new(),
             store_path: None,
+            paused: Mutex::new(HashMap::new()),
         }
     }
 
@@ -195,9 +199,36 @@ impl Scheduler {
             queue: Mutex::new(queue),
             notify: Notify::new(),
             store_path: Some(store_path),
+            paused: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Hold deliveries to `receiver` until `until_ns`; due events are
+    /// rescheduled to that time.
+    pub fn pause_receiver(&self, receiver: &str, ea_id: u32, until_ns: u64) {
+        self.paused
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert((receiver.to_string(), ea_id), until_ns);
+        self.notify.notify_one();
+    }
+
+    pub fn resume_receiver(&self, receiver: &str, ea_id: u32) {
+        self.paused
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&(receiver.to_string(), ea_id));
+        self.notify.notify_one();
+    }
+
+    /// The pause deadline for `receiver`, if it has not yet passed.
+    fn paused_until(&self, receiver: &str, ea_id: u32) -> Option<u64> {
+        let mut paused = self.paused.lock().unwrap_or_else(|e| e.into_inner());
+        let now = now_ns();
+        paused.retain(|_, until| *until > now);
+        paused.get(&(receiver.to_string(), ea_id)).copied()
+    }
+
     pub fn is_persistent(&self) -> bool {
         self.store_path.is_some()
     }
@@ -352,6 +383,22 @@ impl Scheduler {
         count
     }
 
+    /// Readdress events for `old` within an EA to `new`, e.g. after a rename.
+    pub fn rename_receiver(&self, old: &str, new: &str, ea_id: u32) -> usize {
+        self.transaction(true, |queue| {
+            let events: Vec<ScheduledEvent> = queue.drain().collect();
+            let mut count = 0;
+            for mut ev in events {
+                if ev.receiver == old && ev.ea_id == ea_id {
+                    ev.receiver = new.to_string();
+                    count += 1;
+                }
+                queue.push(ev);
+            }
+            count
+        })
+    }
+
     /// Pop all events matching the given receiver, EA, and timestamp.
     /// Fix V7: EA-scoped batching prevents cross-EA event delivery.
     #[cfg(test)]
@@ -421,6 +468,14 @@ impl Scheduler {
                     continue;
                 }
 
+                if let Some(until) = self.paused_until(&receiver, ea_id) {
+                    for mut event in batch {
+                        event.timestamp = event.timestamp.max(until);
+                        remaining.push(event);
+                    }
+                    continue;
+                }
+
                 let mut restore_input = None;
                 if should_defer_for_popup(popup_receiver, &receiver, ea_id) {
                     // If the user has a meaningful draft, preserve it across
@@ -500,7 +555,7 @@ fn pane_target_name(receiver: &str, ea_id: ea::EaId, base_prefix: &str) -> Strin
     if receiver == "ea" || receiver == "omar" {
         ea::ea_manager_session(ea_id, base_prefix)
     } else {
-        format!("{}{}", ea::ea_prefix(ea_id, base_prefix), receiver)
+        crate::naming::session_name(&ea::ea_prefix(ea_id, base_prefix), receiver)
     }
 }
 
@@ -1919,6 +1974,53 @@ mod tests {
         });
     }
 
+    #[test]
+    fn paused_receiver_events_are_held_until_pause_ends() {
+        let scheduler = Scheduler::new();
+        let popup_receiver = new_popup_receiver();
+        let past = now_ns().saturating_sub(1_000_000_000);
+        scheduler.insert(make_event("limited", "ea", past, "task"));
+        scheduler.insert(make_event("other", "ea", past, "task"));
+
+        let until = now_ns() + 60_000_000_000;
+        scheduler.pause_receiver("limited", 0, until);
+        let deliveries = scheduler.take_due_deliveries(&popup_receiver, "omar-agent-");
+        let delivered: Vec<&str> = deliveries.iter().map(|d| d.receiver.as_str()).collect();
+        assert_eq!(delivered, vec!["other"]);
+        let held = scheduler.list_by_ea(0);
+        assert_eq!(held.len(), 1);
+        assert_eq!(held[0].timestamp, until);
+
+        scheduler.resume_receiver("limited", 0);
+        assert!(scheduler.paused_until("limited", 0).is_none());
+        scheduler.pause_receiver("limited", 0, now_ns().saturating_sub(1));
+        assert!(
+            scheduler.paused_until("limited", 0).is_none(),
+            "expired pauses are dropped"
+        );
+    }
+
+    #[test]
+    fn recurring_event_survives_delivery_to_crashed_agent() {
+        let scheduler = Scheduler::new();
+        let popup_receiver = new_popup_receiver();
+        let ticker = TickerBuffer::new();
+        let receiver = format!("crashed-{}", uuid::Uuid::new_v4().simple());
+        let mut event = make_event(&receiver, "ea", now_ns().saturating_sub(1), "status?");
+        event.recurring_ns = Some(60_000_000_000);
+        scheduler.insert(event);
+
+        let deliveries = scheduler.take_due_deliveries(&popup_receiver, "omar-test-");
+        assert_eq!(deliveries.len(), 1);
+        let message = format_delivery(&deliveries[0].batch, deliveries[0].timestamp);
+        deliver_to_tmux(0, &receiver, &message, "omar-test-", &ticker, None);
+        assert!(ticker.latest(1)[0].contains("delivery failed"));
+
+        let next = scheduler.list_by_ea(0);
+        assert_eq!(next.len(), 1);
+        assert!(next[0].timestamp > now_ns());
+    }
+
     // ── Event-loop behaviour with popup state ──
     //
     // Regression check: with the popup open for `(receiver, ea_id)`, a
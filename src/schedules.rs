@@ -0,0 +1,222 @@
+//! Scheduled spawns — cron-style recurring tasks that spawn a fresh worker
+//! each time they fire
+//!
+//! Schedules come from `[[schedules]]` in config.toml (bound to an EA via
+//! `ea`) and from the `add_schedule` MCP tool, which stores them in
+//! `<state_dir>/schedules.json`. The dashboard fires the active EA's due
+//! schedules on refresh and records each fire time in
+//! `<state_dir>/schedule_runs.json`, so a restart neither repeats nor
+//! back-fills more than the one most recent missed run.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, Utc};
+use croner::Cron;
+
+use crate::config::ScheduleConfig;
+
+/// Parse a five-field cron expression.
+pub fn parse_cron(expr: &str) -> Result<Cron> {
+    Cron::new(expr.trim())
+        .parse()
+        .map_err(|e| anyhow!("Invalid cron expression '{}': {}", expr, e))
+}
+
+/// First fire time strictly after `after`, in local time.
+pub fn next_fire(schedule: &ScheduleConfig, after: DateTime<Local>) -> Option<DateTime<Local>> {
+    parse_cron(&schedule.cron)
+        .ok()?
+        .find_next_occurrence(&after, false)
+        .ok()
+}
+
+/// Schedules due at `now`. `last_runs` maps schedule name to its last fire
+/// (or first-seen) time and is updated in place; schedules seen for the
+/// first time start counting from `now` instead of firing retroactively.
+pub fn take_due(
+    schedules: &[ScheduleConfig],
+    last_runs: &mut BTreeMap<String, DateTime<Utc>>,
+    now: DateTime<Local>,
+) -> Vec<ScheduleConfig> {
+    let mut due = Vec::new();
+    for schedule in schedules {
+        let Some(last) = last_runs.get(&schedule.name) else {
+            last_runs.insert(schedule.name.clone(), now.with_timezone(&Utc));
+            continue;
+        };
+        let last = last.with_timezone(&Local);
+        if next_fire(schedule, last).is_some_and(|next| next <= now) {
+            last_runs.insert(schedule.name.clone(), now.with_timezone(&Utc));
+            due.push(schedule.clone());
+        }
+    }
+    last_runs.retain(|name, _| schedules.iter().any(|s| &s.name == name));
+    due
+}
+
+/// Agent name for one firing, e.g. `nightly-tests-0417-0200`.
+pub fn agent_name(schedule: &ScheduleConfig, now: DateTime<Local>) -> String {
+    let name: String = schedule
+        .name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("{}-{}", name, now.format("%m%d-%H%M"))
+}
+
+fn added_path(state_dir: &Path) -> PathBuf {
+    state_dir.join("schedules.json")
+}
+
+fn runs_path(state_dir: &Path) -> PathBuf {
+    state_dir.join("schedule_runs.json")
+}
+
+fn write_json(path: &Path, value: &impl serde::Serialize) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(value)?)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Schedules added through the API for an EA.
+pub fn load_added_in(state_dir: &Path) -> Vec<ScheduleConfig> {
+    fs::read_to_string(added_path(state_dir))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+/// Add a schedule for an EA. `existing` are the schedules it must not
+/// shadow (config schedules of the same EA).
+pub fn add_in(
+    state_dir: &Path,
+    schedule: ScheduleConfig,
+    existing: &[ScheduleConfig],
+) -> Result<()> {
+    if schedule.name.trim().is_empty() {
+        return Err(anyhow!("Schedule name must not be empty"));
+    }
+    parse_cron(&schedule.cron)?;
+    let mut added = load_added_in(state_dir);
+    if added
+        .iter()
+        .chain(existing)
+        .any(|s| s.name == schedule.name)
+    {
+        return Err(anyhow!("Schedule '{}' already exists", schedule.name));
+    }
+    added.push(schedule);
+    write_json(&added_path(state_dir), &added)
+}
+
+/// Remove an API-added schedule. Returns false if there was none.
+pub fn remove_in(state_dir: &Path, name: &str) -> Result<bool> {
+    let mut added = load_added_in(state_dir);
+    let before = added.len();
+    added.retain(|s| s.name != name);
+    if added.len() == before {
+        return Ok(false);
+    }
+    write_json(&added_path(state_dir), &added)?;
+    Ok(true)
+}
+
+/// Config schedules bound to `ea` followed by that EA's API-added ones.
+pub fn for_ea(config: &[ScheduleConfig], ea: u32, state_dir: &Path) -> Vec<ScheduleConfig> {
+    config
+        .iter()
+        .filter(|s| s.ea == ea)
+        .cloned()
+        .chain(load_added_in(state_dir))
+        .collect()
+}
+
+pub fn load_last_runs_in(state_dir: &Path) -> BTreeMap<String, DateTime<Utc>> {
+    fs::read_to_string(runs_path(state_dir))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_last_runs_in(state_dir: &Path, last_runs: &BTreeMap<String, DateTime<Utc>>) {
+    let _ = write_json(&runs_path(state_dir), last_runs);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn schedule(name: &str, cron: &str) -> ScheduleConfig {
+        ScheduleConfig {
+            name: name.to_string(),
+            cron: cron.to_string(),
+            task: "Run the tests".to_string(),
+            backend: None,
+            workdir: None,
+            ea: 0,
+        }
+    }
+
+    #[test]
+    fn test_take_due_fires_once_per_occurrence() {
+        let nightly = vec![schedule("nightly", "0 2 * * *")];
+        let at = |day, hour, minute| {
+            Local
+                .with_ymd_and_hms(2026, 4, day, hour, minute, 0)
+                .unwrap()
+        };
+        let mut last_runs = BTreeMap::new();
+
+        // First sight only starts the clock
+        assert!(take_due(&nightly, &mut last_runs, at(16, 23, 0)).is_empty());
+        assert!(take_due(&nightly, &mut last_runs, at(17, 1, 59)).is_empty());
+        let due = take_due(&nightly, &mut last_runs, at(17, 2, 0));
+        assert_eq!(due.len(), 1);
+        assert!(take_due(&nightly, &mut last_runs, at(17, 2, 1)).is_empty());
+        // Several missed nights fire once
+        assert_eq!(take_due(&nightly, &mut last_runs, at(20, 9, 0)).len(), 1);
+
+        assert!(take_due(&[], &mut last_runs, at(20, 9, 0)).is_empty());
+        assert!(last_runs.is_empty(), "removed schedules are forgotten");
+        assert_eq!(agent_name(&due[0], at(17, 2, 0)), "nightly-0417-0200");
+    }
+
+    #[test]
+    fn test_add_and_remove_schedules() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = vec![schedule("nightly", "0 2 * * *")];
+        add_in(dir.path(), schedule("hourly", "0 * * * *"), &config).unwrap();
+        assert!(add_in(dir.path(), schedule("hourly", "5 * * * *"), &config).is_err());
+        assert!(add_in(dir.path(), schedule("nightly", "0 3 * * *"), &config).is_err());
+        assert!(add_in(dir.path(), schedule("bad", "not cron"), &config).is_err());
+
+        let names: Vec<String> = for_ea(&config, 0, dir.path())
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, vec!["nightly", "hourly"]);
+        assert_eq!(
+            for_ea(&config, 1, dir.path()).len(),
+            1,
+            "config schedules are EA-bound"
+        );
+
+        assert!(remove_in(dir.path(), "hourly").unwrap());
+        assert!(!remove_in(dir.path(), "hourly").unwrap());
+        assert!(load_added_in(dir.path()).is_empty());
+    }
+}
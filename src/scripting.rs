@@ -0,0 +1,329 @@
+//! Rhai automation scripts — user hooks that react to agent events
+//!
+//! Each script listed under `[scripting] scripts` may define:
+//!
+//! - `fn on_event(ev)` — called with a map describing an agent event
+//!   (`kind`: "spawned", "exited", "health", or "result"; plus `agent` and
+//!   kind-specific fields such as `from`/`to` or `result`/`detail`)
+//! - `fn every_interval()` — called every `interval_secs`
+//!
+//! Inside hooks, `this` is a map that persists across calls for the life of
+//! the dashboard, and `spawn_agent(name[, task])`, `send(name, text)`,
+//! `kill(name)`, and `notify(text)` queue actions for the dashboard to run
+//! (`spawn` itself is a reserved word in Rhai).
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rhai::{CallFnOptions, Dynamic, Engine, Map, Scope, AST};
+
+use crate::config::{self, ScriptingConfig};
+
+/// Upper bound on Rhai operations per hook call, so a runaway loop cannot
+/// freeze the dashboard.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// Something a script asked the dashboard to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptAction {
+    Spawn { name: String, task: Option<String> },
+    Send { name: String, text: String },
+    Kill { name: String },
+    Notify { text: String },
+}
+
+/// Agent event delivered to `on_event`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptEvent {
+    pub kind: &'static str,
+    /// Short agent name ("ea" for the manager).
+    pub agent: String,
+    pub fields: Vec<(&'static str, String)>,
+}
+
+impl ScriptEvent {
+    pub fn new(kind: &'static str, agent: impl Into<String>) -> Self {
+        Self {
+            kind,
+            agent: agent.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, key: &'static str, value: impl Into<String>) -> Self {
+        self.fields.push((key, value.into()));
+        self
+    }
+
+    fn to_map(&self) -> Map {
+        let mut map = Map::new();
+        map.insert("kind".into(), Dynamic::from(self.kind.to_string()));
+        map.insert("agent".into(), Dynamic::from(self.agent.clone()));
+        for (key, value) in &self.fields {
+            map.insert((*key).into(), Dynamic::from(value.clone()));
+        }
+        map
+    }
+}
+
+struct Script {
+    name: String,
+    ast: AST,
+    scope: Scope<'static>,
+    this: Dynamic,
+}
+
+pub struct ScriptHost {
+    engine: Engine,
+    scripts: Vec<Script>,
+    actions: Arc<Mutex<Vec<ScriptAction>>>,
+    interval: Duration,
+    last_interval: Instant,
+}
+
+impl ScriptHost {
+    /// Compile every configured script. Returns `None` when none are
+    /// configured, plus one message per script that failed to load.
+    pub fn load(config: &ScriptingConfig) -> (Option<Self>, Vec<String>) {
+        if config.scripts.is_empty() {
+            return (None, Vec::new());
+        }
+        let mut host = Self::new(Duration::from_secs(config.interval_secs.max(1)));
+        let mut errors = Vec::new();
+        for path in &config.scripts {
+            let path = config::expand_tilde(path);
+            if let Err(e) = host.add_script(path.clone()) {
+                errors.push(format!("script {}: {}", path.display(), e));
+            }
+        }
+        (Some(host), errors)
+    }
+
+    fn new(interval: Duration) -> Self {
+        let actions: Arc<Mutex<Vec<ScriptAction>>> = Arc::default();
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+
+        let queue = actions.clone();
+        engine.register_fn("spawn_agent", move |name: &str| {
+            push(
+                &queue,
+                ScriptAction::Spawn {
+                    name: name.to_string(),
+                    task: None,
+                },
+            )
+        });
+        let queue = actions.clone();
+        engine.register_fn("spawn_agent", move |name: &str, task: &str| {
+            push(
+                &queue,
+                ScriptAction::Spawn {
+                    name: name.to_string(),
+                    task: Some(task.to_string()),
+                },
+            )
+        });
+        let queue = actions.clone();
+        engine.register_fn("send", move |name: &str, text: &str| {
+            push(
+                &queue,
+                ScriptAction::Send {
+                    name: name.to_string(),
+                    text: text.to_string(),
+                },
+            )
+        });
+        let queue = actions.clone();
+        engine.register_fn("kill", move |name: &str| {
+            push(
+                &queue,
+                ScriptAction::Kill {
+                    name: name.to_string(),
+                },
+            )
+        });
+        let queue = actions.clone();
+        engine.register_fn("notify", move |text: &str| {
+            push(
+                &queue,
+                ScriptAction::Notify {
+                    text: text.to_string(),
+                },
+            )
+        });
+
+        Self {
+            engine,
+            scripts: Vec::new(),
+            actions,
+            interval,
+            last_interval: Instant::now(),
+        }
+    }
+
+    fn add_script(&mut self, path: PathBuf) -> Result<(), String> {
+        let source = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string());
+        self.add_source(name, &source)
+    }
+
+    fn add_source(&mut self, name: String, source: &str) -> Result<(), String> {
+        let ast = self.engine.compile(source).map_err(|e| e.to_string())?;
+        let mut scope = Scope::new();
+        self.engine
+            .run_ast_with_scope(&mut scope, &ast)
+            .map_err(|e| e.to_string())?;
+        self.scripts.push(Script {
+            name,
+            ast,
+            scope,
+            this: Dynamic::from_map(Map::new()),
+        });
+        Ok(())
+    }
+
+    /// Run every script's `on_event` hook. Script errors are returned as
+    /// `Err` entries alongside the queued actions.
+    pub fn on_event(&mut self, event: &ScriptEvent) -> Vec<Result<ScriptAction, String>> {
+        let map = event.to_map();
+        self.call_all("on_event", || vec![Dynamic::from_map(map.clone())])
+    }
+
+    /// Run `every_interval` hooks if the interval has elapsed.
+    pub fn tick(&mut self) -> Vec<Result<ScriptAction, String>> {
+        if self.last_interval.elapsed() < self.interval {
+            return Vec::new();
+        }
+        self.last_interval = Instant::now();
+        self.call_all("every_interval", Vec::new)
+    }
+
+    fn call_all(
+        &mut self,
+        hook: &str,
+        args: impl Fn() -> Vec<Dynamic>,
+    ) -> Vec<Result<ScriptAction, String>> {
+        let mut results = Vec::new();
+        for script in &mut self.scripts {
+            if !script.ast.iter_functions().any(|f| f.name == hook) {
+                continue;
+            }
+            let options = CallFnOptions::new()
+                .eval_ast(false)
+                .bind_this_ptr(&mut script.this);
+            if let Err(e) = self.engine.call_fn_with_options::<Dynamic>(
+                options,
+                &mut script.scope,
+                &script.ast,
+                hook,
+                args(),
+            ) {
+                results.push(Err(format!("script {} {}: {}", script.name, hook, e)));
+            }
+        }
+        let mut queued = self.actions.lock().unwrap_or_else(|e| e.into_inner());
+        results.extend(queued.drain(..).map(Ok));
+        results
+    }
+}
+
+fn push(queue: &Mutex<Vec<ScriptAction>>, action: ScriptAction) {
+    queue.lock().unwrap_or_else(|e| e.into_inner()).push(action);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(source: &str) -> ScriptHost {
+        let mut host = ScriptHost::new(Duration::ZERO);
+        host.add_source("test.rhai".to_string(), source).unwrap();
+        host
+    }
+
+    #[test]
+    fn test_on_event_queues_actions_and_keeps_state() {
+        let mut host = host(
+            r#"
+            fn on_event(ev) {
+                if ev.kind == "result" && ev.result == "blocked" && ev.agent == "tests" {
+                    this.failures = (this.failures ?? 0) + 1;
+                    if this.failures == 2 {
+                        spawn_agent("debugger", "Investigate why tests is blocked: " + ev.detail);
+                        notify("spawned debugger");
+                    }
+                }
+            }
+            "#,
+        );
+        let event = ScriptEvent::new("result", "tests")
+            .with("result", "blocked")
+            .with("detail", "flaky db");
+
+        assert!(host.on_event(&event).is_empty());
+        let actions = host.on_event(&event);
+        assert_eq!(
+            actions,
+            vec![
+                Ok(ScriptAction::Spawn {
+                    name: "debugger".to_string(),
+                    task: Some("Investigate why tests is blocked: flaky db".to_string()),
+                }),
+                Ok(ScriptAction::Notify {
+                    text: "spawned debugger".to_string(),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tick_runs_every_interval_and_skips_missing_hooks() {
+        let mut host = host(r#"fn every_interval() { send("ea", "status?"); kill("old"); }"#);
+        host.add_source("noop.rhai".to_string(), "let x = 1;")
+            .unwrap();
+        assert_eq!(
+            host.tick(),
+            vec![
+                Ok(ScriptAction::Send {
+                    name: "ea".to_string(),
+                    text: "status?".to_string(),
+                }),
+                Ok(ScriptAction::Kill {
+                    name: "old".to_string(),
+                }),
+            ]
+        );
+        assert!(host.on_event(&ScriptEvent::new("spawned", "a")).is_empty());
+    }
+
+    #[test]
+    fn test_script_errors_are_reported_not_fatal() {
+        let mut host = host("fn on_event(ev) { loop {} }");
+        let results = host.on_event(&ScriptEvent::new("spawned", "a"));
+        assert_eq!(results.len(), 1);
+        assert!(results[0].as_ref().unwrap_err().contains("on_event"));
+
+        let mut host = ScriptHost::new(Duration::ZERO);
+        assert!(host.add_source("bad.rhai".to_string(), "fn (").is_err());
+    }
+
+    #[test]
+    fn test_load_without_scripts_is_disabled() {
+        let (host, errors) = ScriptHost::load(&ScriptingConfig::default());
+        assert!(host.is_none());
+        assert!(errors.is_empty());
+
+        let config = ScriptingConfig {
+            scripts: vec!["/nonexistent/omar.rhai".to_string()],
+            ..ScriptingConfig::default()
+        };
+        let (host, errors) = ScriptHost::load(&config);
+        assert!(host.is_some());
+        assert_eq!(errors.len(), 1);
+    }
+}
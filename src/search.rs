@@ -0,0 +1,129 @@
+//! Scrollback search across agent sessions
+
+use anyhow::Result;
+use regex::{Regex, RegexBuilder};
+
+use crate::tmux::TmuxClient;
+
+/// Lines of scrollback searched per pane; matches the `history-limit` set by
+/// `TmuxClient::new_session`.
+pub const SEARCH_HISTORY_LINES: i32 = 10000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch {
+    /// Full tmux session name.
+    pub session: String,
+    /// 1-based line number within the captured scrollback.
+    pub line: usize,
+    pub text: String,
+    /// Surrounding lines (before and after), in pane order.
+    pub context: Vec<String>,
+}
+
+/// Compile a user query case-insensitively. Queries that are not valid
+/// regexes (e.g. `payments.rs(`) are matched literally instead of failing.
+pub fn compile_query(query: &str) -> Result<Regex> {
+    let query = query.trim();
+    if query.is_empty() {
+        anyhow::bail!("search query must not be empty");
+    }
+    let regex = RegexBuilder::new(query)
+        .case_insensitive(true)
+        .build()
+        .or_else(|_| {
+            RegexBuilder::new(&regex::escape(query))
+                .case_insensitive(true)
+                .build()
+        })?;
+    Ok(regex)
+}
+
+/// Find matching lines in `output`, tagging each with `session`.
+pub fn search_text(
+    session: &str,
+    output: &str,
+    pattern: &Regex,
+    context_lines: usize,
+    limit: usize,
+) -> Vec<SearchMatch> {
+    let lines: Vec<&str> = output.lines().collect();
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| pattern.is_match(line))
+        .take(limit)
+        .map(|(idx, line)| {
+            let start = idx.saturating_sub(context_lines);
+            let end = (idx + context_lines + 1).min(lines.len());
+            SearchMatch {
+                session: session.to_string(),
+                line: idx + 1,
+                text: line.trim_end().to_string(),
+                context: lines[start..end]
+                    .iter()
+                    .enumerate()
+                    .filter(|(offset, _)| start + offset != idx)
+                    .map(|(_, l)| l.trim_end().to_string())
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Search the scrollback of every session in `sessions`, stopping once
+/// `limit` matches have been collected. Sessions that vanish mid-search are
+/// skipped.
+pub fn search_sessions(
+    client: &TmuxClient,
+    sessions: &[String],
+    pattern: &Regex,
+    context_lines: usize,
+    limit: usize,
+) -> Vec<SearchMatch> {
+    let mut matches = Vec::new();
+    for session in sessions {
+        if matches.len() >= limit {
+            break;
+        }
+        let Ok(output) = client.capture_pane_plain(session, SEARCH_HISTORY_LINES) else {
+            continue;
+        };
+        matches.extend(search_text(
+            session,
+            &output,
+            pattern,
+            context_lines,
+            limit - matches.len(),
+        ));
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compile_query_falls_back_to_literal() {
+        let re = compile_query("payments.rs(").unwrap();
+        assert!(re.is_match("edited src/payments.rs(line 4)"));
+        assert!(compile_query("  ").is_err());
+        assert!(compile_query("PAYMENTS").unwrap().is_match("payments.rs"));
+    }
+
+    #[test]
+    fn test_search_text_reports_line_and_context() {
+        let output = "one\ntwo\nEdit payments.rs\nthree\nfour\npayments.rs saved\n";
+        let re = compile_query("payments\\.rs").unwrap();
+        let matches = search_text("omar-agent-0-api", output, &re, 1, 10);
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].line, 3);
+        assert_eq!(matches[0].text, "Edit payments.rs");
+        assert_eq!(matches[0].context, vec!["two", "three"]);
+        assert_eq!(matches[1].context, vec!["four"]);
+
+        let limited = search_text("s", output, &re, 0, 1);
+        assert_eq!(limited.len(), 1);
+        assert!(limited[0].context.is_empty());
+    }
+}
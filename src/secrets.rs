@@ -0,0 +1,244 @@
+//! Named secrets injected into a worker's environment (`[secrets]`)
+//!
+//! `spawn_agent` takes secret *names*; omar resolves them at launch from
+//! `secrets.env_file` or a `[secrets.sources]` reference (`pass:<path>`,
+//! `op://...` for the 1Password CLI, `env:<VAR>`) and hands the values to
+//! the session through a private env file that the launch shell sources and
+//! deletes before starting the agent. Values never reach the task prompt,
+//! the tmux command line, or omar's state files — only the names do.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::config::{self, SecretsConfig};
+use crate::paths;
+use crate::sandbox::shell_single_quote;
+
+/// Secret names double as variable names, so they must be valid ones.
+pub(crate) fn is_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// `KEY=VALUE` lines; blank lines, `#` comments, an `export ` prefix, and
+/// one layer of matching quotes are accepted.
+fn parse_env_file(text: &str) -> BTreeMap<String, String> {
+    let mut values = BTreeMap::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        let value = [('"', '"'), ('\'', '\'')]
+            .iter()
+            .find_map(|(open, close)| value.strip_prefix(*open)?.strip_suffix(*close))
+            .unwrap_or(value);
+        values.insert(key.trim().to_string(), value.to_string());
+    }
+    values
+}
+
+fn run_source(program: &str, args: &[&str], name: &str) -> Result<String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run `{}` for secret {}", program, name))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "`{}` could not read secret {}: {}",
+            program,
+            name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // pass prints the password on the first line, metadata after it
+    Ok(stdout.lines().next().unwrap_or_default().to_string())
+}
+
+/// Read one `pass:<path>`, `op://...`, or `env:<VAR>` reference; `name`
+/// labels errors.
+pub fn read_source(name: &str, source: &str) -> Result<String> {
+    if let Some(path) = source.strip_prefix("pass:") {
+        run_source("pass", &["show", path], name)
+    } else if source.starts_with("op://") {
+        run_source("op", &["read", source], name)
+    } else if let Some(var) = source.strip_prefix("env:") {
+        std::env::var(var).map_err(|_| anyhow!("Secret {} refers to unset variable {}", name, var))
+    } else {
+        Err(anyhow!(
+            "Secret {} has an unknown source; use pass:<path>, op://..., or env:<VAR>",
+            name
+        ))
+    }
+}
+
+fn load_env_file(config: &SecretsConfig) -> Result<BTreeMap<String, String>> {
+    match &config.env_file {
+        Some(path) => {
+            let path = config::expand_tilde(path);
+            Ok(parse_env_file(
+                &std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?,
+            ))
+        }
+        None => Ok(BTreeMap::new()),
+    }
+}
+
+fn check_name(name: &str) -> Result<()> {
+    if is_env_name(name) {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Secret name '{}' must be a valid environment variable name",
+            name
+        ))
+    }
+}
+
+/// Check that each of `names` is configured, without reading any value.
+pub fn check_names(config: &SecretsConfig, names: &[String]) -> Result<()> {
+    if names.is_empty() {
+        return Ok(());
+    }
+    let file_values = load_env_file(config)?;
+    for name in names {
+        check_name(name)?;
+        if !config.sources.contains_key(name) && !file_values.contains_key(name) {
+            return Err(anyhow!("Unknown secret '{}'; add it to [secrets]", name));
+        }
+    }
+    Ok(())
+}
+
+/// Look up each of `names`. Errors name the secret, never its value.
+pub fn resolve(config: &SecretsConfig, names: &[String]) -> Result<Vec<(String, String)>> {
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+    let file_values = load_env_file(config)?;
+    names
+        .iter()
+        .map(|name| {
+            check_name(name)?;
+            let value = match config.sources.get(name) {
+                Some(source) => read_source(name, source)?,
+                None => file_values
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Unknown secret '{}'; add it to [secrets]", name))?,
+            };
+            Ok((name.clone(), value))
+        })
+        .collect()
+}
+
+/// Prefix `command` so its shell loads `secrets` from a private file and
+/// deletes it first. Returns `command` unchanged when there are none.
+pub fn inject(secrets: &[(String, String)], command: &str) -> Result<String> {
+    if secrets.is_empty() {
+        return Ok(command.to_string());
+    }
+    let file = write_env_file(secrets)?;
+    let file = shell_single_quote(&file.display().to_string());
+    Ok(format!(
+        "set -a; . {file}; set +a; rm -f {file}; {}",
+        command
+    ))
+}
+
+fn write_env_file(secrets: &[(String, String)]) -> Result<PathBuf> {
+    use std::io::Write;
+    #[cfg(unix)]
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let path = paths::private_temp_dir()?.join(format!("secrets-{}.env", uuid::Uuid::new_v4()));
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options
+        .open(&path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    for (name, value) in secrets {
+        writeln!(file, "{}={}", name, shell_single_quote(value))?;
+    }
+    Ok(path)
+}
+
+/// Remove an env file left behind when the session never started.
+pub fn discard(command: &str) {
+    if let Some(path) = env_file(command) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// The private file an `inject`ed command loads, if it has one.
+pub fn env_file(command: &str) -> Option<&Path> {
+    command
+        .strip_prefix("set -a; . '")
+        .and_then(|rest| rest.split_once('\''))
+        .map(|(path, _)| Path::new(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_and_inject_keep_values_off_the_command_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let env_file = dir.path().join("secrets.env");
+        std::fs::write(
+            &env_file,
+            "# tokens\nexport GITHUB_TOKEN=\"ghp_abc'def\"\nNPM_TOKEN=npm-123\n",
+        )
+        .unwrap();
+        std::env::set_var("OMAR_TEST_SECRET_SOURCE", "from-env");
+        let config = SecretsConfig {
+            env_file: Some(env_file.display().to_string()),
+            sources: BTreeMap::from([(
+                "DEPLOY_KEY".to_string(),
+                "env:OMAR_TEST_SECRET_SOURCE".to_string(),
+            )]),
+        };
+
+        let names = ["GITHUB_TOKEN", "DEPLOY_KEY"].map(String::from);
+        let secrets = resolve(&config, &names).unwrap();
+        assert_eq!(
+            secrets,
+            vec![
+                ("GITHUB_TOKEN".to_string(), "ghp_abc'def".to_string()),
+                ("DEPLOY_KEY".to_string(), "from-env".to_string()),
+            ]
+        );
+        assert!(resolve(&config, &["MISSING".to_string()]).is_err());
+        assert!(resolve(&config, &["BAD-NAME".to_string()]).is_err());
+
+        let command = inject(&secrets, "printenv GITHUB_TOKEN DEPLOY_KEY").unwrap();
+        assert!(!command.contains("ghp_abc") && !command.contains("from-env"));
+        let output = Command::new("sh").args(["-c", &command]).output().unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "ghp_abc'def\nfrom-env\n"
+        );
+        let path = command
+            .strip_prefix("set -a; . '")
+            .and_then(|rest| rest.split_once('\''))
+            .unwrap()
+            .0;
+        assert!(!Path::new(path).exists());
+        assert_eq!(inject(&[], "claude").unwrap(), "claude");
+    }
+}
@@ -0,0 +1,452 @@
+//! Session snapshots — tmux polling (list-sessions, capture-pane, health
+//! checks) kept off the dashboard's event loop
+//!
+//! A `Poller` captures every OMAR session, local and on `[[hosts]]`, into a
+//! `Snapshot`. The dashboard moves it onto a background thread that
+//! publishes into a shared `Arc<RwLock<Snapshot>>`; `App::refresh` reads the
+//! latest snapshot and wakes the poller for the next one, so a slow tmux or
+//! SSH round trip never holds the `App` lock. Without a background poller
+//! (tests, before the dashboard starts) `App::refresh` polls inline.
+//!
+//! Agents marked hot are captured deeper on every poll; cold ones are only
+//! re-checked every `COLD_POLL_EVERY` polls and keep their last state in
+//! between. config.toml is re-read whenever it changes, so health patterns
+//! (`set_health_patterns`), `[[hosts]]` and the session prefix apply
+//! without a restart.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use std::time::SystemTime;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, HostConfig, Multiplexer};
+use crate::health_history::{self, History};
+use crate::tmux::{
+    self, ActivityDetector, AgentResult, HealthChecker, HealthPlugin, HealthState, Mux, RateLimit,
+    RateLimitDetector, RemoteHost, Session, TmuxClient,
+};
+
+/// Pane lines captured for a hot agent's health check (others get 50).
+const HOT_CAPTURE_LINES: i32 = 200;
+
+/// Polls between health checks of a cold agent.
+const COLD_POLL_EVERY: u64 = 10;
+
+/// How much monitoring effort an agent gets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    #[default]
+    Normal,
+    Hot,
+    Cold,
+}
+
+impl Priority {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim() {
+            "normal" => Ok(Priority::Normal),
+            "hot" => Ok(Priority::Hot),
+            "cold" => Ok(Priority::Cold),
+            other => Err(anyhow!(
+                "Unknown priority '{}'. Supported: normal, hot, cold",
+                other
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Priority::Normal => "normal",
+            Priority::Hot => "hot",
+            Priority::Cold => "cold",
+        }
+    }
+
+    /// The dashboard toggle cycles normal → hot → cold.
+    pub fn next(self) -> Self {
+        match self {
+            Priority::Normal => Priority::Hot,
+            Priority::Hot => Priority::Cold,
+            Priority::Cold => Priority::Normal,
+        }
+    }
+
+    /// Whether a session at this priority is health-checked on poll number
+    /// `poll`.
+    fn due(self, poll: u64) -> bool {
+        self != Priority::Cold || poll.is_multiple_of(COLD_POLL_EVERY)
+    }
+
+    fn capture_lines(self) -> i32 {
+        match self {
+            Priority::Hot => HOT_CAPTURE_LINES,
+            _ => 50,
+        }
+    }
+}
+
+/// Non-normal priorities by session name, written by the dashboard from the
+/// EA's `agent_priorities.json` and read by the poller.
+pub type SharedPriorities = Arc<RwLock<HashMap<String, Priority>>>;
+
+/// Every OMAR session at one point in time.
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    /// All local sessions, OMAR-owned or not
+    pub sessions: Vec<Session>,
+    /// Health of local OMAR sessions
+    pub health: HashMap<String, HealthState>,
+    /// Completion marker behind each Finished/Blocked local session
+    pub results: HashMap<String, AgentResult>,
+    /// Open question of each idle or Blocked local session that asked one
+    pub questions: HashMap<String, String>,
+    /// Rate-limit message behind each RateLimited local session
+    pub rate_limits: HashMap<String, RateLimit>,
+    /// Backend (pane command) of local sessions, known once
+    /// `[health.backends]` or per-backend rate-limit patterns are set
+    pub backends: HashMap<String, String>,
+    /// Recent health transitions of local OMAR sessions
+    pub history: History,
+    /// OMAR sessions on `[[hosts]]` (every EA), tagged with their host
+    pub remote: Vec<(Session, HealthState)>,
+    /// Listing local sessions failed
+    pub error: Option<String>,
+}
+
+pub type SharedSnapshot = Arc<RwLock<Snapshot>>;
+
+struct RemotePoll {
+    client: TmuxClient,
+    health: HealthChecker,
+}
+
+pub struct Poller {
+    base_prefix: String,
+    /// `dashboard.multiplexer` the local client and checker were built for
+    multiplexer: Multiplexer,
+    client: Arc<dyn Mux>,
+    health: HealthChecker,
+    /// `[[hosts]]` the remote pollers were built from
+    hosts: Vec<HostConfig>,
+    remote: Vec<RemotePoll>,
+    /// Where health history is mirrored for the MCP server, and what was
+    /// last written there
+    history_dir: Option<PathBuf>,
+    saved_history: History,
+    priorities: SharedPriorities,
+    /// Polls so far, for scheduling cold agents
+    polls: u64,
+    /// Config file watched for changes, and its last seen mtime
+    config_path: Option<PathBuf>,
+    config_modified: Option<SystemTime>,
+}
+
+impl Poller {
+    pub fn new(config: &Config) -> Self {
+        let base_prefix = config.dashboard.session_prefix.clone();
+        let client = tmux::mux_for(config.dashboard.multiplexer, &base_prefix);
+        Self {
+            remote: remote_polls(config),
+            hosts: config.hosts.clone(),
+            health: local_health(config, client.clone()),
+            multiplexer: config.dashboard.multiplexer,
+            base_prefix,
+            client,
+            history_dir: None,
+            saved_history: History::new(),
+            priorities: SharedPriorities::default(),
+            polls: 0,
+            config_path: None,
+            config_modified: None,
+        }
+    }
+
+    /// Reload the config from `path` whenever it changes.
+    pub fn with_config_reload(mut self, path: PathBuf) -> Self {
+        self.config_modified = modified(&path);
+        self.config_path = Some(path);
+        self
+    }
+
+    fn reload_config(&mut self) {
+        let Some(path) = &self.config_path else {
+            return;
+        };
+        let current = modified(path);
+        if current.is_none() || current == self.config_modified {
+            return;
+        }
+        self.config_modified = current;
+        // A half-written or invalid file keeps the config in use
+        let Ok(config) = Config::load(Some(&path.to_string_lossy())) else {
+            return;
+        };
+        let prefix_changed = config.dashboard.session_prefix != self.base_prefix;
+        let mux_changed = config.dashboard.multiplexer != self.multiplexer;
+        if prefix_changed || mux_changed {
+            self.base_prefix = config.dashboard.session_prefix.clone();
+            self.multiplexer = config.dashboard.multiplexer;
+            self.client = tmux::mux_for(self.multiplexer, &self.base_prefix);
+        }
+        // Health state is keyed by full session name, so it survives a
+        // prefix change; another backend starts over
+        if mux_changed {
+            self.health = local_health(&config, self.client.clone());
+        }
+        if prefix_changed || config.hosts != self.hosts {
+            self.remote = remote_polls(&config);
+            self.hosts = config.hosts.clone();
+        }
+        let rate_limit = &config.health.rate_limit;
+        self.health
+            .set_rate_limits(RateLimitDetector::from_config(rate_limit));
+        self.health
+            .set_plugin(HealthPlugin::from_config(config.health.checker().as_ref()));
+        self.health
+            .set_activity(ActivityDetector::from_config(&config.health));
+        for remote in &mut self.remote {
+            remote
+                .health
+                .set_rate_limits(RateLimitDetector::from_config(rate_limit));
+            remote
+                .health
+                .set_activity(ActivityDetector::from_config(&config.health));
+        }
+    }
+
+    /// Read per-agent priorities from `priorities`.
+    pub fn with_priorities(mut self, priorities: SharedPriorities) -> Self {
+        self.priorities = priorities;
+        self
+    }
+
+    /// Mirror health history into `omar_dir` whenever it changes.
+    pub fn with_history_dir(mut self, omar_dir: PathBuf) -> Self {
+        self.history_dir = Some(omar_dir);
+        self
+    }
+
+    /// Capture every session. Only OMAR-owned sessions are health-checked,
+    /// so unrelated user sessions never cost a `capture-pane`.
+    pub fn poll(&mut self) -> Snapshot {
+        self.reload_config();
+        let mut snapshot = Snapshot::default();
+        let poll = self.polls;
+        self.polls += 1;
+        let priorities = self
+            .priorities
+            .read()
+            .map(|p| p.clone())
+            .unwrap_or_default();
+        match self.client.list_all_sessions() {
+            Ok(sessions) => {
+                let owned: Vec<&Session> = sessions
+                    .iter()
+                    .filter(|s| {
+                        self.base_prefix.is_empty() || s.name.starts_with(&self.base_prefix)
+                    })
+                    .collect();
+                let names: Vec<String> = owned.iter().map(|s| s.name.clone()).collect();
+                self.health.retain_sessions(&names);
+                for session in owned {
+                    let name = session.name.clone();
+                    let priority = priorities.get(&name).copied().unwrap_or_default();
+                    let skipped = self
+                        .health
+                        .last_state(&name)
+                        .filter(|_| !priority.due(poll));
+                    let state = match skipped {
+                        Some(state) => state,
+                        None => self.health.check_session(session, priority.capture_lines()),
+                    };
+                    if let Some(result) = self.health.result(&name) {
+                        snapshot.results.insert(name.clone(), result.clone());
+                    }
+                    if let Some(question) = self.health.question(&name) {
+                        snapshot
+                            .questions
+                            .insert(name.clone(), question.to_string());
+                    }
+                    if let Some(limit) = self.health.rate_limit(&name) {
+                        snapshot.rate_limits.insert(name.clone(), limit.clone());
+                    }
+                    if let Some(backend) = self.health.backend(&name) {
+                        snapshot.backends.insert(name.clone(), backend.to_string());
+                    }
+                    snapshot
+                        .history
+                        .insert(name.clone(), self.health.history(&name));
+                    snapshot.health.insert(name, state);
+                }
+                snapshot.sessions = sessions;
+            }
+            Err(e) => snapshot.error = Some(e.to_string()),
+        }
+        if let Some(dir) = &self.history_dir {
+            if snapshot.history != self.saved_history
+                && health_history::save(dir, &snapshot.history).is_ok()
+            {
+                self.saved_history = snapshot.history.clone();
+            }
+        }
+        // Unreachable hosts are skipped
+        for remote in &mut self.remote {
+            let Ok(sessions) = remote.client.list_sessions() else {
+                continue;
+            };
+            let names: Vec<String> = sessions.iter().map(|s| s.name.clone()).collect();
+            remote.health.retain_sessions(&names);
+            for session in sessions {
+                let state = remote.health.check(&session.name);
+                snapshot.remote.push((session, state));
+            }
+        }
+        snapshot
+    }
+
+    /// Poll on a background thread every `interval`, or sooner when woken.
+    /// The thread exits once the returned handle is dropped.
+    pub fn spawn(mut self, shared: SharedSnapshot, interval: Duration) -> PollerHandle {
+        let (wake, wakeups) = mpsc::sync_channel(1);
+        std::thread::spawn(move || loop {
+            let snapshot = self.poll();
+            if let Ok(mut slot) = shared.write() {
+                *slot = snapshot;
+            }
+            if !wait_for_wake(&wakeups, interval) {
+                break;
+            }
+        });
+        PollerHandle { wake }
+    }
+}
+
+/// Health checker for local sessions on `client`.
+fn local_health(config: &Config, client: Arc<dyn Mux>) -> HealthChecker {
+    HealthChecker::new(client, config.health.idle_warning)
+        .with_plugin(HealthPlugin::from_config(config.health.checker().as_ref()))
+        .with_rate_limits(RateLimitDetector::from_config(&config.health.rate_limit))
+        .with_activity(ActivityDetector::from_config(&config.health))
+}
+
+/// A poller per `[[hosts]]` entry.
+fn remote_polls(config: &Config) -> Vec<RemotePoll> {
+    config
+        .hosts
+        .iter()
+        .map(|host| {
+            let client =
+                TmuxClient::new(&config.dashboard.session_prefix).with_remote(RemoteHost {
+                    name: host.name.clone(),
+                    ssh: host.ssh.clone(),
+                    control_path: host.control_path.as_deref().map(|path| {
+                        crate::config::expand_tilde(path)
+                            .to_string_lossy()
+                            .to_string()
+                    }),
+                });
+            let health = HealthChecker::new(Arc::new(client.clone()), config.health.idle_warning)
+                .with_rate_limits(RateLimitDetector::from_config(&config.health.rate_limit))
+                .with_activity(ActivityDetector::from_config(&config.health));
+            RemotePoll { client, health }
+        })
+        .collect()
+}
+
+pub(crate) fn modified(path: &std::path::Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// False once every handle is gone.
+fn wait_for_wake(wakeups: &Receiver<()>, interval: Duration) -> bool {
+    match wakeups.recv_timeout(interval) {
+        Ok(()) | Err(RecvTimeoutError::Timeout) => true,
+        Err(RecvTimeoutError::Disconnected) => false,
+    }
+}
+
+pub struct PollerHandle {
+    wake: SyncSender<()>,
+}
+
+impl PollerHandle {
+    /// Ask for a fresh snapshot now. Wakes coalesce while a poll is pending.
+    pub fn wake(&self) {
+        let _ = self.wake.try_send(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wake_coalesces_and_drop_stops_the_poller() {
+        let (wake, wakeups) = mpsc::sync_channel(1);
+        let handle = PollerHandle { wake };
+        handle.wake();
+        handle.wake();
+        assert!(wait_for_wake(&wakeups, Duration::from_secs(5)));
+        // The second wake was coalesced into the first
+        assert!(wakeups.try_recv().is_err());
+        assert!(wait_for_wake(&wakeups, Duration::from_millis(10)));
+        drop(handle);
+        assert!(!wait_for_wake(&wakeups, Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_priorities_schedule_checks_and_persist() {
+        assert!((0..COLD_POLL_EVERY).all(|poll| Priority::Hot.due(poll)));
+        let cold: Vec<u64> = (0..25).filter(|&poll| Priority::Cold.due(poll)).collect();
+        assert_eq!(cold, vec![0, 10, 20]);
+        assert_eq!(Priority::Hot.capture_lines(), HOT_CAPTURE_LINES);
+        assert_eq!(Priority::Normal.next().next().next(), Priority::Normal);
+        assert_eq!(Priority::parse("cold").unwrap(), Priority::Cold);
+        assert!(Priority::parse("warm").is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        crate::memory::save_agent_priority_in(dir.path(), "omar-agent-0-api", Priority::Hot);
+        crate::memory::save_agent_priority_in(dir.path(), "omar-agent-0-docs", Priority::Cold);
+        crate::memory::save_agent_priority_in(dir.path(), "omar-agent-0-docs", Priority::Normal);
+        assert_eq!(
+            crate::memory::load_agent_priorities_from(dir.path()),
+            HashMap::from([("omar-agent-0-api".to_string(), Priority::Hot)])
+        );
+    }
+
+    #[test]
+    fn test_config_reload_picks_up_hosts_prefix_and_multiplexer() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "").unwrap();
+        let config = Config::load(Some(&path.to_string_lossy())).unwrap();
+        let mut poller = Poller::new(&config).with_config_reload(path.clone());
+        assert!(poller.remote.is_empty());
+
+        std::fs::write(
+            &path,
+            "[dashboard]\nsession_prefix = \"team-\"\n\n\
+             [[hosts]]\nname = \"build1\"\nssh = \"ci@build1\"\n",
+        )
+        .unwrap();
+        // Force a reload even within the file system's mtime granularity
+        poller.config_modified = None;
+        poller.reload_config();
+        assert_eq!(poller.base_prefix, "team-");
+        assert_eq!(poller.remote.len(), 1);
+        assert_eq!(poller.remote[0].client.host(), Some("build1"));
+        assert_eq!(poller.client.backend(), "tmux");
+
+        std::fs::write(&path, "[dashboard]\nmultiplexer = \"screen\"\n").unwrap();
+        poller.config_modified = None;
+        poller.reload_config();
+        assert_eq!(poller.client.backend(), "screen");
+        assert_eq!(poller.client.prefix(), poller.base_prefix);
+    }
+}
@@ -0,0 +1,428 @@
+//! Spawn wizard form state for the dashboard `n` popup
+
+use std::path::Path;
+
+use crate::config::{self, ProfileConfig};
+use crate::metrics::{self, AgentRunRecord, Estimate};
+
+/// Backend presets cycled with ←/→ on the command field. The first entry
+/// keeps the configured default command.
+pub const BACKEND_PRESETS: &[&str] = &["default", "claude", "codex", "cursor", "opencode", "agy"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnField {
+    Name,
+    Profile,
+    Command,
+    Workdir,
+    Task,
+    Parent,
+    Sandbox,
+}
+
+impl SpawnField {
+    pub const ALL: [SpawnField; 7] = [
+        SpawnField::Name,
+        SpawnField::Profile,
+        SpawnField::Command,
+        SpawnField::Workdir,
+        SpawnField::Task,
+        SpawnField::Parent,
+        SpawnField::Sandbox,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SpawnField::Name => "Name",
+            SpawnField::Profile => "Profile",
+            SpawnField::Command => "Command",
+            SpawnField::Workdir => "Workdir",
+            SpawnField::Task => "Task",
+            SpawnField::Parent => "Parent",
+            SpawnField::Sandbox => "Sandbox",
+        }
+    }
+}
+
+/// A validated spawn request built from the form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpawnRequest {
+    /// Short agent name; `None` auto-generates one.
+    pub name: Option<String>,
+    pub command: String,
+    pub workdir: String,
+    pub task: Option<String>,
+    /// Short parent name, or "ea" for the manager.
+    pub parent: String,
+    pub sandbox: bool,
+    /// `[[profiles]]` entry supplying the sandbox runtime and environment
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SpawnForm {
+    pub field: SpawnField,
+    pub name: String,
+    pub command: String,
+    pub workdir: String,
+    pub task: String,
+    /// Parent choices: "ea" first, then the active EA's agents.
+    pub parents: Vec<String>,
+    pub parent_idx: usize,
+    pub sandbox: bool,
+    /// False when `sandbox.runtime` is "none"; the toggle is then inert.
+    pub sandbox_available: bool,
+    /// The sandbox exposes host credentials (`sandbox.credentials = "on"`)
+    pub shares_credentials: bool,
+    /// Configured profiles; `profile_idx` 0 is none, otherwise index + 1
+    profiles: Vec<ProfileConfig>,
+    profile_idx: usize,
+    default_command: String,
+    default_workdir: String,
+    /// Whether `sandbox.runtime` is set, before any profile override
+    runtime_available: bool,
+    preset_idx: usize,
+    /// Finished runs used to estimate the agent being configured.
+    history: Vec<AgentRunRecord>,
+}
+
+impl SpawnForm {
+    pub fn new(
+        default_command: &str,
+        workdir: &str,
+        parents: Vec<String>,
+        parent: &str,
+        sandbox_available: bool,
+    ) -> Self {
+        let parent_idx = parents.iter().position(|p| p == parent).unwrap_or(0);
+        Self {
+            field: SpawnField::Name,
+            name: String::new(),
+            command: default_command.to_string(),
+            workdir: workdir.to_string(),
+            task: String::new(),
+            parents,
+            parent_idx,
+            sandbox: sandbox_available,
+            sandbox_available,
+            shares_credentials: false,
+            profiles: Vec::new(),
+            profile_idx: 0,
+            default_command: default_command.to_string(),
+            default_workdir: workdir.to_string(),
+            runtime_available: sandbox_available,
+            preset_idx: 0,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn with_history(mut self, history: Vec<AgentRunRecord>) -> Self {
+        self.history = history;
+        self
+    }
+
+    pub fn with_profiles(mut self, profiles: Vec<ProfileConfig>) -> Self {
+        self.profiles = profiles;
+        self
+    }
+
+    pub fn has_profiles(&self) -> bool {
+        !self.profiles.is_empty()
+    }
+
+    pub fn profile(&self) -> Option<&ProfileConfig> {
+        self.profile_idx
+            .checked_sub(1)
+            .and_then(|idx| self.profiles.get(idx))
+    }
+
+    /// Fill command, workdir, and sandbox from the selected profile, or the
+    /// defaults when none is selected.
+    fn apply_profile(&mut self) {
+        let profile = self.profile().cloned().unwrap_or_default();
+        self.command = profile
+            .command
+            .unwrap_or_else(|| self.default_command.clone());
+        self.workdir = profile
+            .workdir
+            .unwrap_or_else(|| self.default_workdir.clone());
+        self.sandbox_available = profile
+            .sandbox
+            .map_or(self.runtime_available, |runtime| runtime != "none");
+        self.sandbox = self.sandbox_available;
+        self.preset_idx = 0;
+    }
+
+    pub fn sharing_credentials(mut self, shares: bool) -> Self {
+        self.shares_credentials = shares;
+        self
+    }
+
+    /// Duration/cost estimate for the name and task entered so far.
+    pub fn estimate(&self) -> Option<Estimate> {
+        metrics::estimate(&self.history, &[self.name.trim()], &self.task)
+    }
+
+    pub fn next_field(&mut self) {
+        let idx = SpawnField::ALL
+            .iter()
+            .position(|f| *f == self.field)
+            .unwrap_or(0);
+        self.field = SpawnField::ALL[(idx + 1) % SpawnField::ALL.len()];
+    }
+
+    pub fn prev_field(&mut self) {
+        let idx = SpawnField::ALL
+            .iter()
+            .position(|f| *f == self.field)
+            .unwrap_or(0);
+        self.field = SpawnField::ALL[(idx + SpawnField::ALL.len() - 1) % SpawnField::ALL.len()];
+    }
+
+    fn text_mut(&mut self) -> Option<&mut String> {
+        match self.field {
+            SpawnField::Name => Some(&mut self.name),
+            SpawnField::Command => Some(&mut self.command),
+            SpawnField::Workdir => Some(&mut self.workdir),
+            SpawnField::Task => Some(&mut self.task),
+            SpawnField::Profile | SpawnField::Parent | SpawnField::Sandbox => None,
+        }
+    }
+
+    pub fn insert(&mut self, c: char) {
+        if self.field == SpawnField::Sandbox && c == ' ' {
+            self.toggle_sandbox();
+        } else if let Some(text) = self.text_mut() {
+            text.push(c);
+        }
+    }
+
+    /// Append pasted text to the focused text field; choice fields ignore it.
+    pub fn paste(&mut self, text: &str) {
+        if let Some(field) = self.text_mut() {
+            field.push_str(text);
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(text) = self.text_mut() {
+            text.pop();
+        }
+    }
+
+    /// ←/→ on a choice field: cycle profiles, backend presets, parents, or
+    /// sandbox.
+    pub fn cycle(&mut self, forward: bool) {
+        let step = |idx: usize, len: usize| {
+            if forward {
+                (idx + 1) % len
+            } else {
+                (idx + len - 1) % len
+            }
+        };
+        match self.field {
+            SpawnField::Profile if !self.profiles.is_empty() => {
+                self.profile_idx = step(self.profile_idx, self.profiles.len() + 1);
+                self.apply_profile();
+            }
+            SpawnField::Command => {
+                self.preset_idx = step(self.preset_idx, BACKEND_PRESETS.len());
+                self.command = match BACKEND_PRESETS[self.preset_idx] {
+                    "default" => self.default_command.clone(),
+                    backend => config::resolve_backend(backend).unwrap_or_default(),
+                };
+            }
+            SpawnField::Parent if !self.parents.is_empty() => {
+                self.parent_idx = step(self.parent_idx, self.parents.len());
+            }
+            SpawnField::Sandbox => self.toggle_sandbox(),
+            _ => {}
+        }
+    }
+
+    pub fn toggle_sandbox(&mut self) {
+        if self.sandbox_available {
+            self.sandbox = !self.sandbox;
+        }
+    }
+
+    pub fn parent(&self) -> &str {
+        self.parents
+            .get(self.parent_idx)
+            .map(String::as_str)
+            .unwrap_or("ea")
+    }
+
+    /// Tab on the workdir field: complete the last path component.
+    pub fn complete_workdir(&mut self) {
+        if let Some(completed) = complete_path(&self.workdir) {
+            self.workdir = completed;
+        }
+    }
+
+    /// Validate the form into a request.
+    pub fn request(&self) -> Result<SpawnRequest, String> {
+        let name = self.name.trim();
+        if name
+            .chars()
+            .any(|c| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+        {
+            return Err("Name may only contain letters, digits, '-' and '_'".to_string());
+        }
+        let command = self.command.trim();
+        if command.is_empty() {
+            return Err("Command must not be empty".to_string());
+        }
+        let workdir = self.workdir.trim();
+        let workdir = if workdir.is_empty() { "." } else { workdir };
+        let resolved = config::expand_tilde(workdir);
+        if !resolved.is_dir() {
+            return Err(format!("Workdir '{}' is not a directory", workdir));
+        }
+        let task = self.task.trim();
+        Ok(SpawnRequest {
+            name: (!name.is_empty()).then(|| name.to_string()),
+            command: command.to_string(),
+            workdir: resolved.to_string_lossy().to_string(),
+            task: (!task.is_empty()).then(|| task.to_string()),
+            parent: self.parent().to_string(),
+            sandbox: self.sandbox_available && self.sandbox,
+            profile: self.profile().map(|profile| profile.name.clone()),
+        })
+    }
+}
+
+/// Complete `input` to the longest common prefix of matching directories.
+/// A unique match gets a trailing `/`. Returns `None` when nothing matches.
+fn complete_path(input: &str) -> Option<String> {
+    let (dir_part, prefix) = match input.rfind('/') {
+        Some(idx) => (&input[..=idx], &input[idx + 1..]),
+        None => ("", input),
+    };
+    let dir = if dir_part.is_empty() {
+        config::expand_tilde(".")
+    } else {
+        config::expand_tilde(dir_part)
+    };
+    let mut matches: Vec<String> = std::fs::read_dir(Path::new(&dir))
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| {
+            name.starts_with(prefix) && (!name.starts_with('.') || prefix.starts_with('.'))
+        })
+        .collect();
+    matches.sort();
+    let first = matches.first()?;
+    if matches.len() == 1 {
+        return Some(format!("{}{}/", dir_part, first));
+    }
+    let common = matches.iter().skip(1).fold(first.clone(), |acc, name| {
+        acc.chars()
+            .zip(name.chars())
+            .take_while(|(a, b)| a == b)
+            .map(|(a, _)| a)
+            .collect()
+    });
+    Some(format!("{}{}", dir_part, common))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form() -> SpawnForm {
+        SpawnForm::new(
+            "claude",
+            ".",
+            vec!["ea".to_string(), "pm".to_string()],
+            "pm",
+            false,
+        )
+    }
+
+    #[test]
+    fn test_complete_path_extends_unique_and_common_prefixes() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["service-api", "service-web", "docs"] {
+            std::fs::create_dir(dir.path().join(name)).unwrap();
+        }
+        std::fs::write(dir.path().join("dotfile"), "").unwrap();
+        let base = format!("{}/", dir.path().display());
+
+        assert_eq!(
+            complete_path(&format!("{}d", base)),
+            Some(format!("{}docs/", base))
+        );
+        assert_eq!(
+            complete_path(&format!("{}s", base)),
+            Some(format!("{}service-", base))
+        );
+        assert_eq!(complete_path(&format!("{}zzz", base)), None);
+    }
+
+    #[test]
+    fn test_cycle_presets_parents_and_sandbox() {
+        let mut form = form();
+        assert_eq!(form.parent(), "pm");
+        form.field = SpawnField::Command;
+        form.cycle(true);
+        assert_eq!(form.command, "claude --dangerously-skip-permissions");
+        form.cycle(false);
+        assert_eq!(form.command, "claude");
+
+        form.field = SpawnField::Parent;
+        form.cycle(true);
+        assert_eq!(form.parent(), "ea");
+
+        form.field = SpawnField::Sandbox;
+        form.insert(' ');
+        assert!(!form.sandbox, "sandbox toggle is inert without a runtime");
+    }
+
+    #[test]
+    fn test_profile_fills_form_and_request() {
+        let mut form = form().with_profiles(vec![ProfileConfig {
+            name: "frontend".to_string(),
+            command: Some("codex".to_string()),
+            sandbox: Some("bwrap".to_string()),
+            ..Default::default()
+        }]);
+        form.field = SpawnField::Profile;
+        form.cycle(true);
+        assert_eq!(form.command, "codex");
+        assert!(form.sandbox_available && form.sandbox);
+        let request = form.request().unwrap();
+        assert_eq!(request.profile.as_deref(), Some("frontend"));
+        assert!(request.sandbox);
+
+        form.cycle(true);
+        assert!(form.profile().is_none());
+        assert_eq!(form.command, "claude");
+        assert!(!form.sandbox_available);
+    }
+
+    #[test]
+    fn test_request_validates_fields() {
+        let mut form = form();
+        for c in "api-1".chars() {
+            form.insert(c);
+        }
+        form.field = SpawnField::Task;
+        for c in "  fix tests ".chars() {
+            form.insert(c);
+        }
+        let request = form.request().unwrap();
+        assert_eq!(request.name.as_deref(), Some("api-1"));
+        assert_eq!(request.task.as_deref(), Some("fix tests"));
+        assert_eq!(request.parent, "pm");
+        assert!(!request.sandbox);
+
+        form.name = "bad name".to_string();
+        assert!(form.request().is_err());
+        form.name.clear();
+        form.workdir = "/nonexistent/omar/dir".to_string();
+        assert!(form.request().unwrap_err().contains("not a directory"));
+    }
+}
@@ -0,0 +1,318 @@
+//! Worker pool gate — `spawn_agent` requests beyond `agent.max_concurrent`
+//! wait in `<state_dir>/spawn_queue.json` and are started by the dashboard,
+//! highest priority then oldest first, when a worker finishes or is killed
+//!
+//! Requests with an unmet `wait_for` (a file to exist, or another agent to
+//! complete or exit) wait here too, and are skipped until it is met.
+//!
+//! Callers serialize read-modify-write access with the MCP state lock.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::tmux::{AgentResult, Mux, ResultKind};
+
+/// What an agent needs before it starts: `path` to exist, or `agent` to
+/// reach `state`. Exactly one of `path` and `agent` is set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WaitFor {
+    /// File or directory; relative paths are resolved against the workdir
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Agent name in the same EA
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    /// Only with `agent`; defaults to `complete`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<WaitState>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WaitState {
+    /// The agent printed `[TASK COMPLETE]`
+    #[default]
+    Complete,
+    /// The agent's session is gone (and it is not queued either)
+    Exited,
+}
+
+impl WaitFor {
+    pub fn validate(&self) -> std::result::Result<(), &'static str> {
+        match (&self.path, &self.agent) {
+            (Some(_), Some(_)) => Err("wait_for takes either path or agent, not both"),
+            (None, None) => Err("wait_for needs a path or an agent"),
+            (Some(path), None) if path.trim().is_empty() => Err("wait_for path must not be empty"),
+            (None, Some(agent)) if agent.trim().is_empty() => {
+                Err("wait_for agent must not be empty")
+            }
+            (Some(_), None) if self.state.is_some() => {
+                Err("wait_for state only applies to an agent")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Resolve a relative `path` against `workdir`, so the check does not
+    /// depend on where the dispatcher runs.
+    pub fn resolved(mut self, workdir: &Path) -> Self {
+        if let Some(path) = self.path.as_mut() {
+            *path = workdir
+                .join(crate::config::expand_tilde(path.trim()))
+                .to_string_lossy()
+                .into_owned();
+        }
+        self
+    }
+
+    /// `agent_reached(name, state)` answers for agent dependencies.
+    pub fn is_met(&self, agent_reached: impl Fn(&str, WaitState) -> bool) -> bool {
+        match (&self.path, &self.agent) {
+            (Some(path), _) => Path::new(path).exists(),
+            (None, Some(agent)) => agent_reached(agent.trim(), self.state.unwrap_or_default()),
+            (None, None) => true,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match (&self.path, &self.agent) {
+            (Some(path), _) => path.clone(),
+            (None, Some(agent)) => match self.state.unwrap_or_default() {
+                WaitState::Complete => format!("{} to complete", agent),
+                WaitState::Exited => format!("{} to exit", agent),
+            },
+            (None, None) => String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedSpawn {
+    /// Short agent name
+    pub agent: String,
+    /// Original `spawn_agent` arguments, replayed when a slot frees up
+    pub args: Value,
+    pub queued_at: String,
+    /// Raised by bumping from the dashboard; higher starts sooner
+    #[serde(default)]
+    pub priority: u32,
+}
+
+impl QueuedSpawn {
+    /// The request's dependency, if it declared one
+    pub fn wait_for(&self) -> Option<WaitFor> {
+        serde_json::from_value(self.args.get("wait_for")?.clone()).ok()
+    }
+}
+
+fn queue_path(state_dir: &Path) -> PathBuf {
+    state_dir.join("spawn_queue.json")
+}
+
+pub fn load_queue_in(state_dir: &Path) -> Vec<QueuedSpawn> {
+    fs::read_to_string(queue_path(state_dir))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save_queue_in(state_dir: &Path, queue: &[QueuedSpawn]) -> Result<()> {
+    let path = queue_path(state_dir);
+    let tmp = path.with_extension("json.tmp");
+    fs::create_dir_all(state_dir)?;
+    fs::write(&tmp, serde_json::to_string_pretty(queue)?)?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// Append a request; returns its 1-based queue position.
+pub fn enqueue_in(state_dir: &Path, agent: &str, args: Value) -> Result<usize> {
+    let mut queue = load_queue_in(state_dir);
+    if queue.iter().any(|queued| queued.agent == agent) {
+        anyhow::bail!("Agent '{}' is already queued", agent);
+    }
+    queue.push(QueuedSpawn {
+        agent: agent.to_string(),
+        args,
+        queued_at: chrono::Utc::now().to_rfc3339(),
+        priority: 0,
+    });
+    save_queue_in(state_dir, &queue)?;
+    Ok(queue.len())
+}
+
+/// Remove and return the first request `ready` accepts, so one waiting on
+/// a dependency does not hold up those behind it.
+pub fn pop_first_in(
+    state_dir: &Path,
+    ready: impl Fn(&QueuedSpawn) -> bool,
+) -> Result<Option<QueuedSpawn>> {
+    let mut queue = load_queue_in(state_dir);
+    let Some(idx) = queue.iter().position(ready) else {
+        return Ok(None);
+    };
+    let next = queue.remove(idx);
+    save_queue_in(state_dir, &queue)?;
+    Ok(Some(next))
+}
+
+/// Raise a queued request's priority by one, moving it ahead of every
+/// request it now outranks. Returns its new 1-based position.
+pub fn bump_in(state_dir: &Path, agent: &str) -> Result<Option<usize>> {
+    let mut queue = load_queue_in(state_dir);
+    let Some(queued) = queue.iter_mut().find(|queued| queued.agent == agent) else {
+        return Ok(None);
+    };
+    queued.priority += 1;
+    // Stable: equal priorities keep their FIFO order
+    queue.sort_by_key(|queued| std::cmp::Reverse(queued.priority));
+    save_queue_in(state_dir, &queue)?;
+    Ok(queue
+        .iter()
+        .position(|queued| queued.agent == agent)
+        .map(|idx| idx + 1))
+}
+
+/// Drop a queued request. Returns false if `agent` was not queued.
+pub fn remove_in(state_dir: &Path, agent: &str) -> Result<bool> {
+    let mut queue = load_queue_in(state_dir);
+    let before = queue.len();
+    queue.retain(|queued| queued.agent != agent);
+    if queue.len() == before {
+        return Ok(false);
+    }
+    save_queue_in(state_dir, &queue)?;
+    Ok(true)
+}
+
+/// Workers that occupy a pool slot: live sessions other than the manager
+/// that have not reported `[TASK COMPLETE]`. Blocked workers keep their slot.
+pub fn count_active(
+    sessions: &[String],
+    manager_session: &str,
+    results: &HashMap<String, AgentResult>,
+) -> usize {
+    sessions
+        .iter()
+        .filter(|session| session.as_str() != manager_session)
+        .filter(|session| {
+            !results
+                .get(*session)
+                .is_some_and(|result| matches!(result.kind, ResultKind::Complete))
+        })
+        .count()
+}
+
+pub fn active_workers(client: &dyn Mux, manager_session: &str, state_dir: &Path) -> Result<usize> {
+    let sessions: Vec<String> = client
+        .list_sessions()?
+        .into_iter()
+        .map(|session| session.name)
+        .collect();
+    let results = crate::memory::load_agent_results_from(state_dir);
+    Ok(count_active(&sessions, manager_session, &results))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_queue_is_fifo_and_rejects_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            enqueue_in(dir.path(), "a", json!({"name": "a"})).unwrap(),
+            1
+        );
+        assert_eq!(
+            enqueue_in(dir.path(), "b", json!({"name": "b"})).unwrap(),
+            2
+        );
+        assert!(enqueue_in(dir.path(), "a", json!({})).is_err());
+        assert_eq!(enqueue_in(dir.path(), "c", json!({})).unwrap(), 3);
+
+        assert!(remove_in(dir.path(), "b").unwrap());
+        assert!(!remove_in(dir.path(), "b").unwrap());
+        let first = pop_first_in(dir.path(), |_| true).unwrap().unwrap();
+        assert_eq!(
+            (first.agent.as_str(), &first.args),
+            ("a", &json!({"name": "a"}))
+        );
+        assert_eq!(
+            pop_first_in(dir.path(), |_| true).unwrap().unwrap().agent,
+            "c"
+        );
+        assert!(pop_first_in(dir.path(), |_| true).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_waiting_requests_are_skipped_until_their_dependency_is_met() {
+        let dir = tempfile::tempdir().unwrap();
+        let wait: WaitFor = serde_json::from_value(json!({"path": "api/openapi.yaml"})).unwrap();
+        assert_eq!(wait.validate(), Ok(()));
+        let wait = wait.resolved(dir.path());
+        enqueue_in(dir.path(), "client", json!({"wait_for": wait})).unwrap();
+        enqueue_in(dir.path(), "docs", json!({})).unwrap();
+
+        let ready = |queued: &QueuedSpawn| queued.wait_for().is_none_or(|w| w.is_met(|_, _| false));
+        assert_eq!(
+            pop_first_in(dir.path(), ready).unwrap().unwrap().agent,
+            "docs"
+        );
+        assert!(pop_first_in(dir.path(), ready).unwrap().is_none());
+        std::fs::create_dir_all(dir.path().join("api")).unwrap();
+        std::fs::write(dir.path().join("api/openapi.yaml"), "openapi: 3.1.0").unwrap();
+        assert_eq!(
+            pop_first_in(dir.path(), ready).unwrap().unwrap().agent,
+            "client"
+        );
+
+        let on_agent: WaitFor = serde_json::from_value(json!({"agent": "schema"})).unwrap();
+        assert_eq!(on_agent.describe(), "schema to complete");
+        assert!(on_agent.is_met(|name, state| name == "schema" && state == WaitState::Complete));
+        let both: WaitFor = serde_json::from_value(json!({"path": "a", "agent": "b"})).unwrap();
+        assert!(both.validate().is_err());
+        assert!(serde_json::from_value::<WaitFor>(json!({"pr": 12})).is_err());
+    }
+
+    #[test]
+    fn test_bump_moves_ahead_of_lower_priority() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a", "b", "c"] {
+            enqueue_in(dir.path(), name, json!({})).unwrap();
+        }
+        assert_eq!(bump_in(dir.path(), "c").unwrap(), Some(1));
+        assert_eq!(bump_in(dir.path(), "b").unwrap(), Some(2));
+        assert_eq!(bump_in(dir.path(), "missing").unwrap(), None);
+        // New requests still join behind bumped ones
+        assert_eq!(enqueue_in(dir.path(), "d", json!({})).unwrap(), 4);
+        let order: Vec<String> = load_queue_in(dir.path())
+            .into_iter()
+            .map(|queued| queued.agent)
+            .collect();
+        assert_eq!(order, vec!["c", "b", "a", "d"]);
+    }
+
+    #[test]
+    fn test_count_active_skips_manager_and_finished() {
+        let sessions = vec!["ea".to_string(), "w1".to_string(), "w2".to_string()];
+        let result = |kind| AgentResult {
+            kind,
+            detail: String::new(),
+            detected_at: String::new(),
+        };
+        let mut results = HashMap::new();
+        assert_eq!(count_active(&sessions, "ea", &results), 2);
+        results.insert("w1".to_string(), result(ResultKind::Blocked));
+        assert_eq!(count_active(&sessions, "ea", &results), 2);
+        results.insert("w2".to_string(), result(ResultKind::Complete));
+        assert_eq!(count_active(&sessions, "ea", &results), 1);
+    }
+}
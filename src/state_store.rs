@@ -0,0 +1,638 @@
+//! Transactional store for per-agent records, the agent hierarchy, and
+//! projects
+//!
+//! `<state_dir>/state.db` (SQLite) is the source of truth for each worker's
+//! task, parent, and other records (project, role, notes, progress, ...)
+//! and for the EA's projects, shared by the dashboard and the MCP server.
+//! Every write runs in one `BEGIN IMMEDIATE` transaction, so two processes
+//! updating at once queue up instead of each rewriting a file from a stale
+//! read and dropping the other's change, and a spawn or rename lands whole.
+//! `worker_tasks.json`, `agent_parents.json`, `tasks.md`, and one JSON file
+//! per `AgentField` are still written from inside the transaction as
+//! read-only exports for scripts and the memory snapshot.
+//!
+//! Schema changes are appended to `MIGRATIONS` and tracked with
+//! `PRAGMA user_version`; each one imports the files it replaces from an
+//! existing state directory.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Result;
+use rusqlite::{params, Connection, Transaction, TransactionBehavior};
+
+use crate::projects::{self, ChecklistItem, Project};
+
+/// How long a writer waits for another process's transaction.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE worker_tasks (
+         session TEXT PRIMARY KEY,
+         task TEXT NOT NULL
+     );
+     CREATE TABLE agent_parents (
+         child TEXT PRIMARY KEY,
+         parent TEXT NOT NULL
+     );",
+    "CREATE TABLE projects (
+         id INTEGER PRIMARY KEY,
+         name TEXT NOT NULL
+     );
+     CREATE TABLE project_items (
+         project_id INTEGER NOT NULL,
+         position INTEGER NOT NULL,
+         done INTEGER NOT NULL,
+         text TEXT NOT NULL,
+         PRIMARY KEY (project_id, position)
+     );",
+    "CREATE TABLE agent_fields (
+         field TEXT NOT NULL,
+         session TEXT NOT NULL,
+         value TEXT NOT NULL,
+         PRIMARY KEY (field, session)
+     );",
+];
+
+const TASKS_EXPORT: &str = "worker_tasks.json";
+const PARENTS_EXPORT: &str = "agent_parents.json";
+const PROJECTS_EXPORT: &str = "tasks.md";
+
+/// A per-agent record, stored as JSON and exported to `<name>.json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentField {
+    Project,
+    Result,
+    Completed,
+    Launch,
+    SpawnStatus,
+    Tags,
+    Note,
+    Progress,
+    Priority,
+    Role,
+    Trace,
+    Checkpoint,
+}
+
+impl AgentField {
+    pub const ALL: [AgentField; 12] = [
+        AgentField::Project,
+        AgentField::Result,
+        AgentField::Completed,
+        AgentField::Launch,
+        AgentField::SpawnStatus,
+        AgentField::Tags,
+        AgentField::Note,
+        AgentField::Progress,
+        AgentField::Priority,
+        AgentField::Role,
+        AgentField::Trace,
+        AgentField::Checkpoint,
+    ];
+
+    /// Also the name of the JSON file the field replaced.
+    pub fn name(self) -> &'static str {
+        match self {
+            AgentField::Project => "agent_projects",
+            AgentField::Result => "agent_results",
+            AgentField::Completed => "completed_agents",
+            AgentField::Launch => "agent_launches",
+            AgentField::SpawnStatus => "spawn_status",
+            AgentField::Tags => "agent_tags",
+            AgentField::Note => "agent_notes",
+            AgentField::Progress => "agent_progress",
+            AgentField::Priority => "agent_priorities",
+            AgentField::Role => "agent_roles",
+            AgentField::Trace => "agent_traces",
+            AgentField::Checkpoint => "agent_checkpoints",
+        }
+    }
+
+    pub fn export(self) -> String {
+        format!("{}.json", self.name())
+    }
+}
+
+/// One change to an agent's records; `update_agent` applies several at once.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgentWrite {
+    Task(String),
+    Parent(String),
+    Set(AgentField, serde_json::Value),
+    Remove(AgentField),
+}
+
+/// Worker task, child->parent, and other per-agent records of one EA.
+pub trait StateStore {
+    fn worker_tasks(&self) -> Result<HashMap<String, String>>;
+    fn set_worker_task(&self, session: &str, task: &str) -> Result<()>;
+    fn agent_parents(&self) -> Result<HashMap<String, String>>;
+    fn set_agent_parent(&self, child: &str, parent: &str) -> Result<()>;
+    /// Drop the parent records of `children`.
+    fn remove_agent_parents(&self, children: &[String]) -> Result<()>;
+    /// Every agent's value of `field`.
+    fn agent_field(&self, field: AgentField) -> Result<HashMap<String, serde_json::Value>>;
+    /// Apply `writes` to `session`'s records in one transaction.
+    fn update_agent(&self, session: &str, writes: &[AgentWrite]) -> Result<()>;
+    /// Drop the parent records and `fields` of `sessions`.
+    fn forget_agents(&self, sessions: &[String], fields: &[AgentField]) -> Result<()>;
+    /// Move `old`'s records to `new`, repoint its children and apply
+    /// `writes` to `new`, in one transaction.
+    fn rename_agent(&self, old: &str, new: &str, writes: &[AgentWrite]) -> Result<()>;
+    fn projects(&self) -> Result<Vec<Project>>;
+    /// Apply `change` to the project list in one transaction.
+    fn update_projects(
+        &self,
+        change: &mut dyn FnMut(&mut Vec<Project>) -> Result<()>,
+    ) -> Result<()>;
+}
+
+pub struct SqliteStore {
+    conn: Connection,
+    state_dir: PathBuf,
+}
+
+impl SqliteStore {
+    /// Open (creating and migrating as needed) the store of `state_dir`.
+    pub fn open(state_dir: &Path) -> Result<Self> {
+        fs::create_dir_all(state_dir)?;
+        let conn = Connection::open(state_dir.join("state.db"))?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        let store = Self {
+            conn,
+            state_dir: state_dir.to_path_buf(),
+        };
+        store.migrate()?;
+        Ok(store)
+    }
+
+    fn migrate(&self) -> Result<()> {
+        let version = |conn: &Connection| -> rusqlite::Result<usize> {
+            conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+        };
+        if version(&self.conn)? >= MIGRATIONS.len() {
+            return Ok(());
+        }
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        // Another process may have migrated while we waited for the lock
+        let current = version(&tx)?;
+        for (idx, sql) in MIGRATIONS.iter().enumerate().skip(current) {
+            tx.execute_batch(sql)?;
+            match idx {
+                0 => self.import_json(&tx)?,
+                1 => self.import_projects(&tx)?,
+                2 => self.import_agent_fields(&tx)?,
+                _ => {}
+            }
+        }
+        tx.pragma_update(None, "user_version", MIGRATIONS.len())?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Seed the tables from the JSON files the store replaces.
+    fn import_json(&self, tx: &Transaction) -> Result<()> {
+        let read = |name: &str| -> HashMap<String, String> {
+            fs::read_to_string(self.state_dir.join(name))
+                .ok()
+                .and_then(|text| serde_json::from_str(&text).ok())
+                .unwrap_or_default()
+        };
+        for (session, task) in read(TASKS_EXPORT) {
+            tx.execute(
+                "INSERT INTO worker_tasks (session, task) VALUES (?1, ?2)",
+                params![session, task],
+            )?;
+        }
+        for (child, parent) in read(PARENTS_EXPORT) {
+            tx.execute(
+                "INSERT INTO agent_parents (child, parent) VALUES (?1, ?2)",
+                params![child, parent],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Seed the project tables from `tasks.md`.
+    fn import_projects(&self, tx: &Transaction) -> Result<()> {
+        let content = fs::read_to_string(self.state_dir.join(PROJECTS_EXPORT)).unwrap_or_default();
+        replace_projects(tx, &projects::parse_projects(&content))
+    }
+
+    /// Seed `agent_fields` from the per-field JSON files.
+    fn import_agent_fields(&self, tx: &Transaction) -> Result<()> {
+        for field in AgentField::ALL {
+            let values: HashMap<String, serde_json::Value> =
+                fs::read_to_string(self.state_dir.join(field.export()))
+                    .ok()
+                    .and_then(|text| serde_json::from_str(&text).ok())
+                    .unwrap_or_default();
+            for (session, value) in values {
+                set_field(tx, field, &session, &value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Run `change` in a write transaction and refresh the exports, of
+    /// `fields` among the agent fields.
+    fn write(
+        &self,
+        fields: &[AgentField],
+        change: impl FnOnce(&Transaction) -> Result<()>,
+    ) -> Result<()> {
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        change(&tx)?;
+        for field in fields {
+            crate::memory::write_json(
+                &self.state_dir.join(field.export()),
+                &load_field(&tx, *field)?,
+            );
+        }
+        crate::memory::write_json(
+            &self.state_dir.join(TASKS_EXPORT),
+            &map(&tx, "SELECT session, task FROM worker_tasks")?,
+        );
+        crate::memory::write_json(
+            &self.state_dir.join(PARENTS_EXPORT),
+            &map(&tx, "SELECT child, parent FROM agent_parents")?,
+        );
+        projects::write_export(
+            &self.state_dir.join(PROJECTS_EXPORT),
+            &projects::render_projects(&load_projects(&tx)?),
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+fn load_field(
+    conn: &Connection,
+    field: AgentField,
+) -> rusqlite::Result<HashMap<String, serde_json::Value>> {
+    let mut stmt = conn.prepare("SELECT session, value FROM agent_fields WHERE field = ?1")?;
+    let rows = stmt.query_map([field.name()], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?;
+    let mut values = HashMap::new();
+    for row in rows {
+        let (session, value) = row?;
+        if let Ok(value) = serde_json::from_str(&value) {
+            values.insert(session, value);
+        }
+    }
+    Ok(values)
+}
+
+fn set_field(
+    tx: &Transaction,
+    field: AgentField,
+    session: &str,
+    value: &serde_json::Value,
+) -> Result<()> {
+    tx.execute(
+        "INSERT INTO agent_fields (field, session, value) VALUES (?1, ?2, ?3)
+         ON CONFLICT(field, session) DO UPDATE SET value = excluded.value",
+        params![field.name(), session, value.to_string()],
+    )?;
+    Ok(())
+}
+
+fn load_projects(conn: &Connection) -> rusqlite::Result<Vec<Project>> {
+    let mut stmt = conn.prepare("SELECT id, name FROM projects ORDER BY id")?;
+    let mut projects: Vec<Project> = stmt
+        .query_map([], |row| {
+            Ok(Project {
+                id: row.get::<_, i64>(0)? as usize,
+                name: row.get(1)?,
+                items: Vec::new(),
+            })
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+    let mut stmt = conn.prepare(
+        "SELECT project_id, done, text FROM project_items ORDER BY project_id, position",
+    )?;
+    let items = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)? as usize,
+            ChecklistItem {
+                done: row.get(1)?,
+                text: row.get(2)?,
+            },
+        ))
+    })?;
+    for item in items {
+        let (id, item) = item?;
+        if let Some(project) = projects.iter_mut().find(|p| p.id == id) {
+            project.items.push(item);
+        }
+    }
+    Ok(projects)
+}
+
+/// The agent fields `writes` change, to export after the transaction.
+fn touched_fields(writes: &[AgentWrite]) -> Vec<AgentField> {
+    writes
+        .iter()
+        .filter_map(|write| match write {
+            AgentWrite::Set(field, _) | AgentWrite::Remove(field) => Some(*field),
+            AgentWrite::Task(_) | AgentWrite::Parent(_) => None,
+        })
+        .collect()
+}
+
+fn apply_writes(tx: &Transaction, session: &str, writes: &[AgentWrite]) -> Result<()> {
+    for write in writes {
+        match write {
+            AgentWrite::Task(task) => {
+                tx.execute(
+                    "INSERT INTO worker_tasks (session, task) VALUES (?1, ?2)
+                     ON CONFLICT(session) DO UPDATE SET task = excluded.task",
+                    params![session, task],
+                )?;
+            }
+            AgentWrite::Parent(parent) => {
+                tx.execute(
+                    "INSERT INTO agent_parents (child, parent) VALUES (?1, ?2)
+                     ON CONFLICT(child) DO UPDATE SET parent = excluded.parent",
+                    params![session, parent],
+                )?;
+            }
+            AgentWrite::Set(field, value) => set_field(tx, *field, session, value)?,
+            AgentWrite::Remove(field) => {
+                tx.execute(
+                    "DELETE FROM agent_fields WHERE field = ?1 AND session = ?2",
+                    params![field.name(), session],
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn replace_projects(tx: &Transaction, projects: &[Project]) -> Result<()> {
+    tx.execute("DELETE FROM project_items", [])?;
+    tx.execute("DELETE FROM projects", [])?;
+    for project in projects {
+        tx.execute(
+            "INSERT INTO projects (id, name) VALUES (?1, ?2)",
+            params![project.id as i64, project.name],
+        )?;
+        for (position, item) in project.items.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO project_items (project_id, position, done, text)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![project.id as i64, position as i64, item.done, item.text],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn map(conn: &Connection, sql: &str) -> rusqlite::Result<HashMap<String, String>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+impl StateStore for SqliteStore {
+    fn worker_tasks(&self) -> Result<HashMap<String, String>> {
+        Ok(map(&self.conn, "SELECT session, task FROM worker_tasks")?)
+    }
+
+    fn set_worker_task(&self, session: &str, task: &str) -> Result<()> {
+        self.write(&[], |tx| {
+            tx.execute(
+                "INSERT INTO worker_tasks (session, task) VALUES (?1, ?2)
+                 ON CONFLICT(session) DO UPDATE SET task = excluded.task",
+                params![session, task],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn agent_parents(&self) -> Result<HashMap<String, String>> {
+        Ok(map(&self.conn, "SELECT child, parent FROM agent_parents")?)
+    }
+
+    fn set_agent_parent(&self, child: &str, parent: &str) -> Result<()> {
+        self.write(&[], |tx| {
+            tx.execute(
+                "INSERT INTO agent_parents (child, parent) VALUES (?1, ?2)
+                 ON CONFLICT(child) DO UPDATE SET parent = excluded.parent",
+                params![child, parent],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn remove_agent_parents(&self, children: &[String]) -> Result<()> {
+        self.write(&[], |tx| {
+            for child in children {
+                tx.execute("DELETE FROM agent_parents WHERE child = ?1", [child])?;
+            }
+            Ok(())
+        })
+    }
+
+    fn agent_field(&self, field: AgentField) -> Result<HashMap<String, serde_json::Value>> {
+        Ok(load_field(&self.conn, field)?)
+    }
+
+    fn update_agent(&self, session: &str, writes: &[AgentWrite]) -> Result<()> {
+        self.write(&touched_fields(writes), |tx| {
+            apply_writes(tx, session, writes)
+        })
+    }
+
+    fn forget_agents(&self, sessions: &[String], fields: &[AgentField]) -> Result<()> {
+        self.write(fields, |tx| {
+            for session in sessions {
+                tx.execute("DELETE FROM agent_parents WHERE child = ?1", [session])?;
+                for field in fields {
+                    tx.execute(
+                        "DELETE FROM agent_fields WHERE field = ?1 AND session = ?2",
+                        params![field.name(), session],
+                    )?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn rename_agent(&self, old: &str, new: &str, writes: &[AgentWrite]) -> Result<()> {
+        self.write(&AgentField::ALL, |tx| {
+            // Rows left under `new` by an earlier agent of that name
+            tx.execute("DELETE FROM worker_tasks WHERE session = ?1", [new])?;
+            tx.execute("DELETE FROM agent_parents WHERE child = ?1", [new])?;
+            tx.execute("DELETE FROM agent_fields WHERE session = ?1", [new])?;
+            tx.execute(
+                "UPDATE agent_fields SET session = ?2 WHERE session = ?1",
+                [old, new],
+            )?;
+            tx.execute(
+                "UPDATE worker_tasks SET session = ?2 WHERE session = ?1",
+                [old, new],
+            )?;
+            tx.execute(
+                "UPDATE agent_parents SET child = ?2 WHERE child = ?1",
+                [old, new],
+            )?;
+            tx.execute(
+                "UPDATE agent_parents SET parent = ?2 WHERE parent = ?1",
+                [old, new],
+            )?;
+            apply_writes(tx, new, writes)
+        })
+    }
+
+    fn projects(&self) -> Result<Vec<Project>> {
+        Ok(load_projects(&self.conn)?)
+    }
+
+    fn update_projects(
+        &self,
+        change: &mut dyn FnMut(&mut Vec<Project>) -> Result<()>,
+    ) -> Result<()> {
+        self.write(&[], |tx| {
+            let mut projects = load_projects(tx)?;
+            change(&mut projects)?;
+            replace_projects(tx, &projects)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migration_imports_json_and_concurrent_writers_keep_every_update() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = dir.path().to_path_buf();
+        fs::write(state.join(TASKS_EXPORT), r#"{"omar-agent-0-api":"build"}"#).unwrap();
+        fs::write(state.join(PARENTS_EXPORT), r#"{"omar-agent-0-api":"ea"}"#).unwrap();
+
+        let store = SqliteStore::open(&state).unwrap();
+        assert_eq!(store.worker_tasks().unwrap()["omar-agent-0-api"], "build");
+        assert_eq!(store.agent_parents().unwrap()["omar-agent-0-api"], "ea");
+
+        // Each writer opens its own connection, as separate processes do
+        let writers: Vec<_> = (0..4)
+            .map(|writer| {
+                let state = state.clone();
+                std::thread::spawn(move || {
+                    let store = SqliteStore::open(&state).unwrap();
+                    for n in 0..10 {
+                        let session = format!("w{}-{}", writer, n);
+                        store.set_worker_task(&session, "task").unwrap();
+                        store.set_agent_parent(&session, "ea").unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        assert_eq!(store.worker_tasks().unwrap().len(), 41);
+        let exported: HashMap<String, String> =
+            serde_json::from_str(&fs::read_to_string(state.join(PARENTS_EXPORT)).unwrap()).unwrap();
+        assert_eq!(exported.len(), 41);
+
+        // Reopening does not import the exports a second time
+        store.remove_agent_parents(&["w0-0".to_string()]).unwrap();
+        let reopened = SqliteStore::open(&state).unwrap();
+        assert_eq!(reopened.agent_parents().unwrap().len(), 40);
+    }
+
+    #[test]
+    fn test_agent_fields_import_json_and_concurrent_updates_keep_every_field() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = dir.path().to_path_buf();
+        fs::write(
+            state.join(AgentField::Role.export()),
+            r#"{"omar-agent-0-pm":"pm"}"#,
+        )
+        .unwrap();
+        fs::write(
+            state.join(AgentField::Note.export()),
+            r#"{"omar-agent-0-pm":"waiting on review"}"#,
+        )
+        .unwrap();
+
+        let store = SqliteStore::open(&state).unwrap();
+        assert_eq!(
+            store.agent_field(AgentField::Role).unwrap()["omar-agent-0-pm"],
+            "pm"
+        );
+
+        // Concurrent notes and progress for different agents all land
+        let writers: Vec<_> = (0..4)
+            .map(|writer| {
+                let state = state.clone();
+                std::thread::spawn(move || {
+                    let store = SqliteStore::open(&state).unwrap();
+                    for n in 0..10 {
+                        let session = format!("w{}-{}", writer, n);
+                        store
+                            .update_agent(
+                                &session,
+                                &[
+                                    AgentWrite::Task("task".to_string()),
+                                    AgentWrite::Set(AgentField::Note, "note".into()),
+                                    AgentWrite::Set(AgentField::Progress, 50.into()),
+                                ],
+                            )
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        assert_eq!(store.agent_field(AgentField::Note).unwrap().len(), 41);
+        assert_eq!(store.agent_field(AgentField::Progress).unwrap().len(), 40);
+        let exported: HashMap<String, String> = serde_json::from_str(
+            &fs::read_to_string(state.join(AgentField::Note.export())).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(exported.len(), 41);
+
+        store
+            .forget_agents(&["omar-agent-0-pm".to_string()], &AgentField::ALL)
+            .unwrap();
+        let reopened = SqliteStore::open(&state).unwrap();
+        assert!(reopened.agent_field(AgentField::Role).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_projects_import_tasks_md_and_concurrent_adds_keep_every_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = dir.path().to_path_buf();
+        fs::write(state.join(PROJECTS_EXPORT), "4. API\n   - [x] Schema\n").unwrap();
+
+        let writers: Vec<_> = (0..4)
+            .map(|writer| {
+                let state = state.clone();
+                std::thread::spawn(move || {
+                    for n in 0..5 {
+                        projects::add_project_in(&state, &format!("p{}-{}", writer, n)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        let loaded = SqliteStore::open(&state).unwrap().projects().unwrap();
+        assert_eq!(loaded.len(), 21);
+        assert_eq!(loaded[0].items[0].text, "Schema");
+        assert_eq!(loaded.last().unwrap().id, 24);
+        let exported = fs::read_to_string(state.join(PROJECTS_EXPORT)).unwrap();
+        assert!(exported.starts_with("4. API\n   - [x] Schema\n5. "));
+    }
+}
@@ -0,0 +1,148 @@
+//! Prompt templates — reusable worker task prompts (`~/.omar/templates/*.md`)
+//!
+//! `spawn_agent` takes a template *name*; omar renders the file before the
+//! first message is delivered, so per-repo coding standards travel with the
+//! task instead of living in the hardcoded worker header. `{{task}}`,
+//! `{{repo}}` (the worker's directory) and `{{conventions}}` (its
+//! `CONVENTIONS.md` or `AGENTS.md`, if any) are always available; callers
+//! add more with `template_vars`. A placeholder left without a value is an
+//! error rather than literal braces in the prompt.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+/// Files read for `{{conventions}}`, first match wins.
+const CONVENTION_FILES: &[&str] = &["CONVENTIONS.md", "AGENTS.md"];
+
+pub fn templates_dir(omar_dir: &Path) -> PathBuf {
+    omar_dir.join("templates")
+}
+
+/// Names of the installed templates, sorted.
+pub fn list(omar_dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(templates_dir(omar_dir)) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            (path.extension()? == "md")
+                .then(|| path.file_stem()?.to_str().map(str::to_string))
+                .flatten()
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+/// Text of template `name`.
+pub fn load(omar_dir: &Path, name: &str) -> Result<String> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+    let path = templates_dir(omar_dir).join(format!("{}.md", name));
+    if !valid || !path.is_file() {
+        let names = list(omar_dir);
+        return Err(anyhow!(
+            "Template '{}' not found in {}. Installed: {}",
+            name,
+            templates_dir(omar_dir).display(),
+            if names.is_empty() {
+                "none".to_string()
+            } else {
+                names.join(", ")
+            }
+        ));
+    }
+    Ok(fs::read_to_string(path)?)
+}
+
+/// Variables every template can use: `task`, `repo`, `conventions`.
+pub fn builtin_vars(task: &str, workdir: &Path) -> BTreeMap<String, String> {
+    let conventions = CONVENTION_FILES
+        .iter()
+        .find_map(|file| fs::read_to_string(workdir.join(file)).ok())
+        .unwrap_or_default();
+    BTreeMap::from([
+        ("task".to_string(), task.to_string()),
+        ("repo".to_string(), workdir.display().to_string()),
+        ("conventions".to_string(), conventions.trim().to_string()),
+    ])
+}
+
+/// Fill each `{{name}}` in `template`. Fails with the names that have no
+/// value; braces around anything but a variable name are kept as written.
+pub fn render(template: &str, vars: &BTreeMap<String, String>) -> Result<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut missing: Vec<&str> = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        let name = after[..end].trim();
+        let is_var =
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        match vars.get(name) {
+            Some(value) if is_var => out.push_str(value),
+            None if is_var => {
+                if !missing.contains(&name) {
+                    missing.push(name);
+                }
+            }
+            _ => out.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    if !missing.is_empty() {
+        return Err(anyhow!(
+            "Template needs values for: {}. Pass them in template_vars",
+            missing.join(", ")
+        ));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_fills_builtins_and_reports_missing_vars() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        fs::create_dir_all(templates_dir(dir.path())).unwrap();
+        fs::create_dir_all(&repo).unwrap();
+        fs::write(repo.join("CONVENTIONS.md"), "Use thiserror.\n").unwrap();
+        fs::write(
+            templates_dir(dir.path()).join("rust.md"),
+            "In {{repo}}: {{ task }}\n{{conventions}}\nKeep {{}} and {x}.\nTicket {{ticket}}",
+        )
+        .unwrap();
+        assert_eq!(list(dir.path()), vec!["rust"]);
+        assert!(load(dir.path(), "../rust").is_err());
+
+        let template = load(dir.path(), "rust").unwrap();
+        let mut vars = builtin_vars("fix the parser", &repo);
+        let err = render(&template, &vars).unwrap_err().to_string();
+        assert!(err.contains("ticket"), "{}", err);
+
+        vars.insert("ticket".to_string(), "OM-7".to_string());
+        assert_eq!(
+            render(&template, &vars).unwrap(),
+            format!(
+                "In {}: fix the parser\nUse thiserror.\nKeep {{{{}}}} and {{x}}.\nTicket OM-7",
+                repo.display()
+            )
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! Per-backend activity patterns configured under `[health.backends]`.
+
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use super::rate_limit::compile_all;
+use crate::config::HealthConfig;
+
+/// Non-empty trailing lines scanned, where backends draw their spinner and
+/// input box.
+const ACTIVITY_TAIL_LINES: usize = 8;
+
+/// What a backend's own screen says about it, over the frame diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activity {
+    Working,
+    Waiting,
+}
+
+#[derive(Default)]
+struct Patterns {
+    working: Vec<Regex>,
+    waiting: Vec<Regex>,
+}
+
+#[derive(Default)]
+pub struct ActivityDetector {
+    backends: HashMap<String, Patterns>,
+}
+
+impl ActivityDetector {
+    /// Patterns that are not valid regexes are matched literally.
+    pub fn from_config(config: &HealthConfig) -> Self {
+        Self {
+            backends: config
+                .backends
+                .iter()
+                .map(|(name, backend)| {
+                    let patterns = Patterns {
+                        working: compile_all(&backend.working_patterns),
+                        waiting: compile_all(&backend.waiting_patterns),
+                    };
+                    (name.clone(), patterns)
+                })
+                .collect(),
+        }
+    }
+
+    /// Scan recent plain-text output of a `backend` pane. Working patterns
+    /// win when both match.
+    pub fn detect(&self, backend: Option<&str>, output: &str) -> Option<Activity> {
+        let patterns = self.backends.get(backend?)?;
+        let tail: Vec<&str> = output
+            .lines()
+            .rev()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .take(ACTIVITY_TAIL_LINES)
+            .collect();
+        let matches = |res: &[Regex]| {
+            tail.iter()
+                .any(|line| res.iter().any(|re| re.is_match(line)))
+        };
+        if matches(&patterns.working) {
+            Some(Activity::Working)
+        } else if matches(&patterns.waiting) {
+            Some(Activity::Waiting)
+        } else {
+            None
+        }
+    }
+
+    /// No backend has overrides, so panes need not be told apart.
+    pub fn is_empty(&self) -> bool {
+        self.backends.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BackendHealthConfig;
+
+    #[test]
+    fn test_activity_follows_the_backend_patterns() {
+        let mut config = HealthConfig::default();
+        config.backends.insert(
+            "opencode".to_string(),
+            BackendHealthConfig {
+                working_patterns: vec!["⠋|working\\.\\.\\.".to_string()],
+                waiting_patterns: vec!["ctrl\\+p commands".to_string()],
+                ..Default::default()
+            },
+        );
+        let detector = ActivityDetector::from_config(&config);
+
+        let busy = "edited src/lib.rs\n  Working...  esc interrupt\n";
+        assert_eq!(
+            detector.detect(Some("opencode"), busy),
+            Some(Activity::Working)
+        );
+        let waiting = "done.\n\n  ctrl+p commands   12:04:31\n";
+        assert_eq!(
+            detector.detect(Some("opencode"), waiting),
+            Some(Activity::Waiting)
+        );
+        assert_eq!(detector.detect(Some("opencode"), "plain output"), None);
+        assert_eq!(detector.detect(Some("claude"), busy), None);
+        assert_eq!(detector.detect(None, busy), None);
+
+        assert_eq!(
+            config.idle_critical_for(Some("opencode")),
+            config.idle_critical
+        );
+        config.backends.get_mut("opencode").unwrap().idle_critical = Some(900);
+        assert_eq!(config.idle_critical_for(Some("opencode")), 900);
+        assert_eq!(
+            config.idle_critical_for(Some("claude")),
+            config.idle_critical
+        );
+    }
+}
@@ -0,0 +1,122 @@
+//! Pane captures reused while a pane prints nothing new
+//!
+//! `list-sessions` reports each pane's `PaneStamp` at no extra cost. A
+//! capture taken after the stamp's activity second, with the stamp still
+//! the same, is as good as a new one, so the dashboard and health checks
+//! skip `capture-pane` for quiet agents instead of running it every tick.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use anyhow::Result;
+
+use super::session::{PaneStamp, Session};
+
+#[derive(Debug, Default)]
+pub struct CaptureCache {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    stamp: PaneStamp,
+    lines: i32,
+    /// Unix seconds just before the capture ran
+    captured_at: i64,
+    content: String,
+}
+
+impl CaptureCache {
+    /// The last `lines` lines of `session`: the cached capture while its
+    /// stamp is unchanged, otherwise a fresh one from `capture`.
+    pub fn capture(
+        &self,
+        session: &Session,
+        lines: i32,
+        capture: impl FnOnce() -> Result<String>,
+    ) -> Result<String> {
+        // A remote host's activity times come from its own clock
+        let Some(stamp) = session.stamp.filter(|_| session.host.is_none()) else {
+            return capture();
+        };
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        // Output in the same second as the capture would not move the
+        // activity stamp, so only a capture from a later second counts
+        if let Some(entry) = entries.get(&session.name).filter(|entry| {
+            entry.stamp == stamp && entry.lines == lines && entry.captured_at > stamp.activity
+        }) {
+            return Ok(entry.content.clone());
+        }
+        let captured_at = chrono::Utc::now().timestamp();
+        let content = capture()?;
+        entries.insert(
+            session.name.clone(),
+            Entry {
+                stamp,
+                lines,
+                captured_at,
+                content: content.clone(),
+            },
+        );
+        Ok(content)
+    }
+
+    /// Forget sessions not in `active_sessions`.
+    pub fn retain(&self, active_sessions: &[String]) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|name, _| active_sessions.contains(name));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_reused_until_stamp_moves() {
+        let cache = CaptureCache::default();
+        let mut session = Session::new("omar-agent-api".to_string(), 0, false, 1);
+        let captures = std::cell::Cell::new(0);
+        let capture = |cache: &CaptureCache, session: &Session| {
+            cache
+                .capture(session, 50, || {
+                    captures.set(captures.get() + 1);
+                    Ok(format!("frame {}", captures.get()))
+                })
+                .unwrap()
+        };
+
+        // Without a stamp every call captures
+        capture(&cache, &session);
+        capture(&cache, &session);
+        assert_eq!(captures.get(), 2);
+
+        session.stamp = Some(PaneStamp {
+            activity: 1_000,
+            history_size: 10,
+        });
+        assert_eq!(capture(&cache, &session), "frame 3");
+        assert_eq!(capture(&cache, &session), "frame 3");
+        assert_eq!(captures.get(), 3);
+
+        session.stamp = Some(PaneStamp {
+            activity: 1_000,
+            history_size: 11,
+        });
+        assert_eq!(capture(&cache, &session), "frame 4");
+
+        // Output in the current second may not have moved the stamp yet
+        session.stamp = Some(PaneStamp {
+            activity: chrono::Utc::now().timestamp() + 60,
+            history_size: 11,
+        });
+        capture(&cache, &session);
+        capture(&cache, &session);
+        assert_eq!(captures.get(), 6);
+
+        cache.retain(&[]);
+        assert!(cache.entries.lock().unwrap().is_empty());
+    }
+}
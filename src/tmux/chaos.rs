@@ -0,0 +1,329 @@
+//! Chaos mode — seeded fault injection into tmux commands, for testing that
+//! orchestration recovers from crashed agents, a slow tmux server, and lost
+//! task delivery
+//!
+//! A `TmuxClient` carrying a `Chaos` may, before each command, sleep up to
+//! `delay`, kill the session the command targets (`kill`), or silently drop
+//! a text delivery — `send-keys -l` or `paste-buffer` (`corrupt`). Faults
+//! are drawn from a PRNG seeded by `seed`, so a failing run replays. Debug
+//! builds read a schedule from `OMAR_CHAOS`, e.g.
+//! `seed=7,kill=0.05,delay=200ms,corrupt=0.1,limit=20`; release builds
+//! ignore it.
+
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChaosSchedule {
+    pub seed: u64,
+    /// Probability of killing the targeted session before a command
+    pub kill: f64,
+    /// Upper bound of the random delay before each command
+    pub delay: Duration,
+    /// Probability of dropping a text delivery
+    pub corrupt: f64,
+    /// Stop injecting kills and drops after this many
+    pub limit: Option<u32>,
+}
+
+impl Default for ChaosSchedule {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            kill: 0.0,
+            delay: Duration::ZERO,
+            corrupt: 0.0,
+            limit: None,
+        }
+    }
+}
+
+impl ChaosSchedule {
+    /// Parse `key=value` pairs separated by commas.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut schedule = Self::default();
+        for pair in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .with_context(|| format!("Expected key=value in chaos schedule: {}", pair))?;
+            let value = value.trim();
+            match key.trim() {
+                "seed" => schedule.seed = value.parse().context("Invalid chaos seed")?,
+                "kill" => schedule.kill = parse_rate(key, value)?,
+                "corrupt" => schedule.corrupt = parse_rate(key, value)?,
+                "delay" => {
+                    let ms = value.strip_suffix("ms").unwrap_or(value);
+                    schedule.delay =
+                        Duration::from_millis(ms.parse().context("Invalid chaos delay")?);
+                }
+                "limit" => schedule.limit = Some(value.parse().context("Invalid chaos limit")?),
+                other => bail!("Unknown chaos setting: {}", other),
+            }
+        }
+        Ok(schedule)
+    }
+}
+
+fn parse_rate(key: &str, value: &str) -> Result<f64> {
+    let rate: f64 = value
+        .parse()
+        .with_context(|| format!("Invalid chaos {} rate", key))?;
+    if !(0.0..=1.0).contains(&rate) {
+        bail!("Chaos {} rate must be between 0 and 1", key);
+    }
+    Ok(rate)
+}
+
+/// What to do to a command before running it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fault {
+    /// Kill this session first
+    Kill(String),
+    /// Report success without running the command
+    Drop,
+}
+
+#[derive(Debug)]
+pub struct Chaos {
+    schedule: ChaosSchedule,
+    state: Mutex<ChaosState>,
+}
+
+#[derive(Debug)]
+struct ChaosState {
+    rng: u64,
+    injected: u32,
+}
+
+impl Chaos {
+    pub fn new(schedule: ChaosSchedule) -> Self {
+        // xorshift has a fixed point at zero
+        let rng = schedule.seed ^ 0x9E37_79B9_7F4A_7C15;
+        Self {
+            schedule,
+            state: Mutex::new(ChaosState { rng, injected: 0 }),
+        }
+    }
+
+    /// Sleep for this command's delay, then pick its fault, if any.
+    pub fn inject(&self, args: &[&str]) -> Option<Fault> {
+        let (delay, fault) = self.draw(args);
+        if !delay.is_zero() {
+            std::thread::sleep(delay);
+        }
+        fault
+    }
+
+    fn draw(&self, args: &[&str]) -> (Duration, Option<Fault>) {
+        let Ok(mut state) = self.state.lock() else {
+            return (Duration::ZERO, None);
+        };
+        let delay = self.schedule.delay.mul_f64(state.next());
+        if self
+            .schedule
+            .limit
+            .is_some_and(|limit| state.injected >= limit)
+        {
+            return (delay, None);
+        }
+        let command = args.first().copied().unwrap_or_default();
+        let fault = if is_delivery(args) && state.next() < self.schedule.corrupt {
+            Some(Fault::Drop)
+        } else if !matches!(command, "kill-session" | "new-session") {
+            target_session(args)
+                .filter(|_| state.next() < self.schedule.kill)
+                .map(Fault::Kill)
+        } else {
+            None
+        };
+        if fault.is_some() {
+            state.injected += 1;
+        }
+        (delay, fault)
+    }
+}
+
+impl ChaosState {
+    /// Next value in [0, 1).
+    fn next(&mut self) -> f64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Commands that carry task text into a pane.
+fn is_delivery(args: &[&str]) -> bool {
+    match args.first() {
+        Some(&"paste-buffer") => true,
+        Some(&"send-keys") => args.contains(&"-l"),
+        _ => false,
+    }
+}
+
+/// Session named by a command's `-t` target (`=name:0.1` → `name`).
+fn target_session(args: &[&str]) -> Option<String> {
+    let target = args
+        .iter()
+        .position(|arg| *arg == "-t")
+        .and_then(|i| args.get(i + 1))?;
+    let name = target.trim_start_matches('=');
+    let name = name.split([':', '.']).next().unwrap_or(name);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// The schedule from `OMAR_CHAOS`, shared by every client so one seed
+/// drives the whole run. Always `None` in release builds.
+pub fn from_env() -> Option<Arc<Chaos>> {
+    static CHAOS: OnceLock<Option<Arc<Chaos>>> = OnceLock::new();
+    CHAOS
+        .get_or_init(|| {
+            if !cfg!(debug_assertions) {
+                return None;
+            }
+            let spec = std::env::var("OMAR_CHAOS").ok()?;
+            match ChaosSchedule::parse(&spec) {
+                Ok(schedule) => Some(Arc::new(Chaos::new(schedule))),
+                Err(e) => {
+                    eprintln!("Ignoring OMAR_CHAOS: {:#}", e);
+                    None
+                }
+            }
+        })
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::AgentInfo;
+    use crate::memory;
+    use crate::tmux::{
+        tmux_command, DeliveryOptions, HealthChecker, HealthState, Session, TmuxClient,
+    };
+
+    #[test]
+    fn test_schedule_parses_and_replays_by_seed() {
+        let schedule =
+            ChaosSchedule::parse("seed=7, kill=0.5,delay=20ms,corrupt=1,limit=3").unwrap();
+        assert_eq!(schedule.seed, 7);
+        assert_eq!(schedule.delay, Duration::from_millis(20));
+        assert_eq!(schedule.limit, Some(3));
+        assert!(ChaosSchedule::parse("kill=2").is_err());
+        assert!(ChaosSchedule::parse("bogus=1").is_err());
+
+        let schedule = ChaosSchedule {
+            seed: 42,
+            kill: 0.5,
+            ..Default::default()
+        };
+        let args = ["capture-pane", "-t", "=omar-agent-api:", "-p"];
+        let run = |chaos: &Chaos| -> Vec<Option<Fault>> {
+            (0..32).map(|_| chaos.draw(&args).1).collect()
+        };
+        let first = run(&Chaos::new(schedule.clone()));
+        assert_eq!(first, run(&Chaos::new(schedule)));
+        assert!(first.contains(&Some(Fault::Kill("omar-agent-api".to_string()))));
+        assert!(first.contains(&None));
+
+        let lossy = Chaos::new(ChaosSchedule {
+            corrupt: 1.0,
+            limit: Some(1),
+            ..Default::default()
+        });
+        let paste = ["paste-buffer", "-b", "buf", "-t", "=api:", "-d", "-p"];
+        assert_eq!(lossy.draw(&paste).1, Some(Fault::Drop));
+        assert_eq!(lossy.draw(&paste).1, None);
+        assert_eq!(lossy.draw(&["send-keys", "-t", "=api:", "C-u"]).1, None);
+    }
+
+    fn tmux_available() -> bool {
+        tmux_command()
+            .arg("-V")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    struct SessionGuard(String);
+    impl Drop for SessionGuard {
+        fn drop(&mut self) {
+            let _ = tmux_command()
+                .args(["kill-session", "-t", &self.0])
+                .output();
+        }
+    }
+
+    /// Health, task delivery, and memory keep working through a killed
+    /// session, a slow server, and a dropped paste.
+    #[test]
+    fn test_subsystems_recover_from_chaos() {
+        if !tmux_available() {
+            eprintln!("Skipping test: tmux not available");
+            return;
+        }
+        let session = format!("omar-test-chaos-{}", uuid::Uuid::new_v4().simple());
+        let _guard = SessionGuard(session.clone());
+        let clean = TmuxClient::new("omar-test-");
+        if clean.new_session(&session, "sh", None).is_err() {
+            eprintln!("Skipping test: failed to create tmux session");
+            return;
+        }
+
+        // Health: the agent crashes mid-check, then comes back
+        let crashing = TmuxClient::new("omar-test-").with_chaos(ChaosSchedule {
+            kill: 1.0,
+            limit: Some(1),
+            ..Default::default()
+        });
+        let mut checker = HealthChecker::new(Arc::new(crashing), 60);
+        checker.check(&session);
+        assert!(!clean.has_session(&session).unwrap());
+        clean.new_session(&session, "sh", None).unwrap();
+        std::thread::sleep(Duration::from_millis(300));
+        assert_eq!(checker.check(&session), HealthState::Running);
+        assert_eq!(checker.check(&session), HealthState::Idle);
+
+        // Delivery: the first paste is lost on a slow server; a retry lands
+        let lossy = TmuxClient::new("omar-test-").with_chaos(ChaosSchedule {
+            seed: 3,
+            delay: Duration::from_millis(20),
+            corrupt: 1.0,
+            limit: Some(1),
+            ..Default::default()
+        });
+        let opts = DeliveryOptions {
+            verify_timeout: Duration::from_millis(800),
+            poll_interval: Duration::from_millis(50),
+            retry_delay: Duration::from_millis(100),
+            ..Default::default()
+        };
+        if let Err(e) = lossy.deliver_prompt(&session, "echo OMAR_CHAOS_OK", &opts) {
+            // Some sandboxes block send-keys; skip rather than fail.
+            eprintln!("Skipping delivery check (likely sandbox): {}", e);
+        } else {
+            std::thread::sleep(Duration::from_millis(300));
+            let pane = clean.capture_pane_plain(&session, 50).unwrap_or_default();
+            assert!(pane.contains("OMAR_CHAOS_OK"), "pane: {:?}", pane);
+        }
+
+        // Memory: a snapshot is still written when the manager pane is gone
+        let dir = tempfile::tempdir().unwrap();
+        let killer = TmuxClient::new("omar-test-").with_chaos(ChaosSchedule {
+            kill: 1.0,
+            ..Default::default()
+        });
+        let manager = AgentInfo {
+            session: Session::new(session.clone(), 0, false, 0),
+            health: HealthState::Running,
+            is_unresolved: false,
+        };
+        memory::write_memory_to(dir.path(), &[], Some(&manager), &session, &killer, &[]);
+        assert!(!clean.has_session(&session).unwrap());
+        let memory = memory::load_memory_from(dir.path());
+        assert!(memory.contains("- Status: Running"), "{}", memory);
+    }
+}
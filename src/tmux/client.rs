@@ -3,9 +3,13 @@
 use anyhow::{anyhow, Context, Result};
 use std::io::Write;
 use std::process::Command;
+use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
 
+use super::chaos::{self, Chaos, ChaosSchedule, Fault};
+use super::retry::{with_retry, RetryPolicy, TmuxError};
+use super::session::PaneStamp;
 use super::Session;
 
 /// Options for reliable prompt delivery and related readiness helpers.
@@ -103,10 +107,59 @@ fn tail_pane_lines(output: String, lines: i32) -> String {
 #[derive(Debug, Clone)]
 pub struct TmuxClient {
     prefix: String,
+    remote: Option<RemoteHost>,
+    /// Fault injection for resilience tests; see `chaos`
+    chaos: Option<Arc<Chaos>>,
+}
+
+/// A tmux server on another machine, reached with `ssh <ssh> tmux ...`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteHost {
+    /// Display name shown on agent cards and in the API.
+    pub name: String,
+    /// ssh destination, e.g. `builder@build1.internal`.
+    pub ssh: String,
+    /// Optional `ControlPath` so repeated polls reuse one SSH connection.
+    pub control_path: Option<String>,
+}
+
+impl RemoteHost {
+    fn ssh_args(&self, tty: bool) -> Vec<String> {
+        let mut args = vec![if tty { "-t" } else { "-T" }.to_string()];
+        if !tty {
+            args.extend(["-o".to_string(), "BatchMode=yes".to_string()]);
+        }
+        if let Some(path) = &self.control_path {
+            args.extend([
+                "-o".to_string(),
+                "ControlMaster=auto".to_string(),
+                "-o".to_string(),
+                format!("ControlPath={}", path),
+                "-o".to_string(),
+                "ControlPersist=60".to_string(),
+            ]);
+        }
+        args.push(self.ssh.clone());
+        args
+    }
+
+    fn command(&self, tty: bool, remote: &[&str]) -> Command {
+        let mut cmd = Command::new("ssh");
+        cmd.args(self.ssh_args(tty)).arg(remote_command(remote));
+        cmd
+    }
+}
+
+/// ssh joins its arguments into one remote shell string, so quote each.
+fn remote_command(args: &[&str]) -> String {
+    args.iter()
+        .map(|arg| crate::sandbox::shell_single_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 pub fn tmux_command() -> Command {
-    let mut cmd = Command::new("tmux");
+    let mut cmd = super::wsl::local_tmux_command();
     if let Ok(server) = std::env::var("OMAR_TMUX_SERVER") {
         let server = server.trim();
         if !server.is_empty() {
@@ -116,6 +169,46 @@ pub fn tmux_command() -> Command {
     cmd
 }
 
+/// Socket of the local server `tmux_command` talks to:
+/// `$TMUX_TMPDIR/tmux-<uid>/<OMAR_TMUX_SERVER or "default">`.
+#[cfg(unix)]
+pub fn tmux_socket_path() -> std::path::PathBuf {
+    let base = std::env::var_os("TMUX_TMPDIR")
+        .filter(|dir| !dir.is_empty())
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| std::path::PathBuf::from("/tmp"));
+    let server = std::env::var("OMAR_TMUX_SERVER")
+        .ok()
+        .map(|server| server.trim().to_string())
+        .filter(|server| !server.is_empty())
+        .unwrap_or_else(|| "default".to_string());
+    // SAFETY: getuid cannot fail
+    let uid = unsafe { libc::getuid() };
+    base.join(format!("tmux-{}", uid)).join(server)
+}
+
+const SESSION_FORMAT: &str = "#{session_name}|#{session_activity}|#{session_attached}|#{pane_pid}|#{window_activity}|#{history_size}|#{session_created}";
+
+/// One `SESSION_FORMAT` line of `list-sessions`.
+fn parse_session_line(line: &str) -> Option<Session> {
+    let parts: Vec<&str> = line.split('|').collect();
+    if parts.len() != 7 {
+        return None;
+    }
+    let mut session = Session::new(
+        parts[0].to_string(),
+        parts[1].parse().ok()?,
+        parts[2] == "1",
+        parts[3].parse().ok()?,
+    );
+    session.stamp = Some(PaneStamp {
+        activity: parts[4].parse().ok()?,
+        history_size: parts[5].parse().ok()?,
+    });
+    session.created = parts[6].parse().ok();
+    Some(session)
+}
+
 fn exact_session_target(target: &str) -> String {
     if target.starts_with('=') || target.contains(':') || target.contains('.') {
         target.to_string()
@@ -125,7 +218,7 @@ fn exact_session_target(target: &str) -> String {
 }
 
 fn exact_pane_target(target: &str) -> String {
-    if target.contains(':') || target.contains('.') {
+    if target.contains(':') || target.contains('.') || target.starts_with('%') {
         target.to_string()
     } else if target.starts_with('=') {
         format!("{target}:")
@@ -142,6 +235,24 @@ fn popup_attach_command(target: &str) -> String {
     popup_attach_command_with_server(target, tmux_server.as_deref())
 }
 
+/// Popup command that attaches to `target` on a remote host over `ssh -t`.
+fn remote_popup_attach_command(host: &RemoteHost, target: &str) -> String {
+    let popup_target = target.strip_prefix('=').unwrap_or(target);
+    let mut parts = vec!["ssh".to_string()];
+    parts.extend(
+        host.ssh_args(true)
+            .iter()
+            .map(|arg| crate::sandbox::shell_single_quote(arg)),
+    );
+    parts.push(crate::sandbox::shell_single_quote(&remote_command(&[
+        "tmux",
+        "attach-session",
+        "-t",
+        popup_target,
+    ])));
+    parts.join(" ")
+}
+
 fn popup_attach_command_with_server(target: &str, tmux_server: Option<&str>) -> String {
     let mut command = String::from("env -u TMUX tmux");
     if let Some(server) = tmux_server {
@@ -158,16 +269,79 @@ impl TmuxClient {
     pub fn new(prefix: impl Into<String>) -> Self {
         Self {
             prefix: prefix.into(),
+            remote: None,
+            chaos: chaos::from_env(),
         }
     }
 
+    /// Inject faults from `schedule` into every command this client runs.
+    pub fn with_chaos(mut self, schedule: ChaosSchedule) -> Self {
+        self.chaos = Some(Arc::new(Chaos::new(schedule)));
+        self
+    }
+
+    /// Target the tmux server on `host` over SSH instead of the local one.
+    pub fn with_remote(mut self, host: RemoteHost) -> Self {
+        self.remote = Some(host);
+        self
+    }
+
     pub fn prefix(&self) -> &str {
         &self.prefix
     }
 
+    /// Remote host name, or `None` for the local tmux server.
+    pub fn host(&self) -> Option<&str> {
+        self.remote.as_ref().map(|host| host.name.as_str())
+    }
+
+    fn tmux(&self, args: &[&str]) -> Command {
+        match &self.remote {
+            Some(host) => {
+                let mut remote = vec!["tmux"];
+                remote.extend(args);
+                host.command(false, &remote)
+            }
+            None => {
+                let mut cmd = tmux_command();
+                cmd.args(args);
+                cmd
+            }
+        }
+    }
+
+    /// `dir` as the tmux server sees it; remote paths are never translated.
+    fn workdir(&self, dir: &str) -> String {
+        match &self.remote {
+            Some(_) => dir.to_string(),
+            None => super::wsl::workdir(dir),
+        }
+    }
+
+    fn tag_host(&self, mut session: Session) -> Session {
+        session.host = self.host().map(str::to_string);
+        session
+    }
+
+    /// Apply chaos to a command; `true` when it should be dropped.
+    fn inject_chaos(&self, args: &[&str]) -> bool {
+        match self.chaos.as_ref().and_then(|chaos| chaos.inject(args)) {
+            Some(Fault::Kill(session)) => {
+                let target = exact_session_target(&session);
+                let _ = self.tmux(&["kill-session", "-t", &target]).output();
+                false
+            }
+            Some(Fault::Drop) => true,
+            None => false,
+        }
+    }
+
     fn run(&self, args: &[&str]) -> Result<String> {
-        let output = tmux_command()
-            .args(args)
+        if self.inject_chaos(args) {
+            return Ok(String::new());
+        }
+        let output = self
+            .tmux(args)
             .output()
             .context("Failed to execute tmux - is tmux installed?")?;
 
@@ -182,72 +356,38 @@ impl TmuxClient {
             {
                 return Ok(String::new());
             }
-            anyhow::bail!("tmux error: {}", stderr);
+            return Err(TmuxError {
+                command: args.first().copied().unwrap_or_default().to_string(),
+                stderr: stderr.into(),
+            }
+            .into());
         }
         Ok(String::from_utf8_lossy(&output.stdout).into())
     }
 
+    /// `run` for commands that change tmux state, retrying transient
+    /// failures so a busy server does not silently lose the command.
+    fn run_retrying(&self, args: &[&str]) -> Result<String> {
+        with_retry(&RetryPolicy::default(), || self.run(args))
+    }
+
     /// List all sessions matching the prefix
     pub fn list_sessions(&self) -> Result<Vec<Session>> {
-        let output = self.run(&[
-            "list-sessions",
-            "-F",
-            "#{session_name}|#{session_activity}|#{session_attached}|#{pane_pid}",
-        ])?;
-
-        if output.is_empty() {
-            return Ok(Vec::new());
-        }
-
-        let sessions = output
-            .lines()
-            .filter(|line| self.prefix.is_empty() || line.starts_with(&self.prefix))
-            .filter_map(|line| {
-                let parts: Vec<&str> = line.split('|').collect();
-                if parts.len() != 4 {
-                    return None;
-                }
-                Some(Session::new(
-                    parts[0].to_string(),
-                    parts[1].parse().ok()?,
-                    parts[2] == "1",
-                    parts[3].parse().ok()?,
-                ))
-            })
-            .collect();
-
-        Ok(sessions)
+        Ok(self
+            .list_all_sessions()?
+            .into_iter()
+            .filter(|session| self.prefix.is_empty() || session.name.starts_with(&self.prefix))
+            .collect())
     }
 
     /// List all sessions (regardless of prefix)
     pub fn list_all_sessions(&self) -> Result<Vec<Session>> {
-        let output = self.run(&[
-            "list-sessions",
-            "-F",
-            "#{session_name}|#{session_activity}|#{session_attached}|#{pane_pid}",
-        ])?;
-
-        if output.is_empty() {
-            return Ok(Vec::new());
-        }
-
-        let sessions = output
+        let output = self.run(&["list-sessions", "-F", SESSION_FORMAT])?;
+        Ok(output
             .lines()
-            .filter_map(|line| {
-                let parts: Vec<&str> = line.split('|').collect();
-                if parts.len() != 4 {
-                    return None;
-                }
-                Some(Session::new(
-                    parts[0].to_string(),
-                    parts[1].parse().ok()?,
-                    parts[2] == "1",
-                    parts[3].parse().ok()?,
-                ))
-            })
-            .collect();
-
-        Ok(sessions)
+            .filter_map(parse_session_line)
+            .map(|session| self.tag_host(session))
+            .collect())
     }
 
     /// Capture the last N lines of a pane's output, including ANSI escape
@@ -268,18 +408,29 @@ impl TmuxClient {
     /// Capture the last N lines of a pane's output as plain text (no ANSI
     /// escapes). Required for substring matching — e.g. Claude Code renders
     /// its banner as `Claude<ESC>[0m <ESC>[1mCode`, so a raw ANSI capture
-    /// would *not* contain the contiguous string "Claude Code".
+    /// would *not* contain the contiguous string "Claude Code". Wrapped
+    /// lines are joined (`-J`) so text longer than the pane width matches
+    /// whole; the trailing spaces `-J` keeps are trimmed.
     pub fn capture_pane_plain(&self, target: &str, lines: i32) -> Result<String> {
         let target = exact_pane_target(target);
         let output = self.run(&[
             "capture-pane",
+            "-J",
             "-t",
             &target,
             "-p",
             "-S",
             &(-lines).to_string(),
         ])?;
-        Ok(tail_pane_lines(output, lines))
+        let mut trimmed: String = output
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n");
+        if output.ends_with('\n') {
+            trimmed.push('\n');
+        }
+        Ok(tail_pane_lines(trimmed, lines))
     }
 
     /// Get the name of the command currently running in a pane.
@@ -297,6 +448,27 @@ impl TmuxClient {
         Ok(output.trim().to_string())
     }
 
+    /// Pipe new pane output into a shell command (`pipe-pane -o`, so an
+    /// existing pipe is left alone).
+    pub fn pipe_pane(&self, target: &str, command: &str) -> Result<()> {
+        let target = exact_pane_target(target);
+        self.run(&["pipe-pane", "-o", "-t", &target, command])?;
+        Ok(())
+    }
+
+    /// Get the current working directory of a pane.
+    pub fn get_pane_path(&self, target: &str) -> Result<String> {
+        let target = exact_pane_target(target);
+        let output = self.run(&[
+            "display-message",
+            "-t",
+            &target,
+            "-p",
+            "#{pane_current_path}",
+        ])?;
+        Ok(output.trim().to_string())
+    }
+
     /// Get the pane process id.
     pub fn get_pane_pid(&self, target: &str) -> Result<u32> {
         let target = exact_pane_target(target);
@@ -306,11 +478,17 @@ impl TmuxClient {
 
     /// Get the full command line for the process running in a pane.
     pub fn get_pane_process_command(&self, target: &str) -> Result<String> {
-        let pid = self.get_pane_pid(target)?;
-        let output = Command::new("ps")
-            .args(["-p", &pid.to_string(), "-o", "command="])
-            .output()
-            .context("Failed to execute ps")?;
+        let pid = self.get_pane_pid(target)?.to_string();
+        let ps_args = ["ps", "-p", &pid, "-o", "command="];
+        let mut ps = match &self.remote {
+            Some(host) => host.command(false, &ps_args),
+            None => {
+                let mut cmd = Command::new("ps");
+                cmd.args(&ps_args[1..]);
+                cmd
+            }
+        };
+        let output = ps.output().context("Failed to execute ps")?;
         if !output.status.success() {
             anyhow::bail!(
                 "ps failed: {}",
@@ -337,10 +515,20 @@ impl TmuxClient {
             .context("Failed to parse window activity timestamp")
     }
 
+    /// Unix timestamp at which a session was created.
+    pub fn get_session_created(&self, target: &str) -> Result<i64> {
+        let target = exact_pane_target(target);
+        let output = self.run(&["display-message", "-t", &target, "-p", "#{session_created}"])?;
+        output
+            .trim()
+            .parse()
+            .context("Failed to parse session creation timestamp")
+    }
+
     /// Send keys to a pane
     pub fn send_keys(&self, target: &str, keys: &str) -> Result<()> {
         let target = exact_pane_target(target);
-        self.run(&["send-keys", "-t", &target, keys])?;
+        self.run_retrying(&["send-keys", "-t", &target, keys])?;
         Ok(())
     }
 
@@ -355,7 +543,7 @@ impl TmuxClient {
     pub fn send_keys_literal(&self, target: &str, text: &str) -> Result<()> {
         let target = exact_pane_target(target);
         if text.len() < Self::LARGE_PAYLOAD_THRESHOLD {
-            self.run(&["send-keys", "-t", &target, "-l", "--", text])?;
+            self.run_retrying(&["send-keys", "-t", &target, "-l", "--", text])?;
         } else {
             // Avoid passing large text as a CLI arg; owner-only temp file is
             // removed when `tmp` drops.
@@ -367,8 +555,8 @@ impl TmuxClient {
                 .path()
                 .to_str()
                 .context("Temp file path is not valid UTF-8")?;
-            self.run(&["load-buffer", path_str])?;
-            self.run(&["paste-buffer", "-t", &target])?;
+            self.run_retrying(&["load-buffer", path_str])?;
+            self.run_retrying(&["paste-buffer", "-t", &target])?;
         }
         Ok(())
     }
@@ -395,6 +583,15 @@ impl TmuxClient {
     pub fn paste_text(&self, target: &str, text: &str) -> Result<()> {
         let target = exact_pane_target(target);
         let buffer_name = format!("omar-paste-{}", uuid::Uuid::new_v4());
+        if self.remote.is_some() {
+            // The temp file below would live on this machine; stream the
+            // payload over ssh instead.
+            let args = ["load-buffer", "-b", &buffer_name, "-"];
+            with_retry(&RetryPolicy::default(), || {
+                self.run_with_input(&args, text.as_bytes())
+            })?;
+            return self.paste_buffer(&buffer_name, &target);
+        }
 
         // Owner-only temp file (payloads can carry pasted secrets), removed
         // when `tmp` drops.
@@ -406,17 +603,21 @@ impl TmuxClient {
             .path()
             .to_str()
             .context("Temp file path is not valid UTF-8")?;
-        self.run(&["load-buffer", "-b", &buffer_name, path_str])?;
-        // Paste from the named buffer using bracketed paste mode so the
-        // target pane treats it as a single paste operation. `-d` deletes
-        // the buffer after pasting. `-r` preserves LFs verbatim — see the
-        // doc comment above for why this matters for raw-mode TUIs.
-        self.run(&[
+        self.run_retrying(&["load-buffer", "-b", &buffer_name, path_str])?;
+        self.paste_buffer(&buffer_name, &target)
+    }
+
+    /// Paste from the named buffer using bracketed paste mode so the
+    /// target pane treats it as a single paste operation. `-d` deletes
+    /// the buffer after pasting. `-r` preserves LFs verbatim — see
+    /// `paste_text` for why this matters for raw-mode TUIs.
+    fn paste_buffer(&self, buffer_name: &str, target: &str) -> Result<()> {
+        self.run_retrying(&[
             "paste-buffer",
             "-b",
-            &buffer_name,
+            buffer_name,
             "-t",
-            &target,
+            target,
             "-d",
             "-p",
             "-r",
@@ -424,6 +625,31 @@ impl TmuxClient {
         Ok(())
     }
 
+    fn run_with_input(&self, args: &[&str], input: &[u8]) -> Result<String> {
+        if self.inject_chaos(args) {
+            return Ok(String::new());
+        }
+        let mut child = self
+            .tmux(args)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .context("Failed to execute tmux")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(input)?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(TmuxError {
+                command: args.first().copied().unwrap_or_default().to_string(),
+                stderr: String::from_utf8_lossy(&output.stderr).into(),
+            }
+            .into());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into())
+    }
+
     /// Reliably deliver a prompt to a tmux session.
     ///
     /// Backend-agnostic: works for claude, codex, cursor, opencode, or any
@@ -527,7 +753,7 @@ impl TmuxClient {
             let activity_before = self.get_pane_activity(session).unwrap_or(0);
 
             let target = exact_pane_target(session);
-            self.run(&["send-keys", "-t", &target, "-H", "0d"])?;
+            self.run_retrying(&["send-keys", "-t", &target, "-H", "0d"])?;
 
             if self.wait_for_change(
                 session,
@@ -652,33 +878,100 @@ impl TmuxClient {
 
     /// Create a new detached session
     pub fn new_session(&self, name: &str, command: &str, workdir: Option<&str>) -> Result<()> {
+        self.new_session_with_env(name, command, workdir, &[])
+    }
+
+    /// `new_session` with `env` set in the session's environment (`-e`).
+    pub fn new_session_with_env(
+        &self,
+        name: &str,
+        command: &str,
+        workdir: Option<&str>,
+        env: &[(String, String)],
+    ) -> Result<()> {
         let mut args = vec!["new-session", "-d", "-s", name];
 
-        if let Some(dir) = workdir {
+        let workdir = workdir.map(|dir| self.workdir(dir));
+        if let Some(dir) = &workdir {
             args.extend(["-c", dir]);
         }
+        let env: Vec<String> = env
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        for pair in &env {
+            args.extend(["-e", pair.as_str()]);
+        }
 
         // Execute the provided command through a shell so the full string is
         // interpreted consistently (including quoted args and shell metacharacters)
         // instead of relying on tmux's shell-command parser heuristics.
         args.extend(["sh", "-lc", command]);
+        self.run_retrying(&args)?;
+        self.run_retrying(&["set-option", "-t", name, "history-limit", "10000"])?;
+        Ok(())
+    }
+
+    /// Run `command` in an extra pane of session `name`, leaving the agent's
+    /// own pane active.
+    pub fn split_pane(&self, name: &str, command: &str, workdir: Option<&str>) -> Result<()> {
+        let target = exact_pane_target(name);
+        let mut args = vec!["split-window", "-d", "-t", &target];
+        let workdir = workdir.map(|dir| self.workdir(dir));
+        if let Some(dir) = &workdir {
+            args.extend(["-c", dir]);
+        }
+        args.extend(["sh", "-lc", command]);
         self.run(&args)?;
-        self.run(&["set-option", "-t", name, "history-limit", "10000"])?;
+        self.run(&["select-layout", "-t", &target, "tiled"])?;
+        Ok(())
+    }
+
+    /// Pane ids (`%N`) of session `name`, the agent's own pane first.
+    pub fn list_pane_ids(&self, name: &str) -> Result<Vec<String>> {
+        let target = exact_pane_target(name);
+        let output = self.run(&["list-panes", "-t", &target, "-F", "#{pane_id}"])?;
+        Ok(output
+            .lines()
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Create session `group` showing the current window of each of
+    /// `sessions`. The windows are linked, not moved, so the agents' own
+    /// sessions are untouched and killing `group` only drops the links.
+    pub fn link_group(&self, group: &str, sessions: &[String]) -> Result<()> {
+        let placeholder =
+            self.run(&["new-session", "-d", "-P", "-F", "#{window_id}", "-s", group])?;
+        let group_target = format!("{}:", exact_session_target(group));
+        for session in sessions {
+            let source = exact_pane_target(session);
+            self.run(&["link-window", "-d", "-s", &source, "-t", &group_target])?;
+        }
+        self.run(&["kill-window", "-t", placeholder.trim()])?;
+        Ok(())
+    }
+
+    pub fn rename_session(&self, name: &str, new_name: &str) -> Result<()> {
+        let target = exact_session_target(name);
+        self.run_retrying(&["rename-session", "-t", &target, new_name])?;
         Ok(())
     }
 
     /// Kill a session
     pub fn kill_session(&self, name: &str) -> Result<()> {
         let target = exact_session_target(name);
-        self.run(&["kill-session", "-t", &target])?;
+        self.run_retrying(&["kill-session", "-t", &target])?;
         Ok(())
     }
 
     /// Check if a session exists
     pub fn has_session(&self, name: &str) -> Result<bool> {
         let target = exact_session_target(name);
-        let result = tmux_command()
-            .args(["has-session", "-t", &target])
+        let result = self
+            .tmux(&["has-session", "-t", &target])
             .output()
             .context("Failed to execute tmux")?;
 
@@ -692,8 +985,8 @@ impl TmuxClient {
     /// but the session cannot accept input or be attached as a running agent.
     pub fn session_has_live_pane(&self, name: &str) -> Result<bool> {
         let target = exact_session_target(name);
-        let result = tmux_command()
-            .args(["list-panes", "-t", &target, "-F", "#{pane_dead}"])
+        let result = self
+            .tmux(&["list-panes", "-t", &target, "-F", "#{pane_dead}"])
             .output()
             .context("Failed to execute tmux")?;
 
@@ -737,17 +1030,33 @@ impl TmuxClient {
     /// Attach to a session (blocks until detached)
     pub fn attach_session(&self, session: &str) -> Result<()> {
         let target = exact_session_target(session);
-        tmux_command()
-            .args(["attach-session", "-t", &target])
-            .status()
-            .context("Failed to attach to tmux session")?;
+        let mut cmd = match &self.remote {
+            Some(host) => host.command(true, &["tmux", "attach-session", "-t", &target]),
+            None => {
+                let mut cmd = tmux_command();
+                cmd.args(["attach-session", "-t", &target]);
+                cmd
+            }
+        };
+        cmd.status().context("Failed to attach to tmux session")?;
+        Ok(())
+    }
+
+    /// Switch the current tmux client to a session (for use inside tmux,
+    /// where attaching would nest)
+    pub fn switch_client(&self, session: &str) -> Result<()> {
+        let target = exact_session_target(session);
+        self.run(&["switch-client", "-t", &target])?;
         Ok(())
     }
 
     /// Open a popup attached to a session
     pub fn attach_popup(&self, session: &str, width: &str, height: &str) -> Result<()> {
         let target = exact_session_target(session);
-        let command = popup_attach_command(&target);
+        let command = match &self.remote {
+            Some(host) => remote_popup_attach_command(host, &target),
+            None => popup_attach_command(&target),
+        };
         let status = tmux_command()
             .args(["display-popup", "-E", "-w", width, "-h", height, &command])
             .status()
@@ -905,6 +1214,7 @@ mod tests {
         );
         assert_eq!(exact_pane_target("=already-exact"), "=already-exact:");
         assert_eq!(exact_pane_target("session:1.0"), "session:1.0");
+        assert_eq!(exact_pane_target("%12"), "%12");
     }
 
     #[test]
@@ -915,6 +1225,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_remote_host_wraps_tmux_in_ssh() {
+        let host = RemoteHost {
+            name: "build1".to_string(),
+            ssh: "ci@build1".to_string(),
+            control_path: Some("/tmp/omar-%r@%h".to_string()),
+        };
+        let client = TmuxClient::new("omar-agent-").with_remote(host.clone());
+        assert_eq!(client.host(), Some("build1"));
+        assert_eq!(TmuxClient::new("").host(), None);
+
+        let cmd = client.tmux(&["capture-pane", "-t", "=a b:"]);
+        let args: Vec<String> = cmd
+            .get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(cmd.get_program(), "ssh");
+        assert_eq!(args[0], "-T");
+        assert!(args.contains(&"ControlPath=/tmp/omar-%r@%h".to_string()));
+        assert_eq!(args[args.len() - 2], "ci@build1");
+        assert_eq!(args[args.len() - 1], "'tmux' 'capture-pane' '-t' '=a b:'");
+
+        let popup = remote_popup_attach_command(&host, "=omar-agent-0-api");
+        assert!(popup.starts_with("ssh '-t' "));
+        assert!(popup.contains("'ci@build1'"));
+        assert!(popup.contains("attach-session"));
+        assert!(popup.contains("omar-agent-0-api"));
+        assert!(!popup.contains("=omar"));
+    }
+
     #[test]
     fn test_popup_attach_command_preserves_custom_tmux_server() {
         assert_eq!(
@@ -950,6 +1290,90 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_link_group_shows_agent_windows_without_moving_them() {
+        if !tmux_available() {
+            eprintln!("Skipping test: tmux not available");
+            return;
+        }
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        let client = TmuxClient::new("omar-test-");
+        let sessions = vec![format!("omar-test-a-{id}"), format!("omar-test-b-{id}")];
+        for session in &sessions {
+            if client.new_session(session, "cat", None).is_err() {
+                eprintln!("Skipping test: failed to create tmux session");
+                return;
+            }
+        }
+        let _guards: Vec<_> = sessions.iter().cloned().map(SessionGuard).collect();
+        let group = format!("omar-test@group-{id}");
+        client.link_group(&group, &sessions).unwrap();
+        let _group_guard = SessionGuard(group.clone());
+        let window_ids = |name: &str| {
+            client
+                .run(&[
+                    "list-windows",
+                    "-t",
+                    &exact_session_target(name),
+                    "-F",
+                    "#{window_id}",
+                ])
+                .unwrap()
+                .lines()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        let expected: Vec<String> = sessions.iter().flat_map(|s| window_ids(s)).collect();
+        assert_eq!(window_ids(&group), expected);
+
+        client.kill_session(&group).unwrap();
+        for session in &sessions {
+            assert!(client.has_session(session).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_split_pane_keeps_agent_pane_first() {
+        if !tmux_available() {
+            eprintln!("Skipping test: tmux not available");
+            return;
+        }
+        let session = format!("omar-test-panes-{}", uuid::Uuid::new_v4().simple());
+        let client = TmuxClient::new("omar-test-");
+        if client.new_session(&session, "cat", None).is_err() {
+            eprintln!("Skipping test: failed to create tmux session");
+            return;
+        }
+        let _guard = SessionGuard(session.clone());
+        let agent = client.list_pane_ids(&session).unwrap();
+        client
+            .split_pane(&session, "echo OMAR_AUX_PANE; cat", None)
+            .unwrap();
+        let panes = client.list_pane_ids(&session).unwrap();
+        assert_eq!(panes.len(), 2);
+        assert_eq!(panes[0], agent[0]);
+        let mut output = String::new();
+        for _ in 0..30 {
+            output = client.capture_pane_plain(&panes[1], 20).unwrap_or_default();
+            if output.contains("OMAR_AUX_PANE") {
+                break;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        assert!(output.contains("OMAR_AUX_PANE"), "{}", output);
+        // The agent's pane is still the one health checks capture
+        let active = client
+            .run(&[
+                "display-message",
+                "-p",
+                "-t",
+                &exact_pane_target(&session),
+                "#{pane_id}",
+            ])
+            .unwrap();
+        assert_eq!(active.trim(), agent[0]);
+    }
+
     /// Deliver a prompt to a shell session and verify the command actually ran.
     #[test]
     fn test_deliver_prompt_to_shell_session() {
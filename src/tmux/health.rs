@@ -1,16 +1,39 @@
 #![allow(dead_code)]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, OnceLock};
 
-use super::TmuxClient;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::backend_health::{Activity, ActivityDetector};
+use super::health_plugin::HealthPlugin;
+use super::rate_limit::{RateLimit, RateLimitDetector};
+use super::{CaptureCache, Mux, Session};
+
+/// Non-empty trailing lines scanned for completion markers.
+const RESULT_TAIL_LINES: usize = 40;
+
+/// Non-empty trailing lines searched for an open question.
+const QUESTION_TAIL_LINES: usize = 8;
+
+/// State transitions kept per session (ring buffer).
+pub const HEALTH_HISTORY_LEN: usize = 64;
 
 /// Health state of an agent
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum HealthState {
     /// Agent is actively producing output
     Running,
     /// Agent has not produced new output recently
     Idle,
+    /// Agent went idle after printing `[TASK COMPLETE]`
+    Finished,
+    /// Agent went idle after printing `[BLOCKED: ...]` or `[NEED INPUT: ...]`
+    Blocked,
+    /// Agent output shows a provider rate-limit message
+    RateLimited,
 }
 
 impl HealthState {
@@ -18,63 +41,610 @@ impl HealthState {
         match self {
             HealthState::Running => "running",
             HealthState::Idle => "idle",
+            HealthState::Finished => "finished",
+            HealthState::Blocked => "blocked",
+            HealthState::RateLimited => "rate_limited",
+        }
+    }
+
+    /// How urgently a human should look: blocked > rate_limited > idle >
+    /// running > finished.
+    pub fn attention_rank(&self) -> u8 {
+        match self {
+            HealthState::Finished => 0,
+            HealthState::Running => 1,
+            HealthState::Idle => 2,
+            HealthState::RateLimited => 3,
+            HealthState::Blocked => 4,
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "running" => Some(HealthState::Running),
+            "idle" => Some(HealthState::Idle),
+            "finished" => Some(HealthState::Finished),
+            "blocked" => Some(HealthState::Blocked),
+            "rate_limited" => Some(HealthState::RateLimited),
+            _ => None,
+        }
+    }
+}
+
+/// Derived "team health" per parent: the state needing the most attention
+/// among all its descendants. Sessions without children are absent.
+pub fn team_health(
+    health: &HashMap<String, HealthState>,
+    parents: &HashMap<String, String>,
+) -> HashMap<String, HealthState> {
+    let mut teams: HashMap<String, HealthState> = HashMap::new();
+    for (session, state) in health {
+        let mut current = session;
+        for _ in 0..=parents.len() {
+            let Some(parent) = parents.get(current) else {
+                break;
+            };
+            let team = teams.entry(parent.clone()).or_insert(*state);
+            if state.attention_rank() > team.attention_rank() {
+                *team = *state;
+            }
+            current = parent;
+        }
+    }
+    teams
+}
+
+/// Kind of completion marker an agent printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultKind {
+    Complete,
+    Blocked,
+    NeedInput,
+}
+
+impl ResultKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResultKind::Complete => "complete",
+            ResultKind::Blocked => "blocked",
+            ResultKind::NeedInput => "need_input",
         }
     }
 }
 
+/// Structured result parsed from an agent's completion marker.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentResult {
+    pub kind: ResultKind,
+    /// Summary lines for `[TASK COMPLETE]`, otherwise the reason/question.
+    pub detail: String,
+    /// RFC3339 time the marker was first seen.
+    #[serde(default)]
+    pub detected_at: String,
+}
+
+impl AgentResult {
+    /// True when both results describe the same marker (ignores timestamps).
+    pub fn same_marker(&self, other: &AgentResult) -> bool {
+        self.kind == other.kind && self.detail == other.detail
+    }
+}
+
+/// Find the last completion marker in the tail of `output`.
+///
+/// Markers must sit on their own line; leading bullets backends draw in
+/// front of assistant text (`⏺`, `•`) are ignored. `detected_at` is left
+/// empty for the caller to stamp.
+pub fn detect_result(output: &str) -> Option<AgentResult> {
+    static MARKER_RE: OnceLock<Regex> = OnceLock::new();
+    let marker_re = MARKER_RE.get_or_init(|| {
+        Regex::new(r"^\[(?:(TASK COMPLETE)|(BLOCKED|NEED INPUT):\s*(.*?))\]$").unwrap()
+    });
+
+    let lines: Vec<&str> = output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    let tail = &lines[lines.len().saturating_sub(RESULT_TAIL_LINES)..];
+
+    let (idx, caps) = tail.iter().enumerate().rev().find_map(|(idx, line)| {
+        let line = line.trim_start_matches(|c: char| !c.is_alphanumeric() && c != '[');
+        marker_re.captures(line).map(|caps| (idx, caps))
+    })?;
+
+    if caps.get(1).is_some() {
+        // Summary bullets follow the marker; stop at the backend's input box.
+        let detail = tail[idx + 1..]
+            .iter()
+            .skip_while(|line| line.eq_ignore_ascii_case("summary:"))
+            .map_while(|line| line.strip_prefix("- "))
+            .collect::<Vec<_>>()
+            .join("; ");
+        return Some(AgentResult {
+            kind: ResultKind::Complete,
+            detail,
+            detected_at: String::new(),
+        });
+    }
+
+    let kind = match caps.get(2).map(|m| m.as_str()) {
+        Some("BLOCKED") => ResultKind::Blocked,
+        _ => ResultKind::NeedInput,
+    };
+    Some(AgentResult {
+        kind,
+        detail: caps.get(3).map_or("", |m| m.as_str().trim()).to_string(),
+        detected_at: String::new(),
+    })
+}
+
+/// What an idle agent is waiting on someone to answer: the text of a
+/// trailing `[NEED INPUT: ...]` or `[BLOCKED: ...]` marker, or else its last
+/// line of prose when that ends in `?`. The backend's input box, hints, and
+/// mode line below it are skipped.
+pub fn detect_question(output: &str) -> Option<String> {
+    if let Some(result) = detect_result(output) {
+        return (result.kind != ResultKind::Complete && !result.detail.is_empty())
+            .then_some(result.detail);
+    }
+    let line = output
+        .lines()
+        .map(str::trim)
+        .rev()
+        .filter(|line| !line.is_empty())
+        .take(QUESTION_TAIL_LINES)
+        .find(|line| !is_chrome(line))?;
+    let line = line.trim_start_matches(|c: char| !c.is_alphanumeric() && !"\"'`(".contains(c));
+    line.ends_with('?').then(|| line.to_string())
+}
+
+/// Backend UI drawn under the conversation rather than agent output.
+fn is_chrome(line: &str) -> bool {
+    line.starts_with(['│', '╭', '╰', '─', '┌', '└', '>', '❯', '›', '⏵'])
+        || !line.chars().any(char::is_alphabetic)
+        || line.contains("for shortcuts")
+        || line.contains("shift+tab")
+}
+
+fn strip_ansi(output: &str) -> String {
+    static ANSI_RE: OnceLock<Regex> = OnceLock::new();
+    let ansi_re = ANSI_RE.get_or_init(|| {
+        Regex::new(r"\x1b(?:\[[0-?]*[ -/]*[@-~]|\][^\x07]*(?:\x07|\x1b\\))").unwrap()
+    });
+    ansi_re.replace_all(output, "").to_string()
+}
+
+/// A change of health state; `at` is a Unix timestamp in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthTransition {
+    pub at: i64,
+    pub state: HealthState,
+}
+
 /// Checks health of agent sessions by comparing pane content between frames.
 /// If the pane content has changed since the last check, the session is Running;
-/// otherwise it is Idle.
+/// otherwise it is Idle, or Finished/Blocked when a completion marker is visible.
 pub struct HealthChecker {
-    client: TmuxClient,
+    client: Arc<dyn Mux>,
     /// Last captured pane content per session name
     last_frames: HashMap<String, String>,
+    /// Completion marker seen on the last idle check per session name
+    results: HashMap<String, AgentResult>,
+    /// Question an idle session is waiting on, per session name
+    questions: HashMap<String, String>,
+    /// Optional external classifier merged over the built-in verdict
+    plugin: Option<HealthPlugin>,
+    /// Plugin verdict per session, keyed by the built-in state it was given;
+    /// reused while the pane and built-in state are unchanged
+    plugin_verdicts: HashMap<String, (HealthState, Option<HealthState>)>,
+    rate_limiter: Option<RateLimitDetector>,
+    /// Rate-limit message behind the last RateLimited check per session
+    rate_limits: HashMap<String, RateLimit>,
+    /// Per-backend working/waiting patterns read over the frame diff
+    activity: ActivityDetector,
+    /// Pane command per session, for per-backend patterns
+    backends: HashMap<String, String>,
+    /// Recent state transitions per session, oldest first
+    history: HashMap<String, VecDeque<HealthTransition>>,
+    /// Captures reused by `check_session` while a pane is quiet
+    captures: CaptureCache,
 }
 
 impl HealthChecker {
-    pub fn new(client: TmuxClient, _idle_threshold: i64) -> Self {
+    pub fn new(client: Arc<dyn Mux>, _idle_threshold: i64) -> Self {
         Self {
             client,
             last_frames: HashMap::new(),
+            results: HashMap::new(),
+            questions: HashMap::new(),
+            plugin: None,
+            plugin_verdicts: HashMap::new(),
+            rate_limiter: None,
+            rate_limits: HashMap::new(),
+            activity: ActivityDetector::default(),
+            backends: HashMap::new(),
+            history: HashMap::new(),
+            captures: CaptureCache::default(),
         }
     }
 
+    pub fn with_rate_limits(mut self, detector: RateLimitDetector) -> Self {
+        self.rate_limiter = Some(detector);
+        self
+    }
+
+    /// Swap in new rate-limit patterns, e.g. after a config change.
+    pub fn set_rate_limits(&mut self, detector: RateLimitDetector) {
+        self.rate_limiter = Some(detector);
+    }
+
+    pub fn with_activity(mut self, detector: ActivityDetector) -> Self {
+        self.activity = detector;
+        self
+    }
+
+    /// Swap in new per-backend activity patterns, e.g. after a config change.
+    pub fn set_activity(&mut self, detector: ActivityDetector) {
+        self.activity = detector;
+    }
+
+    pub fn with_plugin(mut self, plugin: Option<HealthPlugin>) -> Self {
+        self.plugin = plugin;
+        self
+    }
+
+    /// Swap the external classifier, dropping verdicts of the old one.
+    pub fn set_plugin(&mut self, plugin: Option<HealthPlugin>) {
+        self.plugin = plugin;
+        self.plugin_verdicts.clear();
+    }
+
     /// Check the health of a session by comparing against the previous frame.
     /// Returns Running if pane content changed, Idle if unchanged.
     pub fn check(&mut self, session_name: &str) -> HealthState {
+        self.check_lines(session_name, 50)
+    }
+
+    /// `check` against the last `lines` lines of the pane.
+    pub fn check_lines(&mut self, session_name: &str, lines: i32) -> HealthState {
         let current = self
             .client
-            .capture_pane(session_name, 50)
+            .capture_pane(session_name, lines)
+            .unwrap_or_default();
+        self.classify(session_name, current)
+    }
+
+    /// `check_lines` for a listed session, skipping `capture-pane` while
+    /// its stamp shows no new output.
+    pub fn check_session(&mut self, session: &Session, lines: i32) -> HealthState {
+        let current = self
+            .captures
+            .capture(session, lines, || {
+                self.client.capture_pane(&session.name, lines)
+            })
             .unwrap_or_default();
+        self.classify(&session.name, current)
+    }
 
+    fn classify(&mut self, session_name: &str, current: String) -> HealthState {
         let changed = match self.last_frames.get(session_name) {
             Some(prev) => *prev != current,
             None => true, // First check — assume running
         };
+        let busy = match self.check_activity(session_name, &current) {
+            Some(Activity::Working) => true,
+            Some(Activity::Waiting) => false,
+            None => changed,
+        };
 
+        let builtin = if busy {
+            self.results.remove(session_name);
+            self.questions.remove(session_name);
+            HealthState::Running
+        } else {
+            let plain = strip_ansi(&current);
+            match detect_question(&plain) {
+                Some(question) => self.questions.insert(session_name.to_string(), question),
+                None => self.questions.remove(session_name),
+            };
+            match detect_result(&plain) {
+                Some(result) => {
+                    let state = match result.kind {
+                        ResultKind::Complete => HealthState::Finished,
+                        ResultKind::Blocked | ResultKind::NeedInput => HealthState::Blocked,
+                    };
+                    self.results.insert(session_name.to_string(), result);
+                    state
+                }
+                None => {
+                    self.results.remove(session_name);
+                    HealthState::Idle
+                }
+            }
+        };
+
+        let builtin = match builtin {
+            HealthState::Running | HealthState::Idle => self
+                .check_rate_limit(session_name, &current)
+                .unwrap_or(builtin),
+            _ => {
+                self.rate_limits.remove(session_name);
+                builtin
+            }
+        };
+
+        let state = match &self.plugin {
+            Some(plugin) => {
+                let cached = self
+                    .plugin_verdicts
+                    .get(session_name)
+                    .filter(|(input, _)| !changed && *input == builtin)
+                    .map(|(_, verdict)| *verdict);
+                let verdict = cached.unwrap_or_else(|| {
+                    plugin.classify(session_name, builtin, &strip_ansi(&current))
+                });
+                self.plugin_verdicts
+                    .insert(session_name.to_string(), (builtin, verdict));
+                verdict.unwrap_or(builtin)
+            }
+            None => builtin,
+        };
         self.last_frames.insert(session_name.to_string(), current);
+        self.record(session_name, state, chrono::Utc::now().timestamp());
+        state
+    }
 
-        if changed {
-            HealthState::Running
+    /// Append a transition when `state` differs from the last one recorded.
+    fn record(&mut self, session_name: &str, state: HealthState, at: i64) {
+        let history = self.history.entry(session_name.to_string()).or_default();
+        if history.back().is_some_and(|last| last.state == state) {
+            return;
+        }
+        if history.len() == HEALTH_HISTORY_LEN {
+            history.pop_front();
+        }
+        history.push_back(HealthTransition { at, state });
+    }
+
+    /// State from the session's last check, if it has been checked.
+    pub fn last_state(&self, session_name: &str) -> Option<HealthState> {
+        self.history
+            .get(session_name)
+            .and_then(|history| history.back())
+            .map(|transition| transition.state)
+    }
+
+    /// Recent state transitions of a session, oldest first.
+    pub fn history(&self, session_name: &str) -> Vec<HealthTransition> {
+        self.history
+            .get(session_name)
+            .map(|history| history.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Pane command of a session, looked up once.
+    fn lookup_backend(&mut self, session_name: &str) -> Option<&str> {
+        if !self.backends.contains_key(session_name) {
+            if let Ok(command) = self.client.get_pane_command(session_name) {
+                self.backends.insert(session_name.to_string(), command);
+            }
+        }
+        self.backends.get(session_name).map(String::as_str)
+    }
+
+    fn check_activity(&mut self, session_name: &str, current: &str) -> Option<Activity> {
+        if self.activity.is_empty() {
+            return None;
+        }
+        let backend = self.lookup_backend(session_name)?.to_string();
+        self.activity.detect(Some(&backend), &strip_ansi(current))
+    }
+
+    fn check_rate_limit(&mut self, session_name: &str, current: &str) -> Option<HealthState> {
+        let has_backend_patterns = self.rate_limiter.as_ref()?.has_backend_patterns();
+        let backend = if has_backend_patterns {
+            self.lookup_backend(session_name).map(str::to_string)
         } else {
-            HealthState::Idle
+            None
+        };
+        let detector = self.rate_limiter.as_ref()?;
+        match detector.detect(backend.as_deref(), &strip_ansi(current)) {
+            Some(limit) => {
+                self.rate_limits.insert(session_name.to_string(), limit);
+                Some(HealthState::RateLimited)
+            }
+            None => {
+                self.rate_limits.remove(session_name);
+                None
+            }
         }
     }
 
+    /// Rate-limit message behind the last RateLimited check of a session.
+    pub fn rate_limit(&self, session_name: &str) -> Option<&RateLimit> {
+        self.rate_limits.get(session_name)
+    }
+
+    /// Completion marker behind the last Finished/Blocked check of a session.
+    pub fn result(&self, session_name: &str) -> Option<&AgentResult> {
+        self.results.get(session_name)
+    }
+
+    /// Pane command of a session, once a per-backend pattern needed it.
+    pub fn backend(&self, session_name: &str) -> Option<&str> {
+        self.backends.get(session_name).map(String::as_str)
+    }
+
+    /// Question behind the last idle check of a session, if it asked one.
+    pub fn question(&self, session_name: &str) -> Option<&str> {
+        self.questions.get(session_name).map(String::as_str)
+    }
+
     /// Remove stale entries for sessions that no longer exist
     pub fn retain_sessions(&mut self, active_sessions: &[String]) {
+        self.captures.retain(active_sessions);
         self.last_frames
             .retain(|name, _| active_sessions.contains(name));
+        self.results
+            .retain(|name, _| active_sessions.contains(name));
+        self.questions
+            .retain(|name, _| active_sessions.contains(name));
+        self.plugin_verdicts
+            .retain(|name, _| active_sessions.contains(name));
+        self.rate_limits
+            .retain(|name, _| active_sessions.contains(name));
+        self.backends
+            .retain(|name, _| active_sessions.contains(name));
+        self.history
+            .retain(|name, _| active_sessions.contains(name));
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tmux::TmuxClient;
 
     #[test]
     fn test_health_state_display() {
         assert_eq!(HealthState::Running.as_str(), "running");
         assert_eq!(HealthState::Idle.as_str(), "idle");
+        assert_eq!(HealthState::Finished.as_str(), "finished");
+        assert_eq!(HealthState::Blocked.as_str(), "blocked");
+        assert_eq!(HealthState::RateLimited.as_str(), "rate_limited");
+        assert_eq!(
+            HealthState::parse("rate_limited"),
+            Some(HealthState::RateLimited)
+        );
+    }
+
+    #[test]
+    fn test_team_health_is_worst_descendant() {
+        let health: HashMap<String, HealthState> = [
+            ("ea", HealthState::Running),
+            ("pm", HealthState::Running),
+            ("w1", HealthState::Finished),
+            ("w2", HealthState::Idle),
+            ("sub", HealthState::Blocked),
+            ("solo", HealthState::Idle),
+        ]
+        .into_iter()
+        .map(|(name, state)| (name.to_string(), state))
+        .collect();
+        let parents: HashMap<String, String> = [
+            ("pm", "ea"),
+            ("w1", "pm"),
+            ("w2", "pm"),
+            ("sub", "w2"),
+            ("solo", "ea"),
+        ]
+        .into_iter()
+        .map(|(child, parent)| (child.to_string(), parent.to_string()))
+        .collect();
+        let teams = team_health(&health, &parents);
+        assert_eq!(teams.get("pm"), Some(&HealthState::Blocked));
+        assert_eq!(teams.get("w2"), Some(&HealthState::Blocked));
+        assert_eq!(teams.get("ea"), Some(&HealthState::Blocked));
+        assert_eq!(teams.get("w1"), None);
+        assert_eq!(teams.get("solo"), None);
+    }
+
+    #[test]
+    fn test_detect_result_parses_task_complete_summary() {
+        let output = "working...\n⏺ [TASK COMPLETE]\n\n  Summary:\n  - Added retry logic\n  - Changed src/net.rs\n\n> \n? for shortcuts\n";
+        let result = detect_result(output).unwrap();
+        assert_eq!(result.kind, ResultKind::Complete);
+        assert_eq!(result.detail, "Added retry logic; Changed src/net.rs");
+    }
+
+    #[test]
+    fn test_detect_result_parses_blocked_and_need_input() {
+        let blocked = detect_result("• [BLOCKED: missing AWS credentials]\n").unwrap();
+        assert_eq!(blocked.kind, ResultKind::Blocked);
+        assert_eq!(blocked.detail, "missing AWS credentials");
+
+        let question = detect_result("[NEED INPUT: which branch should I target?]").unwrap();
+        assert_eq!(question.kind, ResultKind::NeedInput);
+        assert_eq!(question.detail, "which branch should I target?");
+    }
+
+    #[test]
+    fn test_detect_result_ignores_inline_and_stale_markers() {
+        assert!(detect_result("Do NOT type `[TASK COMPLETE]` until done").is_none());
+
+        let mut output = String::from("[TASK COMPLETE]\n");
+        for i in 0..RESULT_TAIL_LINES {
+            output.push_str(&format!("line {}\n", i));
+        }
+        assert!(detect_result(&output).is_none());
+
+        let latest = detect_result("[BLOCKED: tests fail]\nretrying\n[TASK COMPLETE]\n").unwrap();
+        assert_eq!(latest.kind, ResultKind::Complete);
+    }
+
+    #[test]
+    fn test_detect_question_skips_backend_chrome() {
+        let output =
+            "⏺ Should I also update the docs?\n\n╭──────╮\n│ >    │\n╰──────╯\n  ? for shortcuts\n";
+        assert_eq!(
+            detect_question(output).as_deref(),
+            Some("Should I also update the docs?")
+        );
+        assert_eq!(
+            detect_question("[NEED INPUT: which branch?]\n> ").as_deref(),
+            Some("which branch?")
+        );
+        assert_eq!(
+            detect_question("[BLOCKED: no token]").as_deref(),
+            Some("no token")
+        );
+        assert!(detect_question("Ready?\nStarting the build now.\n> ").is_none());
+        assert!(detect_question("[TASK COMPLETE]\nAnything else?").is_none());
+    }
+
+    #[test]
+    fn test_strip_ansi_removes_color_codes() {
+        assert_eq!(
+            strip_ansi("\x1b[1m[TASK COMPLETE]\x1b[0m"),
+            "[TASK COMPLETE]"
+        );
+    }
+
+    #[test]
+    fn test_history_records_changes_only_and_is_bounded() {
+        let mut checker = HealthChecker::new(Arc::new(TmuxClient::new("test-")), 15);
+        checker.record("a", HealthState::Running, 1);
+        checker.record("a", HealthState::Running, 2);
+        checker.record("a", HealthState::Idle, 3);
+        assert_eq!(
+            checker.history("a"),
+            vec![
+                HealthTransition {
+                    at: 1,
+                    state: HealthState::Running
+                },
+                HealthTransition {
+                    at: 3,
+                    state: HealthState::Idle
+                },
+            ]
+        );
+        for at in 0..(HEALTH_HISTORY_LEN as i64 * 2) {
+            let state = if at % 2 == 0 {
+                HealthState::Running
+            } else {
+                HealthState::Blocked
+            };
+            checker.record("a", state, 10 + at);
+        }
+        assert_eq!(checker.history("a").len(), HEALTH_HISTORY_LEN);
+        checker.retain_sessions(&[]);
+        assert!(checker.history("a").is_empty());
+        assert_eq!(
+            serde_json::to_string(&HealthState::RateLimited).unwrap(),
+            "\"rate_limited\""
+        );
     }
 }
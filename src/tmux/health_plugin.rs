@@ -0,0 +1,158 @@
+//! External health classifier configured under `[health.plugin]`.
+//!
+//! The command runs via `sh -c` with the recent plain-text pane output on
+//! stdin and `OMAR_SESSION` / `OMAR_HEALTH` (the built-in verdict) in its
+//! environment. It prints a JSON object such as `{"state": "blocked"}`;
+//! a missing or null `state` keeps the built-in verdict.
+
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use super::HealthState;
+use crate::config::HealthPluginConfig;
+
+pub struct HealthPlugin {
+    command: String,
+    timeout: Duration,
+}
+
+#[derive(Deserialize)]
+struct Verdict {
+    #[serde(default)]
+    state: Option<String>,
+}
+
+impl HealthPlugin {
+    /// `None` when no plugin command is configured.
+    pub fn from_config(config: Option<&HealthPluginConfig>) -> Option<Self> {
+        let config = config?;
+        let command = config.command.trim();
+        if command.is_empty() {
+            return None;
+        }
+        Some(Self {
+            command: command.to_string(),
+            timeout: Duration::from_millis(config.timeout_ms),
+        })
+    }
+
+    /// Run the classifier. Returns `None` (keep the built-in verdict) when
+    /// the command fails, times out, or prints no usable state.
+    pub fn classify(
+        &self,
+        session: &str,
+        builtin: HealthState,
+        output: &str,
+    ) -> Option<HealthState> {
+        let mut child = Command::new("sh")
+            .args(["-c", &self.command])
+            .env("OMAR_SESSION", session)
+            .env("OMAR_HEALTH", builtin.as_str())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(output.as_bytes());
+        }
+
+        let start = Instant::now();
+        loop {
+            match child.try_wait() {
+                Ok(Some(status)) if status.success() => break,
+                Ok(None) if start.elapsed() < self.timeout => {
+                    thread::sleep(Duration::from_millis(10));
+                }
+                _ => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return None;
+                }
+            }
+        }
+
+        let mut stdout = String::new();
+        child.stdout.take()?.read_to_string(&mut stdout).ok()?;
+        parse_verdict(&stdout)
+    }
+}
+
+fn parse_verdict(stdout: &str) -> Option<HealthState> {
+    let verdict: Verdict = serde_json::from_str(stdout.trim()).ok()?;
+    HealthState::parse(verdict.state.as_deref()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plugin(command: &str, timeout_ms: u64) -> HealthPlugin {
+        HealthPlugin::from_config(Some(&HealthPluginConfig {
+            command: command.to_string(),
+            timeout_ms,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_verdict() {
+        assert_eq!(
+            parse_verdict(r#"{"state":"blocked","reason":"quota"}"#),
+            Some(HealthState::Blocked)
+        );
+        assert_eq!(parse_verdict(r#"{"state":null}"#), None);
+        assert_eq!(parse_verdict(r#"{"state":"exploded"}"#), None);
+        assert_eq!(parse_verdict("not json"), None);
+    }
+
+    #[test]
+    fn test_from_config_ignores_blank_command() {
+        let config = HealthPluginConfig {
+            command: "  ".to_string(),
+            timeout_ms: 100,
+        };
+        assert!(HealthPlugin::from_config(Some(&config)).is_none());
+        assert!(HealthPlugin::from_config(None).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_classify_reads_stdin_and_env() {
+        let plugin = plugin(
+            r#"if grep -q 'quota exceeded'; then echo '{"state":"blocked"}'; else echo "{\"state\":\"$OMAR_HEALTH\"}"; fi"#,
+            2000,
+        );
+        assert_eq!(
+            plugin.classify("s", HealthState::Running, "error: quota exceeded\n"),
+            Some(HealthState::Blocked)
+        );
+        assert_eq!(
+            plugin.classify("s", HealthState::Idle, "all good\n"),
+            Some(HealthState::Idle)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_classify_times_out_and_ignores_failures() {
+        let start = Instant::now();
+        assert_eq!(
+            plugin("sleep 5", 100).classify("s", HealthState::Idle, ""),
+            None
+        );
+        assert!(start.elapsed() < Duration::from_secs(3));
+        assert_eq!(
+            plugin("echo '{\"state\":\"idle\"}'; exit 1", 2000).classify(
+                "s",
+                HealthState::Running,
+                ""
+            ),
+            None
+        );
+    }
+}
@@ -1,10 +1,36 @@
+mod backend_health;
+mod capture_cache;
+mod chaos;
 mod client;
 mod health;
+mod health_plugin;
+mod mux;
+mod rate_limit;
+mod readiness;
+mod retry;
+mod screen;
 mod session;
+mod wezterm;
+pub mod wsl;
+mod zellij;
 
-pub use client::{tmux_command, DeliveryOptions, TmuxClient};
-pub use health::{HealthChecker, HealthState};
+pub use backend_health::{Activity, ActivityDetector};
+pub use capture_cache::CaptureCache;
+#[cfg(unix)]
+pub use client::tmux_socket_path;
+pub use client::{tmux_command, DeliveryOptions, RemoteHost, TmuxClient};
+pub use health::{
+    detect_question, detect_result, team_health, AgentResult, HealthChecker, HealthState,
+    HealthTransition, ResultKind, HEALTH_HISTORY_LEN,
+};
+pub use health_plugin::HealthPlugin;
+pub use mux::{mux_for, Mux};
+pub use rate_limit::{RateLimit, RateLimitDetector};
+pub use readiness::{ReadinessProbe, SpawnStatus};
+pub use screen::ScreenMux;
 pub use session::Session;
+pub use wezterm::WeztermMux;
+pub use zellij::ZellijMux;
 
 /// Readiness markers for each supported backend — strings that must ALL
 /// appear in a backend's rendered TUI before the pane is considered ready
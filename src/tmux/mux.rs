@@ -0,0 +1,255 @@
+//! Terminal multiplexer abstraction — the session primitives orchestration
+//! needs (create, list, capture, type into, kill), so a backend other than
+//! tmux can host agents
+//!
+//! `TmuxClient` implements this trait; `WeztermMux` drives `wezterm cli`
+//! for hosts without tmux (notably Windows, via ConPTY). tmux-only features
+//! — popups, `pipe-pane` transcripts, bracketed paste delivery, SSH hosts —
+//! stay on `TmuxClient`. Code that only needs the primitives should take
+//! `&dyn Mux`; `dashboard.multiplexer` selects the backend for those paths
+//! (`tmux`, `screen`, `zellij`, `wezterm`, or `wsl` — tmux inside WSL,
+//! see `wsl`). The dashboard poller and the MCP server list, check and
+//! kill agents through it.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+
+use super::{ScreenMux, Session, TmuxClient, WeztermMux, ZellijMux};
+use crate::config::Multiplexer;
+
+pub trait Mux: Send + Sync {
+    /// Backend name, e.g. `tmux` or `wezterm`
+    fn backend(&self) -> &'static str;
+
+    /// Session name prefix this client is scoped to
+    fn prefix(&self) -> &str;
+
+    /// Sessions whose names start with the prefix
+    fn list_sessions(&self) -> Result<Vec<Session>>;
+
+    /// Every session, whatever its prefix
+    fn list_all_sessions(&self) -> Result<Vec<Session>> {
+        self.list_sessions()
+    }
+
+    fn has_session(&self, name: &str) -> Result<bool>;
+
+    /// SSH host the sessions live on; `None` for local ones
+    fn host(&self) -> Option<&str> {
+        None
+    }
+
+    /// The session, or an error when it is missing or a client is attached
+    fn ensure_session_not_attached(&self, name: &str) -> Result<Session> {
+        let session = self
+            .list_all_sessions()?
+            .into_iter()
+            .find(|session| session.name == name)
+            .ok_or_else(|| anyhow!("Session '{}' not found", name))?;
+        if session.attached {
+            anyhow::bail!("Cannot kill attached session")
+        }
+        Ok(session)
+    }
+
+    /// True when the session exists and its process is still running
+    fn session_has_live_pane(&self, name: &str) -> Result<bool>;
+
+    /// Start `command` through a shell in a new detached session
+    fn new_session(&self, name: &str, command: &str, workdir: Option<&str>) -> Result<()>;
+
+    fn kill_session(&self, name: &str) -> Result<()>;
+
+    /// Last `lines` lines of output without ANSI escapes
+    fn capture_pane_plain(&self, target: &str, lines: i32) -> Result<String>;
+
+    /// Last `lines` lines of output, with colors where the backend keeps them
+    fn capture_pane(&self, target: &str, lines: i32) -> Result<String> {
+        self.capture_pane_plain(target, lines)
+    }
+
+    /// Name of the command running in the session, e.g. `claude`
+    fn get_pane_command(&self, _target: &str) -> Result<String> {
+        anyhow::bail!("{} does not report pane commands", self.backend())
+    }
+
+    /// Unix timestamp of the last output
+    fn get_pane_activity(&self, target: &str) -> Result<i64>;
+
+    /// Send one named key (tmux key names: `Enter`, `Escape`, `C-c`, ...)
+    fn send_keys(&self, target: &str, keys: &str) -> Result<()>;
+
+    /// Type `text` as-is
+    fn send_keys_literal(&self, target: &str, text: &str) -> Result<()>;
+
+    /// Pipe new output into a shell command, for transcripts
+    fn pipe_pane(&self, _target: &str, _command: &str) -> Result<()> {
+        anyhow::bail!("{} does not support output piping", self.backend())
+    }
+}
+
+/// Client for `prefix` on the configured backend.
+pub fn mux_for(multiplexer: Multiplexer, prefix: impl Into<String>) -> Arc<dyn Mux> {
+    match multiplexer {
+        // `tmux_command` goes through wsl.exe once WSL mode is configured
+        Multiplexer::Tmux | Multiplexer::Wsl => Arc::new(TmuxClient::new(prefix)),
+        Multiplexer::Screen => Arc::new(ScreenMux::new(prefix)),
+        Multiplexer::Zellij => Arc::new(ZellijMux::new(prefix)),
+        Multiplexer::Wezterm => Arc::new(WeztermMux::new(prefix)),
+    }
+}
+
+/// Bytes for a tmux key name; other strings are typed as-is.
+pub(super) fn key_bytes(key: &str) -> &str {
+    match key {
+        "Enter" | "C-m" => "\r",
+        "Escape" => "\x1b",
+        "Tab" => "\t",
+        "BSpace" => "\x7f",
+        "C-c" => "\x03",
+        "C-d" => "\x04",
+        "C-u" => "\x15",
+        "Up" => "\x1b[A",
+        "Down" => "\x1b[B",
+        "Right" => "\x1b[C",
+        "Left" => "\x1b[D",
+        other => other,
+    }
+}
+
+/// Last `lines` lines of `text` (all of it when `lines <= 0`).
+pub(super) fn tail_lines(text: &str, lines: i32) -> String {
+    let all: Vec<&str> = text.lines().collect();
+    let keep = if lines <= 0 {
+        all.len()
+    } else {
+        lines as usize
+    };
+    all[all.len().saturating_sub(keep)..].join("\n")
+}
+
+/// Output activity for backends without an activity timestamp: a session
+/// is active when its capture last changed.
+#[derive(Default)]
+pub(super) struct ActivityTracker {
+    /// Last capture hash and when it changed, per session
+    seen: Mutex<HashMap<String, (u64, i64)>>,
+}
+
+impl ActivityTracker {
+    /// Record a capture; returns when the session's output last changed.
+    pub(super) fn observe(&self, session: &str, capture: &str) -> Result<i64> {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        capture.hash(&mut hasher);
+        let hash = hasher.finish();
+        let now = chrono::Utc::now().timestamp();
+        let mut seen = self
+            .seen
+            .lock()
+            .map_err(|_| anyhow!("activity lock poisoned"))?;
+        let entry = seen.entry(session.to_string()).or_insert((hash, now));
+        if entry.0 != hash {
+            *entry = (hash, now);
+        }
+        Ok(entry.1)
+    }
+
+    pub(super) fn forget(&self, session: &str) {
+        if let Ok(mut seen) = self.seen.lock() {
+            seen.remove(session);
+        }
+    }
+}
+
+impl Mux for TmuxClient {
+    fn backend(&self) -> &'static str {
+        "tmux"
+    }
+
+    fn prefix(&self) -> &str {
+        TmuxClient::prefix(self)
+    }
+
+    fn list_sessions(&self) -> Result<Vec<Session>> {
+        TmuxClient::list_sessions(self)
+    }
+
+    fn list_all_sessions(&self) -> Result<Vec<Session>> {
+        TmuxClient::list_all_sessions(self)
+    }
+
+    fn has_session(&self, name: &str) -> Result<bool> {
+        TmuxClient::has_session(self, name)
+    }
+
+    fn host(&self) -> Option<&str> {
+        TmuxClient::host(self)
+    }
+
+    fn ensure_session_not_attached(&self, name: &str) -> Result<Session> {
+        TmuxClient::ensure_session_not_attached(self, name)
+    }
+
+    fn session_has_live_pane(&self, name: &str) -> Result<bool> {
+        TmuxClient::session_has_live_pane(self, name)
+    }
+
+    fn new_session(&self, name: &str, command: &str, workdir: Option<&str>) -> Result<()> {
+        TmuxClient::new_session(self, name, command, workdir)
+    }
+
+    fn kill_session(&self, name: &str) -> Result<()> {
+        TmuxClient::kill_session(self, name)
+    }
+
+    fn capture_pane_plain(&self, target: &str, lines: i32) -> Result<String> {
+        TmuxClient::capture_pane_plain(self, target, lines)
+    }
+
+    fn capture_pane(&self, target: &str, lines: i32) -> Result<String> {
+        TmuxClient::capture_pane(self, target, lines)
+    }
+
+    fn get_pane_command(&self, target: &str) -> Result<String> {
+        TmuxClient::get_pane_command(self, target)
+    }
+
+    fn get_pane_activity(&self, target: &str) -> Result<i64> {
+        TmuxClient::get_pane_activity(self, target)
+    }
+
+    fn send_keys(&self, target: &str, keys: &str) -> Result<()> {
+        TmuxClient::send_keys(self, target, keys)
+    }
+
+    fn send_keys_literal(&self, target: &str, text: &str) -> Result<()> {
+        TmuxClient::send_keys_literal(self, target, text)
+    }
+
+    fn pipe_pane(&self, target: &str, command: &str) -> Result<()> {
+        TmuxClient::pipe_pane(self, target, command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_bytes_tail_and_activity() {
+        assert_eq!(key_bytes("Enter"), "\r");
+        assert_eq!(key_bytes("C-c"), "\x03");
+        assert_eq!(key_bytes("y"), "y");
+        assert_eq!(tail_lines("a\nb\nc\n", 2), "b\nc");
+        assert_eq!(tail_lines("a\nb", 0), "a\nb");
+
+        let tracker = ActivityTracker::default();
+        let first = tracker.observe("s", "one").unwrap();
+        assert_eq!(tracker.observe("s", "one").unwrap(), first);
+        tracker.forget("s");
+        assert!(tracker.observe("s", "two").unwrap() >= first);
+    }
+}
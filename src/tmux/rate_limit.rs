@@ -0,0 +1,166 @@
+//! Provider rate-limit detection configured under `[health.rate_limit]`.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::config::RateLimitConfig;
+
+/// Non-empty trailing lines scanned for rate-limit messages, so a limit hit
+/// long ago does not pin the agent once it has moved on.
+const RATE_LIMIT_TAIL_LINES: usize = 15;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimit {
+    /// The matching output line.
+    pub message: String,
+    /// Cooldown stated in the message ("try again in 5 minutes"), if any.
+    pub cooldown: Option<Duration>,
+}
+
+pub struct RateLimitDetector {
+    patterns: Vec<Regex>,
+    backends: HashMap<String, Vec<Regex>>,
+}
+
+impl RateLimitDetector {
+    /// Patterns that are not valid regexes are matched literally.
+    pub fn from_config(config: &RateLimitConfig) -> Self {
+        Self {
+            patterns: compile_all(&config.patterns),
+            backends: config
+                .backends
+                .iter()
+                .map(|(backend, patterns)| (backend.clone(), compile_all(patterns)))
+                .collect(),
+        }
+    }
+
+    /// Scan recent plain-text output. `backend` selects extra per-backend
+    /// patterns.
+    pub fn detect(&self, backend: Option<&str>, output: &str) -> Option<RateLimit> {
+        let extra = backend
+            .and_then(|backend| self.backends.get(backend))
+            .map(Vec::as_slice)
+            .unwrap_or(&[]);
+        output
+            .lines()
+            .rev()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .take(RATE_LIMIT_TAIL_LINES)
+            .find(|line| {
+                self.patterns
+                    .iter()
+                    .chain(extra)
+                    .any(|re| re.is_match(line))
+            })
+            .map(|line| RateLimit {
+                message: line.to_string(),
+                cooldown: parse_cooldown(line),
+            })
+    }
+
+    pub fn has_backend_patterns(&self) -> bool {
+        !self.backends.is_empty()
+    }
+}
+
+pub(super) fn compile_all(patterns: &[String]) -> Vec<Regex> {
+    patterns
+        .iter()
+        .filter(|pattern| !pattern.trim().is_empty())
+        .filter_map(|pattern| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .or_else(|_| {
+                    RegexBuilder::new(&regex::escape(pattern))
+                        .case_insensitive(true)
+                        .build()
+                })
+                .ok()
+        })
+        .collect()
+}
+
+/// Parse "in 30s", "after 5 minutes", "in 2 hours" style cooldowns.
+fn parse_cooldown(line: &str) -> Option<Duration> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)\b(?:in|after)\s+(\d+)\s*(s|secs?|seconds?|m|mins?|minutes?|h|hrs?|hours?)\b",
+        )
+        .unwrap()
+    });
+    let caps = re.captures(line)?;
+    let amount: u64 = caps[1].parse().ok()?;
+    let unit = caps[2].to_ascii_lowercase();
+    let secs = match unit.chars().next()? {
+        's' => amount,
+        'm' => amount * 60,
+        _ => amount * 3600,
+    };
+    Some(Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector() -> RateLimitDetector {
+        let mut config = RateLimitConfig::default();
+        config.backends.insert(
+            "codex".to_string(),
+            vec!["stream disconnected (".to_string()],
+        );
+        RateLimitDetector::from_config(&config)
+    }
+
+    #[test]
+    fn test_detect_default_patterns_and_cooldown() {
+        let hit = detector()
+            .detect(
+                None,
+                "working\n  ⎿ API Error: Rate limit reached. Try again in 5 minutes.\n\n> \n",
+            )
+            .unwrap();
+        assert!(hit.message.starts_with("⎿ API Error"));
+        assert_eq!(hit.cooldown, Some(Duration::from_secs(300)));
+
+        let hit = detector().detect(None, "429 Too Many Requests\n").unwrap();
+        assert_eq!(hit.cooldown, None);
+
+        assert!(detector()
+            .detect(None, "implemented a token-bucket rate limiter\n")
+            .is_none());
+    }
+
+    #[test]
+    fn test_detect_backend_patterns_and_tail_window() {
+        let output = "stream disconnected (retrying)\n";
+        assert!(detector().detect(Some("codex"), output).is_some());
+        assert!(detector().detect(Some("claude"), output).is_none());
+
+        let mut stale = String::from("Rate limit exceeded\n");
+        for i in 0..RATE_LIMIT_TAIL_LINES {
+            stale.push_str(&format!("line {}\n", i));
+        }
+        assert!(detector().detect(None, &stale).is_none());
+    }
+
+    #[test]
+    fn test_parse_cooldown_units() {
+        assert_eq!(
+            parse_cooldown("retry after 30s"),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_cooldown("resets in 2 hours"),
+            Some(Duration::from_secs(7200))
+        );
+        assert_eq!(parse_cooldown("try later"), None);
+    }
+}
@@ -0,0 +1,130 @@
+//! Readiness probe for freshly spawned agents, configured under
+//! `[agent.readiness]`
+//!
+//! Polls the pane for the backend's ready prompt — a configured regex, or
+//! else the built-in `backend_readiness_markers` — so a task is sent as soon
+//! as the input widget is live instead of after a fixed sleep. Backends with
+//! neither fall back to waiting for the pane to go quiet.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::rate_limit::compile_all;
+use super::{backend_readiness_markers, TmuxClient};
+use crate::config::ReadinessConfig;
+
+/// Where a spawned agent is on its way to receiving its task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpawnStatus {
+    WaitingForAgent,
+    Ready,
+    Failed,
+}
+
+pub struct ReadinessProbe {
+    pattern: Option<Regex>,
+    markers: &'static [&'static str],
+    timeout: Duration,
+    poll_interval: Duration,
+    retries: u32,
+}
+
+impl ReadinessProbe {
+    /// Probe for `backend` (canonical name, e.g. `claude`). A pattern that
+    /// is not a valid regex is matched literally.
+    pub fn from_config(backend: Option<&str>, config: &ReadinessConfig) -> Self {
+        let pattern = backend
+            .and_then(|backend| config.patterns.get(backend))
+            .and_then(|pattern| compile_all(std::slice::from_ref(pattern)).pop());
+        Self {
+            pattern,
+            markers: backend.map_or(&[], backend_readiness_markers),
+            timeout: Duration::from_secs(config.timeout_secs),
+            poll_interval: Duration::from_millis(250),
+            retries: config.retries,
+        }
+    }
+
+    /// True when `capture` shows the backend's ready prompt.
+    pub fn is_ready(&self, capture: &str) -> bool {
+        match &self.pattern {
+            Some(pattern) => capture.lines().any(|line| pattern.is_match(line)),
+            None => {
+                let hay = capture.to_ascii_lowercase();
+                self.markers
+                    .iter()
+                    .all(|marker| hay.contains(&marker.to_ascii_lowercase()))
+            }
+        }
+    }
+
+    /// Wait for `session` to be ready. `Ok(false)` when the ready prompt did
+    /// not appear in time; an error when the agent exited or its pane could
+    /// not be read `retries` times in a row.
+    pub fn wait(&self, client: &TmuxClient, session: &str) -> Result<bool> {
+        if self.pattern.is_none() && self.markers.is_empty() {
+            client.wait_for_stable(
+                session,
+                Duration::from_millis(500),
+                self.timeout.min(Duration::from_secs(8)),
+                Duration::from_millis(120),
+                false,
+            )?;
+            return Ok(true);
+        }
+        let deadline = Instant::now() + self.timeout;
+        let mut failed_reads = 0;
+        loop {
+            // Plain capture so multi-word markers match across styled words
+            match client.capture_pane_plain(session, 120) {
+                Ok(capture) if self.is_ready(&capture) => return Ok(true),
+                Ok(_) => failed_reads = 0,
+                Err(e) => {
+                    failed_reads += 1;
+                    if failed_reads > self.retries {
+                        bail!("could not read {}: {}", session, e);
+                    }
+                }
+            }
+            if !client.session_has_live_pane(session).unwrap_or(true) {
+                bail!("{} exited before it was ready", session);
+            }
+            if Instant::now() >= deadline {
+                return Ok(false);
+            }
+            thread::sleep(self.poll_interval);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configured_pattern_replaces_builtin_markers() {
+        let mut config = ReadinessConfig::default();
+        let claude = ReadinessProbe::from_config(Some("claude"), &config);
+        assert!(claude.is_ready("Claude Code v2\n❯ "));
+        assert!(!claude.is_ready("Claude Code v2\nLoading..."));
+
+        config
+            .patterns
+            .insert("claude".to_string(), r"^\s*> $".to_string());
+        let claude = ReadinessProbe::from_config(Some("claude"), &config);
+        assert!(claude.is_ready("Welcome\n  > "));
+        assert!(!claude.is_ready("Claude Code v2\n❯ "));
+
+        config
+            .patterns
+            .insert("codex".to_string(), "[ready".to_string());
+        let codex = ReadinessProbe::from_config(Some("codex"), &config);
+        assert!(codex.is_ready("status [ready]"));
+        assert!(ReadinessProbe::from_config(None, &config).is_ready(""));
+    }
+}
@@ -0,0 +1,178 @@
+//! Retries for tmux commands that change state
+//!
+//! A tmux server that is starting, restarting, or briefly overloaded
+//! refuses connections, and a single-shot `send-keys` then loses the task
+//! it carried. Mutating `TmuxClient` calls go through `with_retry`, which
+//! retries transient failures with exponential backoff and jitter and gives
+//! up at once on permanent ones such as a missing or duplicate session.
+
+use std::fmt;
+use std::io;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+
+/// stderr fragments of failures that a later attempt can get past.
+const TRANSIENT_STDERR: &[&str] = &[
+    "error connecting to",
+    "server exited unexpectedly",
+    "lost server",
+    "server busy",
+    "Resource temporarily unavailable",
+    "Connection refused",
+];
+
+/// A tmux command that exited non-zero, with what it printed to stderr.
+#[derive(Debug)]
+pub struct TmuxError {
+    pub command: String,
+    pub stderr: String,
+}
+
+impl TmuxError {
+    pub fn is_transient(&self) -> bool {
+        TRANSIENT_STDERR
+            .iter()
+            .any(|fragment| self.stderr.contains(fragment))
+    }
+}
+
+impl fmt::Display for TmuxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "tmux {} failed: {}", self.command, self.stderr.trim())
+    }
+}
+
+impl std::error::Error for TmuxError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total tries, including the first
+    pub attempts: u32,
+    /// Delay before the first retry; doubled for each one after
+    pub base: Duration,
+    pub max: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 4,
+            base: Duration::from_millis(50),
+            max: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay after failed attempt `attempt` (1-based): the doubled base,
+    /// capped at `max`, with up to half of it taken off at random so
+    /// callers that failed together do not retry in lockstep.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let full = self
+            .base
+            .saturating_mul(1 << (attempt - 1).min(16))
+            .min(self.max);
+        let jitter = (uuid::Uuid::new_v4().as_u128() % 1000) as u32;
+        full - full / 2 * jitter / 1000
+    }
+}
+
+/// Whether `err` is a tmux failure worth another attempt.
+pub fn is_transient(err: &anyhow::Error) -> bool {
+    if let Some(err) = err.downcast_ref::<TmuxError>() {
+        return err.is_transient();
+    }
+    // tmux could not be spawned at all, e.g. EAGAIN under process pressure
+    err.downcast_ref::<io::Error>().is_some_and(|err| {
+        matches!(
+            err.kind(),
+            io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted
+        )
+    })
+}
+
+/// Run `op` until it succeeds, fails permanently, or uses up `policy`.
+pub fn with_retry<T>(policy: &RetryPolicy, mut op: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt < policy.attempts && is_transient(&err) => {
+                thread::sleep(policy.backoff(attempt));
+                attempt += 1;
+            }
+            Err(err) if attempt > 1 => {
+                return Err(err.context(format!("gave up after {} attempts", attempt)))
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure(stderr: &str) -> anyhow::Error {
+        TmuxError {
+            command: "send-keys".to_string(),
+            stderr: stderr.to_string(),
+        }
+        .into()
+    }
+
+    #[test]
+    fn test_retries_transient_failures_only() {
+        let policy = RetryPolicy {
+            attempts: 3,
+            base: Duration::from_millis(1),
+            max: Duration::from_millis(2),
+        };
+
+        let mut calls = 0;
+        let result = with_retry(&policy, || {
+            calls += 1;
+            if calls < 3 {
+                Err(failure("error connecting to /tmp/tmux-0/default"))
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+
+        let mut calls = 0;
+        let err = with_retry(&policy, || -> Result<()> {
+            calls += 1;
+            Err(failure("can't find session: omar-agent-api\n"))
+        })
+        .unwrap_err();
+        assert_eq!(calls, 1);
+        assert_eq!(
+            err.to_string(),
+            "tmux send-keys failed: can't find session: omar-agent-api"
+        );
+
+        let mut calls = 0;
+        let err = with_retry(&policy, || -> Result<()> {
+            calls += 1;
+            Err(failure("server exited unexpectedly"))
+        })
+        .unwrap_err();
+        assert_eq!(calls, 3);
+        assert_eq!(
+            format!("{:#}", err),
+            "gave up after 3 attempts: tmux send-keys failed: server exited unexpectedly"
+        );
+
+        for attempt in 1..=8 {
+            let delay = policy.backoff(attempt);
+            assert!(
+                delay <= policy.max && delay >= policy.base / 2,
+                "{:?}",
+                delay
+            );
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! `Mux` backend for GNU screen
+//!
+//! Each OMAR session is a screen session of the same name with one window.
+//! Output is captured with `hardcopy -h`, input typed with `stuff`. screen
+//! has no activity timestamp, so activity is inferred from capture changes;
+//! pane pids are reported as 0.
+
+use std::process::Command;
+
+use anyhow::{Context, Result};
+
+use super::mux::{key_bytes, tail_lines, ActivityTracker};
+use super::{Mux, Session};
+
+pub struct ScreenMux {
+    prefix: String,
+    activity: ActivityTracker,
+}
+
+impl ScreenMux {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            activity: ActivityTracker::default(),
+        }
+    }
+
+    fn run(&self, args: &[&str]) -> Result<String> {
+        let output = Command::new("screen")
+            .args(args)
+            .output()
+            .context("Failed to execute screen - is GNU screen installed?")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "screen error: {}",
+                String::from_utf8_lossy(&output.stdout).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into())
+    }
+
+    /// Run a screen command inside session `name`.
+    fn command(&self, name: &str, args: &[&str]) -> Result<()> {
+        let mut full = vec!["-S", name, "-p", "0", "-X"];
+        full.extend(args);
+        self.run(&full)?;
+        Ok(())
+    }
+
+    fn all_sessions(&self) -> Result<Vec<(String, bool)>> {
+        // `screen -ls` exits non-zero whenever it lists anything
+        let output = Command::new("screen")
+            .arg("-ls")
+            .output()
+            .context("Failed to execute screen - is GNU screen installed?")?;
+        Ok(parse_screen_list(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+/// (name, attached) for each line like `\t1234.name\t(date)\t(Detached)`.
+fn parse_screen_list(output: &str) -> Vec<(String, bool)> {
+    output
+        .lines()
+        .filter(|line| line.starts_with('\t'))
+        .filter_map(|line| {
+            let id = line.split('\t').nth(1)?;
+            let (pid, name) = id.split_once('.')?;
+            pid.parse::<u32>().ok()?;
+            Some((name.to_string(), line.contains("(Attached)")))
+        })
+        .collect()
+}
+
+/// Escape text for `stuff`, which expands `\`, `^X`, and `$VAR`.
+fn stuff_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '^' | '$') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+impl Mux for ScreenMux {
+    fn backend(&self) -> &'static str {
+        "screen"
+    }
+
+    fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    fn list_sessions(&self) -> Result<Vec<Session>> {
+        Ok(self
+            .all_sessions()?
+            .into_iter()
+            .filter(|(name, _)| name.starts_with(&self.prefix))
+            .map(|(name, attached)| {
+                let activity = self.get_pane_activity(&name).unwrap_or(0);
+                Session::new(name, activity, attached, 0)
+            })
+            .collect())
+    }
+
+    fn has_session(&self, name: &str) -> Result<bool> {
+        Ok(self
+            .all_sessions()?
+            .iter()
+            .any(|(session, _)| session == name))
+    }
+
+    /// screen ends a session when its only window's process exits.
+    fn session_has_live_pane(&self, name: &str) -> Result<bool> {
+        self.has_session(name)
+    }
+
+    fn new_session(&self, name: &str, command: &str, workdir: Option<&str>) -> Result<()> {
+        let mut cmd = Command::new("screen");
+        cmd.args(["-dmS", name, "sh", "-lc", command]);
+        if let Some(dir) = workdir {
+            cmd.current_dir(dir);
+        }
+        let status = cmd
+            .status()
+            .context("Failed to execute screen - is GNU screen installed?")?;
+        if !status.success() {
+            anyhow::bail!("screen failed to start session '{}'", name);
+        }
+        Ok(())
+    }
+
+    fn kill_session(&self, name: &str) -> Result<()> {
+        self.command(name, &["quit"])?;
+        self.activity.forget(name);
+        Ok(())
+    }
+
+    fn capture_pane_plain(&self, target: &str, lines: i32) -> Result<String> {
+        let tmp = crate::paths::create_private_temp_file("omar-screen", "txt")
+            .context("Failed to create temp file for screen capture")?;
+        let path = tmp
+            .path()
+            .to_str()
+            .context("Temp file path is not valid UTF-8")?;
+        self.command(target, &["hardcopy", "-h", path])?;
+        let output =
+            std::fs::read_to_string(tmp.path()).context("Failed to read screen capture")?;
+        Ok(tail_lines(output.trim_end(), lines))
+    }
+
+    fn get_pane_activity(&self, target: &str) -> Result<i64> {
+        self.activity
+            .observe(target, &self.capture_pane_plain(target, 50)?)
+    }
+
+    fn send_keys(&self, target: &str, keys: &str) -> Result<()> {
+        let bytes = key_bytes(keys);
+        let text = if bytes == keys {
+            stuff_escape(keys)
+        } else {
+            bytes.to_string()
+        };
+        self.command(target, &["stuff", &text])
+    }
+
+    fn send_keys_literal(&self, target: &str, text: &str) -> Result<()> {
+        self.command(target, &["stuff", &stuff_escape(text)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_screen_list_and_escape() {
+        let output = "There are screens on:\n\
+            \t4242.omar-agent-0-api\t(10/17/2026 09:12:01 AM)\t(Detached)\n\
+            \t4243.omar-agent-ea-0\t(Attached)\n\
+            2 Sockets in /run/screen/S-dev.\n";
+        assert_eq!(
+            parse_screen_list(output),
+            vec![
+                ("omar-agent-0-api".to_string(), false),
+                ("omar-agent-ea-0".to_string(), true),
+            ]
+        );
+        assert!(parse_screen_list("No Sockets found in /run/screen/S-dev.\n").is_empty());
+        assert_eq!(stuff_escape("cost $5 ^C \\n"), "cost \\$5 \\^C \\\\n");
+    }
+}
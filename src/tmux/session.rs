@@ -5,6 +5,21 @@ pub struct Session {
     pub activity: i64,
     pub attached: bool,
     pub pane_pid: u32,
+    /// Remote host name for sessions listed over SSH (`None` = local).
+    pub host: Option<String>,
+    /// Output marker of the active pane, when the multiplexer reports one
+    pub stamp: Option<PaneStamp>,
+    /// Creation time in seconds, when the multiplexer reports one
+    pub created: Option<i64>,
+}
+
+/// When a pane last printed and how much scrollback it holds. While both
+/// stay the same, the pane's content has not changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaneStamp {
+    /// `#{window_activity}`, in seconds
+    pub activity: i64,
+    pub history_size: u64,
 }
 
 impl Session {
@@ -14,6 +29,9 @@ impl Session {
             activity,
             attached,
             pane_pid,
+            host: None,
+            stamp: None,
+            created: None,
         }
     }
 }
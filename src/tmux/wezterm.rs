@@ -0,0 +1,164 @@
+//! `Mux` backend for WezTerm's multiplexer (`wezterm cli`), which runs
+//! natively on Windows through ConPTY
+//!
+//! Each OMAR session is a WezTerm workspace holding one pane. WezTerm does
+//! not track output activity, so it is inferred from capture changes; pane
+//! pids are not exposed and are reported as 0.
+
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+
+use super::mux::{key_bytes, ActivityTracker};
+use super::{Mux, Session};
+
+#[derive(Debug, Deserialize)]
+struct PaneEntry {
+    pane_id: u64,
+    workspace: String,
+    #[serde(default)]
+    is_active: bool,
+}
+
+pub struct WeztermMux {
+    prefix: String,
+    activity: ActivityTracker,
+}
+
+impl WeztermMux {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            activity: ActivityTracker::default(),
+        }
+    }
+
+    fn run(&self, args: &[&str]) -> Result<String> {
+        let output = Command::new("wezterm")
+            .arg("cli")
+            .args(args)
+            .output()
+            .context("Failed to execute wezterm - is WezTerm installed?")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "wezterm error: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into())
+    }
+
+    fn panes(&self) -> Result<Vec<PaneEntry>> {
+        parse_pane_list(&self.run(&["list", "--format", "json"])?)
+    }
+
+    fn pane_id(&self, session: &str) -> Result<String> {
+        self.panes()?
+            .into_iter()
+            .find(|pane| pane.workspace == session)
+            .map(|pane| pane.pane_id.to_string())
+            .ok_or_else(|| anyhow!("Session '{}' not found", session))
+    }
+
+    fn send_text(&self, target: &str, text: &str) -> Result<()> {
+        let pane = self.pane_id(target)?;
+        self.run(&["send-text", "--pane-id", &pane, "--no-paste", "--", text])?;
+        Ok(())
+    }
+}
+
+fn parse_pane_list(json: &str) -> Result<Vec<PaneEntry>> {
+    serde_json::from_str(json).context("Failed to parse `wezterm cli list` output")
+}
+
+impl Mux for WeztermMux {
+    fn backend(&self) -> &'static str {
+        "wezterm"
+    }
+
+    fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    fn list_sessions(&self) -> Result<Vec<Session>> {
+        let mut sessions: Vec<Session> = Vec::new();
+        for pane in self.panes()? {
+            if !pane.workspace.starts_with(&self.prefix)
+                || sessions.iter().any(|s| s.name == pane.workspace)
+            {
+                continue;
+            }
+            let activity = self.get_pane_activity(&pane.workspace).unwrap_or(0);
+            sessions.push(Session::new(pane.workspace, activity, pane.is_active, 0));
+        }
+        Ok(sessions)
+    }
+
+    fn has_session(&self, name: &str) -> Result<bool> {
+        Ok(self.panes()?.iter().any(|pane| pane.workspace == name))
+    }
+
+    /// WezTerm closes a pane when its process exits.
+    fn session_has_live_pane(&self, name: &str) -> Result<bool> {
+        self.has_session(name)
+    }
+
+    fn new_session(&self, name: &str, command: &str, workdir: Option<&str>) -> Result<()> {
+        let mut args = vec!["spawn", "--new-window", "--workspace", name];
+        if let Some(dir) = workdir {
+            args.extend(["--cwd", dir]);
+        }
+        args.extend(["--", "sh", "-lc", command]);
+        self.run(&args)?;
+        Ok(())
+    }
+
+    fn kill_session(&self, name: &str) -> Result<()> {
+        let pane = self.pane_id(name)?;
+        self.run(&["kill-pane", "--pane-id", &pane])?;
+        self.activity.forget(name);
+        Ok(())
+    }
+
+    fn capture_pane_plain(&self, target: &str, lines: i32) -> Result<String> {
+        let pane = self.pane_id(target)?;
+        let start = (-lines).to_string();
+        self.run(&["get-text", "--pane-id", &pane, "--start-line", &start])
+    }
+
+    fn get_pane_activity(&self, target: &str) -> Result<i64> {
+        self.activity
+            .observe(target, &self.capture_pane_plain(target, 50)?)
+    }
+
+    fn send_keys(&self, target: &str, keys: &str) -> Result<()> {
+        self.send_text(target, key_bytes(keys))
+    }
+
+    fn send_keys_literal(&self, target: &str, text: &str) -> Result<()> {
+        self.send_text(target, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pane_list() {
+        let json = r#"[
+            {"window_id": 0, "tab_id": 0, "pane_id": 3, "workspace": "omar-agent-api",
+             "title": "claude", "cwd": "file:///tmp", "is_active": true},
+            {"window_id": 1, "tab_id": 1, "pane_id": 7, "workspace": "default"}
+        ]"#;
+        let panes = parse_pane_list(json).unwrap();
+        assert_eq!(panes.len(), 2);
+        assert_eq!(
+            (panes[0].pane_id, panes[0].workspace.as_str()),
+            (3, "omar-agent-api")
+        );
+        assert!(panes[0].is_active && !panes[1].is_active);
+        assert!(parse_pane_list("not json").is_err());
+    }
+}
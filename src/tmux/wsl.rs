@@ -0,0 +1,106 @@
+//! `dashboard.multiplexer = "wsl"`: a dashboard running natively on
+//! Windows drives the tmux server inside a WSL distribution.
+//!
+//! Every local tmux invocation becomes `wsl.exe [-d <distro>] --exec tmux
+//! ...`, so `TmuxClient` and the attach/popup paths work unchanged, and
+//! Windows workdirs are translated to their WSL mounts. Agents run inside
+//! WSL, so the `omar` their MCP config points at must be the Linux build.
+
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// `Some(distro)` once WSL mode is configured; the inner `None` is the
+/// default distribution.
+static WSL: OnceLock<Option<String>> = OnceLock::new();
+
+/// Route local tmux through WSL for the rest of the process. Only the
+/// first call takes effect.
+pub fn configure(distro: Option<&str>) {
+    let distro = distro
+        .map(str::trim)
+        .filter(|distro| !distro.is_empty())
+        .map(str::to_string);
+    let _ = WSL.set(distro);
+}
+
+/// The configured distribution, or `None` when tmux runs natively.
+fn active() -> Option<Option<&'static str>> {
+    WSL.get().map(Option::as_deref)
+}
+
+/// `wsl.exe` set up to run tmux in `distro`; arguments follow.
+pub(super) fn tmux_command(distro: Option<&str>) -> Command {
+    let mut cmd = Command::new("wsl.exe");
+    if let Some(distro) = distro {
+        cmd.args(["-d", distro]);
+    }
+    // `--exec` skips the distro's shell, so arguments need no quoting
+    cmd.args(["--exec", "tmux"]);
+    cmd
+}
+
+/// The command to start local tmux with: through WSL when configured.
+pub(super) fn local_tmux_command() -> Command {
+    match active() {
+        Some(distro) => tmux_command(distro),
+        None => Command::new("tmux"),
+    }
+}
+
+/// `workdir` as tmux should see it: unchanged unless WSL mode is on.
+pub(super) fn workdir(dir: &str) -> String {
+    match active() {
+        Some(_) => to_wsl_path(dir),
+        None => dir.to_string(),
+    }
+}
+
+/// Translate a Windows path to its WSL equivalent: drive paths map to
+/// `/mnt/<drive>` and `\\wsl$\<distro>\...` shares to the distro's own
+/// root. Anything else (already a Linux path) is returned as-is.
+pub fn to_wsl_path(path: &str) -> String {
+    let bytes = path.as_bytes();
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        let rest = path[2..].replace('\\', "/");
+        let rest = rest.trim_start_matches('/');
+        let drive = (bytes[0] as char).to_ascii_lowercase();
+        return if rest.is_empty() {
+            format!("/mnt/{}", drive)
+        } else {
+            format!("/mnt/{}/{}", drive, rest.trim_end_matches('/'))
+        };
+    }
+    let unc = path.replace('\\', "/");
+    for share in ["//wsl$/", "//wsl.localhost/"] {
+        if let Some(rest) = unc.strip_prefix(share) {
+            // Skip the distro name
+            let inner = rest.split_once('/').map_or("", |(_, inner)| inner);
+            return format!("/{}", inner.trim_end_matches('/'));
+        }
+    }
+    path.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wsl_paths_and_command() {
+        assert_eq!(
+            to_wsl_path(r"C:\Users\ana\src\app"),
+            "/mnt/c/Users/ana/src/app"
+        );
+        assert_eq!(to_wsl_path("D:/work/"), "/mnt/d/work");
+        assert_eq!(to_wsl_path(r"e:\"), "/mnt/e");
+        assert_eq!(to_wsl_path(r"\\wsl$\Ubuntu\home\ana\app"), "/home/ana/app");
+        assert_eq!(to_wsl_path(r"\\wsl.localhost\Debian\srv"), "/srv");
+        assert_eq!(to_wsl_path("/home/ana/app"), "/home/ana/app");
+
+        let cmd = tmux_command(Some("Ubuntu-24.04"));
+        assert_eq!(cmd.get_program(), "wsl.exe");
+        let args: Vec<_> = cmd.get_args().collect();
+        assert_eq!(args, ["-d", "Ubuntu-24.04", "--exec", "tmux"]);
+        assert_eq!(tmux_command(None).get_args().count(), 2);
+    }
+}
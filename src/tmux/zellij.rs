@@ -0,0 +1,185 @@
+//! `Mux` backend for zellij
+//!
+//! Each OMAR session is a background zellij session; the agent runs in a
+//! command pane opened with `zellij run`, which keeps focus so
+//! `dump-screen` and `write-chars` target it. Exited sessions are kept by
+//! zellij for resurrection and count as dead. Activity is inferred from
+//! capture changes; pane pids are reported as 0.
+
+use std::process::Command;
+
+use anyhow::{Context, Result};
+
+use super::mux::{key_bytes, tail_lines, ActivityTracker};
+use super::{Mux, Session};
+
+pub struct ZellijMux {
+    prefix: String,
+    activity: ActivityTracker,
+}
+
+struct ZellijSession {
+    name: String,
+    exited: bool,
+}
+
+impl ZellijMux {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            activity: ActivityTracker::default(),
+        }
+    }
+
+    fn zellij(&self, args: &[&str]) -> Command {
+        let mut cmd = Command::new("zellij");
+        cmd.args(args);
+        cmd
+    }
+
+    fn run(&self, cmd: &mut Command) -> Result<String> {
+        let output = cmd
+            .output()
+            .context("Failed to execute zellij - is zellij installed?")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "zellij error: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into())
+    }
+
+    /// Run a `zellij action` inside session `name`.
+    fn action(&self, name: &str, args: &[&str]) -> Result<()> {
+        let mut full = vec!["--session", name, "action"];
+        full.extend(args);
+        self.run(&mut self.zellij(&full))?;
+        Ok(())
+    }
+
+    fn all_sessions(&self) -> Result<Vec<ZellijSession>> {
+        let output = self
+            .zellij(&["list-sessions", "--no-formatting"])
+            .output()
+            .context("Failed to execute zellij - is zellij installed?")?;
+        // No sessions is reported as an error
+        Ok(parse_session_list(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+/// Lines like `name [Created 3m ago]` or `name [Created ...] (EXITED - ...)`.
+fn parse_session_list(output: &str) -> Vec<ZellijSession> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let name = line.split_whitespace().next()?;
+            Some(ZellijSession {
+                name: name.to_string(),
+                exited: line.contains("(EXITED"),
+            })
+        })
+        .collect()
+}
+
+impl Mux for ZellijMux {
+    fn backend(&self) -> &'static str {
+        "zellij"
+    }
+
+    fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    fn list_sessions(&self) -> Result<Vec<Session>> {
+        Ok(self
+            .all_sessions()?
+            .into_iter()
+            .filter(|session| !session.exited && session.name.starts_with(&self.prefix))
+            .map(|session| {
+                let activity = self.get_pane_activity(&session.name).unwrap_or(0);
+                Session::new(session.name, activity, false, 0)
+            })
+            .collect())
+    }
+
+    fn has_session(&self, name: &str) -> Result<bool> {
+        Ok(self
+            .all_sessions()?
+            .iter()
+            .any(|session| session.name == name))
+    }
+
+    fn session_has_live_pane(&self, name: &str) -> Result<bool> {
+        Ok(self
+            .all_sessions()?
+            .iter()
+            .any(|session| session.name == name && !session.exited))
+    }
+
+    fn new_session(&self, name: &str, command: &str, workdir: Option<&str>) -> Result<()> {
+        let mut create = self.zellij(&["attach", "--create-background", name]);
+        if let Some(dir) = workdir {
+            create.current_dir(dir);
+        }
+        self.run(&mut create)?;
+        let mut args = vec!["--session", name, "run", "--close-on-exit"];
+        if let Some(dir) = workdir {
+            args.extend(["--cwd", dir]);
+        }
+        args.extend(["--", "sh", "-lc", command]);
+        self.run(&mut self.zellij(&args))?;
+        Ok(())
+    }
+
+    fn kill_session(&self, name: &str) -> Result<()> {
+        self.run(&mut self.zellij(&["delete-session", "--force", name]))?;
+        self.activity.forget(name);
+        Ok(())
+    }
+
+    fn capture_pane_plain(&self, target: &str, lines: i32) -> Result<String> {
+        let tmp = crate::paths::create_private_temp_file("omar-zellij", "txt")
+            .context("Failed to create temp file for zellij capture")?;
+        let path = tmp
+            .path()
+            .to_str()
+            .context("Temp file path is not valid UTF-8")?;
+        self.action(target, &["dump-screen", "--full", path])?;
+        let output =
+            std::fs::read_to_string(tmp.path()).context("Failed to read zellij capture")?;
+        Ok(tail_lines(output.trim_end(), lines))
+    }
+
+    fn get_pane_activity(&self, target: &str) -> Result<i64> {
+        self.activity
+            .observe(target, &self.capture_pane_plain(target, 50)?)
+    }
+
+    fn send_keys(&self, target: &str, keys: &str) -> Result<()> {
+        let bytes: Vec<String> = key_bytes(keys).bytes().map(|b| b.to_string()).collect();
+        let mut args = vec!["write"];
+        args.extend(bytes.iter().map(String::as_str));
+        self.action(target, &args)
+    }
+
+    fn send_keys_literal(&self, target: &str, text: &str) -> Result<()> {
+        self.action(target, &["write-chars", text])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_session_list() {
+        let output = "omar-agent-0-api [Created 3m 2s ago]\n\
+            omar-agent-0-old [Created 1h ago] (EXITED - attach to resurrect)\n";
+        let sessions = parse_session_list(output);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].name, "omar-agent-0-api");
+        assert!(!sessions[0].exited && sessions[1].exited);
+        assert!(parse_session_list("").is_empty());
+    }
+}
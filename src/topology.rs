@@ -0,0 +1,357 @@
+//! Fleet topology — EA → PMs → workers as a graph, for `omar topo`,
+//! `get_topology`, and `/api/topology`
+//!
+//! Edges come from the recorded spawn parents (manages), consecutive stages
+//! of a pipeline run (depends on), and mailbox traffic (messages). Nodes
+//! carry the health reported by `list_agents`, and the text renderers color
+//! them by it.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+/// Graph id of the EA node; also how agents address their manager.
+pub const EA: &str = crate::naming::EA;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Dot,
+    Mermaid,
+}
+
+impl Format {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim() {
+            "" | "json" => Ok(Format::Json),
+            "dot" => Ok(Format::Dot),
+            "mermaid" => Ok(Format::Mermaid),
+            other => Err(anyhow!(
+                "Unknown topology format '{}'. Supported: json, dot, mermaid",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Ea,
+    Pm,
+    Worker,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Node {
+    pub id: String,
+    pub kind: NodeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    Manages,
+    DependsOn,
+    Messages,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+    /// Messages sent along a `messages` edge
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Topology {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+impl Topology {
+    /// Build the graph from short agent names. `parents` maps child to
+    /// parent; agents without a live parent hang off the EA. `depends` is
+    /// (later, earlier) and `messages` (sender, recipient), one per message.
+    pub fn build(
+        agents: &[(String, Option<String>)],
+        parents: &HashMap<String, String>,
+        depends: &[(String, String)],
+        messages: &[(String, String)],
+    ) -> Self {
+        let live: HashSet<&str> = agents
+            .iter()
+            .map(|(id, _)| id.as_str())
+            .chain([EA])
+            .collect();
+        let parent_of = |id: &str| {
+            parents
+                .get(id)
+                .map(String::as_str)
+                .filter(|parent| live.contains(parent))
+                .unwrap_or(EA)
+        };
+        let managers: HashSet<&str> = agents.iter().map(|(id, _)| parent_of(id)).collect();
+
+        let mut topology = Topology::default();
+        topology.nodes.push(Node {
+            id: EA.to_string(),
+            kind: NodeKind::Ea,
+            health: None,
+        });
+        for (id, health) in agents {
+            topology.nodes.push(Node {
+                id: id.clone(),
+                kind: if managers.contains(id.as_str()) {
+                    NodeKind::Pm
+                } else {
+                    NodeKind::Worker
+                },
+                health: health.clone(),
+            });
+            topology.edges.push(Edge {
+                from: parent_of(id).to_string(),
+                to: id.clone(),
+                kind: EdgeKind::Manages,
+                count: None,
+            });
+        }
+        let mut seen = HashSet::new();
+        for (later, earlier) in depends {
+            if live.contains(later.as_str())
+                && live.contains(earlier.as_str())
+                && seen.insert((later, earlier))
+            {
+                topology.edges.push(Edge {
+                    from: later.clone(),
+                    to: earlier.clone(),
+                    kind: EdgeKind::DependsOn,
+                    count: None,
+                });
+            }
+        }
+        let mut counts: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+        for (from, to) in messages {
+            if live.contains(from.as_str()) && live.contains(to.as_str()) {
+                *counts.entry((from, to)).or_default() += 1;
+            }
+        }
+        for ((from, to), count) in counts {
+            topology.edges.push(Edge {
+                from: from.to_string(),
+                to: to.to_string(),
+                kind: EdgeKind::Messages,
+                count: Some(count),
+            });
+        }
+        topology
+    }
+
+    /// Graphviz source.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph omar {\n  rankdir=TB;\n  node [style=filled];\n");
+        for node in &self.nodes {
+            let shape = match node.kind {
+                NodeKind::Ea => "doubleoctagon",
+                NodeKind::Pm => "box",
+                NodeKind::Worker => "ellipse",
+            };
+            out.push_str(&format!(
+                "  {} [label={}, shape={}, fillcolor=\"{}\"];\n",
+                dot_quote(&node.id),
+                dot_quote(&label(node, "\n")),
+                shape,
+                color(node.health.as_deref())
+            ));
+        }
+        for edge in &self.edges {
+            let style = match edge.kind {
+                EdgeKind::Manages => String::new(),
+                EdgeKind::DependsOn => " [style=dashed, label=\"depends on\"]".to_string(),
+                EdgeKind::Messages => format!(
+                    " [style=bold, color=\"#1e88e5\", label=\"{}\"]",
+                    message_label(edge)
+                ),
+            };
+            out.push_str(&format!(
+                "  {} -> {}{};\n",
+                dot_quote(&edge.from),
+                dot_quote(&edge.to),
+                style
+            ));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Mermaid flowchart source. Node ids are positional since agent names
+    /// may contain characters Mermaid treats as syntax.
+    pub fn to_mermaid(&self) -> String {
+        let ids: HashMap<&str, String> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| (node.id.as_str(), format!("n{}", idx)))
+            .collect();
+        let mut out = String::from("flowchart TD\n");
+        for node in &self.nodes {
+            let text = mermaid_escape(&label(node, "<br/>"));
+            let shape = match node.kind {
+                NodeKind::Ea => format!("{{{{\"{}\"}}}}", text),
+                NodeKind::Pm => format!("[\"{}\"]", text),
+                NodeKind::Worker => format!("(\"{}\")", text),
+            };
+            out.push_str(&format!("  {}{}\n", ids[node.id.as_str()], shape));
+        }
+        for edge in &self.edges {
+            let arrow = match edge.kind {
+                EdgeKind::Manages => "-->".to_string(),
+                EdgeKind::DependsOn => "-.->|depends on|".to_string(),
+                EdgeKind::Messages => format!("==>|{}|", message_label(edge)),
+            };
+            out.push_str(&format!(
+                "  {} {} {}\n",
+                ids[edge.from.as_str()],
+                arrow,
+                ids[edge.to.as_str()]
+            ));
+        }
+        let mut classes: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for node in &self.nodes {
+            if let Some(health) = node.health.as_deref() {
+                classes
+                    .entry(health)
+                    .or_default()
+                    .push(&ids[node.id.as_str()]);
+            }
+        }
+        for (health, members) in classes {
+            let class = mermaid_escape(health).replace(' ', "_");
+            out.push_str(&format!(
+                "  classDef {} fill:{}\n  class {} {}\n",
+                class,
+                color(Some(health)),
+                members.join(","),
+                class
+            ));
+        }
+        out
+    }
+}
+
+fn label(node: &Node, separator: &str) -> String {
+    match &node.health {
+        Some(health) => format!("{}{}{}", node.id, separator, health),
+        None => node.id.clone(),
+    }
+}
+
+fn message_label(edge: &Edge) -> String {
+    match edge.count.unwrap_or(1) {
+        1 => "1 message".to_string(),
+        n => format!("{} messages", n),
+    }
+}
+
+fn color(health: Option<&str>) -> &'static str {
+    match health {
+        Some("running") => "#c8e6c9",
+        Some("idle") => "#fff3c4",
+        Some("blocked" | "rate_limited") => "#ffcdd2",
+        Some(_) => "#e0e0e0",
+        None => "#bbdefb",
+    }
+}
+
+fn dot_quote(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+fn mermaid_escape(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_and_render_topology() {
+        let s = |v: &str| v.to_string();
+        let agents = vec![
+            (s("pm"), Some(s("running"))),
+            (s("api"), Some(s("idle"))),
+            (s("tests"), Some(s("blocked"))),
+            (s("orphan"), None),
+        ];
+        let parents = HashMap::from([
+            (s("api"), s("pm")),
+            (s("tests"), s("pm")),
+            (s("orphan"), s("gone")),
+        ]);
+        let depends = vec![(s("tests"), s("api")), (s("tests"), s("gone"))];
+        let messages = vec![
+            (s("api"), s("tests")),
+            (s("api"), s("tests")),
+            (s("user"), s("api")),
+        ];
+        let topology = Topology::build(&agents, &parents, &depends, &messages);
+
+        let kinds: Vec<(&str, NodeKind)> = topology
+            .nodes
+            .iter()
+            .map(|n| (n.id.as_str(), n.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("ea", NodeKind::Ea),
+                ("pm", NodeKind::Pm),
+                ("api", NodeKind::Worker),
+                ("tests", NodeKind::Worker),
+                ("orphan", NodeKind::Worker),
+            ]
+        );
+        let edges: Vec<(&str, &str, EdgeKind, Option<usize>)> = topology
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str(), e.kind, e.count))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("ea", "pm", EdgeKind::Manages, None),
+                ("pm", "api", EdgeKind::Manages, None),
+                ("pm", "tests", EdgeKind::Manages, None),
+                ("ea", "orphan", EdgeKind::Manages, None),
+                ("tests", "api", EdgeKind::DependsOn, None),
+                ("api", "tests", EdgeKind::Messages, Some(2)),
+            ]
+        );
+
+        let mermaid = topology.to_mermaid();
+        assert!(mermaid.starts_with("flowchart TD\n  n0{{\"ea\"}}\n  n1[\"pm<br/>running\"]\n"));
+        assert!(mermaid.contains("  n3 -.->|depends on| n2\n"));
+        assert!(mermaid.contains("  n2 ==>|2 messages| n3\n"));
+        assert!(mermaid.contains("  classDef blocked fill:#ffcdd2\n  class n3 blocked\n"));
+
+        let dot = topology.to_dot();
+        assert!(dot
+            .contains("  \"api\" [label=\"api\\nidle\", shape=ellipse, fillcolor=\"#fff3c4\"];\n"));
+        assert!(dot.contains("  \"tests\" -> \"api\" [style=dashed, label=\"depends on\"];\n"));
+        assert!(dot.ends_with("}\n"));
+
+        assert_eq!(Format::parse("mermaid").unwrap(), Format::Mermaid);
+        assert!(Format::parse("svg").is_err());
+    }
+}
@@ -0,0 +1,249 @@
+//! Agent transcripts — raw pane output logged via `tmux pipe-pane` and
+//! exported as cleaned, timestamped markdown (`omar export`, the
+//! `get_agent_transcript` MCP tool)
+//!
+//! Each agent session pipes its pane into the hidden `omar pane-log`
+//! subcommand, which appends `<rfc3339>\t<line>` records to
+//! `~/.omar/transcripts/<session>.log`. Logs outlive their sessions, so a
+//! finished worker can still be exported. Workers killed for idling are
+//! also archived as markdown under `~/.omar/transcripts/archive/`. A
+//! spawn's trace ID is logged as a `trace <id>` line, shown in the export
+//! header.
+
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+use crate::sandbox::shell_single_quote;
+use crate::tmux::Mux;
+
+pub fn transcripts_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".omar")
+        .join("transcripts")
+}
+
+pub fn log_path(session: &str) -> PathBuf {
+    transcripts_dir().join(format!("{}.log", session))
+}
+
+/// Start logging a freshly created session. Best effort: a session without
+/// a transcript is still a working session.
+pub fn start(client: &dyn Mux, session: &str) {
+    // Under `cargo test` the current executable is the test harness, which
+    // must not be launched as a pane logger.
+    if cfg!(test) {
+        return;
+    }
+    let Ok(exe) = std::env::current_exe() else {
+        return;
+    };
+    if fs::create_dir_all(transcripts_dir()).is_err() {
+        return;
+    }
+    let command = format!(
+        "exec {} pane-log {}",
+        shell_single_quote(&exe.to_string_lossy()),
+        shell_single_quote(&log_path(session).to_string_lossy())
+    );
+    let _ = client.pipe_pane(session, &command);
+}
+
+/// Follow a renamed session. The pane logger keeps its open file, so it
+/// carries on writing to the moved log.
+pub fn rename(old: &str, new: &str) {
+    let _ = fs::rename(log_path(old), log_path(new));
+}
+
+/// Record the trace ID `session` was spawned under. Call before `start`.
+pub fn mark_trace(session: &str, trace_id: &str) -> Result<()> {
+    fs::create_dir_all(transcripts_dir())?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path(session))?;
+    writeln!(file, "trace {}", trace_id)?;
+    Ok(())
+}
+
+/// `omar pane-log`: timestamp each line read from stdin into `path`.
+pub fn run_pane_log(path: &Path, input: impl BufRead) -> Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    for line in input.split(b'\n') {
+        let line = line?;
+        let text = String::from_utf8_lossy(&line);
+        writeln!(
+            file,
+            "{}\t{}",
+            chrono::Utc::now().to_rfc3339(),
+            text.trim_end_matches('\r')
+        )?;
+        file.flush()?;
+    }
+    Ok(())
+}
+
+/// Render the log of `session` as markdown for `agent` (its display name).
+/// `max_lines` keeps only the most recent transcript lines.
+pub fn export(session: &str, agent: &str, max_lines: Option<usize>) -> Result<String> {
+    let path = log_path(session);
+    let raw = fs::read_to_string(&path).map_err(|_| {
+        anyhow!(
+            "No transcript recorded for '{}' ({})",
+            agent,
+            path.display()
+        )
+    })?;
+    Ok(render_markdown(agent, &raw, max_lines))
+}
+
+/// Write `session`'s transcript as markdown into `dir`, falling back to
+/// `pane` (its scrollback) when no log was recorded. Returns the file.
+pub fn archive_to(dir: &Path, session: &str, agent: &str, pane: &str) -> Result<PathBuf> {
+    let markdown = export(session, agent, None).unwrap_or_else(|_| {
+        format!(
+            "# Transcript: {}\n\nNo log was recorded; final scrollback:\n\n```\n{}\n```\n",
+            agent,
+            crate::mcp::clean_human_output(pane).trim_end()
+        )
+    });
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!(
+        "{}-{}.md",
+        session,
+        chrono::Utc::now().format("%Y%m%dT%H%M%SZ")
+    ));
+    fs::write(&path, markdown)?;
+    Ok(path)
+}
+
+/// `archive_to` the default archive directory.
+pub fn archive(session: &str, agent: &str, pane: &str) -> Result<PathBuf> {
+    archive_to(&transcripts_dir().join("archive"), session, agent, pane)
+}
+
+/// Clean pane output for reading: keep only what follows the last carriage
+/// return (progress redraws), strip ANSI/control sequences and trailing
+/// space.
+fn clean_line(text: &str) -> String {
+    let text = text.rsplit('\r').next().unwrap_or(text);
+    crate::mcp::clean_human_output(text).trim_end().to_string()
+}
+
+fn render_markdown(agent: &str, raw: &str, max_lines: Option<usize>) -> String {
+    let mut lines: Vec<(&str, String)> = Vec::new();
+    let mut trace_id = None;
+    for record in raw.lines() {
+        let Some((timestamp, text)) = record.split_once('\t') else {
+            // A session name can be reused, so the latest trace wins
+            if let Some(id) = record.strip_prefix("trace ") {
+                trace_id = Some(id.trim());
+            }
+            continue;
+        };
+        let text = clean_line(text);
+        let last = lines.last().map(|(_, prev)| prev.as_str());
+        // Drop TUI redraw repeats and runs of blank lines.
+        if last == Some(text.as_str()) || (text.is_empty() && last.is_none_or(str::is_empty)) {
+            continue;
+        }
+        lines.push((timestamp, text));
+    }
+    if let Some(max) = max_lines {
+        let skip = lines.len().saturating_sub(max);
+        lines.drain(..skip);
+    }
+
+    let mut out = format!(
+        "# Transcript: {}\n\n_Exported {}_\n",
+        agent,
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S %Z")
+    );
+    if let Some(id) = trace_id {
+        out.push_str(&format!("\n_Trace: {}_\n", id));
+    }
+    let mut current_minute = String::new();
+    for (timestamp, text) in &lines {
+        let minute = chrono::DateTime::parse_from_rfc3339(timestamp)
+            .map(|t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        if minute != current_minute {
+            if !current_minute.is_empty() {
+                out.push_str("```\n");
+            }
+            out.push_str(&format!("\n## {}\n\n```text\n", minute));
+            current_minute = minute;
+        }
+        out.push_str(text);
+        out.push('\n');
+    }
+    if !current_minute.is_empty() {
+        out.push_str("```\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pane_log_timestamps_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("s.log");
+        run_pane_log(&path, &b"hello\r\nworld\n"[..]).unwrap();
+        let log = fs::read_to_string(&path).unwrap();
+        let records: Vec<(&str, &str)> = log.lines().map(|l| l.split_once('\t').unwrap()).collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].1, "hello");
+        assert!(chrono::DateTime::parse_from_rfc3339(records[1].0).is_ok());
+    }
+
+    #[test]
+    fn test_render_markdown_cleans_and_groups_by_minute() {
+        let at = |minute: u32| {
+            chrono::Utc::now()
+                .date_naive()
+                .and_hms_opt(10, minute, 0)
+                .unwrap()
+                .and_utc()
+                .to_rfc3339()
+        };
+        let raw = format!(
+            "trace 7f3a9c\n{a}\t\x1b[1mBuilding\x1b[0m\n{a}\tBuilding\n{a}\t\n{a}\t\n{a}\t10%\r50%\r100%  \n{b}\tdone\nnot a record\n",
+            a = at(1),
+            b = at(2)
+        );
+        let md = render_markdown("api", &raw, None);
+        assert!(md.starts_with("# Transcript: api\n"));
+        assert!(md.contains("_Trace: 7f3a9c_"));
+        assert_eq!(md.matches("```text").count(), 2);
+        let body: Vec<&str> = md
+            .lines()
+            .filter(|l| !l.starts_with('#') && !l.starts_with("```") && !l.starts_with('_'))
+            .filter(|l| !l.is_empty())
+            .collect();
+        assert_eq!(body, vec!["Building", "100%", "done"]);
+
+        let tail = render_markdown("api", &raw, Some(1));
+        assert!(tail.contains("done") && !tail.contains("Building"));
+    }
+
+    #[test]
+    fn test_archive_falls_back_to_scrollback() {
+        let dir = tempfile::tempdir().unwrap();
+        let session = format!("omar-test-{}", uuid::Uuid::new_v4().simple());
+        let path = archive_to(dir.path(), &session, "api", "\x1b[32mall done\x1b[0m\n").unwrap();
+        assert!(path.starts_with(dir.path()));
+        let md = fs::read_to_string(path).unwrap();
+        assert!(md.starts_with("# Transcript: api\n"));
+        assert!(md.contains("```\nall done\n```"));
+    }
+}
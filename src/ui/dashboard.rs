@@ -7,16 +7,20 @@ use ratatui::{
 };
 use regex::Regex;
 
-use crate::app::{AgentInfo, App, ConfirmAction, SidebarPanel};
-use crate::config;
-use crate::tmux::HealthState;
-
-/// Dashboard theme palette. Two named slots — selected/active vs.
-/// inactive/dim — referenced by every panel so the theme cannot drift
-/// one hunk at a time via incidental edits (as happened between v0.1.2
-/// and current main). Change these, change the whole dashboard.
-const COLOR_ACTIVE: Color = Color::LightMagenta;
-const COLOR_INACTIVE: Color = Color::DarkGray;
+use crate::app::{AgentInfo, App, CommandTreeNode, ConfirmAction, SidebarPanel};
+use crate::config::{self, LayoutPreset};
+use crate::exec::ExecPopup;
+use crate::file_browser::{self, FileBrowser, FileViewer};
+use crate::firehose::Firehose;
+use crate::health_history;
+use crate::memory::ProgressReport;
+use crate::naming;
+use crate::pipeline::{PipelineRun, RunStatus};
+use crate::snapshot::Priority;
+use crate::spawn_form::{SpawnField, SpawnForm};
+use crate::tmux::{HealthState, HealthTransition};
+use crate::ui::i18n::{tr, trf};
+use crate::ui::theme::Theme;
 
 const QUOTES: &[&str] = &[
     // Sun Tzu
@@ -332,6 +336,7 @@ pub const QUOTE_COUNT: usize = QUOTES.len();
 
 /// Render the entire dashboard
 pub fn render(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
     let status_height = 3;
     let outer = Layout::default()
         .direction(Direction::Vertical)
@@ -342,95 +347,176 @@ pub fn render(frame: &mut Frame, app: &App) {
         ])
         .split(frame.area());
 
-    render_status_bar(frame, app, outer[0]);
+    render_status_bar(frame, theme, app, outer[0]);
 
-    // Two-column layout: sidebar + main content (sidebar can be left or right)
-    let columns = if app.config.dashboard.sidebar_right {
-        let cols = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Min(0), Constraint::Length(40)])
-            .split(outer[1]);
-        (cols[1], cols[0]) // (sidebar, main)
+    if let Some(agent) = app.zoomed_agent() {
+        render_zoomed(frame, theme, app, agent, outer[1]);
     } else {
-        let cols = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Length(40), Constraint::Min(0)])
-            .split(outer[1]);
-        (cols[0], cols[1]) // (sidebar, main)
-    };
-    let (sidebar_area, main_area) = columns;
-
-    // Sidebar: projects, (optional) event queue, chain of command
-    if app.config.dashboard.show_event_queue {
-        let sidebar = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Ratio(1, 3),
-                Constraint::Ratio(1, 3),
-                Constraint::Ratio(1, 3),
-            ])
-            .split(sidebar_area);
+        let preset = app.ui.preset;
+        let panels = app.sidebar_panels();
+        let main_area = if panels.is_empty() {
+            outer[1]
+        } else {
+            // Two-column layout: sidebar + main content (sidebar can be left or right)
+            let width = if preset == LayoutPreset::TreeFocus {
+                Constraint::Percentage(45)
+            } else {
+                Constraint::Length(40)
+            };
+            let (sidebar_area, main_area) = if app.config.dashboard.sidebar_right {
+                let cols = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Min(0), width])
+                    .split(outer[1]);
+                (cols[1], cols[0])
+            } else {
+                let cols = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([width, Constraint::Min(0)])
+                    .split(outer[1]);
+                (cols[0], cols[1])
+            };
 
-        render_projects_panel(frame, app, sidebar[0]);
-        render_event_queue(frame, app, sidebar[1]);
-        render_command_tree(frame, app, sidebar[2]);
-    } else {
-        let sidebar = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
-            .split(sidebar_area);
+            // Sidebar: projects, (optional) event queue, chain of command;
+            // tree-focus gives the tree most of the height
+            let weight = |panel: &SidebarPanel| match panel {
+                SidebarPanel::ChainOfCommand if preset == LayoutPreset::TreeFocus => 3,
+                _ => 1,
+            };
+            let total: u32 = panels.iter().map(weight).sum();
+            let sidebar = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(
+                    panels
+                        .iter()
+                        .map(|panel| Constraint::Ratio(weight(panel), total))
+                        .collect::<Vec<_>>(),
+                )
+                .split(sidebar_area);
+            for (panel, area) in panels.iter().zip(sidebar.iter()) {
+                match panel {
+                    SidebarPanel::Projects => render_projects_panel(frame, theme, app, *area),
+                    SidebarPanel::Events => render_event_queue(frame, theme, app, *area),
+                    SidebarPanel::ChainOfCommand => render_command_tree(frame, theme, app, *area),
+                }
+            }
+            main_area
+        };
 
-        render_projects_panel(frame, app, sidebar[0]);
-        render_command_tree(frame, app, sidebar[1]);
+        if let Some(firehose) = app.firehose.as_ref() {
+            render_firehose(frame, theme, app, firehose, main_area);
+        } else if app.ui.show_ea_panel {
+            // Main area: agent grid on top (~2/3), focus parent on bottom
+            // (~1/3), the other way round for ea-focus
+            let grid = if preset == LayoutPreset::EaFocus {
+                33
+            } else {
+                67
+            };
+            let main_col = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(grid), Constraint::Min(8)])
+                .split(main_area);
+            render_agent_grid(frame, theme, app, main_col[0]);
+            render_focus_parent(frame, theme, app, main_col[1]);
+        } else {
+            render_agent_grid(frame, theme, app, main_area);
+        }
     }
 
-    // Main area: agent grid on top (~2/3), focus parent on bottom (~1/3)
-    let main_col = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(67), Constraint::Min(8)])
-        .split(main_area);
-
-    render_agent_grid(frame, app, main_col[0]);
-    render_focus_parent(frame, app, main_col[1]);
+    render_help_bar(frame, theme, app, outer[2]);
 
-    render_help_bar(frame, app, outer[2]);
+    if let Some(summary) = app.celebration_banner() {
+        render_celebration(frame, theme, summary, outer[1]);
+    }
 
     // Render overlays
     if app.show_help {
-        render_help_popup(frame);
+        render_help_popup(frame, theme, app);
     }
 
     if let Some(action) = app.pending_confirm {
-        render_confirm_dialog(frame, app, action);
+        render_confirm_dialog(frame, theme, app, action);
+    }
+
+    if let Some(workers) = app.shutdown_dialog.as_deref() {
+        render_shutdown_dialog(frame, theme, app, workers);
     }
 
     if app.project_input_mode {
-        render_project_input(frame, app);
+        render_project_input(frame, theme, app);
     }
 
     if app.ea_input_mode {
-        render_ea_input(frame, app);
+        render_ea_input(frame, theme, app);
+    }
+
+    if let Some(target) = app.message_target.as_deref() {
+        render_message_input(frame, theme, app, target);
+    }
+
+    if let Some(target) = app.note_target.as_deref() {
+        render_note_input(frame, theme, app, target);
+    }
+
+    if let Some(query) = app.search_input.as_deref() {
+        render_search_input(frame, theme, query);
+    }
+
+    if let Some(filter) = app.filter_input.as_deref() {
+        render_filter_input(frame, theme, filter);
+    }
+
+    if let Some(popup) = app.exec_popup.as_ref() {
+        render_exec_popup(frame, theme, app, popup);
+    }
+
+    if let Some(form) = app.spawn_form.as_ref() {
+        render_spawn_form(frame, theme, form);
+    }
+
+    if let Some(browser) = app.file_browser.as_ref() {
+        render_file_browser(frame, theme, browser);
+        if let Some(viewer) = browser.viewer.as_ref() {
+            render_file_viewer(frame, theme, &browser.agent, viewer);
+        }
+    }
+
+    if let Some(run) = app.approval_review_run() {
+        render_approval_review(frame, theme, run);
+    }
+
+    if let Some((query, matches)) = app.search_results.as_ref() {
+        render_search_results(frame, theme, app, query, matches);
     }
 
     if app.show_events {
-        render_events_popup(frame, app);
+        render_events_popup(frame, theme, app);
+    }
+
+    if let Some(selected) = app.queue_selected {
+        render_queue_popup(frame, theme, app, selected);
+    }
+
+    if let Some(selected) = app.attention_selected {
+        render_attention_popup(frame, theme, app, selected);
     }
 
     if app.show_debug_console {
-        render_debug_console(frame, app);
+        render_debug_console(frame, theme, app);
     }
 
     if app.show_settings {
-        render_settings_popup(frame, app);
+        render_settings_popup(frame, theme, app);
     }
 
     if let Some(panel) = app.sidebar_popup {
-        render_sidebar_popup(frame, app, panel);
+        render_sidebar_popup(frame, theme, app, panel);
     }
 }
 
-fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
-    let (running, idle) = app.health_counts();
+fn render_status_bar(frame: &mut Frame, theme: &Theme, app: &App, area: Rect) {
+    let counts = app.health_counts();
     let total = app.total_agents();
 
     let now_ns = std::time::SystemTime::now()
@@ -444,19 +530,61 @@ fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
         Span::styled(format!("{}", total), Style::default().fg(Color::Reset)),
         Span::raw(" | "),
         Span::styled(
-            format!("{} Running", running),
-            Style::default().fg(Color::Green),
+            format!("{} Running", counts.running),
+            Style::default().fg(theme.running),
         ),
         Span::raw(" "),
-        Span::styled(format!("{} Idle", idle), Style::default().fg(Color::Yellow)),
+        Span::styled(
+            format!("{} Idle", counts.idle),
+            Style::default().fg(theme.idle),
+        ),
     ];
+    if counts.finished > 0 {
+        status_spans.push(Span::raw(" "));
+        status_spans.push(Span::styled(
+            format!("{} Finished", counts.finished),
+            Style::default().fg(theme.finished),
+        ));
+    }
+    if counts.blocked > 0 {
+        status_spans.push(Span::raw(" "));
+        status_spans.push(Span::styled(
+            format!("{} Blocked", counts.blocked),
+            Style::default().fg(theme.blocked),
+        ));
+    }
+    if counts.rate_limited > 0 {
+        status_spans.push(Span::raw(" "));
+        status_spans.push(Span::styled(
+            format!("{} Rate-limited", counts.rate_limited),
+            Style::default().fg(theme.rate_limited),
+        ));
+    }
+
+    if !app.filter.is_empty() {
+        status_spans.push(Span::raw(" | Filter: "));
+        status_spans.push(Span::styled(
+            app.filter.clone(),
+            Style::default().fg(theme.selection),
+        ));
+    }
+
+    for run in &app.pipeline_runs {
+        let color = match run.status {
+            RunStatus::Running => theme.selection,
+            RunStatus::AwaitingApproval => Color::Yellow,
+            _ => continue,
+        };
+        status_spans.push(Span::raw(" | ⛓ "));
+        status_spans.push(Span::styled(run.progress(), Style::default().fg(color)));
+    }
 
     // Events count
     if !app.scheduled_events.is_empty() {
         status_spans.push(Span::raw(" | Events: "));
         status_spans.push(Span::styled(
             format!("{}", app.scheduled_events.len()),
-            Style::default().fg(COLOR_ACTIVE),
+            Style::default().fg(theme.selection),
         ));
     }
 
@@ -470,25 +598,62 @@ fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
         status_spans.push(Span::raw(" | EA Wake: "));
         status_spans.push(Span::styled(
             format_countdown_ns(event.timestamp, now_ns),
-            Style::default().fg(COLOR_ACTIVE),
+            Style::default().fg(theme.selection),
+        ));
+    }
+
+    // Fleet drain progress
+    if let Some(drain) = app.drain.as_ref() {
+        status_spans.push(Span::raw(" | Draining: "));
+        let mut text = format!("{} left", app.drain_waiting);
+        if let Some(deadline) = drain.deadline.and_then(|d| d.timestamp_nanos_opt()) {
+            text.push_str(&format!(
+                ", stop in {}",
+                format_countdown_ns(deadline as u64, now_ns)
+            ));
+        }
+        status_spans.push(Span::styled(text, Style::default().fg(theme.blocked)));
+    }
+
+    // Spawns waiting for a worker pool slot
+    if app.spawn_queue_len > 0 {
+        status_spans.push(Span::raw(" | Queued: "));
+        status_spans.push(Span::styled(
+            format!("{}", app.spawn_queue_len),
+            Style::default().fg(theme.idle),
+        ));
+    }
+
+    // Next scheduled spawn
+    if let Some((name, next_ns)) = app.schedule_timers.first() {
+        status_spans.push(Span::raw(format!(" | ⏰ {} ", name)));
+        status_spans.push(Span::styled(
+            format_countdown_ns(*next_ns, now_ns),
+            Style::default().fg(theme.selection),
         ));
     }
 
     let block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Thick)
-        .border_style(Style::default().fg(COLOR_INACTIVE))
+        .border_style(Style::default().fg(theme.dim))
         .padding(Padding::horizontal(1));
 
     // Render block first, then split inner area
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
-    render_status_row(frame, app, &status_spans, inner);
+    render_status_row(frame, theme, app, &status_spans, inner);
 }
 
 /// Render the status info on the left and a scrolling quote on the right.
-fn render_status_row(frame: &mut Frame, app: &App, status_spans: &[Span], area: Rect) {
+fn render_status_row(
+    frame: &mut Frame,
+    theme: &Theme,
+    app: &App,
+    status_spans: &[Span],
+    area: Rect,
+) {
     let left_width: u16 = status_spans.iter().map(|s| s.width() as u16).sum();
     let left_col_width = left_width.saturating_add(1).min(area.width);
 
@@ -526,18 +691,18 @@ fn render_status_row(frame: &mut Frame, app: &App, status_spans: &[Span], area:
 
         let quote_paragraph = Paragraph::new(Line::from(Span::styled(
             visible,
-            Style::default().fg(COLOR_INACTIVE),
+            Style::default().fg(theme.dim),
         )));
         frame.render_widget(quote_paragraph, h_chunks[1]);
     }
 }
 
-fn render_projects_panel(frame: &mut Frame, app: &App, area: Rect) {
+fn render_projects_panel(frame: &mut Frame, theme: &Theme, app: &App, area: Rect) {
     let panel_active = app.sidebar_focused && app.sidebar_panel == SidebarPanel::Projects;
     let border_color = if panel_active {
-        COLOR_ACTIVE
+        theme.selection
     } else {
-        COLOR_INACTIVE
+        theme.dim
     };
     let block = Block::default()
         .title(" Projects ")
@@ -549,7 +714,7 @@ fn render_projects_panel(frame: &mut Frame, app: &App, area: Rect) {
     if app.projects.is_empty() {
         let paragraph = Paragraph::new(Span::styled(
             "No active projects. Spawn a project by chatting with the executive assistant.",
-            Style::default().fg(COLOR_INACTIVE),
+            Style::default().fg(theme.dim),
         ))
         .block(block)
         .wrap(Wrap { trim: true });
@@ -560,11 +725,18 @@ fn render_projects_panel(frame: &mut Frame, app: &App, area: Rect) {
     let lines: Vec<Line> = app
         .projects
         .iter()
-        .map(|p| {
-            Line::from(Span::styled(
+        .flat_map(|p| {
+            let name = Line::from(Span::styled(
                 format!("{}. {}", p.id, p.name),
                 Style::default().fg(Color::Reset),
-            ))
+            ));
+            let progress = p.progress().map(|(done, total)| {
+                Line::from(Span::styled(
+                    format!("   {}", progress_bar(done, total, 10)),
+                    Style::default().fg(theme.dim),
+                ))
+            });
+            std::iter::once(name).chain(progress)
         })
         .collect();
 
@@ -573,18 +745,36 @@ fn render_projects_panel(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(paragraph, area);
 }
 
-fn render_agent_grid(frame: &mut Frame, app: &App, area: Rect) {
+/// `▰▰▰▱▱ 3/5 60%`, `width` cells wide.
+fn progress_bar(done: usize, total: usize, width: usize) -> String {
+    let filled = (done * width).checked_div(total).unwrap_or(0);
+    format!(
+        "{}{} {}/{} {}%",
+        "▰".repeat(filled),
+        "▱".repeat(width - filled),
+        done,
+        total,
+        (done * 100).checked_div(total).unwrap_or(0)
+    )
+}
+
+fn render_agent_grid(frame: &mut Frame, theme: &Theme, app: &App, area: Rect) {
     let children = app.focus_children();
 
     if children.is_empty() {
-        let empty_msg = Paragraph::new("Chat with the executive assistant to spawn agents.")
-            .style(Style::default().fg(COLOR_INACTIVE))
+        let hint = if app.filter.is_empty() {
+            "Chat with the executive assistant to spawn agents."
+        } else {
+            "No agents match the filter. Press / to change it."
+        };
+        let empty_msg = Paragraph::new(hint)
+            .style(Style::default().fg(theme.dim))
             .block(
                 Block::default()
                     .borders(Borders::ALL)
                     .border_type(BorderType::Thick)
                     .title(" Agents ")
-                    .border_style(Style::default().fg(COLOR_INACTIVE))
+                    .border_style(Style::default().fg(theme.dim))
                     .padding(Padding::horizontal(1)),
             );
         frame.render_widget(empty_msg, area);
@@ -624,12 +814,12 @@ fn render_agent_grid(frame: &mut Frame, app: &App, area: Rect) {
 
         if col < col_chunks.len() {
             let is_selected = !app.sidebar_focused && !app.manager_selected && i == app.selected;
-            render_summary_card(frame, app, child, col_chunks[col], is_selected);
+            render_summary_card(frame, theme, app, child, col_chunks[col], is_selected);
         }
     }
 }
 
-fn render_focus_parent(frame: &mut Frame, app: &App, area: Rect) {
+fn render_focus_parent(frame: &mut Frame, theme: &Theme, app: &App, area: Rect) {
     let parent_info = app.focus_parent_info();
 
     if let Some(info) = parent_info {
@@ -652,9 +842,7 @@ fn render_focus_parent(frame: &mut Frame, app: &App, area: Rect) {
                 .unwrap_or("EA");
             format!("Executive Assistant ({})", ea_name)
         } else {
-            let short = parent_name
-                .strip_prefix(app.client().prefix())
-                .unwrap_or(parent_name);
+            let short = naming::short_name(app.client().prefix(), parent_name);
             let mut short = short.to_string();
             if parent_info.map(|p| p.is_unresolved).unwrap_or(false) {
                 short.push(' ');
@@ -664,31 +852,28 @@ fn render_focus_parent(frame: &mut Frame, app: &App, area: Rect) {
         };
 
         // Health status dot
-        let (health_color, status_icon) = match info.health {
-            HealthState::Running => (Color::Green, "●"),
-            HealthState::Idle => (Color::Yellow, "○"),
-        };
+        let (health_color, status_icon) = theme.health(info.health);
 
         let (border_color, title_line) = if is_selected {
             (
-                COLOR_ACTIVE,
+                theme.selection,
                 Line::from(vec![
-                    Span::styled(" [", Style::default().fg(COLOR_ACTIVE)),
-                    Span::styled(status_icon, Style::default().fg(COLOR_ACTIVE)),
-                    Span::styled("] ", Style::default().fg(COLOR_ACTIVE)),
-                    Span::styled(&display_title, Style::default().fg(COLOR_ACTIVE)),
-                    Span::styled(" - Enter to open ", Style::default().fg(COLOR_ACTIVE)),
+                    Span::styled(" [", Style::default().fg(theme.selection)),
+                    Span::styled(status_icon, Style::default().fg(theme.selection)),
+                    Span::styled("] ", Style::default().fg(theme.selection)),
+                    Span::styled(&display_title, Style::default().fg(theme.selection)),
+                    Span::styled(" - Enter to open ", Style::default().fg(theme.selection)),
                 ]),
             )
         } else {
             (
-                COLOR_INACTIVE,
+                theme.ea_panel,
                 Line::from(vec![
-                    Span::styled(" ", Style::default().fg(COLOR_INACTIVE)),
+                    Span::styled(" ", Style::default().fg(theme.dim)),
                     Span::styled(status_icon, Style::default().fg(health_color)),
-                    Span::styled(" ", Style::default().fg(COLOR_INACTIVE)),
+                    Span::styled(" ", Style::default().fg(theme.dim)),
                     Span::styled(&display_title, Style::default().fg(health_color)),
-                    Span::styled(" ", Style::default().fg(COLOR_INACTIVE)),
+                    Span::styled(" ", Style::default().fg(theme.dim)),
                 ]),
             )
         };
@@ -741,10 +926,10 @@ fn render_focus_parent(frame: &mut Frame, app: &App, area: Rect) {
 
             let indicator = if let Some(event) = next_pm_wake {
                 Line::from(vec![
-                    Span::styled("PM Wake: ", Style::default().fg(Color::Cyan)),
+                    Span::styled("PM Wake: ", Style::default().fg(theme.border)),
                     Span::styled(
                         format_countdown_ns(event.timestamp, now_ns),
-                        Style::default().fg(COLOR_ACTIVE),
+                        Style::default().fg(theme.selection),
                     ),
                     Span::raw(" | "),
                     Span::styled(
@@ -752,11 +937,11 @@ fn render_focus_parent(frame: &mut Frame, app: &App, area: Rect) {
                         Style::default().fg(Color::Green),
                     ),
                     Span::raw(" | "),
-                    Span::styled("ETA unknown", Style::default().fg(COLOR_INACTIVE)),
+                    Span::styled("ETA unknown", Style::default().fg(theme.dim)),
                 ])
             } else if !workers.is_empty() {
                 Line::from(vec![
-                    Span::styled("PM Wake: ", Style::default().fg(Color::Cyan)),
+                    Span::styled("PM Wake: ", Style::default().fg(theme.border)),
                     Span::styled("not scheduled", Style::default().fg(Color::Yellow)),
                     Span::raw(" | "),
                     Span::styled(
@@ -764,12 +949,12 @@ fn render_focus_parent(frame: &mut Frame, app: &App, area: Rect) {
                         Style::default().fg(Color::Green),
                     ),
                     Span::raw(" | "),
-                    Span::styled("ETA unknown", Style::default().fg(COLOR_INACTIVE)),
+                    Span::styled("ETA unknown", Style::default().fg(theme.dim)),
                 ])
             } else {
                 Line::from(vec![
-                    Span::styled("PM status: ", Style::default().fg(Color::Cyan)),
-                    Span::styled("no workers", Style::default().fg(COLOR_INACTIVE)),
+                    Span::styled("PM status: ", Style::default().fg(theme.border)),
+                    Span::styled("no workers", Style::default().fg(theme.dim)),
                 ])
             };
 
@@ -793,25 +978,98 @@ fn render_focus_parent(frame: &mut Frame, app: &App, area: Rect) {
             .title(" Executive Assistant ")
             .borders(Borders::ALL)
             .border_type(BorderType::Thick)
-            .border_style(Style::default().fg(COLOR_INACTIVE))
+            .border_style(Style::default().fg(theme.dim))
             .padding(Padding::horizontal(1));
 
         let message = "Starting Executive Assistant...".to_string();
 
         let paragraph = Paragraph::new(message)
-            .style(Style::default().fg(COLOR_INACTIVE))
+            .style(Style::default().fg(theme.dim))
             .block(block);
 
         frame.render_widget(paragraph, area);
     }
 }
 
-fn render_command_tree(frame: &mut Frame, app: &App, area: Rect) {
+/// `[PM]`-style badge of a chain-of-command node, if its role has one.
+fn role_badge_spans(theme: &Theme, node: &CommandTreeNode) -> Option<Span<'static>> {
+    node.role
+        .badge()
+        .map(|badge| Span::styled(format!("{} ", badge), Style::default().fg(theme.dim)))
+}
+
+/// Second icon for a parent's team health: "↳" plus its worst child state.
+fn team_health_spans(theme: &Theme, team: Option<HealthState>) -> Vec<Span<'static>> {
+    let Some(team) = team else {
+        return Vec::new();
+    };
+    let (color, icon) = theme.health(team);
+    vec![
+        Span::styled(" ↳", Style::default().fg(theme.dim)),
+        Span::styled(icon, Style::default().fg(color)),
+    ]
+}
+
+/// Percent and blocker count after a tree node.
+fn progress_tree_spans<'a>(theme: &Theme, report: &ProgressReport) -> Vec<Span<'a>> {
+    let mut spans = Vec::new();
+    if let Some(percent) = report.percent {
+        spans.push(Span::styled(
+            format!(" {}%", percent),
+            Style::default().fg(theme.dim),
+        ));
+    }
+    if !report.blockers.is_empty() {
+        spans.push(Span::styled(" ⛔", Style::default().fg(theme.blocked)));
+    }
+    spans
+}
+
+/// A card's progress bar and step, then one line per blocker.
+fn progress_lines<'a>(theme: &Theme, report: &ProgressReport, width: usize) -> Vec<Line<'a>> {
+    let mut spans = Vec::new();
+    if let Some(percent) = report.percent {
+        const BAR: usize = 10;
+        let filled = (percent as usize * BAR).div_ceil(100).min(BAR);
+        spans.push(Span::styled(
+            "█".repeat(filled),
+            Style::default().fg(theme.running),
+        ));
+        spans.push(Span::styled(
+            "░".repeat(BAR - filled),
+            Style::default().fg(theme.dim),
+        ));
+        spans.push(Span::styled(
+            format!(" {:>3}% ", percent),
+            Style::default().fg(Color::Reset),
+        ));
+    }
+    if let Some(step) = &report.step {
+        let used: usize = spans.iter().map(|span| span.content.chars().count()).sum();
+        spans.push(Span::styled(
+            truncate_str(step, width.saturating_sub(used)),
+            Style::default().fg(theme.dim),
+        ));
+    }
+    let mut lines = Vec::new();
+    if !spans.is_empty() {
+        lines.push(Line::from(spans));
+    }
+    for blocker in &report.blockers {
+        lines.push(Line::from(Span::styled(
+            truncate_str(&format!("⛔ {}", blocker), width),
+            Style::default().fg(theme.blocked),
+        )));
+    }
+    lines
+}
+
+fn render_command_tree(frame: &mut Frame, theme: &Theme, app: &App, area: Rect) {
     let panel_active = app.sidebar_focused && app.sidebar_panel == SidebarPanel::ChainOfCommand;
     let border_color = if panel_active {
-        COLOR_ACTIVE
+        theme.selection
     } else {
-        COLOR_INACTIVE
+        theme.dim
     };
     let block = Block::default()
         .title(" Chain of Command ")
@@ -823,7 +1081,7 @@ fn render_command_tree(frame: &mut Frame, app: &App, area: Rect) {
     if app.command_tree.is_empty() {
         let paragraph = Paragraph::new(Span::styled(
             "No agents yet.",
-            Style::default().fg(COLOR_INACTIVE),
+            Style::default().fg(theme.dim),
         ))
         .block(block);
         frame.render_widget(paragraph, area);
@@ -833,10 +1091,7 @@ fn render_command_tree(frame: &mut Frame, app: &App, area: Rect) {
     let mut lines: Vec<Line> = Vec::new();
 
     for node in &app.command_tree {
-        let (health_color, icon) = match node.health {
-            HealthState::Running => (Color::Green, "●"),
-            HealthState::Idle => (Color::Yellow, "○"),
-        };
+        let (health_color, icon) = theme.health(node.health);
 
         // Check if this node is the current focus parent
         let is_focus = node.session_name == app.focus_parent;
@@ -847,7 +1102,7 @@ fn render_command_tree(frame: &mut Frame, app: &App, area: Rect) {
             // Root (EA): no connector, just name + icon
             let name_style = if is_focus {
                 Style::default()
-                    .fg(COLOR_ACTIVE)
+                    .fg(theme.selection)
                     .add_modifier(Modifier::BOLD)
             } else {
                 Style::default()
@@ -855,10 +1110,11 @@ fn render_command_tree(frame: &mut Frame, app: &App, area: Rect) {
                     .add_modifier(Modifier::BOLD)
             };
             if is_focus {
-                spans.push(Span::styled("►", Style::default().fg(COLOR_ACTIVE)));
+                spans.push(Span::styled("►", Style::default().fg(theme.selection)));
             }
             spans.push(Span::styled(format!(" {} ", node.name), name_style));
             spans.push(Span::styled(icon, Style::default().fg(health_color)));
+            spans.extend(team_health_spans(theme, node.team_health));
         } else {
             // Build prefix from ancestor continuation lines
             let mut prefix = String::from(" ");
@@ -880,18 +1136,18 @@ fn render_command_tree(frame: &mut Frame, app: &App, area: Rect) {
                 prefix.push_str(" ├── ");
             }
 
-            spans.push(Span::styled(prefix, Style::default().fg(COLOR_INACTIVE)));
+            spans.push(Span::styled(prefix, Style::default().fg(theme.dim)));
 
             let name_style = if is_focus {
                 Style::default()
-                    .fg(COLOR_ACTIVE)
+                    .fg(theme.selection)
                     .add_modifier(Modifier::BOLD)
             } else {
                 Style::default().fg(Color::Reset)
             };
 
             if is_focus {
-                spans.push(Span::styled("►", Style::default().fg(COLOR_ACTIVE)));
+                spans.push(Span::styled("►", Style::default().fg(theme.selection)));
             }
             let mut node_name = node.name.clone();
             if node.is_unresolved {
@@ -899,7 +1155,12 @@ fn render_command_tree(frame: &mut Frame, app: &App, area: Rect) {
                 node_name.push_str("[unresolved]");
             }
             spans.push(Span::styled(format!("{} ", node_name), name_style));
+            spans.extend(role_badge_spans(theme, node));
             spans.push(Span::styled(icon, Style::default().fg(health_color)));
+            spans.extend(team_health_spans(theme, node.team_health));
+            if let Some(report) = app.agent_progress(&node.session_name) {
+                spans.extend(progress_tree_spans(theme, report));
+            }
         }
 
         lines.push(Line::from(spans));
@@ -909,12 +1170,12 @@ fn render_command_tree(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(paragraph, area);
 }
 
-fn render_event_queue(frame: &mut Frame, app: &App, area: Rect) {
+fn render_event_queue(frame: &mut Frame, theme: &Theme, app: &App, area: Rect) {
     let panel_active = app.sidebar_focused && app.sidebar_panel == SidebarPanel::Events;
     let border_color = if panel_active {
-        COLOR_ACTIVE
+        theme.selection
     } else {
-        COLOR_INACTIVE
+        theme.dim
     };
     let block = Block::default()
         .title(" Event Queue ")
@@ -943,7 +1204,7 @@ fn render_event_queue(frame: &mut Frame, app: &App, area: Rect) {
                     format!("{:<11}", receiver),
                     Style::default().fg(Color::Yellow),
                 ),
-                Span::styled(countdown, Style::default().fg(COLOR_ACTIVE)),
+                Span::styled(countdown, Style::default().fg(theme.selection)),
             ]));
         }
 
@@ -951,7 +1212,7 @@ fn render_event_queue(frame: &mut Frame, app: &App, area: Rect) {
         if remaining > 0 && lines.len() < available {
             lines.push(Line::from(Span::styled(
                 format!("+{} more", remaining),
-                Style::default().fg(COLOR_INACTIVE),
+                Style::default().fg(theme.dim),
             )));
         }
     }
@@ -970,21 +1231,15 @@ fn strip_ansi(s: &str) -> String {
 
 fn render_summary_card(
     frame: &mut Frame,
+    theme: &Theme,
     app: &App,
     agent: &AgentInfo,
     area: Rect,
     selected: bool,
 ) {
-    let (health_color, status_icon) = match agent.health {
-        HealthState::Running => (Color::Green, "●"),
-        HealthState::Idle => (Color::Yellow, "○"),
-    };
+    let (health_color, status_icon) = theme.health(agent.health);
 
-    let border_color = if selected {
-        COLOR_ACTIVE
-    } else {
-        COLOR_INACTIVE
-    };
+    let border_color = if selected { theme.selection } else { theme.dim };
 
     let border_style = Style::default().fg(border_color).add_modifier(if selected {
         Modifier::BOLD
@@ -993,35 +1248,42 @@ fn render_summary_card(
     });
 
     // Display name: strip session prefix
-    let short_name = agent
-        .session
-        .name
-        .strip_prefix(app.client().prefix())
-        .unwrap_or(&agent.session.name);
+    let short_name = naming::short_name(app.client().prefix(), &agent.session.name);
     let mut title_name = short_name.to_string();
+    if let Some(host) = &agent.session.host {
+        title_name.push_str(&format!(" @{}", host));
+    }
     if agent.is_unresolved {
         title_name.push(' ');
         title_name.push_str("[unresolved]");
     }
+    let pane_view = app.pane_view(&agent.session.name);
+    if let Some((_, position, count)) = pane_view {
+        title_name.push_str(&format!(" pane {}/{}", position, count));
+    }
 
-    // Title with status indicator
-    let title_line = if selected {
-        Line::from(vec![
-            Span::styled(" [", Style::default().fg(COLOR_ACTIVE)),
-            Span::styled(status_icon, Style::default().fg(COLOR_ACTIVE)),
-            Span::styled("] ", Style::default().fg(COLOR_ACTIVE)),
-            Span::styled(&title_name, Style::default().fg(COLOR_ACTIVE)),
-            Span::styled(" ", Style::default().fg(COLOR_ACTIVE)),
-        ])
+    // Title with status indicator, then team health for parents
+    let mut title_spans = if selected {
+        vec![
+            Span::styled(" [", Style::default().fg(theme.selection)),
+            Span::styled(status_icon, Style::default().fg(theme.selection)),
+            Span::styled("] ", Style::default().fg(theme.selection)),
+            Span::styled(title_name.clone(), Style::default().fg(theme.selection)),
+        ]
     } else {
-        Line::from(vec![
+        vec![
             Span::styled(" ", Style::default().fg(border_color)),
             Span::styled(status_icon, Style::default().fg(health_color)),
             Span::styled(" ", Style::default().fg(border_color)),
-            Span::styled(&title_name, Style::default().fg(health_color)),
-            Span::styled(" ", Style::default().fg(border_color)),
-        ])
+            Span::styled(title_name.clone(), Style::default().fg(health_color)),
+        ]
     };
+    title_spans.extend(team_health_spans(
+        theme,
+        app.team_health(&agent.session.name),
+    ));
+    title_spans.push(Span::styled(" ", Style::default().fg(border_color)));
+    let title_line = Line::from(title_spans);
 
     let block = Block::default()
         .title(title_line)
@@ -1039,18 +1301,86 @@ fn render_summary_card(
     let child_count = app.child_count(&agent.session.name);
     if child_count > 0 {
         lines.push(Line::from(vec![
-            Span::styled("▶ ", Style::default().fg(Color::Cyan)),
+            Span::styled("▶ ", Style::default().fg(theme.border)),
             Span::styled(
                 format!("{} workers", child_count),
                 Style::default().fg(Color::Reset),
             ),
             Span::styled(
                 " (Tab to drill in, Shift-Tab to back out)",
-                Style::default().fg(COLOR_INACTIVE),
+                Style::default().fg(theme.dim),
             ),
         ]));
     }
 
+    let tags = app.agent_tags(&agent.session.name);
+    let badge = match app.agent_priority(&agent.session.name) {
+        Priority::Normal => None,
+        Priority::Hot => Some(Span::styled("▲ hot", Style::default().fg(theme.running))),
+        Priority::Cold => Some(Span::styled("▼ cold", Style::default().fg(theme.dim))),
+    };
+    if !tags.is_empty() || badge.is_some() {
+        let tags: Vec<String> = tags.iter().map(|tag| format!("#{}", tag)).collect();
+        let mut spans: Vec<Span> = badge.into_iter().collect();
+        if !tags.is_empty() {
+            if !spans.is_empty() {
+                spans.push(Span::raw(" "));
+            }
+            spans.push(Span::styled(tags.join(" "), Style::default().fg(theme.dim)));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    if let Some(note) = app.agent_note(&agent.session.name) {
+        for text in note.lines() {
+            lines.push(Line::from(vec![
+                Span::styled("✎ ", Style::default().fg(theme.idle)),
+                Span::styled(
+                    truncate_str(text, content_width.saturating_sub(2)),
+                    Style::default().fg(Color::Reset),
+                ),
+            ]));
+        }
+    }
+
+    if let Some(report) = app.agent_progress(&agent.session.name) {
+        lines.extend(progress_lines(theme, report, content_width));
+    }
+
+    // Health timeline, once the agent has changed state
+    let history = app.health_history(&agent.session.name);
+    if history.len() > 1 {
+        lines.push(health_sparkline(theme, history, content_width));
+    }
+
+    if let Some((sender, message)) = app.last_instruction(&agent.session.name) {
+        let label = format!("↳ {}: ", sender);
+        let width = content_width.saturating_sub(label.chars().count());
+        lines.push(Line::from(vec![
+            Span::styled(label, Style::default().fg(theme.border)),
+            Span::styled(truncate_str(message, width), Style::default().fg(theme.dim)),
+        ]));
+    }
+
+    // An auxiliary pane in view replaces the task with its latest output
+    if let Some((pane, _, _)) = pane_view {
+        let inner_height = area.height.saturating_sub(2) as usize;
+        let budget = inner_height.saturating_sub(lines.len());
+        let output = app
+            .client()
+            .capture_pane_plain(pane, budget as i32)
+            .unwrap_or_else(|e| format!("(pane unavailable: {})", e));
+        let tail: Vec<&str> = output.lines().collect();
+        for text in &tail[tail.len().saturating_sub(budget)..] {
+            lines.push(Line::from(Span::styled(
+                truncate_str(text, content_width),
+                Style::default().fg(Color::Reset),
+            )));
+        }
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+        return;
+    }
+
     // Task (multi-line word wrap to fill available card space)
     let task = app
         .worker_tasks()
@@ -1153,48 +1483,70 @@ fn render_summary_card(
     frame.render_widget(paragraph, area);
 }
 
-fn render_help_bar(frame: &mut Frame, app: &App, area: Rect) {
+/// One colored cell per slice of the last ten minutes, taller for states
+/// needing more attention, plus the number of changes in that window.
+fn health_sparkline(theme: &Theme, history: &[HealthTransition], width: usize) -> Line<'static> {
+    let now = chrono::Utc::now().timestamp();
+    let window = health_history::TIMELINE_WINDOW_SECS;
+    let changes = health_history::changes_since(history, now - window);
+    let label = format!(" {} changes/10m", changes);
+    let cells = width.saturating_sub(label.len()).min(30);
+    let mut spans: Vec<Span> = health_history::timeline(history, now, window, cells)
+        .into_iter()
+        .map(|state| match state {
+            Some(state) => {
+                let glyph = match state.attention_rank() {
+                    0 => "▁",
+                    1 => "▃",
+                    2 => "▅",
+                    3 => "▆",
+                    _ => "█",
+                };
+                Span::styled(glyph, Style::default().fg(theme.health(state).0))
+            }
+            None => Span::styled(" ", Style::default()),
+        })
+        .collect();
+    spans.push(Span::styled(label, Style::default().fg(theme.dim)));
+    Line::from(spans)
+}
+
+fn render_help_bar(frame: &mut Frame, theme: &Theme, app: &App, area: Rect) {
     let at_root = app
         .manager
         .as_ref()
         .map(|m| app.focus_parent == m.session.name)
         .unwrap_or(true);
 
+    let lang = app.config.dashboard.language;
+    let key = |k: &'static str| Span::styled(k, Style::default().add_modifier(Modifier::BOLD));
+    let label = |text: &'static str, sep: &str| Span::raw(format!(":{}{}", tr(lang, text), sep));
     let mut help_text = vec![
-        Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(":Chat "),
-        Span::styled("Tab", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(":Drill-in "),
-        Span::styled("n/N", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(":New "),
-        Span::styled("d/D", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(":Kill | "),
-        Span::styled("z", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(":Hold the line | "),
-        Span::styled("Q", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(":Quit | "),
+        key("Enter"),
+        label("Chat", " "),
+        key("Tab"),
+        label("Drill-in", " "),
+        key("n/N"),
+        label("New", " "),
+        key("d/D"),
+        label("Kill", " | "),
+        key("Ctrl+z"),
+        label("Hold the line", " | "),
+        key("Q"),
+        label("Quit", " | "),
     ];
     if !at_root {
-        help_text.push(Span::styled(
-            "Esc",
-            Style::default().add_modifier(Modifier::BOLD),
-        ));
-        help_text.push(Span::raw(":Back | "));
+        help_text.push(key("Esc"));
+        help_text.push(label("Back", " | "));
     }
-    help_text.push(Span::styled(
-        "S",
-        Style::default().add_modifier(Modifier::BOLD),
-    ));
-    help_text.push(Span::raw(":Settings "));
-    help_text.push(Span::styled(
-        "?",
-        Style::default().add_modifier(Modifier::BOLD),
-    ));
-    help_text.push(Span::raw(":Help"));
+    help_text.push(key("S"));
+    help_text.push(label("Settings", " "));
+    help_text.push(key("?"));
+    help_text.push(label("Help", ""));
 
     // Right side: status message takes priority over ticker
     let right_content = if let Some(ref msg) = app.status_message {
-        Some((msg.clone(), Style::default().fg(Color::Cyan)))
+        Some((msg.clone(), Style::default().fg(theme.border)))
     } else {
         let ticker_content = app.ticker.render(std::time::Duration::from_secs(5));
         if !ticker_content.is_empty() {
@@ -1216,7 +1568,7 @@ fn render_help_bar(frame: &mut Frame, app: &App, area: Rect) {
 
         // Left: help text
         let help_paragraph =
-            Paragraph::new(Line::from(help_text)).style(Style::default().fg(COLOR_INACTIVE));
+            Paragraph::new(Line::from(help_text)).style(Style::default().fg(theme.dim));
         frame.render_widget(help_paragraph, h_chunks[0]);
 
         // Right: status message or ticker
@@ -1245,52 +1597,85 @@ fn render_help_bar(frame: &mut Frame, app: &App, area: Rect) {
         }
     } else {
         // No right content — full-width help text
-        let paragraph =
-            Paragraph::new(Line::from(help_text)).style(Style::default().fg(COLOR_INACTIVE));
+        let paragraph = Paragraph::new(Line::from(help_text)).style(Style::default().fg(theme.dim));
         frame.render_widget(paragraph, area);
     }
 }
 
-fn render_help_popup(frame: &mut Frame) {
+/// Help popup rows: keys, then the (translatable) description.
+pub(super) const HELP_KEYS: &[(&str, &str)] = &[
+    ("Q", "Quit: keep, kill idle, or kill all agents"),
+    ("←/→, h/l", "Switch panel (sidebar ↔ main)"),
+    ("↑/↓, j/k", "Move selection up/down"),
+    ("J/K, Ctrl+j/k", "Jump to next/previous group"),
+    ("Tab", "Drill into selected agent"),
+    ("Shift+Tab", "Back (drill up)"),
+    ("Esc", "Back (drill up)"),
+    ("Enter", "Attach to selected agent"),
+    ("n", "Spawn agent (wizard)"),
+    ("d", "Kill selected agent"),
+    ("T", "Kill selected agent and everyone under it"),
+    ("N", "Spawn new EA (prompts for name)"),
+    ("D", "Delete current EA (not the only one)"),
+    ("p", "Add a project"),
+    ("m", "Send a message to selected agent"),
+    ("a", "Add or edit a note on selected agent"),
+    ("F", "Search output of all agents"),
+    ("/", "Filter agents by name or tag"),
+    ("x", "Run a command or shell in the agent's sandbox"),
+    ("L", "Firehose: interleaved log tail of all agents"),
+    ("H", "Cycle refresh priority: normal, hot, cold"),
+    ("{/}", "Cycle the agent card through its extra panes"),
+    ("A", "Review pipeline stages awaiting approval"),
+    (
+        "f",
+        "Browse and view files in the agent's working directory",
+    ),
+    ("[", "Previous EA"),
+    ("]", "Next EA"),
+    ("e", "Show scheduled events"),
+    ("W", "Spawn queue (x cancel, + bump)"),
+    ("!", "Needs attention: agents waiting on you, quick reply"),
+    ("G", "Debug console"),
+    ("S", "Settings"),
+    (
+        "V",
+        "Cycle layout: standard, grid-only, ea-focus, tree-focus",
+    ),
+    ("P/E/C", "Show/hide projects, EA panel, chain of command"),
+    ("z", "Zoom the selected agent to fill the screen"),
+    ("Ctrl+z", "Detach (dashboard keeps running)"),
+    ("Z", "Drain: finish running work, then stop and exit"),
+    ("r", "Refresh agent list"),
+    ("?", "Toggle this help"),
+];
+
+fn render_help_popup(frame: &mut Frame, theme: &Theme, app: &App) {
     let area = centered_rect(60, 50, frame.area());
+    let lang = app.config.dashboard.language;
 
-    let help_content = vec![
+    let mut help_content = vec![
         Line::from(Span::styled(
-            "Keyboard Shortcuts",
+            tr(lang, "Keyboard Shortcuts"),
             Style::default().add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
-        Line::from("  Q           Quit and reset runtime state"),
-        Line::from("  ←/→, h/l   Switch panel (sidebar ↔ main)"),
-        Line::from("  ↑/↓, j/k   Move selection up/down"),
-        Line::from("  Tab         Drill into selected agent"),
-        Line::from("  Shift+Tab   Back (drill up)"),
-        Line::from("  Esc         Back (drill up)"),
-        Line::from("  Enter       Attach to selected agent"),
-        Line::from("  n           Spawn new agent"),
-        Line::from("  d           Kill selected agent"),
-        Line::from("  N           Spawn new EA (prompts for name)"),
-        Line::from("  D           Delete current EA (not the only one)"),
-        Line::from("  p           Add a project"),
-        Line::from("  [           Previous EA"),
-        Line::from("  ]           Next EA"),
-        Line::from("  e           Show scheduled events"),
-        Line::from("  G           Debug console"),
-        Line::from("  S           Settings"),
-        Line::from("  z           Detach (dashboard keeps running)"),
-        Line::from("  r           Refresh agent list"),
-        Line::from("  ?           Toggle this help"),
-        Line::from(""),
-        Line::from(Span::styled(
-            "Press any key to close",
-            Style::default().fg(COLOR_INACTIVE),
-        )),
     ];
+    help_content.extend(
+        HELP_KEYS
+            .iter()
+            .map(|&(keys, desc)| Line::from(format!("  {:<12}{}", keys, tr(lang, desc)))),
+    );
+    help_content.push(Line::from(""));
+    help_content.push(Line::from(Span::styled(
+        tr(lang, "Press any key to close"),
+        Style::default().fg(theme.dim),
+    )));
 
     let block = Block::default()
-        .title(" Help ")
+        .title(format!(" {} ", tr(lang, "Help")))
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(Style::default().fg(theme.border));
 
     let paragraph = Paragraph::new(help_content).block(block);
 
@@ -1298,22 +1683,36 @@ fn render_help_popup(frame: &mut Frame) {
     frame.render_widget(paragraph, area);
 }
 
-fn render_confirm_dialog(frame: &mut Frame, app: &App, action: ConfirmAction) {
+fn render_confirm_dialog(frame: &mut Frame, theme: &Theme, app: &App, action: ConfirmAction) {
+    let lang = app.config.dashboard.language;
     let (title, heading, detail, hint, width) = match action {
         ConfirmAction::Kill => {
             let name = app
                 .selected_agent()
                 .map(|a| a.session.name.clone())
                 .unwrap_or_else(|| "?".to_string());
-            (" Confirm ", "Kill this agent?", name, String::new(), 40)
+            (
+                format!(" {} ", tr(lang, "Confirm")),
+                tr(lang, "Kill this agent?"),
+                name,
+                String::new(),
+                40,
+            )
+        }
+        ConfirmAction::KillGroup => {
+            let group = app.selected_group();
+            let names: Vec<&str> = group
+                .iter()
+                .map(|session| app.display_session_name(session))
+                .collect();
+            (
+                format!(" {} ", tr(lang, "Confirm Kill Group")),
+                tr(lang, "Kill this agent and its team?"),
+                trf(lang, "{} agent(s): {}", &[&names.len(), &names.join(", ")]),
+                tr(lang, "Their hierarchy and results are forgotten.").to_string(),
+                60,
+            )
         }
-        ConfirmAction::ResetQuit => (
-            " Confirm Quit ",
-            "Quit omar?",
-            "This will kill ALL EA sessions and agents.".to_string(),
-            "Press z to walk away instead.".to_string(),
-            50,
-        ),
         ConfirmAction::DeleteEa => {
             let ea_name = app
                 .registered_eas
@@ -1322,13 +1721,41 @@ fn render_confirm_dialog(frame: &mut Frame, app: &App, action: ConfirmAction) {
                 .map(|ea| ea.name.clone())
                 .unwrap_or_else(|| "Unknown EA".to_string());
             (
-                " Confirm Delete EA ",
-                "Delete this EA?",
+                format!(" {} ", tr(lang, "Confirm Delete EA")),
+                tr(lang, "Delete this EA?"),
                 ea_name,
-                "This will kill all agents and remove all state.".to_string(),
+                tr(lang, "This will kill all agents and remove all state.").to_string(),
                 55,
             )
         }
+        ConfirmAction::Drain if app.drain.is_some() => (
+            format!(" {} ", tr(lang, "Cancel Drain")),
+            tr(lang, "Cancel the drain?"),
+            trf(lang, "{} worker(s) still running", &[&app.drain_waiting]),
+            tr(lang, "New agents will be started again.").to_string(),
+            50,
+        ),
+        ConfirmAction::Drain => (
+            format!(" {} ", tr(lang, "Drain")),
+            tr(lang, "Drain and exit?"),
+            tr(lang, "No new agents start; running workers finish.").to_string(),
+            tr(lang, "Then all agents and EAs stop and omar exits.").to_string(),
+            55,
+        ),
+        ConfirmAction::ResumeRun => {
+            let names: Vec<String> = app
+                .resumable
+                .iter()
+                .map(|agent| app.display_session_name(&agent.session).to_string())
+                .collect();
+            (
+                format!(" {} ", tr(lang, "Resume Previous Run")),
+                tr(lang, "Resume unfinished agents?"),
+                trf(lang, "{} agent(s): {}", &[&names.len(), &names.join(", ")]),
+                tr(lang, "No forgets them; their tasks are not re-sent.").to_string(),
+                60,
+            )
+        }
     };
 
     let area = centered_rect(width, 30, frame.area());
@@ -1345,15 +1772,15 @@ fn render_confirm_dialog(frame: &mut Frame, app: &App, action: ConfirmAction) {
     if !hint.is_empty() {
         content.push(Line::from(Span::styled(
             hint,
-            Style::default().fg(COLOR_INACTIVE),
+            Style::default().fg(theme.dim),
         )));
     }
     content.push(Line::from(""));
     content.push(Line::from(vec![
         Span::styled("y", Style::default().fg(Color::Green)),
-        Span::raw(": Yes  "),
+        Span::raw(format!(": {}  ", tr(lang, "Yes"))),
         Span::styled("n", Style::default().fg(Color::Red)),
-        Span::raw(": No"),
+        Span::raw(format!(": {}", tr(lang, "No"))),
     ]));
 
     let block = Block::default()
@@ -1369,7 +1796,81 @@ fn render_confirm_dialog(frame: &mut Frame, app: &App, action: ConfirmAction) {
     frame.render_widget(paragraph, area);
 }
 
-fn render_project_input(frame: &mut Frame, app: &App) {
+/// Workers listed before the rest are summarized as "+N more".
+const SHUTDOWN_LIST_MAX: usize = 12;
+
+fn render_shutdown_dialog(
+    frame: &mut Frame,
+    theme: &Theme,
+    app: &App,
+    workers: &[(String, HealthState)],
+) {
+    let lang = app.config.dashboard.language;
+    let area = centered_rect(60, 60, frame.area());
+    let mut content = vec![
+        Line::from(Span::styled(
+            tr(lang, "Quit omar?"),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    if workers.is_empty() {
+        content.push(Line::from(Span::styled(
+            tr(lang, "No workers are running."),
+            Style::default().fg(theme.dim),
+        )));
+    } else {
+        content.push(Line::from(trf(
+            lang,
+            "{} worker(s) running:",
+            &[&workers.len()],
+        )));
+        for (session, health) in workers.iter().take(SHUTDOWN_LIST_MAX) {
+            let (color, icon) = theme.health(*health);
+            content.push(Line::from(vec![
+                Span::styled(format!("  {} ", icon), Style::default().fg(color)),
+                Span::raw(app.display_session_name(session).to_string()),
+                Span::styled(
+                    format!("  {}", health.as_str()),
+                    Style::default().fg(theme.dim),
+                ),
+            ]));
+        }
+        if workers.len() > SHUTDOWN_LIST_MAX {
+            content.push(Line::from(Span::styled(
+                trf(lang, "  +{} more", &[&(workers.len() - SHUTDOWN_LIST_MAX)]),
+                Style::default().fg(theme.dim),
+            )));
+        }
+    }
+    content.push(Line::from(""));
+    for (key, label, color) in [
+        ("k", "Keep everything running", Color::Green),
+        ("i", "Kill idle and finished workers only", Color::Yellow),
+        ("a", "Kill all EAs and agents, reset state", Color::Red),
+        ("Esc", "Cancel", theme.dim),
+    ] {
+        content.push(Line::from(vec![
+            Span::styled(format!("  {:<4}", key), Style::default().fg(color)),
+            Span::raw(tr(lang, label)),
+        ]));
+    }
+
+    let block = Block::default()
+        .title(format!(" {} ", tr(lang, "Shut Down")))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Red));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(content)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn render_project_input(frame: &mut Frame, theme: &Theme, app: &App) {
     let area = centered_rect(50, 20, frame.area());
 
     let content = vec![
@@ -1381,19 +1882,19 @@ fn render_project_input(frame: &mut Frame, app: &App) {
         Line::from(""),
         Line::from(Span::styled(
             format!("> {}_", app.project_input),
-            Style::default().fg(Color::Cyan),
+            Style::default().fg(theme.border),
         )),
         Line::from(""),
         Line::from(Span::styled(
             "Enter to confirm, Esc to cancel",
-            Style::default().fg(COLOR_INACTIVE),
+            Style::default().fg(theme.dim),
         )),
     ];
 
     let block = Block::default()
         .title(" New Project ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(Style::default().fg(theme.border));
 
     let paragraph = Paragraph::new(content)
         .block(block)
@@ -1403,7 +1904,7 @@ fn render_project_input(frame: &mut Frame, app: &App) {
     frame.render_widget(paragraph, area);
 }
 
-fn render_ea_input(frame: &mut Frame, app: &App) {
+fn render_ea_input(frame: &mut Frame, theme: &Theme, app: &App) {
     let area = centered_rect(50, 20, frame.area());
 
     let content = vec![
@@ -1415,19 +1916,19 @@ fn render_ea_input(frame: &mut Frame, app: &App) {
         Line::from(""),
         Line::from(Span::styled(
             format!("> {}_", app.ea_input),
-            Style::default().fg(Color::Cyan),
+            Style::default().fg(theme.border),
         )),
         Line::from(""),
         Line::from(Span::styled(
             "Enter to confirm, Esc to cancel",
-            Style::default().fg(COLOR_INACTIVE),
+            Style::default().fg(theme.dim),
         )),
     ];
 
     let block = Block::default()
         .title(" New EA ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(Style::default().fg(theme.border));
 
     let paragraph = Paragraph::new(content)
         .block(block)
@@ -1437,56 +1938,715 @@ fn render_ea_input(frame: &mut Frame, app: &App) {
     frame.render_widget(paragraph, area);
 }
 
-fn render_events_popup(frame: &mut Frame, app: &App) {
-    let area = centered_rect(70, 60, frame.area());
-    let inner_width = area.width.saturating_sub(2) as usize; // borders
-    let fixed_cols: usize = 14 + 14 + 16 + 14; // Sender + Receiver + Fires in + Type
-    let payload_width = inner_width.saturating_sub(fixed_cols + 1);
-
-    let mut lines: Vec<Line> = vec![
-        Line::from(Span::styled(
-            "Scheduled Events",
-            Style::default().add_modifier(Modifier::BOLD),
-        )),
-        Line::from(""),
-    ];
+fn render_message_input(frame: &mut Frame, theme: &Theme, app: &App, target: &str) {
+    let area = centered_rect(70, 50, frame.area());
+    let short_name = naming::short_name(app.client().prefix(), target);
 
-    if app.scheduled_events.is_empty() {
-        lines.push(Line::from(Span::styled(
-            "No events in queue",
-            Style::default().fg(COLOR_INACTIVE),
-        )));
-    } else {
-        // Header
-        lines.push(Line::from(vec![
-            Span::styled(
-                format!(
-                    "{:<14} {:<14} {:<16} {:<14} ",
-                    "Sender", "Receiver", "Fires in", "Type"
-                ),
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                "Payload",
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
-        ]));
-        lines.push(Line::from(Span::styled(
-            "─".repeat(inner_width),
-            Style::default().fg(COLOR_INACTIVE),
+    let mut content: Vec<Line> = Vec::new();
+    let mut lines = app.message_input.split('\n').peekable();
+    while let Some(line) = lines.next() {
+        let text = if lines.peek().is_none() {
+            format!("{}_", line)
+        } else {
+            line.to_string()
+        };
+        content.push(Line::from(Span::styled(
+            text,
+            Style::default().fg(theme.border),
         )));
+    }
+    content.push(Line::from(""));
+    content.push(Line::from(Span::styled(
+        "Enter to send, Alt+Enter for newline, Esc to cancel",
+        Style::default().fg(theme.dim),
+    )));
 
-        for event in &app.scheduled_events {
-            // Format timestamp as human-readable relative time
+    let block = Block::default()
+        .title(format!(" Message → {} ", short_name))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+
+    // Keep the cursor line visible once the text outgrows the popup.
+    let inner_height = area.height.saturating_sub(2) as usize;
+    let scroll = content.len().saturating_sub(inner_height) as u16;
+    let paragraph = Paragraph::new(content)
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+fn render_note_input(frame: &mut Frame, theme: &Theme, app: &App, target: &str) {
+    let area = centered_rect(60, 40, frame.area());
+    let short_name = naming::short_name(app.client().prefix(), target);
+
+    let mut content: Vec<Line> = Vec::new();
+    let mut lines = app.note_input.split('\n').peekable();
+    while let Some(line) = lines.next() {
+        let text = if lines.peek().is_none() {
+            format!("{}_", line)
+        } else {
+            line.to_string()
+        };
+        content.push(Line::from(Span::styled(
+            text,
+            Style::default().fg(theme.border),
+        )));
+    }
+    content.push(Line::from(""));
+    content.push(Line::from(Span::styled(
+        "Enter to save (empty removes the note), Alt+Enter for newline, Esc to cancel",
+        Style::default().fg(theme.dim),
+    )));
+
+    let block = Block::default()
+        .title(format!(" Note on {} ", short_name))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+
+    let inner_height = area.height.saturating_sub(2) as usize;
+    let scroll = content.len().saturating_sub(inner_height) as u16;
+    let paragraph = Paragraph::new(content)
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+fn render_search_input(frame: &mut Frame, theme: &Theme, query: &str) {
+    let area = centered_rect(50, 20, frame.area());
+
+    let content = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "Search all agent output",
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("/ {}_", query),
+            Style::default().fg(theme.border),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Regex or text, case-insensitive. Enter to search, Esc to cancel",
+            Style::default().fg(theme.dim),
+        )),
+    ];
+
+    let block = Block::default()
+        .title(" Search ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+
+    let paragraph = Paragraph::new(content)
+        .block(block)
+        .alignment(ratatui::layout::Alignment::Center);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+/// Banner across the top of the main area for a finished pipeline run.
+fn render_celebration(frame: &mut Frame, theme: &Theme, summary: &str, area: Rect) {
+    let text = format!("🎉 {} 🎉", summary);
+    let width = (text.chars().count() as u16 + 6).min(area.width);
+    let banner = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y,
+        width,
+        height: 3.min(area.height),
+    };
+    let paragraph = Paragraph::new(Span::styled(
+        text,
+        Style::default()
+            .fg(theme.finished)
+            .add_modifier(Modifier::BOLD),
+    ))
+    .alignment(ratatui::layout::Alignment::Center)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .border_style(Style::default().fg(theme.finished)),
+    );
+    frame.render_widget(Clear, banner);
+    frame.render_widget(paragraph, banner);
+}
+
+fn render_filter_input(frame: &mut Frame, theme: &Theme, filter: &str) {
+    let area = centered_rect(50, 20, frame.area());
+
+    let content = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "Filter agents",
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("/ {}_", filter),
+            Style::default().fg(theme.border),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Name or tag. Enter to apply (empty clears), Esc to cancel",
+            Style::default().fg(theme.dim),
+        )),
+    ];
+
+    let block = Block::default()
+        .title(" Filter ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+
+    let paragraph = Paragraph::new(content)
+        .block(block)
+        .alignment(ratatui::layout::Alignment::Center);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+fn render_exec_popup(frame: &mut Frame, theme: &Theme, app: &App, popup: &ExecPopup) {
+    let area = centered_rect(80, 70, frame.area());
+    let short_name = naming::short_name(app.client().prefix(), &popup.target);
+
+    let mut content = Vec::new();
+    if let Some((command, output)) = &popup.last {
+        let outcome = match output.exit_code {
+            _ if output.timed_out => "timed out".to_string(),
+            Some(code) => format!("exit {}", code),
+            None => "killed".to_string(),
+        };
+        let color = if output.exit_code == Some(0) {
+            theme.running
+        } else {
+            theme.blocked
+        };
+        content.push(Line::from(vec![
+            Span::styled(format!("$ {} ", command), Style::default().fg(theme.dim)),
+            Span::styled(format!("({})", outcome), Style::default().fg(color)),
+        ]));
+        for line in output.stdout.lines() {
+            content.push(Line::from(line.to_string()));
+        }
+        for line in output.stderr.lines() {
+            content.push(Line::from(Span::styled(
+                line.to_string(),
+                Style::default().fg(theme.blocked),
+            )));
+        }
+        content.push(Line::from(""));
+    }
+    content.push(Line::from(Span::styled(
+        format!("$ {}_", popup.input),
+        Style::default().fg(theme.border),
+    )));
+    content.push(Line::from(Span::styled(
+        "Enter to run (empty opens a shell), Esc to close",
+        Style::default().fg(theme.dim),
+    )));
+
+    let block = Block::default()
+        .title(format!(" Exec → {} ", short_name))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+
+    // Keep the prompt visible below long output
+    let inner_height = area.height.saturating_sub(2) as usize;
+    let scroll = content.len().saturating_sub(inner_height) as u16;
+    let paragraph = Paragraph::new(content)
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+fn render_spawn_form(frame: &mut Frame, theme: &Theme, form: &SpawnForm) {
+    let area = centered_rect(70, 50, frame.area());
+
+    let mut content = vec![
+        Line::from(Span::styled(
+            "Spawn Agent",
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    for field in SpawnField::ALL {
+        let selected = field == form.field;
+        let value = match field {
+            SpawnField::Name if form.name.is_empty() && !selected => "(auto)".to_string(),
+            SpawnField::Name => form.name.clone(),
+            SpawnField::Profile if !form.has_profiles() => "n/a (no [[profiles]])".to_string(),
+            SpawnField::Profile => format!(
+                "◂ {} ▸",
+                form.profile()
+                    .map_or("(none)", |profile| profile.name.as_str())
+            ),
+            SpawnField::Command => form.command.clone(),
+            SpawnField::Workdir => form.workdir.clone(),
+            SpawnField::Task => form.task.clone(),
+            SpawnField::Parent => format!("◂ {} ▸", form.parent()),
+            SpawnField::Sandbox if !form.sandbox_available => {
+                "n/a (sandbox.runtime = none)".to_string()
+            }
+            SpawnField::Sandbox if form.sandbox && form.shares_credentials => {
+                "[ON] ⚠ shares host credentials".to_string()
+            }
+            SpawnField::Sandbox if form.sandbox => "[ON]".to_string(),
+            SpawnField::Sandbox => "[OFF]".to_string(),
+        };
+        let editable = matches!(
+            field,
+            SpawnField::Name | SpawnField::Command | SpawnField::Workdir | SpawnField::Task
+        );
+        let value = if selected && editable {
+            format!("{}_", value)
+        } else {
+            value
+        };
+        content.push(Line::from(vec![
+            Span::styled(
+                if selected { "▸ " } else { "  " },
+                Style::default().fg(theme.border),
+            ),
+            Span::styled(
+                format!("{:<9}", field.label()),
+                Style::default().fg(if selected { Color::Reset } else { theme.dim }),
+            ),
+            Span::styled(
+                value,
+                Style::default().fg(if selected { theme.border } else { Color::Green }),
+            ),
+        ]));
+    }
+    content.push(Line::from(vec![
+        Span::styled(
+            format!("  {:<9}", "Estimate"),
+            Style::default().fg(theme.dim),
+        ),
+        Span::styled(
+            form.estimate()
+                .map(|estimate| estimate.summary())
+                .unwrap_or_else(|| "no similar runs yet".to_string()),
+            Style::default().fg(theme.dim),
+        ),
+    ]));
+    content.push(Line::from(""));
+    content.push(Line::from(Span::styled(
+        "↑/↓ field  ←/→ profile/preset/parent/toggle  Tab complete path  Enter spawn  Esc cancel",
+        Style::default().fg(theme.dim),
+    )));
+
+    let block = Block::default()
+        .title(" New Agent ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+    let paragraph = Paragraph::new(content)
+        .block(block)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+fn render_file_browser(frame: &mut Frame, theme: &Theme, browser: &FileBrowser) {
+    let area = centered_rect(70, 70, frame.area());
+    // Borders, footer, and its spacer
+    let visible = area.height.saturating_sub(4) as usize;
+    let offset = browser.selected.saturating_sub(visible.saturating_sub(1));
+
+    let mut content: Vec<Line> = Vec::new();
+    if let Some(error) = browser.error.as_deref() {
+        content.push(Line::from(Span::styled(
+            error.to_string(),
+            Style::default().fg(Color::Red),
+        )));
+    } else if browser.entries.is_empty() {
+        content.push(Line::from(Span::styled(
+            "(empty)",
+            Style::default().fg(theme.dim),
+        )));
+    }
+    let name_width = area.width.saturating_sub(14) as usize;
+    for (idx, entry) in browser
+        .entries
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(visible)
+    {
+        let selected = idx == browser.selected;
+        let (name, size) = if entry.is_dir {
+            (format!("{}/", entry.name), String::new())
+        } else {
+            (entry.name.clone(), file_browser::format_size(entry.size))
+        };
+        let style = match (selected, entry.is_dir) {
+            (true, _) => Style::default().fg(Color::Black).bg(theme.border),
+            (false, true) => Style::default().fg(theme.border),
+            (false, false) => Style::default(),
+        };
+        content.push(Line::from(Span::styled(
+            format!("{:<width$} {:>8}", name, size, width = name_width),
+            style,
+        )));
+    }
+    content.push(Line::from(""));
+    content.push(Line::from(Span::styled(
+        "↑/↓ select  Enter open  Backspace up  r reload  Esc close",
+        Style::default().fg(theme.dim),
+    )));
+
+    let block = Block::default()
+        .title(format!(" {} — {} ", browser.agent, browser.display_path()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+    let paragraph = Paragraph::new(content).block(block);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+fn render_file_viewer(frame: &mut Frame, theme: &Theme, agent: &str, viewer: &FileViewer) {
+    let area = centered_rect(90, 90, frame.area());
+    let visible = area.height.saturating_sub(2) as usize;
+    let content: Vec<Line> = viewer
+        .lines
+        .iter()
+        .skip(viewer.scroll)
+        .take(visible)
+        .cloned()
+        .collect();
+
+    let mut title = format!(
+        " {} — {} ({}) ",
+        agent,
+        viewer.path,
+        file_browser::format_size(viewer.size)
+    );
+    if viewer.truncated {
+        title.push_str("[truncated] ");
+    }
+    let position = format!(
+        " {}/{}  j/k scroll  g/G top/bottom  Esc back ",
+        (viewer.scroll + 1).min(viewer.lines.len()),
+        viewer.lines.len()
+    );
+    let block = Block::default()
+        .title(title)
+        .title_bottom(Line::from(Span::styled(
+            position,
+            Style::default().fg(theme.dim),
+        )))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+    let paragraph = Paragraph::new(content).block(block);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+fn render_approval_review(frame: &mut Frame, theme: &Theme, run: &PipelineRun) {
+    let area = centered_rect(70, 60, frame.area());
+    let stage = &run.stages[run.current];
+    let previous = run
+        .current
+        .checked_sub(1)
+        .and_then(|prev| run.stages[prev].result.as_deref());
+
+    let mut content = vec![
+        Line::from(Span::styled(
+            format!(
+                "{}#{} stage {}/{}: {}",
+                run.pipeline,
+                run.id,
+                run.current + 1,
+                run.stages.len(),
+                stage.role
+            ),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled("Task", Style::default().fg(theme.border))),
+    ];
+    content.extend(
+        run.stage_task(run.current)
+            .lines()
+            .map(|line| Line::from(line.to_string())),
+    );
+    if let Some(previous) = previous {
+        content.push(Line::from(""));
+        content.push(Line::from(Span::styled(
+            "Previous stage result",
+            Style::default().fg(theme.border),
+        )));
+        content.extend(previous.lines().map(|line| Line::from(line.to_string())));
+    }
+    content.push(Line::from(""));
+    content.push(Line::from(Span::styled(
+        "y approve  x reject  Esc decide later (A reopens)",
+        Style::default().fg(theme.dim),
+    )));
+
+    let block = Block::default()
+        .title(" Approval Required ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+    let paragraph = Paragraph::new(content)
+        .block(block)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+fn render_search_results(
+    frame: &mut Frame,
+    theme: &Theme,
+    app: &App,
+    query: &str,
+    matches: &[crate::search::SearchMatch],
+) {
+    let area = centered_rect(85, 80, frame.area());
+
+    let mut content: Vec<Line> = Vec::new();
+    if matches.is_empty() {
+        content.push(Line::from(Span::styled(
+            "No matches",
+            Style::default().fg(theme.dim),
+        )));
+    }
+    for m in matches {
+        content.push(Line::from(vec![
+            Span::styled(
+                format!("{}:{} ", app.display_session_name(&m.session), m.line),
+                Style::default()
+                    .fg(theme.selection)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(m.text.clone()),
+        ]));
+        for line in &m.context {
+            content.push(Line::from(Span::styled(
+                format!("    {}", line),
+                Style::default().fg(theme.dim),
+            )));
+        }
+    }
+
+    let block = Block::default()
+        .title(format!(
+            " Search: {} ({} matches) — j/k scroll, Esc close ",
+            query,
+            matches.len()
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+
+    let max_scroll = content.len().saturating_sub(1);
+    let paragraph = Paragraph::new(content)
+        .block(block)
+        .scroll((app.search_scroll.min(max_scroll) as u16, 0));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+/// Stable per-agent color for firehose name prefixes.
+fn firehose_color(name: &str) -> Color {
+    const PALETTE: [Color; 6] = [
+        Color::Cyan,
+        Color::Green,
+        Color::Yellow,
+        Color::LightMagenta,
+        Color::LightBlue,
+        Color::LightRed,
+    ];
+    let hash = name.bytes().fold(0usize, |acc, b| {
+        acc.wrapping_mul(31).wrapping_add(b as usize)
+    });
+    PALETTE[hash % PALETTE.len()]
+}
+
+fn render_firehose(frame: &mut Frame, theme: &Theme, app: &App, firehose: &Firehose, area: Rect) {
+    let height = area.height.saturating_sub(2) as usize;
+    let lines = firehose.lines();
+    let max_scroll = lines.len().saturating_sub(height);
+    let scroll = app.firehose_scroll.min(max_scroll);
+    let end = lines.len() - scroll;
+    let start = end.saturating_sub(height);
+
+    let names: Vec<&str> = lines
+        .range(start..end)
+        .map(|line| app.display_session_name(&line.session))
+        .collect();
+    let width = names
+        .iter()
+        .map(|name| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let content: Vec<Line> = lines
+        .range(start..end)
+        .zip(names)
+        .map(|(line, name)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{:<width$} | ", name),
+                    Style::default().fg(firehose_color(name)),
+                ),
+                Span::raw(line.text.clone()),
+            ])
+        })
+        .collect();
+
+    let state = if scroll > 0 {
+        format!("paused, {} newer", scroll)
+    } else {
+        "following".to_string()
+    };
+    let block = Block::default()
+        .title(format!(
+            " Firehose ({}) — j/k scroll, G follow, Esc close ",
+            state
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.selection));
+    frame.render_widget(Paragraph::new(content).block(block), area);
+}
+
+/// Scrollback captured for the zoomed view.
+const ZOOM_HISTORY_LINES: i32 = 2000;
+
+fn render_zoomed(frame: &mut Frame, theme: &Theme, app: &App, agent: &AgentInfo, area: Rect) {
+    let (health_color, status_icon) = theme.health(agent.health);
+    let content_width = area.width.saturating_sub(4) as usize;
+
+    let mut header: Vec<Line> = Vec::new();
+    if let Some(task) = app.worker_tasks().get(&agent.session.name) {
+        header.push(Line::from(vec![
+            Span::raw("Task: "),
+            Span::raw(truncate_str(task, content_width.saturating_sub(6))),
+        ]));
+    }
+    if let Some((sender, message)) = app.last_instruction(&agent.session.name) {
+        let label = format!("↳ {}: ", sender);
+        let width = content_width.saturating_sub(label.chars().count());
+        header.push(Line::from(vec![
+            Span::styled(label, Style::default().fg(theme.border)),
+            Span::styled(truncate_str(message, width), Style::default().fg(theme.dim)),
+        ]));
+    }
+    if !header.is_empty() {
+        header.push(Line::from(Span::styled(
+            "─".repeat(content_width),
+            Style::default().fg(theme.dim),
+        )));
+    }
+
+    let target = app
+        .pane_view(&agent.session.name)
+        .map_or(agent.session.name.as_str(), |(pane, _, _)| pane);
+    let output = app
+        .client()
+        .capture_pane_plain(target, ZOOM_HISTORY_LINES)
+        .unwrap_or_else(|e| format!("(pane unavailable: {})", e));
+    let output: Vec<&str> = output.trim_end().lines().collect();
+    let height = (area.height.saturating_sub(2) as usize).saturating_sub(header.len());
+    let max_scroll = output.len().saturating_sub(height);
+    let scroll = app.zoom_scroll.min(max_scroll);
+    let end = output.len() - scroll;
+    let start = end.saturating_sub(height);
+
+    let mut lines = header;
+    lines.extend(
+        output[start..end]
+            .iter()
+            .map(|text| Line::from(truncate_str(text, content_width))),
+    );
+
+    let state = if scroll > 0 {
+        format!("paused, {} newer", scroll)
+    } else {
+        "following".to_string()
+    };
+    let title = Line::from(vec![
+        Span::styled(
+            format!(" {} ", status_icon),
+            Style::default().fg(health_color),
+        ),
+        Span::styled(
+            app.display_session_name(&agent.session.name).to_string(),
+            Style::default().fg(theme.selection),
+        ),
+        Span::raw(format!(" ({}) — j/k scroll, G follow, z/Esc close ", state)),
+    ]);
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .border_style(Style::default().fg(theme.selection))
+        .padding(Padding::horizontal(1));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn render_events_popup(frame: &mut Frame, theme: &Theme, app: &App) {
+    let area = centered_rect(70, 60, frame.area());
+    let inner_width = area.width.saturating_sub(2) as usize; // borders
+    let fixed_cols: usize = 14 + 14 + 24 + 14; // Sender + Receiver + Fires at + Type
+    let payload_width = inner_width.saturating_sub(fixed_cols + 1);
+
+    let mut lines: Vec<Line> = vec![
+        Line::from(Span::styled(
+            "Scheduled Events",
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    if app.scheduled_events.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No events in queue",
+            Style::default().fg(theme.dim),
+        )));
+    } else {
+        // Header
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(
+                    "{:<14} {:<14} {:<24} {:<14} ",
+                    "Sender", "Receiver", "Fires at", "Type"
+                ),
+                Style::default()
+                    .fg(theme.border)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                "Payload",
+                Style::default()
+                    .fg(theme.border)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]));
+        lines.push(Line::from(Span::styled(
+            "─".repeat(inner_width),
+            Style::default().fg(theme.dim),
+        )));
+
+        for event in &app.scheduled_events {
+            // Clock time in the configured zone, plus relative time
             let now_ns = std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
                 .as_nanos() as u64;
-            let time_str = if event.timestamp <= now_ns {
+            let relative = if event.timestamp <= now_ns {
                 "overdue".to_string()
             } else {
                 let diff_ms = (event.timestamp - now_ns) / 1_000_000;
@@ -1500,6 +2660,11 @@ fn render_events_popup(frame: &mut Frame, app: &App) {
                     format!("in {:.1}h", diff_ms as f64 / 3_600_000.0)
                 }
             };
+            let time_str = format!(
+                "{} ({})",
+                app.config.dashboard.timezone.format_ns(event.timestamp),
+                relative
+            );
 
             // Truncate payload to fill remaining width
             let payload = if event.payload.chars().count() > payload_width {
@@ -1535,15 +2700,15 @@ fn render_events_popup(frame: &mut Frame, app: &App) {
                     Style::default().fg(Color::Yellow),
                 ),
                 Span::styled(
-                    format!("{:<16}", time_str),
+                    format!("{:<24}", time_str),
                     Style::default().fg(Color::Reset),
                 ),
                 Span::styled(
                     format!("{:<14}", type_str),
                     Style::default().fg(if event.recurring_ns.is_some() {
-                        COLOR_ACTIVE
+                        theme.selection
                     } else {
-                        COLOR_INACTIVE
+                        theme.dim
                     }),
                 ),
                 Span::raw(payload),
@@ -1554,13 +2719,13 @@ fn render_events_popup(frame: &mut Frame, app: &App) {
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled(
         "Press Esc or 'e' to close",
-        Style::default().fg(COLOR_INACTIVE),
+        Style::default().fg(theme.dim),
     )));
 
     let block = Block::default()
         .title(" Event Queue ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(Style::default().fg(theme.border));
 
     let paragraph = Paragraph::new(lines).block(block);
 
@@ -1568,7 +2733,150 @@ fn render_events_popup(frame: &mut Frame, app: &App) {
     frame.render_widget(paragraph, area);
 }
 
-fn render_debug_console(frame: &mut Frame, app: &App) {
+fn render_queue_popup(frame: &mut Frame, theme: &Theme, app: &App, selected: usize) {
+    let area = centered_rect(70, 60, frame.area());
+    let inner_width = area.width.saturating_sub(2) as usize;
+    let fixed_cols: usize = 4 + 18 + 5 + 10 + 30; // # + Agent + Prio + Waiting + Reason
+    let task_width = inner_width.saturating_sub(fixed_cols + 1);
+    let header_style = Style::default()
+        .fg(theme.border)
+        .add_modifier(Modifier::BOLD);
+
+    let mut lines: Vec<Line> = Vec::new();
+    if app.spawn_queue.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No spawns waiting",
+            Style::default().fg(theme.dim),
+        )));
+    } else {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "{:<4}{:<18}{:<5}{:<10}{:<30}Task",
+                "#", "Agent", "Prio", "Waiting", "Reason"
+            ),
+            header_style,
+        )));
+        let now = chrono::Utc::now();
+        for (idx, queued) in app.spawn_queue.iter().enumerate() {
+            let waiting = chrono::DateTime::parse_from_rfc3339(&queued.queued_at)
+                .map(|at| {
+                    let secs = (now - at.with_timezone(&chrono::Utc)).num_seconds().max(0);
+                    if secs < 60 {
+                        format!("{}s", secs)
+                    } else if secs < 3600 {
+                        format!("{}m", secs / 60)
+                    } else {
+                        format!("{}h", secs / 3600)
+                    }
+                })
+                .unwrap_or_else(|_| "?".to_string());
+            let task = queued.args["task"]
+                .as_str()
+                .unwrap_or("")
+                .replace('\n', " ");
+            let style = if idx == selected {
+                Style::default()
+                    .fg(theme.selection)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Reset)
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:<4}", idx + 1), style),
+                Span::styled(format!("{:<18}", truncate_str(&queued.agent, 17)), style),
+                Span::styled(format!("{:<5}", queued.priority), style),
+                Span::styled(format!("{:<10}", waiting), Style::default().fg(theme.dim)),
+                Span::styled(
+                    format!("{:<30}", truncate_str(&app.queue_reason(idx), 29)),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::raw(char_truncate(&task, task_width).to_string()),
+            ]));
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "j/k select · x cancel · + bump priority · Esc close",
+        Style::default().fg(theme.dim),
+    )));
+
+    let block = Block::default()
+        .title(format!(" Spawn Queue ({}) ", app.spawn_queue.len()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn render_attention_popup(frame: &mut Frame, theme: &Theme, app: &App, selected: usize) {
+    let lang = app.config.dashboard.language;
+    let area = centered_rect(70, 60, frame.area());
+    let inner_width = area.width.saturating_sub(2) as usize;
+    let waiting = app.attention_agents();
+
+    let mut lines: Vec<Line> = Vec::new();
+    if waiting.is_empty() {
+        lines.push(Line::from(Span::styled(
+            tr(lang, "No agent is waiting on you"),
+            Style::default().fg(theme.dim),
+        )));
+    }
+    for (idx, (agent, question)) in waiting.iter().enumerate() {
+        let name = naming::short_name(app.client().prefix(), &agent.session.name);
+        let style = if idx == selected {
+            Style::default()
+                .fg(theme.selection)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Reset)
+        };
+        let question = if question.is_empty() {
+            tr(lang, "(blocked, no question shown)").to_string()
+        } else {
+            question.replace('\n', " ")
+        };
+        let (color, icon) = theme.health(agent.health);
+        lines.push(Line::from(vec![
+            Span::styled(format!("{} ", icon), Style::default().fg(color)),
+            Span::styled(format!("{:<18}", truncate_str(name, 17)), style),
+            Span::raw(char_truncate(&question, inner_width.saturating_sub(20)).to_string()),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!("{}: ", tr(lang, "Reply")),
+            Style::default().fg(theme.border),
+        ),
+        Span::raw(app.attention_input.as_str()),
+        Span::styled("█", Style::default().fg(theme.dim)),
+    ]));
+    lines.push(Line::from(Span::styled(
+        tr(lang, "↑/↓ select · type a reply · Enter send · Esc close"),
+        Style::default().fg(theme.dim),
+    )));
+
+    let block = Block::default()
+        .title(format!(
+            " {} ",
+            trf(lang, "Needs Attention ({})", &[&waiting.len()])
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn render_debug_console(frame: &mut Frame, theme: &Theme, app: &App) {
     let area = centered_rect(60, 40, frame.area());
 
     let messages = app.ticker.latest(10);
@@ -1584,15 +2892,12 @@ fn render_debug_console(frame: &mut Frame, app: &App) {
     if messages.is_empty() {
         lines.push(Line::from(Span::styled(
             "No messages yet",
-            Style::default().fg(COLOR_INACTIVE),
+            Style::default().fg(theme.dim),
         )));
     } else {
         for (i, msg) in messages.iter().enumerate() {
             lines.push(Line::from(vec![
-                Span::styled(
-                    format!("{:>2}. ", i + 1),
-                    Style::default().fg(COLOR_INACTIVE),
-                ),
+                Span::styled(format!("{:>2}. ", i + 1), Style::default().fg(theme.dim)),
                 Span::styled(msg.clone(), Style::default().fg(Color::Yellow)),
             ]));
         }
@@ -1601,7 +2906,7 @@ fn render_debug_console(frame: &mut Frame, app: &App) {
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled(
         "Press Esc or 'G' to close",
-        Style::default().fg(COLOR_INACTIVE),
+        Style::default().fg(theme.dim),
     )));
 
     let block = Block::default()
@@ -1615,7 +2920,7 @@ fn render_debug_console(frame: &mut Frame, app: &App) {
     frame.render_widget(paragraph, area);
 }
 
-fn render_settings_popup(frame: &mut Frame, app: &App) {
+fn render_settings_popup(frame: &mut Frame, theme: &Theme, app: &App) {
     let area = centered_rect(50, 30, frame.area());
 
     let mut lines: Vec<Line> = vec![
@@ -1635,17 +2940,9 @@ fn render_settings_popup(frame: &mut Frame, app: &App) {
         let prefix = if selected { "▸ " } else { "  " };
         let prefix_span = Span::styled(
             prefix,
-            Style::default().fg(if selected {
-                Color::Cyan
-            } else {
-                COLOR_INACTIVE
-            }),
+            Style::default().fg(if selected { theme.border } else { theme.dim }),
         );
-        let label_style = Style::default().fg(if selected {
-            Color::Reset
-        } else {
-            COLOR_INACTIVE
-        });
+        let label_style = Style::default().fg(if selected { Color::Reset } else { theme.dim });
 
         match item {
             config::SettingItem::Toggle { label, value } => {
@@ -1674,7 +2971,7 @@ fn render_settings_popup(frame: &mut Frame, app: &App) {
                 let value_color = if editing {
                     Color::Yellow
                 } else if value.is_empty() {
-                    COLOR_INACTIVE
+                    theme.dim
                 } else {
                     Color::Green
                 };
@@ -1700,13 +2997,13 @@ fn render_settings_popup(frame: &mut Frame, app: &App) {
     };
     lines.push(Line::from(Span::styled(
         hint,
-        Style::default().fg(COLOR_INACTIVE),
+        Style::default().fg(theme.dim),
     )));
 
     let block = Block::default()
         .title(" Settings ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(Style::default().fg(theme.border));
 
     let paragraph = Paragraph::new(lines).block(block);
 
@@ -1714,7 +3011,7 @@ fn render_settings_popup(frame: &mut Frame, app: &App) {
     frame.render_widget(paragraph, area);
 }
 
-fn render_sidebar_popup(frame: &mut Frame, app: &App, panel: SidebarPanel) {
+fn render_sidebar_popup(frame: &mut Frame, theme: &Theme, app: &App, panel: SidebarPanel) {
     let area = centered_rect(70, 60, frame.area());
 
     let (title, lines) = match panel {
@@ -1723,14 +3020,29 @@ fn render_sidebar_popup(frame: &mut Frame, app: &App, panel: SidebarPanel) {
             if app.projects.is_empty() {
                 lines.push(Line::from(Span::styled(
                     "No active projects.",
-                    Style::default().fg(COLOR_INACTIVE),
+                    Style::default().fg(theme.dim),
                 )));
             } else {
                 for p in &app.projects {
+                    let mut title = format!("{}. {}", p.id, p.name);
+                    if let Some((done, total)) = p.progress() {
+                        title.push_str(&format!("  {}", progress_bar(done, total, 20)));
+                    }
                     lines.push(Line::from(Span::styled(
-                        format!("{}. {}", p.id, p.name),
+                        title,
                         Style::default().fg(Color::Reset),
                     )));
+                    for item in &p.items {
+                        let (mark, color) = if item.done {
+                            ("☑", theme.dim)
+                        } else {
+                            ("☐", Color::Reset)
+                        };
+                        lines.push(Line::from(Span::styled(
+                            format!("   {} {}", mark, item.text),
+                            Style::default().fg(color),
+                        )));
+                    }
                 }
             }
             (" Projects ", lines)
@@ -1744,18 +3056,15 @@ fn render_sidebar_popup(frame: &mut Frame, app: &App, panel: SidebarPanel) {
             if app.command_tree.is_empty() {
                 lines.push(Line::from(Span::styled(
                     "No agents yet.",
-                    Style::default().fg(COLOR_INACTIVE),
+                    Style::default().fg(theme.dim),
                 )));
             } else {
                 for node in &app.command_tree {
-                    let (health_color, icon) = match node.health {
-                        HealthState::Running => (Color::Green, "●"),
-                        HealthState::Idle => (Color::Yellow, "○"),
-                    };
+                    let (health_color, icon) = theme.health(node.health);
                     let is_focus = node.session_name == app.focus_parent;
 
                     let name_style = if is_focus {
-                        Style::default().fg(COLOR_ACTIVE)
+                        Style::default().fg(theme.selection)
                     } else {
                         Style::default().fg(Color::Reset)
                     };
@@ -1778,12 +3087,15 @@ fn render_sidebar_popup(frame: &mut Frame, app: &App, panel: SidebarPanel) {
 
                     let indicator = if is_focus { "► " } else { "  " };
 
-                    lines.push(Line::from(vec![
-                        Span::styled(indicator, Style::default().fg(COLOR_ACTIVE)),
-                        Span::styled(prefix, Style::default().fg(COLOR_INACTIVE)),
+                    let mut spans = vec![
+                        Span::styled(indicator, Style::default().fg(theme.selection)),
+                        Span::styled(prefix, Style::default().fg(theme.dim)),
                         Span::styled(format!("{} ", node.name), name_style),
-                        Span::styled(icon, Style::default().fg(health_color)),
-                    ]));
+                    ];
+                    spans.extend(role_badge_spans(theme, node));
+                    spans.push(Span::styled(icon, Style::default().fg(health_color)));
+                    spans.extend(team_health_spans(theme, node.team_health));
+                    lines.push(Line::from(spans));
                 }
             }
             (" Chain of Command ", lines)
@@ -1794,13 +3106,13 @@ fn render_sidebar_popup(frame: &mut Frame, app: &App, panel: SidebarPanel) {
     all_lines.push(Line::from(""));
     all_lines.push(Line::from(Span::styled(
         "Press Esc or Enter to close",
-        Style::default().fg(COLOR_INACTIVE),
+        Style::default().fg(theme.dim),
     )));
 
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(COLOR_ACTIVE))
+        .border_style(Style::default().fg(theme.selection))
         .padding(Padding::horizontal(1));
 
     let paragraph = Paragraph::new(all_lines).block(block);
@@ -0,0 +1,106 @@
+//! Syntax highlighting for the file viewer (syntect, bundled syntaxes)
+
+use std::path::Path;
+use std::sync::OnceLock;
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{FontStyle, Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+
+/// Lines past this are shown plain; highlighting is slow on huge files.
+const MAX_HIGHLIGHT_LINES: usize = 5000;
+
+fn syntaxes() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme() -> &'static Theme {
+    static THEME: OnceLock<Theme> = OnceLock::new();
+    THEME.get_or_init(|| {
+        let mut themes = ThemeSet::load_defaults().themes;
+        themes.remove("base16-ocean.dark").unwrap_or_default()
+    })
+}
+
+/// Highlight `text` using the syntax for `path`'s extension (or first
+/// line), falling back to plain lines.
+pub fn highlight(path: &str, text: &str) -> Vec<Line<'static>> {
+    let syntaxes = syntaxes();
+    let syntax = Path::new(path)
+        .extension()
+        .and_then(|ext| syntaxes.find_syntax_by_extension(&ext.to_string_lossy()))
+        .or_else(|| syntaxes.find_syntax_by_first_line(text.lines().next().unwrap_or("")));
+    let Some(syntax) = syntax else {
+        return plain(text);
+    };
+
+    let mut highlighter = HighlightLines::new(syntax, theme());
+    let mut lines = Vec::new();
+    for (idx, line) in text.split_inclusive('\n').enumerate() {
+        let regions = if idx < MAX_HIGHLIGHT_LINES {
+            highlighter.highlight_line(line, syntaxes).ok()
+        } else {
+            None
+        };
+        lines.push(match regions {
+            Some(regions) => Line::from(
+                regions
+                    .into_iter()
+                    .map(|(style, piece)| {
+                        Span::styled(
+                            piece.trim_end_matches(['\n', '\r']).to_string(),
+                            to_style(style),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            None => Line::from(line.trim_end_matches(['\n', '\r']).to_string()),
+        });
+    }
+    lines
+}
+
+fn plain(text: &str) -> Vec<Line<'static>> {
+    text.lines()
+        .map(|line| Line::from(line.to_string()))
+        .collect()
+}
+
+fn to_style(style: syntect::highlighting::Style) -> Style {
+    let fg = style.foreground;
+    let mut out = Style::default().fg(Color::Rgb(fg.r, fg.g, fg.b));
+    if style.font_style.contains(FontStyle::BOLD) {
+        out = out.add_modifier(Modifier::BOLD);
+    }
+    if style.font_style.contains(FontStyle::ITALIC) {
+        out = out.add_modifier(Modifier::ITALIC);
+    }
+    if style.font_style.contains(FontStyle::UNDERLINE) {
+        out = out.add_modifier(Modifier::UNDERLINED);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight_by_extension_and_plain_fallback() {
+        let lines = highlight("main.rs", "fn main() {\n    let x = 1;\n}\n");
+        assert_eq!(lines.len(), 3);
+        assert!(
+            lines[0].spans.len() > 1,
+            "rust source is split into styled spans"
+        );
+        let text: String = lines[1].spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, "    let x = 1;");
+
+        let lines = highlight("notes.unknownext", "a\nb");
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].spans.len(), 1);
+    }
+}
@@ -0,0 +1,475 @@
+//! Message catalog for dashboard text (help bar, help popup, dialogs,
+//! status messages)
+//!
+//! Messages are looked up by key where they are built: the English text,
+//! with `{}` placeholders for values, is the key, so untranslated keys
+//! fall back to English. Values are substituted after the lookup.
+
+use std::fmt::Display;
+
+use crate::config::Language;
+
+/// Translation of `key`, or `key` itself.
+pub fn tr(lang: Language, key: &'static str) -> &'static str {
+    catalog(lang)
+        .iter()
+        .find(|(en, _)| *en == key)
+        .map_or(key, |(_, translated)| translated)
+}
+
+/// Translation of the template `key`, with `args` filling its `{}`
+/// placeholders in order.
+pub fn trf(lang: Language, key: &'static str, args: &[&dyn Display]) -> String {
+    let mut out = String::new();
+    let mut args = args.iter();
+    let mut parts = tr(lang, key).split("{}").peekable();
+    while let Some(part) = parts.next() {
+        out.push_str(part);
+        if parts.peek().is_some() {
+            if let Some(arg) = args.next() {
+                out.push_str(&arg.to_string());
+            }
+        }
+    }
+    out
+}
+
+fn catalog(lang: Language) -> &'static [(&'static str, &'static str)] {
+    match lang {
+        Language::En => &[],
+        Language::Es => ES,
+    }
+}
+
+const ES: &[(&str, &str)] = &[
+    // Help bar
+    ("Chat", "Chat"),
+    ("Drill-in", "Entrar"),
+    ("New", "Nuevo"),
+    ("Kill", "Terminar"),
+    ("Hold the line", "Mantener"),
+    ("Quit", "Salir"),
+    ("Back", "Atrás"),
+    ("Settings", "Ajustes"),
+    ("Help", "Ayuda"),
+    // Help popup
+    ("Keyboard Shortcuts", "Atajos de teclado"),
+    (
+        "Quit: keep, kill idle, or kill all agents",
+        "Salir: mantener, terminar inactivos o todos los agentes",
+    ),
+    (
+        "Switch panel (sidebar ↔ main)",
+        "Cambiar de panel (lateral ↔ principal)",
+    ),
+    ("Move selection up/down", "Mover la selección arriba/abajo"),
+    (
+        "Jump to next/previous group",
+        "Saltar al grupo siguiente/anterior",
+    ),
+    (
+        "Drill into selected agent",
+        "Entrar en el agente seleccionado",
+    ),
+    ("Back (drill up)", "Atrás (subir un nivel)"),
+    (
+        "Attach to selected agent",
+        "Conectarse al agente seleccionado",
+    ),
+    ("Spawn agent (wizard)", "Crear agente (asistente)"),
+    ("Kill selected agent", "Terminar el agente seleccionado"),
+    (
+        "Kill selected agent and everyone under it",
+        "Terminar el agente seleccionado y todo su equipo",
+    ),
+    (
+        "Spawn new EA (prompts for name)",
+        "Crear un EA nuevo (pide el nombre)",
+    ),
+    (
+        "Delete current EA (not the only one)",
+        "Eliminar el EA actual (salvo el único)",
+    ),
+    ("Add a project", "Añadir un proyecto"),
+    (
+        "Send a message to selected agent",
+        "Enviar un mensaje al agente seleccionado",
+    ),
+    (
+        "Add or edit a note on selected agent",
+        "Añadir o editar una nota del agente seleccionado",
+    ),
+    (
+        "Search output of all agents",
+        "Buscar en la salida de todos los agentes",
+    ),
+    (
+        "Filter agents by name or tag",
+        "Filtrar agentes por nombre o etiqueta",
+    ),
+    (
+        "Run a command or shell in the agent's sandbox",
+        "Ejecutar un comando o una shell en el sandbox del agente",
+    ),
+    (
+        "Firehose: interleaved log tail of all agents",
+        "Firehose: salida intercalada de todos los agentes",
+    ),
+    (
+        "Cycle refresh priority: normal, hot, cold",
+        "Cambiar prioridad de refresco: normal, alta, baja",
+    ),
+    (
+        "Cycle the agent card through its extra panes",
+        "Recorrer los paneles extra en la tarjeta del agente",
+    ),
+    (
+        "Review pipeline stages awaiting approval",
+        "Revisar etapas de pipeline pendientes de aprobación",
+    ),
+    (
+        "Browse and view files in the agent's working directory",
+        "Explorar los archivos del directorio de trabajo del agente",
+    ),
+    ("Previous EA", "EA anterior"),
+    ("Next EA", "EA siguiente"),
+    ("Show scheduled events", "Ver eventos programados"),
+    (
+        "Spawn queue (x cancel, + bump)",
+        "Cola de creación (x cancelar, + adelantar)",
+    ),
+    (
+        "Cycle layout: standard, grid-only, ea-focus, tree-focus",
+        "Cambiar diseño: standard, grid-only, ea-focus, tree-focus",
+    ),
+    (
+        "Show/hide projects, EA panel, chain of command",
+        "Mostrar/ocultar proyectos, panel del EA, cadena de mando",
+    ),
+    (
+        "Needs attention: agents waiting on you, quick reply",
+        "Requiere atención: agentes que esperan tu respuesta, respuesta rápida",
+    ),
+    ("Debug console", "Consola de depuración"),
+    (
+        "Zoom the selected agent to fill the screen",
+        "Ampliar el agente seleccionado a pantalla completa",
+    ),
+    (
+        "Detach (dashboard keeps running)",
+        "Desconectarse (el panel sigue activo)",
+    ),
+    (
+        "Drain: finish running work, then stop and exit",
+        "Drenar: terminar el trabajo en curso, detener y salir",
+    ),
+    ("Refresh agent list", "Actualizar la lista de agentes"),
+    ("Toggle this help", "Mostrar/ocultar esta ayuda"),
+    (
+        "Press any key to close",
+        "Pulsa cualquier tecla para cerrar",
+    ),
+    // Confirm dialogs
+    ("Confirm", "Confirmar"),
+    ("Yes", "Sí"),
+    ("No", "No"),
+    ("Kill this agent?", "¿Terminar este agente?"),
+    ("Confirm Kill Group", "Confirmar terminar equipo"),
+    (
+        "Kill this agent and its team?",
+        "¿Terminar este agente y su equipo?",
+    ),
+    ("{} agent(s): {}", "{} agente(s): {}"),
+    (
+        "Their hierarchy and results are forgotten.",
+        "Se olvidan su jerarquía y sus resultados.",
+    ),
+    ("Shut Down", "Apagar"),
+    ("Quit omar?", "¿Salir de omar?"),
+    ("No workers are running.", "No hay trabajadores en marcha."),
+    ("{} worker(s) running:", "{} trabajador(es) en marcha:"),
+    ("  +{} more", "  +{} más"),
+    ("Keep everything running", "Mantener todo en marcha"),
+    (
+        "Kill idle and finished workers only",
+        "Terminar solo los trabajadores inactivos y terminados",
+    ),
+    (
+        "Kill all EAs and agents, reset state",
+        "Terminar todos los EA y agentes, reiniciar el estado",
+    ),
+    ("Cancel", "Cancelar"),
+    ("Confirm Delete EA", "Confirmar eliminar EA"),
+    ("Delete this EA?", "¿Eliminar este EA?"),
+    (
+        "This will kill all agents and remove all state.",
+        "Se terminarán todos los agentes y se borrará su estado.",
+    ),
+    ("Cancel Drain", "Cancelar drenado"),
+    ("Cancel the drain?", "¿Cancelar el drenado?"),
+    (
+        "{} worker(s) still running",
+        "{} trabajador(es) aún en marcha",
+    ),
+    (
+        "New agents will be started again.",
+        "Se volverán a crear agentes nuevos.",
+    ),
+    ("Drain", "Drenar"),
+    ("Drain and exit?", "¿Drenar y salir?"),
+    (
+        "No new agents start; running workers finish.",
+        "No se crean agentes nuevos; los activos terminan su trabajo.",
+    ),
+    (
+        "Then all agents and EAs stop and omar exits.",
+        "Después se detienen todos los agentes y EAs y omar sale.",
+    ),
+    ("Resume Previous Run", "Reanudar la ejecución anterior"),
+    (
+        "Resume unfinished agents?",
+        "¿Reanudar los agentes sin terminar?",
+    ),
+    (
+        "No forgets them; their tasks are not re-sent.",
+        "No los olvida; sus tareas no se reenvían.",
+    ),
+    // Status messages
+    ("Error: {}", "Error: {}"),
+    ("No agent selected", "Ningún agente seleccionado"),
+    ("Already at the top level", "Ya estás en el nivel superior"),
+    (
+        "Highlight sub-agents to drill into them",
+        "Selecciona un subagente para entrar en él",
+    ),
+    ("Viewing: {}", "Viendo: {}"),
+    (
+        "Cannot kill attached session",
+        "No se puede terminar una sesión conectada",
+    ),
+    (
+        "Cannot kill manager with 'd'",
+        "No se puede terminar el manager con 'd'",
+    ),
+    ("Killed agent: {}", "Agente terminado: {}"),
+    ("Layout: {}", "Diseño: {}"),
+    (
+        "Cannot kill the manager's group",
+        "No se puede terminar el equipo del manager",
+    ),
+    (
+        "Cannot kill group: {} is attached",
+        "No se puede terminar el equipo: {} está conectado",
+    ),
+    ("Killed {} agent(s)", "{} agente(s) terminado(s)"),
+    ("Spawned agent: {}", "Agente creado: {}"),
+    ("Sent message to {}", "Mensaje enviado a {}"),
+    ("Switched to {}", "Cambiado a {}"),
+    ("Error switching EA: {}", "Error al cambiar de EA: {}"),
+    ("Created EA {}: {}", "EA {} creado: {}"),
+    ("Error creating EA: {}", "Error al crear el EA: {}"),
+    (
+        "Cannot delete the only EA",
+        "No se puede eliminar el único EA",
+    ),
+    (
+        "Cannot delete attached session",
+        "No se puede eliminar una sesión conectada",
+    ),
+    ("Project added", "Proyecto añadido"),
+    ("Refreshed", "Actualizado"),
+    ("Restore failed: {}", "Error al restaurar: {}"),
+    (
+        "Cancelled queued spawn: {}",
+        "Creación en cola cancelada: {}",
+    ),
+    ("{} already left the queue", "{} ya salió de la cola"),
+    ("Bumped {} to position {}", "{} adelantado a la posición {}"),
+    (
+        "No pipeline stages awaiting approval",
+        "No hay etapas de pipeline pendientes de aprobación",
+    ),
+    (
+        "Resumed {} agent(s) from the previous run",
+        "{} agente(s) reanudado(s) de la ejecución anterior",
+    ),
+    (
+        "Could not determine the agent's working directory",
+        "No se pudo determinar el directorio de trabajo del agente",
+    ),
+    (
+        "File browser is not available for agents on {}",
+        "El explorador de archivos no está disponible para agentes en {}",
+    ),
+    (
+        "Slack & computer bridges started",
+        "Puentes de Slack y computer iniciados",
+    ),
+    ("Slack bridge started", "Puente de Slack iniciado"),
+    ("Computer bridge started", "Puente de computer iniciado"),
+    ("Error grouping {}: {}", "Error al agrupar {}: {}"),
+    (
+        "⚠ Sandbox of {} was modified: {}",
+        "⚠ El sandbox de {} fue modificado: {}",
+    ),
+    ("{} is now {} priority", "{} ahora tiene prioridad {}"),
+    (
+        "Pane cycling is only available for local agents",
+        "Recorrer paneles solo está disponible para agentes locales",
+    ),
+    ("{} has no extra panes", "{} no tiene paneles extra"),
+    ("Projects panel", "Panel de proyectos"),
+    ("EA panel", "Panel del EA"),
+    ("Chain of command", "Cadena de mando"),
+    ("{} shown", "{}: visible"),
+    ("{} hidden", "{}: oculto"),
+    ("Sandbox check passed", "Comprobación del sandbox superada"),
+    (
+        "Opened a shell in {}'s sandbox",
+        "Shell abierta en el sandbox de {}",
+    ),
+    ("Removed note on {}", "Nota de {} eliminada"),
+    ("Saved note on {}", "Nota de {} guardada"),
+    (
+        "Paste is disabled in demo mode",
+        "Pegar está desactivado en modo demo",
+    ),
+    ("Pasted {} chars into {}", "{} caracteres pegados en {}"),
+    ("Replied to {}", "Respuesta enviada a {}"),
+    (
+        "No agent selected to zoom",
+        "Ningún agente seleccionado para ampliar",
+    ),
+    (
+        "Project {} still has active agents: {}",
+        "El proyecto {} aún tiene agentes activos: {}",
+    ),
+    (
+        "Deleted EA {} ({} workers, {} events)",
+        "EA {} eliminado ({} trabajadores, {} eventos)",
+    ),
+    (
+        "Demo mode: simulated agents, actions are disabled",
+        "Modo demo: agentes simulados, acciones desactivadas",
+    ),
+    (
+        "Demo mode: actions on agents are disabled",
+        "Modo demo: las acciones sobre agentes están desactivadas",
+    ),
+    (
+        "{} exited; press Enter to restart it",
+        "{} terminó; pulsa Enter para reiniciarlo",
+    ),
+    ("Paste failed: {}", "Error al pegar: {}"),
+    ("Drain cancelled", "Drenado cancelado"),
+    (
+        "Draining: no new agents; exiting when workers finish",
+        "Drenando: sin agentes nuevos; se sale cuando terminen los trabajadores",
+    ),
+    // Needs-attention popup
+    (
+        "No agent is waiting on you",
+        "Ningún agente espera tu respuesta",
+    ),
+    (
+        "(blocked, no question shown)",
+        "(bloqueado, sin pregunta visible)",
+    ),
+    ("Reply", "Respuesta"),
+    (
+        "↑/↓ select · type a reply · Enter send · Esc close",
+        "↑/↓ elegir · escribe una respuesta · Enter enviar · Esc cerrar",
+    ),
+    ("Needs Attention ({})", "Requiere atención ({})"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Literal keys passed to `tr`, `trf` and the `set_status_tr*` helpers
+    fn keys_used_in(source: &str) -> Vec<String> {
+        let mut keys = Vec::new();
+        for call in ["tr(", "trf(", "set_status_tr(", "set_status_trf("] {
+            for (at, _) in source.match_indices(call) {
+                let before = source[..at].chars().last();
+                if before.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                    continue;
+                }
+                let mut rest = source[at + call.len()..].trim_start();
+                // Skip the language argument
+                if !rest.starts_with('"') {
+                    match rest.split_once(',') {
+                        Some((lang, after)) if !lang.contains(['(', '"', ')']) => {
+                            rest = after.trim_start();
+                        }
+                        _ => continue,
+                    }
+                }
+                if let Some(literal) = rest.strip_prefix('"') {
+                    if let Some(end) = literal.find('"') {
+                        keys.push(literal[..end].to_string());
+                    }
+                }
+            }
+        }
+        keys
+    }
+
+    #[test]
+    fn test_tr_and_trf_look_up_by_key() {
+        assert_eq!(tr(Language::Es, "Quit"), "Salir");
+        assert_eq!(tr(Language::En, "Quit"), "Quit");
+        assert_eq!(tr(Language::Es, "Not in the catalog"), "Not in the catalog");
+        assert_eq!(
+            trf(Language::Es, "Killed agent: {}", &[&"api"]),
+            "Agente terminado: api"
+        );
+        assert_eq!(
+            trf(Language::Es, "Bumped {} to position {}", &[&"api", &2]),
+            "api adelantado a la posición 2"
+        );
+        assert_eq!(
+            trf(Language::En, "Killed agent: {}", &[&"api"]),
+            "Killed agent: api"
+        );
+        // Values that look like other messages are not re-translated
+        assert_eq!(
+            trf(Language::Es, "Error: {}", &[&"No agent selected"]),
+            "Error: No agent selected"
+        );
+    }
+
+    #[test]
+    fn test_catalog_has_no_duplicate_ids_and_keeps_placeholders() {
+        for (i, (en, es)) in ES.iter().enumerate() {
+            assert!(
+                ES[..i].iter().all(|(other, _)| other != en),
+                "duplicate message id {:?}",
+                en
+            );
+            assert_eq!(en.matches("{}").count(), es.matches("{}").count(), "{}", en);
+        }
+        for (_, desc) in crate::ui::dashboard::HELP_KEYS {
+            assert_ne!(tr(Language::Es, desc), *desc, "untranslated help: {}", desc);
+        }
+    }
+
+    #[test]
+    fn test_every_key_used_in_the_dashboard_is_translated() {
+        let sources = [
+            include_str!("../app.rs"),
+            include_str!("../omar.rs"),
+            include_str!("dashboard.rs"),
+        ];
+        let keys: Vec<String> = sources.iter().flat_map(|s| keys_used_in(s)).collect();
+        assert!(keys.iter().any(|key| key == "No agent is waiting on you"));
+        for key in keys {
+            assert!(
+                ES.iter().any(|(en, _)| *en == key),
+                "untranslated message key {:?}",
+                key
+            );
+        }
+    }
+}
@@ -1,4 +1,7 @@
 mod dashboard;
+pub mod highlight;
+pub mod i18n;
+pub mod theme;
 
 pub use dashboard::render;
 pub use dashboard::QUOTE_COUNT;
@@ -0,0 +1,105 @@
+//! Dashboard color palette, overridable via `[theme]` in config.toml
+
+use std::str::FromStr;
+
+use ratatui::style::Color;
+
+use crate::config::ThemeConfig;
+use crate::tmux::HealthState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub running: Color,
+    pub idle: Color,
+    pub finished: Color,
+    /// Blocked/stuck agents
+    pub blocked: Color,
+    pub rate_limited: Color,
+    /// Focused panel borders, selected cards, and highlighted values
+    pub selection: Color,
+    /// Popup borders and field labels
+    pub border: Color,
+    /// Unfocused borders and secondary text
+    pub dim: Color,
+    /// Unfocused border of the EA (focus parent) panel
+    pub ea_panel: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            running: Color::Green,
+            idle: Color::Yellow,
+            finished: Color::Cyan,
+            blocked: Color::Red,
+            rate_limited: Color::Magenta,
+            selection: Color::LightMagenta,
+            border: Color::Cyan,
+            dim: Color::DarkGray,
+            ea_panel: Color::DarkGray,
+        }
+    }
+}
+
+impl Theme {
+    /// Apply `[theme]` overrides. Unrecognized colors keep the default.
+    pub fn from_config(config: &ThemeConfig) -> Self {
+        let default = Self::default();
+        Self {
+            running: parse_color(config.running.as_deref(), default.running),
+            idle: parse_color(config.idle.as_deref(), default.idle),
+            finished: parse_color(config.finished.as_deref(), default.finished),
+            blocked: parse_color(config.blocked.as_deref(), default.blocked),
+            rate_limited: parse_color(config.rate_limited.as_deref(), default.rate_limited),
+            selection: parse_color(config.selection.as_deref(), default.selection),
+            border: parse_color(config.border.as_deref(), default.border),
+            dim: parse_color(config.dim.as_deref(), default.dim),
+            ea_panel: parse_color(config.ea_panel.as_deref(), default.ea_panel),
+        }
+    }
+
+    /// Color and icon for a health state.
+    pub fn health(&self, health: HealthState) -> (Color, &'static str) {
+        match health {
+            HealthState::Running => (self.running, "●"),
+            HealthState::Idle => (self.idle, "○"),
+            HealthState::Finished => (self.finished, "✓"),
+            HealthState::Blocked => (self.blocked, "!"),
+            HealthState::RateLimited => (self.rate_limited, "⏸"),
+        }
+    }
+}
+
+/// Color names (`"blue"`, `"lightred"`), hex (`"#005f87"`), or 256-color
+/// indices (`"24"`).
+fn parse_color(value: Option<&str>, fallback: Color) -> Color {
+    value
+        .and_then(|value| Color::from_str(value.trim()).ok())
+        .unwrap_or(fallback)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_overrides_and_fallbacks() {
+        let config = ThemeConfig {
+            running: Some("blue".to_string()),
+            selection: Some("#005f87".to_string()),
+            dim: Some("244".to_string()),
+            idle: Some("not-a-color".to_string()),
+            ..ThemeConfig::default()
+        };
+        let theme = Theme::from_config(&config);
+        assert_eq!(theme.running, Color::Blue);
+        assert_eq!(theme.selection, Color::Rgb(0x00, 0x5f, 0x87));
+        assert_eq!(theme.dim, Color::Indexed(244));
+        assert_eq!(theme.idle, Theme::default().idle);
+        assert_eq!(theme.health(HealthState::Running), (Color::Blue, "●"));
+        assert_eq!(
+            Theme::from_config(&ThemeConfig::default()),
+            Theme::default()
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! Dashboard panel layout kept across restarts in `~/.omar/ui_state.json`
+//!
+//! Starts from `[dashboard] layout_preset` and the `show_*` panel flags;
+//! once the user picks a preset or toggles a panel the file wins.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{DashboardConfig, LayoutPreset};
+
+/// A panel that can be hidden.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutPanel {
+    Projects,
+    EaPanel,
+    CommandTree,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UiState {
+    #[serde(default)]
+    pub preset: LayoutPreset,
+    #[serde(default = "default_true")]
+    pub show_projects: bool,
+    #[serde(default = "default_true")]
+    pub show_ea_panel: bool,
+    #[serde(default = "default_true")]
+    pub show_command_tree: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl UiState {
+    pub fn from_config(dashboard: &DashboardConfig) -> Self {
+        let mut state = Self::for_preset(dashboard.layout_preset);
+        state.show_projects &= dashboard.show_projects;
+        state.show_ea_panel &= dashboard.show_ea_panel;
+        state.show_command_tree &= dashboard.show_command_tree;
+        state
+    }
+
+    /// `preset` with its panels: none for grid-only, all otherwise.
+    pub fn for_preset(preset: LayoutPreset) -> Self {
+        let shown = preset != LayoutPreset::GridOnly;
+        Self {
+            preset,
+            show_projects: shown,
+            show_ea_panel: shown,
+            show_command_tree: shown,
+        }
+    }
+
+    /// Flip `panel`; returns whether it is now shown.
+    pub fn toggle(&mut self, panel: LayoutPanel) -> bool {
+        let shown = match panel {
+            LayoutPanel::Projects => &mut self.show_projects,
+            LayoutPanel::EaPanel => &mut self.show_ea_panel,
+            LayoutPanel::CommandTree => &mut self.show_command_tree,
+        };
+        *shown = !*shown;
+        *shown
+    }
+
+    /// The sidebar (with the event queue) is drawn while either of its
+    /// main panels is.
+    pub fn shows_sidebar(&self) -> bool {
+        self.show_projects || self.show_command_tree
+    }
+}
+
+fn ui_state_path(omar_dir: &Path) -> PathBuf {
+    omar_dir.join("ui_state.json")
+}
+
+pub fn load(omar_dir: &Path) -> Option<UiState> {
+    fs::read_to_string(ui_state_path(omar_dir))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+}
+
+pub fn save(omar_dir: &Path, state: &UiState) -> Result<()> {
+    fs::create_dir_all(omar_dir)?;
+    fs::write(
+        ui_state_path(omar_dir),
+        serde_json::to_string_pretty(state)?,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ui_state_starts_from_config_and_round_trips() {
+        let dashboard = DashboardConfig {
+            layout_preset: LayoutPreset::EaFocus,
+            show_projects: false,
+            ..DashboardConfig::default()
+        };
+        let mut state = UiState::from_config(&dashboard);
+        assert_eq!(state.preset, LayoutPreset::EaFocus);
+        assert!(!state.show_projects && state.show_command_tree && state.shows_sidebar());
+
+        assert!(!state.toggle(LayoutPanel::CommandTree));
+        assert!(!state.shows_sidebar());
+        assert!(!UiState::for_preset(LayoutPreset::GridOnly).show_ea_panel);
+
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(load(dir.path()), None);
+        save(dir.path(), &state).unwrap();
+        assert_eq!(load(dir.path()), Some(state));
+    }
+}
@@ -0,0 +1,115 @@
+//! Field-level request validation — every invalid field reported at once,
+//! so a caller (usually the EA) can correct its request instead of retrying
+//! blindly
+//!
+//! Tools fail with a `ValidationError` through `anyhow`. The MCP layer puts
+//! its `errors` array in the error result's `structuredContent`, and
+//! `omar web` answers 422 with the same array.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldError {
+    /// Request field, e.g. "workdir"; "arguments" when the request as a
+    /// whole could not be parsed
+    pub field: String,
+    /// missing, invalid, not_found, conflict, or exists
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+#[error("{}", self.summary())]
+pub struct ValidationError {
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationError {
+    pub fn push(&mut self, field: &str, code: &str, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: field.to_string(),
+            code: code.to_string(),
+            message: message.into(),
+        });
+    }
+
+    /// `Err(self)` when any field was invalid.
+    pub fn check(self) -> anyhow::Result<()> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.into())
+        }
+    }
+
+    /// Arguments that did not deserialize. serde stops at the first problem,
+    /// so this is a single error, attributed to a field when serde names one.
+    pub fn from_serde(err: serde_json::Error) -> Self {
+        let message = err.to_string();
+        let missing = message
+            .strip_prefix("missing field `")
+            .and_then(|rest| rest.split_once('`'))
+            .map(|(field, _)| field.to_string());
+        let mut invalid = Self::default();
+        match missing {
+            Some(field) => invalid.push(&field, "missing", message),
+            None => invalid.push("arguments", "invalid", message),
+        }
+        invalid
+    }
+
+    fn summary(&self) -> String {
+        match self.errors.as_slice() {
+            [only] => only.message.clone(),
+            errors => {
+                let lines: Vec<String> = errors
+                    .iter()
+                    .map(|e| format!("- {}: {}", e.field, e.message))
+                    .collect();
+                format!("Invalid request:\n{}", lines.join("\n"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Args {
+        name: String,
+        project_id: usize,
+    }
+
+    #[test]
+    fn test_collects_fields_and_attributes_serde_errors() {
+        let mut invalid = ValidationError::default();
+        assert!(invalid.clone().check().is_ok());
+        invalid.push("workdir", "not_found", "No directory /nope");
+        assert_eq!(invalid.to_string(), "No directory /nope");
+        invalid.push("backend", "invalid", "Unknown backend 'gpt'");
+        let err = invalid.clone().check().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid request:\n- workdir: No directory /nope\n- backend: Unknown backend 'gpt'"
+        );
+        assert_eq!(err.downcast_ref::<ValidationError>(), Some(&invalid));
+
+        let missing = serde_json::from_value::<Args>(serde_json::json!({ "name": "api" }))
+            .map_err(ValidationError::from_serde)
+            .unwrap_err();
+        assert_eq!(
+            (
+                missing.errors[0].field.as_str(),
+                missing.errors[0].code.as_str()
+            ),
+            ("project_id", "missing")
+        );
+        let mistyped = serde_json::from_value::<Args>(serde_json::json!({ "name": 1 }))
+            .map_err(ValidationError::from_serde)
+            .unwrap_err();
+        assert_eq!(mistyped.errors[0].field, "arguments");
+    }
+}
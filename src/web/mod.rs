@@ -0,0 +1,944 @@
+//! `omar web` — a web dashboard over the agent API
+//!
+//! Serves a single page at `/` plus JSON endpoints that answer through the
+//! same tool calls as the MCP server, so the browser sees exactly what the
+//! EA sees. `/api/events` streams the agent list as server-sent events.
+//! `/api/openapi.json` describes the API, and `/api/docs` browses it.
+//! Over TCP, every `/api` request needs the token printed at startup,
+//! passed as `?token=` or an `Authorization: Bearer` header. `api.socket`
+//! serves the same router on a Unix socket readable only by its owner,
+//! where file permissions replace the token.
+
+use std::convert::Infallible;
+use std::path::Path as FsPath;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Path, Query, Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures_util::stream::{self, Stream};
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use hyper_util::service::TowerToHyperService;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::heartbeat::FailIf;
+use crate::mcp::{ToolCaller, SEND_FILE_MAX_BYTES};
+use crate::validation::ValidationError;
+
+mod openapi;
+
+const INDEX_HTML: &str = include_str!("index.html");
+const DOCS_HTML: &str = include_str!("docs.html");
+
+/// How often `/api/events` pushes a fresh agent list.
+const EVENT_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Clone)]
+pub struct WebState {
+    pub caller: Arc<ToolCaller>,
+    /// Required on `/api` requests; `None` on the Unix socket
+    pub token: Option<String>,
+}
+
+pub fn build_router(state: WebState) -> Router {
+    let api = Router::new()
+        .route("/agents", get(handle_list_agents).post(handle_spawn_agent))
+        .route(
+            "/agents/:id",
+            get(handle_get_agent).patch(handle_update_agent),
+        )
+        .route("/agents/:id/input", post(handle_send_input))
+        .route(
+            "/agents/:id/send-file",
+            // Room for the largest file send_file accepts, JSON-escaped
+            post(handle_send_file).layer(DefaultBodyLimit::max(2 * SEND_FILE_MAX_BYTES as usize)),
+        )
+        .route("/agents/:id/kill", post(handle_kill_agent))
+        .route("/agents/:id/rollback", post(handle_rollback_agent))
+        .route("/agents/:id/priority", post(handle_set_priority))
+        .route("/agents/:id/report", post(handle_report_progress))
+        .route(
+            "/agents/:id/messages",
+            get(handle_get_messages).post(handle_send_message),
+        )
+        .route("/health/agents/:id", get(handle_agent_heartbeat))
+        .route("/projects", get(handle_list_projects))
+        .route("/projects/:id/items", post(handle_add_project_item))
+        .route(
+            "/projects/:id/items/:item/toggle",
+            post(handle_toggle_project_item),
+        )
+        .route("/topology", get(handle_topology))
+        .route("/runs", get(handle_list_runs))
+        .route("/manager/refresh-context", post(handle_refresh_context))
+        .route(
+            "/config/health-patterns",
+            get(handle_get_health_patterns).put(handle_set_health_patterns),
+        )
+        .route("/events", get(handle_events))
+        .route("/openapi.json", get(|| async { Json(openapi::document()) }))
+        .route("/docs", get(|| async { Html(DOCS_HTML) }))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token));
+    Router::new()
+        .route("/", get(|| async { Html(INDEX_HTML) }))
+        .nest("/api", api)
+        .with_state(state)
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+async fn require_token(
+    State(state): State<WebState>,
+    Query(query): Query<TokenQuery>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(token) = state.token.as_deref() else {
+        return next.run(request).await;
+    };
+    let bearer = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    if query.token.as_deref().or(bearer) != Some(token) {
+        return error_response(StatusCode::UNAUTHORIZED, "missing or invalid token");
+    }
+    next.run(request).await
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "error": message }))).into_response()
+}
+
+/// Serve `router` on a Unix socket at `path` with mode 0600, replacing a
+/// stale socket left by an earlier run. A missing parent directory is
+/// created 0700.
+#[cfg(unix)]
+pub async fn serve_unix(path: &FsPath, router: Router) -> Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+
+    if std::fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_socket()) {
+        std::fs::remove_file(path)?;
+    }
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => FsPath::new("."),
+    };
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(parent)?;
+    // Bind in a private directory and move the socket into place once it
+    // is 0600, so it is never reachable with the default permissions
+    let staging = parent.join(format!(".omar-api-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&staging);
+    std::fs::DirBuilder::new().mode(0o700).create(&staging)?;
+    let staged = staging.join("api.sock");
+    let bound = tokio::net::UnixListener::bind(&staged).and_then(|listener| {
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))?;
+        std::fs::rename(&staged, path)?;
+        Ok(listener)
+    });
+    let _ = std::fs::remove_dir_all(&staging);
+    let listener = bound.with_context(|| format!("Failed to listen on {}", path.display()))?;
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let service = TowerToHyperService::new(router.clone());
+        tokio::spawn(async move {
+            let _ = auto::Builder::new(TokioExecutor::new())
+                .serve_connection(TokioIo::new(stream), service)
+                .await;
+        });
+    }
+}
+
+#[cfg(not(unix))]
+pub async fn serve_unix(path: &FsPath, _router: Router) -> Result<()> {
+    anyhow::bail!(
+        "Cannot serve the API on {}: Unix sockets need a Unix host",
+        path.display()
+    )
+}
+
+/// Run a tool off the async runtime; tool errors become 400s, and invalid
+/// fields 422s listing them in `errors`.
+async fn run_tool(
+    state: &WebState,
+    tool: &'static str,
+    args: Value,
+) -> std::result::Result<Value, Response> {
+    let caller = state.caller.clone();
+    match tokio::task::spawn_blocking(move || caller.call(tool, args)).await {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(e)) => match e.downcast_ref::<ValidationError>() {
+            Some(invalid) => Err((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({ "error": e.to_string(), "errors": invalid.errors })),
+            )
+                .into_response()),
+            None => Err(error_response(StatusCode::BAD_REQUEST, &e.to_string())),
+        },
+        Err(e) => Err(error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            &e.to_string(),
+        )),
+    }
+}
+
+async fn call(state: &WebState, tool: &'static str, args: Value) -> Response {
+    match run_tool(state, tool, args).await {
+        Ok(value) => Json(value).into_response(),
+        Err(response) => response,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ListQuery {
+    tag: Option<String>,
+}
+
+async fn handle_list_agents(
+    State(state): State<WebState>,
+    Query(query): Query<ListQuery>,
+) -> Response {
+    call(&state, "list_agents", json!({ "tag": query.tag })).await
+}
+
+/// The body is `spawn_agent`'s arguments. A spawn held back by
+/// `agent.max_concurrent` or a `wait_for` dependency is a 202 with its
+/// queue position.
+async fn handle_spawn_agent(State(state): State<WebState>, Json(body): Json<Value>) -> Response {
+    match run_tool(&state, "spawn_agent", body).await {
+        Ok(result) if matches!(result["status"].as_str(), Some("queued" | "waiting")) => {
+            (StatusCode::ACCEPTED, Json(result)).into_response()
+        }
+        Ok(result) => Json(result).into_response(),
+        Err(response) => response,
+    }
+}
+
+async fn handle_get_agent(State(state): State<WebState>, Path(id): Path<String>) -> Response {
+    call(&state, "get_agent", json!({ "name": id })).await
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateAgentRequest {
+    name: Option<String>,
+    note: Option<String>,
+}
+
+/// Renames and sets the note (empty removes it) together, so a rejected
+/// rename changes nothing. Without a rename the updated agent is returned.
+async fn handle_update_agent(
+    State(state): State<WebState>,
+    Path(id): Path<String>,
+    Json(body): Json<UpdateAgentRequest>,
+) -> Response {
+    match (body.name, body.note) {
+        (Some(name), note) => {
+            call(
+                &state,
+                "rename_agent",
+                json!({ "name": id, "new_name": name, "note": note }),
+            )
+            .await
+        }
+        (None, Some(note)) => {
+            match run_tool(
+                &state,
+                "set_agent_note",
+                json!({ "name": id, "note": note }),
+            )
+            .await
+            {
+                Ok(_) => call(&state, "get_agent", json!({ "name": id })).await,
+                Err(response) => response,
+            }
+        }
+        (None, None) => call(&state, "get_agent", json!({ "name": id })).await,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct InputRequest {
+    text: String,
+    #[serde(default)]
+    enter: bool,
+}
+
+async fn handle_send_input(
+    State(state): State<WebState>,
+    Path(id): Path<String>,
+    Json(body): Json<InputRequest>,
+) -> Response {
+    call(
+        &state,
+        "send_input",
+        json!({ "name": id, "text": body.text, "enter": body.enter }),
+    )
+    .await
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SendFileQuery {
+    filename: Option<String>,
+    note: Option<String>,
+    from: Option<String>,
+}
+
+/// A JSON body `{content | path, filename, note, from}`, or any other body
+/// taken as the file's text with `filename`/`note`/`from` in the query.
+async fn handle_send_file(
+    State(state): State<WebState>,
+    Path(id): Path<String>,
+    Query(query): Query<SendFileQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let is_json = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    let mut args = if is_json {
+        match serde_json::from_slice::<Value>(&body) {
+            Ok(args @ Value::Object(_)) => args,
+            _ => return error_response(StatusCode::BAD_REQUEST, "body must be a JSON object"),
+        }
+    } else {
+        match String::from_utf8(body.to_vec()) {
+            Ok(content) => json!({
+                "content": content,
+                "filename": query.filename,
+                "note": query.note,
+                "from": query.from,
+            }),
+            Err(_) => return error_response(StatusCode::BAD_REQUEST, "file must be UTF-8 text"),
+        }
+    };
+    args["name"] = json!(id);
+    call(&state, "send_file", args).await
+}
+
+async fn handle_kill_agent(State(state): State<WebState>, Path(id): Path<String>) -> Response {
+    call(&state, "kill_agent", json!({ "name": id })).await
+}
+
+async fn handle_rollback_agent(State(state): State<WebState>, Path(id): Path<String>) -> Response {
+    call(&state, "rollback_agent", json!({ "name": id })).await
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct HeartbeatQuery {
+    fail_if: Option<String>,
+}
+
+/// The agent's heartbeat, as `omar health` reports it. A 503 when one of
+/// the comma-separated `fail_if` conditions holds, so an uptime monitor
+/// can poll it.
+async fn handle_agent_heartbeat(
+    State(state): State<WebState>,
+    Path(id): Path<String>,
+    Query(query): Query<HeartbeatQuery>,
+) -> Response {
+    let fail_if = query.fail_if.unwrap_or_default();
+    if let Err(e) = FailIf::parse_list(&fail_if) {
+        return error_response(StatusCode::BAD_REQUEST, &e.to_string());
+    }
+    let args = json!({ "name": id, "fail_if": fail_if });
+    match run_tool(&state, "get_agent_heartbeat", args).await {
+        Ok(report) if report["ok"] == json!(false) => {
+            (StatusCode::SERVICE_UNAVAILABLE, Json(report)).into_response()
+        }
+        Ok(report) => Json(report).into_response(),
+        Err(response) => response,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PriorityRequest {
+    priority: String,
+}
+
+async fn handle_set_priority(
+    State(state): State<WebState>,
+    Path(id): Path<String>,
+    Json(body): Json<PriorityRequest>,
+) -> Response {
+    call(
+        &state,
+        "set_agent_priority",
+        json!({ "name": id, "priority": body.priority }),
+    )
+    .await
+}
+
+/// The body is `report_progress`' arguments without `name`.
+async fn handle_report_progress(
+    State(state): State<WebState>,
+    Path(id): Path<String>,
+    Json(body): Json<Value>,
+) -> Response {
+    let mut args = match body {
+        args @ Value::Object(_) => args,
+        _ => return error_response(StatusCode::BAD_REQUEST, "body must be a JSON object"),
+    };
+    args["name"] = json!(id);
+    call(&state, "report_progress", args).await
+}
+
+async fn handle_get_health_patterns(State(state): State<WebState>) -> Response {
+    call(&state, "get_health_patterns", json!({})).await
+}
+
+/// The body is `set_health_patterns`' arguments.
+async fn handle_set_health_patterns(
+    State(state): State<WebState>,
+    Json(body): Json<Value>,
+) -> Response {
+    call(&state, "set_health_patterns", body).await
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageRequest {
+    text: String,
+    from: Option<String>,
+}
+
+async fn handle_send_message(
+    State(state): State<WebState>,
+    Path(id): Path<String>,
+    Json(body): Json<MessageRequest>,
+) -> Response {
+    call(
+        &state,
+        "send_message",
+        json!({ "name": id, "text": body.text, "from": body.from }),
+    )
+    .await
+}
+
+async fn handle_get_messages(State(state): State<WebState>, Path(id): Path<String>) -> Response {
+    call(&state, "get_messages", json!({ "name": id })).await
+}
+
+async fn handle_list_projects(State(state): State<WebState>) -> Response {
+    call(&state, "list_projects", json!({})).await
+}
+
+#[derive(Debug, Deserialize)]
+struct ProjectItemRequest {
+    text: String,
+}
+
+async fn handle_add_project_item(
+    State(state): State<WebState>,
+    Path(id): Path<usize>,
+    Json(body): Json<ProjectItemRequest>,
+) -> Response {
+    call(
+        &state,
+        "add_project_item",
+        json!({ "project_id": id, "text": body.text }),
+    )
+    .await
+}
+
+/// Flips the item; there is no body.
+async fn handle_toggle_project_item(
+    State(state): State<WebState>,
+    Path((id, item)): Path<(usize, usize)>,
+) -> Response {
+    call(
+        &state,
+        "toggle_project_item",
+        json!({ "project_id": id, "item": item }),
+    )
+    .await
+}
+
+/// Queues the snapshot; the dashboard types it in once the EA is idle.
+async fn handle_refresh_context(State(state): State<WebState>) -> Response {
+    call(&state, "refresh_manager_context", json!({})).await
+}
+
+async fn handle_list_runs(State(state): State<WebState>) -> Response {
+    call(&state, "list_runs", json!({})).await
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TopologyQuery {
+    format: Option<String>,
+}
+
+/// JSON nodes and edges, or the bare DOT/Mermaid source for `format=dot`
+/// and `format=mermaid` so it can be piped straight into a renderer.
+async fn handle_topology(
+    State(state): State<WebState>,
+    Query(query): Query<TopologyQuery>,
+) -> Response {
+    let args = json!({ "format": query.format.unwrap_or_default() });
+    match run_tool(&state, "get_topology", args).await {
+        Ok(result) => match result["graph"].as_str() {
+            Some(graph) => graph.to_string().into_response(),
+            None => Json(result).into_response(),
+        },
+        Err(response) => response,
+    }
+}
+
+/// The agent list now and every `EVENT_INTERVAL` after.
+async fn handle_events(
+    State(state): State<WebState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let events = stream::unfold((state, true), |(state, first)| async move {
+        if !first {
+            tokio::time::sleep(EVENT_INTERVAL).await;
+        }
+        let caller = state.caller.clone();
+        let agents = tokio::task::spawn_blocking(move || caller.call("list_agents", json!({})))
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|result| result);
+        let event = match agents {
+            Ok(value) => Event::default().event("agents").data(value.to_string()),
+            Err(e) => Event::default()
+                .event("error")
+                .data(json!({ "error": e.to_string() }).to_string()),
+        };
+        Some((Ok(event), (state, false)))
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    use crate::manager::McpLaunchContext;
+
+    fn test_state(omar_dir: &std::path::Path) -> WebState {
+        WebState {
+            caller: Arc::new(ToolCaller::new(McpLaunchContext {
+                omar_dir: omar_dir.to_path_buf(),
+                ea_id: 0,
+                session_prefix: "omar-web-test-".to_string(),
+                default_command: "sh".to_string(),
+                default_workdir: ".".to_string(),
+                health_idle_warning: 15,
+                tmux_server: None,
+                sandbox: Default::default(),
+                limits: Default::default(),
+                prompts: Default::default(),
+            })),
+            token: Some("secret".to_string()),
+        }
+    }
+
+    async fn get_status(app: Router, uri: &str, auth: Option<&str>) -> StatusCode {
+        let mut request = Request::builder().uri(uri);
+        if let Some(auth) = auth {
+            request = request.header(header::AUTHORIZATION, auth);
+        }
+        app.oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_index_is_public_and_api_needs_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = build_router(test_state(dir.path()));
+
+        let resp = app
+            .clone()
+            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("/api/events"));
+
+        let unauthorized = StatusCode::UNAUTHORIZED;
+        assert_eq!(
+            get_status(app.clone(), "/api/agents", None).await,
+            unauthorized
+        );
+        assert_eq!(
+            get_status(app.clone(), "/api/agents?token=wrong", None).await,
+            unauthorized
+        );
+        assert_eq!(
+            get_status(app.clone(), "/api/events", Some("Bearer wrong")).await,
+            unauthorized
+        );
+        assert_eq!(
+            get_status(app.clone(), "/api/agents/nope?token=secret", None).await,
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            get_status(app.clone(), "/api/topology?format=svg&token=secret", None).await,
+            StatusCode::BAD_REQUEST
+        );
+    }
+
+    #[tokio::test]
+    async fn test_openapi_document_covers_every_route() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = build_router(test_state(dir.path()));
+        assert_eq!(
+            get_status(app.clone(), "/api/openapi.json", None).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            get_status(app.clone(), "/api/docs?token=secret", None).await,
+            StatusCode::OK
+        );
+
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/openapi.json?token=secret")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let doc: Value = serde_json::from_slice(&body).unwrap();
+
+        // Bodies come from the tool schemas `omar mcp-schema` prints
+        let tools = crate::mcp::tool_schema_json();
+        let spawn = tools["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|tool| tool["name"] == "spawn_agent")
+            .unwrap();
+        assert_eq!(
+            doc["paths"]["/api/agents"]["post"]["requestBody"]["content"]["application/json"]
+                ["schema"],
+            spawn["inputSchema"]
+        );
+
+        let mut ids = std::collections::HashSet::new();
+        for (path, operations) in doc["paths"].as_object().unwrap() {
+            for (method, operation) in operations.as_object().unwrap() {
+                assert!(ids.insert(operation["operationId"].to_string()));
+                assert!(
+                    !operation.to_string().contains(r#""schema":null"#),
+                    "{} {} refers to a missing tool argument",
+                    method,
+                    path
+                );
+                // The router answers every documented operation
+                let uri = format!(
+                    "{}?token=secret",
+                    path.replace("{id}", "1").replace("{item}", "0")
+                );
+                let status = app
+                    .clone()
+                    .oneshot(
+                        Request::builder()
+                            .method(method.to_uppercase().as_str())
+                            .uri(uri)
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap()
+                    .status();
+                assert_ne!(status, StatusCode::NOT_FOUND, "{} {}", method, path);
+                assert_ne!(
+                    status,
+                    StatusCode::METHOD_NOT_ALLOWED,
+                    "{} {}",
+                    method,
+                    path
+                );
+            }
+        }
+        assert_eq!(ids.len(), 22);
+    }
+
+    #[tokio::test]
+    async fn test_agent_heartbeat_is_503_when_a_condition_holds() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = build_router(test_state(dir.path()));
+        let uri = |query: &str| format!("/api/health/agents/gone?token=secret{}", query);
+
+        assert_eq!(
+            get_status(app.clone(), &uri(""), None).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            get_status(app.clone(), &uri("&fail_if=stuck"), None).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            get_status(app.clone(), &uri("&fail_if=stuck,missing"), None).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            get_status(app.clone(), &uri("&fail_if=slow"), None).await,
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            get_status(app, "/api/health/agents/gone", None).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_spawn_is_422_with_field_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = build_router(test_state(dir.path()));
+        let body = json!({
+            "name": "bad name",
+            "project_id": 99,
+            "backend": "gpt",
+            "workdir": dir.path().join("missing").display().to_string(),
+        });
+        let resp = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/agents?token=secret")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        let fields: Vec<(&str, &str)> = body["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| (e["field"].as_str().unwrap(), e["code"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("name", "invalid"),
+                ("project_id", "not_found"),
+                ("task", "missing"),
+                ("backend", "invalid"),
+                ("workdir", "not_found"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_spawn_held_on_a_dependency_is_202() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_dir = crate::ea::ea_state_dir(0, dir.path());
+        std::fs::create_dir_all(&state_dir).unwrap();
+        let project_id = crate::projects::add_project_in(&state_dir, "API").unwrap();
+        crate::spawn_queue::enqueue_in(&state_dir, "schema", json!({ "name": "schema" })).unwrap();
+        let app = build_router(test_state(dir.path()));
+        let body = json!({
+            "name": "api",
+            "project_id": project_id,
+            "task": "build the API",
+            "workdir": dir.path().display().to_string(),
+            "wait_for": { "agent": "schema", "state": "exited" },
+        });
+        let resp = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/agents?token=secret")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::ACCEPTED);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["status"], "waiting");
+        assert_eq!(body["queue_position"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_send_file_validates_json_and_raw_bodies() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = build_router(test_state(dir.path()));
+        let send = |uri: &str, content_type: &str, body: String| {
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header(header::CONTENT_TYPE, content_type)
+                .body(Body::from(body))
+                .unwrap()
+        };
+        let errors = |body: Bytes| -> Vec<(String, String)> {
+            let body: Value = serde_json::from_slice(&body).unwrap();
+            body["errors"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|e| (e["field"].to_string(), e["code"].to_string()))
+                .collect()
+        };
+
+        let resp = app
+            .clone()
+            .oneshot(send(
+                "/api/agents/w1/send-file?token=secret",
+                "application/json",
+                json!({ "note": "see spec" }).to_string(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(
+            errors(body),
+            vec![("\"content\"".to_string(), "\"missing\"".to_string())]
+        );
+
+        let resp = app
+            .oneshot(send(
+                "/api/agents/w1/send-file?token=secret&filename=../spec.md",
+                "text/markdown",
+                "# Spec".to_string(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(
+            errors(body),
+            vec![("\"filename\"".to_string(), "\"invalid\"".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_health_patterns_round_trip_through_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = build_router(test_state(dir.path()));
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "# mine\n[dashboard]\nshow_quotes = false\n").unwrap();
+        let put = |body: Value| {
+            Request::builder()
+                .method("PUT")
+                .uri("/api/config/health-patterns?token=secret")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let resp = app
+            .clone()
+            .oneshot(put(json!({ "ready": { "codex": " " } })))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let resp = app
+            .clone()
+            .oneshot(put(json!({
+                "rate_limit": ["quota exhausted"],
+                "ready": { "codex": "^› " },
+            })))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let saved = crate::config::Config::load(Some(&path.to_string_lossy())).unwrap();
+        assert_eq!(saved.health.rate_limit.patterns, vec!["quota exhausted"]);
+        assert_eq!(saved.agent.readiness.patterns["codex"], "^› ");
+        // Only the changed keys are written
+        assert!(!saved.dashboard.show_quotes);
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .starts_with("# mine\n[dashboard]\nshow_quotes = false\n"));
+
+        let resp = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/config/health-patterns?token=secret")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["rate_limit"], json!(["quota exhausted"]));
+        assert_eq!(body["ready"]["codex"], "^› ");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_socket_serves_without_token() {
+        use std::os::unix::fs::PermissionsExt;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("run").join("api.sock");
+        let state = WebState {
+            token: None,
+            ..test_state(dir.path())
+        };
+        let server = tokio::spawn({
+            let socket = socket.clone();
+            async move { serve_unix(&socket, build_router(state)).await }
+        });
+        while !socket.exists() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let mode = std::fs::metadata(&socket).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let parent = std::fs::metadata(socket.parent().unwrap()).unwrap();
+        assert_eq!(parent.permissions().mode() & 0o777, 0o700);
+
+        let mut stream = tokio::net::UnixStream::connect(&socket).await.unwrap();
+        stream
+            .write_all(b"GET /api/agents/nope HTTP/1.1\r\nHost: omar\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 400"), "{}", response);
+        server.abort();
+    }
+}
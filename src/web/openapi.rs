@@ -0,0 +1,415 @@
+//! OpenAPI 3 document for the `/api` routes
+//!
+//! Each route answers through an MCP tool, so its description and field
+//! schemas are taken from the same tool definitions `omar mcp-schema`
+//! prints instead of being written out twice.
+
+use serde_json::{json, Map, Value};
+
+use crate::mcp;
+
+/// Where a route's request fields come from.
+enum Body {
+    None,
+    /// The tool's arguments, minus those taken from the path
+    Tool,
+    /// `(field, tool, tool argument)` for each body field
+    Fields(&'static [(&'static str, &'static str, &'static str)]),
+    /// Plain text, or a JSON object of the tool's arguments
+    TextOrTool,
+}
+
+struct Route {
+    method: &'static str,
+    path: &'static str,
+    tool: &'static str,
+    summary: &'static str,
+    /// `(path parameter, tool argument)`
+    params: &'static [(&'static str, &'static str)],
+    /// Tool arguments passed as query parameters
+    query: &'static [&'static str],
+    body: Body,
+    /// Statuses besides 200 and the error responses
+    extra: &'static [(&'static str, &'static str)],
+}
+
+const AGENT_ID: &[(&str, &str)] = &[("id", "name")];
+
+const ROUTES: &[Route] = &[
+    Route {
+        method: "get",
+        path: "/agents",
+        tool: "list_agents",
+        summary: "List agents",
+        params: &[],
+        query: &["tag"],
+        body: Body::None,
+        extra: &[],
+    },
+    Route {
+        method: "post",
+        path: "/agents",
+        tool: "spawn_agent",
+        summary: "Spawn an agent",
+        params: &[],
+        query: &[],
+        body: Body::Tool,
+        extra: &[(
+            "202",
+            "Queued behind max_concurrent or a wait_for dependency",
+        )],
+    },
+    Route {
+        method: "get",
+        path: "/agents/{id}",
+        tool: "get_agent",
+        summary: "Get an agent",
+        params: AGENT_ID,
+        query: &[],
+        body: Body::None,
+        extra: &[],
+    },
+    Route {
+        method: "patch",
+        path: "/agents/{id}",
+        tool: "rename_agent",
+        summary: "Rename an agent or set its note",
+        params: AGENT_ID,
+        query: &[],
+        body: Body::Fields(&[
+            ("name", "rename_agent", "new_name"),
+            ("note", "set_agent_note", "note"),
+        ]),
+        extra: &[],
+    },
+    Route {
+        method: "post",
+        path: "/agents/{id}/input",
+        tool: "send_input",
+        summary: "Type into an agent",
+        params: AGENT_ID,
+        query: &[],
+        body: Body::Fields(&[
+            ("text", "send_input", "text"),
+            ("enter", "send_input", "enter"),
+        ]),
+        extra: &[],
+    },
+    Route {
+        method: "post",
+        path: "/agents/{id}/send-file",
+        tool: "send_file",
+        summary: "Send a file to an agent",
+        params: AGENT_ID,
+        query: &["filename", "note", "from"],
+        body: Body::TextOrTool,
+        extra: &[],
+    },
+    Route {
+        method: "post",
+        path: "/agents/{id}/kill",
+        tool: "kill_agent",
+        summary: "Kill an agent",
+        params: AGENT_ID,
+        query: &[],
+        body: Body::None,
+        extra: &[],
+    },
+    Route {
+        method: "post",
+        path: "/agents/{id}/rollback",
+        tool: "rollback_agent",
+        summary: "Roll back an agent's workspace",
+        params: AGENT_ID,
+        query: &[],
+        body: Body::None,
+        extra: &[],
+    },
+    Route {
+        method: "post",
+        path: "/agents/{id}/priority",
+        tool: "set_agent_priority",
+        summary: "Set an agent's refresh priority",
+        params: AGENT_ID,
+        query: &[],
+        body: Body::Fields(&[("priority", "set_agent_priority", "priority")]),
+        extra: &[],
+    },
+    Route {
+        method: "post",
+        path: "/agents/{id}/report",
+        tool: "report_progress",
+        summary: "Report an agent's progress",
+        params: AGENT_ID,
+        query: &[],
+        body: Body::Tool,
+        extra: &[],
+    },
+    Route {
+        method: "get",
+        path: "/agents/{id}/messages",
+        tool: "get_messages",
+        summary: "Read an agent's mailbox",
+        params: AGENT_ID,
+        query: &[],
+        body: Body::None,
+        extra: &[],
+    },
+    Route {
+        method: "post",
+        path: "/agents/{id}/messages",
+        tool: "send_message",
+        summary: "Message an agent",
+        params: AGENT_ID,
+        query: &[],
+        body: Body::Fields(&[
+            ("text", "send_message", "text"),
+            ("from", "send_message", "from"),
+        ]),
+        extra: &[],
+    },
+    Route {
+        method: "get",
+        path: "/health/agents/{id}",
+        tool: "get_agent_heartbeat",
+        summary: "An agent's heartbeat",
+        params: AGENT_ID,
+        query: &["fail_if"],
+        body: Body::None,
+        extra: &[("503", "One of the fail_if conditions holds")],
+    },
+    Route {
+        method: "get",
+        path: "/projects",
+        tool: "list_projects",
+        summary: "List projects",
+        params: &[],
+        query: &[],
+        body: Body::None,
+        extra: &[],
+    },
+    Route {
+        method: "post",
+        path: "/projects/{id}/items",
+        tool: "add_project_item",
+        summary: "Add a project item",
+        params: &[("id", "project_id")],
+        query: &[],
+        body: Body::Fields(&[("text", "add_project_item", "text")]),
+        extra: &[],
+    },
+    Route {
+        method: "post",
+        path: "/projects/{id}/items/{item}/toggle",
+        tool: "toggle_project_item",
+        summary: "Toggle a project item",
+        params: &[("id", "project_id"), ("item", "item")],
+        query: &[],
+        body: Body::None,
+        extra: &[],
+    },
+    Route {
+        method: "get",
+        path: "/topology",
+        tool: "get_topology",
+        summary: "The agent hierarchy as a graph",
+        params: &[],
+        query: &["format"],
+        body: Body::None,
+        extra: &[],
+    },
+    Route {
+        method: "get",
+        path: "/runs",
+        tool: "list_runs",
+        summary: "List run reports",
+        params: &[],
+        query: &[],
+        body: Body::None,
+        extra: &[],
+    },
+    Route {
+        method: "post",
+        path: "/manager/refresh-context",
+        tool: "refresh_manager_context",
+        summary: "Queue a context snapshot for the EA",
+        params: &[],
+        query: &[],
+        body: Body::None,
+        extra: &[],
+    },
+    Route {
+        method: "get",
+        path: "/config/health-patterns",
+        tool: "get_health_patterns",
+        summary: "Get the health patterns",
+        params: &[],
+        query: &[],
+        body: Body::None,
+        extra: &[],
+    },
+    Route {
+        method: "put",
+        path: "/config/health-patterns",
+        tool: "set_health_patterns",
+        summary: "Set the health patterns",
+        params: &[],
+        query: &[],
+        body: Body::Tool,
+        extra: &[],
+    },
+    Route {
+        method: "get",
+        path: "/events",
+        tool: "list_agents",
+        summary: "Stream the agent list as server-sent events",
+        params: &[],
+        query: &[],
+        body: Body::None,
+        extra: &[],
+    },
+];
+
+/// The OpenAPI document served at `/api/openapi.json`.
+pub fn document() -> Value {
+    let schema = mcp::tool_schema_json();
+    let tools: Map<String, Value> = schema["tools"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|tool| {
+            (
+                tool["name"].as_str().unwrap_or_default().to_string(),
+                tool.clone(),
+            )
+        })
+        .collect();
+    let mut paths = Map::new();
+    for route in ROUTES {
+        let path = paths
+            .entry(format!("/api{}", route.path))
+            .or_insert_with(|| json!({}));
+        path[route.method] = operation(route, &tools);
+    }
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "omar",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "The agent API behind `omar web`. Each operation answers through the MCP tool named in its x-mcp-tool.",
+        },
+        "paths": paths,
+        "components": {
+            "securitySchemes": {
+                "bearer": {"type": "http", "scheme": "bearer"},
+                "token": {"type": "apiKey", "in": "query", "name": "token"},
+            },
+            "schemas": {
+                "Error": {
+                    "type": "object",
+                    "properties": {
+                        "error": {"type": "string"},
+                        "errors": {"type": "array", "items": {"type": "object"}},
+                    },
+                    "required": ["error"],
+                },
+            },
+        },
+        "security": [{"bearer": []}, {"token": []}],
+    })
+}
+
+fn operation(route: &Route, tools: &Map<String, Value>) -> Value {
+    let argument = |tool: &str, name: &str| {
+        tools.get(tool).map_or(Value::Null, |tool| {
+            tool["inputSchema"]["properties"][name].clone()
+        })
+    };
+    let input = &tools.get(route.tool).map_or(Value::Null, Clone::clone)["inputSchema"];
+
+    let mut parameters = Vec::new();
+    for (param, arg) in route.params {
+        parameters.push(json!({
+            "name": param,
+            "in": "path",
+            "required": true,
+            "schema": argument(route.tool, arg),
+        }));
+    }
+    for arg in route.query {
+        parameters.push(json!({
+            "name": arg,
+            "in": "query",
+            "schema": argument(route.tool, arg),
+        }));
+    }
+
+    let from_path: Vec<&str> = route.params.iter().map(|(_, arg)| *arg).collect();
+    let tool_body = || {
+        let mut body = input.clone();
+        if let Some(properties) = body["properties"].as_object_mut() {
+            properties.retain(|name, _| !from_path.contains(&name.as_str()));
+        }
+        if let Some(required) = body["required"].as_array_mut() {
+            required.retain(|name| !from_path.iter().any(|arg| name == arg));
+        }
+        body
+    };
+    let request_body = match route.body {
+        Body::None => None,
+        Body::Tool => Some(json!({"application/json": {"schema": tool_body()}})),
+        Body::Fields(fields) => {
+            let properties: Map<String, Value> = fields
+                .iter()
+                .map(|(field, tool, arg)| (field.to_string(), argument(tool, arg)))
+                .collect();
+            Some(json!({"application/json": {"schema": {
+                "type": "object",
+                "properties": properties,
+            }}}))
+        }
+        Body::TextOrTool => Some(json!({
+            "application/json": {"schema": tool_body()},
+            "text/plain": {"schema": argument(route.tool, "content")},
+        })),
+    };
+
+    let content = if route.path == "/events" {
+        json!({"text/event-stream": {"schema": {"type": "string"}}})
+    } else {
+        json!({"application/json": {"schema": {"type": "object"}}})
+    };
+    let error = json!({"application/json": {"schema": {"$ref": "#/components/schemas/Error"}}});
+    let mut responses = json!({
+        "200": {"description": "The tool's result", "content": content},
+        "400": {"description": "The tool failed", "content": error},
+        "401": {"description": "Missing or wrong token", "content": error},
+        "422": {"description": "Invalid fields, listed in errors", "content": error},
+    });
+    for (status, description) in route.extra {
+        responses[*status] = json!({"description": description, "content": content});
+    }
+
+    let mut operation = json!({
+        "operationId": operation_id(route),
+        "x-mcp-tool": route.tool,
+        "summary": route.summary,
+        "description": tools.get(route.tool).map_or(Value::Null, |tool| tool["description"].clone()),
+        "parameters": parameters,
+        "responses": responses,
+    });
+    if let Some(content) = request_body {
+        operation["requestBody"] = json!({"required": true, "content": content});
+    }
+    operation
+}
+
+/// `get_agents_id_messages` for `GET /agents/{id}/messages`.
+fn operation_id(route: &Route) -> String {
+    let path: Vec<&str> = route
+        .path
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect();
+    format!("{}_{}", route.method, path.join("_"))
+}
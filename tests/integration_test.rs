@@ -1272,6 +1272,7 @@ fn test_spawn_agent_requires_explicit_parent_when_project_has_pm() {
             "project_id": project_id,
             "task": "monitor ownership test",
             "command": "sleep 30",
+            "role": "pm",
         }),
     );
 